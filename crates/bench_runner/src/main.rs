@@ -2,7 +2,7 @@ use anyhow::Result;
 use clap::Parser;
 use glaredb::server::ComputeServer;
 use glob::glob;
-use pgsrv::auth::SingleUserAuthenticator;
+use pgsrv::auth::{PasswordMethod, SingleUserAuthenticator};
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::{Duration, SystemTime};
@@ -56,10 +56,11 @@ fn main() -> Result<()> {
         let pg_addr = pg_listener.local_addr()?;
 
         let server = ComputeServer::builder()
-            .with_authenticator(SingleUserAuthenticator {
-                user: "glaredb".to_string(),
-                password: "glaredb".to_string(),
-            })
+            .with_authenticator(SingleUserAuthenticator::new(
+                "glaredb",
+                "glaredb",
+                PasswordMethod::default(),
+            ))
             .with_pg_listener(pg_listener)
             .connect()
             .await?;
@@ -139,6 +139,31 @@ impl MetastoreClientHandle {
             ClientRequest::ExecMutations {
                 version: current_version,
                 mutations,
+                dry_run: false,
+                response: tx,
+            },
+            rx,
+        )
+        .await
+        .and_then(std::convert::identity) // Flatten
+    }
+
+    /// Validate mutations against the Metastore catalog without applying
+    /// them.
+    ///
+    /// Returns the catalog state as it would look with the mutations applied.
+    /// The worker's cached state is not updated.
+    pub async fn dry_run_mutate(
+        &self,
+        current_version: u64,
+        mutations: Vec<Mutation>,
+    ) -> Result<Arc<CatalogState>> {
+        let (tx, rx) = oneshot::channel();
+        self.send(
+            ClientRequest::ExecMutations {
+                version: current_version,
+                mutations,
+                dry_run: true,
                 response: tx,
             },
             rx,
@@ -188,6 +213,8 @@ enum ClientRequest {
         version: u64,
         /// Mutations to send to Metastore.
         mutations: Vec<Mutation>,
+        /// Only validate the mutations, leaving the catalog untouched.
+        dry_run: bool,
         /// Response channel to await for result.
         response: oneshot::Sender<Result<Arc<CatalogState>>>,
     },
@@ -460,8 +487,8 @@ impl StatefulWorker {
             ClientRequest::ExecMutations {
                 version,
                 mutations,
+                dry_run,
                 response,
-                ..
             } => {
                 let result = mutations
                     .into_iter()
@@ -474,6 +501,7 @@ impl StatefulWorker {
                             db_id: self.db_id.into_bytes().to_vec(),
                             catalog_version: version,
                             mutations,
+                            dry_run,
                         }))
                         .await
                         .map_err(CatalogError::from),
@@ -485,14 +513,21 @@ impl StatefulWorker {
                         let resp = resp.into_inner();
                         // TODO: Properly check if we updated.
                         match resp.catalog {
+                            Some(catalog) if dry_run => {
+                                catalog.try_into().map(Arc::new).map_err(CatalogError::from)
+                            }
                             Some(catalog) => {
                                 // Update this worker's cache.
                                 let state: CatalogState = catalog.try_into().unwrap(); // TODO
                                 self.set_cached_state(state);
+                                Ok(self.cached_state.clone())
+                            }
+                            None if dry_run => Err(CatalogError::new("missing field: 'catalog'")),
+                            None => {
+                                error!("missing catalog state");
+                                Ok(self.cached_state.clone())
                             }
-                            _ => error!("missing catalog state"),
                         }
-                        Ok(self.cached_state.clone())
                     }
                     Err(e) => Err(e),
                 };
@@ -1,4 +1,5 @@
 use crate::errors::{CatalogError, Result};
+use parking_lot::Mutex;
 use protogen::metastore::strategy::ResolveErrorStrategy;
 use protogen::metastore::types::catalog::CatalogState;
 use protogen::metastore::types::service::Mutation;
@@ -11,39 +12,200 @@ use super::client::MetastoreClientHandle;
 #[derive(Clone)]
 pub struct CatalogMutator {
     pub client: Option<MetastoreClientHandle>,
+    /// Catalog changes made inside of an explicit transaction, if one is
+    /// open.
+    ///
+    /// Shared between clones of the mutator since the mutator gets stored on
+    /// the datafusion session config.
+    txn: Arc<Mutex<Option<PendingMutations>>>,
+}
+
+/// Catalog changes made during a transaction.
+///
+/// Nothing is applied to the catalog until commit. Each mutation is validated
+/// by metastore with a dry run against the catalog version the transaction
+/// started from (with all previous mutations in the transaction applied), and
+/// the resulting catalog is what the session sees for the rest of the
+/// transaction. On commit, all mutations are sent in a single request so they
+/// either all apply or none do.
+#[derive(Debug, Default)]
+struct PendingMutations {
+    /// Catalog version the mutations are applied against. Set on the first
+    /// mutation in the transaction.
+    version: Option<u64>,
+    /// Mutations to apply on commit, in order.
+    mutations: Vec<Mutation>,
+    /// Catalog state with all pending mutations applied.
+    state: Option<Arc<CatalogState>>,
 }
 
 impl CatalogMutator {
     pub fn empty() -> Self {
-        CatalogMutator { client: None }
+        Self::new(None)
     }
 
     pub fn new(client: Option<MetastoreClientHandle>) -> Self {
-        CatalogMutator { client }
+        CatalogMutator {
+            client,
+            txn: Arc::new(Mutex::new(None)),
+        }
     }
 
     pub fn get_metastore_client(&self) -> Option<&MetastoreClientHandle> {
         self.client.as_ref()
     }
 
+    /// Returns if there's an open transaction.
+    pub fn in_transaction(&self) -> bool {
+        self.txn.lock().is_some()
+    }
+
+    /// Get the catalog state including changes made in the open transaction.
+    ///
+    /// Returns `None` if there's no open transaction, or if the transaction
+    /// hasn't changed the catalog.
+    pub fn transaction_state(&self) -> Option<Arc<CatalogState>> {
+        self.txn
+            .lock()
+            .as_ref()
+            .and_then(|pending| pending.state.clone())
+    }
+
+    /// Start buffering catalog changes for a transaction.
+    ///
+    /// Does nothing if a transaction is already open.
+    pub fn begin_transaction(&self) {
+        let mut txn = self.txn.lock();
+        if txn.is_none() {
+            *txn = Some(PendingMutations::default());
+        }
+    }
+
+    /// Apply all mutations made in the open transaction.
+    ///
+    /// Errors with a `FetchCatalogAndRetry` strategy if the catalog was
+    /// changed by someone else since the transaction first changed it, in
+    /// which case nothing is applied.
+    ///
+    /// Returns the updated catalog state if any mutations were applied.
+    pub async fn commit_transaction(&self) -> Result<Option<Arc<CatalogState>>> {
+        let pending = self.txn.lock().take();
+        let (version, mutations) = match pending {
+            Some(PendingMutations {
+                version: Some(version),
+                mutations,
+                ..
+            }) if !mutations.is_empty() => (version, mutations),
+            _ => return Ok(None),
+        };
+
+        let client = self.client()?;
+        match client.try_mutate(version, mutations).await {
+            Ok(state) => Ok(Some(state)),
+            Err(e) => Err(transaction_error(e)),
+        }
+    }
+
+    /// Discard all mutations made in the open transaction.
+    ///
+    /// Returns the current catalog state if the transaction changed the
+    /// catalog, since the session will have been seeing the changes.
+    pub async fn rollback_transaction(&self) -> Result<Option<Arc<CatalogState>>> {
+        let pending = self.txn.lock().take();
+        match pending {
+            Some(pending) if pending.state.is_some() => {
+                let state = self.client()?.get_cached_state().await?;
+                Ok(Some(state))
+            }
+            _ => Ok(None),
+        }
+    }
+
     /// Mutate the catalog if possible.
     ///
     /// Errors if the metastore client isn't configured.
     ///
     /// This will retry mutations if we were working with an out of date
     /// catalog.
+    ///
+    /// If a transaction is open, the mutations are only validated and held
+    /// until commit. The returned state includes all of the transaction's
+    /// mutations.
     pub async fn mutate(
         &self,
         catalog_version: u64,
         mutations: impl IntoIterator<Item = Mutation>,
     ) -> Result<Arc<CatalogState>> {
-        let client = match &self.client {
-            Some(client) => client,
-            None => return Err(CatalogError::new("metastore client not configured")),
+        let txn = self
+            .txn
+            .lock()
+            .as_ref()
+            .map(|pending| (pending.version, pending.mutations.clone()));
+        let (version, mut pending) = match txn {
+            Some(txn) => txn,
+            None => return self.mutate_now(catalog_version, mutations).await,
+        };
+        pending.extend(mutations);
+
+        let client = self.client()?;
+        let (version, state) = match version {
+            // Catalog already changed in this transaction, everything needs to
+            // apply against the same version.
+            Some(version) => {
+                let state = client
+                    .dry_run_mutate(version, pending.clone())
+                    .await
+                    .map_err(transaction_error)?;
+                (version, state)
+            }
+            None => match client
+                .dry_run_mutate(catalog_version, pending.clone())
+                .await
+            {
+                Ok(state) => (catalog_version, state),
+                Err(CatalogError {
+                    strategy: Some(ResolveErrorStrategy::FetchCatalogAndRetry),
+                    ..
+                }) => {
+                    // Nothing depends on the old version yet, retry against
+                    // the latest catalog.
+                    let version = self.latest_version().await?;
+                    let state = client.dry_run_mutate(version, pending.clone()).await?;
+                    (version, state)
+                }
+                Err(e) => return Err(e),
+            },
         };
 
-        // Note that when we have transactions, these shouldn't be sent until
-        // commit.
+        if let Some(txn) = self.txn.lock().as_mut() {
+            txn.version = Some(version);
+            txn.mutations = pending;
+            txn.state = Some(state.clone());
+        }
+
+        Ok(state)
+    }
+
+    fn client(&self) -> Result<&MetastoreClientHandle> {
+        self.client
+            .as_ref()
+            .ok_or_else(|| CatalogError::new("metastore client not configured"))
+    }
+
+    async fn latest_version(&self) -> Result<u64> {
+        let client = self.client()?;
+        client.refresh_cached_state().await?;
+        Ok(client.get_cached_state().await?.version)
+    }
+
+    /// Send mutations to metastore.
+    async fn mutate_now(
+        &self,
+        catalog_version: u64,
+        mutations: impl IntoIterator<Item = Mutation>,
+    ) -> Result<Arc<CatalogState>> {
+        let client = self.client()?;
+
         let mutations: Vec<_> = mutations.into_iter().collect();
         let state = match client.try_mutate(catalog_version, mutations.clone()).await {
             Ok(state) => state,
@@ -74,8 +236,150 @@ impl CatalogMutator {
 
 impl From<MetastoreClientHandle> for CatalogMutator {
     fn from(value: MetastoreClientHandle) -> Self {
-        CatalogMutator {
-            client: Some(value),
-        }
+        Self::new(Some(value))
+    }
+}
+
+/// Convert an error from applying a transaction's mutations into one
+/// indicating that the transaction conflicted with another change to the
+/// catalog, if that's what happened.
+fn transaction_error(e: CatalogError) -> CatalogError {
+    match e.strategy {
+        Some(ResolveErrorStrategy::FetchCatalogAndRetry) => CatalogError {
+            msg: "catalog was modified concurrently, transaction cannot be committed".to_string(),
+            strategy: e.strategy,
+        },
+        _ => e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{MetastoreClientSupervisor, DEFAULT_METASTORE_CLIENT_CONFIG};
+    use metastore::local::start_inprocess;
+    use object_store::memory::InMemory;
+    use protogen::metastore::types::service::{CreateSchema, DropSchema};
+    use uuid::Uuid;
+
+    async fn new_mutator() -> CatalogMutator {
+        let client = start_inprocess(Arc::new(InMemory::new())).await.unwrap();
+        let supervisor = MetastoreClientSupervisor::new(client, DEFAULT_METASTORE_CLIENT_CONFIG);
+        let client = supervisor.init_client(Uuid::new_v4()).await.unwrap();
+        CatalogMutator::from(client)
+    }
+
+    fn create_schema(name: &str) -> Mutation {
+        Mutation::CreateSchema(CreateSchema {
+            name: name.to_string(),
+            if_not_exists: false,
+        })
+    }
+
+    fn drop_schema(name: &str) -> Mutation {
+        Mutation::DropSchema(DropSchema {
+            name: name.to_string(),
+            if_exists: false,
+            cascade: false,
+        })
+    }
+
+    fn has_schema(state: &CatalogState, name: &str) -> bool {
+        state
+            .entries
+            .values()
+            .any(|ent| ent.get_meta().name == name)
+    }
+
+    #[tokio::test]
+    async fn transaction_applied_on_commit() {
+        let mutator = new_mutator().await;
+        let client = mutator.get_metastore_client().unwrap().clone();
+        let version = client.get_cached_state().await.unwrap().version;
+
+        mutator.begin_transaction();
+        let state = mutator
+            .mutate(version, [create_schema("goomba")])
+            .await
+            .unwrap();
+        assert!(has_schema(&state, "goomba"));
+
+        // Later statements in the transaction see earlier changes, including
+        // dropping and recreating an object.
+        let state = mutator
+            .mutate(state.version, [drop_schema("goomba")])
+            .await
+            .unwrap();
+        assert!(!has_schema(&state, "goomba"));
+        let state = mutator
+            .mutate(state.version, [create_schema("goomba")])
+            .await
+            .unwrap();
+        assert!(has_schema(&state, "goomba"));
+        assert!(Arc::ptr_eq(&state, &mutator.transaction_state().unwrap()));
+
+        // Nothing visible outside of the transaction.
+        let cached = client.get_cached_state().await.unwrap();
+        assert_eq!(version, cached.version);
+        assert!(!has_schema(&cached, "goomba"));
+
+        let state = mutator.commit_transaction().await.unwrap().unwrap();
+        assert!(has_schema(&state, "goomba"));
+        assert!(!mutator.in_transaction());
+        assert!(has_schema(
+            &client.get_cached_state().await.unwrap(),
+            "goomba"
+        ));
+    }
+
+    #[tokio::test]
+    async fn transaction_discarded_on_rollback() {
+        let mutator = new_mutator().await;
+        let client = mutator.get_metastore_client().unwrap().clone();
+        let version = client.get_cached_state().await.unwrap().version;
+
+        mutator.begin_transaction();
+        mutator
+            .mutate(version, [create_schema("koopa")])
+            .await
+            .unwrap();
+        // Errors during the transaction don't leave anything behind.
+        mutator
+            .mutate(version, [drop_schema("bowser")])
+            .await
+            .unwrap_err();
+
+        let state = mutator.rollback_transaction().await.unwrap().unwrap();
+        assert_eq!(version, state.version);
+        assert!(!has_schema(&state, "koopa"));
+        assert!(mutator.commit_transaction().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn transaction_conflict() {
+        let mutator = new_mutator().await;
+        let client = mutator.get_metastore_client().unwrap().clone();
+        let version = client.get_cached_state().await.unwrap().version;
+
+        mutator.begin_transaction();
+        mutator
+            .mutate(version, [create_schema("boo")])
+            .await
+            .unwrap();
+
+        // Another session changes the catalog before commit.
+        client
+            .try_mutate(version, vec![create_schema("lakitu")])
+            .await
+            .unwrap();
+
+        let err = mutator.commit_transaction().await.unwrap_err();
+        assert_eq!(
+            Some(ResolveErrorStrategy::FetchCatalogAndRetry),
+            err.strategy
+        );
+        let cached = client.get_cached_state().await.unwrap();
+        assert!(!has_schema(&cached, "boo"));
+        assert!(has_schema(&cached, "lakitu"));
     }
 }
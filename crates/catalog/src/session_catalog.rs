@@ -3,17 +3,21 @@ use datafusion::datasource::{MemTable, TableProvider};
 use parking_lot::Mutex;
 use protogen::metastore::types::catalog::{
     CatalogEntry, CatalogState, CredentialsEntry, DatabaseEntry, DeploymentMetadata, EntryMeta,
-    EntryType, FunctionEntry, FunctionType, SchemaEntry, SourceAccessMode, TableEntry, TunnelEntry,
-};
-use protogen::metastore::types::options::{
-    InternalColumnDefinition, TableOptions, TableOptionsInternal,
+    EntryType, FunctionEntry, FunctionType, Privilege, RoleEntry, SchemaEntry, SourceAccessMode,
+    TableEntry, TunnelEntry,
 };
+use protogen::metastore::types::options::{InternalColumnDefinition, TableOptions};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::debug;
 
 use super::client::MetastoreClientHandle;
 
+/// User that's treated as a superuser when no catalog role exists for it.
+///
+/// Matches the user the server is configured with by default.
+pub const DEFAULT_BOOTSTRAP_USER: &str = "glaredb";
+
 /// Configuration for letting the catalog know how to resolve certain items.
 ///
 /// Note this was created to avoid needing to import the constants from
@@ -41,6 +45,8 @@ pub struct SessionCatalog {
     tunnel_names: HashMap<String, u32>,
     /// Map credentials names to their ids.
     credentials_names: HashMap<String, u32>,
+    /// Map role names to their ids.
+    role_names: HashMap<String, u32>,
     /// Map schema names to their ids.
     schema_names: HashMap<String, u32>,
     /// Map schema IDs to objects in the schema.
@@ -49,6 +55,9 @@ pub struct SessionCatalog {
     resolve_conf: ResolveConfig,
     /// Catalog for holding temporary session objects.
     temp: TempCatalog,
+    /// User configured when starting the server. The only user allowed to do
+    /// anything without a role in the catalog.
+    bootstrap_user: String,
 }
 
 impl SessionCatalog {
@@ -59,15 +68,28 @@ impl SessionCatalog {
             database_names: HashMap::new(),
             tunnel_names: HashMap::new(),
             credentials_names: HashMap::new(),
+            role_names: HashMap::new(),
             schema_names: HashMap::new(),
             schema_objects: HashMap::new(),
             resolve_conf,
             temp: TempCatalog::new(resolve_conf),
+            bootstrap_user: DEFAULT_BOOTSTRAP_USER.to_string(),
         };
         catalog.rebuild_name_maps();
         catalog
     }
 
+    /// Set the user that's treated as a superuser when it has no role.
+    pub fn with_bootstrap_user(mut self, user: impl Into<String>) -> SessionCatalog {
+        self.bootstrap_user = user.into();
+        self
+    }
+
+    /// Get the user that's treated as a superuser when it has no role.
+    pub fn bootstrap_user(&self) -> &str {
+        &self.bootstrap_user
+    }
+
     /// Get the version of this catalog state.
     pub fn version(&self) -> u64 {
         self.state.version
@@ -173,6 +195,85 @@ impl SessionCatalog {
         }
     }
 
+    /// Resolve a role by name.
+    pub fn resolve_role(&self, name: &str) -> Option<&RoleEntry> {
+        // Similar invariants as `resolve_database`. If we find an entry in the
+        // role map, it must exist in the state and must be a role.
+
+        let id = self.role_names.get(name)?;
+        let ent = self
+            .state
+            .entries
+            .get(id)
+            .expect("role name points to invalid id");
+
+        match ent {
+            CatalogEntry::Role(ent) => Some(ent),
+            _ => panic!(
+                "entry type not role; name: {}, id: {}, type: {:?}",
+                name,
+                id,
+                ent.entry_type(),
+            ),
+        }
+    }
+
+    /// Check if a user has a privilege on the object with the given id.
+    ///
+    /// Only the privileges granted on the object itself are checked. Users
+    /// without a role in the catalog have no privileges, except for the
+    /// bootstrap user.
+    pub fn has_privilege(&self, user: &str, oid: u32, privilege: Privilege) -> bool {
+        match self.resolve_role(user) {
+            Some(role) => self.role_has_privilege(role, oid, privilege),
+            None => user == self.bootstrap_user,
+        }
+    }
+
+    /// Check if a user can access an object inside of a schema with the given
+    /// privilege.
+    ///
+    /// Along with the privilege on the object, this requires `USAGE` on the
+    /// object's schema. Temporary objects are only visible to the session
+    /// that created them, and can always be accessed.
+    pub fn can_access(&self, user: &str, meta: &EntryMeta, privilege: Privilege) -> bool {
+        if meta.is_temp {
+            return true;
+        }
+        match self.resolve_role(user) {
+            Some(role) => {
+                self.role_has_privilege(role, meta.parent, Privilege::Usage)
+                    && self.role_has_privilege(role, meta.id, privilege)
+            }
+            None => user == self.bootstrap_user,
+        }
+    }
+
+    /// Check if a user is a superuser.
+    ///
+    /// The bootstrap user is a superuser unless a role with the same name says
+    /// otherwise. Any other user without a role (e.g. one whose role was
+    /// dropped while connected) is not.
+    pub fn is_superuser(&self, user: &str) -> bool {
+        match self.resolve_role(user) {
+            Some(role) => role.superuser,
+            None => user == self.bootstrap_user,
+        }
+    }
+
+    fn role_has_privilege(&self, role: &RoleEntry, oid: u32, privilege: Privilege) -> bool {
+        if role.has_privilege(oid, privilege) {
+            return true;
+        }
+        // Builtin objects can always be read.
+        match self.get_by_oid(oid) {
+            Some(ent) if ent.get_meta().builtin => {
+                matches!(privilege, Privilege::Select | Privilege::Usage)
+            }
+            _ => false,
+        }
+    }
+
     /// Resolve a schema by name.
     pub fn resolve_schema(&self, name: &str) -> Option<&SchemaEntry> {
         // Similar invariants as `resolve_database`. If we find an entry in the
@@ -316,9 +417,10 @@ impl SessionCatalog {
     fn as_namespaced_entry<'a>(&'a self, ent: &'a CatalogEntry) -> NamespacedCatalogEntry<'a> {
         let parent_entry = match ent {
             // Explicitly mention all the options to accidentally not leave anything here.
-            CatalogEntry::Database(_) | CatalogEntry::Tunnel(_) | CatalogEntry::Credentials(_) => {
-                None
-            }
+            CatalogEntry::Database(_)
+            | CatalogEntry::Tunnel(_)
+            | CatalogEntry::Credentials(_)
+            | CatalogEntry::Role(_) => None,
             CatalogEntry::Schema(_)
             | CatalogEntry::Table(_)
             | CatalogEntry::View(_)
@@ -338,6 +440,7 @@ impl SessionCatalog {
         self.database_names.clear();
         self.tunnel_names.clear();
         self.credentials_names.clear();
+        self.role_names.clear();
         self.schema_names.clear();
         self.schema_objects.clear();
 
@@ -354,6 +457,9 @@ impl SessionCatalog {
                 CatalogEntry::Credentials(_) => {
                     self.credentials_names.insert(name, *id);
                 }
+                CatalogEntry::Role(_) => {
+                    self.role_names.insert(name, *id);
+                }
                 CatalogEntry::Schema(_) => {
                     self.schema_names.insert(name, *id);
                 }
@@ -447,7 +553,7 @@ impl TempCatalog {
                     external: false,
                     is_temp: true,
                 },
-                options: TableOptions::Internal(TableOptionsInternal { columns }),
+                options: TableOptions::new_internal(columns),
                 tunnel_id: None,
                 access_mode: SourceAccessMode::ReadWrite,
             }
@@ -488,9 +594,7 @@ impl TempCatalog {
                     external: false,
                     is_temp: true,
                 },
-                options: TableOptions::Internal(TableOptionsInternal {
-                    columns: Vec::new(),
-                }),
+                options: TableOptions::new_internal(Vec::new()),
                 tunnel_id: None,
                 access_mode: SourceAccessMode::ReadWrite,
             });
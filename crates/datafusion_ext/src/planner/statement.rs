@@ -1,6 +1,7 @@
 use std::{collections::BTreeMap, sync::Arc};
 
 use datafusion::{
+    arrow::datatypes::DataType,
     common::{DFField, DFSchema, DataFusionError, OwnedTableReference, Result, ToDFSchema},
     logical_expr::{
         builder::project, Analyze, Explain, ExprSchemable, LogicalPlan, PlanType, ToStringifiedPlan,
@@ -52,11 +53,17 @@ impl<'a, S: AsyncContextProvider> SqlQueryPlanner<'a, S> {
         }
     }
 
+    /// Plan the source of an INSERT, casting to the types of the target
+    /// columns.
+    ///
+    /// `param_types` are the types of any parameters provided up front. These
+    /// take precedence over types inferred from the target columns.
     pub async fn insert_to_source_plan(
         &mut self,
         table_name: &OwnedTableReference,
         columns: &Vec<String>,
         source: Box<Query>,
+        param_types: &[DataType],
     ) -> Result<LogicalPlan> {
         // Do a table lookup to verify the table exists
         let provider = self
@@ -81,7 +88,8 @@ impl<'a, S: AsyncContextProvider> SqlQueryPlanner<'a, S> {
         };
 
         // infer types for Values clause... other types should be resolvable the regular way
-        let mut prepare_param_data_types = BTreeMap::new();
+        let mut prepare_param_data_types: BTreeMap<_, _> =
+            param_types.iter().cloned().enumerate().collect();
         if let SetExpr::Values(ast::Values { rows, .. }) = (*source.body).clone() {
             for row in rows.iter() {
                 for (idx, val) in row.iter().enumerate() {
@@ -96,7 +104,7 @@ impl<'a, S: AsyncContextProvider> SqlQueryPlanner<'a, S> {
                             ))
                         })?;
                         let dt = field.field().data_type().clone();
-                        let _ = prepare_param_data_types.insert(name, dt);
+                        prepare_param_data_types.entry(name).or_insert(dt);
                     }
                 }
             }
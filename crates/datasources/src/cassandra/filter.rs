@@ -0,0 +1,270 @@
+//! Filter pushdown for cassandra scans.
+//!
+//! Cassandra can only serve predicates that restrict partition and clustering
+//! columns in specific ways (without resorting to `ALLOW FILTERING`):
+//!
+//! - Every partition key column must be restricted with an equality.
+//! - Clustering columns may then be restricted in order, with zero or more
+//!   equalities followed by at most one range restriction.
+//!
+//! Predicates that satisfy the above are pushed down exactly. Everything else
+//! is left for DataFusion to apply after the scan.
+use super::*;
+use crate::common::util;
+use datafusion::logical_expr::Operator;
+use datafusion::scalar::ScalarValue;
+use std::fmt::Write;
+
+/// Partition and clustering key columns for a table, in key order.
+#[derive(Debug, Clone)]
+pub(super) struct TableKeys {
+    pub(super) partition: Vec<String>,
+    pub(super) clustering: Vec<String>,
+}
+
+/// A simple `<column> <op> <literal>` predicate.
+struct Predicate<'a> {
+    column: &'a str,
+    op: Operator,
+    literal: String,
+}
+
+/// Determine which filters can be pushed down to cassandra.
+///
+/// Filters are only ever `Exact` or `Unsupported`.
+pub(super) fn classify_filters(
+    keys: &TableKeys,
+    schema: &ArrowSchema,
+    col_types: &[ColumnType],
+    filters: &[&Expr],
+) -> Vec<TableProviderFilterPushDown> {
+    let preds: Vec<_> = filters
+        .iter()
+        .map(|f| simple_predicate(schema, col_types, f))
+        .collect();
+    let mut exact = vec![false; filters.len()];
+
+    let find = |exact: &[bool], col: &str, ops: &[Operator]| {
+        preds.iter().enumerate().position(|(idx, pred)| {
+            !exact[idx]
+                && pred
+                    .as_ref()
+                    .is_some_and(|p| p.column == col && ops.contains(&p.op))
+        })
+    };
+
+    // All partition key columns need to be restricted by equality.
+    for col in &keys.partition {
+        match find(&exact, col, &[Operator::Eq]) {
+            Some(idx) => exact[idx] = true,
+            None => return vec![TableProviderFilterPushDown::Unsupported; filters.len()],
+        }
+    }
+
+    for col in &keys.clustering {
+        if let Some(idx) = find(&exact, col, &[Operator::Eq]) {
+            exact[idx] = true;
+            continue;
+        }
+
+        // A range on this column ends the usable clustering prefix. Allow at
+        // most one lower and one upper bound.
+        if let Some(idx) = find(&exact, col, &[Operator::Gt, Operator::GtEq]) {
+            exact[idx] = true;
+        }
+        if let Some(idx) = find(&exact, col, &[Operator::Lt, Operator::LtEq]) {
+            exact[idx] = true;
+        }
+        break;
+    }
+
+    exact
+        .into_iter()
+        .map(|exact| {
+            if exact {
+                TableProviderFilterPushDown::Exact
+            } else {
+                TableProviderFilterPushDown::Unsupported
+            }
+        })
+        .collect()
+}
+
+/// Convert the filters that can be pushed down into a CQL predicate string.
+pub(super) fn exprs_to_predicate_string(
+    keys: &TableKeys,
+    schema: &ArrowSchema,
+    col_types: &[ColumnType],
+    filters: &[Expr],
+) -> Result<String> {
+    let filter_refs: Vec<_> = filters.iter().collect();
+    let pushdowns = classify_filters(keys, schema, col_types, &filter_refs);
+
+    let mut ss = Vec::new();
+    for (filter, pushdown) in filters.iter().zip(pushdowns) {
+        if pushdown != TableProviderFilterPushDown::Exact {
+            continue;
+        }
+        // Classifying already checked that this is a simple predicate.
+        if let Some(pred) = simple_predicate(schema, col_types, filter) {
+            let mut buf = String::new();
+            write!(buf, "{} {} {}", pred.column, pred.op, pred.literal)
+                .map_err(|e| CassandraError::String(e.to_string()))?;
+            ss.push(buf);
+        }
+    }
+
+    Ok(ss.join(" AND "))
+}
+
+/// Try to get a simple comparison predicate from an expression, encoding the
+/// literal for the column's type.
+fn simple_predicate<'a>(
+    schema: &ArrowSchema,
+    col_types: &[ColumnType],
+    expr: &'a Expr,
+) -> Option<Predicate<'a>> {
+    let binary = match expr {
+        Expr::BinaryExpr(binary) => binary,
+        _ => return None,
+    };
+
+    let (column, op, literal) = match (binary.left.as_ref(), binary.right.as_ref()) {
+        (Expr::Column(col), Expr::Literal(lit)) => (col, binary.op, lit),
+        (Expr::Literal(lit), Expr::Column(col)) => (col, binary.op.swap()?, lit),
+        _ => return None,
+    };
+
+    if !matches!(
+        op,
+        Operator::Eq | Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq
+    ) {
+        return None;
+    }
+
+    let col_type = &col_types[schema.index_of(&column.name).ok()?];
+    let literal = encode_literal(col_type, literal)?;
+
+    Some(Predicate {
+        column: &column.name,
+        op,
+        literal,
+    })
+}
+
+/// Encode a literal as CQL text for comparing against a column of the given
+/// type.
+fn encode_literal(col_type: &ColumnType, lit: &ScalarValue) -> Option<String> {
+    if lit.is_null() {
+        return None;
+    }
+
+    let mut buf = String::new();
+    match (col_type, lit) {
+        (ColumnType::Text | ColumnType::Ascii, ScalarValue::Utf8(_))
+        | (
+            ColumnType::TinyInt
+            | ColumnType::SmallInt
+            | ColumnType::Int
+            | ColumnType::BigInt
+            | ColumnType::Float
+            | ColumnType::Double,
+            ScalarValue::Int8(_)
+            | ScalarValue::Int16(_)
+            | ScalarValue::Int32(_)
+            | ScalarValue::Int64(_),
+        )
+        | (
+            ColumnType::Float | ColumnType::Double,
+            ScalarValue::Float32(_) | ScalarValue::Float64(_),
+        ) => {
+            util::encode_literal_to_text(util::Datasource::Cassandra, &mut buf, lit).ok()?;
+        }
+        // Uuids are read as strings, but need to be written unquoted.
+        (ColumnType::Uuid, ScalarValue::Utf8(Some(s))) => {
+            let uuid = uuid::Uuid::parse_str(s).ok()?;
+            write!(buf, "{uuid}").ok()?;
+        }
+        // CQL accepts timestamps as milliseconds since the epoch.
+        (ColumnType::Timestamp, ScalarValue::TimestampMillisecond(Some(v), None)) => {
+            write!(buf, "{v}").ok()?;
+        }
+        _ => return None,
+    }
+
+    Some(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::logical_expr::{col, lit};
+
+    use super::*;
+
+    fn test_table() -> (TableKeys, ArrowSchema, Vec<ColumnType>) {
+        let keys = TableKeys {
+            partition: vec!["pk".to_string()],
+            clustering: vec!["c1".to_string(), "c2".to_string()],
+        };
+        let schema = ArrowSchema::new(vec![
+            Field::new("pk", DataType::Utf8, true),
+            Field::new("c1", DataType::Int32, true),
+            Field::new("c2", DataType::Int32, true),
+            Field::new("v", DataType::Int32, true),
+        ]);
+        let col_types = vec![
+            ColumnType::Uuid,
+            ColumnType::Int,
+            ColumnType::Int,
+            ColumnType::Int,
+        ];
+        (keys, schema, col_types)
+    }
+
+    #[test]
+    fn pushdown_requires_partition_key() {
+        let (keys, schema, col_types) = test_table();
+
+        let filters = [col("c1").eq(lit(1_i32)), col("v").eq(lit(2_i32))];
+        let refs: Vec<_> = filters.iter().collect();
+        let pushdowns = classify_filters(&keys, &schema, &col_types, &refs);
+        assert_eq!(vec![TableProviderFilterPushDown::Unsupported; 2], pushdowns);
+    }
+
+    #[test]
+    fn pushdown_clustering_prefix() {
+        use TableProviderFilterPushDown::*;
+
+        let (keys, schema, col_types) = test_table();
+
+        let filters = [
+            col("v").eq(lit(1_i32)),
+            col("c2").eq(lit(2_i32)),
+            lit(3_i32).lt(col("c1")),
+            col("pk").eq(lit("0d23c01f-90d9-45d0-a2a9-c289991d19aa")),
+            col("c1").lt_eq(lit(10_i32)),
+        ];
+        let refs: Vec<_> = filters.iter().collect();
+        let pushdowns = classify_filters(&keys, &schema, &col_types, &refs);
+        assert_eq!(
+            vec![Unsupported, Unsupported, Exact, Exact, Exact],
+            pushdowns
+        );
+
+        let out = exprs_to_predicate_string(&keys, &schema, &col_types, &filters).unwrap();
+        assert_eq!(
+            "c1 > 3 AND pk = 0d23c01f-90d9-45d0-a2a9-c289991d19aa AND c1 <= 10",
+            out
+        );
+    }
+
+    #[test]
+    fn pushdown_skips_invalid_literals() {
+        let (keys, schema, col_types) = test_table();
+
+        let filters = [col("pk").eq(lit("not-a-uuid"))];
+        let refs: Vec<_> = filters.iter().collect();
+        let pushdowns = classify_filters(&keys, &schema, &col_types, &refs);
+        assert_eq!(vec![TableProviderFilterPushDown::Unsupported], pushdowns);
+    }
+}
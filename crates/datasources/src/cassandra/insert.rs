@@ -0,0 +1,281 @@
+use super::*;
+use datafusion::arrow::array::{Array, AsArray};
+use datafusion::arrow::datatypes::{
+    Date64Type, DurationNanosecondType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
+    Int8Type, TimestampMillisecondType,
+};
+use datafusion::physical_plan::execute_stream;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use scylla::batch::{Batch, BatchType};
+use scylla::frame::value::{CqlDate, CqlDuration, CqlTimestamp};
+use scylla::prepared_statement::PreparedStatement;
+
+use crate::common::util::{create_count_record_batch, COUNT_SCHEMA};
+
+/// Max number of rows to include in a single CQL batch.
+///
+/// Cassandra warns (and eventually fails) on large batches, so keep these
+/// reasonably small.
+const INSERT_BATCH_SIZE: usize = 100;
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+const NANOS_PER_DAY: i64 = 86_400_000_000_000;
+
+pub(super) struct CassandraInsertExec {
+    input: Arc<dyn ExecutionPlan>,
+    session: Arc<Session>,
+    ks: String,
+    table: String,
+    /// Cassandra types for each column in the table.
+    col_types: Arc<Vec<ColumnType>>,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl CassandraInsertExec {
+    pub(super) fn new(
+        input: Arc<dyn ExecutionPlan>,
+        session: Arc<Session>,
+        ks: String,
+        table: String,
+        col_types: Arc<Vec<ColumnType>>,
+    ) -> CassandraInsertExec {
+        CassandraInsertExec {
+            input,
+            session,
+            ks,
+            table,
+            col_types,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl ExecutionPlan for CassandraInsertExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn schema(&self) -> ArrowSchemaRef {
+        COUNT_SCHEMA.clone()
+    }
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(CassandraInsertExec::new(
+            children[0].clone(),
+            self.session.clone(),
+            self.ks.clone(),
+            self.table.clone(),
+            self.col_types.clone(),
+        )))
+    }
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DatafusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Execution(
+                "only single partition supported".to_string(),
+            ));
+        }
+
+        let input = execute_stream(self.input.clone(), context)?;
+        let session = self.session.clone();
+        let table = format!("{}.{}", self.ks, self.table);
+        let col_types = self.col_types.clone();
+
+        let stream = futures::stream::once(async move {
+            let count = insert(session, table, col_types, input)
+                .await
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            Ok(create_count_record_batch(count))
+        });
+
+        Ok(Box::pin(DataSourceMetricsStreamAdapter::new(
+            RecordBatchStreamAdapter::new(self.schema(), stream),
+            partition,
+            &self.metrics,
+        )))
+    }
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+impl DisplayAs for CassandraInsertExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CassandraInsertExec(table = {}.{})", self.ks, self.table)
+    }
+}
+
+impl fmt::Debug for CassandraInsertExec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CassandraInsertExec")
+            .field("ks", &self.ks)
+            .field("table", &self.table)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Insert all batches from the input stream using batches of a prepared insert
+/// statement, returning the number of rows inserted.
+async fn insert(
+    session: Arc<Session>,
+    table: String,
+    col_types: Arc<Vec<ColumnType>>,
+    mut input: SendableRecordBatchStream,
+) -> Result<u64> {
+    let columns = input
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| f.name().clone())
+        .collect::<Vec<_>>();
+    let placeholders = vec!["?"; columns.len()].join(",");
+    let query = format!(
+        "INSERT INTO {table} ({}) VALUES ({placeholders})",
+        columns.join(",")
+    );
+    let prepared = session.prepare(query).await?;
+
+    let mut count = 0;
+    while let Some(batch) = input.next().await {
+        let batch = batch?;
+        let mut rows = batch_to_rows(&col_types, &batch)?.into_iter();
+        loop {
+            let chunk: Vec<_> = rows.by_ref().take(INSERT_BATCH_SIZE).collect();
+            if chunk.is_empty() {
+                break;
+            }
+            let cql_batch = new_batch(&prepared, chunk.len());
+            session.batch(&cql_batch, chunk).await?;
+        }
+        count += batch.num_rows() as u64;
+    }
+
+    Ok(count)
+}
+
+fn new_batch(prepared: &PreparedStatement, num_rows: usize) -> Batch {
+    let mut batch = Batch::new(BatchType::Unlogged);
+    for _ in 0..num_rows {
+        batch.append_statement(prepared.clone());
+    }
+    batch
+}
+
+/// Convert a record batch into rows of cql values.
+fn batch_to_rows(
+    col_types: &[ColumnType],
+    batch: &RecordBatch,
+) -> Result<Vec<Vec<Option<CqlValue>>>> {
+    let mut rows = vec![Vec::with_capacity(batch.num_columns()); batch.num_rows()];
+    for (col_type, col) in col_types.iter().zip(batch.columns()) {
+        for (idx, row) in rows.iter_mut().enumerate() {
+            row.push(value_to_cql(col_type, col.as_ref(), idx)?);
+        }
+    }
+    Ok(rows)
+}
+
+/// Get a cql value from an array.
+///
+/// This is the inverse of `CqlValueArrayBuilder`, using the column's cassandra
+/// type to determine how to convert types that don't have a one-to-one
+/// mapping (e.g. Utf8 may be text, ascii, or a uuid).
+fn value_to_cql(col_type: &ColumnType, arr: &dyn Array, idx: usize) -> Result<Option<CqlValue>> {
+    if arr.is_null(idx) {
+        return Ok(None);
+    }
+
+    let value = match (col_type, arr.data_type()) {
+        (ColumnType::Ascii, DataType::Utf8) => {
+            CqlValue::Ascii(arr.as_string::<i32>().value(idx).to_string())
+        }
+        (ColumnType::Text, DataType::Utf8) => {
+            CqlValue::Text(arr.as_string::<i32>().value(idx).to_string())
+        }
+        (ColumnType::Uuid, DataType::Utf8) => {
+            let s = arr.as_string::<i32>().value(idx);
+            let uuid = uuid::Uuid::parse_str(s)
+                .map_err(|e| CassandraError::String(format!("invalid uuid '{s}': {e}")))?;
+            CqlValue::Uuid(uuid)
+        }
+        (ColumnType::Double, DataType::Float64) => {
+            CqlValue::Double(arr.as_primitive::<Float64Type>().value(idx))
+        }
+        (ColumnType::Float, DataType::Float32) => {
+            CqlValue::Float(arr.as_primitive::<Float32Type>().value(idx))
+        }
+        (ColumnType::BigInt, DataType::Int64) => {
+            CqlValue::BigInt(arr.as_primitive::<Int64Type>().value(idx))
+        }
+        (ColumnType::Int, DataType::Int32) => {
+            CqlValue::Int(arr.as_primitive::<Int32Type>().value(idx))
+        }
+        (ColumnType::SmallInt, DataType::Int16) => {
+            CqlValue::SmallInt(arr.as_primitive::<Int16Type>().value(idx))
+        }
+        (ColumnType::TinyInt, DataType::Int8) => {
+            CqlValue::TinyInt(arr.as_primitive::<Int8Type>().value(idx))
+        }
+        (ColumnType::Timestamp, DataType::Timestamp(TimeUnit::Millisecond, None)) => {
+            CqlValue::Timestamp(CqlTimestamp(
+                arr.as_primitive::<TimestampMillisecondType>().value(idx),
+            ))
+        }
+        (ColumnType::Date, DataType::Date64) => {
+            // Cassandra dates are days since the epoch, centered at 2^31.
+            let millis = arr.as_primitive::<Date64Type>().value(idx);
+            let days = millis.div_euclid(MILLIS_PER_DAY) + (1 << 31);
+            let days = u32::try_from(days)
+                .map_err(|_| CassandraError::String(format!("date out of range: {millis}")))?;
+            CqlValue::Date(CqlDate(days))
+        }
+        (ColumnType::Duration, DataType::Duration(TimeUnit::Nanosecond)) => {
+            let nanos = arr.as_primitive::<DurationNanosecondType>().value(idx);
+            let days = i32::try_from(nanos / NANOS_PER_DAY)
+                .map_err(|_| CassandraError::String(format!("duration out of range: {nanos}")))?;
+            CqlValue::Duration(CqlDuration {
+                months: 0,
+                days,
+                nanoseconds: nanos % NANOS_PER_DAY,
+            })
+        }
+        (ColumnType::List(inner) | ColumnType::Set(inner), DataType::List(_)) => {
+            let values = arr.as_list::<i32>().value(idx);
+            let values = (0..values.len())
+                .map(|i| {
+                    value_to_cql(inner, values.as_ref(), i)?.ok_or_else(|| {
+                        CassandraError::String("cannot insert null into a collection".to_string())
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            match col_type {
+                ColumnType::Set(_) => CqlValue::Set(values),
+                _ => CqlValue::List(values),
+            }
+        }
+        (col_type, dt) => {
+            return Err(CassandraError::UnsupportedDataType(format!(
+                "cannot insert {dt} into column of type {col_type:?}"
+            )))
+        }
+    };
+
+    Ok(Some(value))
+}
@@ -1,6 +1,8 @@
 mod builder;
 mod errors;
 mod exec;
+mod filter;
+mod insert;
 use async_stream::stream;
 use async_trait::async_trait;
 use datafusion::arrow::array::{
@@ -39,6 +41,8 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use self::exec::CassandraExec;
+use self::filter::TableKeys;
+use self::insert::CassandraInsertExec;
 
 struct CassandraAccess {
     session: Session,
@@ -71,9 +75,12 @@ impl CassandraAccess {
         let session = SessionBuilder::new().known_node(conn_str).build().await?;
         Ok(Self { session })
     }
-    async fn get_schema(&self, ks: &str, table: &str) -> Result<ArrowSchema> {
+    /// Get the arrow schema for a table, along with the cassandra types for
+    /// each column.
+    async fn get_schema(&self, ks: &str, table: &str) -> Result<(ArrowSchema, Vec<ColumnType>)> {
         let query = format!("SELECT * FROM {ks}.{table} LIMIT 1");
         let res = self.session.query(query, &[]).await?;
+        let mut col_types = Vec::with_capacity(res.col_specs.len());
         let fields: Fields = res
             .col_specs
             .into_iter()
@@ -81,16 +88,46 @@ impl CassandraAccess {
                 let name = c.name;
                 let ty = c.typ;
                 let dtype = try_convert_dtype(&ty)?;
+                col_types.push(ty);
                 Ok(Field::new(name, dtype, true))
             })
             .collect::<Result<_>>()?;
-        Ok(ArrowSchema::new(fields))
+        Ok((ArrowSchema::new(fields), col_types))
+    }
+
+    /// Get the partition and clustering key columns for a table.
+    async fn get_keys(&self, ks: &str, table: &str) -> Result<TableKeys> {
+        let query = "SELECT column_name, kind, position FROM system_schema.columns WHERE keyspace_name = ? AND table_name = ?";
+        let res = self.session.query(query, (ks, table)).await?;
+        let rows = res
+            .rows_typed::<(String, String, i32)>()
+            .map_err(|e| CassandraError::String(e.to_string()))?;
+
+        let mut partition = Vec::new();
+        let mut clustering = Vec::new();
+        for row in rows {
+            let (name, kind, position) = row.map_err(|e| CassandraError::String(e.to_string()))?;
+            match kind.as_str() {
+                "partition_key" => partition.push((position, name)),
+                "clustering" => clustering.push((position, name)),
+                _ => (),
+            }
+        }
+        partition.sort();
+        clustering.sort();
+
+        Ok(TableKeys {
+            partition: partition.into_iter().map(|(_, name)| name).collect(),
+            clustering: clustering.into_iter().map(|(_, name)| name).collect(),
+        })
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct CassandraTableProvider {
     schema: Arc<ArrowSchema>,
+    col_types: Arc<Vec<ColumnType>>,
+    keys: TableKeys,
     ks: String,
     table: String,
     session: Arc<Session>,
@@ -99,9 +136,12 @@ pub struct CassandraTableProvider {
 impl CassandraTableProvider {
     pub async fn try_new(conn_str: String, ks: String, table: String) -> Result<Self> {
         let access = CassandraAccess::try_new(conn_str).await?;
-        let schema = access.get_schema(&ks, &table).await?;
+        let (schema, col_types) = access.get_schema(&ks, &table).await?;
+        let keys = access.get_keys(&ks, &table).await?;
         Ok(Self {
             schema: Arc::new(schema),
+            col_types: Arc::new(col_types),
+            keys,
             session: Arc::new(access.session),
             ks,
             table,
@@ -123,19 +163,24 @@ impl TableProvider for CassandraTableProvider {
         TableType::Base
     }
 
-    fn supports_filter_pushdown(
+    fn supports_filters_pushdown(
         &self,
-        _filter: &Expr,
-    ) -> DatafusionResult<TableProviderFilterPushDown> {
-        Ok(TableProviderFilterPushDown::Inexact)
+        filters: &[&Expr],
+    ) -> DatafusionResult<Vec<TableProviderFilterPushDown>> {
+        Ok(filter::classify_filters(
+            &self.keys,
+            &self.schema,
+            &self.col_types,
+            filters,
+        ))
     }
 
     async fn scan(
         &self,
         _ctx: &SessionState,
         projection: Option<&Vec<usize>>,
-        _filters: &[Expr],
-        _limit: Option<usize>,
+        filters: &[Expr],
+        limit: Option<usize>,
     ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
         let projected_schema = match projection {
             Some(projection) => Arc::new(self.schema.project(projection)?),
@@ -150,9 +195,24 @@ impl TableProvider for CassandraTableProvider {
             .map(|f| f.name().clone())
             .collect::<Vec<_>>()
             .join(",");
+
+        let predicate_string =
+            filter::exprs_to_predicate_string(&self.keys, &self.schema, &self.col_types, filters)
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let where_string = if predicate_string.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {predicate_string}")
+        };
+
+        let limit_string = match limit {
+            Some(limit) => format!(" LIMIT {limit}"),
+            None => String::new(),
+        };
+
         let query = format!(
-            "SELECT {} FROM {}.{}",
-            projection_string, self.ks, self.table
+            "SELECT {} FROM {}.{}{}{}",
+            projection_string, self.ks, self.table, where_string, limit_string
         );
 
         let exec = CassandraExec::new(projected_schema, query, self.session.clone());
@@ -162,11 +222,22 @@ impl TableProvider for CassandraTableProvider {
     async fn insert_into(
         &self,
         _state: &SessionState,
-        _input: Arc<dyn ExecutionPlan>,
-        _overwrite: bool,
+        input: Arc<dyn ExecutionPlan>,
+        overwrite: bool,
     ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
-        Err(DataFusionError::Execution(
-            "inserts not yet supported for Cassandra".to_string(),
-        ))
+        // Cassandra can't truncate and insert atomically.
+        if overwrite {
+            return Err(DataFusionError::NotImplemented(
+                "Overwriting cassandra tables is not supported".to_string(),
+            ));
+        }
+        let exec = CassandraInsertExec::new(
+            input,
+            self.session.clone(),
+            self.ks.clone(),
+            self.table.clone(),
+            self.col_types.clone(),
+        );
+        Ok(Arc::new(exec))
     }
 }
@@ -0,0 +1,281 @@
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{NaiveDate, TimeZone};
+use chrono_tz::Tz;
+use clickhouse_rs::Block;
+use datafusion::arrow::array::{Array, ArrayRef, AsArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{
+    DataType, Date32Type, Field, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
+    Int8Type, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef, TimeUnit,
+    TimestampNanosecondType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DatafusionResult};
+use datafusion::execution::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    execute_stream, DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning,
+    SendableRecordBatchStream, Statistics,
+};
+use datafusion_ext::metrics::DataSourceMetricsStreamAdapter;
+use futures::StreamExt;
+
+use crate::common::util::{create_count_record_batch, COUNT_SCHEMA};
+
+use super::errors::{ClickhouseError, Result};
+use super::{ClickhouseAccess, ClickhouseAccessState};
+
+/// Insert into a clickhouse table.
+///
+/// Each input batch is converted to a clickhouse block and sent as a native
+/// block insert.
+#[derive(Debug)]
+pub struct ClickhouseInsertExec {
+    input: Arc<dyn ExecutionPlan>,
+    access: ClickhouseAccess,
+    table: String,
+    /// Schema of the table being inserted into.
+    table_schema: ArrowSchemaRef,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl ClickhouseInsertExec {
+    pub fn new(
+        input: Arc<dyn ExecutionPlan>,
+        access: ClickhouseAccess,
+        table: String,
+        table_schema: ArrowSchemaRef,
+    ) -> Self {
+        ClickhouseInsertExec {
+            input,
+            access,
+            table,
+            table_schema,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl ExecutionPlan for ClickhouseInsertExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> Arc<ArrowSchema> {
+        COUNT_SCHEMA.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(ClickhouseInsertExec::new(
+            children[0].clone(),
+            self.access.clone(),
+            self.table.clone(),
+            self.table_schema.clone(),
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DatafusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Execution(
+                "ClickhouseInsertExec only supports 1 partition".to_string(),
+            ));
+        }
+
+        let input = execute_stream(self.input.clone(), context)?;
+        let access = self.access.clone();
+        let table = self.table.clone();
+        let table_schema = self.table_schema.clone();
+
+        let stream = futures::stream::once(async move {
+            let count = insert(access, table, table_schema, input)
+                .await
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            Ok(create_count_record_batch(count))
+        });
+
+        Ok(Box::pin(DataSourceMetricsStreamAdapter::new(
+            RecordBatchStreamAdapter::new(self.schema(), stream),
+            partition,
+            &self.metrics,
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+impl DisplayAs for ClickhouseInsertExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ClickhouseInsertExec(table = {})", self.table)
+    }
+}
+
+/// Insert all batches from the input stream, returning the number of rows
+/// inserted.
+///
+/// A dedicated connection is used so that inserting from a scan of the same
+/// table doesn't contend for the scan's client handle.
+async fn insert(
+    access: ClickhouseAccess,
+    table: String,
+    table_schema: ArrowSchemaRef,
+    mut input: SendableRecordBatchStream,
+) -> Result<u64> {
+    let state = ClickhouseAccessState::connect(&access.conn_string).await?;
+    let mut client = state.pool.get_handle().await?;
+
+    let mut count = 0;
+    while let Some(batch) = input.next().await {
+        let batch = batch?;
+        if batch.num_rows() == 0 {
+            continue;
+        }
+        let block = batch_to_block(&table_schema, &batch)?;
+        client.insert(&table, block).await?;
+        count += batch.num_rows() as u64;
+    }
+
+    Ok(count)
+}
+
+/// Convert a record batch into a clickhouse block.
+///
+/// This is the inverse of the conversion done in `BlockStream`, and only
+/// handles types that we map to when reading the table schema. Nullability is
+/// taken from the table schema since clickhouse won't accept nullable columns
+/// for non-nullable table columns.
+fn batch_to_block(table_schema: &ArrowSchema, batch: &RecordBatch) -> Result<Block> {
+    let mut block = Block::new();
+    for (field, col) in batch.schema().fields().iter().zip(batch.columns()) {
+        let nullable = match table_schema.field_with_name(field.name()) {
+            Ok(table_field) => table_field.is_nullable(),
+            Err(_) => true,
+        };
+        block = append_column(block, field, col, nullable)?;
+    }
+    Ok(block)
+}
+
+fn append_column(block: Block, field: &Field, col: &ArrayRef, nullable: bool) -> Result<Block> {
+    if !nullable && col.null_count() > 0 {
+        return Err(ClickhouseError::String(format!(
+            "cannot insert nulls into non-nullable column '{}'",
+            field.name()
+        )));
+    }
+
+    let name = field.name().as_str();
+    let len = col.len();
+
+    macro_rules! append {
+        ($value:expr) => {{
+            let value = $value;
+            if nullable {
+                let vals: Vec<_> = (0..len)
+                    .map(|idx| col.is_valid(idx).then(|| value(idx)))
+                    .collect();
+                block.column(name, vals)
+            } else {
+                let vals: Vec<_> = (0..len).map(value).collect();
+                block.column(name, vals)
+            }
+        }};
+    }
+
+    macro_rules! append_primitive {
+        ($typ:ty) => {{
+            let arr = col.as_primitive::<$typ>();
+            append!(|idx| arr.value(idx))
+        }};
+    }
+
+    let block = match field.data_type() {
+        DataType::Boolean => {
+            let arr = col.as_boolean();
+            append!(|idx| arr.value(idx))
+        }
+        DataType::UInt8 => append_primitive!(UInt8Type),
+        DataType::UInt16 => append_primitive!(UInt16Type),
+        DataType::UInt32 => append_primitive!(UInt32Type),
+        DataType::UInt64 => append_primitive!(UInt64Type),
+        DataType::Int8 => append_primitive!(Int8Type),
+        DataType::Int16 => append_primitive!(Int16Type),
+        DataType::Int32 => append_primitive!(Int32Type),
+        DataType::Int64 => append_primitive!(Int64Type),
+        DataType::Float32 => append_primitive!(Float32Type),
+        DataType::Float64 => append_primitive!(Float64Type),
+        DataType::Utf8 => {
+            let arr = col.as_string::<i32>();
+            append!(|idx| arr.value(idx).to_string())
+        }
+        DataType::Date32 => {
+            let arr = col.as_primitive::<Date32Type>();
+            // Convert up front so that dates chrono can't represent error
+            // instead of being silently replaced. Nulls are skipped when
+            // building the column.
+            let mut dates = Vec::with_capacity(len);
+            for idx in 0..len {
+                let date = if col.is_valid(idx) {
+                    arr.value_as_date(idx).ok_or_else(|| {
+                        ClickhouseError::String(format!(
+                            "date out of range for column '{}': {} days since epoch",
+                            field.name(),
+                            arr.value(idx)
+                        ))
+                    })?
+                } else {
+                    NaiveDate::default()
+                };
+                dates.push(date);
+            }
+            append!(|idx| dates[idx])
+        }
+        DataType::Timestamp(_, tz) => {
+            let tz: Tz = match tz {
+                Some(tz) => tz
+                    .parse()
+                    .map_err(|e| ClickhouseError::String(format!("invalid timezone: {e}")))?,
+                None => Tz::UTC,
+            };
+            let nanos = cast(col, &DataType::Timestamp(TimeUnit::Nanosecond, None))?;
+            let arr = nanos.as_primitive::<TimestampNanosecondType>();
+            append!(|idx| tz.timestamp_nanos(arr.value(idx)))
+        }
+        other => {
+            return Err(ClickhouseError::String(format!(
+                "unhandled data type trying to convert to clickhouse block: {other}"
+            )))
+        }
+    };
+
+    Ok(block)
+}
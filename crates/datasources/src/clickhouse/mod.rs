@@ -1,5 +1,6 @@
 pub mod errors;
 
+mod insert;
 mod stream;
 
 use clickhouse_rs::types::DateTimeType;
@@ -17,18 +18,22 @@ use datafusion::arrow::datatypes::{
 use datafusion::datasource::TableProvider;
 use datafusion::error::{DataFusionError, Result as DatafusionResult};
 use datafusion::execution::context::{SessionState, TaskContext};
-use datafusion::logical_expr::{Expr, TableProviderFilterPushDown, TableType};
+use datafusion::logical_expr::{Expr, Operator, TableProviderFilterPushDown, TableType};
 use datafusion::physical_plan::{
     DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
     Statistics,
 };
+use datafusion::scalar::ScalarValue;
 use futures::StreamExt;
 use std::any::Any;
-use std::fmt;
+use std::fmt::{self, Write};
 use std::sync::Arc;
+use tracing::{debug, trace};
 use url::Url;
 
+use crate::clickhouse::insert::ClickhouseInsertExec;
 use crate::clickhouse::stream::BlockStream;
+use crate::common::util;
 
 #[derive(Debug, Clone)]
 pub struct ClickhouseAccess {
@@ -166,6 +171,7 @@ impl ClickhouseAccessState {
 }
 
 pub struct ClickhouseTableProvider {
+    access: ClickhouseAccess,
     state: Arc<ClickhouseAccessState>,
     table: String,
     schema: Arc<ArrowSchema>,
@@ -178,6 +184,7 @@ impl ClickhouseTableProvider {
         let schema = Arc::new(state.get_table_schema(&table).await?);
 
         Ok(ClickhouseTableProvider {
+            access,
             state,
             table,
             schema,
@@ -210,8 +217,8 @@ impl TableProvider for ClickhouseTableProvider {
         &self,
         _ctx: &SessionState,
         projection: Option<&Vec<usize>>,
-        _filters: &[Expr],
-        _limit: Option<usize>,
+        filters: &[Expr],
+        limit: Option<usize>,
     ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
         let projected_schema = match projection {
             Some(projection) => Arc::new(self.schema.project(projection)?),
@@ -227,9 +234,24 @@ impl TableProvider for ClickhouseTableProvider {
             .collect::<Vec<_>>()
             .join(",");
 
-        // TODO: Where, Limit
+        let predicate_string = exprs_to_predicate_string(filters)
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let where_string = if predicate_string.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {predicate_string}")
+        };
+
+        let limit_string = match limit {
+            Some(limit) => format!(" LIMIT {limit}"),
+            None => String::new(),
+        };
 
-        let query = format!("SELECT {} FROM {};", projection_string, self.table);
+        let query = format!(
+            "SELECT {} FROM {}{}{};",
+            projection_string, self.table, where_string, limit_string
+        );
+        trace!(%query, "clickhouse scan query");
 
         let client =
             self.state.pool.get_handle().await.map_err(|e| {
@@ -246,12 +268,25 @@ impl TableProvider for ClickhouseTableProvider {
     async fn insert_into(
         &self,
         _state: &SessionState,
-        _input: Arc<dyn ExecutionPlan>,
-        _overwrite: bool,
+        input: Arc<dyn ExecutionPlan>,
+        overwrite: bool,
     ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
-        Err(DataFusionError::Execution(
-            "inserts not yet supported for Clickhouse".to_string(),
-        ))
+        // Clickhouse doesn't support transactions, so a truncate can't be
+        // rolled back if the insert fails.
+        if overwrite {
+            return Err(DataFusionError::NotImplemented(
+                "Overwriting clickhouse tables is not supported".to_string(),
+            ));
+        }
+
+        debug!(table = %self.table, "inserting into clickhouse datasource");
+
+        Ok(Arc::new(ClickhouseInsertExec::new(
+            input,
+            self.access.clone(),
+            self.table.clone(),
+            self.schema.clone(),
+        )))
     }
 }
 
@@ -361,3 +396,118 @@ impl fmt::Debug for ClickhouseExec {
             .finish_non_exhaustive()
     }
 }
+
+/// Convert filtering expressions to a predicate string usable with the
+/// generated Clickhouse query.
+///
+/// Expressions that can't be converted are skipped. Filters are only ever
+/// pushed down inexactly, so they'll still be applied after the scan.
+fn exprs_to_predicate_string(exprs: &[Expr]) -> Result<String> {
+    let mut ss = Vec::new();
+    for expr in exprs {
+        let mut buf = String::new();
+        if write_expr(expr, &mut buf)? {
+            ss.push(buf);
+        }
+    }
+    Ok(ss.join(" AND "))
+}
+
+/// Try to write the expression to the string, returning true if it was written.
+fn write_expr(expr: &Expr, buf: &mut String) -> Result<bool> {
+    match expr {
+        Expr::Column(col) => {
+            write!(buf, "{}", col.name)?;
+        }
+        Expr::Literal(val) => {
+            // Binary and timezone-aware timestamp literals don't have a text
+            // encoding that clickhouse will compare correctly.
+            if matches!(
+                val,
+                ScalarValue::Binary(_)
+                    | ScalarValue::TimestampNanosecond(_, Some(_))
+                    | ScalarValue::TimestampMicrosecond(_, Some(_))
+            ) {
+                return Ok(false);
+            }
+            if util::encode_literal_to_text(util::Datasource::Clickhouse, buf, val).is_err() {
+                return Ok(false);
+            }
+        }
+        Expr::IsNull(expr) => {
+            if write_expr(expr, buf)? {
+                write!(buf, " IS NULL")?;
+            } else {
+                return Ok(false);
+            }
+        }
+        Expr::IsNotNull(expr) => {
+            if write_expr(expr, buf)? {
+                write!(buf, " IS NOT NULL")?;
+            } else {
+                return Ok(false);
+            }
+        }
+        Expr::BinaryExpr(binary)
+            if matches!(
+                binary.op,
+                Operator::Eq
+                    | Operator::NotEq
+                    | Operator::Lt
+                    | Operator::LtEq
+                    | Operator::Gt
+                    | Operator::GtEq
+                    | Operator::And
+                    | Operator::Or
+            ) =>
+        {
+            write!(buf, "(")?;
+            if !write_expr(binary.left.as_ref(), buf)? {
+                return Ok(false);
+            }
+            write!(buf, " {} ", binary.op)?;
+            if !write_expr(binary.right.as_ref(), buf)? {
+                return Ok(false);
+            }
+            write!(buf, ")")?;
+        }
+        _ => {
+            // Unsupported.
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::logical_expr::{col, lit};
+
+    use super::*;
+
+    #[test]
+    fn valid_expr_string() {
+        let exprs = vec![
+            col("a").lt(col("b")),
+            col("c").eq(lit("hello")).or(col("d").gt_eq(lit(3_i64))),
+            col("e").is_null(),
+        ];
+
+        let out = exprs_to_predicate_string(&exprs).unwrap();
+        assert_eq!(out, "(a < b) AND ((c = 'hello') OR (d >= 3)) AND e IS NULL");
+    }
+
+    #[test]
+    fn skip_unsupported_expr_string() {
+        let exprs = vec![
+            col("a").lt(col("b")),
+            // Arithmetic isn't pushed down.
+            (col("c") + lit(1_i64)).eq(lit(2_i64)),
+            col("d").eq(lit(ScalarValue::Binary(Some(vec![1, 2])))),
+        ];
+
+        let out = exprs_to_predicate_string(&exprs).unwrap();
+        assert_eq!(out, "(a < b)");
+    }
+}
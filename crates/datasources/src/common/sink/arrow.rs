@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::ipc::writer::FileWriter as IpcFileWriter;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Result as DfResult;
+use datafusion::error::DataFusionError;
+use datafusion::execution::TaskContext;
+use datafusion::physical_plan::insert::DataSink;
+use datafusion::physical_plan::DisplayAs;
+use datafusion::physical_plan::{DisplayFormatType, SendableRecordBatchStream};
+use futures::StreamExt;
+use object_store::{path::Path as ObjectPath, ObjectStore};
+use std::fmt::Display;
+use std::sync::Arc;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::common::errors::Result;
+
+use super::SharedBuffer;
+
+const BUFFER_SIZE: usize = 2 * 1024 * 1024;
+
+/// Sink for writing batches out as an Arrow IPC file.
+#[derive(Debug)]
+pub struct ArrowSink {
+    store: Arc<dyn ObjectStore>,
+    loc: ObjectPath,
+}
+
+impl Display for ArrowSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ArrowSink({}:{})", self.store, self.loc)
+    }
+}
+
+impl DisplayAs for ArrowSink {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(f, "{self}"),
+            DisplayFormatType::Verbose => write!(f, "{self}"),
+        }
+    }
+}
+
+impl ArrowSink {
+    pub fn from_obj_store(store: Arc<dyn ObjectStore>, loc: impl Into<ObjectPath>) -> ArrowSink {
+        ArrowSink {
+            store,
+            loc: loc.into(),
+        }
+    }
+
+    async fn stream_into_inner(&self, mut stream: SendableRecordBatchStream) -> Result<usize> {
+        let schema = stream.schema();
+        let (_id, obj_handle) = self.store.put_multipart(&self.loc).await?;
+        let mut writer = AsyncIpcWriter::try_new(obj_handle, &schema, BUFFER_SIZE)?;
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            writer.write_batch(batch).await?;
+        }
+        writer.finish().await
+    }
+}
+
+#[async_trait]
+impl DataSink for ArrowSink {
+    async fn write_all(
+        &self,
+        data: Vec<SendableRecordBatchStream>,
+        _context: &Arc<TaskContext>,
+    ) -> DfResult<u64> {
+        let mut count = 0;
+        for stream in data {
+            count += self
+                .stream_into_inner(stream)
+                .await
+                .map(|x| x as u64)
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        }
+        Ok(count)
+    }
+}
+
+/// Wrapper around Arrow's IPC file writer to provide async write support.
+///
+/// Modeled after the parquet crate's `AsyncArrowWriter`.
+struct AsyncIpcWriter<W> {
+    async_writer: W,
+    sync_writer: IpcFileWriter<SharedBuffer>,
+    buffer: SharedBuffer,
+    row_count: usize,
+}
+
+impl<W: AsyncWrite + Unpin + Send> AsyncIpcWriter<W> {
+    fn try_new(async_writer: W, schema: &Schema, buf_size: usize) -> Result<Self> {
+        let buf = SharedBuffer::with_capacity(buf_size);
+        let sync_writer = IpcFileWriter::try_new(buf.clone(), schema)?;
+        Ok(AsyncIpcWriter {
+            async_writer,
+            sync_writer,
+            buffer: buf,
+            row_count: 0,
+        })
+    }
+
+    async fn write_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        let num_rows = batch.num_rows();
+        self.sync_writer.write(&batch)?;
+        self.try_flush(false).await?;
+        self.row_count += num_rows;
+        Ok(())
+    }
+
+    async fn finish(mut self) -> Result<usize> {
+        self.sync_writer.finish()?;
+        self.try_flush(true).await?;
+        self.async_writer.shutdown().await?;
+        Ok(self.row_count)
+    }
+
+    async fn try_flush(&mut self, force: bool) -> Result<()> {
+        let mut buf = self.buffer.buffer.try_lock().unwrap();
+        if !force && buf.len() < buf.capacity() / 2 {
+            return Ok(());
+        }
+
+        self.async_writer.write_all(&buf).await?;
+        self.async_writer.flush().await?;
+
+        buf.clear();
+
+        Ok(())
+    }
+}
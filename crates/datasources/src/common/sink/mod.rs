@@ -1,3 +1,4 @@
+pub mod arrow;
 pub mod bson;
 pub mod csv;
 pub mod json;
@@ -24,6 +24,10 @@ pub enum Datasource {
     MySql,
     BigQuery,
     Snowflake,
+    Clickhouse,
+    SqlServer,
+    Cassandra,
+    Sqlite,
 }
 
 /// Returns true if the literal expression encoding should be wrapped inside
@@ -37,7 +41,22 @@ fn is_literal_quotable(datasource: Datasource, lit: &ScalarValue) -> bool {
         | ScalarValue::Float32(_)
         | ScalarValue::Float64(_)
         | ScalarValue::Decimal128(..) => false,
-        ScalarValue::Binary(_) if datasource == Datasource::MySql => false,
+        ScalarValue::Binary(_)
+            if matches!(
+                datasource,
+                Datasource::MySql | Datasource::SqlServer | Datasource::Cassandra
+            ) =>
+        {
+            false
+        }
+        ScalarValue::Boolean(_)
+            if matches!(
+                datasource,
+                Datasource::Clickhouse | Datasource::SqlServer | Datasource::Cassandra
+            ) =>
+        {
+            false
+        }
         _ => true,
     }
 }
@@ -58,6 +77,11 @@ pub fn encode_literal_to_text(
         buf.write_str("'")?;
     }
     match lit {
+        // SQL Server doesn't have boolean literals, bits are compared against
+        // 1 and 0 instead.
+        ScalarValue::Boolean(Some(v)) if datasource == Datasource::SqlServer => {
+            buf.write_str(if *v { "1" } else { "0" })?;
+        }
         ScalarValue::Boolean(Some(v)) => {
             if *v {
                 buf.write_str("TRUE")?;
@@ -72,7 +96,12 @@ pub fn encode_literal_to_text(
         ScalarValue::Float32(Some(v)) => encode_float(buf, *v)?,
         ScalarValue::Float64(Some(v)) => encode_float(buf, *v)?,
         ScalarValue::Utf8(Some(v)) => encode_string(buf, v)?,
-        ScalarValue::Binary(Some(v)) if datasource == Datasource::MySql => {
+        ScalarValue::Binary(Some(v))
+            if matches!(
+                datasource,
+                Datasource::MySql | Datasource::SqlServer | Datasource::Cassandra
+            ) =>
+        {
             encode_binary_mysql(buf, v)?
         }
         ScalarValue::Binary(Some(v)) if datasource == Datasource::Snowflake => {
@@ -253,6 +282,26 @@ mod tests {
                 literal: ScalarValue::Binary(Some(b"abc".to_vec())),
                 expected: Some("'616263'"),
             },
+            TestCase {
+                datasource: SqlServer,
+                literal: ScalarValue::Binary(Some(b"abc".to_vec())),
+                expected: Some("0x616263"),
+            },
+            TestCase {
+                datasource: Postgres,
+                literal: ScalarValue::Boolean(Some(true)),
+                expected: Some("'TRUE'"),
+            },
+            TestCase {
+                datasource: Clickhouse,
+                literal: ScalarValue::Boolean(Some(true)),
+                expected: Some("TRUE"),
+            },
+            TestCase {
+                datasource: SqlServer,
+                literal: ScalarValue::Boolean(Some(false)),
+                expected: Some("0"),
+            },
             TestCase {
                 datasource: Postgres,
                 literal: ScalarValue::TimestampNanosecond(Some(938709124 * 1_000_000_000), None),
@@ -0,0 +1,159 @@
+use crate::common::url::DatasourceUrl;
+use crate::native::insert::NativeTableInsertExec;
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::datasource::streaming::StreamingTable;
+use datafusion::datasource::TableProvider;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::context::SessionState;
+use datafusion::execution::TaskContext;
+use datafusion::physical_plan::insert::DataSink;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::streaming::PartitionStream;
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, SendableRecordBatchStream,
+};
+use deltalake::protocol::SaveMode;
+use deltalake::storage::DeltaObjectStore;
+use deltalake::{DeltaTable, DeltaTableConfig, DeltaTableError};
+use futures::StreamExt;
+use object_store::path::Path as ObjectStorePath;
+use object_store::prefix::PrefixStore;
+use object_store::ObjectStore;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Appends data to a delta table, creating the table if it doesn't exist.
+///
+/// All input streams are written as part of a single commit.
+#[derive(Debug, Clone)]
+pub struct DeltaSink {
+    location: DatasourceUrl,
+    store: Arc<dyn ObjectStore>,
+}
+
+impl DeltaSink {
+    /// Create a new sink for the table at `location`.
+    ///
+    /// `store` should be rooted at the bucket (or filesystem root) containing
+    /// the table.
+    pub fn new(location: DatasourceUrl, store: Arc<dyn ObjectStore>) -> DeltaSink {
+        DeltaSink { location, store }
+    }
+
+    /// Open the delta table at the location.
+    ///
+    /// If there's no table at the location, the returned table will be
+    /// unloaded, and will be created on first write.
+    async fn open_table(&self) -> DataFusionResult<DeltaTable> {
+        let url = self
+            .location
+            .as_url()
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let prefix = ObjectStorePath::from(self.location.path().as_ref());
+        let prefixed: Arc<dyn ObjectStore> = Arc::new(PrefixStore::new(self.store.clone(), prefix));
+        let delta_store = Arc::new(DeltaObjectStore::new(prefixed, url));
+
+        let mut table = DeltaTable::new(delta_store, DeltaTableConfig::default());
+        match table.load().await {
+            Ok(_) | Err(DeltaTableError::NotATable(_)) => Ok(table),
+            Err(e) => Err(DataFusionError::External(Box::new(e))),
+        }
+    }
+}
+
+impl fmt::Display for DeltaSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DeltaSink({})", self.location)
+    }
+}
+
+impl DisplayAs for DeltaSink {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(f, "{self}"),
+            DisplayFormatType::Verbose => write!(f, "{self}"),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSink for DeltaSink {
+    async fn write_all(
+        &self,
+        data: Vec<SendableRecordBatchStream>,
+        context: &Arc<TaskContext>,
+    ) -> DataFusionResult<u64> {
+        let schema = match data.first() {
+            Some(stream) => stream.schema(),
+            None => return Ok(0),
+        };
+
+        let table = self.open_table().await?;
+
+        // Wrap the input streams so that they can be used as the input to an
+        // insert, counting rows as they're written.
+        let count = Arc::new(AtomicU64::new(0));
+        let partitions = data
+            .into_iter()
+            .map(|stream| {
+                let count = count.clone();
+                let stream = stream.inspect(move |batch| {
+                    if let Ok(batch) = batch {
+                        count.fetch_add(batch.num_rows() as u64, Ordering::Relaxed);
+                    }
+                });
+                let stream = Box::pin(RecordBatchStreamAdapter::new(schema.clone(), stream));
+                Arc::new(SinkPartition::new(schema.clone(), stream)) as Arc<dyn PartitionStream>
+            })
+            .collect();
+
+        let state = SessionState::new_with_config_rt(
+            context.session_config().clone(),
+            context.runtime_env(),
+        );
+        let input = StreamingTable::try_new(schema.clone(), partitions)?
+            .scan(&state, None, &[], None)
+            .await?;
+
+        let exec =
+            NativeTableInsertExec::new(input, table.object_store(), table.state, SaveMode::Append);
+        let mut stream = exec.execute(0, context.clone())?;
+        while let Some(res) = stream.next().await {
+            // Drain stream to write everything.
+            let _ = res?;
+        }
+
+        Ok(count.load(Ordering::Relaxed))
+    }
+}
+
+/// A partition that yields a single, already executing stream.
+struct SinkPartition {
+    schema: SchemaRef,
+    stream: Mutex<Option<SendableRecordBatchStream>>,
+}
+
+impl SinkPartition {
+    fn new(schema: SchemaRef, stream: SendableRecordBatchStream) -> Self {
+        SinkPartition {
+            schema,
+            stream: Mutex::new(Some(stream)),
+        }
+    }
+}
+
+impl PartitionStream for SinkPartition {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        self.stream
+            .lock()
+            .unwrap()
+            .take()
+            .expect("stream to only be executed once")
+    }
+}
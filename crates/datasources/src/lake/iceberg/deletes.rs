@@ -0,0 +1,449 @@
+//! Row-level deletes for iceberg tables.
+//!
+//! v2 tables may contain delete files alongside data files:
+//!
+//! - Position deletes: Rows identified by the data file path and the position
+//!   of the row in that file.
+//! - Equality deletes: Rows identified by the values for one or more columns.
+//!
+//! Delete files only apply to data files with an older sequence number (or the
+//! same sequence number for position deletes).
+//!
+//! See <https://iceberg.apache.org/spec/#row-level-deletes>
+
+use super::spec::DataFile;
+
+use crate::lake::iceberg::errors::{IcebergError, Result};
+use datafusion::arrow::array::{Array, BooleanArray, Int64Array, StringArray};
+use datafusion::arrow::compute::filter_record_batch;
+use datafusion::arrow::datatypes::{Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::context::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+    Statistics,
+};
+use datafusion::scalar::ScalarValue;
+use futures::StreamExt;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// A data or delete file to include in a scan along with the metadata needed
+/// to determine which deletes apply to it.
+#[derive(Debug, Clone)]
+pub struct ScanFile {
+    pub file: DataFile,
+    /// Data sequence number for the file.
+    pub sequence_number: i64,
+    /// Id of the partition spec the file was written with.
+    pub spec_id: i32,
+    /// If the spec the file was written with is unpartitioned.
+    pub unpartitioned: bool,
+}
+
+/// Index of all delete files for a scan.
+#[derive(Debug, Default)]
+pub struct DeleteIndex {
+    position: Vec<PositionDeleteFile>,
+    equality: Vec<EqualityDeleteFile>,
+}
+
+#[derive(Debug)]
+struct PositionDeleteFile {
+    sequence_number: i64,
+    /// Deleted positions keyed by data file path.
+    positions: HashMap<String, Vec<i64>>,
+}
+
+#[derive(Debug)]
+struct EqualityDeleteFile {
+    sequence_number: i64,
+    spec_id: i32,
+    unpartitioned: bool,
+    partition_values: Vec<ScalarValue>,
+    deletes: Arc<EqualityDeletes>,
+}
+
+impl DeleteIndex {
+    /// Add the contents of a position delete file.
+    ///
+    /// Batches are expected to have the 'file_path' and 'pos' columns as the
+    /// first two columns.
+    pub fn add_position_deletes(&mut self, file: &ScanFile, batches: &[RecordBatch]) -> Result<()> {
+        let mut positions: HashMap<String, Vec<i64>> = HashMap::new();
+        for batch in batches {
+            let (paths, pos) = match (
+                batch.column(0).as_any().downcast_ref::<StringArray>(),
+                batch.column(1).as_any().downcast_ref::<Int64Array>(),
+            ) {
+                (Some(paths), Some(pos)) => (paths, pos),
+                _ => {
+                    return Err(IcebergError::DataInvalid(format!(
+                        "Unexpected schema for position delete file: {}",
+                        batch.schema()
+                    )))
+                }
+            };
+
+            for idx in 0..batch.num_rows() {
+                if paths.is_null(idx) || pos.is_null(idx) {
+                    continue;
+                }
+                positions
+                    .entry(paths.value(idx).to_string())
+                    .or_default()
+                    .push(pos.value(idx));
+            }
+        }
+
+        self.position.push(PositionDeleteFile {
+            sequence_number: file.sequence_number,
+            positions,
+        });
+
+        Ok(())
+    }
+
+    /// Add the contents of an equality delete file.
+    ///
+    /// `columns` are the indices of the equality columns in the table schema,
+    /// and batches are expected to contain only those columns in the same
+    /// order.
+    pub fn add_equality_deletes(
+        &mut self,
+        file: &ScanFile,
+        columns: Vec<usize>,
+        batches: &[RecordBatch],
+    ) -> Result<()> {
+        let mut values = HashSet::new();
+        for batch in batches {
+            for row in 0..batch.num_rows() {
+                let row = batch
+                    .columns()
+                    .iter()
+                    .map(|col| ScalarValue::try_from_array(col, row))
+                    .collect::<DataFusionResult<Vec<_>>>()?;
+                values.insert(row);
+            }
+        }
+
+        self.equality.push(EqualityDeleteFile {
+            sequence_number: file.sequence_number,
+            spec_id: file.spec_id,
+            unpartitioned: file.unpartitioned,
+            partition_values: file.file.partition_values.clone(),
+            deletes: Arc::new(EqualityDeletes { columns, values }),
+        });
+
+        Ok(())
+    }
+
+    /// Get the deletes that apply to a data file.
+    pub fn deletes_for_file(&self, file: &ScanFile) -> FileDeletes {
+        let mut positions: Vec<i64> = self
+            .position
+            .iter()
+            .filter(|d| file.sequence_number <= d.sequence_number)
+            .filter_map(|d| d.positions.get(&file.file.file_path))
+            .flatten()
+            .copied()
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+
+        let equality = self
+            .equality
+            .iter()
+            .filter(|d| file.sequence_number < d.sequence_number)
+            .filter(|d| {
+                d.unpartitioned
+                    || (d.spec_id == file.spec_id
+                        && d.partition_values == file.file.partition_values)
+            })
+            .map(|d| d.deletes.clone())
+            .collect();
+
+        FileDeletes {
+            positions,
+            equality,
+        }
+    }
+}
+
+/// Deleted values for a set of columns.
+#[derive(Debug)]
+pub struct EqualityDeletes {
+    /// Indices of the columns in the table schema.
+    columns: Vec<usize>,
+    values: HashSet<Vec<ScalarValue>>,
+}
+
+/// All deletes that apply to a single data file.
+#[derive(Debug, Default)]
+pub struct FileDeletes {
+    /// Sorted positions of deleted rows.
+    positions: Vec<i64>,
+    equality: Vec<Arc<EqualityDeletes>>,
+}
+
+impl FileDeletes {
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty() && self.equality.is_empty()
+    }
+
+    /// Get a mask of the rows to keep for a batch read from the data file.
+    ///
+    /// `offset` is the position in the file of the first row in the batch.
+    fn keep_mask(&self, offset: i64, batch: &RecordBatch) -> DataFusionResult<BooleanArray> {
+        let num_rows = batch.num_rows();
+        let mut keep = vec![true; num_rows];
+
+        let start = self.positions.partition_point(|pos| *pos < offset);
+        for pos in &self.positions[start..] {
+            let idx = (pos - offset) as usize;
+            if idx >= num_rows {
+                break;
+            }
+            keep[idx] = false;
+        }
+
+        for deletes in &self.equality {
+            let columns: Vec<_> = deletes
+                .columns
+                .iter()
+                .map(|idx| batch.column(*idx))
+                .collect();
+            for (row, keep) in keep.iter_mut().enumerate() {
+                if !*keep {
+                    continue;
+                }
+                let values = columns
+                    .iter()
+                    .map(|col| ScalarValue::try_from_array(col, row))
+                    .collect::<DataFusionResult<Vec<_>>>()?;
+                if deletes.values.contains(&values) {
+                    *keep = false;
+                }
+            }
+        }
+
+        Ok(BooleanArray::from(keep))
+    }
+}
+
+/// Removes deleted rows from a scan of a single data file.
+///
+/// The input must read the full table schema from a single file, in order,
+/// without any pruning so that row positions line up with the positions in
+/// delete files. For that reason the input is not exposed as a child of this
+/// plan, preventing the optimizer from repartitioning it.
+#[derive(Debug)]
+pub struct IcebergDeleteFilterExec {
+    input: Arc<dyn ExecutionPlan>,
+    deletes: Arc<FileDeletes>,
+    projection: Option<Vec<usize>>,
+    schema: ArrowSchemaRef,
+}
+
+impl IcebergDeleteFilterExec {
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        deletes: FileDeletes,
+        projection: Option<Vec<usize>>,
+    ) -> Result<Self> {
+        let schema = match &projection {
+            Some(projection) => Arc::new(input.schema().project(projection)?),
+            None => input.schema(),
+        };
+        Ok(IcebergDeleteFilterExec {
+            input,
+            deletes: Arc::new(deletes),
+            projection,
+            schema,
+        })
+    }
+}
+
+impl ExecutionPlan for IcebergDeleteFilterExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> Arc<ArrowSchema> {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        Vec::new()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if children.is_empty() {
+            Ok(self)
+        } else {
+            Err(DataFusionError::Plan(
+                "Cannot change children for IcebergDeleteFilterExec".to_string(),
+            ))
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        let stream = self.input.execute(partition, context)?;
+        let deletes = self.deletes.clone();
+        let projection = self.projection.clone();
+
+        let mut offset = 0;
+        let stream = stream.map(move |batch| {
+            let batch = batch?;
+            let keep = deletes.keep_mask(offset, &batch)?;
+            offset += batch.num_rows() as i64;
+
+            let batch = filter_record_batch(&batch, &keep)?;
+            Ok(match &projection {
+                Some(projection) => batch.project(projection)?,
+                None => batch,
+            })
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            stream,
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+impl DisplayAs for IcebergDeleteFilterExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "IcebergDeleteFilterExec: positions={}, equality_deletes={}, input=",
+            self.deletes.positions.len(),
+            self.deletes.equality.len(),
+        )?;
+        self.input.fmt_as(t, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::array::Int32Array;
+    use datafusion::arrow::datatypes::{DataType, Field};
+
+    fn scan_file(path: &str, sequence_number: i64) -> ScanFile {
+        ScanFile {
+            file: DataFile {
+                content: 0,
+                file_path: path.to_string(),
+                file_format: "PARQUET".to_string(),
+                record_count: 0,
+                file_size_in_bytes: 0,
+                column_sizes: None,
+                value_counts: None,
+                null_value_counts: None,
+                nan_value_counts: None,
+                distinct_counts: None,
+                lower_bounds: None,
+                upper_bounds: None,
+                key_metadata: None,
+                split_offsets: None,
+                equality_ids: None,
+                sort_order_id: None,
+                partition_values: Vec::new(),
+            },
+            sequence_number,
+            spec_id: 0,
+            unpartitioned: true,
+        }
+    }
+
+    fn int_batch(values: Vec<i32>) -> RecordBatch {
+        let schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "a",
+            DataType::Int32,
+            true,
+        )]));
+        RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(values))]).unwrap()
+    }
+
+    fn position_batch(rows: Vec<(&str, i64)>) -> RecordBatch {
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("file_path", DataType::Utf8, false),
+            Field::new("pos", DataType::Int64, false),
+        ]));
+        let paths: StringArray = rows.iter().map(|(p, _)| Some(*p)).collect();
+        let pos: Int64Array = rows.iter().map(|(_, p)| Some(*p)).collect();
+        RecordBatch::try_new(schema, vec![Arc::new(paths), Arc::new(pos)]).unwrap()
+    }
+
+    #[test]
+    fn test_position_deletes() {
+        let mut index = DeleteIndex::default();
+        index
+            .add_position_deletes(
+                &scan_file("deletes.parquet", 2),
+                &[position_batch(vec![
+                    ("a.parquet", 1),
+                    ("a.parquet", 4),
+                    ("b.parquet", 0),
+                ])],
+            )
+            .unwrap();
+
+        let deletes = index.deletes_for_file(&scan_file("a.parquet", 1));
+        assert_eq!(vec![1, 4], deletes.positions);
+
+        // First batch is positions 0-2, second is 3-5.
+        let mask = deletes.keep_mask(0, &int_batch(vec![0, 1, 2])).unwrap();
+        assert_eq!(BooleanArray::from(vec![true, false, true]), mask);
+        let mask = deletes.keep_mask(3, &int_batch(vec![3, 4, 5])).unwrap();
+        assert_eq!(BooleanArray::from(vec![true, false, true]), mask);
+
+        // Data file written after the deletes.
+        let deletes = index.deletes_for_file(&scan_file("a.parquet", 3));
+        assert!(deletes.is_empty());
+    }
+
+    #[test]
+    fn test_equality_deletes() {
+        let mut index = DeleteIndex::default();
+        index
+            .add_equality_deletes(
+                &scan_file("deletes.parquet", 2),
+                vec![0],
+                &[int_batch(vec![2, 3])],
+            )
+            .unwrap();
+
+        let deletes = index.deletes_for_file(&scan_file("a.parquet", 1));
+        let mask = deletes.keep_mask(0, &int_batch(vec![1, 2, 3, 4])).unwrap();
+        assert_eq!(BooleanArray::from(vec![true, false, false, true]), mask);
+
+        // Equality deletes don't apply to files with the same sequence number.
+        let deletes = index.deletes_for_file(&scan_file("a.parquet", 2));
+        assert!(deletes.is_empty());
+    }
+}
@@ -6,6 +6,15 @@ pub enum IcebergError {
     #[error("Data is invalid: {0}")]
     DataInvalid(String),
 
+    #[error("Missing snapshot for id: {0}")]
+    MissingSnapshot(i64),
+
+    #[error("No snapshot exists as of {0}")]
+    NoSnapshotAsOf(chrono::DateTime<chrono::Utc>),
+
+    #[error("Table was concurrently modified, metadata version {0} already exists")]
+    CommitConflict(u64),
+
     #[error(transparent)]
     ObjectStore(#[from] object_store::Error),
 
@@ -21,6 +30,15 @@ pub enum IcebergError {
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
 
+    #[error(transparent)]
+    Avro(#[from] apache_avro::Error),
+
+    #[error(transparent)]
+    Parquet(#[from] datafusion::parquet::errors::ParquetError),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
     #[error("{0}")]
     Static(&'static str),
 }
@@ -1,4 +1,8 @@
 pub mod errors;
+pub mod sink;
 pub mod table;
 
+mod deletes;
+mod pruning;
 mod spec;
+mod writer;
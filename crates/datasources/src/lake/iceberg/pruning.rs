@@ -0,0 +1,426 @@
+//! Pruning of manifests and data files using the statistics stored in table
+//! metadata.
+//!
+//! Manifest lists contain per-manifest summaries for each partition field, and
+//! manifests contain per-file column bounds and null counts. Both are exposed
+//! to datafusion's `PruningPredicate` so that manifests and files that can't
+//! contain matching rows can be skipped entirely.
+
+use super::spec::{
+    AnyType, DataFile, FieldSummary, PartitionField, PrimitiveType, Schema, Transform,
+};
+
+use crate::lake::iceberg::errors::Result;
+use datafusion::arrow::array::{ArrayRef, UInt64Array};
+use datafusion::arrow::datatypes::SchemaRef as ArrowSchemaRef;
+use datafusion::common::{Column, ScalarValue};
+use datafusion::physical_optimizer::pruning::{PruningPredicate, PruningStatistics};
+use datafusion::physical_plan::ColumnStatistics;
+use std::sync::Arc;
+
+/// Statistics for a single column in a container (a manifest or data file).
+#[derive(Debug, Clone, Default)]
+pub struct ColumnBounds {
+    pub min: Option<ScalarValue>,
+    pub max: Option<ScalarValue>,
+    pub null_count: Option<u64>,
+}
+
+/// Column statistics for a set of containers.
+///
+/// Columns are indexed the same as fields in the table's arrow schema.
+#[derive(Debug)]
+pub struct ContainerStatistics {
+    schema: ArrowSchemaRef,
+    containers: Vec<Vec<ColumnBounds>>,
+}
+
+impl ContainerStatistics {
+    /// Create statistics for manifests using the partition summaries from the
+    /// manifest list.
+    ///
+    /// Only identity partitions can be used since the summaries are in terms
+    /// of the partition values, not the source column values.
+    pub fn from_partition_summaries<'a>(
+        arrow_schema: ArrowSchemaRef,
+        schema: &Schema,
+        manifests: impl IntoIterator<Item = (&'a [PartitionField], &'a [FieldSummary])>,
+    ) -> Result<ContainerStatistics> {
+        let containers = manifests
+            .into_iter()
+            .map(|(spec, summaries)| {
+                let mut columns = vec![ColumnBounds::default(); arrow_schema.fields().len()];
+                for (field, summary) in spec.iter().zip(summaries) {
+                    if field.transform != Transform::Identity {
+                        continue;
+                    }
+                    let (idx, typ) = match column_for_field(&arrow_schema, schema, field.source_id)
+                    {
+                        Some(v) => v,
+                        None => continue,
+                    };
+                    columns[idx] = ColumnBounds {
+                        min: decode_bound(typ, summary.lower_bound.as_deref())?,
+                        max: decode_bound(typ, summary.upper_bound.as_deref())?,
+                        // We only know if there's nulls, not how many.
+                        null_count: if summary.contains_null { None } else { Some(0) },
+                    };
+                }
+                Ok(columns)
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(ContainerStatistics {
+            schema: arrow_schema,
+            containers,
+        })
+    }
+
+    /// Create statistics for data files using the column bounds stored in the
+    /// manifest entries.
+    ///
+    /// Identity partition values are used for columns missing bounds.
+    pub fn from_data_files<'a>(
+        arrow_schema: ArrowSchemaRef,
+        schema: &Schema,
+        files: impl IntoIterator<Item = (&'a [PartitionField], &'a DataFile)>,
+    ) -> Result<ContainerStatistics> {
+        let containers = files
+            .into_iter()
+            .map(|(spec, file)| data_file_bounds(&arrow_schema, schema, spec, file))
+            .collect::<Result<_>>()?;
+
+        Ok(ContainerStatistics {
+            schema: arrow_schema,
+            containers,
+        })
+    }
+
+    /// Returns which containers may contain rows matching the predicate.
+    pub fn prune(&self, predicate: &PruningPredicate) -> Result<Vec<bool>> {
+        Ok(predicate.prune(self)?)
+    }
+
+    /// Merge the statistics for all containers into statistics for the table.
+    ///
+    /// The min and max values are lower and upper bounds for the column, and
+    /// aren't necessarily values that exist in the table. They must not be
+    /// reported as exact.
+    pub fn merged_column_statistics(&self) -> Vec<ColumnStatistics> {
+        (0..self.schema.fields().len())
+            .map(|idx| {
+                let mut stats = ColumnStatistics {
+                    null_count: Some(0),
+                    max_value: None,
+                    min_value: None,
+                    distinct_count: None,
+                };
+
+                for (container_idx, container) in self.containers.iter().enumerate() {
+                    let bounds = &container[idx];
+                    stats.null_count = match (stats.null_count, bounds.null_count) {
+                        (Some(a), Some(b)) => Some(a + b as usize),
+                        _ => None,
+                    };
+
+                    if container_idx == 0 {
+                        stats.min_value = bounds.min.clone();
+                        stats.max_value = bounds.max.clone();
+                        continue;
+                    }
+                    stats.min_value = match (stats.min_value, &bounds.min) {
+                        (Some(a), Some(b)) if b < &a => Some(b.clone()),
+                        (Some(a), Some(_)) => Some(a),
+                        _ => None,
+                    };
+                    stats.max_value = match (stats.max_value, &bounds.max) {
+                        (Some(a), Some(b)) if b > &a => Some(b.clone()),
+                        (Some(a), Some(_)) => Some(a),
+                        _ => None,
+                    };
+                }
+
+                // Bounds for strings and binary values may be truncated, so
+                // they're not exact.
+                if matches!(
+                    stats.min_value,
+                    Some(ScalarValue::Utf8(_) | ScalarValue::Binary(_))
+                ) {
+                    stats.min_value = None;
+                    stats.max_value = None;
+                }
+
+                stats
+            })
+            .collect()
+    }
+
+    fn values(
+        &self,
+        column: &Column,
+        f: impl Fn(&ColumnBounds) -> Option<ScalarValue>,
+    ) -> Option<ArrayRef> {
+        let idx = self.schema.index_of(&column.name).ok()?;
+        let null = ScalarValue::try_from(self.schema.field(idx).data_type()).ok()?;
+        let values = self
+            .containers
+            .iter()
+            .map(|c| f(&c[idx]).unwrap_or_else(|| null.clone()));
+        ScalarValue::iter_to_array(values).ok()
+    }
+}
+
+impl PruningStatistics for ContainerStatistics {
+    fn min_values(&self, column: &Column) -> Option<ArrayRef> {
+        self.values(column, |b| b.min.clone())
+    }
+
+    fn max_values(&self, column: &Column) -> Option<ArrayRef> {
+        self.values(column, |b| b.max.clone())
+    }
+
+    fn num_containers(&self) -> usize {
+        self.containers.len()
+    }
+
+    fn null_counts(&self, column: &Column) -> Option<ArrayRef> {
+        let idx = self.schema.index_of(&column.name).ok()?;
+        let counts: UInt64Array = self.containers.iter().map(|c| c[idx].null_count).collect();
+        Some(Arc::new(counts))
+    }
+}
+
+fn data_file_bounds(
+    arrow_schema: &ArrowSchemaRef,
+    schema: &Schema,
+    spec: &[PartitionField],
+    file: &DataFile,
+) -> Result<Vec<ColumnBounds>> {
+    let mut columns = vec![ColumnBounds::default(); arrow_schema.fields().len()];
+
+    for (field, value) in spec.iter().zip(&file.partition_values) {
+        if field.transform != Transform::Identity {
+            continue;
+        }
+        if let Some((idx, _)) = column_for_field(arrow_schema, schema, field.source_id) {
+            if value.is_null() {
+                columns[idx].null_count = Some(file.record_count as u64);
+            } else {
+                columns[idx].min = Some(value.clone());
+                columns[idx].max = Some(value.clone());
+                columns[idx].null_count = Some(0);
+            }
+        }
+    }
+
+    for ent in file.lower_bounds.iter().flatten() {
+        if let Some((idx, typ)) = column_for_field(arrow_schema, schema, ent.key) {
+            columns[idx].min = decode_bound(typ, Some(&ent.value))?;
+        }
+    }
+    for ent in file.upper_bounds.iter().flatten() {
+        if let Some((idx, typ)) = column_for_field(arrow_schema, schema, ent.key) {
+            columns[idx].max = decode_bound(typ, Some(&ent.value))?;
+        }
+    }
+    for ent in file.null_value_counts.iter().flatten() {
+        if let Some((idx, _)) = column_for_field(arrow_schema, schema, ent.key) {
+            columns[idx].null_count = Some(ent.value as u64);
+        }
+    }
+
+    Ok(columns)
+}
+
+/// Find the index and type of the top-level primitive column with the given
+/// field id.
+fn column_for_field<'a>(
+    arrow_schema: &ArrowSchemaRef,
+    schema: &'a Schema,
+    field_id: i32,
+) -> Option<(usize, &'a PrimitiveType)> {
+    let field = schema.field_by_id(field_id)?;
+    let typ = match &field.r#type {
+        AnyType::Primitive(typ) => typ,
+        _ => return None,
+    };
+    let idx = arrow_schema.index_of(&field.name).ok()?;
+    Some((idx, typ))
+}
+
+/// Decode a bound, ignoring bounds for floating point values since NaNs make
+/// them unreliable.
+fn decode_bound(typ: &PrimitiveType, bs: Option<&[u8]>) -> Result<Option<ScalarValue>> {
+    match (typ, bs) {
+        (PrimitiveType::Float | PrimitiveType::Double, _) => Ok(None),
+        (_, Some(bs)) => Ok(Some(typ.scalar_from_bytes(bs)?)),
+        (_, None) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lake::iceberg::spec::BinaryEntry;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
+    use datafusion::common::ToDFSchema;
+    use datafusion::logical_expr::{col, lit};
+    use datafusion::physical_expr::create_physical_expr;
+    use datafusion::physical_expr::execution_props::ExecutionProps;
+
+    fn schema() -> Schema {
+        serde_json::from_str(
+            r#"{
+                "schema-id": 0,
+                "fields": [
+                    {"id": 1, "name": "a", "required": false, "type": "long"},
+                    {"id": 2, "name": "b", "required": false, "type": "string"}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn data_file(a: (i64, i64), b: (&str, &str)) -> DataFile {
+        DataFile {
+            content: 0,
+            file_path: "data.parquet".to_string(),
+            file_format: "PARQUET".to_string(),
+            record_count: 10,
+            file_size_in_bytes: 100,
+            column_sizes: None,
+            value_counts: None,
+            null_value_counts: None,
+            nan_value_counts: None,
+            distinct_counts: None,
+            lower_bounds: Some(vec![
+                BinaryEntry {
+                    key: 1,
+                    value: a.0.to_le_bytes().to_vec(),
+                },
+                BinaryEntry {
+                    key: 2,
+                    value: b.0.as_bytes().to_vec(),
+                },
+            ]),
+            upper_bounds: Some(vec![
+                BinaryEntry {
+                    key: 1,
+                    value: a.1.to_le_bytes().to_vec(),
+                },
+                BinaryEntry {
+                    key: 2,
+                    value: b.1.as_bytes().to_vec(),
+                },
+            ]),
+            key_metadata: None,
+            split_offsets: None,
+            equality_ids: None,
+            sort_order_id: None,
+            partition_values: Vec::new(),
+        }
+    }
+
+    fn predicate(
+        arrow_schema: &ArrowSchemaRef,
+        expr: datafusion::logical_expr::Expr,
+    ) -> PruningPredicate {
+        let df_schema = arrow_schema.as_ref().clone().to_dfschema().unwrap();
+        let expr =
+            create_physical_expr(&expr, &df_schema, arrow_schema, &ExecutionProps::new()).unwrap();
+        PruningPredicate::try_new(expr, arrow_schema.clone()).unwrap()
+    }
+
+    #[test]
+    fn test_prune_data_files() {
+        let schema = schema();
+        let arrow_schema = Arc::new(schema.to_arrow_schema().unwrap());
+        let files = [
+            data_file((1, 10), ("a", "c")),
+            data_file((11, 20), ("d", "f")),
+        ];
+        let stats = ContainerStatistics::from_data_files(
+            arrow_schema.clone(),
+            &schema,
+            files.iter().map(|f| (&[][..], f)),
+        )
+        .unwrap();
+
+        let pruned = stats
+            .prune(&predicate(&arrow_schema, col("a").gt(lit(15_i64))))
+            .unwrap();
+        assert_eq!(vec![false, true], pruned);
+
+        let pruned = stats
+            .prune(&predicate(&arrow_schema, col("b").eq(lit("b"))))
+            .unwrap();
+        assert_eq!(vec![true, false], pruned);
+    }
+
+    #[test]
+    fn test_prune_partition_summaries() {
+        let schema = schema();
+        let arrow_schema = Arc::new(schema.to_arrow_schema().unwrap());
+        let spec = vec![PartitionField {
+            source_id: 2,
+            field_id: 1000,
+            name: "b".to_string(),
+            transform: Transform::Identity,
+        }];
+        let summaries = [
+            vec![FieldSummary {
+                contains_null: false,
+                contains_nan: false,
+                lower_bound: Some(b"AIR".to_vec()),
+                upper_bound: Some(b"AIR".to_vec()),
+            }],
+            vec![FieldSummary {
+                contains_null: false,
+                contains_nan: false,
+                lower_bound: Some(b"MAIL".to_vec()),
+                upper_bound: Some(b"SHIP".to_vec()),
+            }],
+        ];
+        let stats = ContainerStatistics::from_partition_summaries(
+            arrow_schema.clone(),
+            &schema,
+            summaries.iter().map(|s| (spec.as_slice(), s.as_slice())),
+        )
+        .unwrap();
+
+        let pruned = stats
+            .prune(&predicate(&arrow_schema, col("b").eq(lit("RAIL"))))
+            .unwrap();
+        assert_eq!(vec![false, true], pruned);
+
+        // No stats for this column, can't prune.
+        let pruned = stats
+            .prune(&predicate(&arrow_schema, col("a").eq(lit(1_i64))))
+            .unwrap();
+        assert_eq!(vec![true, true], pruned);
+    }
+
+    #[test]
+    fn test_merged_column_statistics() {
+        let schema = schema();
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let files = [
+            data_file((1, 10), ("a", "c")),
+            data_file((11, 20), ("d", "f")),
+        ];
+        let stats = ContainerStatistics::from_data_files(
+            arrow_schema,
+            &schema,
+            files.iter().map(|f| (&[][..], f)),
+        )
+        .unwrap();
+
+        let merged = stats.merged_column_statistics();
+        assert_eq!(Some(ScalarValue::Int64(Some(1))), merged[0].min_value);
+        assert_eq!(Some(ScalarValue::Int64(Some(20))), merged[0].max_value);
+        assert_eq!(None, merged[1].min_value);
+    }
+}
@@ -0,0 +1,168 @@
+use super::table::IcebergTable;
+
+use crate::common::url::DatasourceUrl;
+use crate::common::util::{create_count_record_batch, COUNT_SCHEMA};
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::insert::DataSink;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    execute_stream_partitioned, DisplayAs, DisplayFormatType, Distribution, ExecutionPlan,
+    Partitioning, SendableRecordBatchStream, Statistics,
+};
+use futures::StreamExt;
+use object_store::ObjectStore;
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+/// Appends data to an iceberg table, creating the table if it doesn't exist.
+///
+/// All input streams are written as part of a single snapshot.
+#[derive(Debug, Clone)]
+pub struct IcebergSink {
+    location: DatasourceUrl,
+    store: Arc<dyn ObjectStore>,
+}
+
+impl IcebergSink {
+    pub fn new(location: DatasourceUrl, store: Arc<dyn ObjectStore>) -> IcebergSink {
+        IcebergSink { location, store }
+    }
+}
+
+impl fmt::Display for IcebergSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "IcebergSink({})", self.location)
+    }
+}
+
+impl DisplayAs for IcebergSink {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(f, "{self}"),
+            DisplayFormatType::Verbose => write!(f, "{self}"),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSink for IcebergSink {
+    async fn write_all(
+        &self,
+        data: Vec<SendableRecordBatchStream>,
+        _context: &Arc<TaskContext>,
+    ) -> DataFusionResult<u64> {
+        let schema = match data.first() {
+            Some(stream) => stream.schema(),
+            None => return Ok(0),
+        };
+
+        let table =
+            IcebergTable::open_or_create(self.location.clone(), self.store.clone(), &schema)
+                .await
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+        let stream = Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::iter(data).flatten(),
+        ));
+
+        table
+            .append(stream)
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))
+    }
+}
+
+/// An execution plan for inserting data into an existing iceberg table.
+#[derive(Debug)]
+pub struct IcebergInsertExec {
+    input: Arc<dyn ExecutionPlan>,
+    sink: IcebergSink,
+}
+
+impl IcebergInsertExec {
+    pub fn new(input: Arc<dyn ExecutionPlan>, sink: IcebergSink) -> IcebergInsertExec {
+        IcebergInsertExec { input, sink }
+    }
+}
+
+impl ExecutionPlan for IcebergInsertExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        COUNT_SCHEMA.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::UnspecifiedDistribution]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(IcebergInsertExec {
+            input: children[0].clone(),
+            sink: self.sink.clone(),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "Invalid requested partition {partition}. IcebergInsertExec requires a single input partition."
+            )));
+        }
+
+        // Write all input partitions as a single snapshot.
+        let streams = execute_stream_partitioned(self.input.clone(), context.clone())?;
+        let sink = self.sink.clone();
+        let output = futures::stream::once(async move {
+            let count = sink.write_all(streams, &context).await?;
+            Ok(create_count_record_batch(count))
+        })
+        .boxed();
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            output,
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+impl DisplayAs for IcebergInsertExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "IcebergInsertExec")
+    }
+}
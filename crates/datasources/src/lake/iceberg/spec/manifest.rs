@@ -1,7 +1,10 @@
 use super::{PartitionField, Schema};
 
 use crate::lake::iceberg::errors::{IcebergError, Result};
-use apache_avro::{from_value, Reader};
+use apache_avro::{
+    from_value, to_value, types::Value as AvroValue, Codec, Reader, Schema as AvroSchema, Writer,
+};
+use datafusion::scalar::ScalarValue;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, Bytes};
 use std::fmt;
@@ -10,7 +13,7 @@ use std::{collections::HashMap, str::FromStr};
 /// Manifest lists include summary medata for the table alongside the path the
 /// actual manifest.
 #[serde_as]
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ManifestListEntry {
     pub manifest_path: String,
     pub manifest_length: i64,
@@ -26,31 +29,39 @@ pub struct ManifestListEntry {
     pub added_snapshot_id: i64,
     /// > Number of entries in the manifest that have status ADDED (1), when
     /// > null this is assumed to be non-zero
-    // TODO: Remove default and deserialize into something more meaningful.
-    #[serde(default)]
-    pub added_files_count: i32,
+    ///
+    /// The Java implementation writes the `added_data_files_count` name.
+    #[serde(default, alias = "added_data_files_count")]
+    pub added_files_count: Option<i32>,
     /// > Number of entries in the manifest that have status EXISTING (0), when
     /// > null this is assumed to be non-zero
-    #[serde(default)]
-    pub existing_files_count: i32,
+    #[serde(default, alias = "existing_data_files_count")]
+    pub existing_files_count: Option<i32>,
     /// > Number of entries in the manifest that have status DELETED (2), when
     /// > null this is assumed to be non-zero
-    #[serde(default)]
-    pub deleted_files_count: i32,
+    #[serde(default, alias = "deleted_data_files_count")]
+    pub deleted_files_count: Option<i32>,
     /// > Number of rows in all of files in the manifest that have status ADDED,
     /// > when null this is assumed to be non-zero
     #[serde(default)]
-    pub added_rows_count: i32,
+    pub added_rows_count: Option<i64>,
     /// > Number of rows in all of files in the manifest that have status
     /// > EXISTING, when null this is assumed to be non-zero
     #[serde(default)]
-    pub existing_rows_count: i32,
+    pub existing_rows_count: Option<i64>,
     pub deleted_rows_count: i64,
     pub partitions: Vec<FieldSummary>,
     #[serde_as(as = "Option<Bytes>")]
     pub key_metadata: Option<Vec<u8>>,
 }
 
+impl ManifestListEntry {
+    /// Returns if this manifest tracks delete files.
+    pub fn is_delete(&self) -> bool {
+        self.content == 1
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ManifestList {
     pub entries: Vec<ManifestListEntry>,
@@ -83,10 +94,69 @@ impl ManifestList {
 
         Ok(ManifestList { entries })
     }
+
+    /// Write the manifest list as an Avro file.
+    pub fn to_raw_avro(
+        &self,
+        snapshot_id: i64,
+        parent_snapshot_id: Option<i64>,
+        sequence_number: i64,
+    ) -> Result<Vec<u8>> {
+        let schema = AvroSchema::parse_str(MANIFEST_LIST_AVRO_SCHEMA)?;
+        let mut writer = Writer::with_codec(&schema, Vec::new(), Codec::Deflate);
+
+        writer.add_user_metadata("snapshot-id".to_string(), snapshot_id.to_string())?;
+        writer.add_user_metadata(
+            "parent-snapshot-id".to_string(),
+            parent_snapshot_id
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| "null".to_string()),
+        )?;
+        writer.add_user_metadata("sequence-number".to_string(), sequence_number.to_string())?;
+        writer.add_user_metadata("format-version".to_string(), "2")?;
+
+        for entry in &self.entries {
+            writer.append(to_value(entry)?.resolve(&schema)?)?;
+        }
+
+        Ok(writer.into_inner()?)
+    }
 }
 
+/// Avro schema for v2 manifest lists.
+const MANIFEST_LIST_AVRO_SCHEMA: &str = r#"{
+  "type": "record",
+  "name": "manifest_file",
+  "fields": [
+    {"name": "manifest_path", "type": "string", "field-id": 500},
+    {"name": "manifest_length", "type": "long", "field-id": 501},
+    {"name": "partition_spec_id", "type": "int", "field-id": 502},
+    {"name": "content", "type": "int", "field-id": 517},
+    {"name": "sequence_number", "type": "long", "field-id": 515},
+    {"name": "min_sequence_number", "type": "long", "field-id": 516},
+    {"name": "added_snapshot_id", "type": "long", "field-id": 503},
+    {"name": "added_files_count", "type": ["null", "int"], "default": null, "field-id": 504},
+    {"name": "existing_files_count", "type": ["null", "int"], "default": null, "field-id": 505},
+    {"name": "deleted_files_count", "type": ["null", "int"], "default": null, "field-id": 506},
+    {"name": "added_rows_count", "type": ["null", "long"], "default": null, "field-id": 512},
+    {"name": "existing_rows_count", "type": ["null", "long"], "default": null, "field-id": 513},
+    {"name": "deleted_rows_count", "type": "long", "field-id": 514},
+    {"name": "partitions", "type": ["null", {"type": "array", "items": {
+      "type": "record",
+      "name": "r508",
+      "fields": [
+        {"name": "contains_null", "type": "boolean", "field-id": 509},
+        {"name": "contains_nan", "type": ["null", "boolean"], "default": null, "field-id": 518},
+        {"name": "lower_bound", "type": ["null", "bytes"], "default": null, "field-id": 510},
+        {"name": "upper_bound", "type": ["null", "bytes"], "default": null, "field-id": 511}
+      ]
+    }, "element-id": 508}], "default": null, "field-id": 507},
+    {"name": "key_metadata", "type": ["null", "bytes"], "default": null, "field-id": 519}
+  ]
+}"#;
+
 #[serde_as]
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FieldSummary {
     pub contains_null: bool,
     pub contains_nan: bool,
@@ -193,16 +263,172 @@ impl Manifest {
             let value = value.map_err(|e| {
                 IcebergError::DataInvalid(format!("failed to get value for manifest entry: {e}"))
             })?;
-            let entry: ManifestEntry = from_value(&value).map_err(|e| {
+            let mut entry: ManifestEntry = from_value(&value).map_err(|e| {
                 IcebergError::DataInvalid(format!(
                     "failed to deserialize value for manifest entry: {e}"
                 ))
             })?;
+            entry.data_file.partition_values = partition_values(&metadata, &value)?;
             entries.push(entry);
         }
 
         Ok(Manifest { metadata, entries })
     }
+
+    /// Write the manifest as an Avro file.
+    ///
+    /// Only manifests for unpartitioned tables can currently be written.
+    pub fn to_raw_avro(&self) -> Result<Vec<u8>> {
+        if !self.metadata.partition_spec.is_empty() {
+            return Err(IcebergError::Static(
+                "Writing manifests for partitioned tables is not supported",
+            ));
+        }
+
+        let schema = AvroSchema::parse_str(UNPARTITIONED_MANIFEST_AVRO_SCHEMA)?;
+        let mut writer = Writer::with_codec(&schema, Vec::new(), Codec::Deflate);
+
+        let m = &self.metadata;
+        writer.add_user_metadata("schema".to_string(), serde_json::to_vec(&m.schema)?)?;
+        writer.add_user_metadata("schema-id".to_string(), m.schema_id.to_string())?;
+        writer.add_user_metadata(
+            "partition-spec".to_string(),
+            serde_json::to_vec(&m.partition_spec)?,
+        )?;
+        writer.add_user_metadata(
+            "partition-spec-id".to_string(),
+            m.partition_spec_id.to_string(),
+        )?;
+        writer.add_user_metadata("format-version".to_string(), m.format_version.to_string())?;
+        writer.add_user_metadata("content".to_string(), m.content.to_string())?;
+
+        for entry in &self.entries {
+            let mut value = to_value(entry)?;
+            // The partition tuple isn't part of the serialized entry. Tables
+            // without partitioning always have an empty tuple.
+            if let Some(AvroValue::Record(data_file)) = record_field_mut(&mut value, "data_file") {
+                data_file.push(("partition".to_string(), AvroValue::Record(Vec::new())));
+            }
+            writer.append(value.resolve(&schema)?)?;
+        }
+
+        Ok(writer.into_inner()?)
+    }
+}
+
+fn record_field_mut<'a>(value: &'a mut AvroValue, name: &str) -> Option<&'a mut AvroValue> {
+    match value {
+        AvroValue::Record(fields) => fields.iter_mut().find(|(n, _)| n == name).map(|(_, v)| v),
+        _ => None,
+    }
+}
+
+/// Avro schema for v2 manifests of unpartitioned tables.
+const UNPARTITIONED_MANIFEST_AVRO_SCHEMA: &str = r#"{
+  "type": "record",
+  "name": "manifest_entry",
+  "fields": [
+    {"name": "status", "type": "int", "field-id": 0},
+    {"name": "snapshot_id", "type": ["null", "long"], "default": null, "field-id": 1},
+    {"name": "sequence_number", "type": ["null", "long"], "default": null, "field-id": 3},
+    {"name": "file_sequence_number", "type": ["null", "long"], "default": null, "field-id": 4},
+    {"name": "data_file", "type": {
+      "type": "record",
+      "name": "r2",
+      "fields": [
+        {"name": "content", "type": "int", "field-id": 134},
+        {"name": "file_path", "type": "string", "field-id": 100},
+        {"name": "file_format", "type": "string", "field-id": 101},
+        {"name": "partition", "type": {"type": "record", "name": "r102", "fields": []}, "field-id": 102},
+        {"name": "record_count", "type": "long", "field-id": 103},
+        {"name": "file_size_in_bytes", "type": "long", "field-id": 104},
+        {"name": "column_sizes", "type": ["null", {"type": "array", "items": {
+          "type": "record", "name": "k117_v118", "fields": [
+            {"name": "key", "type": "int", "field-id": 117},
+            {"name": "value", "type": "long", "field-id": 118}
+          ]}, "logicalType": "map"}], "default": null, "field-id": 108},
+        {"name": "value_counts", "type": ["null", {"type": "array", "items": {
+          "type": "record", "name": "k119_v120", "fields": [
+            {"name": "key", "type": "int", "field-id": 119},
+            {"name": "value", "type": "long", "field-id": 120}
+          ]}, "logicalType": "map"}], "default": null, "field-id": 109},
+        {"name": "null_value_counts", "type": ["null", {"type": "array", "items": {
+          "type": "record", "name": "k121_v122", "fields": [
+            {"name": "key", "type": "int", "field-id": 121},
+            {"name": "value", "type": "long", "field-id": 122}
+          ]}, "logicalType": "map"}], "default": null, "field-id": 110},
+        {"name": "nan_value_counts", "type": ["null", {"type": "array", "items": {
+          "type": "record", "name": "k138_v139", "fields": [
+            {"name": "key", "type": "int", "field-id": 138},
+            {"name": "value", "type": "long", "field-id": 139}
+          ]}, "logicalType": "map"}], "default": null, "field-id": 137},
+        {"name": "distinct_counts", "type": ["null", {"type": "array", "items": {
+          "type": "record", "name": "k123_v124", "fields": [
+            {"name": "key", "type": "int", "field-id": 123},
+            {"name": "value", "type": "long", "field-id": 124}
+          ]}, "logicalType": "map"}], "default": null, "field-id": 111},
+        {"name": "lower_bounds", "type": ["null", {"type": "array", "items": {
+          "type": "record", "name": "k126_v127", "fields": [
+            {"name": "key", "type": "int", "field-id": 126},
+            {"name": "value", "type": "bytes", "field-id": 127}
+          ]}, "logicalType": "map"}], "default": null, "field-id": 125},
+        {"name": "upper_bounds", "type": ["null", {"type": "array", "items": {
+          "type": "record", "name": "k129_v130", "fields": [
+            {"name": "key", "type": "int", "field-id": 129},
+            {"name": "value", "type": "bytes", "field-id": 130}
+          ]}, "logicalType": "map"}], "default": null, "field-id": 128},
+        {"name": "key_metadata", "type": ["null", "bytes"], "default": null, "field-id": 131},
+        {"name": "split_offsets", "type": ["null", {"type": "array", "items": "long", "element-id": 133}], "default": null, "field-id": 132},
+        {"name": "equality_ids", "type": ["null", {"type": "array", "items": "int", "element-id": 136}], "default": null, "field-id": 135},
+        {"name": "sort_order_id", "type": ["null", "int"], "default": null, "field-id": 140}
+      ]
+    }, "field-id": 2}
+  ]
+}"#;
+
+/// Get the partition values for the data file in a manifest entry.
+///
+/// The partition tuple is a record whose schema depends on the partition spec,
+/// so it's pulled out of the raw avro value and converted using the spec.
+/// Values are ordered the same as the fields in the spec.
+fn partition_values(metadata: &ManifestMetadata, entry: &AvroValue) -> Result<Vec<ScalarValue>> {
+    fn record_field<'a>(value: &'a AvroValue, name: &str) -> Option<&'a AvroValue> {
+        match value {
+            AvroValue::Record(fields) => fields.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    let partition = record_field(entry, "data_file").and_then(|f| record_field(f, "partition"));
+
+    metadata
+        .partition_spec
+        .iter()
+        .map(|field| {
+            let typ = field.result_type(&metadata.schema)?;
+            match partition.and_then(|p| record_field(p, &field.name)) {
+                Some(value) => typ.scalar_from_avro(value),
+                None => typ.null_scalar(),
+            }
+        })
+        .collect()
+}
+
+/// Status of a manifest entry.
+///
+/// > Used to track additions and deletions. Deletes are informational only and
+/// > not used in scans.
+pub mod entry_status {
+    pub const EXISTING: i32 = 0;
+    pub const ADDED: i32 = 1;
+    pub const DELETED: i32 = 2;
+}
+
+/// Content of a data file.
+pub mod data_file_content {
+    pub const DATA: i32 = 0;
+    pub const POSITION_DELETES: i32 = 1;
+    pub const EQUALITY_DELETES: i32 = 2;
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -237,18 +463,22 @@ pub struct DataFile {
     pub split_offsets: Option<Vec<i64>>,
     pub equality_ids: Option<Vec<i32>>,
     pub sort_order_id: Option<i32>,
+    /// Partition values for the file, ordered by the fields in the manifest's
+    /// partition spec.
+    #[serde(skip)]
+    pub partition_values: Vec<ScalarValue>,
 }
 
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BinaryEntry {
-    key: i32,
+    pub key: i32,
     #[serde_as(as = "Bytes")]
-    value: Vec<u8>,
+    pub value: Vec<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct I64Entry {
-    key: i32,
-    value: i64,
+    pub key: i32,
+    pub value: i64,
 }
@@ -1,20 +1,25 @@
-use super::Schema;
+use super::{AnyType, PrimitiveType, Schema};
 
 use crate::lake::iceberg::errors::{IcebergError, Result};
 use once_cell::sync::Lazy;
 use regex::Regex;
-use serde::{de, Deserialize, Deserializer};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
 use std::{collections::HashMap, str::FromStr};
 
 /// On disk table metadata.
 ///
 /// JSON serialization only.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct TableMetadata {
     pub format_version: i32,
     pub table_uuid: String,
     pub location: String,
+    /// > The table’s highest assigned sequence number, a monotonically
+    /// > increasing long that tracks the order of snapshots in a table.
+    #[serde(default)]
+    pub last_sequence_number: i64,
     pub last_updated_ms: i64,
     pub last_column_id: i32,
     pub schemas: Vec<Schema>,
@@ -22,49 +27,113 @@ pub struct TableMetadata {
     pub partition_specs: Vec<PartitionSpec>,
     pub default_spec_id: i32,
     pub last_partition_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub properties: Option<HashMap<String, String>>,
+    #[serde(
+        default,
+        serialize_with = "serialize_snapshot_id",
+        deserialize_with = "deserialize_snapshot_id"
+    )]
     pub current_snapshot_id: Option<i64>,
     pub snapshots: Vec<Snapshot>,
     pub snapshot_log: Vec<SnapshotLog>,
     pub metadata_log: Vec<MetadataLog>,
     pub sort_orders: Vec<SortOrder>,
     pub default_sort_order_id: i32,
-    // TODO: Figure out what this field is for.
-    // refs: Option<HashMap<String, SnapshotReference>>,
+    /// > A map of snapshot references. The map keys are the unique snapshot
+    /// > reference names in the table, and the map values are snapshot
+    /// > reference objects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refs: Option<HashMap<String, SnapshotReference>>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// Writers may use `-1` to indicate that a table has no current snapshot.
+fn serialize_snapshot_id<S>(id: &Option<i64>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_i64(id.unwrap_or(-1))
+}
+
+fn deserialize_snapshot_id<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let id: Option<i64> = Deserialize::deserialize(deserializer)?;
+    Ok(id.filter(|id| *id != -1))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Snapshot {
     pub snapshot_id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_snapshot_id: Option<i64>,
+    /// Required in v2.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence_number: Option<i64>,
     pub timestamp_ms: i64,
     pub summary: HashMap<String, String>,
     pub manifest_list: String,
-    pub schema_id: i32,
+    /// > ID of the table’s current schema when the snapshot was created
+    ///
+    /// Optional in the spec.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_id: Option<i32>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct SnapshotLog {
     pub snapshot_id: i64,
     pub timestamp_ms: i64,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct MetadataLog {
     pub metadata_file: String,
     pub timestamp_ms: i64,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// A named reference to a snapshot, either a branch or a tag.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SnapshotReference {
+    pub snapshot_id: i64,
+    /// Either "branch" or "tag".
+    pub r#type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_snapshots_to_keep: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_snapshot_age_ms: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ref_age_ms: Option<i64>,
+}
+
+impl SnapshotReference {
+    /// Name of the main branch of a table.
+    pub const MAIN_BRANCH: &'static str = "main";
+
+    pub fn branch(snapshot_id: i64) -> SnapshotReference {
+        SnapshotReference {
+            snapshot_id,
+            r#type: "branch".to_string(),
+            min_snapshots_to_keep: None,
+            max_snapshot_age_ms: None,
+            max_ref_age_ms: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct PartitionSpec {
     pub spec_id: i32,
     pub fields: Vec<PartitionField>,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub struct PartitionField {
     pub source_id: i32,
@@ -73,14 +142,44 @@ pub struct PartitionField {
     pub transform: Transform,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl PartitionField {
+    /// Get the type of the values produced by this field's transform.
+    pub fn result_type(&self, schema: &Schema) -> Result<PrimitiveType> {
+        let source = schema.field_by_id(self.source_id).ok_or_else(|| {
+            IcebergError::DataInvalid(format!(
+                "Missing source field {} for partition field '{}'",
+                self.source_id, self.name
+            ))
+        })?;
+        let source_type = match &source.r#type {
+            AnyType::Primitive(typ) => *typ,
+            other => {
+                return Err(IcebergError::DataInvalid(format!(
+                    "Partition source field '{}' is not a primitive: {other:?}",
+                    source.name
+                )))
+            }
+        };
+
+        Ok(match self.transform {
+            Transform::Identity | Transform::Truncate(_) | Transform::Void => source_type,
+            Transform::Year
+            | Transform::Month
+            | Transform::Day
+            | Transform::Hour
+            | Transform::Bucket(_) => PrimitiveType::Int,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct SortOrder {
     pub order_id: i32,
     pub fields: Vec<SortField>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct SortField {
     pub transform: Transform,
@@ -89,14 +188,14 @@ pub struct SortField {
     pub null_order: NullOrder,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum SortDirection {
     Asc,
     Desc,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum NullOrder {
     NullsFirst,
@@ -184,6 +283,30 @@ impl FromStr for Transform {
     }
 }
 
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Transform::Identity => write!(f, "identity"),
+            Transform::Year => write!(f, "year"),
+            Transform::Month => write!(f, "month"),
+            Transform::Day => write!(f, "day"),
+            Transform::Hour => write!(f, "hour"),
+            Transform::Void => write!(f, "void"),
+            Transform::Bucket(n) => write!(f, "bucket[{n}]"),
+            Transform::Truncate(n) => write!(f, "truncate[{n}]"),
+        }
+    }
+}
+
+impl Serialize for Transform {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 impl<'de> Deserialize<'de> for Transform {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -215,6 +338,7 @@ mod tests {
         for t in test_cases {
             let out: Transform = t.0.parse().unwrap();
             assert_eq!(t.1, out);
+            assert_eq!(t.0, t.1.to_string());
         }
     }
 
@@ -238,4 +362,32 @@ mod tests {
 
         assert_eq!(expected, deserialized);
     }
+
+    #[test]
+    fn test_current_snapshot_id_none() {
+        #[derive(Serialize, Deserialize)]
+        #[serde(rename_all = "kebab-case")]
+        struct Wrapper {
+            #[serde(
+                serialize_with = "serialize_snapshot_id",
+                deserialize_with = "deserialize_snapshot_id"
+            )]
+            current_snapshot_id: Option<i64>,
+        }
+
+        let w: Wrapper = serde_json::from_str(r#"{"current-snapshot-id": -1}"#).unwrap();
+        assert_eq!(None, w.current_snapshot_id);
+
+        let w: Wrapper = serde_json::from_str(r#"{"current-snapshot-id": null}"#).unwrap();
+        assert_eq!(None, w.current_snapshot_id);
+
+        let w: Wrapper = serde_json::from_str(r#"{"current-snapshot-id": 42}"#).unwrap();
+        assert_eq!(Some(42), w.current_snapshot_id);
+
+        let json = serde_json::to_string(&Wrapper {
+            current_snapshot_id: None,
+        })
+        .unwrap();
+        assert_eq!(r#"{"current-snapshot-id":-1}"#, json);
+    }
 }
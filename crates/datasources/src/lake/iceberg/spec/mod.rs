@@ -24,3 +24,5 @@ pub use metadata::*;
 
 mod manifest;
 pub use manifest::*;
+
+mod values;
@@ -1,13 +1,18 @@
 use crate::lake::iceberg::errors::{IcebergError, Result};
 use datafusion::arrow::datatypes::{
-    DataType, Field as ArrowField, Schema as ArrowSchema, TimeUnit,
+    DataType, Field as ArrowField, Fields as ArrowFields, Schema as ArrowSchema, TimeUnit,
 };
 use once_cell::sync::Lazy;
 use regex::Regex;
-use serde::{de, Deserialize, Deserializer};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
 use std::str::FromStr;
 use std::sync::Arc;
 
+/// Metadata key the parquet reader and writer use for field ids.
+pub const PARQUET_FIELD_ID_META_KEY: &str = "PARQUET:field_id";
+
 /// Primitive types supported in iceberg tables.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PrimitiveType {
@@ -50,6 +55,63 @@ impl TryFrom<PrimitiveType> for DataType {
     }
 }
 
+impl TryFrom<&DataType> for PrimitiveType {
+    type Error = IcebergError;
+
+    fn try_from(value: &DataType) -> Result<Self> {
+        Ok(match value {
+            DataType::Boolean => PrimitiveType::Boolean,
+            DataType::Int8 | DataType::Int16 | DataType::Int32 => PrimitiveType::Int,
+            DataType::UInt8 | DataType::UInt16 => PrimitiveType::Int,
+            DataType::Int64 | DataType::UInt32 => PrimitiveType::Long,
+            DataType::Float16 | DataType::Float32 => PrimitiveType::Float,
+            DataType::Float64 => PrimitiveType::Double,
+            DataType::Decimal128(p, s) if *s >= 0 => PrimitiveType::Decimal { p: *p, s: *s as u8 },
+            DataType::Date32 | DataType::Date64 => PrimitiveType::Date,
+            DataType::Timestamp(_, None) => PrimitiveType::Timestamp,
+            DataType::Timestamp(_, Some(_)) => PrimitiveType::Timestamptz,
+            DataType::Utf8 | DataType::LargeUtf8 => PrimitiveType::String,
+            DataType::Binary | DataType::LargeBinary => PrimitiveType::Binary,
+            DataType::FixedSizeBinary(l) => PrimitiveType::Fixed(*l as usize),
+            other => {
+                return Err(IcebergError::DataInvalid(format!(
+                    "Unsupported arrow type for iceberg: {other}"
+                )))
+            }
+        })
+    }
+}
+
+impl fmt::Display for PrimitiveType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrimitiveType::Boolean => write!(f, "boolean"),
+            PrimitiveType::Int => write!(f, "int"),
+            PrimitiveType::Long => write!(f, "long"),
+            PrimitiveType::Float => write!(f, "float"),
+            PrimitiveType::Double => write!(f, "double"),
+            PrimitiveType::Decimal { p, s } => write!(f, "decimal({p}, {s})"),
+            PrimitiveType::Date => write!(f, "date"),
+            PrimitiveType::Time => write!(f, "time"),
+            PrimitiveType::Timestamp => write!(f, "timestamp"),
+            PrimitiveType::Timestamptz => write!(f, "timestamptz"),
+            PrimitiveType::String => write!(f, "string"),
+            PrimitiveType::Uuid => write!(f, "uuid"),
+            PrimitiveType::Fixed(l) => write!(f, "fixed[{l}]"),
+            PrimitiveType::Binary => write!(f, "binary"),
+        }
+    }
+}
+
+impl Serialize for PrimitiveType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 impl<'de> Deserialize<'de> for PrimitiveType {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -141,7 +203,7 @@ impl FromStr for PrimitiveType {
 }
 
 /// Union between primitive and nested types.
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum AnyType {
     Primitive(PrimitiveType),
@@ -163,7 +225,7 @@ impl TryFrom<&AnyType> for DataType {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 #[serde(tag = "type", rename = "list")]
 pub struct ListType {
@@ -185,7 +247,7 @@ impl TryFrom<&ListType> for DataType {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 #[serde(tag = "type", rename = "map")]
 pub struct MapType {
@@ -214,7 +276,7 @@ impl TryFrom<&MapType> for DataType {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 #[serde(tag = "type", rename = "struct")]
 pub struct StructType {
@@ -240,17 +302,20 @@ impl TryFrom<&StructType> for DataType {
 }
 
 /// Fields on a struct.
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub struct StructField {
     pub id: i32,
     pub name: String,
     pub required: bool,
     pub r#type: AnyType,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub doc: Option<String>,
     /// JSON serialized initial value for the field.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub initial_default: Option<String>, // TODO
     /// JSON serialized write default value for the field.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub write_default: Option<String>, // TODO
 }
 
@@ -261,15 +326,64 @@ impl StructField {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
+#[serde(tag = "type", rename = "struct")]
 pub struct Schema {
     pub schema_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub identifier_field_ids: Option<Vec<i32>>,
     pub fields: Vec<StructField>,
 }
 
 impl Schema {
+    /// Create a schema from an arrow schema, assigning fresh field ids.
+    ///
+    /// Top-level fields get ids starting at 1, nested fields are assigned ids
+    /// after that.
+    pub fn try_from_arrow(schema_id: i32, schema: &ArrowSchema) -> Result<Schema> {
+        let mut ids = FieldIdAssigner { next_id: 1 };
+        let fields = ids.struct_fields(schema.fields())?;
+        Ok(Schema {
+            schema_id,
+            identifier_field_ids: None,
+            fields,
+        })
+    }
+
+    /// Get the highest field id in use by this schema, including nested
+    /// fields.
+    pub fn highest_field_id(&self) -> i32 {
+        fn max_id(typ: &AnyType) -> i32 {
+            match typ {
+                AnyType::Primitive(_) => 0,
+                AnyType::List(l) => l.element_id.max(max_id(&l.element)),
+                AnyType::Struct(s) => s
+                    .fields
+                    .iter()
+                    .map(|f| f.id.max(max_id(&f.r#type)))
+                    .max()
+                    .unwrap_or_default(),
+                AnyType::Map(m) => m
+                    .key_id
+                    .max(m.value_id)
+                    .max(max_id(&m.key))
+                    .max(max_id(&m.value)),
+            }
+        }
+
+        self.fields
+            .iter()
+            .map(|f| f.id.max(max_id(&f.r#type)))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Get a top-level field by its id.
+    pub fn field_by_id(&self, id: i32) -> Option<&StructField> {
+        self.fields.iter().find(|f| f.id == id)
+    }
+
     pub fn to_arrow_schema(&self) -> Result<ArrowSchema> {
         let fields = self
             .fields
@@ -278,6 +392,143 @@ impl Schema {
             .collect::<Result<Vec<_>>>()?;
         Ok(ArrowSchema::new(fields))
     }
+
+    /// Get the arrow schema to use when writing data files for this schema.
+    ///
+    /// Every field (including nested fields) is annotated with its field id
+    /// so that readers can resolve columns by id, and `timestamptz` columns
+    /// are marked as being adjusted to UTC.
+    pub fn to_arrow_write_schema(&self) -> Result<ArrowSchema> {
+        let fields = self
+            .fields
+            .iter()
+            .map(|f| {
+                let field = f.to_arrow_field()?;
+                with_field_ids(&field, &f.r#type, f.id)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ArrowSchema::new(fields))
+    }
+}
+
+/// Annotate an arrow field (and its children) with field ids from the
+/// matching iceberg type.
+fn with_field_ids(field: &ArrowField, typ: &AnyType, id: i32) -> Result<ArrowField> {
+    let data_type = match (field.data_type(), typ) {
+        (DataType::Timestamp(unit, None), AnyType::Primitive(PrimitiveType::Timestamptz)) => {
+            DataType::Timestamp(*unit, Some("UTC".into()))
+        }
+        (_, AnyType::Primitive(prim @ (PrimitiveType::Time | PrimitiveType::Uuid))) => {
+            return Err(IcebergError::DataInvalid(format!(
+                "Writing '{prim}' columns is not supported"
+            )))
+        }
+        (DataType::List(elem), AnyType::List(l)) => {
+            DataType::List(Arc::new(with_field_ids(elem, &l.element, l.element_id)?))
+        }
+        (DataType::Struct(fields), AnyType::Struct(s)) => DataType::Struct(
+            fields
+                .iter()
+                .zip(&s.fields)
+                .map(|(f, sf)| with_field_ids(f, &sf.r#type, sf.id))
+                .collect::<Result<ArrowFields>>()?,
+        ),
+        (DataType::Map(entries, sorted), AnyType::Map(m)) => match entries.data_type() {
+            DataType::Struct(kv) if kv.len() == 2 => {
+                let kv = ArrowFields::from(vec![
+                    with_field_ids(&kv[0], &m.key, m.key_id)?,
+                    with_field_ids(&kv[1], &m.value, m.value_id)?,
+                ]);
+                let entries = entries
+                    .as_ref()
+                    .clone()
+                    .with_data_type(DataType::Struct(kv));
+                DataType::Map(Arc::new(entries), *sorted)
+            }
+            other => {
+                return Err(IcebergError::DataInvalid(format!(
+                    "Unexpected arrow type for map entries: {other}"
+                )))
+            }
+        },
+        (other, _) => other.clone(),
+    };
+
+    Ok(field
+        .clone()
+        .with_data_type(data_type)
+        .with_metadata(HashMap::from([(
+            PARQUET_FIELD_ID_META_KEY.to_string(),
+            id.to_string(),
+        )])))
+}
+
+/// Assigns fresh field ids when converting arrow types to iceberg types.
+struct FieldIdAssigner {
+    next_id: i32,
+}
+
+impl FieldIdAssigner {
+    fn next(&mut self) -> i32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn struct_fields(&mut self, fields: &ArrowFields) -> Result<Vec<StructField>> {
+        // Fields at the same level get ids before any of their children.
+        let ids: Vec<_> = fields.iter().map(|_| self.next()).collect();
+        fields
+            .iter()
+            .zip(ids)
+            .map(|(field, id)| {
+                Ok(StructField {
+                    id,
+                    name: field.name().clone(),
+                    required: !field.is_nullable(),
+                    r#type: self.any_type(field.data_type())?,
+                    doc: None,
+                    initial_default: None,
+                    write_default: None,
+                })
+            })
+            .collect()
+    }
+
+    fn any_type(&mut self, typ: &DataType) -> Result<AnyType> {
+        Ok(match typ {
+            DataType::List(elem) | DataType::LargeList(elem) => {
+                let element_id = self.next();
+                AnyType::List(ListType {
+                    element_id,
+                    element_required: !elem.is_nullable(),
+                    element: Box::new(self.any_type(elem.data_type())?),
+                })
+            }
+            DataType::Struct(fields) => AnyType::Struct(StructType {
+                fields: self.struct_fields(fields)?,
+            }),
+            DataType::Map(entries, _) => match entries.data_type() {
+                DataType::Struct(kv) if kv.len() == 2 => {
+                    let key_id = self.next();
+                    let value_id = self.next();
+                    AnyType::Map(MapType {
+                        key_id,
+                        key: Box::new(self.any_type(kv[0].data_type())?),
+                        value_id,
+                        value_required: !kv[1].is_nullable(),
+                        value: Box::new(self.any_type(kv[1].data_type())?),
+                    })
+                }
+                other => {
+                    return Err(IcebergError::DataInvalid(format!(
+                        "Unexpected arrow type for map entries: {other}"
+                    )))
+                }
+            },
+            other => AnyType::Primitive(other.try_into()?),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -480,4 +731,82 @@ mod tests {
         };
         assert_eq!(expected, deserialized);
     }
+
+    #[test]
+    fn test_primitive_type_display_roundtrip() {
+        let types = [
+            PrimitiveType::Long,
+            PrimitiveType::Decimal { p: 15, s: 2 },
+            PrimitiveType::Fixed(16),
+            PrimitiveType::Timestamptz,
+        ];
+
+        for typ in types {
+            let out: PrimitiveType = typ.to_string().parse().unwrap();
+            assert_eq!(typ, out);
+        }
+    }
+
+    #[test]
+    fn test_schema_from_arrow() {
+        let arrow_schema = ArrowSchema::new(vec![
+            ArrowField::new("a", DataType::Int64, false),
+            ArrowField::new(
+                "b",
+                DataType::List(Arc::new(ArrowField::new("item", DataType::Utf8, true))),
+                true,
+            ),
+            ArrowField::new("c", DataType::Decimal128(15, 2), true),
+        ]);
+
+        let schema = Schema::try_from_arrow(0, &arrow_schema).unwrap();
+        let expected = vec![
+            StructField {
+                id: 1,
+                name: "a".to_string(),
+                required: true,
+                r#type: AnyType::Primitive(PrimitiveType::Long),
+                doc: None,
+                initial_default: None,
+                write_default: None,
+            },
+            StructField {
+                id: 2,
+                name: "b".to_string(),
+                required: false,
+                r#type: AnyType::List(ListType {
+                    element_id: 4,
+                    element_required: false,
+                    element: Box::new(AnyType::Primitive(PrimitiveType::String)),
+                }),
+                doc: None,
+                initial_default: None,
+                write_default: None,
+            },
+            StructField {
+                id: 3,
+                name: "c".to_string(),
+                required: false,
+                r#type: AnyType::Primitive(PrimitiveType::Decimal { p: 15, s: 2 }),
+                doc: None,
+                initial_default: None,
+                write_default: None,
+            },
+        ];
+        assert_eq!(expected, schema.fields);
+        assert_eq!(4, schema.highest_field_id());
+
+        // Round trip through json.
+        let json = serde_json::to_string(&schema).unwrap();
+        let deserialized: Schema = serde_json::from_str(&json).unwrap();
+        assert_eq!(schema.fields, deserialized.fields);
+
+        let write_schema = schema.to_arrow_write_schema().unwrap();
+        let field_id = |f: &ArrowField| f.metadata().get(PARQUET_FIELD_ID_META_KEY).cloned();
+        assert_eq!(Some("2".to_string()), field_id(write_schema.field(1)));
+        match write_schema.field(1).data_type() {
+            DataType::List(elem) => assert_eq!(Some("4".to_string()), field_id(elem)),
+            other => panic!("unexpected type: {other}"),
+        }
+    }
 }
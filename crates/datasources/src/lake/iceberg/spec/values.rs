@@ -0,0 +1,302 @@
+//! Conversions between serialized iceberg values and datafusion scalars.
+//!
+//! Values are serialized in two ways that we care about:
+//!
+//! - Single-value binary serialization: used for lower and upper bounds in
+//!   manifest lists and manifests.
+//! - Avro values: used for the partition tuple of a data file.
+//!
+//! The produced scalars match the arrow types that columns are read as (see
+//! `TryFrom<PrimitiveType> for DataType`).
+
+use super::PrimitiveType;
+
+use crate::lake::iceberg::errors::{IcebergError, Result};
+use apache_avro::types::Value as AvroValue;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::scalar::ScalarValue;
+
+impl PrimitiveType {
+    /// Get a typed null scalar for this type.
+    pub fn null_scalar(&self) -> Result<ScalarValue> {
+        let typ: DataType = (*self).try_into()?;
+        Ok(ScalarValue::try_from(&typ)?)
+    }
+
+    /// Decode a value using the single-value binary serialization.
+    ///
+    /// See <https://iceberg.apache.org/spec/#binary-single-value-serialization>
+    pub fn scalar_from_bytes(&self, bs: &[u8]) -> Result<ScalarValue> {
+        fn fixed<const N: usize>(typ: &PrimitiveType, bs: &[u8]) -> Result<[u8; N]> {
+            bs.try_into().map_err(|_| {
+                IcebergError::DataInvalid(format!(
+                    "Expected {N} bytes for {typ:?} value, got {}",
+                    bs.len()
+                ))
+            })
+        }
+
+        Ok(match self {
+            PrimitiveType::Boolean => ScalarValue::Boolean(Some(fixed::<1>(self, bs)?[0] != 0)),
+            PrimitiveType::Int => ScalarValue::Int32(Some(i32::from_le_bytes(fixed(self, bs)?))),
+            PrimitiveType::Long => ScalarValue::Int64(Some(i64::from_le_bytes(fixed(self, bs)?))),
+            PrimitiveType::Float => {
+                ScalarValue::Float32(Some(f32::from_le_bytes(fixed(self, bs)?)))
+            }
+            PrimitiveType::Double => {
+                ScalarValue::Float64(Some(f64::from_le_bytes(fixed(self, bs)?)))
+            }
+            PrimitiveType::Date => ScalarValue::Date32(Some(i32::from_le_bytes(fixed(self, bs)?))),
+            PrimitiveType::Time | PrimitiveType::Timestamp | PrimitiveType::Timestamptz => {
+                ScalarValue::TimestampMicrosecond(Some(i64::from_le_bytes(fixed(self, bs)?)), None)
+            }
+            PrimitiveType::String => {
+                ScalarValue::Utf8(Some(String::from_utf8(bs.to_vec()).map_err(|e| {
+                    IcebergError::DataInvalid(format!("Expected utf-8 for string value: {e}"))
+                })?))
+            }
+            PrimitiveType::Uuid => ScalarValue::Utf8(Some(format_uuid(&fixed::<16>(self, bs)?))),
+            PrimitiveType::Fixed(len) => {
+                ScalarValue::FixedSizeBinary(*len as i32, Some(bs.to_vec()))
+            }
+            PrimitiveType::Binary => ScalarValue::Binary(Some(bs.to_vec())),
+            PrimitiveType::Decimal { p, s } => {
+                ScalarValue::Decimal128(Some(decimal_from_be_bytes(bs)?), *p, *s as i8)
+            }
+        })
+    }
+
+    /// Encode a scalar using the single-value binary serialization.
+    ///
+    /// Returns `None` for nulls and NaNs, as well as for types that we don't
+    /// produce bounds for. Bounds for strings and binary values may need to be
+    /// truncated, so we skip those entirely.
+    pub fn scalar_to_bytes(&self, scalar: &ScalarValue) -> Option<Vec<u8>> {
+        Some(match (self, scalar) {
+            (PrimitiveType::Boolean, ScalarValue::Boolean(Some(v))) => vec![*v as u8],
+            (PrimitiveType::Int, ScalarValue::Int32(Some(v))) => v.to_le_bytes().to_vec(),
+            (PrimitiveType::Long, ScalarValue::Int64(Some(v))) => v.to_le_bytes().to_vec(),
+            (PrimitiveType::Float, ScalarValue::Float32(Some(v))) if !v.is_nan() => {
+                v.to_le_bytes().to_vec()
+            }
+            (PrimitiveType::Double, ScalarValue::Float64(Some(v))) if !v.is_nan() => {
+                v.to_le_bytes().to_vec()
+            }
+            (PrimitiveType::Date, ScalarValue::Date32(Some(v))) => v.to_le_bytes().to_vec(),
+            (
+                PrimitiveType::Timestamp | PrimitiveType::Timestamptz,
+                ScalarValue::TimestampMicrosecond(Some(v), _),
+            ) => v.to_le_bytes().to_vec(),
+            (PrimitiveType::Decimal { .. }, ScalarValue::Decimal128(Some(v), _, _)) => {
+                decimal_to_be_bytes(*v)
+            }
+            _ => return None,
+        })
+    }
+
+    /// Convert an avro value into a scalar of this type.
+    pub fn scalar_from_avro(&self, value: &AvroValue) -> Result<ScalarValue> {
+        let invalid =
+            || IcebergError::DataInvalid(format!("Unexpected avro value for {self:?}: {value:?}"));
+
+        Ok(match (self, value) {
+            (_, AvroValue::Union(_, inner)) => return self.scalar_from_avro(inner),
+            (_, AvroValue::Null) => return self.null_scalar(),
+            (PrimitiveType::Boolean, AvroValue::Boolean(v)) => ScalarValue::Boolean(Some(*v)),
+            (PrimitiveType::Int, AvroValue::Int(v)) => ScalarValue::Int32(Some(*v)),
+            (PrimitiveType::Long, AvroValue::Long(v)) => ScalarValue::Int64(Some(*v)),
+            (PrimitiveType::Long, AvroValue::Int(v)) => ScalarValue::Int64(Some(*v as i64)),
+            (PrimitiveType::Float, AvroValue::Float(v)) => ScalarValue::Float32(Some(*v)),
+            (PrimitiveType::Double, AvroValue::Double(v)) => ScalarValue::Float64(Some(*v)),
+            (PrimitiveType::Double, AvroValue::Float(v)) => ScalarValue::Float64(Some(*v as f64)),
+            (PrimitiveType::Date, AvroValue::Date(v) | AvroValue::Int(v)) => {
+                ScalarValue::Date32(Some(*v))
+            }
+            (
+                PrimitiveType::Time | PrimitiveType::Timestamp | PrimitiveType::Timestamptz,
+                AvroValue::TimeMicros(v) | AvroValue::TimestampMicros(v) | AvroValue::Long(v),
+            ) => ScalarValue::TimestampMicrosecond(Some(*v), None),
+            (PrimitiveType::String, AvroValue::String(v)) => ScalarValue::Utf8(Some(v.clone())),
+            (PrimitiveType::Uuid, AvroValue::Uuid(v)) => ScalarValue::Utf8(Some(v.to_string())),
+            (PrimitiveType::Uuid, AvroValue::Fixed(16, bs)) => {
+                let bs: [u8; 16] = bs.as_slice().try_into().map_err(|_| invalid())?;
+                ScalarValue::Utf8(Some(format_uuid(&bs)))
+            }
+            (PrimitiveType::Fixed(len), AvroValue::Fixed(_, bs)) => {
+                ScalarValue::FixedSizeBinary(*len as i32, Some(bs.clone()))
+            }
+            (PrimitiveType::Binary, AvroValue::Bytes(bs)) => ScalarValue::Binary(Some(bs.clone())),
+            (PrimitiveType::Decimal { p, s }, AvroValue::Decimal(v)) => {
+                let bs = Vec::<u8>::try_from(v).map_err(|_| invalid())?;
+                ScalarValue::Decimal128(Some(decimal_from_be_bytes(&bs)?), *p, *s as i8)
+            }
+            (PrimitiveType::Decimal { p, s }, AvroValue::Bytes(bs) | AvroValue::Fixed(_, bs)) => {
+                ScalarValue::Decimal128(Some(decimal_from_be_bytes(bs)?), *p, *s as i8)
+            }
+            _ => return Err(invalid()),
+        })
+    }
+}
+
+/// Decode a big-endian two's complement unscaled decimal value.
+fn decimal_from_be_bytes(bs: &[u8]) -> Result<i128> {
+    if bs.is_empty() || bs.len() > 16 {
+        return Err(IcebergError::DataInvalid(format!(
+            "Invalid number of bytes for decimal: {}",
+            bs.len()
+        )));
+    }
+
+    // Sign extend to 16 bytes.
+    let fill = if bs[0] & 0x80 != 0 { 0xFF } else { 0x00 };
+    let mut buf = [fill; 16];
+    buf[16 - bs.len()..].copy_from_slice(bs);
+
+    Ok(i128::from_be_bytes(buf))
+}
+
+/// Encode an unscaled decimal value using the minimum number of bytes.
+fn decimal_to_be_bytes(v: i128) -> Vec<u8> {
+    let bs = v.to_be_bytes();
+
+    // Strip leading bytes that only carry the sign.
+    let mut start = 0;
+    while start < bs.len() - 1 {
+        let redundant = (bs[start] == 0x00 && bs[start + 1] & 0x80 == 0)
+            || (bs[start] == 0xFF && bs[start + 1] & 0x80 != 0);
+        if !redundant {
+            break;
+        }
+        start += 1;
+    }
+
+    bs[start..].to_vec()
+}
+
+fn format_uuid(bs: &[u8; 16]) -> String {
+    let hex: String = bs.iter().map(|b| format!("{b:02x}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scalar_from_bytes() {
+        // (type, bytes, expected)
+        let test_cases = vec![
+            (
+                PrimitiveType::Int,
+                vec![0x2A, 0, 0, 0],
+                ScalarValue::Int32(Some(42)),
+            ),
+            (
+                PrimitiveType::Long,
+                (-3_i64).to_le_bytes().to_vec(),
+                ScalarValue::Int64(Some(-3)),
+            ),
+            (
+                PrimitiveType::String,
+                b"AIR".to_vec(),
+                ScalarValue::Utf8(Some("AIR".to_string())),
+            ),
+            (
+                PrimitiveType::Decimal { p: 15, s: 2 },
+                vec![0x04, 0xD2],
+                ScalarValue::Decimal128(Some(1234), 15, 2),
+            ),
+            (
+                PrimitiveType::Decimal { p: 15, s: 2 },
+                vec![0xFB, 0x2E],
+                ScalarValue::Decimal128(Some(-1234), 15, 2),
+            ),
+            (
+                PrimitiveType::Uuid,
+                vec![
+                    0xf7, 0x9c, 0x3e, 0x09, 0x67, 0x7c, 0x4b, 0xbd, 0xa4, 0x79, 0x3f, 0x34, 0x9c,
+                    0xb7, 0x85, 0xe7,
+                ],
+                ScalarValue::Utf8(Some("f79c3e09-677c-4bbd-a479-3f349cb785e7".to_string())),
+            ),
+        ];
+
+        for (typ, bs, expected) in test_cases {
+            let out = typ.scalar_from_bytes(&bs).unwrap();
+            assert_eq!(expected, out, "type: {typ:?}");
+        }
+    }
+
+    #[test]
+    fn test_scalar_from_bytes_invalid_length() {
+        PrimitiveType::Long
+            .scalar_from_bytes(&[1, 2, 3])
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_scalar_to_bytes_roundtrip() {
+        let test_cases = vec![
+            (PrimitiveType::Int, ScalarValue::Int32(Some(-7))),
+            (PrimitiveType::Long, ScalarValue::Int64(Some(1 << 40))),
+            (PrimitiveType::Double, ScalarValue::Float64(Some(1.5))),
+            (PrimitiveType::Date, ScalarValue::Date32(Some(19000))),
+            (
+                PrimitiveType::Decimal { p: 15, s: 2 },
+                ScalarValue::Decimal128(Some(1234), 15, 2),
+            ),
+            (
+                PrimitiveType::Decimal { p: 15, s: 2 },
+                ScalarValue::Decimal128(Some(-1234), 15, 2),
+            ),
+            (
+                PrimitiveType::Decimal { p: 15, s: 2 },
+                ScalarValue::Decimal128(Some(128), 15, 2),
+            ),
+        ];
+
+        for (typ, scalar) in test_cases {
+            let bs = typ.scalar_to_bytes(&scalar).unwrap();
+            let out = typ.scalar_from_bytes(&bs).unwrap();
+            assert_eq!(scalar, out, "type: {typ:?}");
+        }
+
+        // Minimal encoding.
+        assert_eq!(vec![0x04, 0xD2], decimal_to_be_bytes(1234));
+        assert_eq!(vec![0xFB, 0x2E], decimal_to_be_bytes(-1234));
+        assert_eq!(vec![0x00, 0x80], decimal_to_be_bytes(128));
+
+        // No bounds for nulls or strings.
+        assert_eq!(
+            None,
+            PrimitiveType::Int.scalar_to_bytes(&ScalarValue::Int32(None))
+        );
+        assert_eq!(
+            None,
+            PrimitiveType::String.scalar_to_bytes(&ScalarValue::Utf8(Some("a".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_scalar_from_avro() {
+        let out = PrimitiveType::String
+            .scalar_from_avro(&AvroValue::Union(
+                1,
+                Box::new(AvroValue::String("MAIL".to_string())),
+            ))
+            .unwrap();
+        assert_eq!(ScalarValue::Utf8(Some("MAIL".to_string())), out);
+
+        let out = PrimitiveType::Int
+            .scalar_from_avro(&AvroValue::Union(0, Box::new(AvroValue::Null)))
+            .unwrap();
+        assert_eq!(ScalarValue::Int32(None), out);
+    }
+}
@@ -1,10 +1,20 @@
-use super::spec::{Manifest, ManifestContent, ManifestList, Snapshot, TableMetadata};
+use super::deletes::{DeleteIndex, IcebergDeleteFilterExec, ScanFile};
+use super::pruning::ContainerStatistics;
+use super::sink::{IcebergInsertExec, IcebergSink};
+use super::spec::{
+    data_file_content, entry_status, DataFile, Manifest, ManifestContent, ManifestList,
+    ManifestListEntry, PartitionField, Schema, Snapshot, TableMetadata,
+};
 
+use crate::common::exprs_to_phys_exprs;
 use crate::common::url::DatasourceUrl;
 use crate::lake::iceberg::errors::{IcebergError, Result};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use datafusion::arrow::datatypes::{Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
+use datafusion::arrow::datatypes::{
+    DataType, Field as ArrowField, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef,
+};
+use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::datasource::file_format::parquet::ParquetFormat;
 use datafusion::datasource::file_format::FileFormat;
 use datafusion::datasource::listing::PartitionedFile;
@@ -15,9 +25,11 @@ use datafusion::execution::context::SessionState;
 use datafusion::execution::context::TaskContext;
 use datafusion::execution::object_store::ObjectStoreUrl;
 use datafusion::logical_expr::{Expr, TableProviderFilterPushDown, TableType};
-use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_expr::{PhysicalExpr, PhysicalSortExpr};
+use datafusion::physical_optimizer::pruning::PruningPredicate;
+use datafusion::physical_plan::union::UnionExec;
 use datafusion::physical_plan::{
-    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+    collect, DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
     Statistics,
 };
 use object_store::{path::Path as ObjectPath, ObjectMeta, ObjectStore};
@@ -27,7 +39,7 @@ use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct IcebergTable {
-    state: TableState,
+    pub(super) state: TableState,
 }
 
 impl IcebergTable {
@@ -59,27 +71,66 @@ impl IcebergTable {
     }
 
     pub async fn table_reader(&self) -> Result<Arc<dyn TableProvider>> {
-        let schema = self.table_arrow_schema()?;
+        self.table_reader_at(SnapshotSelector::Current).await
+    }
+
+    /// Get a reader for the table as of some snapshot.
+    ///
+    /// The reader uses the schema the snapshot was written with.
+    pub async fn table_reader_at(
+        &self,
+        snapshot: SnapshotSelector,
+    ) -> Result<Arc<dyn TableProvider>> {
+        let mut state = self.state.clone();
+        state.snapshot_id = match snapshot {
+            SnapshotSelector::Current => None,
+            SnapshotSelector::Id(id) => Some(id),
+            SnapshotSelector::AsOf(timestamp) => Some(self.state.snapshot_id_as_of(timestamp)?),
+        };
+        if state.snapshot_id.is_some() {
+            // Make sure the snapshot exists.
+            state.snapshot()?;
+        }
+
+        let schema = state.table_arrow_schema()?;
 
         Ok(Arc::new(IcebergTableReader {
             schema: Arc::new(schema),
-            state: self.state.clone(),
+            state,
         }))
     }
 }
 
+/// Selects the snapshot to read from a table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SnapshotSelector {
+    /// The current snapshot according to the table metadata.
+    #[default]
+    Current,
+    /// A snapshot by id.
+    Id(i64),
+    /// The snapshot that was current at some point in time.
+    AsOf(DateTime<Utc>),
+}
+
 /// Information about the state of the table at some table version.
 #[derive(Debug, Clone)]
-struct TableState {
+pub(super) struct TableState {
     /// The root of the table.
-    location: DatasourceUrl,
+    pub(super) location: DatasourceUrl,
 
     /// Store for accessing the table.
-    store: Arc<dyn ObjectStore>,
+    pub(super) store: Arc<dyn ObjectStore>,
+
+    /// Version of the loaded metadata, according to the version hint.
+    pub(super) version: String,
+
+    /// Loaded table metadata.
+    pub(super) metadata: TableMetadata,
 
-    /// Loaded table metadata. Table reads will use the snapshot in this
-    /// metadata.
-    metadata: TableMetadata,
+    /// Snapshot to read. Table reads will use the current snapshot in the
+    /// metadata if not set.
+    pub(super) snapshot_id: Option<i64>,
 
     /// Resolve paths relative to the table's root.
     resolver: PathResolver,
@@ -101,7 +152,7 @@ impl TableState {
         } else {
             version_contents.as_str()
         };
-        let version = first_line.trim();
+        let version = first_line.trim().to_string();
 
         // Read metadata.
         let path = format_object_path(&location, format!("metadata/v{version}.metadata.json"))?;
@@ -115,38 +166,66 @@ impl TableState {
         Ok(TableState {
             location,
             store,
+            version,
             metadata,
+            snapshot_id: None,
             resolver,
         })
     }
 
-    /// Get the current snapshot from the table metadata
-    fn current_snapshot(&self) -> Result<&Snapshot> {
-        let current_snapshot_id = self
-            .metadata
-            .current_snapshot_id
-            .ok_or_else(|| IcebergError::DataInvalid("Missing current snapshot id".to_string()))?;
+    /// Get the snapshot to read from the table metadata.
+    fn snapshot(&self) -> Result<&Snapshot> {
+        let snapshot_id = match self.snapshot_id {
+            Some(id) => id,
+            None => self.metadata.current_snapshot_id.ok_or_else(|| {
+                IcebergError::DataInvalid("Missing current snapshot id".to_string())
+            })?,
+        };
 
-        let current_snapshot = self
-            .metadata
+        self.metadata
             .snapshots
             .iter()
-            .find(|s| s.snapshot_id == current_snapshot_id)
-            .ok_or_else(|| {
-                IcebergError::DataInvalid(format!(
-                    "Missing snapshot for id: {}",
-                    current_snapshot_id
-                ))
-            })?;
+            .find(|s| s.snapshot_id == snapshot_id)
+            .ok_or(IcebergError::MissingSnapshot(snapshot_id))
+    }
 
-        Ok(current_snapshot)
+    /// Get the id of the snapshot that was current at the given time.
+    ///
+    /// Uses the snapshot log since that tracks which snapshots were actually
+    /// current for the table, falling back to snapshot timestamps if the log
+    /// is empty.
+    fn snapshot_id_as_of(&self, timestamp: DateTime<Utc>) -> Result<i64> {
+        let timestamp_ms = timestamp.timestamp_millis();
+
+        let candidates: Vec<_> = if self.metadata.snapshot_log.is_empty() {
+            self.metadata
+                .snapshots
+                .iter()
+                .map(|s| (s.timestamp_ms, s.snapshot_id))
+                .collect()
+        } else {
+            self.metadata
+                .snapshot_log
+                .iter()
+                .map(|s| (s.timestamp_ms, s.snapshot_id))
+                .collect()
+        };
+
+        candidates
+            .into_iter()
+            .filter(|(ts, _)| *ts <= timestamp_ms)
+            .max_by_key(|(ts, _)| *ts)
+            .map(|(_, id)| id)
+            .ok_or(IcebergError::NoSnapshotAsOf(timestamp))
     }
 
-    fn table_arrow_schema(&self) -> Result<ArrowSchema> {
+    /// Get the schema for the snapshot being read.
+    pub(super) fn schema(&self) -> Result<&Schema> {
         // v1: Read `schema`
         //
         // v2: Read `current-schema-id`, then find that correct schema in
-        // `schemas`.
+        // `schemas`. When reading an older snapshot, use the schema that the
+        // snapshot was written with instead.
 
         if self.metadata.format_version != 2 {
             return Err(IcebergError::UnsupportedFormatVersion(
@@ -154,26 +233,50 @@ impl TableState {
             ));
         }
 
-        let schema = self
-            .metadata
+        let schema_id = match self.snapshot_id {
+            Some(_) => self
+                .snapshot()?
+                .schema_id
+                .unwrap_or(self.metadata.current_schema_id),
+            None => self.metadata.current_schema_id,
+        };
+
+        self.metadata
             .schemas
             .iter()
-            .find(|s| s.schema_id == self.metadata.current_schema_id)
+            .find(|s| s.schema_id == schema_id)
             .ok_or_else(|| {
-                IcebergError::DataInvalid(format!(
-                    "Missing schema for id: {}",
-                    self.metadata.current_schema_id
-                ))
-            })?;
+                IcebergError::DataInvalid(format!("Missing schema for id: {}", schema_id))
+            })
+    }
 
-        schema.to_arrow_schema()
+    fn table_arrow_schema(&self) -> Result<ArrowSchema> {
+        self.schema()?.to_arrow_schema()
+    }
+
+    /// Get the fields for a partition spec.
+    pub(super) fn partition_spec(&self, spec_id: i32) -> Result<&[PartitionField]> {
+        self.metadata
+            .partition_specs
+            .iter()
+            .find(|s| s.spec_id == spec_id)
+            .map(|s| s.fields.as_slice())
+            .ok_or_else(|| {
+                IcebergError::DataInvalid(format!("Missing partition spec for id: {spec_id}"))
+            })
     }
 
     async fn read_manifests(&self) -> Result<Vec<Manifest>> {
         let list = self.read_manifest_list().await?;
+        self.read_manifests_from_list(&list.entries).await
+    }
 
+    async fn read_manifests_from_list(
+        &self,
+        entries: &[ManifestListEntry],
+    ) -> Result<Vec<Manifest>> {
         let mut manifests = Vec::new();
-        for ent in list.entries {
+        for ent in entries {
             let manifest_path = self.resolver.relative_path(&ent.manifest_path);
 
             let path = format_object_path(&self.location, manifest_path)?;
@@ -181,16 +284,255 @@ impl TableState {
 
             let cursor = Cursor::new(bs);
 
-            let manifest = Manifest::from_raw_avro(cursor)?;
+            let mut manifest = Manifest::from_raw_avro(cursor)?;
+
+            // > When reading v2 manifests, the data sequence number and file
+            // > sequence number for entries with status ADDED are inherited
+            // > from the manifest's sequence number when null.
+            for entry in &mut manifest.entries {
+                if entry.status == entry_status::ADDED {
+                    entry.sequence_number.get_or_insert(ent.sequence_number);
+                    entry
+                        .file_sequence_number
+                        .get_or_insert(ent.sequence_number);
+                }
+            }
+
             manifests.push(manifest);
         }
 
         Ok(manifests)
     }
 
-    async fn read_manifest_list(&self) -> Result<ManifestList> {
-        let current_snapshot = self.current_snapshot()?;
-        let manifest_list_path = self.resolver.relative_path(&current_snapshot.manifest_list);
+    /// Get the live data and delete files for the current snapshot.
+    ///
+    /// If a pruning predicate is provided, data manifests and data files that
+    /// can't contain matching rows are skipped. Delete files are never pruned.
+    async fn scan_files(
+        &self,
+        arrow_schema: &ArrowSchemaRef,
+        pruning: Option<&PruningPredicate>,
+    ) -> Result<(Vec<ScanFile>, Vec<ScanFile>)> {
+        let schema = self.schema()?;
+        let mut entries = self.read_manifest_list().await?.entries;
+
+        if let Some(pruning) = pruning {
+            let specs = entries
+                .iter()
+                .map(|ent| self.partition_spec(ent.partition_spec_id))
+                .collect::<Result<Vec<_>>>()?;
+            let stats = ContainerStatistics::from_partition_summaries(
+                arrow_schema.clone(),
+                schema,
+                specs
+                    .iter()
+                    .zip(&entries)
+                    .map(|(spec, ent)| (*spec, ent.partitions.as_slice())),
+            )?;
+            let keep = stats.prune(pruning)?;
+            entries = entries
+                .into_iter()
+                .zip(keep)
+                .filter(|(ent, keep)| *keep || ent.is_delete())
+                .map(|(ent, _)| ent)
+                .collect();
+        }
+
+        let mut data_files = Vec::new();
+        let mut delete_files = Vec::new();
+        for manifest in self.read_manifests_from_list(&entries).await? {
+            let spec_id = manifest.metadata.partition_spec_id;
+            let unpartitioned = manifest.metadata.partition_spec.is_empty();
+
+            for entry in manifest.entries {
+                // Deleted entries are only kept for history.
+                if entry.status == entry_status::DELETED {
+                    continue;
+                }
+                let file = ScanFile {
+                    file: entry.data_file,
+                    sequence_number: entry.sequence_number.unwrap_or_default(),
+                    spec_id,
+                    unpartitioned,
+                };
+                match manifest.metadata.content {
+                    ManifestContent::Data => data_files.push(file),
+                    ManifestContent::Delete => delete_files.push(file),
+                }
+            }
+        }
+
+        if let Some(pruning) = pruning {
+            let stats = self.data_file_statistics(arrow_schema, &data_files)?;
+            let keep = stats.prune(pruning)?;
+            data_files = data_files
+                .into_iter()
+                .zip(keep)
+                .filter_map(|(file, keep)| keep.then_some(file))
+                .collect();
+        }
+
+        Ok((data_files, delete_files))
+    }
+
+    fn data_file_statistics(
+        &self,
+        arrow_schema: &ArrowSchemaRef,
+        files: &[ScanFile],
+    ) -> Result<ContainerStatistics> {
+        let specs = files
+            .iter()
+            .map(|f| self.partition_spec(f.spec_id))
+            .collect::<Result<Vec<_>>>()?;
+        ContainerStatistics::from_data_files(
+            arrow_schema.clone(),
+            self.schema()?,
+            specs.iter().zip(files).map(|(spec, f)| (*spec, &f.file)),
+        )
+    }
+
+    /// Get statistics for a scan over the provided data files.
+    ///
+    /// Row counts are only exact if no rows have been deleted from the files.
+    /// Lower and upper bounds from the manifests are never exact, and since
+    /// exactness applies to all statistics, they're left out when row counts
+    /// are exact.
+    fn scan_statistics(
+        &self,
+        arrow_schema: &ArrowSchemaRef,
+        files: &[ScanFile],
+        is_exact: bool,
+    ) -> Result<Statistics> {
+        let stats = self.data_file_statistics(arrow_schema, files)?;
+        let mut column_statistics = stats.merged_column_statistics();
+        if is_exact {
+            for column in &mut column_statistics {
+                column.min_value = None;
+                column.max_value = None;
+            }
+        }
+        Ok(Statistics {
+            num_rows: Some(files.iter().map(|f| f.file.record_count as usize).sum()),
+            total_byte_size: Some(
+                files
+                    .iter()
+                    .map(|f| f.file.file_size_in_bytes as usize)
+                    .sum(),
+            ),
+            column_statistics: Some(column_statistics),
+            is_exact,
+        })
+    }
+
+    /// Read the contents of all delete files.
+    async fn load_deletes(
+        &self,
+        ctx: &SessionState,
+        object_url: &ObjectStoreUrl,
+        arrow_schema: &ArrowSchemaRef,
+        files: &[ScanFile],
+    ) -> Result<DeleteIndex> {
+        let schema = self.schema()?;
+        let mut index = DeleteIndex::default();
+
+        for file in files {
+            match file.file.content {
+                data_file_content::POSITION_DELETES => {
+                    let delete_schema = Arc::new(ArrowSchema::new(vec![
+                        ArrowField::new("file_path", DataType::Utf8, false),
+                        ArrowField::new("pos", DataType::Int64, false),
+                    ]));
+                    let batches = self
+                        .read_parquet_file(ctx, object_url, &file.file, delete_schema)
+                        .await?;
+                    index.add_position_deletes(file, &batches)?;
+                }
+                data_file_content::EQUALITY_DELETES => {
+                    let columns = file
+                        .file
+                        .equality_ids
+                        .iter()
+                        .flatten()
+                        .map(|id| {
+                            let field = schema.field_by_id(*id).ok_or_else(|| {
+                                IcebergError::DataInvalid(format!(
+                                    "Missing field for equality delete id: {id}"
+                                ))
+                            })?;
+                            Ok(arrow_schema.index_of(&field.name)?)
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    let delete_schema = Arc::new(arrow_schema.project(&columns)?);
+                    let batches = self
+                        .read_parquet_file(ctx, object_url, &file.file, delete_schema)
+                        .await?;
+                    index.add_equality_deletes(file, columns, &batches)?;
+                }
+                other => {
+                    return Err(IcebergError::DataInvalid(format!(
+                        "Unexpected content for delete file: {other}"
+                    )))
+                }
+            }
+        }
+
+        Ok(index)
+    }
+
+    /// Read all rows from a parquet file using the provided schema.
+    async fn read_parquet_file(
+        &self,
+        ctx: &SessionState,
+        object_url: &ObjectStoreUrl,
+        file: &DataFile,
+        schema: ArrowSchemaRef,
+    ) -> Result<Vec<RecordBatch>> {
+        let conf = FileScanConfig {
+            object_store_url: object_url.clone(),
+            file_schema: schema,
+            projection: None,
+            statistics: Statistics::default(),
+            file_groups: vec![vec![self.partitioned_file(file)?]],
+            limit: None,
+            table_partition_cols: Vec::new(),
+            output_ordering: Vec::new(),
+            infinite_source: false,
+        };
+        let plan = ParquetFormat::new()
+            .create_physical_plan(ctx, conf, None)
+            .await?;
+        Ok(collect(plan, ctx.task_ctx()).await?)
+    }
+
+    fn partitioned_file(&self, file: &DataFile) -> Result<PartitionedFile> {
+        let path = self.resolver.relative_path(&file.file_path);
+        let meta = ObjectMeta {
+            location: format_object_path(&self.location, path)?,
+            last_modified: DateTime::<Utc>::MIN_UTC, // TODO: Get the actual time.
+            size: file.file_size_in_bytes as usize,
+            e_tag: None,
+        };
+
+        Ok(PartitionedFile {
+            object_meta: meta,
+            partition_values: file.partition_values.clone(),
+            range: None,
+            extensions: None,
+        })
+    }
+
+    /// Read the manifest list for the snapshot being read.
+    ///
+    /// Tables without any snapshots have an empty manifest list.
+    pub(super) async fn read_manifest_list(&self) -> Result<ManifestList> {
+        if self.snapshot_id.is_none() && self.metadata.current_snapshot_id.is_none() {
+            return Ok(ManifestList {
+                entries: Vec::new(),
+            });
+        }
+
+        let snapshot = self.snapshot()?;
+        let manifest_list_path = self.resolver.relative_path(&snapshot.manifest_list);
 
         let path = format_object_path(&self.location, manifest_list_path)?;
         let bs = self.store.get(&path).await?.bytes().await?;
@@ -270,7 +612,7 @@ impl TableProvider for IcebergTableReader {
         &self,
         ctx: &SessionState,
         projection: Option<&Vec<usize>>,
-        _filters: &[Expr],
+        filters: &[Expr],
         limit: Option<usize>,
     ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
         // Create the datafusion specific url, and register the object store.
@@ -279,75 +621,148 @@ impl TableProvider for IcebergTableReader {
             .object_store_registry
             .register_store(object_url.as_ref(), self.state.store.clone());
 
-        // TODO: Properly prune based on partition values. This currently skips
-        // any partition processing, and shoves everything into a single file
-        // group when passing to the parquet exec.
-        //
-        // We also miss out on parallel reading by using a single file group.
+        let predicate = exprs_to_phys_exprs(filters, ctx, &self.schema)?;
+        let pruning = predicate
+            .as_ref()
+            .map(|expr| PruningPredicate::try_new(expr.clone(), self.schema.clone()))
+            .transpose()?;
 
-        // TODO: Properly handle row-level deletes. Currently files containing
-        // delete information are ignored.
+        let scan = self
+            .create_scan(ctx, object_url, projection, predicate, pruning, limit)
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
 
-        // TODO: Use provided filters to prune out partitions and/or data files
-        // (since the metadata will have some info about file content).
+        Ok(Arc::new(scan))
+    }
 
-        // TODO: Collect statistics and pass to exec.
+    async fn insert_into(
+        &self,
+        _state: &SessionState,
+        input: Arc<dyn ExecutionPlan>,
+        overwrite: bool,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if overwrite {
+            return Err(DataFusionError::NotImplemented(
+                "Overwriting iceberg tables is not supported".to_string(),
+            ));
+        }
+        if self.state.snapshot_id.is_some() {
+            return Err(DataFusionError::Plan(
+                "Cannot insert into a previous snapshot of an iceberg table".to_string(),
+            ));
+        }
+
+        let sink = IcebergSink::new(self.state.location.clone(), self.state.store.clone());
+        Ok(Arc::new(IcebergInsertExec::new(input, sink)))
+    }
+}
 
-        let manifests = self
+impl IcebergTableReader {
+    async fn create_scan(
+        &self,
+        ctx: &SessionState,
+        object_url: ObjectStoreUrl,
+        projection: Option<&Vec<usize>>,
+        predicate: Option<Arc<dyn PhysicalExpr>>,
+        pruning: Option<PruningPredicate>,
+        limit: Option<usize>,
+    ) -> Result<IcebergTableScan> {
+        let (data_files, delete_files) = self
             .state
-            .read_manifests()
-            .await
-            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            .scan_files(&self.schema, pruning.as_ref())
+            .await?;
+        let deletes = self
+            .state
+            .load_deletes(ctx, &object_url, &self.schema, &delete_files)
+            .await?;
 
-        // Get only data files with "data" content.
-        //
-        // TODO: Handle "delete" content and also pull out partition
-        // information.
-        let data_files: Vec<_> = manifests
-            .into_iter()
-            .filter(|m| matches!(m.metadata.content, ManifestContent::Data))
-            .flat_map(|m| m.entries.into_iter().map(|ent| ent.data_file))
-            .collect();
+        // Files without any deletes can be read directly, everything else
+        // needs to go through a delete filter.
+        let mut plain_files = Vec::new();
+        let mut inputs: Vec<Arc<dyn ExecutionPlan>> = Vec::new();
+        for file in &data_files {
+            let file_deletes = deletes.deletes_for_file(file);
+            if file_deletes.is_empty() {
+                plain_files.push(file.clone());
+                continue;
+            }
 
-        let partitioned_files = data_files
-            .iter()
-            .map(|f| {
-                let path = self.state.resolver.relative_path(&f.file_path);
-                let meta = ObjectMeta {
-                    location: format_object_path(&self.state.location, path)?,
-                    last_modified: DateTime::<Utc>::MIN_UTC, // TODO: Get the actual time.
-                    size: f.file_size_in_bytes as usize,
-                    e_tag: None,
-                };
+            // Read the full file without any pruning so that row positions
+            // are preserved.
+            let conf = FileScanConfig {
+                object_store_url: object_url.clone(),
+                file_schema: self.schema.clone(),
+                projection: None,
+                statistics: Statistics::default(),
+                file_groups: vec![vec![self.state.partitioned_file(&file.file)?]],
+                limit: None,
+                table_partition_cols: Vec::new(),
+                output_ordering: Vec::new(),
+                infinite_source: false,
+            };
+            let input = ParquetFormat::new()
+                .create_physical_plan(ctx, conf, None)
+                .await?;
+            inputs.push(Arc::new(IcebergDeleteFilterExec::try_new(
+                input,
+                file_deletes,
+                projection.cloned(),
+            )?));
+        }
 
-                Ok(PartitionedFile {
-                    object_meta: meta,
-                    partition_values: Vec::new(),
-                    range: None,
-                    extensions: None,
-                })
-            })
-            .collect::<Result<Vec<PartitionedFile>>>()
-            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        if !plain_files.is_empty() || inputs.is_empty() {
+            // Spread files across partitions for parallel reads.
+            let num_groups = ctx
+                .config()
+                .target_partitions()
+                .min(plain_files.len())
+                .max(1);
+            let mut file_groups = vec![Vec::new(); num_groups];
+            for (idx, file) in plain_files.iter().enumerate() {
+                file_groups[idx % num_groups].push(self.state.partitioned_file(&file.file)?);
+            }
 
-        let conf = FileScanConfig {
-            object_store_url: object_url,
-            file_schema: self.schema(),
-            projection: projection.cloned(),
-            statistics: Statistics::default(),
-            file_groups: vec![partitioned_files],
-            limit,
-            table_partition_cols: Vec::new(),
-            output_ordering: Vec::new(),
-            infinite_source: false,
+            let conf = FileScanConfig {
+                object_store_url: object_url,
+                file_schema: self.schema.clone(),
+                projection: projection.cloned(),
+                statistics: self
+                    .state
+                    .scan_statistics(&self.schema, &plain_files, true)?,
+                file_groups,
+                limit,
+                table_partition_cols: Vec::new(),
+                output_ordering: Vec::new(),
+                infinite_source: false,
+            };
+            let plan = ParquetFormat::new()
+                .create_physical_plan(ctx, conf, predicate.as_ref())
+                .await?;
+            inputs.push(plan);
+        }
+
+        let input = if inputs.len() == 1 {
+            inputs.pop().unwrap()
+        } else {
+            Arc::new(UnionExec::new(inputs))
         };
 
-        let plan = ParquetFormat::new()
-            .create_physical_plan(ctx, conf, None)
-            .await?;
+        let statistics =
+            self.state
+                .scan_statistics(&self.schema, &data_files, delete_files.is_empty())?;
+        let statistics = project_statistics(statistics, projection);
+
+        Ok(IcebergTableScan { input, statistics })
+    }
+}
 
-        Ok(Arc::new(IcebergTableScan { parquet_scan: plan }))
+/// Project column statistics to match the projected output of a scan.
+fn project_statistics(mut statistics: Statistics, projection: Option<&Vec<usize>>) -> Statistics {
+    if let (Some(projection), Some(columns)) = (projection, statistics.column_statistics.take()) {
+        statistics.column_statistics =
+            Some(projection.iter().map(|idx| columns[*idx].clone()).collect());
     }
+    statistics
 }
 
 /// Creates a datafusion object store url from the provided data source url.
@@ -367,7 +782,10 @@ fn datasource_url_to_unique_url(url: &DatasourceUrl) -> ObjectStoreUrl {
 
 #[derive(Debug)]
 pub struct IcebergTableScan {
-    parquet_scan: Arc<dyn ExecutionPlan>,
+    /// Scan of the data files. Either a single parquet scan, or a union of a
+    /// parquet scan and scans of files with deletes.
+    input: Arc<dyn ExecutionPlan>,
+    statistics: Statistics,
 }
 
 impl ExecutionPlan for IcebergTableScan {
@@ -376,26 +794,29 @@ impl ExecutionPlan for IcebergTableScan {
     }
 
     fn schema(&self) -> Arc<ArrowSchema> {
-        self.parquet_scan.schema()
+        self.input.schema()
     }
 
     fn output_partitioning(&self) -> Partitioning {
-        self.parquet_scan.output_partitioning()
+        self.input.output_partitioning()
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        self.parquet_scan.output_ordering()
+        self.input.output_ordering()
     }
 
     fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
-        vec![self.parquet_scan.clone()]
+        vec![self.input.clone()]
     }
 
     fn with_new_children(
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
-        ExecutionPlan::with_new_children(self.parquet_scan.clone(), children)
+        Ok(Arc::new(IcebergTableScan {
+            input: children[0].clone(),
+            statistics: self.statistics.clone(),
+        }))
     }
 
     fn execute(
@@ -403,25 +824,28 @@ impl ExecutionPlan for IcebergTableScan {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> DataFusionResult<SendableRecordBatchStream> {
-        self.parquet_scan.execute(partition, context)
+        self.input.execute(partition, context)
     }
 
     fn statistics(&self) -> Statistics {
-        Statistics::default()
+        self.statistics.clone()
     }
 }
 
 impl DisplayAs for IcebergTableScan {
     fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "IcebergTableScan(")?;
-        self.parquet_scan.fmt_as(t, f)?;
+        self.input.fmt_as(t, f)?;
         write!(f, ")")
     }
 }
 
 /// Formats an object path depending on if it's a url (for real object stores),
 /// or if it's a local path.
-fn format_object_path(
+///
+/// Local table roots must exist, but the file being pointed to does not need
+/// to.
+pub(super) fn format_object_path(
     url: &DatasourceUrl,
     path: impl AsRef<str>,
 ) -> Result<ObjectPath, object_store::path::Error> {
@@ -433,8 +857,9 @@ fn format_object_path(
             ObjectPath::parse(path)
         }
         DatasourceUrl::File(root_path) => {
-            let path = root_path.join(path);
-            ObjectPath::from_filesystem_path(path)
+            let root = ObjectPath::from_filesystem_path(root_path)?;
+            let path = ObjectPath::parse(path)?;
+            Ok(root.parts().chain(path.parts()).collect())
         }
     }
 }
@@ -0,0 +1,716 @@
+//! Writing data to iceberg tables.
+//!
+//! An append writes a single parquet data file along with a new manifest for
+//! that file. A new manifest list containing the new manifest and all of the
+//! table's existing manifests is then written, and the append is committed by
+//! writing the next version of the table metadata and updating the version
+//! hint.
+//!
+//! Only unpartitioned v2 tables can currently be written to.
+
+use super::spec::{
+    data_file_content, entry_status, AnyType, BinaryEntry, DataFile, I64Entry, Manifest,
+    ManifestContent, ManifestEntry, ManifestList, ManifestListEntry, ManifestMetadata, MetadataLog,
+    PartitionSpec, PrimitiveType, Schema, Snapshot, SnapshotLog, SnapshotReference, SortOrder,
+    TableMetadata,
+};
+use super::table::{format_object_path, IcebergTable, TableState};
+
+use crate::common::url::DatasourceUrl;
+use crate::lake::iceberg::errors::{IcebergError, Result};
+use chrono::Utc;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::logical_expr::Accumulator;
+use datafusion::parquet::arrow::AsyncArrowWriter;
+use datafusion::parquet::file::properties::WriterProperties;
+use datafusion::physical_expr::expressions::{MaxAccumulator, MinAccumulator};
+use datafusion::physical_plan::SendableRecordBatchStream;
+use futures::StreamExt;
+use object_store::ObjectStore;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const BUFFER_SIZE: usize = 8 * 1024 * 1024;
+
+const VERSION_HINT_PATH: &str = "metadata/version-hint.text";
+
+impl IcebergTable {
+    /// Create a new empty table at a location.
+    ///
+    /// The table's schema is derived from the provided arrow schema.
+    pub async fn create(
+        location: DatasourceUrl,
+        store: Arc<dyn ObjectStore>,
+        schema: &ArrowSchema,
+    ) -> Result<IcebergTable> {
+        let table_location = match &location {
+            DatasourceUrl::File(path) => {
+                tokio::fs::create_dir_all(path).await?;
+                tokio::fs::canonicalize(path)
+                    .await?
+                    .to_string_lossy()
+                    .into_owned()
+            }
+            DatasourceUrl::Url(url) => url.as_str().trim_end_matches('/').to_string(),
+        };
+
+        let schema = Schema::try_from_arrow(0, schema)?;
+        // Make sure we'll actually be able to write data files for this
+        // schema before creating anything.
+        schema.to_arrow_write_schema()?;
+
+        let metadata = TableMetadata {
+            format_version: 2,
+            table_uuid: Uuid::new_v4().to_string(),
+            location: table_location,
+            last_sequence_number: 0,
+            last_updated_ms: Utc::now().timestamp_millis(),
+            last_column_id: schema.highest_field_id(),
+            current_schema_id: schema.schema_id,
+            schemas: vec![schema],
+            partition_specs: vec![PartitionSpec {
+                spec_id: 0,
+                fields: Vec::new(),
+            }],
+            default_spec_id: 0,
+            // > Partition field IDs ... start at 1000
+            last_partition_id: 999,
+            properties: None,
+            current_snapshot_id: None,
+            snapshots: Vec::new(),
+            snapshot_log: Vec::new(),
+            metadata_log: Vec::new(),
+            sort_orders: vec![SortOrder {
+                order_id: 0,
+                fields: Vec::new(),
+            }],
+            default_sort_order_id: 0,
+            refs: None,
+        };
+
+        commit_metadata(&location, store.as_ref(), 1, &metadata).await?;
+
+        IcebergTable::open(location, store).await
+    }
+
+    /// Open the table at a location, creating a new empty table with the
+    /// provided schema if a table doesn't already exist.
+    pub async fn open_or_create(
+        location: DatasourceUrl,
+        store: Arc<dyn ObjectStore>,
+        schema: &ArrowSchema,
+    ) -> Result<IcebergTable> {
+        let exists = match &location {
+            DatasourceUrl::File(path) => {
+                tokio::fs::try_exists(path.join(VERSION_HINT_PATH)).await?
+            }
+            DatasourceUrl::Url(_) => {
+                let path = format_object_path(&location, VERSION_HINT_PATH)?;
+                match store.head(&path).await {
+                    Ok(_) => true,
+                    Err(object_store::Error::NotFound { .. }) => false,
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        };
+
+        if exists {
+            IcebergTable::open(location, store).await
+        } else {
+            IcebergTable::create(location, store, schema).await
+        }
+    }
+
+    /// Append record batches to the table, committing a new snapshot.
+    ///
+    /// Columns are matched to the table's current schema by position. Returns
+    /// the number of rows written. Nothing is committed if the stream doesn't
+    /// produce any rows.
+    pub async fn append(&self, stream: SendableRecordBatchStream) -> Result<u64> {
+        let state = &self.state;
+        if state.metadata.format_version != 2 {
+            return Err(IcebergError::UnsupportedFormatVersion(
+                state.metadata.format_version,
+            ));
+        }
+        if !state
+            .partition_spec(state.metadata.default_spec_id)?
+            .is_empty()
+        {
+            return Err(IcebergError::Static(
+                "Writing to partitioned tables is not supported",
+            ));
+        }
+
+        let data_file = match write_data_file(state, stream).await? {
+            Some(file) => file,
+            None => return Ok(0),
+        };
+        let num_rows = data_file.record_count as u64;
+
+        commit_append(state, data_file).await?;
+
+        Ok(num_rows)
+    }
+}
+
+/// Write the stream to a new parquet data file in the table.
+///
+/// Returns `None` if the stream was empty, in which case no file is left
+/// behind.
+async fn write_data_file(
+    state: &TableState,
+    mut stream: SendableRecordBatchStream,
+) -> Result<Option<DataFile>> {
+    let schema = state.schema()?;
+    let write_schema = Arc::new(schema.to_arrow_write_schema()?);
+
+    let relative_path = format!("data/{}.parquet", Uuid::new_v4());
+    let path = format_object_path(&state.location, &relative_path)?;
+
+    let (_id, obj_handle) = state.store.put_multipart(&path).await?;
+    let props = WriterProperties::builder()
+        .set_created_by("GlareDB".to_string())
+        .build();
+    let mut writer =
+        AsyncArrowWriter::try_new(obj_handle, write_schema.clone(), BUFFER_SIZE, Some(props))?;
+
+    let mut metrics = ColumnMetrics::try_new(schema)?;
+    let mut num_rows = 0;
+    while let Some(batch) = stream.next().await {
+        let batch = cast_batch(&batch?, &write_schema)?;
+        metrics.update(&batch)?;
+        num_rows += batch.num_rows();
+        writer.write(&batch).await?;
+    }
+    writer.close().await?;
+
+    if num_rows == 0 {
+        state.store.delete(&path).await?;
+        return Ok(None);
+    }
+
+    let size = state.store.head(&path).await?.size;
+    let (lower_bounds, upper_bounds) = metrics.bounds()?;
+
+    Ok(Some(DataFile {
+        content: data_file_content::DATA,
+        file_path: table_file_path(&state.metadata, &relative_path),
+        file_format: "PARQUET".to_string(),
+        record_count: num_rows as i64,
+        file_size_in_bytes: size as i64,
+        column_sizes: None,
+        value_counts: Some(metrics.value_counts(num_rows)),
+        null_value_counts: Some(metrics.null_value_counts()),
+        nan_value_counts: None,
+        distinct_counts: None,
+        lower_bounds: Some(lower_bounds),
+        upper_bounds: Some(upper_bounds),
+        key_metadata: None,
+        split_offsets: None,
+        equality_ids: None,
+        sort_order_id: None,
+        partition_values: Vec::new(),
+    }))
+}
+
+/// Cast a batch to the table's write schema, matching columns by position.
+fn cast_batch(batch: &RecordBatch, schema: &ArrowSchemaRef) -> Result<RecordBatch> {
+    if batch.num_columns() != schema.fields().len() {
+        return Err(IcebergError::DataInvalid(format!(
+            "Expected {} columns for table, got {}",
+            schema.fields().len(),
+            batch.num_columns()
+        )));
+    }
+
+    let columns = batch
+        .columns()
+        .iter()
+        .zip(schema.fields())
+        .map(|(col, field)| cast(col, field.data_type()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+/// Metrics for the top-level primitive columns of a data file.
+struct ColumnMetrics {
+    columns: Vec<ColumnMetric>,
+}
+
+struct ColumnMetric {
+    /// Index of the column in the batch.
+    idx: usize,
+    field_id: i32,
+    typ: PrimitiveType,
+    null_count: usize,
+    /// Min and max accumulators. Only tracked for types we produce bounds
+    /// for.
+    bounds: Option<(MinAccumulator, MaxAccumulator)>,
+}
+
+impl ColumnMetrics {
+    fn try_new(schema: &Schema) -> Result<ColumnMetrics> {
+        let mut columns = Vec::new();
+        for (idx, field) in schema.fields.iter().enumerate() {
+            let typ = match &field.r#type {
+                AnyType::Primitive(typ) => *typ,
+                _ => continue,
+            };
+            let bounds = match typ {
+                PrimitiveType::String | PrimitiveType::Binary | PrimitiveType::Fixed(_) => None,
+                _ => {
+                    let arrow_type = typ.try_into()?;
+                    Some((
+                        MinAccumulator::try_new(&arrow_type)?,
+                        MaxAccumulator::try_new(&arrow_type)?,
+                    ))
+                }
+            };
+            columns.push(ColumnMetric {
+                idx,
+                field_id: field.id,
+                typ,
+                null_count: 0,
+                bounds,
+            });
+        }
+
+        Ok(ColumnMetrics { columns })
+    }
+
+    fn update(&mut self, batch: &RecordBatch) -> Result<()> {
+        for col in &mut self.columns {
+            let arr = batch.column(col.idx);
+            col.null_count += arr.null_count();
+            if let Some((min, max)) = &mut col.bounds {
+                // Accumulators work on the unannotated arrow types.
+                let arr = cast(arr, &col.typ.try_into()?)?;
+                min.update_batch(&[arr.clone()])?;
+                max.update_batch(&[arr])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn value_counts(&self, num_rows: usize) -> Vec<I64Entry> {
+        self.columns
+            .iter()
+            .map(|col| I64Entry {
+                key: col.field_id,
+                value: num_rows as i64,
+            })
+            .collect()
+    }
+
+    fn null_value_counts(&self) -> Vec<I64Entry> {
+        self.columns
+            .iter()
+            .map(|col| I64Entry {
+                key: col.field_id,
+                value: col.null_count as i64,
+            })
+            .collect()
+    }
+
+    /// Get the lower and upper bounds for all columns.
+    fn bounds(&self) -> Result<(Vec<BinaryEntry>, Vec<BinaryEntry>)> {
+        let mut lower = Vec::new();
+        let mut upper = Vec::new();
+        for col in &self.columns {
+            let (min, max) = match &col.bounds {
+                Some(bounds) => bounds,
+                None => continue,
+            };
+            if let (Some(min), Some(max)) = (
+                col.typ.scalar_to_bytes(&min.evaluate()?),
+                col.typ.scalar_to_bytes(&max.evaluate()?),
+            ) {
+                lower.push(BinaryEntry {
+                    key: col.field_id,
+                    value: min,
+                });
+                upper.push(BinaryEntry {
+                    key: col.field_id,
+                    value: max,
+                });
+            }
+        }
+        Ok((lower, upper))
+    }
+}
+
+/// Commit a new snapshot for the table that adds the provided data file.
+async fn commit_append(state: &TableState, data_file: DataFile) -> Result<()> {
+    let metadata = &state.metadata;
+    let schema = state.schema()?;
+
+    let snapshot_id = new_snapshot_id();
+    let sequence_number = metadata.last_sequence_number + 1;
+    let timestamp_ms = Utc::now().timestamp_millis();
+    let added_records = data_file.record_count;
+    let added_files_size = data_file.file_size_in_bytes;
+
+    // Write the manifest for the new data file. Sequence numbers are left
+    // unset so that they're inherited from the manifest list.
+    let manifest = Manifest {
+        metadata: ManifestMetadata {
+            schema: schema.clone(),
+            schema_id: schema.schema_id,
+            partition_spec: Vec::new(),
+            partition_spec_id: metadata.default_spec_id,
+            format_version: 2,
+            content: ManifestContent::Data,
+        },
+        entries: vec![ManifestEntry {
+            status: entry_status::ADDED,
+            snapshot_id: Some(snapshot_id),
+            sequence_number: None,
+            file_sequence_number: None,
+            data_file,
+        }],
+    };
+    let manifest_path = format!("metadata/{}-m0.avro", Uuid::new_v4());
+    let manifest_length = put_file(state, &manifest_path, manifest.to_raw_avro()?).await?;
+
+    // Write the manifest list, carrying over all existing manifests.
+    let mut entries = vec![ManifestListEntry {
+        manifest_path: table_file_path(metadata, &manifest_path),
+        manifest_length: manifest_length as i64,
+        partition_spec_id: metadata.default_spec_id,
+        content: 0,
+        sequence_number,
+        min_sequence_number: sequence_number,
+        added_snapshot_id: snapshot_id,
+        added_files_count: Some(1),
+        existing_files_count: Some(0),
+        deleted_files_count: Some(0),
+        added_rows_count: Some(added_records),
+        existing_rows_count: Some(0),
+        deleted_rows_count: 0,
+        partitions: Vec::new(),
+        key_metadata: None,
+    }];
+    entries.extend(state.read_manifest_list().await?.entries);
+    let list = ManifestList { entries };
+    let list_path = format!("metadata/snap-{snapshot_id}-1-{}.avro", Uuid::new_v4());
+    put_file(
+        state,
+        &list_path,
+        list.to_raw_avro(snapshot_id, metadata.current_snapshot_id, sequence_number)?,
+    )
+    .await?;
+
+    // Write the new metadata.
+    let parent = metadata
+        .current_snapshot_id
+        .and_then(|id| metadata.snapshots.iter().find(|s| s.snapshot_id == id));
+    let summary = append_summary(parent, added_records, added_files_size);
+
+    let mut new_metadata = metadata.clone();
+    new_metadata.last_sequence_number = sequence_number;
+    new_metadata.last_updated_ms = timestamp_ms;
+    new_metadata.current_snapshot_id = Some(snapshot_id);
+    new_metadata.snapshots.push(Snapshot {
+        snapshot_id,
+        parent_snapshot_id: metadata.current_snapshot_id,
+        sequence_number: Some(sequence_number),
+        timestamp_ms,
+        summary,
+        manifest_list: table_file_path(metadata, &list_path),
+        schema_id: Some(schema.schema_id),
+    });
+    new_metadata.snapshot_log.push(SnapshotLog {
+        snapshot_id,
+        timestamp_ms,
+    });
+    new_metadata.metadata_log.push(MetadataLog {
+        metadata_file: table_file_path(
+            metadata,
+            &format!("metadata/v{}.metadata.json", state.version),
+        ),
+        timestamp_ms: metadata.last_updated_ms,
+    });
+    new_metadata.refs.get_or_insert_with(HashMap::new).insert(
+        SnapshotReference::MAIN_BRANCH.to_string(),
+        SnapshotReference::branch(snapshot_id),
+    );
+
+    let version: u64 = state.version.parse().map_err(|_| {
+        IcebergError::DataInvalid(format!(
+            "Cannot determine next version from version hint '{}'",
+            state.version
+        ))
+    })?;
+
+    commit_metadata(
+        &state.location,
+        state.store.as_ref(),
+        version + 1,
+        &new_metadata,
+    )
+    .await
+}
+
+/// Build the summary for an append snapshot.
+///
+/// Totals are only included if the parent snapshot included them.
+fn append_summary(
+    parent: Option<&Snapshot>,
+    added_records: i64,
+    added_files_size: i64,
+) -> HashMap<String, String> {
+    let mut summary = HashMap::from([
+        ("operation".to_string(), "append".to_string()),
+        ("added-data-files".to_string(), "1".to_string()),
+        ("added-records".to_string(), added_records.to_string()),
+        ("added-files-size".to_string(), added_files_size.to_string()),
+    ]);
+
+    for (total, added) in [
+        ("total-data-files", 1),
+        ("total-records", added_records),
+        ("total-files-size", added_files_size),
+    ] {
+        let prev = match parent {
+            Some(parent) => parent
+                .summary
+                .get(total)
+                .and_then(|v| v.parse::<i64>().ok()),
+            None => Some(0),
+        };
+        if let Some(prev) = prev {
+            summary.insert(total.to_string(), (prev + added).to_string());
+        }
+    }
+
+    summary
+}
+
+/// Write the table metadata for a version, and point the version hint at it.
+///
+/// Fails if metadata for the version already exists. Not all object stores
+/// support atomically creating objects, in which case the check is best
+/// effort.
+async fn commit_metadata(
+    location: &DatasourceUrl,
+    store: &dyn ObjectStore,
+    version: u64,
+    metadata: &TableMetadata,
+) -> Result<()> {
+    let path = format_object_path(location, format!("metadata/v{version}.metadata.json"))?;
+    let tmp = format_object_path(
+        location,
+        format!("metadata/{}.metadata.json.tmp", Uuid::new_v4()),
+    )?;
+    store
+        .put(&tmp, serde_json::to_vec_pretty(metadata)?.into())
+        .await?;
+
+    match store.rename_if_not_exists(&tmp, &path).await {
+        Ok(()) => (),
+        Err(object_store::Error::AlreadyExists { .. }) => {
+            let _ = store.delete(&tmp).await;
+            return Err(IcebergError::CommitConflict(version));
+        }
+        Err(object_store::Error::NotSupported { .. } | object_store::Error::NotImplemented) => {
+            if store.head(&path).await.is_ok() {
+                let _ = store.delete(&tmp).await;
+                return Err(IcebergError::CommitConflict(version));
+            }
+            store.rename(&tmp, &path).await?;
+        }
+        Err(e) => {
+            let _ = store.delete(&tmp).await;
+            return Err(e.into());
+        }
+    }
+
+    let hint = format_object_path(location, VERSION_HINT_PATH)?;
+    store.put(&hint, version.to_string().into()).await?;
+
+    Ok(())
+}
+
+/// Write a file relative to the table's root, returning the number of bytes
+/// written.
+async fn put_file(state: &TableState, relative_path: &str, bs: Vec<u8>) -> Result<usize> {
+    let path = format_object_path(&state.location, relative_path)?;
+    let len = bs.len();
+    state.store.put(&path, bs.into()).await?;
+    Ok(len)
+}
+
+/// Get the full path for a file in the table to store in table metadata and
+/// manifests.
+fn table_file_path(metadata: &TableMetadata, relative_path: &str) -> String {
+    // Matches how paths are resolved when reading.
+    let location = metadata
+        .location
+        .trim_start_matches("./")
+        .trim_end_matches('/');
+    format!("{location}/{relative_path}")
+}
+
+/// Generate a new positive snapshot id.
+fn new_snapshot_id() -> i64 {
+    (rand::random::<u64>() >> 1) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lake::iceberg::table::SnapshotSelector;
+    use datafusion::arrow::array::{Int64Array, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field};
+    use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+    use datafusion::prelude::SessionContext;
+    use object_store::local::LocalFileSystem;
+    use tempfile::tempdir;
+
+    fn batch_stream(
+        schema: ArrowSchemaRef,
+        batches: Vec<RecordBatch>,
+    ) -> SendableRecordBatchStream {
+        Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::iter(batches.into_iter().map(Ok)),
+        ))
+    }
+
+    async fn count_rows(table: &IcebergTable, snapshot: SnapshotSelector) -> usize {
+        let ctx = SessionContext::new();
+        let reader = table.table_reader_at(snapshot).await.unwrap();
+        ctx.read_table(reader)
+            .unwrap()
+            .collect()
+            .await
+            .unwrap()
+            .iter()
+            .map(|b| b.num_rows())
+            .sum()
+    }
+
+    #[tokio::test]
+    async fn test_create_and_append() {
+        let dir = tempdir().unwrap();
+        let location = DatasourceUrl::File(dir.path().join("table"));
+        let store: Arc<dyn ObjectStore> = Arc::new(LocalFileSystem::new());
+
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec![Some("x"), None, Some("z")])),
+            ],
+        )
+        .unwrap();
+
+        let table = IcebergTable::open_or_create(location.clone(), store.clone(), &schema)
+            .await
+            .unwrap();
+        assert_eq!(None, table.metadata().current_snapshot_id);
+        assert_eq!(0, count_rows(&table, SnapshotSelector::Current).await);
+
+        let n = table
+            .append(batch_stream(schema.clone(), vec![batch.clone()]))
+            .await
+            .unwrap();
+        assert_eq!(3, n);
+
+        // Append again after reopening.
+        let table = IcebergTable::open_or_create(location.clone(), store.clone(), &schema)
+            .await
+            .unwrap();
+        let first_snapshot = table.metadata().current_snapshot_id.unwrap();
+        table
+            .append(batch_stream(schema.clone(), vec![batch.clone(), batch]))
+            .await
+            .unwrap();
+
+        // Empty appends don't create snapshots.
+        let n = table
+            .append(batch_stream(schema.clone(), Vec::new()))
+            .await
+            .unwrap();
+        assert_eq!(0, n);
+
+        let table = IcebergTable::open(location, store).await.unwrap();
+        let metadata = table.metadata();
+        assert_eq!(2, metadata.snapshots.len());
+        assert_eq!(2, metadata.last_sequence_number);
+        assert_eq!(1, metadata.metadata_log.len());
+        assert_eq!(
+            Some("9"),
+            metadata.snapshots[1]
+                .summary
+                .get("total-records")
+                .map(|s| s.as_str())
+        );
+
+        assert_eq!(9, count_rows(&table, SnapshotSelector::Current).await);
+        assert_eq!(
+            3,
+            count_rows(&table, SnapshotSelector::Id(first_snapshot)).await
+        );
+
+        let manifests = table.read_manifests().await.unwrap();
+        assert_eq!(2, manifests.len());
+        let file = &manifests[0].entries[0].data_file;
+        assert_eq!(6, file.record_count);
+        assert_eq!(
+            Some(2),
+            file.null_value_counts
+                .iter()
+                .flatten()
+                .find(|e| e.key == 2)
+                .map(|e| e.value)
+        );
+        assert_eq!(
+            Some(1_i64.to_le_bytes().to_vec()),
+            file.lower_bounds
+                .iter()
+                .flatten()
+                .find(|e| e.key == 1)
+                .map(|e| e.value.clone())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_append_column_mismatch() {
+        let dir = tempdir().unwrap();
+        let location = DatasourceUrl::File(dir.path().to_path_buf());
+        let store: Arc<dyn ObjectStore> = Arc::new(LocalFileSystem::new());
+
+        let schema = ArrowSchema::new(vec![Field::new("a", DataType::Int64, false)]);
+        let table = IcebergTable::create(location, store, &schema)
+            .await
+            .unwrap();
+
+        let other = Arc::new(ArrowSchema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Int64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            other.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1])),
+                Arc::new(Int64Array::from(vec![2])),
+            ],
+        )
+        .unwrap();
+
+        table
+            .append(batch_stream(other, vec![batch]))
+            .await
+            .unwrap_err();
+    }
+}
@@ -14,4 +14,5 @@ pub mod native;
 pub mod object_store;
 pub mod postgres;
 pub mod snowflake;
+pub mod sqlite;
 pub mod sqlserver;
@@ -12,16 +12,19 @@ use exec::MongoDbBsonExec;
 use infer::TableSampler;
 
 use async_trait::async_trait;
-use datafusion::arrow::datatypes::{Fields, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
+use datafusion::arrow::datatypes::{
+    DataType, Fields, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef, TimeUnit,
+};
 use datafusion::datasource::TableProvider;
 use datafusion::error::{DataFusionError, Result as DatafusionResult};
 use datafusion::execution::context::SessionState;
-use datafusion::logical_expr::Operator;
-use datafusion::logical_expr::{Expr, TableProviderFilterPushDown, TableType};
+use datafusion::logical_expr::{
+    Expr, GetFieldAccess, GetIndexedField, Like, Operator, TableProviderFilterPushDown, TableType,
+};
 use datafusion::physical_plan::ExecutionPlan;
 use datafusion::scalar::ScalarValue;
 use mongodb::bson::spec::BinarySubtype;
-use mongodb::bson::{bson, Binary, Bson, Document, RawDocumentBuf};
+use mongodb::bson::{doc, Binary, Bson, DateTime, Document, RawDocumentBuf};
 use mongodb::options::{ClientOptions, FindOptions};
 use mongodb::Client;
 use mongodb::Collection;
@@ -272,7 +275,7 @@ impl TableProvider for MongoDbTableProvider {
         &self,
         _ctx: &SessionState,
         projection: Option<&Vec<usize>>,
-        _filters: &[Expr],
+        filters: &[Expr],
         limit: Option<usize>,
     ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
         // Projection.
@@ -304,13 +307,8 @@ impl TableProvider for MongoDbTableProvider {
         find_opts.limit = limit.map(|v| v as i64);
         find_opts.projection = Some(proj_doc);
 
-        let filter = match exprs_to_mdb_query(_filters) {
-            Ok(query) => query,
-            Err(err) => {
-                debug!("mdb pushdown query err: {}", err.to_string());
-                Document::new()
-            }
-        };
+        let filter = exprs_to_mdb_query(&self.schema, filters);
+        debug!(%filter, "mongo pushdown filter");
 
         let cursor = Mutex::new(Some(
             self.collection
@@ -322,43 +320,267 @@ impl TableProvider for MongoDbTableProvider {
     }
 }
 
-fn exprs_to_mdb_query(exprs: &[Expr]) -> Result<Document, ExtensionError> {
-    let mut doc = Document::new();
-    for e in exprs {
-        let expr = &e;
-        match expr {
-            Expr::BinaryExpr(val) => {
-                match val.left.as_ref() {
-                    Expr::Column(key) => match val.right.as_ref() {
-                        Expr::Literal(v) => doc.insert(
-                            key.to_string(),
-                            bson!({operator_to_mdbq(val.op)?: df_to_bson(v.clone())?}),
-                        ),
-                        _ => {
-                            continue;
-                        }
-                    },
-                    Expr::Literal(v) => match val.right.as_ref() {
-                        Expr::Column(key) => doc.insert(
-                            key.to_string(),
-                            bson!({operator_to_mdbq(val.op)?: df_to_bson(v.clone())?}),
-                        ),
-                        _ => {
-                            continue;
-                        }
-                    },
-                    _ => {
-                        continue;
-                    }
-                };
+/// Convert filters into a mongo query document.
+///
+/// Each filter is translated independently, and filters that can't be
+/// translated are skipped. Since pushdown is inexact, any skipped filters will
+/// still be applied by DataFusion after the scan.
+fn exprs_to_mdb_query(schema: &ArrowSchema, exprs: &[Expr]) -> Document {
+    let mut conds: Vec<_> = exprs
+        .iter()
+        .filter_map(|expr| expr_to_mdb_query(schema, expr))
+        .collect();
+    match conds.len() {
+        0 => Document::new(),
+        1 => conds.pop().unwrap(),
+        _ => doc! { "$and": conds },
+    }
+}
+
+/// Try to convert a single filter expression into a mongo query document.
+///
+/// Mongo's semantics differ from SQL around missing and null fields (e.g.
+/// `$ne` matches documents missing the field). Translations only need to
+/// return a superset of the matching documents.
+fn expr_to_mdb_query(schema: &ArrowSchema, expr: &Expr) -> Option<Document> {
+    match expr {
+        Expr::BinaryExpr(binary) => match binary.op {
+            Operator::And | Operator::Or => {
+                let left = expr_to_mdb_query(schema, &binary.left)?;
+                let right = expr_to_mdb_query(schema, &binary.right)?;
+                Some(doc! { operator_to_mdbq(binary.op).ok()?: [left, right] })
             }
-            _ => {
-                continue;
+            Operator::Eq
+            | Operator::NotEq
+            | Operator::Lt
+            | Operator::LtEq
+            | Operator::Gt
+            | Operator::GtEq => {
+                let (field, op, lit) = match (binary.left.as_ref(), binary.right.as_ref()) {
+                    (Expr::Literal(lit), right) => (right, binary.op.swap()?, lit),
+                    (left, Expr::Literal(lit)) => (left, binary.op, lit),
+                    _ => return None,
+                };
+                let (path, types) = typed_field_path(schema, field)?;
+                let val = literal_to_bson(lit, types)?;
+                Some(guard_by_type(
+                    path,
+                    types,
+                    doc! { operator_to_mdbq(op).ok()?: val },
+                ))
             }
-        };
+            _ => None,
+        },
+        Expr::InList(in_list) => {
+            let (path, types) = typed_field_path(schema, &in_list.expr)?;
+            let vals = in_list
+                .list
+                .iter()
+                .map(|expr| match expr {
+                    Expr::Literal(lit) => literal_to_bson(lit, types),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            let op = if in_list.negated { "$nin" } else { "$in" };
+            Some(guard_by_type(path, types, doc! { op: vals }))
+        }
+        // Both of these match regardless of the field's type. `$exists` is
+        // used since `$ne: null` excludes arrays containing a null.
+        Expr::IsNull(expr) => Some(doc! { field_path(expr)?: { "$eq": Bson::Null } }),
+        Expr::IsNotNull(expr) => Some(doc! { field_path(expr)?: { "$exists": true } }),
+        Expr::Like(like) => like_to_mdb_query(schema, like),
+        _ => None,
+    }
+}
+
+/// Convert a prefix pattern match (e.g. `name LIKE 'abc%'`) into an anchored
+/// regex.
+fn like_to_mdb_query(schema: &ArrowSchema, like: &Like) -> Option<Document> {
+    if like.negated || like.escape_char.is_some() {
+        return None;
+    }
+
+    let prefix = match like.pattern.as_ref() {
+        Expr::Literal(ScalarValue::Utf8(Some(pattern))) => pattern.strip_suffix('%')?,
+        _ => return None,
+    };
+    // Only handle a literal prefix, anything else is left to DataFusion.
+    if prefix.contains(['%', '_', '\\']) {
+        return None;
     }
 
-    Ok(doc.to_owned())
+    let (path, types) = typed_field_path(schema, &like.expr)?;
+    if types != STRING_TYPES {
+        return None;
+    }
+    let mut regex = doc! { "$regex": format!("^{}", regex::escape(prefix)) };
+    if like.case_insensitive {
+        regex.insert("$options", "i");
+    }
+
+    Some(guard_by_type(path, types, regex))
+}
+
+const BOOL_TYPES: &[&str] = &["bool"];
+const INT_TYPES: &[&str] = &["int"];
+const LONG_TYPES: &[&str] = &["int", "long"];
+const DOUBLE_TYPES: &[&str] = &["double"];
+const STRING_TYPES: &[&str] = &["string"];
+const DATE_TYPES: &[&str] = &["date"];
+
+/// Get the bson types that are read into a column of the given type without
+/// changing the value.
+///
+/// The schema is inferred from a sample, so other documents may hold a
+/// different type for the same field. Those values are converted when read
+/// (e.g. numbers into a string column, or doubles truncated into an int
+/// column), and a condition on the original value could exclude documents
+/// that match after the conversion.
+fn bson_types_for_column(datatype: &DataType) -> Option<&'static [&'static str]> {
+    Some(match datatype {
+        DataType::Boolean => BOOL_TYPES,
+        DataType::Int32 => INT_TYPES,
+        DataType::Int64 => LONG_TYPES,
+        DataType::Float64 => DOUBLE_TYPES,
+        DataType::Utf8 | DataType::LargeUtf8 => STRING_TYPES,
+        DataType::Date64 | DataType::Timestamp(TimeUnit::Millisecond, None) => DATE_TYPES,
+        _ => return None,
+    })
+}
+
+/// Only apply the condition to documents where the field holds one of the
+/// given types.
+///
+/// Documents with any other type (including arrays, which mongo matches
+/// element-wise) are returned unconditionally and left for DataFusion to
+/// filter.
+fn guard_by_type(path: String, types: &[&str], cond: Document) -> Document {
+    doc! {
+        "$or": [
+            { &path: cond },
+            { &path: { "$not": { "$type": types.to_vec() } } },
+            { &path: { "$type": "array" } },
+        ]
+    }
+}
+
+/// Get the path to a field along with the bson types that can be compared
+/// against it.
+fn typed_field_path(
+    schema: &ArrowSchema,
+    expr: &Expr,
+) -> Option<(String, &'static [&'static str])> {
+    let datatype = field_type(schema, expr)?;
+    let types = bson_types_for_column(&datatype)?;
+    Some((field_path(expr)?, types))
+}
+
+/// Get the data type of a (possibly nested) field.
+fn field_type(schema: &ArrowSchema, expr: &Expr) -> Option<DataType> {
+    match expr {
+        Expr::Column(col) => schema
+            .field_with_name(&col.name)
+            .ok()
+            .map(|f| f.data_type().clone()),
+        Expr::GetIndexedField(GetIndexedField {
+            expr,
+            field:
+                GetFieldAccess::NamedStructField {
+                    name: ScalarValue::Utf8(Some(name)),
+                },
+        }) => match field_type(schema, expr)? {
+            DataType::Struct(fields) => fields
+                .iter()
+                .find(|f| f.name() == name)
+                .map(|f| f.data_type().clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Get the path to a field for use in a query document.
+///
+/// Nested struct fields are referenced using dot notation.
+fn field_path(expr: &Expr) -> Option<String> {
+    let (path, name) = match expr {
+        Expr::Column(col) => (None, &col.name),
+        Expr::GetIndexedField(GetIndexedField {
+            expr,
+            field:
+                GetFieldAccess::NamedStructField {
+                    name: ScalarValue::Utf8(Some(name)),
+                },
+        }) => (Some(field_path(expr)?), name),
+        _ => return None,
+    };
+
+    // Names that mongo would interpret as a path or an operator can't be
+    // referenced.
+    if name.is_empty() || name.contains('.') || name.starts_with('$') {
+        return None;
+    }
+
+    Some(match path {
+        Some(path) => format!("{path}.{name}"),
+        None => name.clone(),
+    })
+}
+
+/// Convert a literal to a bson value for comparing against a field holding
+/// one of `types`.
+///
+/// Nulls and binary values (which may be object ids in mongo) are not
+/// converted. Numbers compare by value across numeric types, anything else
+/// needs to be the same type as the field.
+fn literal_to_bson(lit: &ScalarValue, types: &[&str]) -> Option<Bson> {
+    if lit.is_null() {
+        return None;
+    }
+
+    let val = match lit {
+        ScalarValue::Boolean(_)
+        | ScalarValue::Utf8(_)
+        | ScalarValue::LargeUtf8(_)
+        | ScalarValue::Int8(_)
+        | ScalarValue::Int16(_)
+        | ScalarValue::Int32(_)
+        | ScalarValue::Int64(_)
+        | ScalarValue::UInt8(_)
+        | ScalarValue::UInt16(_)
+        | ScalarValue::UInt32(_)
+        | ScalarValue::Float32(_)
+        | ScalarValue::Float64(_) => df_to_bson(lit.clone()).ok()?,
+        ScalarValue::UInt64(Some(v)) => Bson::Int64(i64::try_from(*v).ok()?),
+        // Mongo dates are inferred as Date64.
+        ScalarValue::Date64(Some(v)) => Bson::DateTime(DateTime::from_millis(*v)),
+        ScalarValue::TimestampSecond(Some(v), None) => {
+            Bson::DateTime(DateTime::from_millis(v.checked_mul(1_000)?))
+        }
+        ScalarValue::TimestampMillisecond(Some(v), None) => {
+            Bson::DateTime(DateTime::from_millis(*v))
+        }
+        // Mongo dates only have millisecond precision. Truncating would change
+        // the meaning of range comparisons, so skip anything more precise.
+        ScalarValue::TimestampMicrosecond(Some(v), None) if v % 1_000 == 0 => {
+            Bson::DateTime(DateTime::from_millis(v / 1_000))
+        }
+        ScalarValue::TimestampNanosecond(Some(v), None) if v % 1_000_000 == 0 => {
+            Bson::DateTime(DateTime::from_millis(v / 1_000_000))
+        }
+        _ => return None,
+    };
+
+    let comparable = match &val {
+        Bson::Int32(_) | Bson::Int64(_) | Bson::Double(_) => {
+            types == INT_TYPES || types == LONG_TYPES || types == DOUBLE_TYPES
+        }
+        Bson::Boolean(_) => types == BOOL_TYPES,
+        Bson::String(_) => types == STRING_TYPES,
+        Bson::DateTime(_) => types == DATE_TYPES,
+        _ => false,
+    };
+    comparable.then_some(val)
 }
 
 fn operator_to_mdbq(op: Operator) -> Result<String, ExtensionError> {
@@ -442,8 +664,116 @@ fn df_to_bson(val: ScalarValue) -> Result<Bson, ExtensionError> {
 
 #[cfg(test)]
 mod tests {
+    use datafusion::arrow::datatypes::Field;
+    use datafusion::logical_expr::{col, in_list, lit};
+
     use super::*;
 
+    fn test_schema() -> ArrowSchema {
+        ArrowSchema::new(vec![
+            Field::new("_id", DataType::Binary, true),
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Utf8, true),
+            Field::new("c", DataType::Int32, true),
+            Field::new("d", DataType::Int32, true),
+            Field::new("e", DataType::Utf8, true),
+            Field::new("f", DataType::Int32, true),
+            Field::new("g", DataType::Float64, true),
+            Field::new(
+                "h",
+                DataType::Struct(vec![Field::new("i", DataType::Utf8, true)].into()),
+                true,
+            ),
+            Field::new("j", DataType::Decimal128(38, 10), true),
+        ])
+    }
+
+    /// The condition produced by `guard_by_type`.
+    fn guarded(path: &str, types: &[&str], cond: Document) -> Document {
+        doc! {
+            "$or": [
+                { path: cond },
+                { path: { "$not": { "$type": types.to_vec() } } },
+                { path: { "$type": "array" } },
+            ]
+        }
+    }
+
+    #[test]
+    fn filters_to_query() {
+        let filters = [
+            col("a").eq(lit(1_i64)),
+            lit("x").lt_eq(col("b")),
+            col("c").is_null(),
+            in_list(col("d"), vec![lit(1_i32), lit(2_i32)], true),
+            col("e").like(lit("ab.c%")),
+            col("f").gt(lit(1_i32)).or(col("g").is_not_null()),
+        ];
+        let query = exprs_to_mdb_query(&test_schema(), &filters);
+        let expected = doc! {
+            "$and": [
+                guarded("a", &["int", "long"], doc! { "$eq": 1_i64 }),
+                guarded("b", &["string"], doc! { "$gte": "x" }),
+                { "c": { "$eq": null } },
+                guarded("d", &["int"], doc! { "$nin": [1, 2] }),
+                guarded("e", &["string"], doc! { "$regex": "^ab\\.c" }),
+                {
+                    "$or": [
+                        guarded("f", &["int"], doc! { "$gt": 1 }),
+                        { "g": { "$exists": true } },
+                    ]
+                },
+            ]
+        };
+        assert_eq!(expected, query);
+    }
+
+    #[test]
+    fn nested_field_filter() {
+        let nested = Expr::GetIndexedField(GetIndexedField::new(
+            Box::new(col("h")),
+            GetFieldAccess::NamedStructField {
+                name: ScalarValue::from("i"),
+            },
+        ));
+        let query = exprs_to_mdb_query(&test_schema(), &[nested.eq(lit("hello"))]);
+        assert_eq!(guarded("h.i", &["string"], doc! { "$eq": "hello" }), query);
+    }
+
+    #[test]
+    fn skip_unsupported_filters() {
+        let filters = [
+            // Non-prefix pattern.
+            col("a").like(lit("%abc")),
+            // Null literal.
+            col("b").eq(lit(ScalarValue::Int32(None))),
+            // Binary literal, may be an object id.
+            col("_id").eq(lit(ScalarValue::Binary(Some(vec![1, 2, 3])))),
+            // Only one side of the OR can be translated.
+            col("c").eq(lit(1_i32)).or(col("d").like(lit("%abc"))),
+            // Not a simple comparison.
+            (col("e") + lit(1_i32)).eq(lit(2_i32)),
+            // Literal type doesn't match the column.
+            col("b").eq(lit(1_i32)),
+            col("a").eq(lit("1")),
+            // Column type with no comparable bson type.
+            col("j").eq(lit(1_i64)),
+            // Unknown column.
+            col("z").eq(lit(1_i64)),
+        ];
+        assert_eq!(
+            Document::new(),
+            exprs_to_mdb_query(&test_schema(), &filters)
+        );
+
+        // Supported filters are still pushed down.
+        let filters = [col("a").like(lit("%abc")), col("g").gt(lit(1.5_f64))];
+        assert_eq!(
+            guarded("g", &["double"], doc! { "$gt": 1.5 }),
+            exprs_to_mdb_query(&test_schema(), &filters)
+        );
+    }
+
     #[test]
     fn connection_string() {
         let conn_str = MongoDbConnection::ConnectionString(
@@ -388,8 +388,14 @@ impl TableProvider for MysqlTableProvider {
         &self,
         state: &SessionState,
         input: Arc<dyn ExecutionPlan>,
-        _overwrite: bool,
+        overwrite: bool,
     ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
+        if overwrite {
+            return Err(DataFusionError::NotImplemented(
+                "Overwriting mysql tables is not supported".to_string(),
+            ));
+        }
+
         let mut values = String::new();
 
         let mut input = execute_stream(input, state.task_ctx())?;
@@ -1,19 +1,25 @@
+use crate::lake::delta::access::load_table_direct;
 use crate::native::errors::{NativeError, Result};
 use crate::native::insert::NativeTableInsertExec;
+use crate::native::transaction::NativeTransaction;
 use async_trait::async_trait;
 use datafusion::arrow::datatypes::{DataType, Schema as ArrowSchema, TimeUnit};
 use datafusion::datasource::TableProvider;
 use datafusion::error::Result as DataFusionResult;
-use datafusion::execution::context::SessionState;
+use datafusion::execution::context::{SessionContext, SessionState};
 
 use datafusion::logical_expr::{LogicalPlan, TableProviderFilterPushDown, TableType};
+use datafusion::physical_expr::expressions::{cast, Column};
 use datafusion::physical_plan::empty::EmptyExec;
+use datafusion::physical_plan::projection::ProjectionExec;
 use datafusion::physical_plan::{ExecutionPlan, Statistics};
 use datafusion::prelude::Expr;
 use datafusion_ext::metrics::ReadOnlyDataSourceMetricsExecAdapter;
 use deltalake::operations::create::CreateBuilder;
 use deltalake::operations::delete::DeleteBuilder;
+use deltalake::operations::optimize::{OptimizeBuilder, OptimizeType};
 use deltalake::operations::update::UpdateBuilder;
+use deltalake::operations::vacuum::VacuumBuilder;
 use deltalake::storage::DeltaObjectStore;
 use deltalake::{DeltaTable, DeltaTableConfig};
 use futures::StreamExt;
@@ -21,6 +27,7 @@ use object_store::path::Path as ObjectStorePath;
 use object_store::prefix::PrefixStore;
 use object_store::ObjectStore;
 use object_store_util::shared::SharedObjectStore;
+use parking_lot::Mutex;
 use protogen::metastore::types::catalog::TableEntry;
 use protogen::metastore::types::options::{
     InternalColumnDefinition, TableOptions, TableOptionsInternal,
@@ -51,6 +58,12 @@ pub struct NativeTableStorage {
     ///
     /// Arcs all the way down...
     store: SharedObjectStore,
+
+    /// Writes buffered by the session's open transaction, if any.
+    ///
+    /// A storage instance is created per session, so this is never shared
+    /// across sessions.
+    txn: Arc<Mutex<Option<NativeTransaction>>>,
 }
 
 impl NativeTableStorage {
@@ -61,6 +74,7 @@ impl NativeTableStorage {
             db_id,
             root_url,
             store: SharedObjectStore::new(store),
+            txn: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -69,6 +83,60 @@ impl NativeTableStorage {
         self.db_id
     }
 
+    /// Returns if there's an open transaction buffering writes.
+    pub fn in_transaction(&self) -> bool {
+        self.txn.lock().is_some()
+    }
+
+    /// Start buffering writes to native tables.
+    ///
+    /// Tables loaded or created after this will have their commits held in
+    /// memory until the transaction is committed. Does nothing if a
+    /// transaction is already open.
+    pub fn begin_transaction(&self) {
+        let mut txn = self.txn.lock();
+        if txn.is_none() {
+            *txn = Some(NativeTransaction::default());
+        }
+    }
+
+    /// Check that no other writer has committed to any of the tables written
+    /// to in the open transaction.
+    ///
+    /// Nothing is written. Committing the transaction performs the same check,
+    /// this allows finding conflicts before committing other changes that
+    /// are part of the same transaction.
+    pub async fn check_transaction_conflicts(&self) -> Result<()> {
+        let tables = match self.txn.lock().as_ref() {
+            Some(txn) => txn.tables(),
+            None => return Ok(()),
+        };
+        for store in tables {
+            store.check_conflicts().await?;
+        }
+        Ok(())
+    }
+
+    /// Commit all buffered writes.
+    ///
+    /// Errors if another writer committed to any of the written tables since
+    /// the transaction read them, in which case all writes are discarded.
+    pub async fn commit_transaction(&self) -> Result<()> {
+        let txn = self.txn.lock().take();
+        match txn {
+            Some(txn) => txn.commit().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Discard all buffered writes.
+    pub async fn rollback_transaction(&self) {
+        let txn = self.txn.lock().take();
+        if let Some(txn) = txn {
+            txn.rollback().await;
+        }
+    }
+
     fn table_prefix(&self, tbl_id: u32) -> String {
         format!("databases/{}/tables/{}", self.db_id, tbl_id)
     }
@@ -118,7 +186,10 @@ impl NativeTableStorage {
                 );
             }
 
-            // TODO: Partitioning
+            if !opts.partition_by.is_empty() {
+                builder = builder.with_partition_columns(opts.partition_by.clone());
+            }
+
             NativeTable::new(builder.await?)
         };
 
@@ -163,6 +234,19 @@ impl NativeTableStorage {
         Ok(opts)
     }
 
+    /// Load the delta table backing a table entry for modification.
+    ///
+    /// External delta tables are written to directly, and aren't part of any
+    /// open transaction.
+    async fn load_delta_table(&self, table: &TableEntry) -> Result<DeltaTable> {
+        match &table.options {
+            TableOptions::Delta(opts) => {
+                Ok(load_table_direct(&opts.location, opts.storage_options.clone()).await?)
+            }
+            _ => Ok(self.load_table(table).await?.delta),
+        }
+    }
+
     async fn create_delta_store_for_table(
         &self,
         table: &TableEntry,
@@ -170,19 +254,28 @@ impl NativeTableStorage {
         let prefix = self.table_prefix(table.meta.id);
 
         // Add the table prefix to the shared store and the root URL
-        let prefixed = PrefixStore::new(self.store.clone(), prefix.clone());
+        let mut prefixed: Arc<dyn ObjectStore> =
+            Arc::new(PrefixStore::new(self.store.clone(), prefix.clone()));
         let url = self.root_url.join(&prefix)?;
 
-        let delta_store = DeltaObjectStore::new(Arc::new(prefixed), url);
+        if let Some(txn) = self.txn.lock().as_mut() {
+            prefixed = txn.store_for_table(&prefix, prefixed);
+        }
+
+        let delta_store = DeltaObjectStore::new(prefixed, url);
         Ok(Arc::new(delta_store))
     }
 
+    /// Delete rows matching the expression, or all rows if no expression is
+    /// provided.
+    ///
+    /// Works with both native tables and external delta tables.
     pub async fn delete_rows_where(
         &self,
         table_entry: &TableEntry,
         where_expr: Option<Expr>,
     ) -> Result<usize> {
-        let table = self.load_table(table_entry).await?;
+        let table = NativeTable::new(self.load_delta_table(table_entry).await?);
         if let Some(where_expr) = where_expr {
             let deleted_rows = DeleteBuilder::new(table.delta.object_store(), table.delta.state)
                 .with_predicate(where_expr)
@@ -191,27 +284,41 @@ impl NativeTableStorage {
                 .num_deleted_rows;
             Ok(deleted_rows.unwrap_or_default())
         } else {
-            let mut records: usize = 0;
-            let stats = table.statistics();
-            if let Some(stats) = stats {
-                let num_rows = stats.num_rows;
-                if let Some(num_rows) = num_rows {
-                    records = num_rows;
+            // Without a predicate, delta removes every file without reading
+            // it and doesn't report the number of deleted rows. Count them
+            // up front so we can fall back to that.
+            let store = table.delta.object_store();
+            let state = table.delta.state.clone();
+            let num_rows = match table.statistics() {
+                Some(Statistics {
+                    num_rows: Some(num_rows),
+                    is_exact: true,
+                    ..
+                }) => num_rows,
+                _ => {
+                    SessionContext::new()
+                        .read_table(Arc::new(table))?
+                        .count()
+                        .await?
                 }
-            }
-            DeleteBuilder::new(table.delta.object_store(), table.delta.state).await?;
-            Ok(records)
+            };
+            let metrics = DeleteBuilder::new(store, state).await?.1;
+            Ok(metrics.num_deleted_rows.unwrap_or(num_rows))
         }
     }
 
+    /// Update rows matching the expression, or all rows if no expression is
+    /// provided.
+    ///
+    /// Works with both native tables and external delta tables.
     pub async fn update_rows_where(
         &self,
         table: &TableEntry,
         updates: Vec<(String, Expr)>,
         where_expr: Option<Expr>,
     ) -> Result<usize> {
-        let table = self.load_table(table).await?;
-        let mut builder = UpdateBuilder::new(table.delta.object_store(), table.delta.state);
+        let delta = self.load_delta_table(table).await?;
+        let mut builder = UpdateBuilder::new(delta.object_store(), delta.state);
         for update in updates.into_iter() {
             builder = builder.with_update(update.0, update.1);
         }
@@ -221,6 +328,66 @@ impl NativeTableStorage {
         let updated_rows = builder.await?.1.num_updated_rows;
         Ok(updated_rows)
     }
+
+    /// Compact the files backing a table into fewer, larger files.
+    ///
+    /// If columns are provided, the data is also z-ordered by those columns.
+    /// Returns the number of files that were compacted away.
+    pub async fn optimize_table(&self, table: &TableEntry, zorder_by: Vec<String>) -> Result<u64> {
+        if self.in_transaction() {
+            return Err(NativeError::Static(
+                "Cannot optimize tables inside a transaction",
+            ));
+        }
+
+        let table = self.load_table(table).await?;
+        let mut builder = OptimizeBuilder::new(table.delta.object_store(), table.delta.state);
+        if !zorder_by.is_empty() {
+            builder = builder.with_type(OptimizeType::ZOrder(zorder_by));
+        }
+        let metrics = builder.await?.1;
+        Ok(metrics.num_files_removed)
+    }
+
+    /// Delete files that are no longer referenced by the table.
+    ///
+    /// Only files that were removed from the table longer than the retention
+    /// period ago are deleted. The table's configured retention period
+    /// (defaulting to 7 days) is used if one isn't provided. A provided
+    /// retention period may only be shorter than the table's configured period
+    /// if `skip_retention_check` is set, since removing files that are still
+    /// being read by other queries will fail those queries.
+    ///
+    /// Returns the number of files deleted, or that would be deleted if this
+    /// is a dry run.
+    pub async fn vacuum_table(
+        &self,
+        table: &TableEntry,
+        retain_hours: Option<u64>,
+        skip_retention_check: bool,
+        dry_run: bool,
+    ) -> Result<usize> {
+        if self.in_transaction() {
+            return Err(NativeError::Static(
+                "Cannot vacuum tables inside a transaction",
+            ));
+        }
+
+        let table = self.load_table(table).await?;
+        let mut builder =
+            VacuumBuilder::new(table.delta.object_store(), table.delta.state).with_dry_run(dry_run);
+        if let Some(hours) = retain_hours {
+            let millis = i64::try_from(hours)
+                .ok()
+                .and_then(|hours| hours.checked_mul(60 * 60 * 1000))
+                .ok_or(NativeError::Static("Retention period too large"))?;
+            builder = builder
+                .with_retention_period(chrono::Duration::milliseconds(millis))
+                .with_enforce_retention_duration(!skip_retention_check);
+        }
+        let metrics = builder.await?.1;
+        Ok(metrics.files_deleted.len())
+    }
 }
 
 #[derive(Debug)]
@@ -229,7 +396,11 @@ pub struct NativeTable {
 }
 
 impl NativeTable {
-    fn new(delta: DeltaTable) -> Self {
+    /// Wrap a delta table.
+    ///
+    /// This is also used for external delta tables, allowing them to be
+    /// scanned and inserted into the same way as native tables.
+    pub fn new(delta: DeltaTable) -> Self {
         NativeTable { delta }
     }
 
@@ -237,6 +408,61 @@ impl NativeTable {
         self.delta.table_uri()
     }
 
+    /// Columns the table is partitioned by.
+    fn partition_columns(&self) -> &[String] {
+        self.delta
+            .get_metadata()
+            .map(|meta| meta.partition_columns.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Scan a partitioned table.
+    ///
+    /// Delta places partition columns at the end of the schema and wraps
+    /// string partition values in dictionaries. This projects the scan back
+    /// into the schema the table was created with.
+    async fn scan_partitioned(
+        &self,
+        session: &SessionState,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        let schema = TableProvider::schema(self);
+        let delta_schema = TableProvider::schema(&self.delta);
+
+        let indices: Vec<usize> = match projection {
+            Some(projection) => projection.clone(),
+            None => (0..schema.fields().len()).collect(),
+        };
+        let delta_projection = indices
+            .iter()
+            .map(|idx| delta_schema.index_of(schema.field(*idx).name()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let plan = self
+            .delta
+            .scan(session, Some(&delta_projection), filters, limit)
+            .await?;
+
+        let plan_schema = plan.schema();
+        let exprs = indices
+            .iter()
+            .enumerate()
+            .map(|(plan_idx, idx)| {
+                let field = schema.field(*idx);
+                let expr = cast(
+                    Arc::new(Column::new(field.name(), plan_idx)),
+                    &plan_schema,
+                    field.data_type().clone(),
+                )?;
+                Ok((expr, field.name().clone()))
+            })
+            .collect::<DataFusionResult<Vec<_>>>()?;
+
+        Ok(Arc::new(ProjectionExec::try_new(exprs, plan)?))
+    }
+
     pub fn into_table_provider(self) -> Arc<dyn TableProvider> {
         Arc::new(self)
     }
@@ -267,6 +493,18 @@ impl TableProvider for NativeTable {
     }
 
     fn schema(&self) -> Arc<ArrowSchema> {
+        if !self.partition_columns().is_empty() {
+            // Keep the column order the table was created with, see
+            // `scan_partitioned`.
+            if let Some(schema) = self
+                .delta
+                .get_schema()
+                .ok()
+                .and_then(|schema| ArrowSchema::try_from(schema).ok())
+            {
+                return Arc::new(schema);
+            }
+        }
         TableProvider::schema(&self.delta)
     }
 
@@ -289,14 +527,25 @@ impl TableProvider for NativeTable {
         filters: &[Expr],
         limit: Option<usize>,
     ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
-        let stats = self
-            .statistics()
-            .unwrap_or_default()
-            .num_rows
-            .unwrap_or_default();
-        if stats == 0 {
-            let schema = TableProvider::schema(&self.delta);
+        // Only skip the scan if we know the table is empty. Tables written
+        // outside of GlareDB (external delta tables) may not have row counts
+        // for each file, in which case the statistics aren't exact.
+        let is_empty = matches!(
+            self.statistics(),
+            Some(Statistics {
+                num_rows: Some(0),
+                is_exact: true,
+                ..
+            })
+        );
+        if is_empty {
+            let schema = TableProvider::schema(self);
             Ok(Arc::new(EmptyExec::new(false, schema)))
+        } else if !self.partition_columns().is_empty() {
+            let plan = self
+                .scan_partitioned(session, projection, filters, limit)
+                .await?;
+            Ok(Arc::new(ReadOnlyDataSourceMetricsExecAdapter::new(plan)))
         } else {
             let plan = self.delta.scan(session, projection, filters, limit).await?;
             Ok(Arc::new(ReadOnlyDataSourceMetricsExecAdapter::new(plan)))
@@ -312,7 +561,17 @@ impl TableProvider for NativeTable {
     }
 
     fn statistics(&self) -> Option<Statistics> {
-        self.delta.statistics()
+        let stats = self.delta.statistics()?;
+        if self.partition_columns().is_empty() {
+            Some(stats)
+        } else {
+            // Column statistics follow delta's column order, which differs
+            // from ours for partitioned tables.
+            Some(Statistics {
+                column_statistics: None,
+                ..stats
+            })
+        }
     }
 
     async fn insert_into(
@@ -327,7 +586,15 @@ impl TableProvider for NativeTable {
 
 #[cfg(test)]
 mod tests {
-    use datafusion::arrow::datatypes::DataType;
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Int32Array, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::datasource::TableProvider;
+    use datafusion::physical_plan::collect;
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::prelude::{col, lit, SessionContext};
     use deltalake::protocol::SaveMode;
     use object_store_util::conf::StorageConfig;
     use protogen::metastore::types::{
@@ -370,6 +637,7 @@ mod tests {
                     nullable: true,
                     arrow_type: DataType::Int32,
                 }],
+                partition_by: Vec::new(),
             }),
             tunnel_id: None,
             access_mode: SourceAccessMode::ReadOnly,
@@ -390,4 +658,276 @@ mod tests {
             .unwrap_err();
         assert_eq!(err, "Error loading table");
     }
+
+    #[tokio::test]
+    async fn test_create_table_rollback() {
+        let db_id = Uuid::new_v4();
+        let dir = tempdir().unwrap();
+        let conf = StorageConfig::Local {
+            path: dir.path().to_path_buf(),
+        };
+
+        let storage = NativeTableStorage::new(
+            db_id,
+            Url::from_file_path(dir.path()).unwrap(),
+            conf.new_object_store().unwrap(),
+        );
+
+        let entry = TableEntry {
+            meta: EntryMeta {
+                entry_type: EntryType::Table,
+                id: 12345,
+                parent: 54321,
+                name: "table_1".to_string(),
+                builtin: false,
+                external: false,
+                is_temp: false,
+            },
+            options: TableOptions::Internal(TableOptionsInternal {
+                columns: vec![InternalColumnDefinition {
+                    name: "id".to_string(),
+                    nullable: true,
+                    arrow_type: DataType::Int32,
+                }],
+                partition_by: Vec::new(),
+            }),
+            tunnel_id: None,
+            access_mode: SourceAccessMode::ReadOnly,
+        };
+
+        // Table is visible inside the transaction, but not outside of it.
+        storage.begin_transaction();
+        storage
+            .create_table(&entry, SaveMode::ErrorIfExists)
+            .await
+            .unwrap();
+        storage.load_table(&entry).await.unwrap();
+        storage.rollback_transaction().await;
+
+        storage.load_table(&entry).await.unwrap_err();
+
+        // Committed tables stick around.
+        storage.begin_transaction();
+        storage
+            .create_table(&entry, SaveMode::ErrorIfExists)
+            .await
+            .unwrap();
+        storage.commit_transaction().await.unwrap();
+
+        storage.load_table(&entry).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_optimize_and_vacuum() {
+        let db_id = Uuid::new_v4();
+        let dir = tempdir().unwrap();
+        let conf = StorageConfig::Local {
+            path: dir.path().to_path_buf(),
+        };
+
+        let storage = NativeTableStorage::new(
+            db_id,
+            Url::from_file_path(dir.path()).unwrap(),
+            conf.new_object_store().unwrap(),
+        );
+
+        let entry = TableEntry {
+            meta: EntryMeta {
+                entry_type: EntryType::Table,
+                id: 12345,
+                parent: 54321,
+                name: "table_1".to_string(),
+                builtin: false,
+                external: false,
+                is_temp: false,
+            },
+            options: TableOptions::Internal(TableOptionsInternal {
+                columns: vec![InternalColumnDefinition {
+                    name: "id".to_string(),
+                    nullable: true,
+                    arrow_type: DataType::Int32,
+                }],
+                partition_by: Vec::new(),
+            }),
+            tunnel_id: None,
+            access_mode: SourceAccessMode::ReadOnly,
+        };
+
+        storage
+            .create_table(&entry, SaveMode::ErrorIfExists)
+            .await
+            .unwrap();
+
+        // Each insert adds a file.
+        let ctx = SessionContext::new();
+        let schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "id",
+            DataType::Int32,
+            true,
+        )]));
+        for i in 0..3 {
+            let batch =
+                RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(vec![i]))])
+                    .unwrap();
+            let input =
+                Arc::new(MemoryExec::try_new(&[vec![batch]], schema.clone(), None).unwrap());
+            let table = storage.load_table(&entry).await.unwrap();
+            collect(table.insert_exec(input, false), ctx.task_ctx())
+                .await
+                .unwrap();
+        }
+
+        let removed = storage.optimize_table(&entry, Vec::new()).await.unwrap();
+        assert_eq!(3, removed);
+
+        // Compacted files are still within the default retention period.
+        let deleted = storage
+            .vacuum_table(&entry, None, false, false)
+            .await
+            .unwrap();
+        assert_eq!(0, deleted);
+
+        // Retention shorter than the table's minimum needs to skip the check.
+        storage
+            .vacuum_table(&entry, Some(0), false, false)
+            .await
+            .unwrap_err();
+
+        let would_delete = storage
+            .vacuum_table(&entry, Some(0), true, true)
+            .await
+            .unwrap();
+        assert_eq!(3, would_delete);
+        let deleted = storage
+            .vacuum_table(&entry, Some(0), true, false)
+            .await
+            .unwrap();
+        assert_eq!(would_delete, deleted);
+        let deleted = storage
+            .vacuum_table(&entry, Some(0), true, false)
+            .await
+            .unwrap();
+        assert_eq!(0, deleted);
+
+        // Data is unchanged.
+        let table = storage.load_table(&entry).await.unwrap();
+        let count = ctx
+            .read_table(table.into_table_provider())
+            .unwrap()
+            .count()
+            .await
+            .unwrap();
+        assert_eq!(3, count);
+
+        // Maintenance can't happen inside of a transaction.
+        storage.begin_transaction();
+        storage
+            .optimize_table(&entry, Vec::new())
+            .await
+            .unwrap_err();
+        storage
+            .vacuum_table(&entry, None, false, false)
+            .await
+            .unwrap_err();
+        storage.rollback_transaction().await;
+    }
+
+    #[tokio::test]
+    async fn test_partitioned_table() {
+        let db_id = Uuid::new_v4();
+        let dir = tempdir().unwrap();
+        let conf = StorageConfig::Local {
+            path: dir.path().to_path_buf(),
+        };
+
+        let storage = NativeTableStorage::new(
+            db_id,
+            Url::from_file_path(dir.path()).unwrap(),
+            conf.new_object_store().unwrap(),
+        );
+
+        let entry = TableEntry {
+            meta: EntryMeta {
+                entry_type: EntryType::Table,
+                id: 12345,
+                parent: 54321,
+                name: "table_1".to_string(),
+                builtin: false,
+                external: false,
+                is_temp: false,
+            },
+            options: TableOptions::Internal(TableOptionsInternal {
+                columns: vec![
+                    InternalColumnDefinition {
+                        name: "part".to_string(),
+                        nullable: true,
+                        arrow_type: DataType::Utf8,
+                    },
+                    InternalColumnDefinition {
+                        name: "id".to_string(),
+                        nullable: true,
+                        arrow_type: DataType::Int32,
+                    },
+                ],
+                partition_by: vec!["part".to_string()],
+            }),
+            tunnel_id: None,
+            access_mode: SourceAccessMode::ReadOnly,
+        };
+
+        storage
+            .create_table(&entry, SaveMode::ErrorIfExists)
+            .await
+            .unwrap();
+
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("part", DataType::Utf8, true),
+            Field::new("id", DataType::Int32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["a", "b", "a"])),
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+            ],
+        )
+        .unwrap();
+        let input = Arc::new(MemoryExec::try_new(&[vec![batch]], schema.clone(), None).unwrap());
+
+        let ctx = SessionContext::new();
+        let table = storage.load_table(&entry).await.unwrap();
+        collect(table.insert_exec(input, false), ctx.task_ctx())
+            .await
+            .unwrap();
+
+        // Files are written hive-style, one partition per distinct value.
+        let table = storage.load_table(&entry).await.unwrap();
+        let files = table.delta.get_files();
+        assert_eq!(2, files.len());
+        assert!(files.iter().all(|file| file.as_ref().starts_with("part=")));
+
+        // Column order is the same as when the table was created.
+        let provider = table.into_table_provider();
+        assert_eq!(schema, provider.schema());
+
+        let batches = ctx
+            .read_table(provider)
+            .unwrap()
+            .filter(col("part").eq(lit("a")))
+            .unwrap()
+            .sort(vec![col("id").sort(true, true)])
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+        let expected = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["a", "a"])),
+                Arc::new(Int32Array::from(vec![1, 3])),
+            ],
+        )
+        .unwrap();
+        assert_eq!(vec![expected], batches);
+    }
 }
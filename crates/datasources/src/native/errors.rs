@@ -3,6 +3,9 @@ pub enum NativeError {
     #[error(transparent)]
     DeltaTable(#[from] deltalake::DeltaTableError),
 
+    #[error(transparent)]
+    Delta(#[from] crate::lake::delta::errors::DeltaError),
+
     #[error(transparent)]
     ObjectStore(#[from] object_store::Error),
 
@@ -21,6 +24,9 @@ pub enum NativeError {
     #[error("Table entry not a native table: {0}")]
     NotNative(protogen::metastore::types::catalog::TableEntry),
 
+    #[error("Could not serialize access due to a concurrent commit: {0}")]
+    TransactionConflict(String),
+
     #[error("{0}")]
     Static(&'static str),
 }
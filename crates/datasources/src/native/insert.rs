@@ -4,7 +4,9 @@ use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::error::{DataFusionError, Result as DataFusionResult};
 use datafusion::execution::context::SessionState;
 use datafusion::execution::TaskContext;
-use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_expr::expressions::Column;
+use datafusion::physical_expr::{PhysicalExpr, PhysicalSortExpr};
+use datafusion::physical_plan::projection::ProjectionExec;
 use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::{
     DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, Partitioning,
@@ -113,10 +115,23 @@ impl ExecutionPlan for NativeTableInsertExec {
         // plan.
         //
         // TODO: Possibly try avoiding cloning the snapshot.
-        let builder = WriteBuilder::new(self.store.clone(), self.snapshot.clone())
+        let mut builder = WriteBuilder::new(self.store.clone(), self.snapshot.clone())
             .with_input_session_state(state)
-            .with_save_mode(self.save_mode.clone())
-            .with_input_execution_plan(self.input.clone());
+            .with_save_mode(self.save_mode.clone());
+
+        let partition_columns = self
+            .snapshot
+            .current_metadata()
+            .map(|meta| meta.partition_columns.clone())
+            .unwrap_or_default();
+        builder = if partition_columns.is_empty() {
+            builder.with_input_execution_plan(self.input.clone())
+        } else {
+            let input = partition_columns_last(self.input.clone(), &partition_columns)?;
+            builder
+                .with_partition_columns(partition_columns)
+                .with_input_execution_plan(input)
+        };
 
         let input = self.input.clone();
         let output = futures::stream::once(async move {
@@ -147,6 +162,37 @@ impl ExecutionPlan for NativeTableInsertExec {
     }
 }
 
+/// Project the input so that partition columns come after all other columns,
+/// in the order they were declared in.
+///
+/// This matches the column order delta uses for partitioned tables.
+fn partition_columns_last(
+    input: Arc<dyn ExecutionPlan>,
+    partition_columns: &[String],
+) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+    let schema = input.schema();
+    let data = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| !partition_columns.contains(field.name()))
+        .map(|(idx, field)| (idx, field.name().clone()));
+    let partitions = partition_columns
+        .iter()
+        .map(|name| Ok((schema.index_of(name)?, name.clone())))
+        .collect::<DataFusionResult<Vec<_>>>()?;
+
+    let exprs = data
+        .chain(partitions)
+        .map(|(idx, name)| {
+            let expr: Arc<dyn PhysicalExpr> = Arc::new(Column::new(&name, idx));
+            (expr, name)
+        })
+        .collect();
+
+    Ok(Arc::new(ProjectionExec::try_new(exprs, input)?))
+}
+
 impl DisplayAs for NativeTableInsertExec {
     fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match t {
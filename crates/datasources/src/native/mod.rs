@@ -4,3 +4,4 @@
 pub mod access;
 pub mod errors;
 pub mod insert;
+pub mod transaction;
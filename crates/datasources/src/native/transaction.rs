@@ -0,0 +1,794 @@
+//! Buffering of native table writes for explicit transactions.
+//!
+//! Native tables are delta tables, and a write only becomes visible once its
+//! commit file is written to the table's `_delta_log`. Inside of a transaction
+//! we hand delta-rs an object store that keeps everything written to the log in
+//! memory while data files go straight to the underlying store. Reads through
+//! that object store see the buffered log, so statements later in the
+//! transaction observe earlier writes, while other sessions don't.
+//!
+//! On commit, the buffered commit files for every table are first staged to
+//! temporary files in the underlying store, then moved into place with "put if
+//! not exists" semantics. If another writer got to a version first, the commit
+//! fails with a conflict. On rollback, the buffered log is dropped and the data
+//! files written during the transaction are deleted.
+//!
+//! Object stores can't move several files at once, so a commit touching more
+//! than one table isn't strictly atomic. If publishing one table's commit
+//! fails, the commits already published for other tables are removed again.
+//! Readers may briefly observe those commits before they're removed.
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::{
+    Error as ObjectStoreError, GetOptions, GetResult, GetResultPayload, ListResult, MultipartId,
+    ObjectMeta, ObjectStore, Result,
+};
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+use tokio::io::AsyncWrite;
+use tracing::{debug, warn};
+
+use super::errors::{NativeError, Result as NativeResult};
+
+/// Directory containing a delta table's transaction log.
+const DELTA_LOG_DIR: &str = "_delta_log";
+
+/// File in the delta log pointing to the latest checkpoint.
+const LAST_CHECKPOINT_FILE: &str = "_last_checkpoint";
+
+/// Writes to native tables buffered by a session's open transaction.
+#[derive(Debug, Default)]
+pub struct NativeTransaction {
+    /// Buffering object stores keyed by table prefix.
+    ///
+    /// Ordered so that tables are always committed in the same order.
+    tables: BTreeMap<String, Arc<TransactionObjectStore>>,
+}
+
+impl NativeTransaction {
+    /// Get the buffering store for a table, wrapping `inner` if this is the
+    /// first time the table is accessed in this transaction.
+    pub fn store_for_table(
+        &mut self,
+        prefix: &str,
+        inner: Arc<dyn ObjectStore>,
+    ) -> Arc<TransactionObjectStore> {
+        self.tables
+            .entry(prefix.to_string())
+            .or_insert_with(|| Arc::new(TransactionObjectStore::new(inner)))
+            .clone()
+    }
+
+    /// Get the buffering stores for all tables accessed in this transaction.
+    pub fn tables(&self) -> Vec<Arc<TransactionObjectStore>> {
+        self.tables.values().cloned().collect()
+    }
+
+    /// Write out all buffered commits.
+    ///
+    /// All tables are checked for conflicting commits before anything is
+    /// written. Commits for every table are then staged before any of them
+    /// are published. If anything fails, the transaction is rolled back,
+    /// including removing commits already published for other tables.
+    pub async fn commit(self) -> NativeResult<()> {
+        let mut conflict = None;
+        for (prefix, store) in &self.tables {
+            if let Err(e) = store.check_conflicts().await {
+                warn!(%prefix, %e, "conflicting native table commit, rolling back");
+                conflict = Some(e);
+                break;
+            }
+        }
+        if let Some(e) = conflict {
+            self.rollback().await;
+            return Err(e);
+        }
+
+        let mut staged = Vec::with_capacity(self.tables.len());
+        let mut failed = None;
+        for (prefix, store) in &self.tables {
+            debug!(%prefix, "staging buffered native table writes");
+            match store.stage().await {
+                Ok(commit) => staged.push((prefix, store, commit)),
+                Err(e) => {
+                    warn!(%prefix, %e, "failed to stage native table commit, rolling back");
+                    failed = Some(e);
+                    break;
+                }
+            }
+        }
+
+        let mut published = Vec::with_capacity(staged.len());
+        if failed.is_none() {
+            for (prefix, store, commit) in &staged {
+                debug!(%prefix, "publishing staged native table commit");
+                match store.publish(commit).await {
+                    Ok(()) => published.push((*store, commit)),
+                    Err(e) => {
+                        warn!(%prefix, %e, "failed to publish native table commit, rolling back");
+                        failed = Some(e);
+                        break;
+                    }
+                }
+            }
+        }
+
+        if let Some(e) = failed {
+            for (store, commit) in published {
+                store.unpublish(commit).await;
+            }
+            for (_, store, commit) in staged {
+                store.discard(commit).await;
+            }
+            self.rollback().await;
+            return Err(e);
+        }
+
+        for (_, store, commit) in staged {
+            store.finish(commit).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Discard all buffered commits, removing any data files that were
+    /// written.
+    pub async fn rollback(self) {
+        for (prefix, store) in self.tables {
+            debug!(%prefix, "rolling back buffered native table writes");
+            store.rollback().await;
+        }
+    }
+}
+
+/// An object buffered in memory.
+#[derive(Debug, Clone)]
+struct BufferedObject {
+    bytes: Bytes,
+    last_modified: DateTime<Utc>,
+}
+
+impl BufferedObject {
+    fn meta(&self, location: &Path) -> ObjectMeta {
+        ObjectMeta {
+            location: location.clone(),
+            last_modified: self.last_modified,
+            size: self.bytes.len(),
+            e_tag: None,
+        }
+    }
+}
+
+/// A table's buffered log after being staged in the underlying store.
+#[derive(Debug, Default)]
+struct StagedCommit {
+    /// Checkpoint files written to the underlying store.
+    checkpoints: Vec<Path>,
+    /// Temporary files holding commits, along with the location each should
+    /// be moved to. In version order.
+    commits: Vec<(Path, Path)>,
+    /// Pointer to the latest checkpoint, written after all commits are
+    /// published.
+    last_checkpoint: Option<(Path, Bytes)>,
+}
+
+#[derive(Debug, Default)]
+struct BufferState {
+    /// Files written to the delta log during the transaction.
+    ///
+    /// Ordered so that commit files are written out in version order.
+    log: BTreeMap<Path, BufferedObject>,
+    /// Data files written to the underlying store during the transaction.
+    written: Vec<Path>,
+}
+
+/// Object store for a single delta table that buffers writes to the delta log
+/// until the transaction is committed.
+///
+/// Paths are relative to the root of the table.
+#[derive(Debug)]
+pub struct TransactionObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    state: Mutex<BufferState>,
+}
+
+impl TransactionObjectStore {
+    fn new(inner: Arc<dyn ObjectStore>) -> Self {
+        TransactionObjectStore {
+            inner,
+            state: Mutex::new(BufferState::default()),
+        }
+    }
+
+    fn get_buffered(&self, location: &Path) -> Option<BufferedObject> {
+        self.state.lock().log.get(location).cloned()
+    }
+
+    /// Check that none of the buffered commit files have been written to the
+    /// underlying store by someone else.
+    pub async fn check_conflicts(&self) -> NativeResult<()> {
+        let commits: Vec<_> = self
+            .state
+            .lock()
+            .log
+            .keys()
+            .filter(|location| is_commit_file(location))
+            .cloned()
+            .collect();
+
+        for location in commits {
+            match self.inner.head(&location).await {
+                Ok(_) => return Err(NativeError::TransactionConflict(location.to_string())),
+                Err(ObjectStoreError::NotFound { .. }) => (),
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stage the buffered log in the underlying store without making any of
+    /// it visible.
+    ///
+    /// Checkpoints are written as is since nothing references them until the
+    /// commit for their version exists. Commit files are written to temporary
+    /// files. If anything fails, everything staged so far is removed.
+    async fn stage(&self) -> NativeResult<StagedCommit> {
+        let log = std::mem::take(&mut self.state.lock().log);
+
+        let mut staged = StagedCommit::default();
+        for (location, obj) in log {
+            let result = if is_commit_file(&location) {
+                // Same as what delta-rs does for its commits, write to a temp
+                // file then move into place if there's not already a commit for
+                // this version.
+                let tmp = Path::from(format!(
+                    "{DELTA_LOG_DIR}/_commit_{:016x}.json.tmp",
+                    rand::random::<u64>()
+                ));
+                let result = self.inner.put(&tmp, obj.bytes).await;
+                staged.commits.push((tmp, location));
+                result
+            } else if location.filename() == Some(LAST_CHECKPOINT_FILE) {
+                staged.last_checkpoint = Some((location, obj.bytes));
+                Ok(())
+            } else {
+                let result = self.inner.put(&location, obj.bytes).await;
+                staged.checkpoints.push(location);
+                result
+            };
+
+            if let Err(e) = result {
+                self.discard(staged).await;
+                return Err(e.into());
+            }
+        }
+
+        Ok(staged)
+    }
+
+    /// Move staged commit files into place, making the versions visible.
+    ///
+    /// Errors with a conflict if a commit for one of the versions already
+    /// exists. Commits moved into place before an error are removed again.
+    async fn publish(&self, staged: &StagedCommit) -> NativeResult<()> {
+        for (i, (tmp, location)) in staged.commits.iter().enumerate() {
+            if let Err(e) = self.inner.rename_if_not_exists(tmp, location).await {
+                self.remove_commits(&staged.commits[..i]).await;
+                return Err(match e {
+                    ObjectStoreError::AlreadyExists { .. } => {
+                        NativeError::TransactionConflict(location.to_string())
+                    }
+                    other => other.into(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove commits that were published, used when publishing another
+    /// table's commits fails.
+    async fn unpublish(&self, staged: &StagedCommit) {
+        self.remove_commits(&staged.commits).await;
+    }
+
+    async fn remove_commits(&self, commits: &[(Path, Path)]) {
+        // Newest first so the table never points to a version with a missing
+        // parent.
+        for (_, location) in commits.iter().rev() {
+            if let Err(e) = self.inner.delete(location).await {
+                warn!(%location, %e, "failed to remove commit after failed transaction");
+            }
+        }
+    }
+
+    /// Remove anything that was staged and not published.
+    async fn discard(&self, staged: StagedCommit) {
+        let staged_files = staged
+            .commits
+            .into_iter()
+            .map(|(tmp, _)| tmp)
+            .chain(staged.checkpoints);
+        for location in staged_files {
+            match self.inner.delete(&location).await {
+                Ok(_) | Err(ObjectStoreError::NotFound { .. }) => (),
+                Err(e) => warn!(%location, %e, "failed to remove staged file"),
+            }
+        }
+    }
+
+    /// Write out the checkpoint pointer once all commits are published.
+    ///
+    /// The pointer must never reference a checkpoint for a version that
+    /// didn't get committed.
+    async fn finish(&self, staged: StagedCommit) -> NativeResult<()> {
+        if let Some((location, bytes)) = staged.last_checkpoint {
+            self.inner.put(&location, bytes).await?;
+        }
+        Ok(())
+    }
+
+    async fn rollback(&self) {
+        let written = {
+            let mut state = self.state.lock();
+            state.log.clear();
+            std::mem::take(&mut state.written)
+        };
+
+        for location in written {
+            match self.inner.delete(&location).await {
+                Ok(_) | Err(ObjectStoreError::NotFound { .. }) => (),
+                Err(e) => warn!(%location, %e, "failed to remove data file on rollback"),
+            }
+        }
+    }
+
+    fn already_exists(location: &Path) -> ObjectStoreError {
+        ObjectStoreError::AlreadyExists {
+            path: location.to_string(),
+            source: "object already exists".into(),
+        }
+    }
+
+    fn not_found(location: &Path) -> ObjectStoreError {
+        ObjectStoreError::NotFound {
+            path: location.to_string(),
+            source: "object not found".into(),
+        }
+    }
+}
+
+impl fmt::Display for TransactionObjectStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TransactionObjectStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for TransactionObjectStore {
+    async fn put(&self, location: &Path, bytes: Bytes) -> Result<()> {
+        if is_log_file(location) {
+            self.state.lock().log.insert(
+                location.clone(),
+                BufferedObject {
+                    bytes,
+                    last_modified: Utc::now(),
+                },
+            );
+            return Ok(());
+        }
+
+        self.inner.put(location, bytes).await?;
+        self.state.lock().written.push(location.clone());
+        Ok(())
+    }
+
+    async fn put_multipart(
+        &self,
+        location: &Path,
+    ) -> Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+        let upload = self.inner.put_multipart(location).await?;
+        self.state.lock().written.push(location.clone());
+        Ok(upload)
+    }
+
+    async fn abort_multipart(&self, location: &Path, multipart_id: &MultipartId) -> Result<()> {
+        self.inner.abort_multipart(location, multipart_id).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let obj = match self.get_buffered(location) {
+            Some(obj) => obj,
+            None => return self.inner.get_opts(location, options).await,
+        };
+
+        let meta = obj.meta(location);
+        let range = options.range.unwrap_or(0..obj.bytes.len());
+        if range.end > obj.bytes.len() || range.start > range.end {
+            return Err(ObjectStoreError::Generic {
+                store: "TransactionObjectStore",
+                source: format!("invalid range {range:?} for {location}").into(),
+            });
+        }
+        let bytes = obj.bytes.slice(range.clone());
+
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(stream::once(async move { Ok(bytes) }).boxed()),
+            meta,
+            range,
+        })
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        match self.get_buffered(location) {
+            Some(obj) if range.end <= obj.bytes.len() && range.start <= range.end => {
+                Ok(obj.bytes.slice(range))
+            }
+            Some(_) => Err(ObjectStoreError::Generic {
+                store: "TransactionObjectStore",
+                source: format!("invalid range {range:?} for {location}").into(),
+            }),
+            None => self.inner.get_range(location, range).await,
+        }
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        match self.get_buffered(location) {
+            Some(obj) => Ok(obj.meta(location)),
+            None => self.inner.head(location).await,
+        }
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        if self.state.lock().log.remove(location).is_some() {
+            return Ok(());
+        }
+        self.inner.delete(location).await
+    }
+
+    async fn list(&self, prefix: Option<&Path>) -> Result<BoxStream<'_, Result<ObjectMeta>>> {
+        let buffered: Vec<_> = self
+            .state
+            .lock()
+            .log
+            .iter()
+            .filter(|(location, _)| prefix.map(|p| location.prefix_matches(p)).unwrap_or(true))
+            .map(|(location, obj)| Ok(obj.meta(location)))
+            .collect();
+
+        let inner = self.inner.list(prefix).await?;
+        Ok(inner.chain(stream::iter(buffered)).boxed())
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let mut result = self.inner.list_with_delimiter(prefix).await?;
+        let root = prefix.cloned().unwrap_or_default();
+
+        let state = self.state.lock();
+        for (location, obj) in &state.log {
+            let mut parts = match location.prefix_match(&root) {
+                Some(parts) => parts,
+                None => continue,
+            };
+            let first = match parts.next() {
+                Some(part) => part,
+                None => continue,
+            };
+            if parts.next().is_none() {
+                result.objects.push(obj.meta(location));
+            } else {
+                let dir = root.child(first);
+                if !result.common_prefixes.contains(&dir) {
+                    result.common_prefixes.push(dir);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        match self.get_buffered(from) {
+            Some(obj) => self.put(to, obj.bytes).await,
+            None => self.inner.copy(from, to).await,
+        }
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        if !is_log_file(to) {
+            return self.inner.copy_if_not_exists(from, to).await;
+        }
+
+        // Commits to the log need to check both the buffered log and the
+        // underlying store. This is what detects conflicts with commits from
+        // other sessions made before this transaction's commit.
+        if self.get_buffered(to).is_some() {
+            return Err(Self::already_exists(to));
+        }
+        match self.inner.head(to).await {
+            Ok(_) => return Err(Self::already_exists(to)),
+            Err(ObjectStoreError::NotFound { .. }) => (),
+            Err(e) => return Err(e),
+        }
+
+        match self.get_buffered(from) {
+            Some(obj) => self.put(to, obj.bytes).await,
+            None => Err(Self::not_found(from)),
+        }
+    }
+}
+
+fn is_log_file(location: &Path) -> bool {
+    location.as_ref().starts_with(DELTA_LOG_DIR)
+}
+
+/// Check if a path points to a versioned commit file in the delta log, e.g.
+/// `_delta_log/00000000000000000001.json`.
+fn is_commit_file(location: &Path) -> bool {
+    if !is_log_file(location) {
+        return false;
+    }
+    match location
+        .filename()
+        .and_then(|name| name.strip_suffix(".json"))
+    {
+        Some(version) => !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+
+    fn commit_path(version: i64) -> Path {
+        Path::from(format!("{DELTA_LOG_DIR}/{version:020}.json"))
+    }
+
+    #[test]
+    fn commit_file_detection() {
+        assert!(is_commit_file(&commit_path(0)));
+        assert!(is_commit_file(&commit_path(12)));
+        assert!(!is_commit_file(&Path::from("_delta_log/_last_checkpoint")));
+        assert!(!is_commit_file(&Path::from(
+            "_delta_log/_commit_abc.json.tmp"
+        )));
+        assert!(!is_commit_file(&Path::from("part-0001.parquet")));
+    }
+
+    #[tokio::test]
+    async fn buffered_until_commit() {
+        let inner: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let mut txn = NativeTransaction::default();
+        let store = txn.store_for_table("tables/1", inner.clone());
+
+        store
+            .put(&Path::from("part-0001.parquet"), Bytes::from("data"))
+            .await
+            .unwrap();
+        store
+            .put(&commit_path(0), Bytes::from("commit"))
+            .await
+            .unwrap();
+
+        // Visible through the transaction store, not the underlying store.
+        store.head(&commit_path(0)).await.unwrap();
+        inner.head(&commit_path(0)).await.unwrap_err();
+        let listed: Vec<_> = store
+            .list(Some(&Path::from(DELTA_LOG_DIR)))
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(1, listed.len());
+
+        txn.commit().await.unwrap();
+        let got = inner
+            .get(&commit_path(0))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(Bytes::from("commit"), got);
+    }
+
+    #[tokio::test]
+    async fn rollback_removes_data_files() {
+        let inner: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let mut txn = NativeTransaction::default();
+        let store = txn.store_for_table("tables/1", inner.clone());
+
+        store
+            .put(&Path::from("part-0001.parquet"), Bytes::from("data"))
+            .await
+            .unwrap();
+        store
+            .put(&commit_path(0), Bytes::from("commit"))
+            .await
+            .unwrap();
+
+        txn.rollback().await;
+        inner
+            .head(&Path::from("part-0001.parquet"))
+            .await
+            .unwrap_err();
+        inner.head(&commit_path(0)).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn commit_conflict() {
+        let inner: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let mut txn = NativeTransaction::default();
+        let store = txn.store_for_table("tables/1", inner.clone());
+
+        store
+            .put(&commit_path(1), Bytes::from("ours"))
+            .await
+            .unwrap();
+        // Someone else commits the same version.
+        inner
+            .put(&commit_path(1), Bytes::from("theirs"))
+            .await
+            .unwrap();
+
+        let err = txn.commit().await.unwrap_err();
+        assert!(matches!(err, NativeError::TransactionConflict(_)));
+        let got = inner
+            .get(&commit_path(1))
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(Bytes::from("theirs"), got);
+    }
+
+    #[tokio::test]
+    async fn failed_commit_removes_checkpoint() {
+        let inner: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let mut txn = NativeTransaction::default();
+        let store = txn.store_for_table("tables/1", inner.clone());
+
+        let checkpoint = Path::from(format!(
+            "{DELTA_LOG_DIR}/00000000000000000001.checkpoint.parquet"
+        ));
+        let last_checkpoint = Path::from(format!("{DELTA_LOG_DIR}/{LAST_CHECKPOINT_FILE}"));
+        store
+            .put(&commit_path(1), Bytes::from("ours"))
+            .await
+            .unwrap();
+        store
+            .put(&checkpoint, Bytes::from("checkpoint"))
+            .await
+            .unwrap();
+        store
+            .put(&last_checkpoint, Bytes::from("pointer"))
+            .await
+            .unwrap();
+
+        // Someone else commits the same version after the conflict check.
+        store.check_conflicts().await.unwrap();
+        let staged = store.stage().await.unwrap();
+        inner
+            .put(&commit_path(1), Bytes::from("theirs"))
+            .await
+            .unwrap();
+
+        let err = store.publish(&staged).await.unwrap_err();
+        assert!(matches!(err, NativeError::TransactionConflict(_)));
+        store.discard(staged).await;
+        inner.head(&checkpoint).await.unwrap_err();
+        inner.head(&last_checkpoint).await.unwrap_err();
+    }
+
+    /// Store that fails to move commit files into place.
+    #[derive(Debug)]
+    struct FailingCommitStore {
+        inner: InMemory,
+    }
+
+    impl fmt::Display for FailingCommitStore {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "FailingCommitStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for FailingCommitStore {
+        async fn put(&self, location: &Path, bytes: Bytes) -> Result<()> {
+            self.inner.put(location, bytes).await
+        }
+
+        async fn put_multipart(
+            &self,
+            location: &Path,
+        ) -> Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+            self.inner.put_multipart(location).await
+        }
+
+        async fn abort_multipart(&self, location: &Path, multipart_id: &MultipartId) -> Result<()> {
+            self.inner.abort_multipart(location, multipart_id).await
+        }
+
+        async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+            self.inner.get_opts(location, options).await
+        }
+
+        async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+            self.inner.head(location).await
+        }
+
+        async fn delete(&self, location: &Path) -> Result<()> {
+            self.inner.delete(location).await
+        }
+
+        async fn list(&self, prefix: Option<&Path>) -> Result<BoxStream<'_, Result<ObjectMeta>>> {
+            self.inner.list(prefix).await
+        }
+
+        async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(&self, _from: &Path, to: &Path) -> Result<()> {
+            Err(ObjectStoreError::Generic {
+                store: "FailingCommitStore",
+                source: format!("failed to write {to}").into(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_commit_rolls_back_other_tables() {
+        let first: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let second: Arc<dyn ObjectStore> = Arc::new(FailingCommitStore {
+            inner: InMemory::new(),
+        });
+        let mut txn = NativeTransaction::default();
+
+        for (prefix, inner) in [("tables/1", &first), ("tables/2", &second)] {
+            let store = txn.store_for_table(prefix, inner.clone());
+            store
+                .put(&Path::from("part-0001.parquet"), Bytes::from("data"))
+                .await
+                .unwrap();
+            store
+                .put(&commit_path(1), Bytes::from("commit"))
+                .await
+                .unwrap();
+        }
+
+        // Tables are committed in order, so the first table's commit is
+        // published before the second fails.
+        txn.commit().await.unwrap_err();
+
+        for inner in [&first, &second] {
+            inner.head(&commit_path(1)).await.unwrap_err();
+            inner
+                .head(&Path::from("part-0001.parquet"))
+                .await
+                .unwrap_err();
+            // No staged commits left behind.
+            let listed: Vec<_> = inner
+                .list(Some(&Path::from(DELTA_LOG_DIR)))
+                .await
+                .unwrap()
+                .collect()
+                .await;
+            assert!(listed.is_empty(), "unexpected files: {listed:?}");
+        }
+    }
+}
@@ -32,6 +32,12 @@ pub enum ObjectStoreSourceError {
 
     #[error("This file type is not supported: {0}")]
     NotSupportFileType(String),
+    #[error("Object '{location}' is missing partition column '{column}'")]
+    MissingPartitionColumn { column: String, location: String },
+
+    #[error("Partition column '{0}' conflicts with a column in the file schema")]
+    PartitionColumnConflict(String),
+
     #[error("{0}")]
     InvalidHttpStatus(String),
     #[error("{0}")]
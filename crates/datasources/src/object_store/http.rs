@@ -16,6 +16,7 @@ use crate::{
     object_store::{errors::ObjectStoreSourceError, Result},
 };
 
+use super::partition::PartitionColumns;
 use super::{MultiSourceTableProvider, ObjStoreAccess, ObjStoreTableProvider};
 
 #[derive(Debug, Clone)]
@@ -108,7 +109,9 @@ impl ObjStoreAccess for HttpStoreAccess {
         state: &SessionState,
         file_format: Arc<dyn FileFormat>,
         locations: Vec<DatasourceUrl>,
+        _partition_columns: &PartitionColumns,
     ) -> Result<Arc<dyn TableProvider>> {
+        // Http objects have no directories to discover partitions from.
         let store = self.create_store()?;
         let mut providers: Vec<Arc<dyn TableProvider>> = Vec::new();
 
@@ -131,13 +134,15 @@ impl ObjStoreAccess for HttpStoreAccess {
 
         let base_url = self.base_url()?;
 
-        let prov = Arc::new(ObjStoreTableProvider {
-            store: store.clone(),
-            arrow_schema: arrow_schema.clone(),
-            file_format: file_format.clone(),
+        let prov = Arc::new(ObjStoreTableProvider::try_new(
+            store.clone(),
             base_url,
+            file_format.clone(),
+            arrow_schema.clone(),
             objects,
-        });
+            &[],
+            &PartitionColumns::Disabled,
+        )?);
         providers.push(prov);
 
         for loc in locations {
@@ -168,12 +173,14 @@ impl HttpStoreAccess {
             .await
             .map_err(|_| DataFusionError::Plan("unable to list globbed".to_string()))?;
 
-        Ok(ObjStoreTableProvider {
+        ObjStoreTableProvider::try_new(
             store,
-            arrow_schema,
             base_url,
-            objects,
             file_format,
-        })
+            arrow_schema,
+            objects,
+            &[],
+            &PartitionColumns::Disabled,
+        )
     }
 }
@@ -1,5 +1,5 @@
 use std::fmt::Display;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -11,7 +11,7 @@ use object_store::path::Path as ObjectStorePath;
 use object_store::{ObjectMeta, ObjectStore};
 
 use super::errors::Result;
-use super::ObjStoreAccess;
+use super::{ObjStoreAccess, GLOB_CHARS};
 
 #[derive(Debug, Clone)]
 pub struct LocalStoreAccess;
@@ -48,6 +48,20 @@ impl ObjStoreAccess for LocalStoreAccess {
             .map_err(super::errors::ObjectStoreSourceError::ObjectStorePath)
     }
 
+    /// Relative patterns are resolved against the current directory.
+    fn glob_base(&self, pattern: &str) -> Result<ObjectStorePath> {
+        let base = match pattern.split_once(GLOB_CHARS) {
+            Some((prefix, _)) => match prefix.rfind('/') {
+                Some(0) => "/",
+                Some(idx) => &prefix[..idx],
+                None => ".",
+            },
+            None => pattern,
+        };
+        let base = resolve_path(Path::new(base))?;
+        self.path(base.to_string_lossy().as_ref())
+    }
+
     /// Given relative paths and all other stuff, it's much simpler to use
     /// `glob_with` from the crate to get metas for all objects.
     async fn list_globbed(
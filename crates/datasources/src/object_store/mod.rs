@@ -1,10 +1,10 @@
 use std::any::Any;
-use std::fmt::{Debug, Display};
+use std::fmt::{self, Debug, Display};
+use std::str::FromStr;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use datafusion::arrow::datatypes::SchemaRef;
-use datafusion::common::FileType;
+use datafusion::arrow::datatypes::{Field, Schema, SchemaRef};
 use datafusion::datasource::file_format::FileFormat;
 use datafusion::datasource::listing::PartitionedFile;
 use datafusion::datasource::physical_plan::FileScanConfig;
@@ -13,7 +13,7 @@ use datafusion::error::{DataFusionError, Result as DatafusionResult};
 use datafusion::execution::context::SessionState;
 use datafusion::execution::object_store::ObjectStoreUrl;
 use datafusion::execution::runtime_env::RuntimeEnv;
-use datafusion::logical_expr::TableType;
+use datafusion::logical_expr::{TableProviderFilterPushDown, TableType};
 use datafusion::physical_plan::union::UnionExec;
 use datafusion::physical_plan::ExecutionPlan;
 use datafusion::prelude::Expr;
@@ -31,6 +31,9 @@ use crate::common::url::DatasourceUrl;
 use crate::object_store::gcs::GcsStoreAccess;
 use crate::object_store::generic::GenericStoreAccess;
 use crate::object_store::local::LocalStoreAccess;
+use crate::object_store::partition::{
+    is_partition_filter, prune_files, HivePartitions, PartitionColumns,
+};
 use crate::object_store::s3::S3StoreAccess;
 
 pub mod errors;
@@ -38,8 +41,13 @@ pub mod gcs;
 pub mod generic;
 pub mod http;
 pub mod local;
+pub mod orc;
+pub mod partition;
 pub mod s3;
 
+/// Characters that start a glob in an object location.
+const GLOB_CHARS: [char; 5] = ['*', '?', '!', '[', ']'];
+
 pub struct MultiSourceTableProvider {
     sources: Vec<Arc<dyn TableProvider>>,
 }
@@ -125,13 +133,28 @@ pub trait ObjStoreAccess: Debug + Display + Send + Sync {
     /// Gets the object store path.
     fn path(&self, location: &str) -> Result<ObjectStorePath>;
 
+    /// Gets the path that objects matching the pattern are listed under.
+    ///
+    /// For glob patterns this is the directory containing the first glob
+    /// character. Otherwise the pattern is the path of a single object.
+    fn glob_base(&self, pattern: &str) -> Result<ObjectStorePath> {
+        match pattern.split_once(GLOB_CHARS) {
+            Some((prefix, _)) => Ok(prefix
+                .rsplit_once(object_store::path::DELIMITER)
+                .map(|(base, _)| self.path(base))
+                .transpose()?
+                .unwrap_or_default()),
+            None => self.path(pattern),
+        }
+    }
+
     /// Gets a list of objects that match the glob pattern.
     async fn list_globbed(
         &self,
         store: &Arc<dyn ObjectStore>,
         pattern: &str,
     ) -> Result<Vec<ObjectMeta>> {
-        if let Some((prefix, _)) = pattern.split_once(['*', '?', '!', '[', ']']) {
+        if let Some((prefix, _)) = pattern.split_once(GLOB_CHARS) {
             // This pattern might actually be a "glob" pattern.
             //
             // NOTE: Break the path at "/" (delimeter) since `object_store` will
@@ -185,9 +208,11 @@ pub trait ObjStoreAccess: Debug + Display + Send + Sync {
         state: &SessionState,
         file_format: Arc<dyn FileFormat>,
         locations: Vec<DatasourceUrl>,
+        partition_columns: &PartitionColumns,
     ) -> Result<Arc<dyn TableProvider>> {
         let store = self.create_store()?;
         let mut objects = Vec::new();
+        let mut bases = Vec::with_capacity(locations.len());
         for loc in locations {
             let list = self
                 .list_globbed(&store, &loc.path())
@@ -200,20 +225,23 @@ pub trait ObjStoreAccess: Debug + Display + Send + Sync {
                 return Err(ObjectStoreSourceError::ObjectStorePath(e));
             }
 
+            bases.push(self.glob_base(&loc.path())?);
             objects.push(list);
         }
         let objects = objects.into_iter().flatten().collect::<Vec<_>>();
 
-        let arrow_schema = file_format.infer_schema(state, &store, &objects).await?;
+        let file_schema = file_format.infer_schema(state, &store, &objects).await?;
         let base_url = self.base_url()?;
 
-        Ok(Arc::new(ObjStoreTableProvider {
+        Ok(Arc::new(ObjStoreTableProvider::try_new(
             store,
-            arrow_schema,
             base_url,
-            objects,
             file_format,
-        }))
+            file_schema,
+            objects,
+            &bases,
+            partition_columns,
+        )?))
     }
 }
 
@@ -245,36 +273,114 @@ impl ObjStoreAccessor {
             .await
     }
 
+    /// Gets the path that objects matching the pattern are listed under.
+    pub fn glob_base(&self, pattern: impl AsRef<str>) -> Result<ObjectStorePath> {
+        self.access.glob_base(pattern.as_ref())
+    }
+
     /// Takes all the objects and creates the table provider from the accesor.
+    ///
+    /// `bases` are the paths the objects were listed under, see
+    /// [`ObjStoreAccessor::glob_base`].
     pub async fn into_table_provider(
         self,
         state: &SessionState,
         file_format: Arc<dyn FileFormat>,
         objects: Vec<ObjectMeta>,
+        bases: &[ObjectStorePath],
+        partition_columns: &PartitionColumns,
     ) -> Result<Arc<dyn TableProvider>> {
         let store = self.store;
-        let arrow_schema = file_format.infer_schema(state, &store, &objects).await?;
+        let file_schema = file_format.infer_schema(state, &store, &objects).await?;
         let base_url = self.access.base_url()?;
 
-        Ok(Arc::new(ObjStoreTableProvider {
+        Ok(Arc::new(ObjStoreTableProvider::try_new(
             store,
-            arrow_schema,
             base_url,
-            objects,
             file_format,
-        }))
+            file_schema,
+            objects,
+            bases,
+            partition_columns,
+        )?))
     }
 }
 
 #[derive(Debug)]
 pub struct ObjStoreTableProvider {
     store: Arc<dyn ObjectStore>,
+    /// Schema of the table, the file schema followed by partition columns.
     arrow_schema: SchemaRef,
+    file_schema: SchemaRef,
     base_url: ObjectStoreUrl,
-    objects: Vec<ObjectMeta>,
+    files: Vec<PartitionedFile>,
+    partition_cols: Vec<Field>,
     file_format: Arc<dyn FileFormat>,
 }
 
+impl ObjStoreTableProvider {
+    /// Create a new table provider for the objects, discovering any hive
+    /// style partition columns from their paths below `bases`.
+    pub fn try_new(
+        store: Arc<dyn ObjectStore>,
+        base_url: ObjectStoreUrl,
+        file_format: Arc<dyn FileFormat>,
+        file_schema: SchemaRef,
+        objects: Vec<ObjectMeta>,
+        bases: &[ObjectStorePath],
+        partition_columns: &PartitionColumns,
+    ) -> Result<Self> {
+        let mut partitions = HivePartitions::discover(&objects, bases, partition_columns)?;
+
+        // Files may already contain the partition columns. Declared columns
+        // must not conflict, but inferred ones can just be skipped.
+        let conflicts: Vec<_> = partitions
+            .fields
+            .iter()
+            .map(|f| file_schema.field_with_name(f.name()).is_ok())
+            .collect();
+        if let Some(idx) = conflicts.iter().position(|c| *c) {
+            if matches!(partition_columns, PartitionColumns::Declared(_)) {
+                return Err(ObjectStoreSourceError::PartitionColumnConflict(
+                    partitions.fields[idx].name().clone(),
+                ));
+            }
+            fn keep<T>(vals: Vec<T>, conflicts: &[bool]) -> Vec<T> {
+                vals.into_iter()
+                    .zip(conflicts)
+                    .filter_map(|(v, conflict)| (!conflict).then_some(v))
+                    .collect()
+            }
+            partitions.fields = keep(partitions.fields, &conflicts);
+            partitions.values = partitions
+                .values
+                .into_iter()
+                .map(|vals| keep(vals, &conflicts))
+                .collect();
+        }
+
+        let partition_cols = partitions.fields.clone();
+        let arrow_schema = Arc::new(Schema::new(
+            file_schema
+                .fields()
+                .iter()
+                .map(|f| f.as_ref().clone())
+                .chain(partition_cols.iter().cloned())
+                .collect::<Vec<_>>(),
+        ));
+
+        Ok(ObjStoreTableProvider {
+            store,
+            arrow_schema,
+            file_schema,
+            base_url,
+            files: partitions.into_files(objects),
+            partition_cols,
+            file_format,
+        })
+    }
+}
+
 #[async_trait]
 impl TableProvider for ObjStoreTableProvider {
     fn as_any(&self) -> &dyn Any {
@@ -289,6 +395,18 @@ impl TableProvider for ObjStoreTableProvider {
         TableType::View
     }
 
+    fn supports_filter_pushdown(
+        &self,
+        filter: &Expr,
+    ) -> DatafusionResult<TableProviderFilterPushDown> {
+        // Only filters on partition columns are used, for pruning objects.
+        if is_partition_filter(filter, &self.partition_cols) {
+            Ok(TableProviderFilterPushDown::Inexact)
+        } else {
+            Ok(TableProviderFilterPushDown::Unsupported)
+        }
+    }
+
     async fn scan(
         &self,
         ctx: &SessionState,
@@ -296,32 +414,47 @@ impl TableProvider for ObjStoreTableProvider {
         filters: &[Expr],
         limit: Option<usize>,
     ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
+        let files = prune_files(self.files.clone(), &self.partition_cols, filters, ctx)?;
+
         // See datafusion's `ListingTable::list_files_for_scan`.
-        let files = futures::stream::iter(&self.objects)
-            .map(|object| async {
-                let file: PartitionedFile = object.clone().into();
+        let files = futures::stream::iter(files)
+            .map(|file| async {
                 let stats = self
                     .file_format
-                    .infer_stats(ctx, &self.store, self.schema(), object)
+                    .infer_stats(
+                        ctx,
+                        &self.store,
+                        self.file_schema.clone(),
+                        &file.object_meta,
+                    )
                     .await?;
                 Ok((file, stats))
             })
             .boxed()
             .buffered(ctx.config_options().execution.meta_fetch_concurrency);
-        let (files, statistics) = get_statistics_with_limit(files, self.schema(), limit).await?;
+        let (files, statistics) =
+            get_statistics_with_limit(files, self.file_schema.clone(), limit).await?;
 
         let config = FileScanConfig {
             object_store_url: self.base_url.clone(),
-            file_schema: self.arrow_schema.clone(),
+            file_schema: self.file_schema.clone(),
             file_groups: vec![files],
             statistics,
             projection: projection.cloned(),
             limit,
-            table_partition_cols: Vec::new(),
+            table_partition_cols: self.partition_cols.clone(),
             output_ordering: Vec::new(),
             infinite_source: false,
         };
-        let filters = exprs_to_phys_exprs(filters, ctx, &self.arrow_schema)?;
+
+        // Partition filters have already been applied above, and the file
+        // format only knows about columns in the files.
+        let filters: Vec<_> = filters
+            .iter()
+            .filter(|expr| !is_partition_filter(expr, &self.partition_cols))
+            .cloned()
+            .collect();
+        let filters = exprs_to_phys_exprs(&filters, ctx, &self.file_schema)?;
 
         // We register the store at scan time so that it can be used by the
         // exec plan.
@@ -338,11 +471,59 @@ impl TableProvider for ObjStoreTableProvider {
     }
 }
 
+/// File types that can be read from object storage.
+///
+/// This is a superset of DataFusion's file types, including formats that
+/// DataFusion can't read natively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileType {
+    Arrow,
+    Avro,
+    Csv,
+    Json,
+    Orc,
+    Parquet,
+}
+
+impl FileType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileType::Arrow => "arrow",
+            FileType::Avro => "avro",
+            FileType::Csv => "csv",
+            FileType::Json => "json",
+            FileType::Orc => "orc",
+            FileType::Parquet => "parquet",
+        }
+    }
+}
+
+impl Display for FileType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for FileType {
+    type Err = ObjectStoreSourceError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.to_lowercase().as_str() {
+            "arrow" => FileType::Arrow,
+            "avro" => FileType::Avro,
+            "csv" => FileType::Csv,
+            "json" | "ndjson" => FileType::Json,
+            "orc" => FileType::Orc,
+            "parquet" => FileType::Parquet,
+            _ => return Err(ObjectStoreSourceError::NotSupportFileType(s.to_string())),
+        })
+    }
+}
+
 pub fn file_type_from_path(path: &ObjectStorePath) -> Result<FileType> {
     path.extension()
         .ok_or(ObjectStoreSourceError::NoFileExtension)?
         .parse()
-        .map_err(ObjectStoreSourceError::DataFusion)
 }
 
 pub fn init_session_registry<'a>(
@@ -403,7 +584,8 @@ pub fn init_session_registry<'a>(
             | TableOptions::MongoDb(_)
             | TableOptions::Snowflake(_)
             | TableOptions::SqlServer(_)
-            | TableOptions::Clickhouse(_) => continue,
+            | TableOptions::Clickhouse(_)
+            | TableOptions::Sqlite(_) => continue,
         };
 
         let base_url = access.base_url()?;
@@ -413,3 +595,75 @@ pub fn init_session_registry<'a>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use datafusion::datasource::file_format::csv::CsvFormat;
+    use datafusion::logical_expr::{col, lit};
+    use datafusion::physical_plan::displayable;
+    use datafusion::prelude::SessionContext;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn scan_prunes_partitions() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../testdata/partitioned/events")
+            .canonicalize()
+            .unwrap();
+        let location = DatasourceUrl::try_new(format!("{}/*/*/*.csv", dir.display())).unwrap();
+
+        let state = SessionContext::new().state();
+        let provider = LocalStoreAccess
+            .create_table_provider(
+                &state,
+                Arc::new(CsvFormat::default()),
+                vec![location],
+                &PartitionColumns::Infer,
+            )
+            .await
+            .unwrap();
+
+        let filter = col("year")
+            .eq(lit(2023_i64))
+            .and(col("month").gt(lit(11_i64)));
+        let plan = provider.scan(&state, None, &[filter], None).await.unwrap();
+        let plan = displayable(plan.as_ref()).indent(true).to_string();
+
+        assert!(plan.contains("year=2023/month=12/part.csv"), "{plan}");
+        assert!(!plan.contains("month=11"), "{plan}");
+        assert!(!plan.contains("year=2024"), "{plan}");
+    }
+
+    #[tokio::test]
+    async fn partition_dirs_in_location_keep_schema() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../testdata/partitioned/events")
+            .canonicalize()
+            .unwrap();
+
+        let state = SessionContext::new().state();
+        for pattern in ["year=2024/month=1/part.csv", "year=2024/month=1/*.csv"] {
+            let location = DatasourceUrl::try_new(format!("{}/{pattern}", dir.display())).unwrap();
+            let provider = LocalStoreAccess
+                .create_table_provider(
+                    &state,
+                    Arc::new(CsvFormat::default()),
+                    vec![location],
+                    &PartitionColumns::Infer,
+                )
+                .await
+                .unwrap();
+
+            let fields: Vec<_> = provider
+                .schema()
+                .fields()
+                .iter()
+                .map(|f| f.name().clone())
+                .collect();
+            assert_eq!(vec!["id", "kind"], fields, "{pattern}");
+        }
+    }
+}
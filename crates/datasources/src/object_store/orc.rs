@@ -0,0 +1,410 @@
+//! ORC file format.
+//!
+//! DataFusion doesn't support reading ORC files, so this provides a
+//! `FileFormat` implementation which can be used with any of our object
+//! store accesses.
+use std::any::Any;
+use std::fmt;
+use std::future::Future;
+use std::io::{self, SeekFrom};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use datafusion::arrow::array::{new_null_array, ArrayRef};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use datafusion::datasource::file_format::FileFormat;
+use datafusion::datasource::physical_plan::{
+    FileMeta, FileOpenFuture, FileOpener, FileScanConfig, FileStream,
+};
+use datafusion::error::{DataFusionError, Result as DatafusionResult};
+use datafusion::execution::context::{SessionState, TaskContext};
+use datafusion::physical_expr::{PhysicalExpr, PhysicalSortExpr};
+use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+    Statistics,
+};
+use futures::future::BoxFuture;
+use futures::{ready, StreamExt};
+use object_store::path::Path;
+use object_store::{ObjectMeta, ObjectStore};
+use orc_rust::arrow_reader::{create_arrow_schema, Cursor};
+use orc_rust::async_arrow_reader::ArrowStreamReader;
+use orc_rust::reader::Reader;
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+
+/// Minimum number of bytes to fetch per request when reading ORC files.
+///
+/// The reader issues many small reads (e.g. for the footer and each stream in
+/// a stripe), so reading ahead avoids a request per read.
+const MIN_FETCH_SIZE: usize = 1024 * 1024;
+
+/// Read ORC files.
+///
+/// ORC metadata lives at the end of the file. Files are read with ranged
+/// requests, so only the metadata and the projected columns are fetched.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OrcFormat;
+
+#[async_trait]
+impl FileFormat for OrcFormat {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn infer_schema(
+        &self,
+        _state: &SessionState,
+        store: &Arc<dyn ObjectStore>,
+        objects: &[ObjectMeta],
+    ) -> DatafusionResult<SchemaRef> {
+        let mut schemas = Vec::with_capacity(objects.len());
+        for object in objects {
+            let reader = Reader::new_async(ObjectStoreReader::new(store.clone(), object))
+                .await
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            schemas.push(create_arrow_schema(&reader));
+        }
+
+        Ok(Arc::new(Schema::try_merge(schemas)?))
+    }
+
+    async fn infer_stats(
+        &self,
+        _state: &SessionState,
+        _store: &Arc<dyn ObjectStore>,
+        _table_schema: SchemaRef,
+        _object: &ObjectMeta,
+    ) -> DatafusionResult<Statistics> {
+        Ok(Statistics::default())
+    }
+
+    async fn create_physical_plan(
+        &self,
+        _state: &SessionState,
+        conf: FileScanConfig,
+        _filters: Option<&Arc<dyn PhysicalExpr>>,
+    ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(OrcExec::new(conf)?))
+    }
+}
+
+/// Execution plan for scanning ORC files.
+#[derive(Debug)]
+pub struct OrcExec {
+    base_config: FileScanConfig,
+    /// Output schema, including any partition columns.
+    projected_schema: SchemaRef,
+    /// Projected columns that are read from the files.
+    file_projected_schema: SchemaRef,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl OrcExec {
+    pub fn new(base_config: FileScanConfig) -> DatafusionResult<Self> {
+        let (projected_schema, _, _) = base_config.project();
+
+        // Partition columns come after the file columns in the table schema,
+        // and are appended to each batch by the `FileStream`.
+        let num_file_cols = base_config.file_schema.fields().len();
+        let file_projection: Vec<_> = match &base_config.projection {
+            Some(projection) => projection
+                .iter()
+                .copied()
+                .filter(|idx| *idx < num_file_cols)
+                .collect(),
+            None => (0..num_file_cols).collect(),
+        };
+        let file_projected_schema = Arc::new(base_config.file_schema.project(&file_projection)?);
+
+        Ok(OrcExec {
+            base_config,
+            projected_schema,
+            file_projected_schema,
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+}
+
+impl ExecutionPlan for OrcExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.projected_schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(self.base_config.file_groups.len())
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        Vec::new()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DatafusionResult<SendableRecordBatchStream> {
+        let store = context
+            .runtime_env()
+            .object_store(&self.base_config.object_store_url)?;
+
+        let opener = OrcOpener {
+            store,
+            projected_schema: self.file_projected_schema.clone(),
+            batch_size: context.session_config().batch_size(),
+        };
+
+        let stream = FileStream::new(&self.base_config, partition, opener, &self.metrics)?;
+        Ok(Box::pin(stream))
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+impl DisplayAs for OrcExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OrcExec: files={}", self.base_config.file_groups.len())
+    }
+}
+
+/// Opens individual ORC files for a `FileStream`.
+///
+/// Batches only contain the projected file columns. The `FileStream` appends
+/// the partition values for each file.
+struct OrcOpener {
+    store: Arc<dyn ObjectStore>,
+    projected_schema: SchemaRef,
+    batch_size: usize,
+}
+
+impl FileOpener for OrcOpener {
+    fn open(&self, file_meta: FileMeta) -> DatafusionResult<FileOpenFuture> {
+        let store = self.store.clone();
+        let schema = self.projected_schema.clone();
+        let batch_size = self.batch_size;
+
+        Ok(Box::pin(async move {
+            let reader = ObjectStoreReader::new(store, &file_meta.object_meta);
+            let stream = open_stream(reader, &schema, batch_size)
+                .await
+                .map_err(|e| DataFusionError::External(Box::new(e)))?
+                .map(move |batch| {
+                    let batch = batch.map_err(|e| ArrowError::ExternalError(Box::new(e)))?;
+                    adapt_batch(batch, &schema)
+                });
+            Ok(stream.boxed())
+        }))
+    }
+}
+
+/// Open a stream reading only the columns in the (projected) table schema.
+///
+/// Columns in the schema that aren't in the file are skipped, and are filled
+/// with nulls when adapting the batch. If none of the columns are in the
+/// file, the first column is read to get the number of rows.
+async fn open_stream(
+    reader: ObjectStoreReader,
+    schema: &SchemaRef,
+    batch_size: usize,
+) -> Result<ArrowStreamReader<ObjectStoreReader>, orc_rust::error::Error> {
+    let reader = Reader::new_async(reader).await?;
+    let file_schema = create_arrow_schema(&reader);
+
+    let mut fields: Vec<&str> = schema
+        .fields()
+        .iter()
+        .map(|f| f.name().as_str())
+        .filter(|name| file_schema.index_of(name).is_ok())
+        .collect();
+    if fields.is_empty() {
+        fields.extend(file_schema.fields().first().map(|f| f.name().as_str()));
+    }
+
+    let cursor = Cursor::new(reader, &fields)?;
+    Ok(ArrowStreamReader::new(cursor, Some(batch_size)))
+}
+
+/// Reads an object using ranged requests.
+struct ObjectStoreReader {
+    store: Arc<dyn ObjectStore>,
+    location: Path,
+    size: u64,
+    /// Current position in the object.
+    pos: u64,
+    /// Most recently fetched bytes, starting at `buf_start`.
+    buf: Bytes,
+    buf_start: u64,
+    /// In progress request, starting at `pos`.
+    fetch: Option<BoxFuture<'static, object_store::Result<Bytes>>>,
+}
+
+impl ObjectStoreReader {
+    fn new(store: Arc<dyn ObjectStore>, meta: &ObjectMeta) -> Self {
+        ObjectStoreReader {
+            store,
+            location: meta.location.clone(),
+            size: meta.size as u64,
+            pos: 0,
+            buf: Bytes::new(),
+            buf_start: 0,
+            fetch: None,
+        }
+    }
+}
+
+impl AsyncRead for ObjectStoreReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        out: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        loop {
+            if this.pos >= this.size || out.remaining() == 0 {
+                return Poll::Ready(Ok(()));
+            }
+
+            let buf_end = this.buf_start + this.buf.len() as u64;
+            if this.pos >= this.buf_start && this.pos < buf_end {
+                let start = (this.pos - this.buf_start) as usize;
+                let len = out.remaining().min(this.buf.len() - start);
+                out.put_slice(&this.buf[start..start + len]);
+                this.pos += len as u64;
+                return Poll::Ready(Ok(()));
+            }
+
+            match this.fetch.as_mut() {
+                Some(fetch) => {
+                    let result = ready!(fetch.as_mut().poll(cx));
+                    this.fetch = None;
+                    let bytes = result.map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+                    if bytes.is_empty() {
+                        return Poll::Ready(Ok(()));
+                    }
+                    this.buf = bytes;
+                    this.buf_start = this.pos;
+                }
+                None => {
+                    let start = this.pos as usize;
+                    let end = (this.size as usize).min(start + out.remaining().max(MIN_FETCH_SIZE));
+                    let store = this.store.clone();
+                    let location = this.location.clone();
+                    this.fetch = Some(Box::pin(async move {
+                        store.get_range(&location, start..end).await
+                    }));
+                }
+            }
+        }
+    }
+}
+
+impl AsyncSeek for ObjectStoreReader {
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        let pos = match position {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+        };
+        let pos = pos.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative position",
+            )
+        })?;
+
+        if pos != self.pos {
+            // Any in progress request was for the old position.
+            self.fetch = None;
+            self.pos = pos;
+        }
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Poll::Ready(Ok(self.pos))
+    }
+}
+
+/// Adapt a batch read from a single file to the (projected) table schema.
+///
+/// The table schema is merged from all files being scanned, so columns
+/// missing from this file are filled with nulls, and columns with a
+/// different type are cast.
+fn adapt_batch(batch: RecordBatch, schema: &SchemaRef) -> Result<RecordBatch, ArrowError> {
+    let file_schema = batch.schema();
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| match file_schema.index_of(field.name()) {
+            Ok(idx) => {
+                let col = batch.column(idx);
+                if col.data_type() == field.data_type() {
+                    Ok(col.clone())
+                } else {
+                    cast(col, field.data_type())
+                }
+            }
+            Err(_) => Ok(new_null_array(field.data_type(), batch.num_rows())),
+        })
+        .collect::<Result<Vec<ArrayRef>, _>>()?;
+
+    let opts = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+    RecordBatch::try_new_with_options(schema.clone(), columns, &opts)
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::{Int32Array, Int64Array, StringArray};
+    use datafusion::arrow::datatypes::{DataType, Field};
+
+    use super::*;
+
+    #[test]
+    fn adapt_batch_to_table_schema() {
+        let batch = RecordBatch::try_from_iter(vec![
+            ("b", Arc::new(StringArray::from(vec!["x", "y"])) as ArrayRef),
+            ("a", Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef),
+        ])
+        .unwrap();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("c", DataType::Utf8, true),
+        ]));
+
+        let out = adapt_batch(batch, &schema).unwrap();
+        assert_eq!(schema, out.schema());
+        assert_eq!(
+            &Int64Array::from(vec![1, 2]),
+            out.column(0).as_any().downcast_ref::<Int64Array>().unwrap()
+        );
+        assert_eq!(2, out.column(1).null_count());
+    }
+}
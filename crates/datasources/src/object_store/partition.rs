@@ -0,0 +1,424 @@
+//! Hive style partition discovery.
+//!
+//! Objects laid out like `events/year=2023/month=01/data.parquet` are exposed
+//! with `year` and `month` columns, with the values for each object parsed
+//! from its path. Filters on only those columns are used to prune objects
+//! before they're scanned.
+//!
+//! Partition columns are only inferred from directories below the location
+//! the user provided. Reading `events/year=2023/*/*.parquet` exposes a `month`
+//! column, but not `year`.
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::cast::as_boolean_array;
+use datafusion::datasource::listing::PartitionedFile;
+use datafusion::error::Result as DatafusionResult;
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::utils::expr_to_columns;
+use datafusion::prelude::Expr;
+use datafusion::scalar::ScalarValue;
+use object_store::path::Path as ObjectStorePath;
+use object_store::ObjectMeta;
+use percent_encoding::percent_decode_str;
+
+use super::errors::{ObjectStoreSourceError, Result};
+use crate::common::exprs_to_phys_exprs;
+
+/// Directory value Hive uses for null partition values.
+const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// How partition columns should be resolved for a set of objects.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PartitionColumns {
+    /// Use the `key=value` directories below the read location shared by all
+    /// objects, if any.
+    #[default]
+    Infer,
+    /// Use exactly these columns, erroring if an object is missing one.
+    Declared(Vec<String>),
+    /// Don't expose any partition columns.
+    Disabled,
+}
+
+impl From<Vec<String>> for PartitionColumns {
+    /// No declared columns means partitions should be inferred.
+    fn from(cols: Vec<String>) -> Self {
+        if cols.is_empty() {
+            PartitionColumns::Infer
+        } else {
+            PartitionColumns::Declared(cols)
+        }
+    }
+}
+
+/// Partition columns discovered for a list of objects.
+#[derive(Debug, Clone, Default)]
+pub struct HivePartitions {
+    pub fields: Vec<Field>,
+    /// Partition values for each object, in the order of `fields`.
+    pub values: Vec<Vec<ScalarValue>>,
+}
+
+impl HivePartitions {
+    fn empty(num_objects: usize) -> Self {
+        HivePartitions {
+            fields: Vec::new(),
+            values: vec![Vec::new(); num_objects],
+        }
+    }
+
+    /// Discover partition columns and values for objects.
+    ///
+    /// `bases` are the paths the objects were listed under (see
+    /// `ObjStoreAccess::glob_base`). Inferred columns only come from
+    /// directories below these, while declared columns may be anywhere in an
+    /// object's path.
+    pub fn discover(
+        objects: &[ObjectMeta],
+        bases: &[ObjectStorePath],
+        cols: &PartitionColumns,
+    ) -> Result<Self> {
+        let parsed: Vec<_> = objects
+            .iter()
+            .map(|obj| {
+                let dirs = match cols {
+                    PartitionColumns::Infer => dirs_below_base(&obj.location, bases),
+                    _ => object_dirs(&obj.location),
+                };
+                partitions_from_dirs(dirs)
+            })
+            .collect();
+
+        let names = match cols {
+            PartitionColumns::Disabled => return Ok(Self::empty(objects.len())),
+            PartitionColumns::Declared(cols) => cols.clone(),
+            PartitionColumns::Infer => match infer_names(&parsed) {
+                Some(names) => names,
+                None => return Ok(Self::empty(objects.len())),
+            },
+        };
+
+        // Raw values for each column, across all objects.
+        let mut raw: Vec<Vec<Option<&str>>> = vec![Vec::with_capacity(objects.len()); names.len()];
+        for (obj, parts) in objects.iter().zip(&parsed) {
+            for (col, name) in raw.iter_mut().zip(&names) {
+                let (_, val) = parts.iter().find(|(key, _)| key == name).ok_or_else(|| {
+                    ObjectStoreSourceError::MissingPartitionColumn {
+                        column: name.clone(),
+                        location: obj.location.to_string(),
+                    }
+                })?;
+                col.push(match val.as_str() {
+                    "" | HIVE_DEFAULT_PARTITION => None,
+                    val => Some(val),
+                });
+            }
+        }
+
+        let mut partitions = Self::empty(objects.len());
+        for (name, col) in names.into_iter().zip(raw) {
+            let datatype = infer_type(&col);
+            for (obj_values, val) in partitions.values.iter_mut().zip(col) {
+                obj_values.push(parse_value(val, &datatype));
+            }
+            partitions.fields.push(Field::new(name, datatype, true));
+        }
+
+        Ok(partitions)
+    }
+
+    /// Convert objects into partitioned files, attaching the discovered
+    /// partition values.
+    pub fn into_files(self, objects: Vec<ObjectMeta>) -> Vec<PartitionedFile> {
+        objects
+            .into_iter()
+            .zip(self.values)
+            .map(|(obj, partition_values)| {
+                let mut file: PartitionedFile = obj.into();
+                file.partition_values = partition_values;
+                file
+            })
+            .collect()
+    }
+}
+
+/// Get the directories an object is in.
+fn object_dirs(location: &ObjectStorePath) -> &str {
+    match location.as_ref().rsplit_once(object_store::path::DELIMITER) {
+        Some((dirs, _file)) => dirs,
+        None => "",
+    }
+}
+
+/// Get the directories an object is in below the closest base path
+/// containing it.
+///
+/// Returns an empty string if the object isn't below any of the base paths.
+fn dirs_below_base<'a>(location: &'a ObjectStorePath, bases: &[ObjectStorePath]) -> &'a str {
+    let dirs = object_dirs(location);
+    bases
+        .iter()
+        .filter_map(|base| {
+            let base = base.as_ref();
+            if base.is_empty() {
+                return Some(dirs);
+            }
+            match dirs.strip_prefix(base)? {
+                "" => Some(""),
+                rest => rest.strip_prefix(object_store::path::DELIMITER),
+            }
+        })
+        .min_by_key(|rest| rest.len())
+        .unwrap_or_default()
+}
+
+/// Get the `key=value` pairs from directories.
+///
+/// Hive percent-encodes special characters in keys and values, so these are
+/// decoded.
+fn partitions_from_dirs(dirs: &str) -> Vec<(String, String)> {
+    if dirs.is_empty() {
+        return Vec::new();
+    }
+    dirs.split(object_store::path::DELIMITER)
+        .filter_map(|part| {
+            let (key, val) = part.split_once('=')?;
+            if key.is_empty() {
+                return None;
+            }
+            let decode = |s| percent_decode_str(s).decode_utf8_lossy().into_owned();
+            Some((decode(key), decode(val)))
+        })
+        .collect()
+}
+
+/// Infer partition column names, only returning names if every object has
+/// the same keys in the same order.
+fn infer_names(parsed: &[Vec<(String, String)>]) -> Option<Vec<String>> {
+    let first: Vec<String> = parsed.first()?.iter().map(|(key, _)| key.clone()).collect();
+    if first.is_empty() {
+        return None;
+    }
+
+    let consistent = parsed
+        .iter()
+        .all(|parts| parts.iter().map(|(key, _)| key).eq(first.iter()));
+    let unique = first.iter().collect::<HashSet<_>>().len() == first.len();
+
+    (consistent && unique).then_some(first)
+}
+
+/// Pick the narrowest type that all non-null values can be parsed as.
+fn infer_type(values: &[Option<&str>]) -> DataType {
+    let mut values = values.iter().flatten();
+    if values.clone().all(|v| v.parse::<i64>().is_ok()) {
+        DataType::Int64
+    } else if values.clone().all(|v| v.parse::<f64>().is_ok()) {
+        DataType::Float64
+    } else if values.all(|v| v.parse::<bool>().is_ok()) {
+        DataType::Boolean
+    } else {
+        DataType::Utf8
+    }
+}
+
+/// Parse a value that's already been checked with `infer_type`.
+fn parse_value(val: Option<&str>, datatype: &DataType) -> ScalarValue {
+    match datatype {
+        DataType::Int64 => ScalarValue::Int64(val.and_then(|v| v.parse().ok())),
+        DataType::Float64 => ScalarValue::Float64(val.and_then(|v| v.parse().ok())),
+        DataType::Boolean => ScalarValue::Boolean(val.and_then(|v| v.parse().ok())),
+        _ => ScalarValue::Utf8(val.map(|v| v.to_string())),
+    }
+}
+
+/// Check if a filter only references partition columns, and so can be
+/// evaluated against partition values alone.
+pub fn is_partition_filter(expr: &Expr, fields: &[Field]) -> bool {
+    let mut cols = HashSet::new();
+    if expr_to_columns(expr, &mut cols).is_err() {
+        return false;
+    }
+    !cols.is_empty()
+        && cols
+            .iter()
+            .all(|col| fields.iter().any(|f| f.name() == &col.name))
+}
+
+/// Remove files whose partition values can't satisfy the filters.
+///
+/// Filters referencing non-partition columns are ignored.
+pub fn prune_files(
+    files: Vec<PartitionedFile>,
+    fields: &[Field],
+    filters: &[Expr],
+    state: &SessionState,
+) -> DatafusionResult<Vec<PartitionedFile>> {
+    let filters: Vec<_> = filters
+        .iter()
+        .filter(|expr| is_partition_filter(expr, fields))
+        .cloned()
+        .collect();
+    if filters.is_empty() || files.is_empty() {
+        return Ok(files);
+    }
+
+    let schema = Arc::new(Schema::new(fields.to_vec()));
+    let predicate = match exprs_to_phys_exprs(&filters, state, &schema)? {
+        Some(predicate) => predicate,
+        None => return Ok(files),
+    };
+
+    let columns = (0..fields.len())
+        .map(|idx| {
+            ScalarValue::iter_to_array(files.iter().map(|f| f.partition_values[idx].clone()))
+        })
+        .collect::<DatafusionResult<Vec<_>>>()?;
+    let batch = RecordBatch::try_new(schema, columns)?;
+
+    let mask = predicate.evaluate(&batch)?.into_array(batch.num_rows());
+    let mask = as_boolean_array(&mask)?;
+
+    Ok(files
+        .into_iter()
+        .zip(mask.iter())
+        .filter_map(|(file, keep)| keep.unwrap_or(false).then_some(file))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn object(path: &str) -> ObjectMeta {
+        ObjectMeta {
+            location: ObjectStorePath::parse(path).unwrap(),
+            last_modified: Utc::now(),
+            size: 0,
+            e_tag: None,
+        }
+    }
+
+    #[test]
+    fn infer_partitions() {
+        let objects = vec![
+            object("events/year=2023/month=01/a.parquet"),
+            object("events/year=2023/month=02/b.parquet"),
+            object("events/year=2024/month=__HIVE_DEFAULT_PARTITION__/c.parquet"),
+        ];
+        let bases = vec![ObjectStorePath::from("events")];
+
+        let partitions =
+            HivePartitions::discover(&objects, &bases, &PartitionColumns::Infer).unwrap();
+        assert_eq!(
+            vec![
+                Field::new("year", DataType::Int64, true),
+                Field::new("month", DataType::Int64, true),
+            ],
+            partitions.fields
+        );
+        assert_eq!(
+            vec![ScalarValue::Int64(Some(2024)), ScalarValue::Int64(None)],
+            partitions.values[2]
+        );
+    }
+
+    #[test]
+    fn infer_inconsistent_partitions() {
+        let objects = vec![
+            object("events/year=2023/month=01/a.parquet"),
+            object("events/year=2023/b.parquet"),
+        ];
+        let bases = vec![ObjectStorePath::from("events")];
+
+        let partitions =
+            HivePartitions::discover(&objects, &bases, &PartitionColumns::Infer).unwrap();
+        assert!(partitions.fields.is_empty());
+        assert_eq!(2, partitions.values.len());
+    }
+
+    #[test]
+    fn declared_partitions() {
+        let objects = vec![
+            object("events/region=us-east/year=2023/a.parquet"),
+            object("events/region=eu/year=2023/b.parquet"),
+        ];
+        let bases = vec![ObjectStorePath::from("events")];
+
+        let cols = PartitionColumns::Declared(vec!["region".to_string()]);
+        let partitions = HivePartitions::discover(&objects, &bases, &cols).unwrap();
+        assert_eq!(
+            vec![Field::new("region", DataType::Utf8, true)],
+            partitions.fields
+        );
+
+        let cols = PartitionColumns::Declared(vec!["day".to_string()]);
+        HivePartitions::discover(&objects, &bases, &cols).unwrap_err();
+    }
+
+    #[test]
+    fn infer_partitions_below_base() {
+        let objects = vec![
+            object("events/year=2023/month=01/a.parquet"),
+            object("events/year=2023/month=02/b.parquet"),
+        ];
+
+        // Directories in the base path aren't partitions.
+        let bases = vec![ObjectStorePath::from("events/year=2023")];
+        let partitions =
+            HivePartitions::discover(&objects, &bases, &PartitionColumns::Infer).unwrap();
+        assert_eq!(
+            vec![Field::new("month", DataType::Int64, true)],
+            partitions.fields
+        );
+
+        // Objects read directly have no inferred partitions.
+        let bases: Vec<_> = objects.iter().map(|obj| obj.location.clone()).collect();
+        let partitions =
+            HivePartitions::discover(&objects, &bases, &PartitionColumns::Infer).unwrap();
+        assert!(partitions.fields.is_empty());
+
+        // But they can still be declared.
+        let cols = PartitionColumns::Declared(vec!["year".to_string()]);
+        let partitions = HivePartitions::discover(&objects, &bases, &cols).unwrap();
+        assert_eq!(
+            vec![Field::new("year", DataType::Int64, true)],
+            partitions.fields
+        );
+    }
+
+    #[test]
+    fn infer_unpartitioned() {
+        let objects = vec![object("data/2023/a.parquet"), object("data/2024/b.parquet")];
+        let bases = vec![ObjectStorePath::from("data")];
+
+        let partitions =
+            HivePartitions::discover(&objects, &bases, &PartitionColumns::Infer).unwrap();
+        assert!(partitions.fields.is_empty());
+        assert_eq!(2, partitions.values.len());
+    }
+
+    #[test]
+    fn decode_partition_values() {
+        let objects = vec![
+            object("events/city=New%20York/a.parquet"),
+            object("events/city=S%C3%A3o%20Paulo/b.parquet"),
+        ];
+        let bases = vec![ObjectStorePath::from("events")];
+
+        let partitions =
+            HivePartitions::discover(&objects, &bases, &PartitionColumns::Infer).unwrap();
+        assert_eq!(
+            vec![
+                vec![ScalarValue::Utf8(Some("New York".to_string()))],
+                vec![ScalarValue::Utf8(Some("São Paulo".to_string()))],
+            ],
+            partitions.values
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use datafusion::arrow::array::{Array, ArrayRef, AsArray};
+use datafusion::arrow::datatypes::{
+    DataType, Date32Type, Decimal128Type, Float32Type, Float64Type, Int16Type, Int32Type,
+    Int64Type, Schema as ArrowSchema, Time64NanosecondType, TimeUnit, TimestampNanosecondType,
+};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    execute_stream, DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning,
+    SendableRecordBatchStream, Statistics,
+};
+use datafusion_ext::metrics::DataSourceMetricsStreamAdapter;
+use futures::{pin_mut, StreamExt};
+use tokio_postgres::binary_copy::BinaryCopyInWriter;
+use tokio_postgres::types::{ToSql, Type as PostgresType};
+
+use crate::common::util::{create_count_record_batch, COUNT_SCHEMA};
+
+use super::errors::{PostgresError, Result};
+use super::{PostgresAccess, Str};
+
+/// Insert into a postgres table using the binary copy protocol.
+///
+/// Input batches are streamed to postgres as they're produced. The insert
+/// happens on a dedicated connection inside of a transaction so that a failed
+/// insert (or an overwrite) doesn't leave the table partially written.
+#[derive(Debug)]
+pub struct PostgresInsertExec {
+    input: Arc<dyn ExecutionPlan>,
+    access: PostgresAccess,
+    schema: String,
+    table: String,
+    pg_types: Arc<Vec<PostgresType>>,
+    overwrite: bool,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl PostgresInsertExec {
+    pub fn new(
+        input: Arc<dyn ExecutionPlan>,
+        access: PostgresAccess,
+        schema: String,
+        table: String,
+        pg_types: Arc<Vec<PostgresType>>,
+        overwrite: bool,
+    ) -> Self {
+        PostgresInsertExec {
+            input,
+            access,
+            schema,
+            table,
+            pg_types,
+            overwrite,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl ExecutionPlan for PostgresInsertExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> Arc<ArrowSchema> {
+        COUNT_SCHEMA.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(PostgresInsertExec::new(
+            children[0].clone(),
+            self.access.clone(),
+            self.schema.clone(),
+            self.table.clone(),
+            self.pg_types.clone(),
+            self.overwrite,
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Execution(
+                "PostgresInsertExec only supports 1 partition".to_string(),
+            ));
+        }
+
+        let input = execute_stream(self.input.clone(), context)?;
+        let columns = self
+            .input
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect::<Vec<_>>()
+            .join(",");
+        let copy_query = format!(
+            "COPY {}.{} ({}) FROM STDIN (FORMAT binary)",
+            self.schema, self.table, columns
+        );
+        let truncate_query = self
+            .overwrite
+            .then(|| format!("TRUNCATE {}.{}", self.schema, self.table));
+
+        let access = self.access.clone();
+        let pg_types = self.pg_types.clone();
+        let stream = futures::stream::once(async move {
+            let count = copy_in(access, copy_query, truncate_query, pg_types, input)
+                .await
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            Ok(create_count_record_batch(count))
+        });
+
+        Ok(Box::pin(DataSourceMetricsStreamAdapter::new(
+            RecordBatchStreamAdapter::new(self.schema(), stream),
+            partition,
+            &self.metrics,
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+impl DisplayAs for PostgresInsertExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "PostgresInsertExec(table = {}.{}, overwrite = {})",
+            self.schema, self.table, self.overwrite
+        )
+    }
+}
+
+/// Copy all batches from the input stream into postgres, returning the number
+/// of rows written.
+async fn copy_in(
+    access: PostgresAccess,
+    copy_query: String,
+    truncate_query: Option<String>,
+    pg_types: Arc<Vec<PostgresType>>,
+    mut input: SendableRecordBatchStream,
+) -> Result<u64> {
+    let mut state = access.connect().await?;
+    let txn = state.client.transaction().await?;
+
+    if let Some(query) = truncate_query {
+        txn.execute(query.as_str(), &[]).await?;
+    }
+
+    let sink = txn.copy_in(copy_query.as_str()).await?;
+    let writer = BinaryCopyInWriter::new(sink, &pg_types);
+    pin_mut!(writer);
+
+    while let Some(batch) = input.next().await {
+        write_batch(writer.as_mut(), &batch?).await?;
+    }
+
+    let count = writer.finish().await?;
+    txn.commit().await?;
+
+    Ok(count)
+}
+
+async fn write_batch(
+    mut writer: std::pin::Pin<&mut BinaryCopyInWriter>,
+    batch: &RecordBatch,
+) -> Result<()> {
+    for row in 0..batch.num_rows() {
+        let values = batch
+            .columns()
+            .iter()
+            .map(|col| value_to_sql(col, row))
+            .collect::<Result<Vec<_>>>()?;
+        let values: Vec<&(dyn ToSql + Sync)> = values.iter().map(|v| v.as_ref()).collect();
+        writer.as_mut().write(&values).await?;
+    }
+    Ok(())
+}
+
+/// Get a value from an array that can be written to postgres.
+///
+/// The input to the insert has already been cast to the table's arrow schema,
+/// so this only needs to handle types produced by `try_create_arrow_schema`.
+fn value_to_sql(col: &ArrayRef, row: usize) -> Result<Box<dyn ToSql + Sync + '_>> {
+    let valid = col.is_valid(row);
+
+    macro_rules! primitive {
+        ($typ:ty) => {{
+            let arr = col.as_primitive::<$typ>();
+            Box::new(valid.then(|| arr.value(row)))
+        }};
+    }
+
+    let val: Box<dyn ToSql + Sync + '_> = match col.data_type() {
+        DataType::Boolean => Box::new(valid.then(|| col.as_boolean().value(row))),
+        DataType::Int16 => primitive!(Int16Type),
+        DataType::Int32 => primitive!(Int32Type),
+        DataType::Int64 => primitive!(Int64Type),
+        DataType::Float32 => primitive!(Float32Type),
+        DataType::Float64 => primitive!(Float64Type),
+        DataType::Utf8 => {
+            let arr = col.as_string::<i32>();
+            Box::new(valid.then(|| Str::from(arr.value(row))))
+        }
+        DataType::Binary => {
+            let arr = col.as_binary::<i32>();
+            Box::new(valid.then(|| arr.value(row)))
+        }
+        DataType::Decimal128(_, s) => {
+            let arr = col.as_primitive::<Decimal128Type>();
+            let val = if valid {
+                let scale = u32::try_from(*s)?;
+                let val = rust_decimal::Decimal::try_from_i128_with_scale(arr.value(row), scale)
+                    .map_err(|e| PostgresError::QueryError(e.to_string()))?;
+                Some(val)
+            } else {
+                None
+            };
+            Box::new(val)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, None) => {
+            let arr = col.as_primitive::<TimestampNanosecondType>();
+            Box::new(valid.then(|| arr.value_as_datetime(row)).flatten())
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, Some(_)) => {
+            let arr = col.as_primitive::<TimestampNanosecondType>();
+            let val = valid
+                .then(|| arr.value_as_datetime(row))
+                .flatten()
+                .map(|dt| Utc.from_utc_datetime(&dt));
+            Box::new(val)
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            let arr = col.as_primitive::<Time64NanosecondType>();
+            Box::new(valid.then(|| arr.value_as_time(row)).flatten())
+        }
+        DataType::Date32 => {
+            let arr = col.as_primitive::<Date32Type>();
+            Box::new(valid.then(|| arr.value_as_date(row)).flatten())
+        }
+        other => return Err(PostgresError::FailedBinaryCopy(other.clone())),
+    };
+    Ok(val)
+}
@@ -1,12 +1,13 @@
 pub mod errors;
 
-mod query_exec;
+mod insert;
 mod tls;
 
 use crate::common::ssh::session::SshTunnelSession;
 use crate::common::ssh::{key::SshKey, session::SshTunnelAccess};
-use crate::common::util::{self, create_count_record_batch};
+use crate::common::util;
 use async_trait::async_trait;
+use bytes::BytesMut;
 use chrono::naive::{NaiveDateTime, NaiveTime};
 use chrono::{DateTime, NaiveDate, Timelike, Utc};
 use datafusion::arrow::array::Decimal128Builder;
@@ -20,14 +21,12 @@ use datafusion::execution::context::SessionState;
 use datafusion::execution::context::TaskContext;
 use datafusion::logical_expr::{Expr, TableProviderFilterPushDown, TableType};
 use datafusion::physical_expr::PhysicalSortExpr;
-use datafusion::physical_plan::memory::MemoryExec;
 use datafusion::physical_plan::metrics::ExecutionPlanMetricsSet;
 use datafusion::physical_plan::metrics::MetricsSet;
 use datafusion::physical_plan::{
-    execute_stream, DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream,
+    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream,
     SendableRecordBatchStream, Statistics,
 };
-use datafusion::scalar::ScalarValue;
 use datafusion_ext::errors::ExtensionError;
 use datafusion_ext::functions::VirtualLister;
 use datafusion_ext::metrics::DataSourceMetricsStreamAdapter;
@@ -48,11 +47,11 @@ use tokio::task::JoinHandle;
 use tokio_postgres::binary_copy::{BinaryCopyOutRow, BinaryCopyOutStream};
 use tokio_postgres::config::{Host, SslMode};
 use tokio_postgres::tls::MakeTlsConnect;
-use tokio_postgres::types::{FromSql, Type as PostgresType};
+use tokio_postgres::types::{to_sql_checked, FromSql, IsNull, ToSql, Type as PostgresType};
 use tokio_postgres::{Client, Config, Connection, CopyOutStream, NoTls, Socket};
 use tracing::{debug, warn};
 
-use self::query_exec::PostgresQueryExec;
+use self::insert::PostgresInsertExec;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PostgresDbConnection {
@@ -532,6 +531,8 @@ impl From<PostgresTableProviderConfig>
 
 /// Table provider for a single postgres table.
 pub struct PostgresTableProvider {
+    /// Access details, used to open a dedicated connection for inserts.
+    access: PostgresAccess,
     /// Schema name of table we're accessing.
     schema: String,
     /// Table we're accessing.
@@ -557,6 +558,7 @@ impl PostgresTableProvider {
         let (arrow_schema, pg_types) = state.get_table_schema(&schema, &table).await?;
 
         Ok(PostgresTableProvider {
+            access,
             schema,
             table,
             state,
@@ -664,50 +666,20 @@ impl TableProvider for PostgresTableProvider {
 
     async fn insert_into(
         &self,
-        state: &SessionState,
+        _state: &SessionState,
         input: Arc<dyn ExecutionPlan>,
-        _overwrite: bool,
+        overwrite: bool,
     ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
-        let mut values = String::new();
-
-        let mut input = execute_stream(input, state.task_ctx())?;
-        while let Some(batch) = input.next().await {
-            let batch = batch?;
-
-            let mut row_sep = "";
-            for row_idx in 0..batch.num_rows() {
-                write!(&mut values, "{row_sep}(")?;
-                row_sep = ",";
-
-                let mut col_sep = "";
-                for col in batch.columns() {
-                    write!(&mut values, "{col_sep}")?;
-                    col_sep = ",";
-
-                    let val = ScalarValue::try_from_array(col.as_ref(), row_idx)?;
-                    util::encode_literal_to_text(util::Datasource::Postgres, &mut values, &val)
-                        .map_err(|e| DataFusionError::External(Box::new(e)))?;
-                }
-
-                write!(&mut values, ")")?;
-            }
-        }
-
-        if values.is_empty() {
-            let batch = create_count_record_batch(0);
-            let schema = batch.schema();
-            return Ok(Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None)?));
-        }
-
-        let query = format!(
-            "INSERT INTO {}.{} VALUES {}",
-            self.schema, self.table, values
-        );
-
-        debug!(%query, "inserting into postgres datasource");
-
-        let exec = PostgresQueryExec::new(query, self.state.clone());
-        Ok(Arc::new(exec))
+        debug!(schema = %self.schema, table = %self.table, %overwrite, "inserting into postgres datasource");
+
+        Ok(Arc::new(PostgresInsertExec::new(
+            input,
+            self.access.clone(),
+            self.schema.clone(),
+            self.table.clone(),
+            self.pg_types.clone(),
+            overwrite,
+        )))
     }
 }
 
@@ -995,6 +967,7 @@ impl RecordBatchStream for ChunkStream {
 
 /// Str is a wrapper to represent multiple datatypes as arrow
 /// `DataType::Utf8`, i.e., a string.
+#[derive(Debug)]
 struct Str<'a>(Cow<'a, str>);
 
 impl<'a> Borrow<str> for Str<'a> {
@@ -1050,6 +1023,30 @@ impl<'a> FromSql<'a> for Str<'a> {
     }
 }
 
+impl<'a> ToSql for Str<'a> {
+    fn to_sql(
+        &self,
+        ty: &PostgresType,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        let s: &str = self.borrow();
+        match ty.name() {
+            "uuid" => uuid::Uuid::parse_str(s)?.to_sql(ty, out),
+            "json" | "jsonb" => serde_json::from_str::<serde_json::Value>(s)?.to_sql(ty, out),
+            _ => s.to_sql(ty, out),
+        }
+    }
+
+    fn accepts(ty: &PostgresType) -> bool {
+        <&str as ToSql>::accepts(ty)
+            || ty == &PostgresType::UUID
+            || ty == &PostgresType::JSON
+            || ty == &PostgresType::JSONB
+    }
+
+    to_sql_checked!();
+}
+
 /// Macro for generating the match arms when converting a binary row to a record
 /// batch.
 ///
@@ -0,0 +1,445 @@
+//! Conversion between sqlite's dynamic types and arrow.
+//!
+//! Sqlite columns don't have a fixed type. Instead a column has a declared
+//! type which determines the column's "affinity", and any value may be stored
+//! in any column. We pick an arrow type from the declared type, then convert
+//! each value to that type when reading, erroring if a value can't be
+//! converted without loss.
+//!
+//! See <https://www.sqlite.org/datatype3.html>.
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use datafusion::arrow::array::{
+    ArrayRef, BinaryBuilder, BooleanBuilder, Date32Builder, Float64Builder, Int64Builder,
+    StringBuilder, Time64MicrosecondBuilder, TimestampMicrosecondBuilder,
+};
+use datafusion::arrow::datatypes::{DataType, SchemaRef as ArrowSchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use rusqlite::types::ValueRef;
+
+use super::errors::{Result, SqliteError};
+
+const SECS_PER_DAY: i64 = 86_400;
+const MICROS_PER_SEC: i64 = 1_000_000;
+
+/// Julian day number of the unix epoch.
+const UNIX_EPOCH_JULIAN_DAY: f64 = 2_440_587.5;
+
+/// Type affinity of a sqlite column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Affinity {
+    Integer,
+    Text,
+    Blob,
+    Real,
+    Numeric,
+}
+
+impl Affinity {
+    /// Determine the affinity from a column's declared type.
+    pub(super) fn from_decl_type(decl_type: &str) -> Affinity {
+        let decl_type = decl_type.to_uppercase();
+        if decl_type.contains("INT") {
+            Affinity::Integer
+        } else if ["CHAR", "CLOB", "TEXT"]
+            .iter()
+            .any(|s| decl_type.contains(s))
+        {
+            Affinity::Text
+        } else if decl_type.is_empty() || decl_type.contains("BLOB") {
+            Affinity::Blob
+        } else if ["REAL", "FLOA", "DOUB"]
+            .iter()
+            .any(|s| decl_type.contains(s))
+        {
+            Affinity::Real
+        } else {
+            Affinity::Numeric
+        }
+    }
+}
+
+/// Get the arrow type to use for a column with the given declared type.
+///
+/// Sqlite doesn't have dedicated boolean or date/time types, but declared
+/// types commonly indicate how the column is used, so those are mapped to the
+/// appropriate arrow types.
+pub(super) fn decl_type_to_arrow(decl_type: &str) -> DataType {
+    let upper = decl_type.to_uppercase();
+    match Affinity::from_decl_type(decl_type) {
+        Affinity::Integer => DataType::Int64,
+        Affinity::Text => DataType::Utf8,
+        Affinity::Real => DataType::Float64,
+        // Columns without a declared type may hold anything, read them as
+        // text.
+        Affinity::Blob if upper.is_empty() => DataType::Utf8,
+        Affinity::Blob => DataType::Binary,
+        Affinity::Numeric => {
+            if upper.contains("BOOL") {
+                DataType::Boolean
+            } else if upper.contains("DATETIME") || upper.contains("TIMESTAMP") {
+                DataType::Timestamp(TimeUnit::Microsecond, None)
+            } else if upper.contains("DATE") {
+                DataType::Date32
+            } else if upper.contains("TIME") {
+                DataType::Time64(TimeUnit::Microsecond)
+            } else {
+                DataType::Float64
+            }
+        }
+    }
+}
+
+/// Builds record batches from sqlite rows.
+pub(super) struct RecordBatchBuilder {
+    schema: ArrowSchemaRef,
+    columns: Vec<ColumnBuilder>,
+    num_rows: usize,
+}
+
+impl RecordBatchBuilder {
+    pub(super) fn try_new(schema: ArrowSchemaRef, capacity: usize) -> Result<Self> {
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|f| ColumnBuilder::try_new(f.data_type(), capacity))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(RecordBatchBuilder {
+            schema,
+            columns,
+            num_rows: 0,
+        })
+    }
+
+    pub(super) fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Append a row to the batch.
+    ///
+    /// The row must contain values for each column in the schema (in order).
+    pub(super) fn append_row(&mut self, row: &rusqlite::Row<'_>) -> Result<()> {
+        for (idx, (col, field)) in self
+            .columns
+            .iter_mut()
+            .zip(self.schema.fields())
+            .enumerate()
+        {
+            let val = row.get_ref(idx)?;
+            if !col.append(val) {
+                return Err(SqliteError::InvalidConversion {
+                    column: field.name().clone(),
+                    value_type: value_type_name(val),
+                    data_type: field.data_type().clone(),
+                });
+            }
+        }
+        self.num_rows += 1;
+        Ok(())
+    }
+
+    /// Build a record batch from all appended rows, resetting the builder.
+    pub(super) fn finish(&mut self) -> Result<RecordBatch> {
+        let columns: Vec<ArrayRef> = self.columns.iter_mut().map(|c| c.finish()).collect();
+        // Row count needs to be provided explicitly for empty projections.
+        let opts = RecordBatchOptions::new().with_row_count(Some(self.num_rows));
+        self.num_rows = 0;
+        Ok(RecordBatch::try_new_with_options(
+            self.schema.clone(),
+            columns,
+            &opts,
+        )?)
+    }
+}
+
+enum ColumnBuilder {
+    Boolean(BooleanBuilder),
+    Int64(Int64Builder),
+    Float64(Float64Builder),
+    Utf8(StringBuilder),
+    Binary(BinaryBuilder),
+    Date32(Date32Builder),
+    Time64(Time64MicrosecondBuilder),
+    Timestamp(TimestampMicrosecondBuilder),
+}
+
+impl ColumnBuilder {
+    fn try_new(data_type: &DataType, capacity: usize) -> Result<Self> {
+        Ok(match data_type {
+            DataType::Boolean => Self::Boolean(BooleanBuilder::with_capacity(capacity)),
+            DataType::Int64 => Self::Int64(Int64Builder::with_capacity(capacity)),
+            DataType::Float64 => Self::Float64(Float64Builder::with_capacity(capacity)),
+            // Assumes an average of 16 bytes per item.
+            DataType::Utf8 => Self::Utf8(StringBuilder::with_capacity(capacity, capacity * 16)),
+            DataType::Binary => Self::Binary(BinaryBuilder::with_capacity(capacity, capacity * 16)),
+            DataType::Date32 => Self::Date32(Date32Builder::with_capacity(capacity)),
+            DataType::Time64(TimeUnit::Microsecond) => {
+                Self::Time64(Time64MicrosecondBuilder::with_capacity(capacity))
+            }
+            DataType::Timestamp(TimeUnit::Microsecond, None) => {
+                Self::Timestamp(TimestampMicrosecondBuilder::with_capacity(capacity))
+            }
+            other => {
+                return Err(SqliteError::String(format!(
+                    "unsupported data type for sqlite: {other}"
+                )))
+            }
+        })
+    }
+
+    /// Append a value, returning false if the value couldn't be converted to
+    /// the column's type.
+    fn append(&mut self, val: ValueRef<'_>) -> bool {
+        macro_rules! append {
+            ($builder:expr, $conv:expr) => {{
+                if matches!(val, ValueRef::Null) {
+                    $builder.append_null();
+                    return true;
+                }
+                match $conv(val) {
+                    Some(v) => {
+                        $builder.append_value(v);
+                        true
+                    }
+                    None => false,
+                }
+            }};
+        }
+
+        match self {
+            Self::Boolean(b) => append!(b, value_to_bool),
+            Self::Int64(b) => append!(b, value_to_i64),
+            Self::Float64(b) => append!(b, value_to_f64),
+            Self::Utf8(b) => append!(b, value_to_string),
+            Self::Binary(b) => append!(b, value_to_bytes),
+            Self::Date32(b) => append!(b, value_to_date32),
+            Self::Time64(b) => append!(b, value_to_time64),
+            Self::Timestamp(b) => append!(b, value_to_timestamp),
+        }
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            Self::Boolean(b) => Arc::new(b.finish()),
+            Self::Int64(b) => Arc::new(b.finish()),
+            Self::Float64(b) => Arc::new(b.finish()),
+            Self::Utf8(b) => Arc::new(b.finish()),
+            Self::Binary(b) => Arc::new(b.finish()),
+            Self::Date32(b) => Arc::new(b.finish()),
+            Self::Time64(b) => Arc::new(b.finish()),
+            Self::Timestamp(b) => Arc::new(b.finish()),
+        }
+    }
+}
+
+fn value_type_name(val: ValueRef<'_>) -> &'static str {
+    match val {
+        ValueRef::Null => "NULL",
+        ValueRef::Integer(_) => "INTEGER",
+        ValueRef::Real(_) => "REAL",
+        ValueRef::Text(_) => "TEXT",
+        ValueRef::Blob(_) => "BLOB",
+    }
+}
+
+fn value_to_bool(val: ValueRef<'_>) -> Option<bool> {
+    match val {
+        ValueRef::Integer(v) => Some(v != 0),
+        ValueRef::Real(v) => Some(v != 0.0),
+        ValueRef::Text(v) => match std::str::from_utf8(v).ok()?.to_lowercase().as_str() {
+            "1" | "t" | "true" => Some(true),
+            "0" | "f" | "false" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn value_to_i64(val: ValueRef<'_>) -> Option<i64> {
+    match val {
+        ValueRef::Integer(v) => Some(v),
+        ValueRef::Real(v) if v.fract() == 0.0 && v >= i64::MIN as f64 && v < i64::MAX as f64 => {
+            Some(v as i64)
+        }
+        ValueRef::Text(v) => std::str::from_utf8(v).ok()?.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn value_to_f64(val: ValueRef<'_>) -> Option<f64> {
+    match val {
+        ValueRef::Integer(v) => Some(v as f64),
+        ValueRef::Real(v) => Some(v),
+        ValueRef::Text(v) => std::str::from_utf8(v).ok()?.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn value_to_string(val: ValueRef<'_>) -> Option<String> {
+    match val {
+        ValueRef::Integer(v) => Some(v.to_string()),
+        ValueRef::Real(v) => Some(v.to_string()),
+        ValueRef::Text(v) | ValueRef::Blob(v) => std::str::from_utf8(v).ok().map(str::to_string),
+        ValueRef::Null => None,
+    }
+}
+
+fn value_to_bytes(val: ValueRef<'_>) -> Option<&[u8]> {
+    match val {
+        ValueRef::Text(v) | ValueRef::Blob(v) => Some(v),
+        _ => None,
+    }
+}
+
+/// Sqlite date and time values may be stored as ISO-8601 text, unix
+/// timestamps (integers), or julian day numbers (reals).
+fn value_to_timestamp(val: ValueRef<'_>) -> Option<i64> {
+    match val {
+        ValueRef::Integer(v) => v.checked_mul(MICROS_PER_SEC),
+        // Julian day numbers don't have enough precision for microseconds,
+        // round to milliseconds like sqlite does.
+        ValueRef::Real(v) => {
+            let millis = ((v - UNIX_EPOCH_JULIAN_DAY) * (SECS_PER_DAY * 1_000) as f64).round();
+            millis
+                .is_finite()
+                .then(|| (millis as i64).checked_mul(1_000))
+                .flatten()
+        }
+        ValueRef::Text(v) => {
+            let datetime = parse_datetime(std::str::from_utf8(v).ok()?.trim())?;
+            Some(datetime.timestamp_micros())
+        }
+        _ => None,
+    }
+}
+
+fn value_to_date32(val: ValueRef<'_>) -> Option<i32> {
+    let micros = value_to_timestamp(val)?;
+    i32::try_from(micros.div_euclid(SECS_PER_DAY * MICROS_PER_SEC)).ok()
+}
+
+fn value_to_time64(val: ValueRef<'_>) -> Option<i64> {
+    let s = match val {
+        ValueRef::Text(v) => std::str::from_utf8(v).ok()?.trim(),
+        _ => return None,
+    };
+    let time = NaiveTime::parse_from_str(s, "%H:%M:%S%.f")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M"))
+        .ok()?;
+    Some(
+        time.num_seconds_from_midnight() as i64 * MICROS_PER_SEC
+            + (time.nanosecond() / 1_000) as i64,
+    )
+}
+
+/// Parse the text formats accepted by sqlite's date and time functions.
+fn parse_datetime(s: &str) -> Option<NaiveDateTime> {
+    const FORMATS: &[&str] = &[
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ];
+
+    for format in FORMATS {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(s, format) {
+            return Some(datetime);
+        }
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0);
+    }
+
+    // Values with a timezone suffix are converted to UTC.
+    let s = s.replacen(' ', "T", 1);
+    DateTime::parse_from_rfc3339(&s)
+        .ok()
+        .map(|datetime| datetime.naive_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decl_types() {
+        let cases = [
+            ("INTEGER", DataType::Int64),
+            ("bigint", DataType::Int64),
+            ("VARCHAR(255)", DataType::Utf8),
+            ("text", DataType::Utf8),
+            ("", DataType::Utf8),
+            ("BLOB", DataType::Binary),
+            ("DOUBLE PRECISION", DataType::Float64),
+            ("NUMERIC(10,2)", DataType::Float64),
+            ("BOOLEAN", DataType::Boolean),
+            ("DATE", DataType::Date32),
+            ("DATETIME", DataType::Timestamp(TimeUnit::Microsecond, None)),
+            (
+                "TIMESTAMP",
+                DataType::Timestamp(TimeUnit::Microsecond, None),
+            ),
+            ("TIME", DataType::Time64(TimeUnit::Microsecond)),
+        ];
+
+        for (decl_type, expected) in cases {
+            assert_eq!(expected, decl_type_to_arrow(decl_type), "{decl_type}");
+        }
+    }
+
+    #[test]
+    fn dynamic_values() {
+        assert_eq!(Some(3), value_to_i64(ValueRef::Real(3.0)));
+        assert_eq!(None, value_to_i64(ValueRef::Real(3.5)));
+        assert_eq!(Some(42), value_to_i64(ValueRef::Text(b"42")));
+        assert_eq!(None, value_to_i64(ValueRef::Text(b"abc")));
+
+        assert_eq!(Some(true), value_to_bool(ValueRef::Text(b"TRUE")));
+        assert_eq!(Some(false), value_to_bool(ValueRef::Integer(0)));
+
+        assert_eq!(
+            Some("1.5".to_string()),
+            value_to_string(ValueRef::Real(1.5))
+        );
+        assert_eq!(None, value_to_string(ValueRef::Blob(&[0xff, 0xfe])));
+    }
+
+    #[test]
+    fn date_and_time_values() {
+        // 2023-01-02 03:04:05 UTC
+        let expected = 1_672_628_645 * MICROS_PER_SEC;
+        assert_eq!(
+            Some(expected),
+            value_to_timestamp(ValueRef::Text(b"2023-01-02 03:04:05"))
+        );
+        assert_eq!(
+            Some(expected),
+            value_to_timestamp(ValueRef::Text(b"2023-01-02T05:04:05+02:00"))
+        );
+        assert_eq!(
+            Some(expected),
+            value_to_timestamp(ValueRef::Integer(1_672_628_645))
+        );
+        assert_eq!(
+            Some(expected),
+            value_to_timestamp(ValueRef::Real(2459946.627835648))
+        );
+
+        assert_eq!(Some(19359), value_to_date32(ValueRef::Text(b"2023-01-02")));
+        assert_eq!(
+            Some(19359),
+            value_to_date32(ValueRef::Text(b"2023-01-02 03:04:05"))
+        );
+        assert_eq!(
+            Some(19359),
+            value_to_date32(ValueRef::Integer(1_672_628_645))
+        );
+
+        assert_eq!(
+            Some((3 * 3600 + 4 * 60 + 5) * MICROS_PER_SEC + 500_000),
+            value_to_time64(ValueRef::Text(b"03:04:05.5"))
+        );
+    }
+}
@@ -0,0 +1,47 @@
+#[derive(Debug, thiserror::Error)]
+pub enum SqliteError {
+    #[error("{0}")]
+    String(String),
+
+    #[error("Missing table: {0}")]
+    MissingTable(String),
+
+    #[error("Cannot read sqlite {value_type} value in column '{column}' as {data_type}")]
+    InvalidConversion {
+        column: String,
+        value_type: &'static str,
+        data_type: datafusion::arrow::datatypes::DataType,
+    },
+
+    #[error(transparent)]
+    Rusqlite(#[from] rusqlite::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Fmt(#[from] std::fmt::Error),
+
+    #[error(transparent)]
+    Arrow(#[from] datafusion::arrow::error::ArrowError),
+
+    #[error(transparent)]
+    ObjectStore(#[from] object_store::Error),
+
+    #[error(transparent)]
+    ObjectStorePath(#[from] object_store::path::Error),
+
+    #[error(transparent)]
+    ObjectStoreSource(#[from] crate::object_store::errors::ObjectStoreSourceError),
+
+    #[error(transparent)]
+    DatasourceCommon(#[from] crate::common::errors::DatasourceCommonError),
+
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
+}
+
+pub type Result<T, E = SqliteError> = std::result::Result<T, E>;
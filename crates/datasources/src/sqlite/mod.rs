@@ -0,0 +1,667 @@
+//! Sqlite as a data source.
+pub mod errors;
+
+mod convert;
+
+use std::any::Any;
+use std::collections::HashSet;
+use std::fmt::{self, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use convert::{decl_type_to_arrow, Affinity, RecordBatchBuilder};
+use datafusion::arrow::datatypes::{
+    Field, Fields, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef,
+};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::TableProvider;
+use datafusion::error::{DataFusionError, Result as DatafusionResult};
+use datafusion::execution::context::{SessionState, TaskContext};
+use datafusion::logical_expr::{Expr, Operator, TableProviderFilterPushDown, TableType};
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+    Statistics,
+};
+use datafusion::scalar::ScalarValue;
+use datafusion_ext::errors::ExtensionError;
+use datafusion_ext::functions::VirtualLister;
+use datafusion_ext::metrics::DataSourceMetricsStreamAdapter;
+use errors::{Result, SqliteError};
+use futures::StreamExt;
+use object_store::path::Path as ObjectStorePath;
+use protogen::metastore::types::options::StorageOptions;
+use rusqlite::{Connection, OpenFlags};
+use tempfile::NamedTempFile;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tracing::{debug, trace};
+
+use crate::common::url::{DatasourceUrl, DatasourceUrlType};
+use crate::common::util;
+use crate::object_store::generic::GenericStoreAccess;
+use crate::object_store::ObjStoreAccess;
+
+/// Name of the only schema in a sqlite database.
+pub const SQLITE_SCHEMA: &str = "main";
+
+/// Information needed for accessing a sqlite database file.
+#[derive(Debug, Clone)]
+pub struct SqliteAccess {
+    /// Location of the database file. May be a local path or an object store
+    /// url.
+    pub location: String,
+    /// Options for accessing the database file in object storage.
+    pub storage_options: StorageOptions,
+}
+
+impl SqliteAccess {
+    /// Validate that we can open the database.
+    pub async fn validate_access(&self) -> Result<()> {
+        let state = self.connect().await?;
+        state
+            .run(|conn| {
+                // Opening is lazy, reading the schema ensures the file is
+                // actually a sqlite database.
+                conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// Validate that we can read a table from the database.
+    pub async fn validate_table_access(&self, table: &str) -> Result<()> {
+        let state = self.connect().await?;
+        let _schema = state.get_table_schema(table).await?;
+        Ok(())
+    }
+
+    /// Get a local copy of the database and return the access state.
+    ///
+    /// Databases in object storage are downloaded to a temporary file which
+    /// lives as long as the access state.
+    pub async fn connect(&self) -> Result<SqliteAccessState> {
+        let temp = match DatasourceUrl::try_new(&self.location)? {
+            DatasourceUrl::File(path) => return Ok(SqliteAccessState { path, _temp: None }),
+            url if url.datasource_url_type() == DatasourceUrlType::Http => {
+                self.download_http().await?
+            }
+            url => self.download_object(&url).await?,
+        };
+
+        Ok(SqliteAccessState {
+            path: temp.path().to_path_buf(),
+            _temp: Some(Arc::new(temp)),
+        })
+    }
+
+    async fn download_http(&self) -> Result<NamedTempFile> {
+        debug!(location = %self.location, "downloading sqlite database");
+
+        let temp = NamedTempFile::new()?;
+        let mut file = tokio::fs::File::create(temp.path()).await?;
+
+        let mut resp = reqwest::get(&self.location).await?.error_for_status()?;
+        while let Some(chunk) = resp.chunk().await? {
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+
+        Ok(temp)
+    }
+
+    async fn download_object(&self, url: &DatasourceUrl) -> Result<NamedTempFile> {
+        debug!(location = %self.location, "downloading sqlite database");
+
+        let store = GenericStoreAccess::new_from_location_and_opts(
+            &self.location,
+            self.storage_options.clone(),
+        )?
+        .create_store()?;
+        let path = ObjectStorePath::from_url_path(url.path())?;
+
+        let temp = NamedTempFile::new()?;
+        let mut file = tokio::fs::File::create(temp.path()).await?;
+
+        let mut stream = store.get(&path).await?.into_stream();
+        while let Some(chunk) = stream.next().await {
+            file.write_all(&chunk?).await?;
+        }
+        file.flush().await?;
+
+        Ok(temp)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SqliteAccessState {
+    /// Path to the local database file.
+    path: PathBuf,
+    /// Temporary copy of a database from object storage.
+    ///
+    /// Kept on struct to avoid deleting the file while it's being read.
+    _temp: Option<Arc<NamedTempFile>>,
+}
+
+impl SqliteAccessState {
+    /// Open a new read-only connection to the database.
+    ///
+    /// Connections are cheap to open, so every scan gets its own connection
+    /// instead of contending for a shared one.
+    fn open(&self) -> Result<Connection> {
+        let flags = OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+        Ok(Connection::open_with_flags(&self.path, flags)?)
+    }
+
+    /// Run a function against a new connection on a blocking thread.
+    async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+    {
+        let state = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = state.open()?;
+            f(&conn)
+        })
+        .await?
+    }
+
+    /// Get the columns in a table along with their declared types.
+    async fn get_table_columns(&self, table: &str) -> Result<Vec<(String, String)>> {
+        let table = table.to_string();
+        self.run(move |conn| {
+            let mut stmt = conn.prepare("SELECT name, type FROM pragma_table_info(?1)")?;
+            let cols = stmt
+                .query_map([&table], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<(String, String)>, _>>()?;
+            if cols.is_empty() {
+                return Err(SqliteError::MissingTable(table));
+            }
+            Ok(cols)
+        })
+        .await
+    }
+
+    /// Get the arrow schema for a table.
+    async fn get_table_schema(&self, table: &str) -> Result<ArrowSchema> {
+        let cols = self.get_table_columns(table).await?;
+        let fields: Vec<_> = cols
+            .iter()
+            .map(|(name, decl_type)| Field::new(name, decl_type_to_arrow(decl_type), true))
+            .collect();
+        Ok(ArrowSchema::new(fields))
+    }
+}
+
+#[async_trait]
+impl VirtualLister for SqliteAccessState {
+    async fn list_schemas(&self) -> Result<Vec<String>, ExtensionError> {
+        Ok(vec![SQLITE_SCHEMA.to_string()])
+    }
+
+    async fn list_tables(&self, schema: &str) -> Result<Vec<String>, ExtensionError> {
+        if schema != SQLITE_SCHEMA {
+            return Ok(Vec::new());
+        }
+
+        self.run(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT name FROM sqlite_master WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' ORDER BY name",
+            )?;
+            let tables = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<Vec<String>, _>>()?;
+            Ok(tables)
+        })
+        .await
+        .map_err(ExtensionError::access)
+    }
+
+    async fn list_columns(&self, _schema: &str, table: &str) -> Result<Fields, ExtensionError> {
+        use ExtensionError::ListingErrBoxed;
+
+        let schema = self
+            .get_table_schema(table)
+            .await
+            .map_err(|e| ListingErrBoxed(Box::new(e)))?;
+
+        Ok(schema.fields)
+    }
+}
+
+pub struct SqliteTableProvider {
+    state: SqliteAccessState,
+    table: String,
+    schema: ArrowSchemaRef,
+    /// Columns that can be referenced in pushed down filters.
+    ///
+    /// Sqlite only converts literals to the type of the column being compared
+    /// against for columns with an integer, real, or text affinity. Comparing
+    /// against any other column may produce different results than comparing
+    /// the converted arrow values.
+    filter_columns: HashSet<String>,
+}
+
+impl SqliteTableProvider {
+    pub async fn try_new(access: SqliteAccess, table: impl Into<String>) -> Result<Self> {
+        let state = access.connect().await?;
+        Self::try_new_from_state(state, table).await
+    }
+
+    pub async fn try_new_from_state(
+        state: SqliteAccessState,
+        table: impl Into<String>,
+    ) -> Result<Self> {
+        let table = table.into();
+        let cols = state.get_table_columns(&table).await?;
+
+        let fields: Vec<_> = cols
+            .iter()
+            .map(|(name, decl_type)| Field::new(name, decl_type_to_arrow(decl_type), true))
+            .collect();
+        let filter_columns = cols
+            .into_iter()
+            .filter(|(_, decl_type)| {
+                matches!(
+                    Affinity::from_decl_type(decl_type),
+                    Affinity::Integer | Affinity::Real | Affinity::Text
+                )
+            })
+            .map(|(name, _)| name)
+            .collect();
+
+        Ok(SqliteTableProvider {
+            state,
+            table,
+            schema: Arc::new(ArrowSchema::new(fields)),
+            filter_columns,
+        })
+    }
+}
+
+#[async_trait]
+impl TableProvider for SqliteTableProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> ArrowSchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    fn supports_filter_pushdown(
+        &self,
+        _filter: &Expr,
+    ) -> DatafusionResult<TableProviderFilterPushDown> {
+        Ok(TableProviderFilterPushDown::Inexact)
+    }
+
+    async fn scan(
+        &self,
+        _ctx: &SessionState,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
+        // Project the schema.
+        let projected_schema = match projection {
+            Some(projection) => Arc::new(self.schema.project(projection)?),
+            None => self.schema.clone(),
+        };
+
+        // Get the projected columns, joined by a ','. This will be put in the
+        // 'SELECT ...' portion of the query. An empty projection (e.g. for
+        // 'count(*)') still needs to select something.
+        let projection_string = if projected_schema.fields().is_empty() {
+            "1".to_string()
+        } else {
+            projected_schema
+                .fields()
+                .iter()
+                .map(|f| quote_ident(f.name()))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        let limit_string = match limit {
+            Some(limit) => format!(" LIMIT {limit}"),
+            None => String::new(),
+        };
+
+        let predicate_string = exprs_to_predicate_string(filters, &self.filter_columns)
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let where_string = if predicate_string.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {predicate_string}")
+        };
+
+        let query = format!(
+            "SELECT {projection_string} FROM {}{where_string}{limit_string}",
+            quote_ident(&self.table)
+        );
+        trace!(%query, "sqlite scan query");
+
+        Ok(Arc::new(SqliteExec {
+            query,
+            state: self.state.clone(),
+            arrow_schema: projected_schema,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }))
+    }
+}
+
+/// Execution plan for reading from sqlite.
+struct SqliteExec {
+    query: String,
+    state: SqliteAccessState,
+    arrow_schema: ArrowSchemaRef,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl ExecutionPlan for SqliteExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> ArrowSchemaRef {
+        self.arrow_schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        Vec::new()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
+        Err(DataFusionError::Execution(
+            "cannot replace children for SqliteExec".to_string(),
+        ))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DatafusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Execution(
+                "only single partition supported".to_string(),
+            ));
+        }
+
+        // Rows are read on a blocking thread and sent back over a channel.
+        let (tx, rx) = mpsc::channel(2);
+        let query = self.query.clone();
+        let state = self.state.clone();
+        let schema = self.arrow_schema.clone();
+        let batch_size = context.session_config().batch_size();
+
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = read_batches(&state, &query, schema, batch_size, &tx) {
+                let _ = tx.blocking_send(Err(DataFusionError::External(Box::new(e))));
+            }
+        });
+
+        let stream = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|batch| (batch, rx))
+        });
+
+        Ok(Box::pin(DataSourceMetricsStreamAdapter::new(
+            RecordBatchStreamAdapter::new(self.arrow_schema.clone(), stream),
+            partition,
+            &self.metrics,
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+impl DisplayAs for SqliteExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SqliteExec")
+    }
+}
+
+impl fmt::Debug for SqliteExec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqliteExec")
+            .field("query", &self.query)
+            .field("arrow_schema", &self.arrow_schema)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Run the query, sending record batches to the channel.
+///
+/// Stops early without error if the receiving side is dropped.
+fn read_batches(
+    state: &SqliteAccessState,
+    query: &str,
+    schema: ArrowSchemaRef,
+    batch_size: usize,
+    tx: &mpsc::Sender<DatafusionResult<RecordBatch>>,
+) -> Result<()> {
+    let conn = state.open()?;
+    let mut stmt = conn.prepare(query)?;
+    let mut rows = stmt.query([])?;
+
+    let mut builder = RecordBatchBuilder::try_new(schema, batch_size)?;
+    while let Some(row) = rows.next()? {
+        builder.append_row(row)?;
+        if builder.num_rows() >= batch_size && tx.blocking_send(Ok(builder.finish()?)).is_err() {
+            return Ok(());
+        }
+    }
+
+    if builder.num_rows() > 0 {
+        let _ = tx.blocking_send(Ok(builder.finish()?));
+    }
+
+    Ok(())
+}
+
+/// Quote an identifier for use in a sqlite query.
+fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// Convert filtering expressions to a predicate string usable with the
+/// generated sqlite query.
+///
+/// Expressions that can't be converted are skipped. Filters are only ever
+/// pushed down inexactly, so they'll still be applied after the scan.
+fn exprs_to_predicate_string(exprs: &[Expr], filter_columns: &HashSet<String>) -> Result<String> {
+    let mut ss = Vec::new();
+    for expr in exprs {
+        let mut buf = String::new();
+        if write_expr(expr, filter_columns, &mut buf)? {
+            ss.push(buf);
+        }
+    }
+    Ok(ss.join(" AND "))
+}
+
+/// Try to write the expression to the string, returning true if it was written.
+fn write_expr(expr: &Expr, filter_columns: &HashSet<String>, buf: &mut String) -> Result<bool> {
+    match expr {
+        Expr::Column(col) => {
+            if !filter_columns.contains(&col.name) {
+                return Ok(false);
+            }
+            write!(buf, "{}", quote_ident(&col.name))?;
+        }
+        Expr::Literal(val) => match val {
+            ScalarValue::Utf8(Some(s)) => write!(buf, "'{}'", s.replace('\'', "''"))?,
+            ScalarValue::Int8(Some(_))
+            | ScalarValue::Int16(Some(_))
+            | ScalarValue::Int32(Some(_))
+            | ScalarValue::Int64(Some(_)) => {
+                util::encode_literal_to_text(util::Datasource::Sqlite, buf, val)?
+            }
+            ScalarValue::Float32(Some(v)) if v.is_finite() => {
+                util::encode_literal_to_text(util::Datasource::Sqlite, buf, val)?
+            }
+            ScalarValue::Float64(Some(v)) if v.is_finite() => {
+                util::encode_literal_to_text(util::Datasource::Sqlite, buf, val)?
+            }
+            // Booleans are stored as integers and date/time values may be
+            // stored as text or numbers, none of which would be compared
+            // correctly.
+            _ => return Ok(false),
+        },
+        Expr::IsNull(expr) => {
+            if write_expr(expr, filter_columns, buf)? {
+                write!(buf, " IS NULL")?;
+            } else {
+                return Ok(false);
+            }
+        }
+        Expr::IsNotNull(expr) => {
+            if write_expr(expr, filter_columns, buf)? {
+                write!(buf, " IS NOT NULL")?;
+            } else {
+                return Ok(false);
+            }
+        }
+        Expr::BinaryExpr(binary)
+            if matches!(
+                binary.op,
+                Operator::Eq
+                    | Operator::NotEq
+                    | Operator::Lt
+                    | Operator::LtEq
+                    | Operator::Gt
+                    | Operator::GtEq
+                    | Operator::And
+                    | Operator::Or
+            ) =>
+        {
+            write!(buf, "(")?;
+            if !write_expr(binary.left.as_ref(), filter_columns, buf)? {
+                return Ok(false);
+            }
+            write!(buf, " {} ", binary.op)?;
+            if !write_expr(binary.right.as_ref(), filter_columns, buf)? {
+                return Ok(false);
+            }
+            write!(buf, ")")?;
+        }
+        _ => {
+            // Unsupported.
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::logical_expr::{col, lit};
+
+    use super::*;
+
+    #[test]
+    fn valid_expr_string() {
+        let filter_columns: HashSet<_> = ["a", "b", "c"].into_iter().map(String::from).collect();
+
+        let exprs = vec![
+            col("a").eq(lit(1_i64)),
+            col("b").gt(lit("it's")).or(col("c").is_null()),
+            col("c").lt_eq(lit(2.5_f64)),
+        ];
+        let out = exprs_to_predicate_string(&exprs, &filter_columns).unwrap();
+        assert_eq!(
+            out,
+            "(\"a\" = 1) AND ((\"b\" > 'it''s') OR \"c\" IS NULL) AND (\"c\" <= 2.5)"
+        );
+    }
+
+    #[test]
+    fn skip_unsupported_expr_string() {
+        let filter_columns: HashSet<_> = ["a"].into_iter().map(String::from).collect();
+
+        let exprs = vec![
+            // Column without a matching affinity.
+            col("d").eq(lit(1_i64)),
+            // Unsupported literals.
+            col("a").eq(lit(true)),
+            col("a").eq(lit(f64::NAN)),
+            col("a").eq(lit(ScalarValue::Int64(None))),
+            col("a").eq(lit(1_i64)),
+        ];
+        let out = exprs_to_predicate_string(&exprs, &filter_columns).unwrap();
+        assert_eq!(out, "(\"a\" = 1)");
+    }
+
+    #[tokio::test]
+    async fn read_table() {
+        let temp = NamedTempFile::new().unwrap();
+        let conn = Connection::open(temp.path()).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE t (a INTEGER, b TEXT, c REAL, d BOOLEAN, e DATE, f);
+             INSERT INTO t VALUES (1, 'one', 1.5, 1, '2023-01-02', 1);
+             INSERT INTO t VALUES (2, 'two', NULL, 0, NULL, 'two');",
+        )
+        .unwrap();
+        drop(conn);
+
+        let access = SqliteAccess {
+            location: temp.path().to_string_lossy().to_string(),
+            storage_options: StorageOptions::default(),
+        };
+        access.validate_table_access("t").await.unwrap();
+        access.validate_table_access("missing").await.unwrap_err();
+
+        let state = access.connect().await.unwrap();
+        assert_eq!(
+            vec!["t".to_string()],
+            state.list_tables("main").await.unwrap()
+        );
+
+        let provider = SqliteTableProvider::try_new_from_state(state.clone(), "t")
+            .await
+            .unwrap();
+        let expected: HashSet<_> = ["a", "b", "c"].into_iter().map(String::from).collect();
+        assert_eq!(expected, provider.filter_columns);
+
+        let (tx, mut rx) = mpsc::channel(2);
+        let query = "SELECT a, b, c, d, e, f FROM t ORDER BY a".to_string();
+        let schema = provider.schema();
+        tokio::task::spawn_blocking(move || read_batches(&state, &query, schema, 1024, &tx))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let batch = rx.recv().await.unwrap().unwrap();
+        assert_eq!(2, batch.num_rows());
+        assert_eq!(6, batch.num_columns());
+        assert_eq!(1, batch.column(2).null_count());
+        assert!(rx.recv().await.is_none());
+    }
+}
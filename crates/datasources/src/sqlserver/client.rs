@@ -15,8 +15,8 @@ use futures::{AsyncRead, AsyncWrite, Stream, StreamExt};
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::{borrow::Cow, collections::VecDeque};
-use tiberius::{Column, QueryItem, ResultMetadata, Row};
-use tokio::sync::mpsc;
+use tiberius::{Column, QueryItem, ResultMetadata, Row, ToSql, TokenRow};
+use tokio::sync::{mpsc, oneshot};
 use tracing::debug;
 
 /// Connect to a SQL Server database using the provided tiberius config and stream.
@@ -59,8 +59,13 @@ where
     pub async fn run(mut self) -> Result<()> {
         while let Some(req) = self.receiver.recv().await {
             match req {
-                Request::Query { query, response } => {
-                    let mut stream = match self.client.query(query, &[]).await {
+                Request::Query {
+                    query,
+                    params,
+                    response,
+                } => {
+                    let params: Vec<&dyn ToSql> = params.iter().map(|p| p as &dyn ToSql).collect();
+                    let mut stream = match self.client.query(query, &params).await {
                         Ok(stream) => stream,
                         Err(e) => {
                             // We don't care if this errors, just means that the
@@ -78,6 +83,30 @@ where
                         }
                     }
                 }
+                Request::Execute { query, response } => {
+                    let result = self
+                        .client
+                        .execute(query, &[])
+                        .await
+                        .map(|result| result.total())
+                        .map_err(|e| e.into());
+                    let _ = response.send(result);
+                }
+                Request::SimpleQuery { query, response } => {
+                    let result = match self.client.simple_query(query).await {
+                        Ok(stream) => stream.into_results().await.map(|_| ()),
+                        Err(e) => Err(e),
+                    };
+                    let _ = response.send(result.map_err(|e| e.into()));
+                }
+                Request::BulkInsert {
+                    table,
+                    rows,
+                    response,
+                } => {
+                    let result = Self::bulk_insert(&mut self.client, &table, rows).await;
+                    let _ = response.send(result);
+                }
                 Request::Drop => {
                     debug!("closing SQL Server connection");
                     self.client.close().await?;
@@ -87,6 +116,30 @@ where
         }
         Ok(())
     }
+
+    /// Bulk load rows received on the channel into a table.
+    ///
+    /// The load is only finalized once the client side signals that it's
+    /// finished. If the channel closes before that, the load is abandoned.
+    async fn bulk_insert(
+        client: &mut tiberius::Client<S>,
+        table: &str,
+        mut rows: mpsc::Receiver<BulkInsertMessage>,
+    ) -> Result<u64> {
+        let mut req = client.bulk_insert(table).await?;
+        while let Some(msg) = rows.recv().await {
+            match msg {
+                BulkInsertMessage::Row(row) => req.send(row).await?,
+                BulkInsertMessage::Finish => {
+                    let result = req.finalize().await?;
+                    return Ok(result.total());
+                }
+            }
+        }
+        Err(SqlServerError::String(
+            "bulk insert aborted before finishing".to_string(),
+        ))
+    }
 }
 
 /// Client side of the connection.
@@ -100,14 +153,24 @@ impl Client {
         Client { sender }
     }
 
-    // TODO: Zero clue how we'd want to handle params. Might as well just fork
-    // tiberius to make it more reasonable.
     pub async fn query<'a>(&self, query: impl Into<Cow<'a, str>>) -> Result<QueryStream> {
+        self.query_with_params(query, Vec::new()).await
+    }
+
+    /// Run a query with string parameters.
+    ///
+    /// Parameters are referenced in the query as `@P1`, `@P2`, etc.
+    pub async fn query_with_params<'a>(
+        &self,
+        query: impl Into<Cow<'a, str>>,
+        params: Vec<String>,
+    ) -> Result<QueryStream> {
         let query = query.into().to_string();
 
         let (sender, receiver) = mpsc::channel(1);
         let req = Request::Query {
             query,
+            params,
             response: sender,
         };
 
@@ -123,6 +186,76 @@ impl Client {
             buffered_rows: VecDeque::new(),
         })
     }
+
+    /// Execute a statement, returning the number of rows affected.
+    pub async fn execute<'a>(&self, query: impl Into<Cow<'a, str>>) -> Result<u64> {
+        let query = query.into().to_string();
+
+        let (sender, receiver) = oneshot::channel();
+        let req = Request::Execute {
+            query,
+            response: sender,
+        };
+
+        if self.sender.send(req).is_err() {
+            return Err(SqlServerError::String(
+                "connection to SQL Server closed".to_string(),
+            ));
+        }
+
+        receiver
+            .await
+            .map_err(|_| SqlServerError::String("connection to SQL Server closed".to_string()))?
+    }
+
+    /// Execute statements without preparing them, discarding any results.
+    ///
+    /// This is needed for statements like `BEGIN TRANSACTION` that shouldn't
+    /// be wrapped in `sp_executesql`.
+    pub async fn batch_execute<'a>(&self, query: impl Into<Cow<'a, str>>) -> Result<()> {
+        let query = query.into().to_string();
+
+        let (sender, receiver) = oneshot::channel();
+        let req = Request::SimpleQuery {
+            query,
+            response: sender,
+        };
+
+        if self.sender.send(req).is_err() {
+            return Err(SqlServerError::String(
+                "connection to SQL Server closed".to_string(),
+            ));
+        }
+
+        receiver
+            .await
+            .map_err(|_| SqlServerError::String("connection to SQL Server closed".to_string()))?
+    }
+
+    /// Start a bulk insert into a table.
+    ///
+    /// The connection will be busy with the bulk insert until it's finished
+    /// (or dropped), so any other requests will be queued behind it.
+    pub fn bulk_insert(&self, table: impl Into<String>) -> Result<BulkInsert> {
+        let (rows_sender, rows_receiver) = mpsc::channel(1024);
+        let (sender, receiver) = oneshot::channel();
+        let req = Request::BulkInsert {
+            table: table.into(),
+            rows: rows_receiver,
+            response: sender,
+        };
+
+        if self.sender.send(req).is_err() {
+            return Err(SqlServerError::String(
+                "connection to SQL Server closed".to_string(),
+            ));
+        }
+
+        Ok(BulkInsert {
+            rows: rows_sender,
+            response: receiver,
+        })
+    }
 }
 
 impl Drop for Client {
@@ -136,12 +269,73 @@ enum Request {
     /// Run a query, sending response items to the channel.
     Query {
         query: String,
+        params: Vec<String>,
         response: mpsc::Sender<Result<QueryItem>>,
     },
+    /// Run statements without preparing them.
+    SimpleQuery {
+        query: String,
+        response: oneshot::Sender<Result<()>>,
+    },
+    /// Execute a statement, sending back the number of rows affected.
+    Execute {
+        query: String,
+        response: oneshot::Sender<Result<u64>>,
+    },
+    /// Bulk insert rows from the channel into a table, sending back the number
+    /// of rows inserted.
+    BulkInsert {
+        table: String,
+        rows: mpsc::Receiver<BulkInsertMessage>,
+        response: oneshot::Sender<Result<u64>>,
+    },
     /// Client was dropped, drop the connection.
     Drop,
 }
 
+#[derive(Debug)]
+enum BulkInsertMessage {
+    Row(TokenRow<'static>),
+    Finish,
+}
+
+/// Handle for sending rows for an in-progress bulk insert.
+#[derive(Debug)]
+pub struct BulkInsert {
+    rows: mpsc::Sender<BulkInsertMessage>,
+    response: oneshot::Receiver<Result<u64>>,
+}
+
+impl BulkInsert {
+    /// Send a row to be inserted.
+    pub async fn send(&mut self, row: TokenRow<'static>) -> Result<()> {
+        if self.rows.send(BulkInsertMessage::Row(row)).await.is_err() {
+            // Connection side stopped receiving rows, which only happens if
+            // the insert errored.
+            return Err(self.error().await);
+        }
+        Ok(())
+    }
+
+    /// Finish the bulk insert, returning the number of rows inserted.
+    pub async fn finish(mut self) -> Result<u64> {
+        if self.rows.send(BulkInsertMessage::Finish).await.is_err() {
+            return Err(self.error().await);
+        }
+        self.response
+            .await
+            .map_err(|_| SqlServerError::String("connection to SQL Server closed".to_string()))?
+    }
+
+    /// Get the error that caused the connection side to stop receiving rows.
+    async fn error(&mut self) -> SqlServerError {
+        match (&mut self.response).await {
+            Ok(Err(e)) => e,
+            _ => SqlServerError::String("connection to SQL Server closed".to_string()),
+        }
+    }
+}
+
 /// Streaming result of a query.
 ///
 /// Note that the stream implementation for this returns _only_ rows. The
@@ -7,7 +7,11 @@ pub enum SqlServerError {
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
+    Fmt(#[from] std::fmt::Error),
+    #[error(transparent)]
     Arrow(#[from] datafusion::arrow::error::ArrowError),
+    #[error(transparent)]
+    DataFusion(#[from] datafusion::error::DataFusionError),
 }
 
 pub type Result<T, E = SqlServerError> = std::result::Result<T, E>;
@@ -0,0 +1,312 @@
+use std::any::Any;
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use datafusion::arrow::array::{Array, ArrayRef, AsArray};
+use datafusion::arrow::datatypes::{
+    DataType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
+    Schema as ArrowSchema, TimeUnit, TimestampNanosecondType,
+};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DatafusionResult};
+use datafusion::execution::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    execute_stream, DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning,
+    SendableRecordBatchStream, Statistics,
+};
+use datafusion_ext::metrics::DataSourceMetricsStreamAdapter;
+use futures::StreamExt;
+use tiberius::{ColumnData, IntoSql, TokenRow};
+
+use crate::common::util::{create_count_record_batch, COUNT_SCHEMA};
+
+use super::errors::{Result, SqlServerError};
+use super::{SqlServerAccess, SqlServerAccessState};
+
+/// Insert into a SQL Server table using a bulk load.
+#[derive(Debug)]
+pub struct SqlServerInsertExec {
+    input: Arc<dyn ExecutionPlan>,
+    access: SqlServerAccess,
+    schema: String,
+    table: String,
+    overwrite: bool,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl SqlServerInsertExec {
+    pub fn new(
+        input: Arc<dyn ExecutionPlan>,
+        access: SqlServerAccess,
+        schema: String,
+        table: String,
+        overwrite: bool,
+    ) -> Self {
+        SqlServerInsertExec {
+            input,
+            access,
+            schema,
+            table,
+            overwrite,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl ExecutionPlan for SqlServerInsertExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> Arc<ArrowSchema> {
+        COUNT_SCHEMA.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(SqlServerInsertExec::new(
+            children[0].clone(),
+            self.access.clone(),
+            self.schema.clone(),
+            self.table.clone(),
+            self.overwrite,
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DatafusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Execution(
+                "SqlServerInsertExec only supports 1 partition".to_string(),
+            ));
+        }
+
+        let input = execute_stream(self.input.clone(), context)?;
+        let access = self.access.clone();
+        let schema = self.schema.clone();
+        let table = self.table.clone();
+        let overwrite = self.overwrite;
+
+        let stream = futures::stream::once(async move {
+            let count = bulk_insert(access, schema, table, overwrite, input)
+                .await
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            Ok(create_count_record_batch(count))
+        });
+
+        Ok(Box::pin(DataSourceMetricsStreamAdapter::new(
+            RecordBatchStreamAdapter::new(self.schema(), stream),
+            partition,
+            &self.metrics,
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+impl DisplayAs for SqlServerInsertExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SqlServerInsertExec(table = {}.{}, overwrite = {})",
+            self.schema, self.table, self.overwrite
+        )
+    }
+}
+
+/// Bulk load all batches from the input stream, returning the number of rows
+/// inserted.
+///
+/// This uses a dedicated connection since the connection is blocked for the
+/// duration of the bulk load. The load (and truncate when overwriting) happens
+/// in a transaction. If anything fails, the connection is closed without
+/// committing, rolling back the transaction.
+async fn bulk_insert(
+    access: SqlServerAccess,
+    schema: String,
+    table: String,
+    overwrite: bool,
+    mut input: SendableRecordBatchStream,
+) -> Result<u64> {
+    let state = SqlServerAccessState::connect(access.config).await?;
+    let sql_types = get_column_types(&state, &schema, &table).await?;
+
+    state.client.batch_execute("BEGIN TRANSACTION").await?;
+
+    if overwrite {
+        state
+            .client
+            .execute(format!("TRUNCATE TABLE {schema}.{table}"))
+            .await?;
+    }
+
+    let mut bulk = state.client.bulk_insert(format!("{schema}.{table}"))?;
+    while let Some(batch) = input.next().await {
+        let batch = batch?;
+        for row in batch_to_rows(&sql_types, &batch)? {
+            bulk.send(row).await?;
+        }
+    }
+
+    let count = bulk.finish().await?;
+    state.client.batch_execute("COMMIT TRANSACTION").await?;
+
+    Ok(count)
+}
+
+/// Get the declared types for each column in the table.
+///
+/// The bulk load requires values to match the width of the declared column
+/// type, which isn't available from the query metadata for variable width
+/// columns (e.g. a nullable `int` and `bigint` are both reported as `Intn`).
+async fn get_column_types(
+    state: &SqlServerAccessState,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<String>> {
+    let mut query = state
+        .client
+        .query_with_params(
+            "SELECT data_type FROM information_schema.columns WHERE table_schema = @P1 AND table_name = @P2 ORDER BY ordinal_position",
+            vec![schema.to_string(), table.to_string()],
+        )
+        .await?;
+
+    let mut types = Vec::new();
+    while let Some(row) = query.next().await {
+        let row = row?;
+        let typ = row.try_get::<&str, usize>(0)?;
+        types.push(typ.unwrap_or_default().to_lowercase());
+    }
+
+    Ok(types)
+}
+
+/// Convert a record batch to rows for a bulk load.
+///
+/// Only handles the arrow types we map SQL Server types to when reading the
+/// table schema.
+fn batch_to_rows(sql_types: &[String], batch: &RecordBatch) -> Result<Vec<TokenRow<'static>>> {
+    let mut rows: Vec<_> = (0..batch.num_rows()).map(|_| TokenRow::new()).collect();
+
+    for (col_idx, col) in batch.columns().iter().enumerate() {
+        let sql_type = sql_types.get(col_idx).map(|s| s.as_str()).unwrap_or("");
+        for (idx, row) in rows.iter_mut().enumerate() {
+            row.push(value_to_column_data(col, idx, sql_type)?);
+        }
+    }
+
+    Ok(rows)
+}
+
+fn value_to_column_data(col: &ArrayRef, row: usize, sql_type: &str) -> Result<ColumnData<'static>> {
+    let valid = col.is_valid(row);
+
+    macro_rules! int {
+        ($typ:ty) => {{
+            let arr = col.as_primitive::<$typ>();
+            int_column_data(valid.then(|| arr.value(row) as i64), sql_type)?
+        }};
+    }
+
+    Ok(match col.data_type() {
+        DataType::Null => ColumnData::I32(None),
+        DataType::Boolean => ColumnData::Bit(valid.then(|| col.as_boolean().value(row))),
+        DataType::Int8 => int!(Int8Type),
+        DataType::Int16 => int!(Int16Type),
+        DataType::Int32 => int!(Int32Type),
+        DataType::Int64 => int!(Int64Type),
+        DataType::Float32 => {
+            let arr = col.as_primitive::<Float32Type>();
+            float_column_data(valid.then(|| arr.value(row) as f64), sql_type)
+        }
+        DataType::Float64 => {
+            let arr = col.as_primitive::<Float64Type>();
+            float_column_data(valid.then(|| arr.value(row)), sql_type)
+        }
+        DataType::Utf8 => {
+            let arr = col.as_string::<i32>();
+            ColumnData::String(valid.then(|| Cow::Owned(arr.value(row).to_string())))
+        }
+        DataType::Binary => {
+            let arr = col.as_binary::<i32>();
+            ColumnData::Binary(valid.then(|| Cow::Owned(arr.value(row).to_vec())))
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, None) => {
+            let arr = col.as_primitive::<TimestampNanosecondType>();
+            valid
+                .then(|| arr.value_as_datetime(row))
+                .flatten()
+                .into_sql()
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, Some(_)) => {
+            let arr = col.as_primitive::<TimestampNanosecondType>();
+            valid
+                .then(|| arr.value_as_datetime(row))
+                .flatten()
+                .map(|dt| Utc.from_utc_datetime(&dt))
+                .into_sql()
+        }
+        other => {
+            return Err(SqlServerError::String(format!(
+                "unsupported data type for sql server insert: {other}"
+            )))
+        }
+    })
+}
+
+/// Narrow an integer to the width of the declared column type.
+fn int_column_data(val: Option<i64>, sql_type: &str) -> Result<ColumnData<'static>> {
+    fn narrow<T: TryFrom<i64>>(val: Option<i64>, sql_type: &str) -> Result<Option<T>> {
+        val.map(|v| {
+            T::try_from(v).map_err(|_| {
+                SqlServerError::String(format!("value out of range for {sql_type}: {v}"))
+            })
+        })
+        .transpose()
+    }
+
+    Ok(match sql_type {
+        // SQL Server's tinyint is unsigned.
+        "tinyint" => ColumnData::U8(narrow(val, sql_type)?),
+        "smallint" => ColumnData::I16(narrow(val, sql_type)?),
+        "int" => ColumnData::I32(narrow(val, sql_type)?),
+        _ => ColumnData::I64(val),
+    })
+}
+
+/// Narrow a float to the width of the declared column type.
+fn float_column_data(val: Option<f64>, sql_type: &str) -> ColumnData<'static> {
+    match sql_type {
+        "real" => ColumnData::F32(val.map(|v| v as f32)),
+        _ => ColumnData::F64(val),
+    }
+}
@@ -1,9 +1,11 @@
 pub mod errors;
 
 mod client;
+mod insert;
 
 use chrono::{DateTime, Utc};
 use client::{Client, QueryStream};
+use insert::SqlServerInsertExec;
 
 use async_trait::async_trait;
 use chrono::naive::NaiveDateTime;
@@ -15,7 +17,7 @@ use datafusion::datasource::TableProvider;
 use datafusion::error::{DataFusionError, Result as DatafusionResult};
 use datafusion::execution::context::SessionState;
 use datafusion::execution::context::TaskContext;
-use datafusion::logical_expr::{Expr, TableProviderFilterPushDown, TableType};
+use datafusion::logical_expr::{Expr, Operator, TableProviderFilterPushDown, TableType};
 use datafusion::physical_expr::PhysicalSortExpr;
 use datafusion::physical_plan::metrics::ExecutionPlanMetricsSet;
 use datafusion::physical_plan::metrics::MetricsSet;
@@ -23,13 +25,14 @@ use datafusion::physical_plan::{
     DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream,
     SendableRecordBatchStream, Statistics,
 };
+use datafusion::scalar::ScalarValue;
 use datafusion_ext::errors::ExtensionError;
 use datafusion_ext::functions::VirtualLister;
 use datafusion_ext::metrics::DataSourceMetricsStreamAdapter;
 use errors::{Result, SqlServerError};
 use futures::{future::BoxFuture, ready, stream::BoxStream, FutureExt, Stream, StreamExt};
 use std::any::Any;
-use std::fmt;
+use std::fmt::{self, Write};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -38,12 +41,15 @@ use tiberius::FromSql;
 use tokio::net::TcpStream;
 use tokio::task::JoinHandle;
 use tokio_util::compat::TokioAsyncWriteCompatExt;
-use tracing::warn;
+use tracing::{debug, trace, warn};
+
+use crate::common::util;
 
 /// Timeout when attempting to connecting to the remote server.
 pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Configuration needed for accessing a sql server instance.
+#[derive(Debug, Clone)]
 pub struct SqlServerAccess {
     config: tiberius::Config,
 }
@@ -248,6 +254,7 @@ pub struct SqlServerTableProviderConfig {
 }
 
 pub struct SqlServerTableProvider {
+    access: SqlServerAccess,
     schema: String,
     table: String,
     state: Arc<SqlServerAccessState>,
@@ -256,10 +263,11 @@ pub struct SqlServerTableProvider {
 
 impl SqlServerTableProvider {
     pub async fn try_new(conf: SqlServerTableProviderConfig) -> Result<Self> {
-        let state = SqlServerAccessState::connect(conf.access.config).await?;
+        let state = conf.access.connect().await?;
         let arrow_schema = state.get_table_schema(&conf.schema, &conf.table).await?;
 
         Ok(Self {
+            access: conf.access,
             schema: conf.schema,
             table: conf.table,
             state: Arc::new(state),
@@ -293,7 +301,7 @@ impl TableProvider for SqlServerTableProvider {
         &self,
         _ctx: &SessionState,
         projection: Option<&Vec<usize>>,
-        _filters: &[Expr],
+        filters: &[Expr],
         limit: Option<usize>,
     ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
         // Project the schema.
@@ -311,17 +319,25 @@ impl TableProvider for SqlServerTableProvider {
             .collect::<Vec<_>>()
             .join(",");
 
-        let limit_string = match limit {
-            Some(limit) => format!("LIMIT {}", limit),
+        // SQL Server doesn't support LIMIT, use TOP instead.
+        let top_string = match limit {
+            Some(limit) => format!("TOP {limit} "),
             None => String::new(),
         };
 
-        // TODO: Where/filters
+        let predicate_string = exprs_to_predicate_string(&self.arrow_schema, filters)
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let where_string = if predicate_string.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {predicate_string}")
+        };
 
         let query = format!(
-            "SELECT {projection_string} FROM {}.{} {limit_string}",
+            "SELECT {top_string}{projection_string} FROM {}.{}{where_string}",
             self.schema, self.table
         );
+        trace!(%query, "sql server scan query");
 
         Ok(Arc::new(SqlServerExec {
             query,
@@ -334,12 +350,18 @@ impl TableProvider for SqlServerTableProvider {
     async fn insert_into(
         &self,
         _state: &SessionState,
-        _input: Arc<dyn ExecutionPlan>,
-        _overwrite: bool,
+        input: Arc<dyn ExecutionPlan>,
+        overwrite: bool,
     ) -> DatafusionResult<Arc<dyn ExecutionPlan>> {
-        Err(DataFusionError::Execution(
-            "inserts not yet supported for SQL Server".to_string(),
-        ))
+        debug!(schema = %self.schema, table = %self.table, %overwrite, "inserting into sql server datasource");
+
+        Ok(Arc::new(SqlServerInsertExec::new(
+            input,
+            self.access.clone(),
+            self.schema.clone(),
+            self.table.clone(),
+            overwrite,
+        )))
     }
 }
 
@@ -615,6 +637,111 @@ fn rows_to_record_batch(
     Ok(batch)
 }
 
+/// Convert filtering expressions to a predicate string usable with the
+/// generated SQL Server query.
+///
+/// Expressions that can't be converted are skipped. Filters are only ever
+/// pushed down inexactly, so they'll still be applied after the scan.
+fn exprs_to_predicate_string(schema: &ArrowSchema, exprs: &[Expr]) -> Result<String> {
+    let mut ss = Vec::new();
+    for expr in exprs {
+        let mut buf = String::new();
+        if write_expr(expr, schema, &mut buf)? {
+            ss.push(buf);
+        }
+    }
+    Ok(ss.join(" AND "))
+}
+
+/// Returns true if the expression is a string column or literal.
+fn is_string_expr(expr: &Expr, schema: &ArrowSchema) -> bool {
+    match expr {
+        Expr::Column(col) => schema
+            .field_with_name(&col.name)
+            .map(|f| matches!(f.data_type(), DataType::Utf8 | DataType::LargeUtf8))
+            .unwrap_or(false),
+        Expr::Literal(ScalarValue::Utf8(_) | ScalarValue::LargeUtf8(_)) => true,
+        _ => false,
+    }
+}
+
+/// Try to write the expression to the string, returning true if it was written.
+fn write_expr(expr: &Expr, schema: &ArrowSchema, buf: &mut String) -> Result<bool> {
+    match expr {
+        Expr::Column(col) => {
+            write!(buf, "{}", col.name)?;
+        }
+        Expr::Literal(val) => {
+            // Timestamp literals may have more precision than the column
+            // (e.g. 'datetime' only has millisecond precision), which SQL
+            // Server will fail to convert.
+            if matches!(
+                val,
+                ScalarValue::TimestampNanosecond(..) | ScalarValue::TimestampMicrosecond(..)
+            ) {
+                return Ok(false);
+            }
+            if util::encode_literal_to_text(util::Datasource::SqlServer, buf, val).is_err() {
+                return Ok(false);
+            }
+        }
+        Expr::IsNull(expr) => {
+            if write_expr(expr, schema, buf)? {
+                write!(buf, " IS NULL")?;
+            } else {
+                return Ok(false);
+            }
+        }
+        Expr::IsNotNull(expr) => {
+            if write_expr(expr, schema, buf)? {
+                write!(buf, " IS NOT NULL")?;
+            } else {
+                return Ok(false);
+            }
+        }
+        Expr::BinaryExpr(binary)
+            if matches!(
+                binary.op,
+                Operator::Eq
+                    | Operator::NotEq
+                    | Operator::Lt
+                    | Operator::LtEq
+                    | Operator::Gt
+                    | Operator::GtEq
+                    | Operator::And
+                    | Operator::Or
+            ) =>
+        {
+            // String comparisons use the column's collation, which is usually
+            // case insensitive and ignores trailing spaces. Equality under
+            // such a collation still matches every row DataFusion would, so
+            // it's safe to push down since the filter is reapplied. Other
+            // operators may exclude rows that should match.
+            if !matches!(binary.op, Operator::Eq | Operator::And | Operator::Or)
+                && (is_string_expr(&binary.left, schema) || is_string_expr(&binary.right, schema))
+            {
+                return Ok(false);
+            }
+
+            write!(buf, "(")?;
+            if !write_expr(binary.left.as_ref(), schema, buf)? {
+                return Ok(false);
+            }
+            write!(buf, " {} ", binary.op)?;
+            if !write_expr(binary.right.as_ref(), schema, buf)? {
+                return Ok(false);
+            }
+            write!(buf, ")")?;
+        }
+        _ => {
+            // Unsupported.
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
 /// Read a variable width integer from a column value.
 ///
 /// SQL Server has the interesting property where a column can store ints of
@@ -660,3 +787,62 @@ impl<'a> FromSql<'a> for Floatn {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use datafusion::logical_expr::{col, lit};
+
+    use super::*;
+
+    fn test_schema() -> ArrowSchema {
+        ArrowSchema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+            Field::new("c", DataType::Utf8, true),
+            Field::new("d", DataType::Boolean, true),
+            Field::new("e", DataType::Utf8, true),
+        ])
+    }
+
+    #[test]
+    fn valid_expr_string() {
+        let exprs = vec![
+            col("a").lt(col("b")),
+            col("c").eq(lit("hello")).or(col("d").eq(lit(true))),
+            col("e").is_not_null(),
+        ];
+
+        let out = exprs_to_predicate_string(&test_schema(), &exprs).unwrap();
+        assert_eq!(
+            out,
+            "(a < b) AND ((c = 'hello') OR (d = 1)) AND e IS NOT NULL"
+        );
+    }
+
+    #[test]
+    fn skip_unsupported_expr_string() {
+        let exprs = vec![
+            col("a").lt(col("b")),
+            // Not valid for SQL Server.
+            col("c").is_true(),
+            col("d").lt(lit(ScalarValue::TimestampNanosecond(Some(1), None))),
+        ];
+
+        let out = exprs_to_predicate_string(&test_schema(), &exprs).unwrap();
+        assert_eq!(out, "(a < b)");
+    }
+
+    #[test]
+    fn skip_string_comparison_expr_string() {
+        let exprs = vec![
+            col("c").lt(lit("hello")),
+            col("c").not_eq(col("e")),
+            lit("hello").gt_eq(col("e")),
+            col("a").gt(lit(1)).or(col("c").gt(lit("hello"))),
+            col("c").eq(col("e")),
+        ];
+
+        let out = exprs_to_predicate_string(&test_schema(), &exprs).unwrap();
+        assert_eq!(out, "(c = e)");
+    }
+}
@@ -1,4 +1,5 @@
 use clap::Args;
+use pgsrv::auth::PasswordMethod;
 
 use super::*;
 
@@ -30,6 +31,9 @@ pub struct ServerArgs {
     ///
     /// Only has an affect if a password is also provided. If a password is
     /// not provided, the GlareDB server will not prompt for a password.
+    ///
+    /// This user doesn't need a role in the catalog, and is always allowed to
+    /// do anything.
     #[arg(short, long, value_parser, default_value_t = String::from("glaredb"), requires = "password")]
     pub user: String,
 
@@ -39,6 +43,18 @@ pub struct ServerArgs {
     #[arg(short, long, value_parser)]
     pub password: Option<String>,
 
+    /// Method used for authenticating with a password.
+    ///
+    /// One of 'scram-sha-256', 'md5' or 'cleartext'. Only a verifier for the
+    /// password is kept in memory regardless of the method.
+    #[arg(
+        long,
+        value_parser = |s: &str| s.parse::<PasswordMethod>().map_err(|e| e.to_string()),
+        default_value_t = PasswordMethod::ScramSha256,
+        requires = "password"
+    )]
+    pub password_method: PasswordMethod,
+
     /// Optional file path for persisting data.
     ///
     /// Catalog data and user data will be stored in this directory.
@@ -109,4 +125,24 @@ pub struct ServerArgs {
     /// This will fully disable the postgres server on port 6543.
     #[arg(long, default_value="false", action = clap::ArgAction::SetTrue)]
     pub disable_postgres_api: bool,
+
+    /// Seconds a Flight SQL session may be idle before it's closed.
+    ///
+    /// Defaults to 15 minutes.
+    #[arg(long, value_parser)]
+    pub flight_session_idle_timeout: Option<u64>,
+
+    /// Maximum number of open Flight SQL sessions.
+    ///
+    /// New sessions are rejected once this is reached. Defaults to 1024.
+    #[arg(long, value_parser)]
+    pub flight_max_sessions: Option<usize>,
+
+    /// Interval in seconds for compacting and vacuuming native tables in the
+    /// background.
+    ///
+    /// If unset, native tables are only maintained through explicit
+    /// `OPTIMIZE` and `VACUUM` statements.
+    #[arg(long, value_parser)]
+    pub native_maintenance_interval: Option<u64>,
 }
@@ -13,6 +13,7 @@ use pgsrv::auth::{LocalAuthenticator, PasswordlessAuthenticator, SingleUserAuthe
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::runtime::{Builder, Runtime};
 use tracing::info;
@@ -134,6 +135,7 @@ impl RunCommand for ServerArgs {
             metastore_addr,
             user,
             password,
+            password_method,
             data_dir,
             service_account_path,
             storage_config,
@@ -144,6 +146,9 @@ impl RunCommand for ServerArgs {
             enable_simple_query_rpc,
             enable_flight_api,
             disable_postgres_api,
+            flight_session_idle_timeout,
+            flight_max_sessions,
+            native_maintenance_interval,
         } = self;
 
         // Map an empty string to None. Makes writing the terraform easier.
@@ -160,7 +165,11 @@ impl RunCommand for ServerArgs {
         }
 
         let auth: Box<dyn LocalAuthenticator> = match password {
-            Some(password) => Box::new(SingleUserAuthenticator { user, password }),
+            Some(password) => Box::new(SingleUserAuthenticator::new(
+                user.clone(),
+                &password,
+                password_method,
+            )),
             None => Box::new(PasswordlessAuthenticator {
                 drop_auth_messages: ignore_pg_auth,
             }),
@@ -182,6 +191,7 @@ impl RunCommand for ServerArgs {
 
             let server = ComputeServer::builder()
                 .with_authenticator(auth)
+                .with_bootstrap_user(user)
                 .with_pg_listener_opt(pg_listener)
                 .with_rpc_listener_opt(rpc_listener)
                 .with_metastore_addr_opt(metastore_addr)
@@ -194,6 +204,13 @@ impl RunCommand for ServerArgs {
                 .disable_rpc_auth(disable_rpc_auth)
                 .enable_simple_query_rpc(enable_simple_query_rpc)
                 .enable_flight_api(enable_flight_api)
+                .with_flight_session_idle_timeout_opt(
+                    flight_session_idle_timeout.map(Duration::from_secs),
+                )
+                .with_flight_max_sessions_opt(flight_max_sessions)
+                .with_native_maintenance_interval_opt(
+                    native_maintenance_interval.map(Duration::from_secs),
+                )
                 .connect()
                 .await?;
 
@@ -208,7 +208,7 @@ impl LocalSession {
             let stream = self.sess.execute_portal(&UNNAMED, 0).await?;
 
             match stream {
-                ExecutionResult::Query { stream, .. } => {
+                ExecutionResult::Query { stream, .. } | ExecutionResult::Fetch { stream } => {
                     print_stream(
                         stream,
                         self.opts.mode,
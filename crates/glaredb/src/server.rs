@@ -1,15 +1,17 @@
 use anyhow::{anyhow, Result};
 use metastore::util::MetastoreClientMode;
-use pgsrv::auth::LocalAuthenticator;
+use pgsrv::auth::{LocalAuthenticator, PasswordMethod, SingleUserAuthenticator};
 use pgsrv::handler::{ProtocolHandler, ProtocolHandlerConfig};
 use protogen::gen::rpcsrv::service::execution_service_server::ExecutionServiceServer;
 use protogen::gen::rpcsrv::simple::simple_service_server::SimpleServiceServer;
-use rpcsrv::flight::handler::{FlightServiceServer, FlightSessionHandler};
+use rpcsrv::flight::handler::{FlightServiceServer, FlightSessionHandler, FlightSqlServer};
+use rpcsrv::flight::session::FlightSessionConfig;
 use rpcsrv::{handler::RpcHandler, simple::SimpleHandler};
 use sqlexec::engine::{Engine, EngineStorageConfig};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use std::{env, fs};
 use telemetry::{SegmentTracker, Tracker};
 use tokio::net::TcpListener;
@@ -33,6 +35,7 @@ pub struct ComputeServer {
     disable_rpc_auth: bool,
     enable_simple_query_rpc: bool,
     enable_flight_api: bool,
+    flight_session_conf: FlightSessionConfig,
     engine: Arc<Engine>,
     pg_config: Option<PostgresProtocolConfig>,
     rpc_listener: Option<TcpListener>,
@@ -46,6 +49,7 @@ pub struct ComputeServerBuilder {
     metastore_addr: Option<String>,
     segment_key: Option<String>,
     authenticator: Option<Box<dyn LocalAuthenticator>>,
+    bootstrap_user: Option<String>,
     data_dir: Option<PathBuf>,
     service_account_path: Option<String>,
     location: Option<String>,
@@ -55,6 +59,8 @@ pub struct ComputeServerBuilder {
     disable_rpc_auth: bool,
     enable_simple_query_rpc: bool,
     enable_flight_api: bool,
+    flight_session_conf: FlightSessionConfig,
+    native_maintenance_interval: Option<Duration>,
 }
 
 impl ComputeServerBuilder {
@@ -65,6 +71,7 @@ impl ComputeServerBuilder {
             metastore_addr: None,
             segment_key: None,
             authenticator: None,
+            bootstrap_user: None,
             data_dir: None,
             service_account_path: None,
             location: None,
@@ -74,6 +81,8 @@ impl ComputeServerBuilder {
             disable_rpc_auth: false,
             enable_simple_query_rpc: false,
             enable_flight_api: false,
+            flight_session_conf: FlightSessionConfig::default(),
+            native_maintenance_interval: None,
         }
     }
     /// Set the authenticator to use for the pg handler.
@@ -81,6 +90,20 @@ impl ComputeServerBuilder {
         self.authenticator = Some(Box::new(authenticator));
         self
     }
+    /// Require a single user and password for the pg handler, using the given
+    /// method to authenticate the user.
+    ///
+    /// Only a verifier for the password is kept.
+    pub fn with_password_auth(self, user: String, password: &str, method: PasswordMethod) -> Self {
+        let auth = SingleUserAuthenticator::new(user.clone(), password, method);
+        self.with_authenticator(auth).with_bootstrap_user(user)
+    }
+    /// Set the user that's allowed to do anything without having a role in
+    /// the catalog. Defaults to "glaredb".
+    pub fn with_bootstrap_user(mut self, user: String) -> Self {
+        self.bootstrap_user = Some(user);
+        self
+    }
     /// Add a tcp listener to use for serving over the pg protocol.
     pub fn with_pg_listener(mut self, pg_listener: TcpListener) -> Self {
         self.pg_listener = Some(pg_listener);
@@ -173,12 +196,34 @@ impl ComputeServerBuilder {
         self.enable_flight_api = enable_flight_api;
         self
     }
+    /// Optionally override how long Flight SQL sessions may be idle before
+    /// being closed.
+    pub fn with_flight_session_idle_timeout_opt(mut self, timeout: Option<Duration>) -> Self {
+        if let Some(timeout) = timeout {
+            self.flight_session_conf.idle_timeout = timeout;
+        }
+        self
+    }
+    /// Optionally override the maximum number of open Flight SQL sessions.
+    pub fn with_flight_max_sessions_opt(mut self, max_sessions: Option<usize>) -> Self {
+        if let Some(max_sessions) = max_sessions {
+            self.flight_session_conf.max_sessions = max_sessions;
+        }
+        self
+    }
+    /// Optionally compact and vacuum native tables in the background at the
+    /// given interval.
+    pub fn with_native_maintenance_interval_opt(mut self, interval: Option<Duration>) -> Self {
+        self.native_maintenance_interval = interval;
+        self
+    }
 
     pub async fn connect(self) -> Result<ComputeServer> {
         let ComputeServerBuilder {
             metastore_addr,
             segment_key,
             authenticator,
+            bootstrap_user,
             data_dir,
             service_account_path,
             location,
@@ -190,6 +235,8 @@ impl ComputeServerBuilder {
             pg_listener,
             rpc_listener,
             enable_flight_api,
+            flight_session_conf,
+            native_maintenance_interval,
         } = self;
 
         // Invalid state if we have a pg_listener but no authenticator.
@@ -225,6 +272,15 @@ impl ComputeServerBuilder {
             spill_path,
         )
         .await?;
+        let engine = Arc::new(match bootstrap_user {
+            Some(user) => engine.with_bootstrap_user(user),
+            None => engine,
+        });
+
+        if let Some(interval) = native_maintenance_interval {
+            info!(?interval, "starting background native table maintenance");
+            engine.start_native_table_maintenance(interval);
+        }
 
         let pg_config = if let Some(listener) = pg_listener {
             let handler_conf = ProtocolHandlerConfig {
@@ -250,6 +306,7 @@ impl ComputeServerBuilder {
             disable_rpc_auth,
             enable_simple_query_rpc,
             enable_flight_api,
+            flight_session_conf,
             pg_config,
             engine,
             rpc_listener,
@@ -265,13 +322,13 @@ async fn create_engine_from_opts(
     data_dir: Option<PathBuf>,
     service_account_path: Option<String>,
     spill_path: Option<PathBuf>,
-) -> Result<Arc<Engine>, anyhow::Error> {
+) -> Result<Engine, anyhow::Error> {
     let engine = if let Some(location) = location {
         // TODO: try to consolidate with --data-dir and --metastore-addr options
         let engine =
             Engine::from_storage_options(&location, &HashMap::from_iter(storage_options.clone()))
                 .await?;
-        Arc::new(engine.with_tracker(Arc::new(tracker)))
+        engine.with_tracker(Arc::new(tracker))
     } else {
         // Connect to metastore.
         let mode = match (metastore_addr, &data_dir) {
@@ -305,15 +362,13 @@ async fn create_engine_from_opts(
             }
         };
 
-        Arc::new(
-            Engine::new(
-                metastore_client,
-                storage_conf,
-                Arc::new(tracker),
-                spill_path,
-            )
-            .await?,
+        Engine::new(
+            metastore_client,
+            storage_conf,
+            Arc::new(tracker),
+            spill_path,
         )
+        .await?
     };
     Ok(engine)
 }
@@ -336,8 +391,13 @@ impl ComputeServer {
 
         if self.enable_flight_api {
             info!("enabling flight sql service");
-            let flight_handler = FlightSessionHandler::new(self.engine.clone());
-            server = server.add_service(FlightServiceServer::new(flight_handler));
+            let flight_handler = Arc::new(FlightSessionHandler::new(
+                self.engine.clone(),
+                self.flight_session_conf,
+            ));
+            flight_handler.start_session_expiry();
+            let flight_server = FlightSqlServer::new(flight_handler);
+            server = server.add_service(FlightServiceServer::new(flight_server));
         }
         // Add in the simple interface if requested.
         if self.enable_simple_query_rpc {
@@ -469,7 +529,6 @@ fn spawn_shutdown_handler(
 mod tests {
     use std::time::Duration;
 
-    use pgsrv::auth::SingleUserAuthenticator;
     use tokio_postgres::{Config as ClientConfig, NoTls};
 
     use super::*;
@@ -492,10 +551,11 @@ mod tests {
         let pg_addr = pg_listener.local_addr().unwrap();
 
         let server = ComputeServer::builder()
-            .with_authenticator(SingleUserAuthenticator {
-                user: "glaredb".to_string(),
-                password: "glaredb".to_string(),
-            })
+            .with_password_auth(
+                "glaredb".to_string(),
+                "glaredb",
+                PasswordMethod::ScramSha256,
+            )
             .with_pg_listener(pg_listener)
             .with_rpc_listener(rpc_listener)
             .connect()
@@ -526,4 +586,48 @@ mod tests {
             .unwrap() // Timeout error
             .unwrap(); // Query error
     }
+
+    #[tokio::test]
+    async fn password_methods() {
+        for method in [
+            PasswordMethod::Cleartext,
+            PasswordMethod::Md5,
+            PasswordMethod::ScramSha256,
+        ] {
+            let pg_listener = TcpListener::bind("localhost:0").await.unwrap();
+            let pg_addr = pg_listener.local_addr().unwrap();
+
+            let server = ComputeServer::builder()
+                .with_password_auth("glaredb".to_string(), "glaredb", method)
+                .with_pg_listener(pg_listener)
+                .connect()
+                .await
+                .unwrap();
+
+            tokio::spawn(server.serve());
+
+            let connect = |password: &'static str| async move {
+                let mut config = ClientConfig::new();
+                config
+                    .user("glaredb")
+                    .password(password)
+                    .dbname("glaredb")
+                    .host("localhost")
+                    .port(pg_addr.port());
+                tokio::time::timeout(Duration::from_secs(5), config.connect(NoTls)).await
+            };
+
+            let (client, conn) = connect("glaredb")
+                .await
+                .unwrap() // Timeout error
+                .unwrap_or_else(|e| panic!("connect with {method}: {e}"));
+            tokio::spawn(conn);
+            client.simple_query("select 1").await.unwrap();
+
+            connect("wrong")
+                .await
+                .unwrap() // Timeout error
+                .expect_err("wrong password should fail");
+        }
+    }
 }
@@ -5,12 +5,15 @@ use once_cell::sync::Lazy;
 use pgrepr::oid::FIRST_AVAILABLE_ID;
 use protogen::metastore::types::catalog::{
     CatalogEntry, CatalogState, CredentialsEntry, DatabaseEntry, DeploymentMetadata, EntryMeta,
-    EntryType, FunctionEntry, SchemaEntry, SourceAccessMode, TableEntry, TunnelEntry, ViewEntry,
+    EntryType, FunctionEntry, Privilege, RoleEntry, RoleGrant, SchemaEntry, SourceAccessMode,
+    TableEntry, TunnelEntry, ViewEntry,
 };
 use protogen::metastore::types::options::{
     DatabaseOptions, DatabaseOptionsInternal, TableOptions, TunnelOptions,
 };
-use protogen::metastore::types::service::{AlterDatabaseOperation, AlterTableOperation, Mutation};
+use protogen::metastore::types::service::{
+    AlterDatabaseOperation, AlterTableOperation, Mutation, PrivilegeObject,
+};
 use protogen::metastore::types::storage::{ExtraState, PersistedCatalog};
 use sqlbuiltins::builtins::{
     BuiltinDatabase, BuiltinSchema, BuiltinTable, BuiltinView, DATABASE_DEFAULT, DEFAULT_SCHEMA,
@@ -152,6 +155,42 @@ impl DatabaseCatalog {
         Ok(updated)
     }
 
+    /// Validate mutations against the catalog without persisting them.
+    ///
+    /// Errors if the provided version doesn't match the version of the current
+    /// catalog, or if any of the mutations would fail.
+    ///
+    /// On success, the catalog state as it would look with the mutations
+    /// applied is returned. The cached state is left untouched.
+    pub async fn dry_run_mutate(
+        &self,
+        version: u64,
+        mutations: Vec<Mutation>,
+    ) -> Result<CatalogState> {
+        debug!(db_id = %self.db_id, %version, ?mutations, "dry run mutating catalog");
+
+        self.load_latest().await?;
+
+        let mut state = {
+            let cached = self.cached.lock().await;
+            if cached.version != version {
+                return Err(MetastoreError::VersionMismatch {
+                    have: version,
+                    need: cached.version,
+                });
+            }
+            cached.clone()
+        };
+
+        state.mutate(mutations)?;
+
+        Ok(CatalogState {
+            version: state.version,
+            entries: state.entries.as_ref().clone(),
+            deployment: state.deployment.clone(),
+        })
+    }
+
     /// Return the serializable state of the catalog at this version.
     fn serializable_state(&self, guard: MutexGuard<State>) -> CatalogState {
         CatalogState {
@@ -299,7 +338,7 @@ impl CreatePolicy {
 }
 
 /// Inner state of the catalog.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct State {
     /// Version incremented on every update.
     version: u64,
@@ -315,6 +354,8 @@ struct State {
     tunnel_names: HashMap<String, u32>,
     /// Map credentials names to their ids.
     credentials_names: HashMap<String, u32>,
+    /// Map role names to their ids.
+    role_names: HashMap<String, u32>,
     /// Map schema names to their ids.
     schema_names: HashMap<String, u32>,
     /// Map schema IDs to objects in the schema.
@@ -333,6 +374,7 @@ impl State {
         let mut database_names = HashMap::new();
         let mut tunnel_names = HashMap::new();
         let mut credentials_names = HashMap::new();
+        let mut role_names = HashMap::new();
         let mut schema_names = HashMap::new();
         let mut schema_objects = HashMap::new();
 
@@ -395,6 +437,17 @@ impl State {
 
                     credentials_names.insert(creds.meta.name.clone(), *oid);
                 }
+                CatalogEntry::Role(role) => {
+                    if role.meta.parent != DATABASE_PARENT_ID {
+                        return Err(MetastoreError::ObjectHasNonZeroParent {
+                            object: *oid,
+                            parent: role.meta.parent,
+                            object_type: "role",
+                        });
+                    }
+
+                    role_names.insert(role.meta.name.clone(), *oid);
+                }
                 CatalogEntry::Schema(schema) => {
                     if schema.meta.parent == DATABASE_PARENT_ID {
                         return Err(MetastoreError::ObjectHasInvalidParentId {
@@ -461,6 +514,7 @@ impl State {
             database_names,
             tunnel_names,
             credentials_names,
+            role_names,
             schema_names,
             schema_objects,
         };
@@ -531,6 +585,8 @@ impl State {
             self.mutate_one(mutation)?;
         }
 
+        self.prune_role_grants()?;
+
         Ok(())
     }
 
@@ -568,6 +624,16 @@ impl State {
 
                 self.entries.remove(&credentials_id)?.unwrap();
             }
+            Mutation::DropRole(drop_role) => {
+                let if_exists = drop_role.if_exists;
+                let role_id = match self.role_names.remove(&drop_role.name) {
+                    None if if_exists => return Ok(()),
+                    None => return Err(MetastoreError::MissingRole(drop_role.name)),
+                    Some(id) => id,
+                };
+
+                self.entries.remove(&role_id)?.unwrap();
+            }
             Mutation::DropSchema(drop_schema) => {
                 let if_exists = drop_schema.if_exists;
                 let schema_id = match self.schema_names.remove(&drop_schema.name) {
@@ -774,6 +840,77 @@ impl State {
                 // Add to creadentials map
                 self.credentials_names.insert(create_credential.name, oid);
             }
+            Mutation::CreateRole(create_role) => {
+                validate_object_name(&create_role.name)?;
+                match self.role_names.get(&create_role.name) {
+                    Some(_) if create_role.if_not_exists => return Ok(()), // Already exists, nothing to do.
+                    Some(_) => return Err(MetastoreError::DuplicateName(create_role.name)),
+                    None => (),
+                }
+
+                // Create new entry
+                let oid = self.next_oid();
+                let ent = RoleEntry {
+                    meta: EntryMeta {
+                        entry_type: EntryType::Role,
+                        id: oid,
+                        // Roles are shared across the database and don't have a
+                        // parent.
+                        parent: DATABASE_PARENT_ID,
+                        name: create_role.name.clone(),
+                        builtin: false,
+                        external: false,
+                        is_temp: false,
+                    },
+                    password_verifier: create_role.password_verifier,
+                    login: create_role.login,
+                    superuser: create_role.superuser,
+                    grants: Vec::new(),
+                };
+                self.entries.insert(oid, CatalogEntry::Role(ent))?;
+
+                // Add to role map
+                self.role_names.insert(create_role.name, oid);
+            }
+            Mutation::GrantPrivileges(grant) => {
+                let object_ids =
+                    self.resolve_privilege_objects(&grant.objects, &grant.privileges)?;
+                for role in grant.roles {
+                    let role = self.get_role_mut(role)?;
+                    for object_id in &object_ids {
+                        let idx = match role.grants.iter().position(|g| g.object_id == *object_id) {
+                            Some(idx) => idx,
+                            None => {
+                                role.grants.push(RoleGrant {
+                                    object_id: *object_id,
+                                    privileges: Vec::new(),
+                                });
+                                role.grants.len() - 1
+                            }
+                        };
+
+                        let privileges = &mut role.grants[idx].privileges;
+                        privileges.extend_from_slice(&grant.privileges);
+                        privileges.sort();
+                        privileges.dedup();
+                    }
+                }
+            }
+            Mutation::RevokePrivileges(revoke) => {
+                let object_ids =
+                    self.resolve_privilege_objects(&revoke.objects, &revoke.privileges)?;
+                for role in revoke.roles {
+                    let role = self.get_role_mut(role)?;
+                    for grant in role.grants.iter_mut() {
+                        if object_ids.contains(&grant.object_id) {
+                            grant
+                                .privileges
+                                .retain(|privilege| !revoke.privileges.contains(privilege));
+                        }
+                    }
+                    role.grants.retain(|grant| !grant.privileges.is_empty());
+                }
+            }
             Mutation::CreateSchema(create_schema) => {
                 validate_object_name(&create_schema.name)?;
 
@@ -1081,6 +1218,84 @@ impl State {
         Ok(())
     }
 
+    /// Remove grants on objects that no longer exist from all roles.
+    fn prune_role_grants(&mut self) -> Result<()> {
+        let role_ids: Vec<_> = self.role_names.values().copied().collect();
+        for role_id in role_ids {
+            let stale: Vec<_> = match self.entries.get(&role_id)? {
+                Some(CatalogEntry::Role(role)) => role
+                    .grants
+                    .iter()
+                    .map(|grant| grant.object_id)
+                    .filter(|id| !self.entries.as_ref().contains_key(id))
+                    .collect(),
+                _ => continue,
+            };
+            if stale.is_empty() {
+                continue;
+            }
+
+            if let Some(CatalogEntry::Role(role)) = self.entries.get_mut(&role_id)? {
+                role.grants
+                    .retain(|grant| !stale.contains(&grant.object_id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve the ids of objects privileges are being granted on (or revoked
+    /// from), checking that the privileges apply to the type of object.
+    fn resolve_privilege_objects(
+        &self,
+        objects: &[PrivilegeObject],
+        privileges: &[Privilege],
+    ) -> Result<Vec<u32>> {
+        let check_privileges = |allowed: &[Privilege], object_type| {
+            if let Some(privilege) = privileges.iter().find(|p| !allowed.contains(p)) {
+                return Err(MetastoreError::InvalidPrivilegeForObject {
+                    privilege: *privilege,
+                    object_type,
+                });
+            }
+            Ok(())
+        };
+
+        let mut ids = Vec::with_capacity(objects.len());
+        for object in objects {
+            let schema_id = self.get_schema_id(&object.schema)?;
+            match &object.name {
+                Some(name) => {
+                    check_privileges(Privilege::TABLE_PRIVILEGES, "table")?;
+                    let id = self
+                        .schema_objects
+                        .get(&schema_id)
+                        .and_then(|objs| objs.tables.get(name))
+                        .ok_or_else(|| MetastoreError::MissingNamedObject {
+                            schema: object.schema.clone(),
+                            name: name.clone(),
+                        })?;
+                    ids.push(*id);
+                }
+                None => {
+                    check_privileges(Privilege::SCHEMA_PRIVILEGES, "schema")?;
+                    ids.push(schema_id);
+                }
+            }
+        }
+        Ok(ids)
+    }
+
+    fn get_role_mut(&mut self, name: String) -> Result<&mut RoleEntry> {
+        let oid = match self.role_names.get(&name) {
+            Some(oid) => *oid,
+            None => return Err(MetastoreError::MissingRole(name)),
+        };
+        match self.entries.get_mut(&oid)?.expect("entry should exist") {
+            CatalogEntry::Role(role) => Ok(role),
+            ent => unreachable!("entry should be a role entry but found: {ent:?}"),
+        }
+    }
+
     fn get_schema_id(&self, name: &str) -> Result<u32> {
         self.schema_names
             .get(name)
@@ -1383,9 +1598,10 @@ mod tests {
     use protogen::metastore::types::service::AlterDatabase;
     use protogen::metastore::types::service::DropDatabase;
     use protogen::metastore::types::service::{
-        CreateExternalDatabase, CreateExternalTable, CreateSchema, CreateView, DropSchema,
+        CreateExternalDatabase, CreateExternalTable, CreateRole, CreateSchema, CreateView,
+        DropObject, DropSchema, GrantPrivileges, RevokePrivileges,
     };
-    use sqlbuiltins::builtins::DEFAULT_CATALOG;
+    use sqlbuiltins::builtins::{DEFAULT_CATALOG, SCHEMA_DEFAULT};
     use std::collections::HashSet;
 
     async fn new_catalog() -> DatabaseCatalog {
@@ -1466,6 +1682,53 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn dry_run_not_persisted() {
+        let db = new_catalog().await;
+        let initial = version(&db).await;
+
+        let create_then_drop = vec![
+            Mutation::CreateSchema(CreateSchema {
+                name: "mushroom".to_string(),
+                if_not_exists: false,
+            }),
+            Mutation::DropSchema(DropSchema {
+                name: "mushroom".to_string(),
+                if_exists: false,
+                cascade: false,
+            }),
+            Mutation::CreateSchema(CreateSchema {
+                name: "mushroom".to_string(),
+                if_not_exists: false,
+            }),
+        ];
+
+        let dry_run = db
+            .dry_run_mutate(initial, create_then_drop.clone())
+            .await
+            .unwrap();
+        assert!(dry_run
+            .entries
+            .values()
+            .any(|ent| ent.get_meta().name == "mushroom"));
+
+        // Nothing changed.
+        let state = db.get_state().await.unwrap();
+        assert_eq!(initial, state.version);
+        assert!(!state
+            .entries
+            .values()
+            .any(|ent| ent.get_meta().name == "mushroom"));
+
+        // Applying the same mutations for real results in the same catalog.
+        let applied = db.try_mutate(initial, create_then_drop).await.unwrap();
+        assert_eq!(dry_run.version, applied.version);
+        assert_eq!(dry_run.entries, applied.entries);
+
+        // Stale versions are rejected.
+        db.dry_run_mutate(initial, Vec::new()).await.unwrap_err();
+    }
+
     #[tokio::test]
     async fn multiple_entries() {
         let db = new_catalog().await;
@@ -1956,4 +2219,109 @@ mod tests {
             .await
             .unwrap();
     }
+
+    fn get_role<'a>(state: &'a CatalogState, name: &str) -> &'a RoleEntry {
+        state
+            .entries
+            .values()
+            .find_map(|ent| match ent {
+                CatalogEntry::Role(role) if role.meta.name == name => Some(role),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn grant_and_revoke_privileges() {
+        let db = new_catalog().await;
+
+        let view = |name: &str| CreateView {
+            schema: "public".to_string(),
+            name: name.to_string(),
+            sql: "select 1".to_string(),
+            or_replace: false,
+            columns: Vec::new(),
+        };
+        let object = |name: Option<&str>| PrivilegeObject {
+            schema: "public".to_string(),
+            name: name.map(String::from),
+        };
+
+        let state = db
+            .try_mutate(
+                version(&db).await,
+                vec![
+                    Mutation::CreateRole(CreateRole {
+                        name: "analyst".to_string(),
+                        password_verifier: String::new(),
+                        login: true,
+                        superuser: false,
+                        if_not_exists: false,
+                    }),
+                    Mutation::CreateView(view("v1")),
+                    Mutation::CreateView(view("v2")),
+                    Mutation::GrantPrivileges(GrantPrivileges {
+                        roles: vec!["analyst".to_string()],
+                        objects: vec![object(None)],
+                        privileges: vec![Privilege::Usage],
+                    }),
+                    Mutation::GrantPrivileges(GrantPrivileges {
+                        roles: vec!["analyst".to_string()],
+                        objects: vec![object(Some("v1")), object(Some("v2"))],
+                        privileges: vec![Privilege::Select, Privilege::Insert],
+                    }),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let role = get_role(&state, "analyst");
+        assert_eq!(3, role.grants.len());
+
+        let public = SCHEMA_DEFAULT.oid;
+        assert!(role.has_privilege(public, Privilege::Usage));
+        assert!(!role.has_privilege(public, Privilege::Create));
+
+        // Schema privileges can't be granted on tables, and vice versa.
+        db.try_mutate(
+            state.version,
+            vec![Mutation::GrantPrivileges(GrantPrivileges {
+                roles: vec!["analyst".to_string()],
+                objects: vec![object(None)],
+                privileges: vec![Privilege::Select],
+            })],
+        )
+        .await
+        .unwrap_err();
+
+        // Revoking all privileges on an object removes the grant, and dropping
+        // an object removes grants on it.
+        let state = db
+            .try_mutate(
+                version(&db).await,
+                vec![
+                    Mutation::RevokePrivileges(RevokePrivileges {
+                        roles: vec!["analyst".to_string()],
+                        objects: vec![object(Some("v1")), object(Some("v2"))],
+                        privileges: vec![Privilege::Insert],
+                    }),
+                    Mutation::RevokePrivileges(RevokePrivileges {
+                        roles: vec!["analyst".to_string()],
+                        objects: vec![object(None)],
+                        privileges: vec![Privilege::Usage],
+                    }),
+                    Mutation::DropObject(DropObject {
+                        schema: "public".to_string(),
+                        name: "v2".to_string(),
+                        if_exists: false,
+                    }),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let role = get_role(&state, "analyst");
+        assert_eq!(1, role.grants.len());
+        assert_eq!(vec![Privilege::Select], role.grants[0].privileges);
+    }
 }
@@ -42,6 +42,15 @@ pub enum MetastoreError {
     #[error("Missing credentials: {0}")]
     MissingCredentials(String),
 
+    #[error("Missing role: {0}")]
+    MissingRole(String),
+
+    #[error("Privilege {privilege} cannot be granted on a {object_type}")]
+    InvalidPrivilegeForObject {
+        privilege: protogen::metastore::types::catalog::Privilege,
+        object_type: &'static str,
+    },
+
     #[error("Missing schema: {0}")]
     MissingNamedSchema(String),
 
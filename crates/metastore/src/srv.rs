@@ -103,7 +103,13 @@ impl MetastoreService for Service {
 
         // TODO: Catch error and return status.
 
-        let updated = catalog.try_mutate(req.catalog_version, mutations).await?;
+        let updated = if req.dry_run {
+            catalog
+                .dry_run_mutate(req.catalog_version, mutations)
+                .await?
+        } else {
+            catalog.try_mutate(req.catalog_version, mutations).await?
+        };
 
         Ok(Response::new(MutateResponse {
             status: service::mutate_response::Status::Applied as i32,
@@ -159,6 +165,7 @@ mod tests {
             })
             .try_into()
             .unwrap()],
+            dry_run: false,
         }))
         .await
         .unwrap();
@@ -69,10 +69,24 @@ impl TryFrom<(char, Message)> for SerializedMessage {
                     tag: msg.tag()?.to_string(),
                 })?,
             ),
+            Message::CopyInResponse(msg) => (
+                "CopyInResponse",
+                serde_json::to_string(&CopyInResponse {
+                    format: msg.format(),
+                    column_formats: msg.column_formats().collect()?,
+                })?,
+            ),
+            Message::ParameterDescription(msg) => (
+                "ParameterDescription",
+                serde_json::to_string(&ParameterDescription {
+                    parameters: msg.parameters().collect()?,
+                })?,
+            ),
             Message::ParseComplete => ("ParseComplete", String::new()),
             Message::BindComplete => ("BindComplete", String::new()),
             Message::CloseComplete => ("CloseComplete", String::new()),
             Message::NoData => ("NoData", String::new()),
+            Message::PortalSuspended => ("PortalSuspended", String::new()),
             Message::EmptyQueryResponse => ("EmptyQueryResponse", String::new()),
             Message::ErrorResponse(msg) => (
                 "ErrorResponse",
@@ -106,6 +120,7 @@ pub struct Query {
 pub struct Parse {
     pub name: Option<String>,
     pub query: String,
+    pub param_types: Option<Vec<u32>>,
 }
 
 #[derive(Deserialize)]
@@ -132,6 +147,16 @@ pub struct ClosePortal {
     pub name: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct CopyData {
+    pub data: String,
+}
+
+#[derive(Deserialize)]
+pub struct CopyFail {
+    pub message: String,
+}
+
 #[derive(Deserialize)]
 pub struct Describe {
     pub variant: Option<String>,
@@ -165,6 +190,12 @@ pub struct ParameterDescription {
     pub parameters: Vec<u32>,
 }
 
+#[derive(Serialize)]
+pub struct CopyInResponse {
+    pub format: u8,
+    pub column_formats: Vec<u16>,
+}
+
 #[derive(Serialize)]
 pub struct CommandComplete {
     pub tag: String,
@@ -33,6 +33,7 @@ pub fn walk(
             match testcase.directive.as_str() {
                 "send" => run_send(&mut conn, &testcase.args, &testcase.input, verbose),
                 "until" => run_until(&mut conn, &testcase.args, &testcase.input, timeout, verbose),
+                "sleep" => run_sleep(&testcase.args),
                 unknown => panic!("unknown directive: {}", unknown),
             }
         });
@@ -65,7 +66,12 @@ fn run_send(
             }
             "Parse" => {
                 let val: Parse = serde_json::from_str(json)?;
-                frontend::parse(&val.name.unwrap_or_default(), &val.query, Vec::new(), buf)?;
+                frontend::parse(
+                    &val.name.unwrap_or_default(),
+                    &val.query,
+                    val.param_types.unwrap_or_default(),
+                    buf,
+                )?;
                 Ok(())
             }
             "Bind" => {
@@ -88,6 +94,16 @@ fn run_send(
                 })?;
                 Ok(())
             }
+            "Describe" => {
+                let val: Describe = serde_json::from_str(json)?;
+                let variant = match val.variant.as_deref() {
+                    None | Some("S") => b'S',
+                    Some("P") => b'P',
+                    Some(other) => return Err(anyhow!("invalid describe variant: {}", other)),
+                };
+                frontend::describe(variant, &val.name.unwrap_or_default(), buf)?;
+                Ok(())
+            }
             "Execute" => {
                 let val: Execute = serde_json::from_str(json)?;
                 frontend::execute(
@@ -111,6 +127,20 @@ fn run_send(
                 frontend::sync(buf);
                 Ok(())
             }
+            "CopyData" => {
+                let val: CopyData = serde_json::from_str(json)?;
+                frontend::CopyData::new(val.data.as_bytes())?.write(buf);
+                Ok(())
+            }
+            "CopyDone" => {
+                frontend::copy_done(buf);
+                Ok(())
+            }
+            "CopyFail" => {
+                let val: CopyFail = serde_json::from_str(json)?;
+                frontend::copy_fail(&val.message, buf)?;
+                Ok(())
+            }
             unknown => panic!("unknown type: {}", unknown),
         })
         .unwrap();
@@ -118,6 +148,21 @@ fn run_send(
     "".to_string()
 }
 
+/// Run the "sleep" directive.
+///
+/// Sleeps for the number of milliseconds provided with 'ms=<millis>'. Useful
+/// for letting timeouts pass between messages. No output is expected.
+fn run_sleep(args: &HashMap<String, Vec<String>>) -> String {
+    let ms: u64 = args
+        .get("ms")
+        .and_then(|vals| vals.first())
+        .expect("sleep requires 'ms'")
+        .parse()
+        .expect("'ms' should be a number");
+    std::thread::sleep(Duration::from_millis(ms));
+    "".to_string()
+}
+
 /// Run the "until" directive.
 ///
 /// Continually reads messages from the connection until either all expected
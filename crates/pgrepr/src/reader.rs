@@ -58,6 +58,54 @@ impl Reader for TextReader {
     }
 }
 
+/// Reader for values in Postgres' binary format.
+///
+/// All values are in network byte order.
+#[derive(Debug)]
+pub struct BinaryReader;
+
+impl BinaryReader {
+    fn read_exact<const N: usize>(buf: &[u8]) -> Result<[u8; N]> {
+        buf.try_into().map_err(|_| {
+            PgReprError::InternalError(format!(
+                "invalid binary value length, expected {N} bytes, got {}",
+                buf.len()
+            ))
+        })
+    }
+}
+
+impl Reader for BinaryReader {
+    fn read_bool(buf: &[u8]) -> Result<bool> {
+        let [b] = Self::read_exact::<1>(buf)?;
+        Ok(b != 0)
+    }
+
+    fn read_int2(buf: &[u8]) -> Result<i16> {
+        Ok(i16::from_be_bytes(Self::read_exact(buf)?))
+    }
+
+    fn read_int4(buf: &[u8]) -> Result<i32> {
+        Ok(i32::from_be_bytes(Self::read_exact(buf)?))
+    }
+
+    fn read_int8(buf: &[u8]) -> Result<i64> {
+        Ok(i64::from_be_bytes(Self::read_exact(buf)?))
+    }
+
+    fn read_float4(buf: &[u8]) -> Result<f32> {
+        Ok(f32::from_be_bytes(Self::read_exact(buf)?))
+    }
+
+    fn read_float8(buf: &[u8]) -> Result<f64> {
+        Ok(f64::from_be_bytes(Self::read_exact(buf)?))
+    }
+
+    fn read_text(buf: &[u8]) -> Result<String> {
+        Ok(std::str::from_utf8(buf)?.to_string())
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 #[error("String was not 't', 'true', 'f', or 'false'")]
 struct ParseSqlBoolError;
@@ -95,4 +143,18 @@ mod tests {
 
         let _ = TextReader::read_bool("none".as_bytes()).unwrap_err();
     }
+
+    #[test]
+    fn read_binary() {
+        assert!(BinaryReader::read_bool(&[1]).unwrap());
+        assert_eq!(-2, BinaryReader::read_int2(&[0xff, 0xfe]).unwrap());
+        assert_eq!(256, BinaryReader::read_int4(&[0, 0, 1, 0]).unwrap());
+        assert_eq!(
+            1.5,
+            BinaryReader::read_float8(&1.5_f64.to_be_bytes()).unwrap()
+        );
+        assert_eq!("glare", BinaryReader::read_text(b"glare").unwrap());
+
+        let _ = BinaryReader::read_int4(&[0, 1]).unwrap_err();
+    }
 }
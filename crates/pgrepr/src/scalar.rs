@@ -16,7 +16,7 @@ use tokio_postgres::types::Type as PgType;
 use crate::{
     error::{PgReprError, Result},
     format::Format,
-    reader::TextReader,
+    reader::{BinaryReader, TextReader},
     writer::{BinaryWriter, TextWriter},
 };
 
@@ -117,7 +117,7 @@ impl Scalar {
     pub fn decode_with_format(format: Format, buf: &[u8], as_type: &PgType) -> Result<Self> {
         match format {
             Format::Text => Self::decode::<TextReader>(buf, as_type),
-            Format::Binary => Err(PgReprError::UnsupportedPgTypeForDecode(as_type.to_owned())),
+            Format::Binary => Self::decode::<BinaryReader>(buf, as_type),
         }
     }
 
@@ -132,7 +132,9 @@ impl Scalar {
             PgType::INT8 => Self::Int8(R::read_int8(buf)?),
             PgType::FLOAT4 => Self::Float4(R::read_float4(buf)?),
             PgType::FLOAT8 => Self::Float8(R::read_float8(buf)?),
-            PgType::TEXT => Self::Text(R::read_text(buf)?),
+            PgType::TEXT | PgType::VARCHAR | PgType::BPCHAR | PgType::NAME | PgType::UNKNOWN => {
+                Self::Text(R::read_text(buf)?)
+            }
             _ => return Err(PgReprError::UnsupportedPgTypeForDecode(as_type.clone())),
         };
         Ok(scalar)
@@ -30,3 +30,21 @@ pub fn arrow_to_pg_type(df_type: &ArrowType, type_hint: Option<PgType>) -> PgTyp
         _ => return PgType::TEXT,
     })
 }
+
+/// Returns the arrow type to use for values of the postgres type.
+///
+/// Returns `None` for postgres types we can't decode values for.
+pub fn pg_to_arrow_type(pg_type: &PgType) -> Option<ArrowType> {
+    Some(match *pg_type {
+        PgType::BOOL => ArrowType::Boolean,
+        PgType::INT2 => ArrowType::Int16,
+        PgType::INT4 => ArrowType::Int32,
+        PgType::INT8 => ArrowType::Int64,
+        PgType::FLOAT4 => ArrowType::Float32,
+        PgType::FLOAT8 => ArrowType::Float64,
+        PgType::TEXT | PgType::VARCHAR | PgType::BPCHAR | PgType::NAME | PgType::UNKNOWN => {
+            ArrowType::Utf8
+        }
+        _ => return None,
+    })
+}
@@ -0,0 +1,463 @@
+pub mod scram;
+
+use std::fmt;
+use std::str::FromStr;
+
+use md5::{Digest, Md5};
+use subtle::ConstantTimeEq;
+
+use self::scram::ScramVerifier;
+use crate::errors::{PgSrvError, Result};
+
+#[derive(Debug, Clone, Copy)]
+pub enum PasswordMode {
+    /// A cleartext password is required.
+    ///
+    /// Should error if no password is provided.
+    RequireCleartext,
+
+    /// An MD5 hashed password is required.
+    RequireMd5,
+
+    /// SCRAM-SHA-256 authentication is required.
+    RequireScramSha256,
+
+    /// No password is required.
+    NoPassword {
+        /// Drop any authentication messages as well.
+        ///
+        /// A compliant frontend should not send any additional authentication
+        /// messages after receiving AuthenticationOk. However, node-postgres
+        /// will attempt to send a password message regardless. Setting this to
+        /// true will drop that message.
+        drop_auth_messages: bool,
+    },
+}
+
+/// Method used for authenticating a user with a password.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PasswordMethod {
+    /// Password is sent in cleartext.
+    Cleartext,
+    /// Password is sent as a salted MD5 hash.
+    Md5,
+    /// Password is never sent, with the client proving it knows the password
+    /// using SCRAM-SHA-256.
+    #[default]
+    ScramSha256,
+}
+
+impl PasswordMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cleartext => "cleartext",
+            Self::Md5 => "md5",
+            Self::ScramSha256 => "scram-sha-256",
+        }
+    }
+}
+
+impl fmt::Display for PasswordMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for PasswordMethod {
+    type Err = PgSrvError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "cleartext" | "password" => Self::Cleartext,
+            "md5" => Self::Md5,
+            "scram-sha-256" => Self::ScramSha256,
+            other => return Err(PgSrvError::InvalidPasswordMethod(other.to_string())),
+        })
+    }
+}
+
+impl From<PasswordMethod> for PasswordMode {
+    fn from(method: PasswordMethod) -> Self {
+        match method {
+            PasswordMethod::Cleartext => PasswordMode::RequireCleartext,
+            PasswordMethod::Md5 => PasswordMode::RequireMd5,
+            PasswordMethod::ScramSha256 => PasswordMode::RequireScramSha256,
+        }
+    }
+}
+
+/// A stored password verifier.
+///
+/// Verifiers are stored instead of raw passwords. The string representations
+/// match what postgres stores in `pg_authid`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasswordVerifier {
+    /// Hex encoded MD5 hash of the password concatenated with the user name.
+    ///
+    /// Formatted as `md5<hash>`.
+    Md5(String),
+    /// Salted SCRAM-SHA-256 keys.
+    ScramSha256(ScramVerifier),
+}
+
+impl PasswordVerifier {
+    /// Create an MD5 verifier for a user's password.
+    pub fn new_md5(user: &str, password: &str) -> Self {
+        PasswordVerifier::Md5(md5_hex([password.as_bytes(), user.as_bytes()]))
+    }
+
+    /// Create a SCRAM-SHA-256 verifier for a password.
+    pub fn new_scram_sha256(password: &str) -> Self {
+        PasswordVerifier::ScramSha256(ScramVerifier::new(password))
+    }
+
+    /// Create a verifier suitable for the given method.
+    pub fn for_method(method: PasswordMethod, user: &str, password: &str) -> Self {
+        match method {
+            PasswordMethod::Md5 => Self::new_md5(user, password),
+            PasswordMethod::Cleartext | PasswordMethod::ScramSha256 => {
+                Self::new_scram_sha256(password)
+            }
+        }
+    }
+
+    /// Check a cleartext password.
+    pub fn verify_password(&self, user: &str, password: &str) -> bool {
+        match self {
+            PasswordVerifier::Md5(hash) => {
+                let other = md5_hex([password.as_bytes(), user.as_bytes()]);
+                other.as_bytes().ct_eq(hash.as_bytes()).into()
+            }
+            PasswordVerifier::ScramSha256(verifier) => verifier.verify_password(password),
+        }
+    }
+
+    /// Check the response to an AuthenticationMD5Password request.
+    ///
+    /// The client responds with `md5` followed by the hex encoded MD5 hash of
+    /// the stored hash concatenated with the salt.
+    ///
+    /// Always fails for SCRAM verifiers.
+    pub fn verify_md5_response(&self, salt: &[u8; 4], response: &str) -> bool {
+        match self {
+            PasswordVerifier::Md5(hash) => {
+                let expected = format!("md5{}", md5_hex([hash.as_bytes(), salt]));
+                expected.as_bytes().ct_eq(response.as_bytes()).into()
+            }
+            PasswordVerifier::ScramSha256(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for PasswordVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasswordVerifier::Md5(hash) => write!(f, "md5{hash}"),
+            PasswordVerifier::ScramSha256(verifier) => write!(f, "{verifier}"),
+        }
+    }
+}
+
+impl FromStr for PasswordVerifier {
+    type Err = PgSrvError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(hash) = s.strip_prefix("md5") {
+            if hash.len() != 32 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(PgSrvError::InvalidPasswordVerifier(
+                    "malformed MD5 verifier".to_string(),
+                ));
+            }
+            return Ok(PasswordVerifier::Md5(hash.to_ascii_lowercase()));
+        }
+        Ok(PasswordVerifier::ScramSha256(s.parse()?))
+    }
+}
+
+fn md5_hex<const N: usize>(parts: [&[u8]; N]) -> String {
+    let mut hasher = Md5::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Authenticate connection on the glaredb node itself.
+pub trait LocalAuthenticator: Sync + Send {
+    fn password_mode(&self) -> PasswordMode;
+
+    /// Authenticate using a cleartext password.
+    fn authenticate(&self, user: &str, password: &str, db_name: &str) -> Result<()>;
+
+    /// Get the stored verifier for a user.
+    ///
+    /// Used for MD5 and SCRAM-SHA-256 authentication where the password isn't
+    /// sent in cleartext.
+    fn verifier(&self, user: &str, db_name: &str) -> Result<PasswordVerifier>;
+}
+impl<B> LocalAuthenticator for Box<B>
+where
+    B: LocalAuthenticator + ?Sized,
+{
+    fn password_mode(&self) -> PasswordMode {
+        (**self).password_mode()
+    }
+
+    fn authenticate(&self, user: &str, password: &str, db_name: &str) -> Result<()> {
+        (**self).authenticate(user, password, db_name)
+    }
+
+    fn verifier(&self, user: &str, db_name: &str) -> Result<PasswordVerifier> {
+        (**self).verifier(user, db_name)
+    }
+}
+
+/// A simple single user authenticator.
+///
+/// Only a verifier for the password is kept around.
+#[derive(Debug, Clone)]
+pub struct SingleUserAuthenticator {
+    user: String,
+    method: PasswordMethod,
+    verifier: PasswordVerifier,
+}
+
+impl SingleUserAuthenticator {
+    /// Create an authenticator for a user and password, using the given
+    /// method for authenticating the user.
+    pub fn new(user: impl Into<String>, password: &str, method: PasswordMethod) -> Self {
+        let user = user.into();
+        let verifier = PasswordVerifier::for_method(method, &user, password);
+        SingleUserAuthenticator {
+            user,
+            method,
+            verifier,
+        }
+    }
+
+    /// Create an authenticator from an already stored verifier.
+    ///
+    /// Errors if the verifier can't be used with the method. MD5
+    /// authentication requires an MD5 verifier, and SCRAM-SHA-256
+    /// authentication requires a SCRAM verifier.
+    pub fn with_verifier(
+        user: impl Into<String>,
+        verifier: PasswordVerifier,
+        method: PasswordMethod,
+    ) -> Result<Self> {
+        match (method, &verifier) {
+            (PasswordMethod::Cleartext, _)
+            | (PasswordMethod::Md5, PasswordVerifier::Md5(_))
+            | (PasswordMethod::ScramSha256, PasswordVerifier::ScramSha256(_)) => (),
+            (method, _) => {
+                return Err(PgSrvError::IncompatiblePasswordVerifier(method.to_string()))
+            }
+        }
+        Ok(SingleUserAuthenticator {
+            user: user.into(),
+            method,
+            verifier,
+        })
+    }
+}
+
+impl LocalAuthenticator for SingleUserAuthenticator {
+    fn password_mode(&self) -> PasswordMode {
+        self.method.into()
+    }
+
+    fn authenticate(&self, user: &str, password: &str, _db_name: &str) -> Result<()> {
+        if user != self.user || !self.verifier.verify_password(user, password) {
+            return Err(PgSrvError::InvalidUserOrPassword);
+        }
+        Ok(())
+    }
+
+    fn verifier(&self, user: &str, _db_name: &str) -> Result<PasswordVerifier> {
+        if user != self.user {
+            return Err(PgSrvError::InvalidUserOrPassword);
+        }
+        Ok(self.verifier.clone())
+    }
+}
+
+/// Require no password provided.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PasswordlessAuthenticator {
+    pub drop_auth_messages: bool,
+}
+
+impl LocalAuthenticator for PasswordlessAuthenticator {
+    fn password_mode(&self) -> PasswordMode {
+        PasswordMode::NoPassword {
+            drop_auth_messages: self.drop_auth_messages,
+        }
+    }
+
+    fn authenticate(&self, _user: &str, _password: &str, _db_name: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn verifier(&self, _user: &str, _db_name: &str) -> Result<PasswordVerifier> {
+        Err(PgSrvError::InternalError(
+            "passwordless authenticator has no verifiers".to_string(),
+        ))
+    }
+}
+
+/// Login details for a role stored in the catalog.
+#[derive(Debug, Clone)]
+pub struct RoleLogin {
+    /// Stored password verifier. Empty if the role has no password.
+    pub password_verifier: String,
+    /// If the role is allowed to log in.
+    pub login: bool,
+}
+
+/// Authenticate a user against a role stored in the catalog, falling back to
+/// the server's configured authenticator for the bootstrap user.
+///
+/// Created per connection since roles may change between connections.
+pub struct RoleAuthenticator<'a> {
+    fallback: &'a dyn LocalAuthenticator,
+    role: Option<RoleLogin>,
+}
+
+impl<'a> RoleAuthenticator<'a> {
+    /// Create an authenticator for a connecting user.
+    ///
+    /// `role` should be the catalog role matching the user, if any. Users
+    /// without a role are only let through to the fallback authenticator if
+    /// they're the bootstrap user, or if the catalog doesn't have any roles
+    /// yet.
+    pub fn new(
+        fallback: &'a dyn LocalAuthenticator,
+        user: &str,
+        bootstrap_user: &str,
+        role: Option<RoleLogin>,
+        has_roles: bool,
+    ) -> Result<Self> {
+        if role.is_none() && has_roles && user != bootstrap_user {
+            return Err(PgSrvError::InvalidUserOrPassword);
+        }
+        Ok(RoleAuthenticator { fallback, role })
+    }
+}
+
+impl LocalAuthenticator for RoleAuthenticator<'_> {
+    fn password_mode(&self) -> PasswordMode {
+        let role = match &self.role {
+            Some(role) => role,
+            None => return self.fallback.password_mode(),
+        };
+        match self.fallback.password_mode() {
+            PasswordMode::RequireCleartext => PasswordMode::RequireCleartext,
+            // Roles always require a password, use the method matching the
+            // stored verifier.
+            _ => match role.password_verifier.parse::<PasswordVerifier>() {
+                Ok(PasswordVerifier::Md5(_)) => PasswordMode::RequireMd5,
+                _ => PasswordMode::RequireScramSha256,
+            },
+        }
+    }
+
+    fn authenticate(&self, user: &str, password: &str, db_name: &str) -> Result<()> {
+        if self.role.is_none() {
+            return self.fallback.authenticate(user, password, db_name);
+        }
+        if !self
+            .verifier(user, db_name)?
+            .verify_password(user, password)
+        {
+            return Err(PgSrvError::InvalidUserOrPassword);
+        }
+        Ok(())
+    }
+
+    fn verifier(&self, user: &str, db_name: &str) -> Result<PasswordVerifier> {
+        match &self.role {
+            // Roles without a password or without LOGIN can't connect.
+            Some(role) if !role.login || role.password_verifier.is_empty() => {
+                Err(PgSrvError::InvalidUserOrPassword)
+            }
+            Some(role) => role.password_verifier.parse(),
+            None => self.fallback.verifier(user, db_name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn md5_response() {
+        let verifier = PasswordVerifier::new_md5("glaredb", "password");
+        assert_eq!("md5bf19ff077f349deaa62cb273a81b9480", verifier.to_string());
+
+        let salt = [1, 2, 3, 4];
+        let response = format!(
+            "md5{}",
+            md5_hex([b"bf19ff077f349deaa62cb273a81b9480".as_slice(), &salt])
+        );
+        assert!(verifier.verify_md5_response(&salt, &response));
+        assert!(!verifier.verify_md5_response(&[4, 3, 2, 1], &response));
+    }
+
+    #[test]
+    fn single_user_cleartext() {
+        let auth = SingleUserAuthenticator::new("glaredb", "password", PasswordMethod::Cleartext);
+        auth.authenticate("glaredb", "password", "db").unwrap();
+        auth.authenticate("glaredb", "wrong", "db").unwrap_err();
+        auth.authenticate("other", "password", "db").unwrap_err();
+    }
+
+    #[test]
+    fn verifier_for_method() {
+        let verifier = PasswordVerifier::new_md5("glaredb", "password");
+        SingleUserAuthenticator::with_verifier("glaredb", verifier.clone(), PasswordMethod::Md5)
+            .unwrap();
+        SingleUserAuthenticator::with_verifier("glaredb", verifier, PasswordMethod::ScramSha256)
+            .unwrap_err();
+    }
+
+    #[test]
+    fn role_login() {
+        let fallback =
+            SingleUserAuthenticator::new("glaredb", "password", PasswordMethod::ScramSha256);
+        let role = RoleLogin {
+            password_verifier: PasswordVerifier::new_md5("alice", "secret").to_string(),
+            login: true,
+        };
+
+        let auth = RoleAuthenticator::new(&fallback, "alice", "glaredb", Some(role.clone()), true)
+            .unwrap();
+        assert!(matches!(auth.password_mode(), PasswordMode::RequireMd5));
+        auth.authenticate("alice", "secret", "db").unwrap();
+        auth.authenticate("alice", "password", "db").unwrap_err();
+
+        let auth = RoleAuthenticator::new(
+            &fallback,
+            "alice",
+            "glaredb",
+            Some(RoleLogin {
+                login: false,
+                ..role
+            }),
+            true,
+        )
+        .unwrap();
+        auth.authenticate("alice", "secret", "db").unwrap_err();
+
+        let auth = RoleAuthenticator::new(&fallback, "glaredb", "glaredb", None, true).unwrap();
+        auth.authenticate("glaredb", "password", "db").unwrap();
+
+        // Users without a role are rejected once roles exist.
+        let passwordless = PasswordlessAuthenticator::default();
+        RoleAuthenticator::new(&passwordless, "bob", "glaredb", None, false).unwrap();
+        assert!(matches!(
+            RoleAuthenticator::new(&passwordless, "bob", "glaredb", None, true),
+            Err(PgSrvError::InvalidUserOrPassword)
+        ));
+    }
+}
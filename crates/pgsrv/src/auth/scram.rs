@@ -0,0 +1,379 @@
+//! Server side SCRAM-SHA-256 authentication.
+//!
+//! - <https://www.postgresql.org/docs/current/sasl-authentication.html>
+//! - <https://datatracker.ietf.org/doc/html/rfc5802>
+//!
+//! Channel binding (SCRAM-SHA-256-PLUS) is not supported.
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+use crate::errors::{PgSrvError, Result};
+
+/// Name of the only SASL mechanism we support.
+pub const SCRAM_SHA_256: &str = "SCRAM-SHA-256";
+
+/// Iterations used when generating new verifiers. Matches the postgres
+/// default.
+const DEFAULT_ITERATIONS: u32 = 4096;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 18;
+
+/// A stored SCRAM-SHA-256 verifier.
+///
+/// Formatted the same way postgres stores verifiers in `pg_authid`:
+/// `SCRAM-SHA-256$<iterations>:<salt>$<stored_key>:<server_key>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScramVerifier {
+    iterations: u32,
+    salt: Vec<u8>,
+    stored_key: [u8; 32],
+    server_key: [u8; 32],
+}
+
+impl ScramVerifier {
+    /// Create a verifier for a password using a random salt.
+    pub fn new(password: &str) -> Self {
+        let salt: [u8; SALT_LEN] = rand::thread_rng().gen();
+        Self::with_salt(password, &salt, DEFAULT_ITERATIONS)
+    }
+
+    fn with_salt(password: &str, salt: &[u8], iterations: u32) -> Self {
+        let salted = hi(normalize(password).as_bytes(), salt, iterations);
+        let client_key = hmac_sha256(&salted, b"Client Key");
+        ScramVerifier {
+            iterations,
+            salt: salt.to_vec(),
+            stored_key: Sha256::digest(client_key).into(),
+            server_key: hmac_sha256(&salted, b"Server Key"),
+        }
+    }
+
+    /// A verifier that no password will match.
+    ///
+    /// Used to run through the exchange for users that don't exist, so that
+    /// the client can't tell the difference between an unknown user and a
+    /// wrong password.
+    ///
+    /// The salt is derived from the user name and a secret generated when the
+    /// server starts, so repeated attempts for the same user see the same
+    /// salt, like they would for a user that exists.
+    pub fn mock(user: &str) -> Self {
+        static MOCK_SECRET: OnceLock<[u8; 32]> = OnceLock::new();
+        let secret = MOCK_SECRET.get_or_init(|| rand::thread_rng().gen());
+        let salt = hmac_sha256(secret, user.as_bytes());
+
+        let mut rng = rand::thread_rng();
+        ScramVerifier {
+            iterations: DEFAULT_ITERATIONS,
+            salt: salt[..SALT_LEN].to_vec(),
+            stored_key: rng.gen(),
+            server_key: rng.gen(),
+        }
+    }
+
+    /// Check a cleartext password against this verifier.
+    pub fn verify_password(&self, password: &str) -> bool {
+        let other = Self::with_salt(password, &self.salt, self.iterations);
+        other.stored_key.ct_eq(&self.stored_key).into()
+    }
+}
+
+impl fmt::Display for ScramVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{SCRAM_SHA_256}${}:{}${}:{}",
+            self.iterations,
+            BASE64.encode(&self.salt),
+            BASE64.encode(self.stored_key),
+            BASE64.encode(self.server_key),
+        )
+    }
+}
+
+impl FromStr for ScramVerifier {
+    type Err = PgSrvError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid =
+            || PgSrvError::InvalidPasswordVerifier("malformed SCRAM-SHA-256 verifier".to_string());
+
+        let rest = s
+            .strip_prefix(SCRAM_SHA_256)
+            .and_then(|s| s.strip_prefix('$'))
+            .ok_or_else(invalid)?;
+        let (params, keys) = rest.split_once('$').ok_or_else(invalid)?;
+        let (iterations, salt) = params.split_once(':').ok_or_else(invalid)?;
+        let (stored_key, server_key) = keys.split_once(':').ok_or_else(invalid)?;
+
+        let decode_key = |key: &str| -> Result<[u8; 32]> {
+            BASE64
+                .decode(key)
+                .ok()
+                .and_then(|key| key.try_into().ok())
+                .ok_or_else(invalid)
+        };
+
+        Ok(ScramVerifier {
+            iterations: iterations.parse().map_err(|_| invalid())?,
+            salt: BASE64.decode(salt).map_err(|_| invalid())?,
+            stored_key: decode_key(stored_key)?,
+            server_key: decode_key(server_key)?,
+        })
+    }
+}
+
+/// State for a single SCRAM exchange with a client.
+#[derive(Debug)]
+pub struct ScramExchange<'a> {
+    verifier: &'a ScramVerifier,
+    /// The gs2 header sent by the client, echoed back in the channel binding
+    /// attribute of the final message.
+    gs2_header: String,
+    client_first_bare: String,
+    server_first: String,
+    /// Combined client and server nonce.
+    nonce: String,
+}
+
+impl<'a> ScramExchange<'a> {
+    /// Start an exchange from the client-first-message, returning the
+    /// server-first-message to send to the client.
+    pub fn start(verifier: &'a ScramVerifier, client_first: &str) -> Result<(Self, String)> {
+        let invalid = |msg: &str| PgSrvError::InvalidSaslMessage(msg.to_string());
+
+        // gs2-header: cbind-flag "," [authzid] ","
+        let (cbind_flag, rest) = client_first
+            .split_once(',')
+            .ok_or_else(|| invalid("missing gs2 header"))?;
+        match cbind_flag {
+            "n" | "y" => (),
+            flag if flag.starts_with("p=") => {
+                return Err(invalid("channel binding is not supported"))
+            }
+            _ => return Err(invalid("invalid channel binding flag")),
+        }
+        let (authzid, client_first_bare) = rest
+            .split_once(',')
+            .ok_or_else(|| invalid("missing gs2 header"))?;
+        if !authzid.is_empty() {
+            return Err(invalid("authorization identities are not supported"));
+        }
+        let gs2_header = client_first[..client_first.len() - client_first_bare.len()].to_string();
+
+        // Postgres ignores the user name here, and uses the one from the
+        // startup message instead.
+        let mut attrs = client_first_bare.split(',');
+        match attrs.next() {
+            Some(user) if user.starts_with("n=") => (),
+            Some(m) if m.starts_with("m=") => {
+                return Err(invalid("mandatory extensions are not supported"))
+            }
+            _ => return Err(invalid("missing user name")),
+        }
+        let client_nonce = attrs
+            .next()
+            .and_then(|attr| attr.strip_prefix("r="))
+            .filter(|nonce| !nonce.is_empty())
+            .ok_or_else(|| invalid("missing client nonce"))?;
+
+        let server_nonce: [u8; NONCE_LEN] = rand::thread_rng().gen();
+        let nonce = format!("{client_nonce}{}", BASE64.encode(server_nonce));
+        let server_first = format!(
+            "r={nonce},s={},i={}",
+            BASE64.encode(&verifier.salt),
+            verifier.iterations
+        );
+
+        let exchange = ScramExchange {
+            verifier,
+            gs2_header,
+            client_first_bare: client_first_bare.to_string(),
+            server_first: server_first.clone(),
+            nonce,
+        };
+
+        Ok((exchange, server_first))
+    }
+
+    /// Finish the exchange using the client-final-message, returning the
+    /// server-final-message to send to the client.
+    ///
+    /// Errors if the client's proof doesn't match the stored verifier.
+    pub fn finish(self, client_final: &str) -> Result<String> {
+        let invalid = |msg: &str| PgSrvError::InvalidSaslMessage(msg.to_string());
+
+        // The proof is always the last attribute.
+        let (without_proof, proof) = client_final
+            .rsplit_once(",p=")
+            .ok_or_else(|| invalid("missing client proof"))?;
+        let proof: [u8; 32] = BASE64
+            .decode(proof)
+            .ok()
+            .and_then(|proof| proof.try_into().ok())
+            .ok_or_else(|| invalid("invalid client proof"))?;
+
+        let mut attrs = without_proof.split(',');
+        let channel_binding = attrs
+            .next()
+            .and_then(|attr| attr.strip_prefix("c="))
+            .and_then(|cb| BASE64.decode(cb).ok())
+            .ok_or_else(|| invalid("missing channel binding"))?;
+        if channel_binding != self.gs2_header.as_bytes() {
+            return Err(invalid("channel binding mismatch"));
+        }
+        let nonce = attrs
+            .next()
+            .and_then(|attr| attr.strip_prefix("r="))
+            .ok_or_else(|| invalid("missing nonce"))?;
+        if nonce != self.nonce {
+            return Err(invalid("nonce mismatch"));
+        }
+
+        let auth_message = format!(
+            "{},{},{}",
+            self.client_first_bare, self.server_first, without_proof
+        );
+
+        let client_signature = hmac_sha256(&self.verifier.stored_key, auth_message.as_bytes());
+        let mut client_key = proof;
+        for (key, sig) in client_key.iter_mut().zip(client_signature) {
+            *key ^= sig;
+        }
+        let stored_key: [u8; 32] = Sha256::digest(client_key).into();
+        if !bool::from(stored_key.ct_eq(&self.verifier.stored_key)) {
+            return Err(PgSrvError::InvalidUserOrPassword);
+        }
+
+        let server_signature = hmac_sha256(&self.verifier.server_key, auth_message.as_bytes());
+        Ok(format!("v={}", BASE64.encode(server_signature)))
+    }
+}
+
+/// Parse the mechanism and data from a SASLInitialResponse message body.
+pub fn parse_initial_response(buf: &[u8]) -> Result<(&str, &[u8])> {
+    let invalid = || PgSrvError::InvalidSaslMessage("malformed initial response".to_string());
+
+    let nul = buf.iter().position(|b| *b == 0).ok_or_else(invalid)?;
+    let mechanism = std::str::from_utf8(&buf[..nul]).map_err(|_| invalid())?;
+
+    let rest = &buf[nul + 1..];
+    if rest.len() < 4 {
+        return Err(invalid());
+    }
+    let len = i32::from_be_bytes(rest[..4].try_into().unwrap());
+    let data = &rest[4..];
+    // A length of -1 means no initial response was provided.
+    if len != -1 && len as usize != data.len() {
+        return Err(invalid());
+    }
+
+    Ok((mechanism, data))
+}
+
+/// Normalize a password with SASLprep, falling back to the raw password if it
+/// can't be normalized. This matches what postgres does.
+fn normalize(password: &str) -> std::borrow::Cow<'_, str> {
+    stringprep::saslprep(password).unwrap_or(std::borrow::Cow::Borrowed(password))
+}
+
+fn hmac_sha256(key: &[u8], msg: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac to accept keys of any size");
+    mac.update(msg);
+    mac.finalize().into_bytes().into()
+}
+
+/// The `Hi` function from RFC 5802, which is PBKDF2 with HMAC-SHA-256.
+fn hi(password: &[u8], salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(password).expect("hmac to accept keys of any size");
+    mac.update(salt);
+    mac.update(&1_u32.to_be_bytes());
+    let mut prev: [u8; 32] = mac.finalize().into_bytes().into();
+
+    let mut out = prev;
+    for _ in 1..iterations {
+        prev = hmac_sha256(password, &prev);
+        for (out, prev) in out.iter_mut().zip(prev) {
+            *out ^= prev;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run through an exchange as the client would, using the example from
+    /// RFC 7677.
+    #[test]
+    fn rfc_exchange() {
+        let salt = BASE64.decode("W22ZaJ0SNY7soEsUEjb6gQ==").unwrap();
+        let verifier = ScramVerifier::with_salt("pencil", &salt, 4096);
+
+        let client_first = "n,,n=user,r=rOprNGfwEbeRWgbNEkqO";
+        let (mut exchange, server_first) = ScramExchange::start(&verifier, client_first).unwrap();
+        assert!(server_first.starts_with("r=rOprNGfwEbeRWgbNEkqO"));
+
+        // Use the server nonce from the RFC so that the proof matches.
+        exchange.nonce = "rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0".to_string();
+        exchange.server_first =
+            "r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096"
+                .to_string();
+
+        let client_final = "c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ=";
+        let server_final = exchange.finish(client_final).unwrap();
+        assert_eq!(
+            "v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4=",
+            server_final
+        );
+    }
+
+    #[test]
+    fn wrong_proof() {
+        let verifier = ScramVerifier::new("pencil");
+        let (exchange, server_first) =
+            ScramExchange::start(&verifier, "n,,n=,r=clientnonce").unwrap();
+        let nonce = server_first
+            .split(',')
+            .next()
+            .unwrap()
+            .strip_prefix("r=")
+            .unwrap();
+
+        let client_final = format!("c=biws,r={nonce},p={}", BASE64.encode([0; 32]));
+        let err = exchange.finish(&client_final).unwrap_err();
+        assert!(matches!(err, PgSrvError::InvalidUserOrPassword));
+    }
+
+    #[test]
+    fn verifier_roundtrip() {
+        let verifier = ScramVerifier::new("pencil");
+        let parsed: ScramVerifier = verifier.to_string().parse().unwrap();
+        assert_eq!(verifier, parsed);
+
+        assert!(parsed.verify_password("pencil"));
+        assert!(!parsed.verify_password("pen"));
+    }
+
+    #[test]
+    fn mock_salt_per_user() {
+        let first = ScramVerifier::mock("user");
+        let second = ScramVerifier::mock("user");
+        assert_eq!(first.salt, second.salt);
+        assert_eq!(SALT_LEN, first.salt.len());
+
+        let other = ScramVerifier::mock("other");
+        assert_ne!(first.salt, other.salt);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::errors::{PgSrvError, Result};
-use crate::messages::{BackendMessage, FrontendMessage, StartupMessage};
+use crate::messages::{BackendMessage, FrontendMessage, StartupMessage, TransactionStatus};
 use crate::ssl::Connection;
 use bytes::{Buf, BufMut, BytesMut};
 use bytesutil::{BufStringMut, Cursor};
@@ -67,6 +67,29 @@ impl PgClientCodec {
             _ => unimplemented!("auth type {}", auth_type),
         }
     }
+
+    fn decode_backend_key_data(buf: &mut Cursor<'_>) -> Result<BackendMessage> {
+        Ok(BackendMessage::BackendKeyData {
+            pid: buf.get_i32(),
+            secret: buf.get_i32(),
+        })
+    }
+
+    fn decode_parameter_status(buf: &mut Cursor<'_>) -> Result<BackendMessage> {
+        let key = buf.read_cstring()?.to_string();
+        let val = buf.read_cstring()?.to_string();
+        Ok(BackendMessage::ParameterStatus { key, val })
+    }
+
+    fn decode_ready_for_query(buf: &mut Cursor<'_>) -> Result<BackendMessage> {
+        let status = match buf.get_u8() {
+            b'I' => TransactionStatus::Idle,
+            b'T' => TransactionStatus::InBlock,
+            b'E' => TransactionStatus::Failed,
+            other => return Err(PgSrvError::InvalidMsgType(other)),
+        };
+        Ok(BackendMessage::ReadyForQuery(status))
+    }
 }
 
 impl Encoder<StartupMessage> for PgClientCodec {
@@ -104,8 +127,18 @@ impl Encoder<StartupMessage> for PgClientCodec {
 
                 Ok(())
             }
-            StartupMessage::CancelRequest { version: _ } => {
-                todo!("encode<StartupMessage::CancelRequest>")
+            StartupMessage::CancelRequest {
+                version,
+                pid,
+                secret,
+            } => {
+                dst.reserve(16);
+                dst.put_i32(16); // message length, including itself
+                dst.put_i32(version);
+                dst.put_i32(pid);
+                dst.put_i32(secret);
+
+                Ok(())
             }
         }
     }
@@ -167,6 +200,9 @@ impl Decoder for PgClientCodec {
 
         let msg = match msg_type {
             b'R' => Self::decode_authentication(&mut buf)?,
+            b'K' => Self::decode_backend_key_data(&mut buf)?,
+            b'S' => Self::decode_parameter_status(&mut buf)?,
+            b'Z' => Self::decode_ready_for_query(&mut buf)?,
             other => return Err(PgSrvError::InvalidMsgType(other)),
         };
 
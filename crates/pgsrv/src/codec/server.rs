@@ -94,16 +94,26 @@ where
     pub fn set_encoding_state(&mut self, s: Vec<(PgType, Format)>) {
         self.conn.get_mut().codec_mut().encoding_state = s;
     }
+
+    /// Sets whether or not we're in the middle of a SASL exchange.
+    ///
+    /// Password messages will be decoded as SASL responses while set.
+    pub fn set_sasl_exchange(&mut self, in_exchange: bool) {
+        self.conn.get_mut().codec_mut().sasl_exchange = in_exchange;
+    }
 }
 
 pub struct PgCodec {
     encoding_state: Vec<(PgType, Format)>,
+    /// If we're in a SASL exchange.
+    sasl_exchange: bool,
 }
 
 impl PgCodec {
     fn new() -> Self {
         Self {
             encoding_state: Vec::new(),
+            sasl_exchange: false,
         }
     }
 
@@ -122,7 +132,15 @@ impl PgCodec {
         match version {
             VERSION_V3 => (), // Continue with normal startup flow.
             VERSION_SSL => return Ok(StartupMessage::SSLRequest { version }),
-            VERSION_CANCEL => return Ok(StartupMessage::CancelRequest { version }),
+            VERSION_CANCEL => {
+                let pid = conn.read_i32().await?;
+                let secret = conn.read_i32().await?;
+                return Ok(StartupMessage::CancelRequest {
+                    version,
+                    pid,
+                    secret,
+                });
+            }
             other => return Err(PgSrvError::InvalidProtocolVersion(other)),
         }
 
@@ -164,6 +182,12 @@ impl PgCodec {
         })
     }
 
+    fn decode_sasl_response(buf: &mut Cursor<'_>) -> Result<FrontendMessage> {
+        let mut data = vec![0; buf.remaining()];
+        buf.copy_to_slice(&mut data);
+        Ok(FrontendMessage::SASLResponse { data })
+    }
+
     fn decode_parse(buf: &mut Cursor<'_>) -> Result<FrontendMessage> {
         let name = buf.read_cstring()?.to_string();
         let sql = buf.read_cstring()?.to_string();
@@ -246,6 +270,22 @@ impl PgCodec {
         Ok(FrontendMessage::Close { object_type, name })
     }
 
+    fn decode_copy_data(buf: &mut Cursor<'_>) -> Result<FrontendMessage> {
+        let mut data = vec![0; buf.remaining()];
+        buf.copy_to_slice(&mut data);
+        Ok(FrontendMessage::CopyData { data })
+    }
+
+    fn decode_copy_done(_buf: &mut Cursor<'_>) -> Result<FrontendMessage> {
+        Ok(FrontendMessage::CopyDone)
+    }
+
+    fn decode_copy_fail(buf: &mut Cursor<'_>) -> Result<FrontendMessage> {
+        Ok(FrontendMessage::CopyFail {
+            message: buf.read_cstring()?.to_string(),
+        })
+    }
+
     fn decode_sync(_buf: &mut Cursor<'_>) -> Result<FrontendMessage> {
         Ok(FrontendMessage::Sync)
     }
@@ -266,8 +306,13 @@ impl Encoder<BackendMessage> for PgCodec {
         let byte = match &item {
             BackendMessage::AuthenticationOk => b'R',
             BackendMessage::AuthenticationCleartextPassword => b'R',
+            BackendMessage::AuthenticationMD5Password { .. } => b'R',
+            BackendMessage::AuthenticationSASL { .. } => b'R',
+            BackendMessage::AuthenticationSASLContinue { .. } => b'R',
+            BackendMessage::AuthenticationSASLFinal { .. } => b'R',
             BackendMessage::EmptyQueryResponse => b'I',
             BackendMessage::ParameterStatus { .. } => b'S',
+            BackendMessage::BackendKeyData { .. } => b'K',
             BackendMessage::ReadyForQuery(_) => b'Z',
             BackendMessage::CommandComplete { .. } => b'C',
             BackendMessage::RowDescription(_) => b'T',
@@ -278,7 +323,9 @@ impl Encoder<BackendMessage> for PgCodec {
             BackendMessage::BindComplete => b'2',
             BackendMessage::CloseComplete => b'3',
             BackendMessage::NoData => b'n',
+            BackendMessage::PortalSuspended => b's',
             BackendMessage::ParameterDescription(_) => b't',
+            BackendMessage::CopyInResponse { .. } => b'G',
         };
         dst.put_u8(byte);
 
@@ -289,15 +336,39 @@ impl Encoder<BackendMessage> for PgCodec {
         match item {
             BackendMessage::AuthenticationOk => dst.put_i32(0),
             BackendMessage::AuthenticationCleartextPassword => dst.put_i32(3),
+            BackendMessage::AuthenticationMD5Password { salt } => {
+                dst.put_i32(5);
+                dst.put_slice(&salt);
+            }
+            BackendMessage::AuthenticationSASL { mechanisms } => {
+                dst.put_i32(10);
+                for mechanism in mechanisms {
+                    dst.put_cstring(&mechanism);
+                }
+                dst.put_u8(0);
+            }
+            BackendMessage::AuthenticationSASLContinue { data } => {
+                dst.put_i32(11);
+                dst.put_slice(&data);
+            }
+            BackendMessage::AuthenticationSASLFinal { data } => {
+                dst.put_i32(12);
+                dst.put_slice(&data);
+            }
             BackendMessage::EmptyQueryResponse => (),
             BackendMessage::ParseComplete => (),
             BackendMessage::BindComplete => (),
             BackendMessage::CloseComplete => (),
             BackendMessage::NoData => (),
+            BackendMessage::PortalSuspended => (),
             BackendMessage::ParameterStatus { key, val } => {
                 dst.put_cstring(&key);
                 dst.put_cstring(&val);
             }
+            BackendMessage::BackendKeyData { pid, secret } => {
+                dst.put_i32(pid);
+                dst.put_i32(secret);
+            }
             BackendMessage::ReadyForQuery(status) => match status {
                 TransactionStatus::Idle => dst.put_u8(b'I'),
                 TransactionStatus::InBlock => dst.put_u8(b'T'),
@@ -379,6 +450,16 @@ impl Encoder<BackendMessage> for PgCodec {
                     dst.put_i32(desc);
                 }
             }
+            BackendMessage::CopyInResponse {
+                format,
+                column_formats,
+            } => {
+                dst.put_i8(i16::from(format) as i8);
+                dst.put_i16(column_formats.len() as i16);
+                for format in column_formats {
+                    dst.put_i16(format.into());
+                }
+            }
         }
 
         let msg_len = dst.len() - len_idx;
@@ -415,6 +496,7 @@ impl Decoder for PgCodec {
 
         let msg = match msg_type {
             b'Q' => Self::decode_query(&mut buf)?,
+            b'p' if self.sasl_exchange => Self::decode_sasl_response(&mut buf)?,
             b'p' => Self::decode_password(&mut buf)?,
             b'P' => Self::decode_parse(&mut buf)?,
             b'B' => Self::decode_bind(&mut buf)?,
@@ -423,6 +505,9 @@ impl Decoder for PgCodec {
             b'C' => Self::decode_close(&mut buf)?,
             b'S' => Self::decode_sync(&mut buf)?,
             b'H' => Self::decode_flush(&mut buf)?,
+            b'd' => Self::decode_copy_data(&mut buf)?,
+            b'c' => Self::decode_copy_done(&mut buf)?,
+            b'f' => Self::decode_copy_fail(&mut buf)?,
             b'X' => Self::decode_terminate(&mut buf)?,
             other => return Err(PgSrvError::InvalidMsgType(other)),
         };
@@ -0,0 +1,729 @@
+//! Decoding of data sent by the frontend for `COPY ... FROM STDIN`.
+//!
+//! See <https://www.postgresql.org/docs/current/sql-copy.html#id-1.9.3.55.9>
+//! for a description of the formats.
+use std::sync::Arc;
+
+use bytes::{Buf, BytesMut};
+use datafusion::arrow::array::{ArrayRef, StringArray};
+use datafusion::arrow::compute::{cast_with_options, CastOptions};
+use datafusion::arrow::datatypes::{DataType, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::scalar::ScalarValue;
+use pgrepr::format::Format;
+use pgrepr::scalar::Scalar;
+use pgrepr::types::arrow_to_pg_type;
+use sqlexec::copy_in::CopyInFormat;
+
+use crate::messages::{ErrorResponse, SqlState};
+
+/// Max number of rows to decode before producing a batch.
+const BATCH_SIZE: usize = 8192;
+
+/// Signature at the start of data in the binary format.
+const BINARY_SIGNATURE: &[u8] = b"PGCOPY\n\xff\r\n\0";
+
+/// A single row of values. Values are unescaped text for the text and csv
+/// formats, and the raw bytes for the binary format.
+type Row = Vec<Option<Vec<u8>>>;
+
+/// Decodes `CopyData` messages into batches matching the schema of the table
+/// being copied into.
+///
+/// Rows may be split across messages, so incomplete rows are held on to until
+/// the rest of the row is received.
+pub struct CopyInDecoder {
+    schema: SchemaRef,
+    format: CopyInFormat,
+    /// Data received but not yet decoded.
+    buf: BytesMut,
+    /// Decoded rows not yet turned into a batch.
+    rows: Vec<Row>,
+    /// Whether we still need to read the header (csv header row, or the binary
+    /// file header).
+    header_pending: bool,
+    /// Set once the end of data marker is seen. Everything after is ignored.
+    done: bool,
+}
+
+impl CopyInDecoder {
+    pub fn new(schema: SchemaRef, format: CopyInFormat) -> Self {
+        let header_pending = match &format {
+            CopyInFormat::Text { .. } => false,
+            CopyInFormat::Csv { header, .. } => *header,
+            CopyInFormat::Binary => true,
+        };
+        CopyInDecoder {
+            schema,
+            format,
+            buf: BytesMut::new(),
+            rows: Vec::new(),
+            header_pending,
+            done: false,
+        }
+    }
+
+    /// Decode data from a single `CopyData` message, returning any batches
+    /// that are ready.
+    pub fn push(&mut self, data: &[u8]) -> Result<Vec<RecordBatch>, ErrorResponse> {
+        if self.done {
+            return Ok(Vec::new());
+        }
+        self.buf.extend_from_slice(data);
+
+        // Rows in the text formats only end at a line ending. Don't rescan a
+        // partial row until one arrives, otherwise a row spanning many
+        // messages would be scanned once per message.
+        if !self.format.is_binary() && !data.iter().any(|&b| b == b'\n' || b == b'\r') {
+            return Ok(Vec::new());
+        }
+
+        self.decode_rows(false)
+    }
+
+    /// Decode any remaining data once the frontend sends `CopyDone`.
+    pub fn finish(mut self) -> Result<Vec<RecordBatch>, ErrorResponse> {
+        let mut batches = if self.done {
+            Vec::new()
+        } else {
+            self.decode_rows(true)?
+        };
+
+        if !self.done && !self.buf.is_empty() {
+            return Err(bad_copy_format("unexpected end of copy data"));
+        }
+        if self.format.is_binary() && !self.done {
+            return Err(bad_copy_format("missing file trailer in binary copy data"));
+        }
+
+        if !self.rows.is_empty() {
+            batches.push(self.flush_rows()?);
+        }
+        Ok(batches)
+    }
+
+    fn decode_rows(&mut self, last: bool) -> Result<Vec<RecordBatch>, ErrorResponse> {
+        let mut batches = Vec::new();
+        let mut buf = std::mem::take(&mut self.buf);
+        let mut pos = 0;
+
+        while !self.done {
+            let (row, consumed) = match self.decode_row(&buf[pos..], last)? {
+                Some(decoded) => decoded,
+                None => break,
+            };
+            pos += consumed;
+
+            let row = match row {
+                Some(row) => row,
+                None => continue, // Header or end of data.
+            };
+
+            if row.len() != self.schema.fields().len() {
+                return Err(bad_copy_format(format!(
+                    "expected {} columns in copy data, got {}",
+                    self.schema.fields().len(),
+                    row.len()
+                )));
+            }
+            self.rows.push(row);
+
+            if self.rows.len() >= BATCH_SIZE {
+                batches.push(self.flush_rows()?);
+            }
+        }
+
+        if !self.done {
+            // Only the start of the buffer moves, the remaining data isn't
+            // copied.
+            buf.advance(pos);
+            self.buf = buf;
+        }
+
+        Ok(batches)
+    }
+
+    /// Try to decode the next row from the buffer, returning the row and the
+    /// number of bytes consumed.
+    ///
+    /// Returns `None` if there isn't enough data for a full row yet. A `None`
+    /// row is returned for data that doesn't produce a row (headers, the end of
+    /// data marker).
+    fn decode_row(
+        &mut self,
+        buf: &[u8],
+        last: bool,
+    ) -> Result<Option<(Option<Row>, usize)>, ErrorResponse> {
+        match &self.format {
+            CopyInFormat::Text { delimiter, null } => {
+                let (line, consumed) = match next_line(buf, last) {
+                    Some(line) => line,
+                    None => return Ok(None),
+                };
+                if line == b"\\." {
+                    self.done = true;
+                    return Ok(Some((None, consumed)));
+                }
+                Ok(Some((
+                    Some(decode_text_line(line, *delimiter, null.as_bytes())),
+                    consumed,
+                )))
+            }
+            CopyInFormat::Csv {
+                delimiter,
+                quote,
+                null,
+                ..
+            } => {
+                let (row, consumed) =
+                    match decode_csv_record(buf, *delimiter, *quote, null.as_bytes(), last) {
+                        Some(record) => record,
+                        None => return Ok(None),
+                    };
+                if self.header_pending {
+                    self.header_pending = false;
+                    return Ok(Some((None, consumed)));
+                }
+                if row.len() == 1 && row[0].as_deref() == Some(&b"\\."[..]) {
+                    self.done = true;
+                    return Ok(Some((None, consumed)));
+                }
+                Ok(Some((Some(row), consumed)))
+            }
+            CopyInFormat::Binary => {
+                if self.header_pending {
+                    let consumed = match decode_binary_header(buf)? {
+                        Some(consumed) => consumed,
+                        None => return Ok(None),
+                    };
+                    self.header_pending = false;
+                    return Ok(Some((None, consumed)));
+                }
+                match decode_binary_tuple(buf)? {
+                    Some((Some(row), consumed)) => Ok(Some((Some(row), consumed))),
+                    Some((None, consumed)) => {
+                        self.done = true;
+                        Ok(Some((None, consumed)))
+                    }
+                    None => Ok(None),
+                }
+            }
+        }
+    }
+
+    /// Convert all buffered rows into a batch.
+    fn flush_rows(&mut self) -> Result<RecordBatch, ErrorResponse> {
+        let rows = std::mem::take(&mut self.rows);
+
+        let columns = self
+            .schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let values = rows.iter().map(|row| row[idx].as_deref());
+                if self.format.is_binary() {
+                    binary_values_to_array(values, field.data_type())
+                } else {
+                    text_values_to_array(values, field.data_type())
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        RecordBatch::try_new(self.schema.clone(), columns)
+            .map_err(|e| bad_copy_format(e.to_string()))
+    }
+}
+
+fn bad_copy_format(msg: impl Into<String>) -> ErrorResponse {
+    ErrorResponse::error(SqlState::BadCopyFileFormat, msg)
+}
+
+/// Get the next newline terminated line, returning the line (without the
+/// line ending) and the number of bytes consumed.
+///
+/// If this is the last of the data, the line may be unterminated.
+fn next_line(buf: &[u8], last: bool) -> Option<(&[u8], usize)> {
+    let (line, consumed) = match buf.iter().position(|&b| b == b'\n') {
+        Some(idx) => (&buf[..idx], idx + 1),
+        None if last && !buf.is_empty() => (buf, buf.len()),
+        None => return None,
+    };
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    Some((line, consumed))
+}
+
+/// Decode a line in the text format.
+///
+/// Values are split on unescaped delimiters, and compared against the null
+/// string before unescaping.
+fn decode_text_line(line: &[u8], delimiter: u8, null: &[u8]) -> Row {
+    let mut row = Vec::new();
+    let mut start = 0;
+    let mut idx = 0;
+    while idx < line.len() {
+        match line[idx] {
+            b'\\' => idx += 2,
+            b if b == delimiter => {
+                row.push(&line[start..idx]);
+                idx += 1;
+                start = idx;
+            }
+            _ => idx += 1,
+        }
+    }
+    row.push(&line[start.min(line.len())..]);
+
+    row.into_iter()
+        .map(|raw| {
+            if raw == null {
+                None
+            } else {
+                Some(unescape_text(raw))
+            }
+        })
+        .collect()
+}
+
+/// Unescape backslash sequences in a text format value.
+fn unescape_text(raw: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(raw.len());
+    let mut idx = 0;
+    while idx < raw.len() {
+        let b = raw[idx];
+        idx += 1;
+        if b != b'\\' || idx == raw.len() {
+            out.push(b);
+            continue;
+        }
+
+        let escaped = raw[idx];
+        idx += 1;
+        match escaped {
+            b'b' => out.push(0x08),
+            b'f' => out.push(0x0c),
+            b'n' => out.push(b'\n'),
+            b'r' => out.push(b'\r'),
+            b't' => out.push(b'\t'),
+            b'v' => out.push(0x0b),
+            b'0'..=b'7' => {
+                // Up to three octal digits.
+                let mut val = (escaped - b'0') as u32;
+                let mut digits = 1;
+                while digits < 3 && idx < raw.len() && (b'0'..=b'7').contains(&raw[idx]) {
+                    val = val * 8 + (raw[idx] - b'0') as u32;
+                    idx += 1;
+                    digits += 1;
+                }
+                out.push(val as u8);
+            }
+            b'x' if idx < raw.len() && raw[idx].is_ascii_hexdigit() => {
+                // Up to two hex digits.
+                let mut val = 0_u32;
+                let mut digits = 0;
+                while digits < 2 && idx < raw.len() && raw[idx].is_ascii_hexdigit() {
+                    val = val * 16 + (raw[idx] as char).to_digit(16).unwrap();
+                    idx += 1;
+                    digits += 1;
+                }
+                out.push(val as u8);
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Decode a single csv record, returning the record and the number of bytes
+/// consumed.
+///
+/// Quoted values may span multiple lines. Unquoted values matching the null
+/// string are null, quoted values never are.
+fn decode_csv_record(
+    buf: &[u8],
+    delimiter: u8,
+    quote: u8,
+    null: &[u8],
+    last: bool,
+) -> Option<(Row, usize)> {
+    let mut row = Vec::new();
+    let mut value = Vec::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+
+    let end_value = |value: &mut Vec<u8>, quoted: &mut bool, row: &mut Row| {
+        let v = std::mem::take(value);
+        if !*quoted && v == null {
+            row.push(None);
+        } else {
+            row.push(Some(v));
+        }
+        *quoted = false;
+    };
+
+    let mut idx = 0;
+    while idx < buf.len() {
+        let b = buf[idx];
+        idx += 1;
+
+        if in_quotes {
+            if b == quote {
+                match buf.get(idx) {
+                    // Doubled quote is a literal quote.
+                    Some(&next) if next == quote => {
+                        value.push(quote);
+                        idx += 1;
+                    }
+                    // Need to see the next byte to know if this is the end of
+                    // the quoted section.
+                    None if !last => return None,
+                    _ => in_quotes = false,
+                }
+            } else {
+                value.push(b);
+            }
+            continue;
+        }
+
+        match b {
+            b if b == quote => {
+                in_quotes = true;
+                quoted = true;
+            }
+            b if b == delimiter => end_value(&mut value, &mut quoted, &mut row),
+            b'\n' => {
+                end_value(&mut value, &mut quoted, &mut row);
+                return Some((row, idx));
+            }
+            b'\r' => {
+                match buf.get(idx) {
+                    Some(b'\n') => idx += 1,
+                    None if !last => return None,
+                    _ => (),
+                }
+                end_value(&mut value, &mut quoted, &mut row);
+                return Some((row, idx));
+            }
+            b => value.push(b),
+        }
+    }
+
+    // Unterminated record, only valid at the end of the data.
+    if !last || in_quotes || idx == 0 {
+        return None;
+    }
+    end_value(&mut value, &mut quoted, &mut row);
+    Some((row, idx))
+}
+
+/// Decode the header for the binary format, returning the number of bytes
+/// consumed.
+fn decode_binary_header(buf: &[u8]) -> Result<Option<usize>, ErrorResponse> {
+    // Signature, flags, header extension length.
+    let fixed_len = BINARY_SIGNATURE.len() + 4 + 4;
+    if buf.len() < fixed_len {
+        return Ok(None);
+    }
+    if &buf[..BINARY_SIGNATURE.len()] != BINARY_SIGNATURE {
+        return Err(bad_copy_format("invalid binary copy file signature"));
+    }
+
+    let flags_start = BINARY_SIGNATURE.len();
+    let flags = i32::from_be_bytes(buf[flags_start..flags_start + 4].try_into().unwrap());
+    // Bit 16 indicates OIDs are included, which we don't support. Lower bits
+    // must be ignored, and other high bits are critical.
+    if flags & !0xffff != 0 {
+        return Err(bad_copy_format(
+            "unrecognized critical flags in binary copy header",
+        ));
+    }
+
+    let ext_len = i32::from_be_bytes(buf[flags_start + 4..fixed_len].try_into().unwrap());
+    if ext_len < 0 {
+        return Err(bad_copy_format(
+            "invalid binary copy header extension length",
+        ));
+    }
+    let total = fixed_len + ext_len as usize;
+    if buf.len() < total {
+        return Ok(None);
+    }
+    Ok(Some(total))
+}
+
+/// Decode a single tuple in the binary format, returning the tuple and the
+/// number of bytes consumed.
+///
+/// A `None` tuple indicates the file trailer was read.
+fn decode_binary_tuple(buf: &[u8]) -> Result<Option<(Option<Row>, usize)>, ErrorResponse> {
+    if buf.len() < 2 {
+        return Ok(None);
+    }
+    let num_fields = i16::from_be_bytes([buf[0], buf[1]]);
+    if num_fields == -1 {
+        return Ok(Some((None, 2)));
+    }
+    if num_fields < 0 {
+        return Err(bad_copy_format(format!(
+            "invalid field count in binary copy data: {num_fields}"
+        )));
+    }
+
+    // Find the field lengths first so that nothing is copied until the whole
+    // tuple has been received.
+    let mut idx = 2;
+    let mut fields = Vec::with_capacity(num_fields as usize);
+    for _ in 0..num_fields {
+        if buf.len() < idx + 4 {
+            return Ok(None);
+        }
+        let len = i32::from_be_bytes(buf[idx..idx + 4].try_into().unwrap());
+        idx += 4;
+
+        if len == -1 {
+            fields.push(None);
+            continue;
+        }
+        if len < 0 {
+            return Err(bad_copy_format(format!(
+                "invalid field length in binary copy data: {len}"
+            )));
+        }
+
+        let len = len as usize;
+        if buf.len() < idx + len {
+            return Ok(None);
+        }
+        fields.push(Some(idx..idx + len));
+        idx += len;
+    }
+
+    let row = fields
+        .into_iter()
+        .map(|field| field.map(|range| buf[range].to_vec()))
+        .collect();
+
+    Ok(Some((Some(row), idx)))
+}
+
+/// Build an array of the given type from text values.
+///
+/// Values are parsed using arrow's string casts, which accept the text output
+/// of the same Postgres types.
+fn text_values_to_array<'a>(
+    values: impl Iterator<Item = Option<&'a [u8]>>,
+    datatype: &DataType,
+) -> Result<ArrayRef, ErrorResponse> {
+    let values = values
+        .map(|v| v.map(std::str::from_utf8).transpose())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| bad_copy_format(format!("invalid utf8 in copy data: {e}")))?;
+    let array: ArrayRef = Arc::new(StringArray::from(values));
+
+    if datatype == &DataType::Utf8 {
+        return Ok(array);
+    }
+
+    let opts = CastOptions {
+        safe: false, // Error on invalid values instead of using nulls.
+        ..Default::default()
+    };
+    cast_with_options(&array, datatype, &opts).map_err(|e| {
+        ErrorResponse::error(
+            SqlState::InvalidTextRepresentation,
+            format!("invalid input for type {datatype}: {e}"),
+        )
+    })
+}
+
+/// Build an array of the given type from binary values.
+fn binary_values_to_array<'a>(
+    values: impl Iterator<Item = Option<&'a [u8]>>,
+    datatype: &DataType,
+) -> Result<ArrayRef, ErrorResponse> {
+    let pg_type = arrow_to_pg_type(datatype, None);
+    let scalars = values
+        .map(|v| match v {
+            Some(v) => Ok(Scalar::decode_with_format(Format::Binary, v, &pg_type)?
+                .into_datafusion(datatype)?),
+            None => ScalarValue::try_from(datatype)
+                .map_err(|e| ErrorResponse::error_internal(e.to_string())),
+        })
+        .collect::<Result<Vec<_>, ErrorResponse>>()?;
+
+    ScalarValue::iter_to_array(scalars).map_err(|e| ErrorResponse::error_internal(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::Int32Array;
+    use datafusion::arrow::datatypes::{Field, Schema};
+
+    use super::*;
+
+    fn test_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]))
+    }
+
+    fn text_format() -> CopyInFormat {
+        CopyInFormat::Text {
+            delimiter: b'\t',
+            null: "\\N".to_string(),
+        }
+    }
+
+    fn csv_format() -> CopyInFormat {
+        CopyInFormat::Csv {
+            delimiter: b',',
+            quote: b'"',
+            header: true,
+            null: String::new(),
+        }
+    }
+
+    fn collect(
+        decoder: &mut CopyInDecoder,
+        chunks: &[&[u8]],
+    ) -> Result<Vec<RecordBatch>, ErrorResponse> {
+        let mut batches = Vec::new();
+        for chunk in chunks {
+            batches.extend(decoder.push(chunk)?);
+        }
+        Ok(batches)
+    }
+
+    fn assert_batch(batches: &[RecordBatch], a: Vec<Option<i32>>, b: Vec<Option<&str>>) {
+        assert_eq!(1, batches.len());
+        let batch = &batches[0];
+        let a: ArrayRef = Arc::new(Int32Array::from(a));
+        let b: ArrayRef = Arc::new(StringArray::from(b));
+        assert_eq!(&a, batch.column(0));
+        assert_eq!(&b, batch.column(1));
+    }
+
+    #[test]
+    fn text_rows_split_across_messages() {
+        let mut decoder = CopyInDecoder::new(test_schema(), text_format());
+        let mut batches = collect(
+            &mut decoder,
+            &[b"1\thel", b"lo\n2\t\\N\n", b"\\N\ttab\\there\n3\tlast"],
+        )
+        .unwrap();
+        batches.extend(decoder.finish().unwrap());
+
+        assert_batch(
+            &batches,
+            vec![Some(1), Some(2), None, Some(3)],
+            vec![Some("hello"), None, Some("tab\there"), Some("last")],
+        );
+    }
+
+    #[test]
+    fn text_row_split_into_many_messages() {
+        let long = "x".repeat(64 * 1024);
+        let data = format!("1\t{long}\n2\tshort\n");
+
+        let mut decoder = CopyInDecoder::new(test_schema(), text_format());
+        let chunks: Vec<_> = data.as_bytes().chunks(7).collect();
+        let mut batches = collect(&mut decoder, &chunks).unwrap();
+        batches.extend(decoder.finish().unwrap());
+
+        assert_batch(
+            &batches,
+            vec![Some(1), Some(2)],
+            vec![Some(long.as_str()), Some("short")],
+        );
+    }
+
+    #[test]
+    fn text_end_of_data_marker() {
+        let mut decoder = CopyInDecoder::new(test_schema(), text_format());
+        let mut batches = collect(&mut decoder, &[b"1\ta\n\\.\nignored\n"]).unwrap();
+        batches.extend(decoder.finish().unwrap());
+
+        assert_batch(&batches, vec![Some(1)], vec![Some("a")]);
+    }
+
+    #[test]
+    fn text_invalid_value() {
+        let mut decoder = CopyInDecoder::new(test_schema(), text_format());
+        collect(&mut decoder, &[b"one\ta\n"]).unwrap();
+        decoder.finish().unwrap_err();
+    }
+
+    #[test]
+    fn text_wrong_column_count() {
+        let mut decoder = CopyInDecoder::new(test_schema(), text_format());
+        collect(&mut decoder, &[b"1\ta\textra\n"]).unwrap_err();
+    }
+
+    #[test]
+    fn csv_quoted_values() {
+        let mut decoder = CopyInDecoder::new(test_schema(), csv_format());
+        let mut batches = collect(
+            &mut decoder,
+            &[
+                b"a,b\n1,\"multi\nline\"\n2,",
+                b"\n3,\"\"\n4,\"say \"\"hi\"\"\"\n",
+            ],
+        )
+        .unwrap();
+        batches.extend(decoder.finish().unwrap());
+
+        assert_batch(
+            &batches,
+            vec![Some(1), Some(2), Some(3), Some(4)],
+            vec![Some("multi\nline"), None, Some(""), Some("say \"hi\"")],
+        );
+    }
+
+    #[test]
+    fn binary_rows() {
+        let mut data = Vec::new();
+        data.extend_from_slice(BINARY_SIGNATURE);
+        data.extend_from_slice(&0_i32.to_be_bytes()); // Flags
+        data.extend_from_slice(&0_i32.to_be_bytes()); // Extension length
+        for (a, b) in [(Some(1_i32), Some("x")), (None, None)] {
+            data.extend_from_slice(&2_i16.to_be_bytes());
+            match a {
+                Some(a) => {
+                    data.extend_from_slice(&4_i32.to_be_bytes());
+                    data.extend_from_slice(&a.to_be_bytes());
+                }
+                None => data.extend_from_slice(&(-1_i32).to_be_bytes()),
+            }
+            match b {
+                Some(b) => {
+                    data.extend_from_slice(&(b.len() as i32).to_be_bytes());
+                    data.extend_from_slice(b.as_bytes());
+                }
+                None => data.extend_from_slice(&(-1_i32).to_be_bytes()),
+            }
+        }
+        data.extend_from_slice(&(-1_i16).to_be_bytes()); // Trailer
+
+        // Split in the middle of the header and a tuple.
+        let mut decoder = CopyInDecoder::new(test_schema(), CopyInFormat::Binary);
+        let (first, rest) = data.split_at(7);
+        let (second, third) = rest.split_at(20);
+        let mut batches = collect(&mut decoder, &[first, second, third]).unwrap();
+        batches.extend(decoder.finish().unwrap());
+
+        assert_batch(&batches, vec![Some(1), None], vec![Some("x"), None]);
+    }
+
+    #[test]
+    fn binary_missing_trailer() {
+        let mut data = Vec::new();
+        data.extend_from_slice(BINARY_SIGNATURE);
+        data.extend_from_slice(&0_i32.to_be_bytes());
+        data.extend_from_slice(&0_i32.to_be_bytes());
+
+        let mut decoder = CopyInDecoder::new(test_schema(), CopyInFormat::Binary);
+        collect(&mut decoder, &[&data]).unwrap();
+        decoder.finish().unwrap_err();
+    }
+}
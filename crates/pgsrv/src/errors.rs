@@ -41,6 +41,21 @@ pub enum PgSrvError {
     #[error("Invalid user or password")]
     InvalidUserOrPassword,
 
+    #[error("Invalid password method: {0}")]
+    InvalidPasswordMethod(String),
+
+    #[error("Invalid password verifier: {0}")]
+    InvalidPasswordVerifier(String),
+
+    #[error("Password verifier cannot be used with {0} authentication")]
+    IncompatiblePasswordVerifier(String),
+
+    #[error("Invalid SASL message: {0}")]
+    InvalidSaslMessage(String),
+
+    #[error("Unsupported SASL mechanism: {0}")]
+    UnsupportedSaslMechanism(String),
+
     /// A stringified error from cloud.
     #[error("cloud: {0}")]
     CloudResponse(String),
@@ -1,5 +1,9 @@
-use crate::auth::{LocalAuthenticator, PasswordMode};
+use crate::auth::scram::{self, ScramExchange, ScramVerifier, SCRAM_SHA_256};
+use crate::auth::{
+    LocalAuthenticator, PasswordMode, PasswordVerifier, RoleAuthenticator, RoleLogin,
+};
 use crate::codec::server::{FramedConn, PgCodec};
+use crate::copy::CopyInDecoder;
 use crate::errors::{PgSrvError, Result};
 use crate::messages::{
     BackendMessage, DescribeObjectType, ErrorResponse, FieldDescriptionBuilder, FrontendMessage,
@@ -12,19 +16,25 @@ use crate::proxy::{
 };
 use crate::ssl::{Connection, SslConfig};
 use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::physical_plan::SendableRecordBatchStream;
 use datafusion::scalar::ScalarValue;
 use datafusion::variable::VarType;
 use datafusion_ext::vars::{Dialect, SessionVars};
 use futures::StreamExt;
+use parking_lot::Mutex;
 use pgrepr::format::Format;
 use pgrepr::scalar::Scalar;
+use rand::Rng;
+use sqlexec::cancel::CancelHandle;
 use sqlexec::context::local::{OutputFields, Portal, PreparedStatement};
+use sqlexec::copy_in::CopyInSink;
 use sqlexec::engine::SessionStorageConfig;
+use sqlexec::errors::ExecError;
 use sqlexec::{
     engine::Engine,
     parser::{self, StatementWithExtensions},
-    session::{ExecutionResult, Session},
+    session::{ExecutionResult, Session, TransactionStatus as SessionTransactionStatus},
 };
 use std::collections::HashMap;
 use std::collections::VecDeque;
@@ -45,16 +55,41 @@ pub struct ProtocolHandlerConfig {
     pub integration_testing: bool,
 }
 
+/// Key identifying a session for cancel requests. Sent to the client in the
+/// `BackendKeyData` message during startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BackendKey {
+    pub pid: i32,
+    pub secret: i32,
+}
+
+impl BackendKey {
+    /// Generate a new random key.
+    pub fn new_random() -> Self {
+        let mut rng = rand::thread_rng();
+        BackendKey {
+            pid: rng.gen_range(1..i32::MAX),
+            secret: rng.gen(),
+        }
+    }
+}
+
 /// A wrapper around a SQL engine that implements the Postgres frontend/backend
 /// protocol.
 pub struct ProtocolHandler {
     engine: Arc<Engine>,
     conf: ProtocolHandlerConfig,
+    /// Cancel handles for active sessions.
+    cancel_handles: Mutex<HashMap<BackendKey, CancelHandle>>,
 }
 
 impl ProtocolHandler {
     pub fn new(engine: Arc<Engine>, conf: ProtocolHandlerConfig) -> Self {
-        ProtocolHandler { engine, conf }
+        ProtocolHandler {
+            engine,
+            conf,
+            cancel_handles: Mutex::new(HashMap::new()),
+        }
     }
 
     pub async fn handle_connection<C>(&self, id: Uuid, conn: C) -> Result<()>
@@ -89,8 +124,8 @@ impl ProtocolHandler {
                         }
                     }
                 }
-                StartupMessage::CancelRequest { .. } => {
-                    self.cancel(conn).await?;
+                StartupMessage::CancelRequest { pid, secret, .. } => {
+                    self.cancel(conn, BackendKey { pid, secret }).await?;
                     return Ok(());
                 }
             }
@@ -128,6 +163,156 @@ impl ProtocolHandler {
         self.conf.integration_testing
     }
 
+    /// Authenticate the user with a cleartext password.
+    ///
+    /// Returns `false` if the connection was closed before the password was
+    /// received.
+    async fn authenticate_cleartext<C>(
+        &self,
+        framed: &mut FramedConn<C>,
+        auth: &dyn LocalAuthenticator,
+        user: &str,
+        db_name: &str,
+    ) -> Result<bool>
+    where
+        C: AsyncRead + AsyncWrite + Unpin,
+    {
+        framed
+            .send(BackendMessage::AuthenticationCleartextPassword)
+            .await?;
+        match framed.read().await? {
+            Some(FrontendMessage::PasswordMessage { password }) => {
+                auth.authenticate(user, &password, db_name)?;
+                Ok(true)
+            }
+            Some(other) => Err(PgSrvError::UnexpectedFrontendMessage(Box::new(other))),
+            None => Ok(false),
+        }
+    }
+
+    /// Authenticate the user with a salted MD5 hash of their password.
+    ///
+    /// Returns `false` if the connection was closed before the password was
+    /// received.
+    async fn authenticate_md5<C>(
+        &self,
+        framed: &mut FramedConn<C>,
+        auth: &dyn LocalAuthenticator,
+        user: &str,
+        db_name: &str,
+    ) -> Result<bool>
+    where
+        C: AsyncRead + AsyncWrite + Unpin,
+    {
+        let salt: [u8; 4] = rand::thread_rng().gen();
+        framed
+            .send(BackendMessage::AuthenticationMD5Password { salt })
+            .await?;
+        match framed.read().await? {
+            Some(FrontendMessage::PasswordMessage { password }) => {
+                let verifier = auth.verifier(user, db_name)?;
+                if !verifier.verify_md5_response(&salt, &password) {
+                    return Err(PgSrvError::InvalidUserOrPassword);
+                }
+                Ok(true)
+            }
+            Some(other) => Err(PgSrvError::UnexpectedFrontendMessage(Box::new(other))),
+            None => Ok(false),
+        }
+    }
+
+    /// Authenticate the user using SCRAM-SHA-256.
+    ///
+    /// Returns `false` if the connection was closed before the exchange
+    /// completed.
+    async fn authenticate_scram<C>(
+        &self,
+        framed: &mut FramedConn<C>,
+        auth: &dyn LocalAuthenticator,
+        user: &str,
+        db_name: &str,
+    ) -> Result<bool>
+    where
+        C: AsyncRead + AsyncWrite + Unpin,
+    {
+        framed
+            .send(BackendMessage::AuthenticationSASL {
+                mechanisms: vec![SCRAM_SHA_256.to_string()],
+            })
+            .await?;
+
+        framed.set_sasl_exchange(true);
+        let result = self.scram_exchange(framed, auth, user, db_name).await;
+        framed.set_sasl_exchange(false);
+
+        result
+    }
+
+    async fn scram_exchange<C>(
+        &self,
+        framed: &mut FramedConn<C>,
+        auth: &dyn LocalAuthenticator,
+        user: &str,
+        db_name: &str,
+    ) -> Result<bool>
+    where
+        C: AsyncRead + AsyncWrite + Unpin,
+    {
+        let initial = match Self::read_sasl_response(framed).await? {
+            Some(data) => data,
+            None => return Ok(false),
+        };
+        let (mechanism, client_first) = scram::parse_initial_response(&initial)?;
+        if mechanism != SCRAM_SHA_256 {
+            return Err(PgSrvError::UnsupportedSaslMechanism(mechanism.to_string()));
+        }
+
+        // Go through the full exchange for unknown users, failing at the end.
+        // This prevents clients from being able to tell if a user exists.
+        let verifier = match auth.verifier(user, db_name) {
+            Ok(PasswordVerifier::ScramSha256(verifier)) => verifier,
+            Ok(PasswordVerifier::Md5(_)) | Err(PgSrvError::InvalidUserOrPassword) => {
+                ScramVerifier::mock(user)
+            }
+            Err(e) => return Err(e),
+        };
+
+        let (exchange, server_first) =
+            ScramExchange::start(&verifier, sasl_message_str(client_first)?)?;
+        framed
+            .send(BackendMessage::AuthenticationSASLContinue {
+                data: server_first.into_bytes(),
+            })
+            .await?;
+
+        let client_final = match Self::read_sasl_response(framed).await? {
+            Some(data) => data,
+            None => return Ok(false),
+        };
+        let server_final = exchange.finish(sasl_message_str(&client_final)?)?;
+        framed
+            .send(BackendMessage::AuthenticationSASLFinal {
+                data: server_final.into_bytes(),
+            })
+            .await?;
+
+        Ok(true)
+    }
+
+    /// Read the next SASL response from the client.
+    ///
+    /// Returns `None` if the connection was closed.
+    async fn read_sasl_response<C>(framed: &mut FramedConn<C>) -> Result<Option<Vec<u8>>>
+    where
+        C: AsyncRead + AsyncWrite + Unpin,
+    {
+        match framed.read().await? {
+            Some(FrontendMessage::SASLResponse { data }) => Ok(Some(data)),
+            Some(other) => Err(PgSrvError::UnexpectedFrontendMessage(Box::new(other))),
+            None => Ok(None),
+        }
+    }
+
     /// Runs the postgres protocol for a connection to completion.
     async fn begin<C>(
         &self,
@@ -178,42 +363,76 @@ impl ProtocolHandler {
             db_id
         };
 
-        // Handle password.
-        match self.conf.authenticator.password_mode() {
-            PasswordMode::RequireCleartext => {
+        // Users matching a catalog role authenticate against that role. Once
+        // roles exist, only the bootstrap user may connect without one.
+        let bootstrap_user = self.engine.bootstrap_user();
+        let role = match self.engine.resolve_role(db_id, &user_name).await {
+            Ok(role) => role.map(|role| RoleLogin {
+                password_verifier: role.password_verifier,
+                login: role.login,
+            }),
+            Err(e) => {
                 framed
-                    .send(BackendMessage::AuthenticationCleartextPassword)
+                    .send(
+                        ErrorResponse::fatal_internal(format!("failed to resolve role: {}", e))
+                            .into(),
+                    )
                     .await?;
-                let msg = framed.read().await?;
-                match msg {
-                    Some(FrontendMessage::PasswordMessage { password }) => {
-                        match self.conf.authenticator.authenticate(
-                            &user_name,
-                            &password,
-                            &database_name,
-                        ) {
-                            Ok(sess) => sess,
-                            Err(e) => {
-                                framed
-                                    .send(
-                                        ErrorResponse::fatal_internal(format!(
-                                            "Failed to authenticate: {}",
-                                            e
-                                        ))
-                                        .into(),
-                                    )
-                                    .await?;
-                                return Err(e);
-                            }
-                        }
-                        framed.send(BackendMessage::AuthenticationOk).await?;
-                    }
-                    Some(other) => {
-                        // TODO: Send error.
-                        return Err(PgSrvError::UnexpectedFrontendMessage(Box::new(other)));
-                    }
-                    None => return Ok(()),
-                }
+                return Err(e.into());
+            }
+        };
+        let has_roles = match self.engine.has_roles(db_id).await {
+            Ok(has_roles) => has_roles,
+            Err(e) => {
+                framed
+                    .send(
+                        ErrorResponse::fatal_internal(format!("failed to list roles: {}", e))
+                            .into(),
+                    )
+                    .await?;
+                return Err(e.into());
+            }
+        };
+        // Users without a role run as the bootstrap user.
+        let session_user = match role {
+            Some(_) => user_name.clone(),
+            None => bootstrap_user.to_string(),
+        };
+        let auth = match RoleAuthenticator::new(
+            self.conf.authenticator.as_ref(),
+            &user_name,
+            bootstrap_user,
+            role,
+            has_roles,
+        ) {
+            Ok(auth) => auth,
+            Err(e) => {
+                framed
+                    .send(
+                        ErrorResponse::fatal(
+                            SqlState::InvalidPassword,
+                            format!("Failed to authenticate: {e}"),
+                        )
+                        .into(),
+                    )
+                    .await?;
+                return Err(e);
+            }
+        };
+
+        // Handle password.
+        let authenticated = match auth.password_mode() {
+            PasswordMode::RequireCleartext => {
+                self.authenticate_cleartext(&mut framed, &auth, &user_name, &database_name)
+                    .await
+            }
+            PasswordMode::RequireMd5 => {
+                self.authenticate_md5(&mut framed, &auth, &user_name, &database_name)
+                    .await
+            }
+            PasswordMode::RequireScramSha256 => {
+                self.authenticate_scram(&mut framed, &auth, &user_name, &database_name)
+                    .await
             }
             PasswordMode::NoPassword { drop_auth_messages } => {
                 if drop_auth_messages {
@@ -237,12 +456,37 @@ impl ProtocolHandler {
                 }
 
                 // Nothin to do.
-                framed.send(BackendMessage::AuthenticationOk).await?;
+                Ok(true)
+            }
+        };
+        match authenticated {
+            Ok(true) => framed.send(BackendMessage::AuthenticationOk).await?,
+            Ok(false) => return Ok(()), // Connection closed
+            Err(e) => {
+                let code = match e {
+                    PgSrvError::InvalidUserOrPassword => SqlState::InvalidPassword,
+                    _ => SqlState::ProtocolViolation,
+                };
+                framed
+                    .send(ErrorResponse::fatal(code, format!("Failed to authenticate: {e}")).into())
+                    .await?;
+                return Err(e);
             }
         }
+
+        // Send the key right after authenticating so that a proxy in front of
+        // us can pick it up without needing to understand any of the other
+        // startup messages.
+        let key = BackendKey::new_random();
+        framed
+            .send(BackendMessage::BackendKeyData {
+                pid: key.pid,
+                secret: key.secret,
+            })
+            .await?;
         let mut vars = SessionVars::default()
             .with_user_id(user_id, VarType::System)
-            .with_user_name(user_name, VarType::System)
+            .with_user_name(session_user, VarType::System)
             .with_connection_id(conn_id, VarType::System)
             .with_database_id(db_id, VarType::System)
             .with_database_name(database_name, VarType::System)
@@ -299,19 +543,39 @@ impl ProtocolHandler {
             framed.send(msg).await?;
         }
 
+        {
+            let mut handles = self.cancel_handles.lock();
+            if handles.contains_key(&key) {
+                // Incredibly unlikely. Don't clobber the other session's key.
+                warn!(?key, "duplicate backend key, session won't be cancelable");
+            } else {
+                handles.insert(key, sess.cancel_handle());
+            }
+        }
+
         let cs = ClientSession::new(sess, framed);
-        cs.run().await
+        let result = cs.run().await;
+
+        self.cancel_handles.lock().remove(&key);
+
+        result
     }
 
-    /// Cancel a connection.
+    /// Cancel the statement executing in the session identified by `key`.
     ///
-    /// Unimplemented. The protocol states that there's no guarantee that
-    /// anything is actually canceled, so no-op is fine for now.
-    async fn cancel<C>(&self, _conn: Connection<C>) -> Result<()>
+    /// The protocol states that there's no guarantee that anything is actually
+    /// canceled, and nothing is sent back on the connection. Unknown keys are
+    /// ignored.
+    async fn cancel<C>(&self, _conn: Connection<C>, key: BackendKey) -> Result<()>
     where
         C: AsyncRead + AsyncWrite + Unpin,
     {
-        debug!("cancel received (local)");
+        debug!(?key, "cancel received (local)");
+        let handle = self.cancel_handles.lock().get(&key).cloned();
+        match handle {
+            Some(handle) => handle.cancel(),
+            None => debug!(?key, "no session found for cancel request"),
+        }
         Ok(())
     }
 }
@@ -402,6 +666,12 @@ where
                 FrontendMessage::Sync => self.sync().instrument(span).await?,
                 FrontendMessage::Flush => self.flush().instrument(span).await?,
                 FrontendMessage::Terminate => return Ok(()),
+                // Copy messages received outside of a copy (e.g. data the
+                // client sent before seeing a copy error) are dropped, same as
+                // Postgres.
+                FrontendMessage::CopyData { .. }
+                | FrontendMessage::CopyDone
+                | FrontendMessage::CopyFail { .. } => (),
                 other => {
                     warn!(?other, "unsupported frontend message");
                     self.conn
@@ -420,15 +690,22 @@ where
     }
 
     /// Send an error response to the client.
+    ///
+    /// Any error fails the current transaction block.
     async fn send_error(&mut self, err: ErrorResponse) -> Result<()> {
+        self.session.mark_transaction_failed();
         self.conn.send(err.into()).await?;
         Ok(())
     }
 
     async fn ready_for_query(&mut self) -> Result<()> {
-        // TODO: Proper status.
+        let status = match self.session.transaction_status() {
+            SessionTransactionStatus::Idle => TransactionStatus::Idle,
+            SessionTransactionStatus::InBlock => TransactionStatus::InBlock,
+            SessionTransactionStatus::Failed => TransactionStatus::Failed,
+        };
         self.conn
-            .send(BackendMessage::ReadyForQuery(TransactionStatus::Idle))
+            .send(BackendMessage::ReadyForQuery(status))
             .await?;
         self.flush().await
     }
@@ -454,8 +731,6 @@ where
         let num_statements = stmts.len();
 
         for stmt in stmts {
-            // TODO: Ensure in transaction.
-
             // Note everything is using unnamed portals/prepared statements.
 
             const UNNAMED: String = String::new();
@@ -495,9 +770,22 @@ where
                 }
             };
 
-            // If we're returning data (SELECT), send back the output fields
-            // before sending back actual data.
-            if let ExecutionResult::Query { .. } = stream {
+            // COPY FROM STDIN hands control over to the client. An error
+            // during the copy aborts the rest of the query string.
+            let stream = match stream {
+                ExecutionResult::CopyIn { sink } => {
+                    if let Err(e) = Self::copy_in(conn, sink).await? {
+                        self.send_error(e).await?;
+                        return self.ready_for_query().await;
+                    }
+                    continue;
+                }
+                stream => stream,
+            };
+
+            // If we're returning data (SELECT or FETCH), send back the output
+            // fields before sending back actual data.
+            if let ExecutionResult::Query { .. } | ExecutionResult::Fetch { .. } = stream {
                 let output_fields =
                     session_do!(self, session, get_portal, &UNNAMED, Portal::output_fields);
                 if let Some(fields) = output_fields {
@@ -522,7 +810,6 @@ where
 
     /// Parse the provided SQL statement and store it in the session.
     async fn parse(&mut self, name: String, sql: String, param_types: Vec<i32>) -> Result<()> {
-        // TODO: Ensure in transaction.
         let vars = self.session.get_session_vars();
         let mut stmts = match parse_sql(vars, &sql) {
             Ok(stmts) => stmts,
@@ -538,8 +825,6 @@ where
                 .await;
         }
 
-        // TODO: Check if in failed transaction.
-
         // Store statement for future use.
         match self
             .session
@@ -560,8 +845,6 @@ where
         param_values: Vec<Option<Vec<u8>>>,
         result_formats: Vec<Format>,
     ) -> Result<()> {
-        // TODO: Ensure in transaction.
-
         // Check for the statement.
         let stmt = match self.session.get_prepared_statement(&statement) {
             Ok(stmt) => stmt,
@@ -596,15 +879,16 @@ where
     }
 
     async fn describe(&mut self, object_type: DescribeObjectType, name: String) -> Result<()> {
-        // TODO: Ensure in a transaction.
-
         let conn = &mut self.conn;
         match object_type {
             DescribeObjectType::Statement => match self.session.get_prepared_statement(&name) {
                 Ok(stmt) => {
-                    // TODO: We don't accept parameters yet. So just send back
-                    // an empty paramaters list.
-                    conn.send(BackendMessage::ParameterDescription(Vec::new()))
+                    let param_oids = stmt
+                        .ordered_input_parameters()
+                        .iter()
+                        .map(|(typ, _)| typ.oid() as i32)
+                        .collect();
+                    conn.send(BackendMessage::ParameterDescription(param_oids))
                         .await?;
 
                     // Send back row description.
@@ -644,8 +928,6 @@ where
     }
 
     async fn execute(&mut self, portal: String, max_rows: i32) -> Result<()> {
-        // TODO: Ensure in transaction.
-
         let conn = &mut self.conn;
         let session = &mut self.session;
         let stream = match session.execute_portal(&portal, max_rows).await {
@@ -653,6 +935,13 @@ where
             Err(e) => return self.send_error(e.into()).await,
         };
 
+        if let ExecutionResult::CopyIn { sink } = stream {
+            return match Self::copy_in(conn, sink).await? {
+                Ok(()) => Ok(()),
+                Err(e) => self.send_error(e).await,
+            };
+        }
+
         // TODO: This seems to be missing sending back row description. Is it
         // needed? If not, a comment needs to go here.
 
@@ -673,6 +962,7 @@ where
     }
 
     async fn sync(&mut self) -> Result<()> {
+        self.session.sync();
         self.ready_for_query().await
     }
 
@@ -693,6 +983,19 @@ where
                     Self::command_complete(conn, format!("SELECT {}", num_rows)).await?;
                 }
             }
+            ExecutionResult::Suspended { stream } => {
+                if Self::stream_batch(conn, stream, encoding_state)
+                    .await?
+                    .is_some()
+                {
+                    conn.send(BackendMessage::PortalSuspended).await?;
+                }
+            }
+            ExecutionResult::Fetch { stream } => {
+                if let Some(num_rows) = Self::stream_batch(conn, stream, encoding_state).await? {
+                    Self::command_complete(conn, format!("FETCH {}", num_rows)).await?;
+                }
+            }
             ExecutionResult::EmptyQuery => conn.send(BackendMessage::EmptyQueryResponse).await?,
             ExecutionResult::Begin => Self::command_complete(conn, "BEGIN").await?,
             ExecutionResult::Commit => Self::command_complete(conn, "COMMIT").await?,
@@ -703,6 +1006,23 @@ where
                 Self::command_complete(conn, format!("INSERT 0 {rows_inserted}")).await?
             }
             ExecutionResult::CopySuccess => Self::command_complete(conn, "COPY").await?,
+            ExecutionResult::OptimizeSuccess { files_removed } => {
+                Self::command_complete(conn, format!("OPTIMIZE {files_removed}")).await?
+            }
+            ExecutionResult::VacuumSuccess { files_deleted } => {
+                Self::command_complete(conn, format!("VACUUM {files_deleted}")).await?
+            }
+            ExecutionResult::CopyIn { .. } => {
+                // Needs to go through the session to fail the transaction on
+                // errors.
+                return Err(PgSrvError::InternalError(
+                    "COPY FROM STDIN must be handled before sending results".to_string(),
+                ));
+            }
+            ExecutionResult::DeclareCursor => {
+                Self::command_complete(conn, "DECLARE CURSOR").await?
+            }
+            ExecutionResult::CloseCursor => Self::command_complete(conn, "CLOSE CURSOR").await?,
             ExecutionResult::DeleteSuccess { deleted_rows } => {
                 Self::command_complete(conn, format!("DELETE {}", deleted_rows)).await?
             }
@@ -742,6 +1062,10 @@ where
             ExecutionResult::DropCredentials => {
                 Self::command_complete(conn, "DROP CREDENTIALS").await?
             }
+            ExecutionResult::CreateRole => Self::command_complete(conn, "CREATE ROLE").await?,
+            ExecutionResult::DropRoles => Self::command_complete(conn, "DROP ROLE").await?,
+            ExecutionResult::Grant => Self::command_complete(conn, "GRANT").await?,
+            ExecutionResult::Revoke => Self::command_complete(conn, "REVOKE").await?,
         };
         Ok(())
     }
@@ -774,7 +1098,7 @@ where
             let batch = match result {
                 Ok(r) => r,
                 Err(e) => {
-                    conn.send(ErrorResponse::error(SqlState::InternalError, e.to_string()).into())
+                    conn.send(ErrorResponse::from(ExecError::from(e)).into())
                         .await?;
                     return Ok(None);
                 }
@@ -789,6 +1113,96 @@ where
         Ok(Some(num_rows))
     }
 
+    /// Run the copy-in flow for `COPY ... FROM STDIN`, inserting everything
+    /// the client sends through the sink.
+    ///
+    /// Errors during the copy are returned in the inner result so that the
+    /// caller can report them through the session, and nothing is inserted.
+    /// The outer result is for errors with the connection itself.
+    async fn copy_in(
+        conn: &mut FramedConn<C>,
+        mut sink: CopyInSink,
+    ) -> Result<Result<(), ErrorResponse>> {
+        let format = if sink.format().is_binary() {
+            Format::Binary
+        } else {
+            Format::Text
+        };
+        conn.send(BackendMessage::CopyInResponse {
+            format,
+            column_formats: vec![format; sink.schema().fields().len()],
+        })
+        .await?;
+        conn.flush().await?;
+
+        let mut decoder = CopyInDecoder::new(sink.schema(), sink.format().clone());
+        loop {
+            let msg = match conn.read().await? {
+                Some(msg) => msg,
+                None => {
+                    // Connection closed, dropping the sink aborts the copy.
+                    debug!("connection closed during copy");
+                    return Ok(Ok(()));
+                }
+            };
+
+            match msg {
+                FrontendMessage::CopyData { data } => {
+                    let result = match decoder.push(&data) {
+                        Ok(batches) => Self::copy_batches(&mut sink, batches).await,
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = result {
+                        return Ok(Err(e));
+                    }
+                }
+                FrontendMessage::CopyDone => {
+                    let result = match decoder.finish() {
+                        Ok(batches) => Self::copy_batches(&mut sink, batches).await,
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = result {
+                        return Ok(Err(e));
+                    }
+                    return match sink.finish().await {
+                        Ok(rows) => {
+                            Self::command_complete(conn, format!("COPY {rows}")).await?;
+                            Ok(Ok(()))
+                        }
+                        Err(e) => Ok(Err(e.into())),
+                    };
+                }
+                FrontendMessage::CopyFail { message } => {
+                    return Ok(Err(ErrorResponse::error(
+                        SqlState::QueryCanceled,
+                        format!("COPY from stdin failed: {message}"),
+                    )));
+                }
+                // Allowed during a copy, but don't do anything.
+                FrontendMessage::Flush | FrontendMessage::Sync => (),
+                other => {
+                    return Ok(Err(ErrorResponse::error(
+                        SqlState::ProtocolViolation,
+                        format!(
+                            "unexpected message type during COPY from stdin: {}",
+                            other.name()
+                        ),
+                    )));
+                }
+            }
+        }
+    }
+
+    async fn copy_batches(
+        sink: &mut CopyInSink,
+        batches: Vec<RecordBatch>,
+    ) -> Result<(), ErrorResponse> {
+        for batch in batches {
+            sink.send(batch).await?;
+        }
+        Ok(())
+    }
+
     async fn command_complete(conn: &mut FramedConn<C>, tag: impl Into<String>) -> Result<()> {
         conn.send(BackendMessage::CommandComplete { tag: tag.into() })
             .await
@@ -894,6 +1308,12 @@ fn get_encoding_state(portal: &Portal) -> Vec<(PgType, Format)> {
     }
 }
 
+/// SCRAM messages are always text.
+fn sasl_message_str(data: &[u8]) -> Result<&str> {
+    std::str::from_utf8(data)
+        .map_err(|_| PgSrvError::InvalidSaslMessage("message is not valid utf8".to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
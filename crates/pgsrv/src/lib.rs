@@ -7,8 +7,8 @@
 //! - <https://www.postgresql.org/docs/current/protocol-message-formats.html>
 //!
 //! We currently implement most of the Simple Query Flow and the Extended Query
-//! Flow. We do not implement the copy protocol (yet), or the functional call
-//! protocol (never).
+//! Flow, along with COPY FROM STDIN for the copy protocol. We do not implement
+//! the functional call protocol (never).
 pub mod auth;
 pub mod errors;
 pub mod handler;
@@ -16,4 +16,5 @@ pub mod proxy;
 pub mod ssl;
 
 mod codec;
+mod copy;
 mod messages;
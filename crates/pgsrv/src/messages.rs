@@ -23,6 +23,10 @@ pub enum StartupMessage {
     },
     CancelRequest {
         version: i32,
+        /// Process ID of the backend, as sent in `BackendKeyData`.
+        pid: i32,
+        /// Secret key of the backend, as sent in `BackendKeyData`.
+        secret: i32,
     },
     StartupRequest {
         version: i32,
@@ -37,6 +41,12 @@ pub enum FrontendMessage {
     Query { sql: String },
     /// An encrypted or unencrypted password.
     PasswordMessage { password: String },
+    /// A SASLInitialResponse or SASLResponse message.
+    ///
+    /// Both share the same message type with `PasswordMessage`, and are only
+    /// decoded as SASL messages during a SASL exchange. The data is left
+    /// unparsed since its contents depend on the stage of the exchange.
+    SASLResponse { data: Vec<u8> },
     /// An extended query parse message.
     Parse {
         /// The name of the prepared statement. An empty string denotes the
@@ -97,6 +107,12 @@ pub enum FrontendMessage {
         /// Name of the object to close.
         name: String,
     },
+    /// Data for `COPY ... FROM STDIN`.
+    CopyData { data: Vec<u8> },
+    /// All data for a copy has been sent.
+    CopyDone,
+    /// The frontend failed to send all data for a copy.
+    CopyFail { message: String },
     /// Synchronize after running through the extended query protocol.
     Sync,
     /// Flush the connection.
@@ -110,11 +126,15 @@ impl FrontendMessage {
         match self {
             FrontendMessage::Query { .. } => "query",
             FrontendMessage::PasswordMessage { .. } => "password",
+            FrontendMessage::SASLResponse { .. } => "sasl_response",
             FrontendMessage::Parse { .. } => "parse",
             FrontendMessage::Bind { .. } => "bind",
             FrontendMessage::Describe { .. } => "describe",
             FrontendMessage::Execute { .. } => "execute",
             FrontendMessage::Close { .. } => "close",
+            FrontendMessage::CopyData { .. } => "copy_data",
+            FrontendMessage::CopyDone => "copy_done",
+            FrontendMessage::CopyFail { .. } => "copy_fail",
             FrontendMessage::Flush => "flush",
             FrontendMessage::Sync => "sync",
             FrontendMessage::Terminate => "terminate",
@@ -122,7 +142,10 @@ impl FrontendMessage {
     }
 
     pub(crate) fn is_auth_message(&self) -> bool {
-        matches!(self, FrontendMessage::PasswordMessage { .. })
+        matches!(
+            self,
+            FrontendMessage::PasswordMessage { .. } | FrontendMessage::SASLResponse { .. }
+        )
     }
 }
 
@@ -139,17 +162,49 @@ pub enum BackendMessage {
     NoticeResponse(NoticeResponse),
     AuthenticationOk,
     AuthenticationCleartextPassword,
-    ParameterStatus { key: String, val: String },
+    AuthenticationMD5Password {
+        salt: [u8; 4],
+    },
+    AuthenticationSASL {
+        /// SASL mechanisms supported by the server, in order of preference.
+        mechanisms: Vec<String>,
+    },
+    AuthenticationSASLContinue {
+        data: Vec<u8>,
+    },
+    AuthenticationSASLFinal {
+        data: Vec<u8>,
+    },
+    ParameterStatus {
+        key: String,
+        val: String,
+    },
+    BackendKeyData {
+        pid: i32,
+        secret: i32,
+    },
     EmptyQueryResponse,
     ReadyForQuery(TransactionStatus),
-    CommandComplete { tag: String },
+    CommandComplete {
+        tag: String,
+    },
     RowDescription(Vec<FieldDescription>),
     DataRow(RecordBatch, usize),
     ParseComplete,
     BindComplete,
     CloseComplete,
     NoData,
+    /// Execute stopped after hitting the max number of rows. Executing the
+    /// portal again continues from where it left off.
+    PortalSuspended,
     ParameterDescription(Vec<i32>),
+    CopyInResponse {
+        /// Overall format of the copy data.
+        format: Format,
+        /// Format of each column. Must all be text if the overall format is
+        /// text.
+        column_formats: Vec<Format>,
+    },
 }
 
 impl From<ErrorResponse> for BackendMessage {
@@ -192,11 +247,35 @@ pub enum SqlState {
     // Class 01 — Warning
     Warning,
 
+    // Class 08 — Connection Exception
+    ProtocolViolation,
+
     // Class 0A — Feature Not Supported
     FeatureNotSupported,
 
+    // Class 22 — Data Exception
+    InvalidTextRepresentation,
+    BadCopyFileFormat,
+
+    // Class 25 — Invalid Transaction State
+    NoActiveSqlTransaction,
+    InFailedSqlTransaction,
+
+    // Class 28 — Invalid Authorization Specification
+    InvalidPassword,
+
+    // Class 34 — Invalid Cursor Name
+    InvalidCursorName,
+
+    // Class 40 — Transaction Rollback
+    SerializationFailure,
+
     // Class 42 — Syntax Error or Access Rule Violation
     SyntaxError,
+    DuplicateCursor,
+
+    // Class 57 — Operator Intervention
+    QueryCanceled,
 
     // Class XX — Internal Error
     InternalError,
@@ -207,8 +286,18 @@ impl SqlState {
         match self {
             SqlState::Successful => "00000",
             SqlState::Warning => "01000",
+            SqlState::ProtocolViolation => "08P01",
             SqlState::FeatureNotSupported => "0A000",
+            SqlState::InvalidTextRepresentation => "22P02",
+            SqlState::BadCopyFileFormat => "22P04",
+            SqlState::NoActiveSqlTransaction => "25P01",
+            SqlState::InFailedSqlTransaction => "25P02",
+            SqlState::InvalidPassword => "28P01",
+            SqlState::InvalidCursorName => "34000",
+            SqlState::SerializationFailure => "40001",
             SqlState::SyntaxError => "42601",
+            SqlState::DuplicateCursor => "42P03",
+            SqlState::QueryCanceled => "57014",
             SqlState::InternalError => "XX000",
         }
     }
@@ -238,19 +327,35 @@ impl ErrorResponse {
         Self::error(SqlState::InternalError, msg)
     }
 
-    pub fn fatal_internal(msg: impl Into<String>) -> ErrorResponse {
+    pub fn fatal(code: SqlState, msg: impl Into<String>) -> ErrorResponse {
         ErrorResponse {
             severity: ErrorSeverity::Fatal,
-            code: SqlState::InternalError,
+            code,
             message: msg.into(),
         }
     }
+
+    pub fn fatal_internal(msg: impl Into<String>) -> ErrorResponse {
+        Self::fatal(SqlState::InternalError, msg)
+    }
 }
 
 impl From<ExecError> for ErrorResponse {
     fn from(e: ExecError) -> Self {
         // TODO: Actually set appropriate codes.
-        ErrorResponse::error_internal(e.to_string())
+        if let Some(canceled) = e.as_query_canceled() {
+            // Report the cancel itself, not the datafusion error wrapping it.
+            return ErrorResponse::error(SqlState::QueryCanceled, canceled.to_string());
+        }
+        let code = match &e {
+            ExecError::InFailedTransaction => SqlState::InFailedSqlTransaction,
+            ExecError::TransactionConflict(_) => SqlState::SerializationFailure,
+            ExecError::CursorOutsideTransaction => SqlState::NoActiveSqlTransaction,
+            ExecError::UnknownCursor(_) => SqlState::InvalidCursorName,
+            ExecError::DuplicateCursor(_) => SqlState::DuplicateCursor,
+            _ => SqlState::InternalError,
+        };
+        ErrorResponse::error(code, e.to_string())
     }
 }
 
@@ -3,9 +3,13 @@ use crate::codec::{
     server::{FramedConn, PgCodec},
 };
 use crate::errors::{PgSrvError, Result};
-use crate::messages::{BackendMessage, ErrorResponse, FrontendMessage, StartupMessage, VERSION_V3};
+use crate::handler::BackendKey;
+use crate::messages::{
+    BackendMessage, ErrorResponse, FrontendMessage, StartupMessage, VERSION_CANCEL, VERSION_V3,
+};
 use crate::ssl::Connection;
 use crate::ssl::SslConfig;
+use parking_lot::Mutex;
 use proxyutil::cloudauth::{AuthParams, DatabaseDetails, ProxyAuthenticator, ServiceProtocol};
 use std::{borrow::Cow, collections::HashMap};
 use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
@@ -150,6 +154,10 @@ pub const GLAREDB_GCS_STORAGE_BUCKET_KEY: &str = "gcs_storage_bucket";
 pub struct ProxyHandler<A> {
     authenticator: A,
     ssl_conf: Option<SslConfig>,
+    /// Addresses of the databases that proxied connections are connected to,
+    /// keyed by the backend key each database handed out. Used to forward
+    /// cancel requests.
+    cancel_targets: Mutex<HashMap<BackendKey, String>>,
 }
 
 impl<A: ProxyAuthenticator> ProxyHandler<A> {
@@ -157,6 +165,7 @@ impl<A: ProxyAuthenticator> ProxyHandler<A> {
         Self {
             authenticator,
             ssl_conf,
+            cancel_targets: Mutex::new(HashMap::new()),
         }
     }
 
@@ -196,8 +205,8 @@ impl<A: ProxyAuthenticator> ProxyHandler<A> {
                         }
                     }
                 }
-                StartupMessage::CancelRequest { .. } => {
-                    self.proxy_cancel(conn).await?;
+                StartupMessage::CancelRequest { pid, secret, .. } => {
+                    self.proxy_cancel(conn, BackendKey { pid, secret }).await?;
                     return Ok(());
                 }
             }
@@ -244,7 +253,7 @@ impl<A: ProxyAuthenticator> ProxyHandler<A> {
         // startup message We need to send the same parameters as the client
         // sent us
         let db_addr = format!("{}:{}", db_details.ip, db_details.port);
-        let db_conn = TcpStream::connect(db_addr).await?;
+        let db_conn = TcpStream::connect(&db_addr).await?;
         // Note that the connection from the proxy to the db is unencrypted,
         // with no option (currently) of encrypting it.
         let mut db_framed = FramedClientConn::new(Connection::Unencrypted(db_conn));
@@ -289,14 +298,46 @@ impl<A: ProxyAuthenticator> ProxyHandler<A> {
         match auth_msg {
             Some(BackendMessage::AuthenticationOk) => {
                 framed.send(BackendMessage::AuthenticationOk).await?;
+
+                // The database sends its backend key after authenticating,
+                // possibly after other startup messages (e.g. parameter
+                // statuses). Remember where the key came from so cancel
+                // requests can be forwarded to the right database. Stop
+                // looking once the database is ready for queries, in which
+                // case it didn't send a key.
+                let key = loop {
+                    match db_framed.read().await? {
+                        Some(BackendMessage::BackendKeyData { pid, secret }) => {
+                            framed
+                                .send(BackendMessage::BackendKeyData { pid, secret })
+                                .await?;
+                            break Some(BackendKey { pid, secret });
+                        }
+                        Some(msg @ BackendMessage::ReadyForQuery(_)) => {
+                            framed.send(msg).await?;
+                            break None;
+                        }
+                        Some(msg) => framed.send(msg).await?,
+                        None => return Ok(()),
+                    }
+                };
+                if let Some(key) = key {
+                    self.cancel_targets.lock().insert(key, db_addr);
+                }
+
                 // from here, we can just forward messages between the client to the database
                 let server_conn = db_framed.into_inner();
                 let client_conn = framed.into_inner();
-                tokio::io::copy_bidirectional(
+                let result = tokio::io::copy_bidirectional(
                     &mut client_conn.into_inner(),
                     &mut server_conn.into_inner(),
                 )
-                .await?;
+                .await;
+
+                if let Some(key) = key {
+                    self.cancel_targets.lock().remove(&key);
+                }
+                result?;
 
                 Ok(())
             }
@@ -307,13 +348,32 @@ impl<A: ProxyAuthenticator> ProxyHandler<A> {
 
     /// Proxy a cancel request.
     ///
-    /// Currently unimplemented. This will require that we broadcast cancel
-    /// requests to the appropriate database instances.
-    async fn proxy_cancel<C>(&self, mut _conn: Connection<C>) -> Result<()>
+    /// Forwards the request to the database that handed out the key. Note
+    /// that this only works if the cancel request hits the same proxy
+    /// instance as the connection being canceled. Unknown keys are ignored.
+    async fn proxy_cancel<C>(&self, _conn: Connection<C>, key: BackendKey) -> Result<()>
     where
         C: AsyncRead + AsyncWrite + Unpin,
     {
-        debug!("cancel received (proxy)");
+        debug!(?key, "cancel received (proxy)");
+        let db_addr = match self.cancel_targets.lock().get(&key).cloned() {
+            Some(addr) => addr,
+            None => {
+                debug!(?key, "no database found for cancel request");
+                return Ok(());
+            }
+        };
+
+        let db_conn = TcpStream::connect(db_addr).await?;
+        let mut db_framed = FramedClientConn::new(Connection::Unencrypted(db_conn));
+        db_framed
+            .send_startup(StartupMessage::CancelRequest {
+                version: VERSION_CANCEL,
+                pid: key.pid,
+                secret: key.secret,
+            })
+            .await?;
+
         Ok(())
     }
 
@@ -424,6 +484,62 @@ fn parse_options(params: &HashMap<String, String>) -> Option<HashMap<String, Str
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::codec::client::PgClientCodec;
+    use crate::messages::TransactionStatus;
+    use bytes::{BufMut, BytesMut};
+    use tokio_util::codec::{Decoder, Encoder};
+
+    #[tokio::test]
+    async fn forwarded_cancel_request_decodes() {
+        let mut buf = BytesMut::new();
+        PgClientCodec
+            .encode(
+                StartupMessage::CancelRequest {
+                    version: VERSION_CANCEL,
+                    pid: 1234,
+                    secret: -5678,
+                },
+                &mut buf,
+            )
+            .unwrap();
+
+        let msg = PgCodec::decode_startup_from_conn(&mut buf.as_ref())
+            .await
+            .unwrap();
+        match msg {
+            StartupMessage::CancelRequest { pid, secret, .. } => {
+                assert_eq!(1234, pid);
+                assert_eq!(-5678, secret);
+            }
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn startup_messages_decode() {
+        // Messages a database may send between the backend key and being
+        // ready for queries.
+        let mut buf = BytesMut::new();
+        let status = b"server_version\015.1\0";
+        buf.put_u8(b'S');
+        buf.put_i32(4 + status.len() as i32);
+        buf.put_slice(status);
+        buf.put_u8(b'Z');
+        buf.put_i32(5);
+        buf.put_u8(b'I');
+
+        match PgClientCodec.decode(&mut buf).unwrap() {
+            Some(BackendMessage::ParameterStatus { key, val }) => {
+                assert_eq!("server_version", key);
+                assert_eq!("15.1", val);
+            }
+            other => panic!("unexpected message: {other:?}"),
+        }
+        match PgClientCodec.decode(&mut buf).unwrap() {
+            Some(BackendMessage::ReadyForQuery(TransactionStatus::Idle)) => (),
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
 
     #[test]
     fn parse_options_from_params() {
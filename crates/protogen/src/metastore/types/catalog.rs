@@ -98,6 +98,7 @@ pub enum CatalogEntry {
     Tunnel(TunnelEntry),
     Function(FunctionEntry),
     Credentials(CredentialsEntry),
+    Role(RoleEntry),
 }
 
 impl CatalogEntry {
@@ -110,6 +111,7 @@ impl CatalogEntry {
             CatalogEntry::Tunnel(_) => EntryType::Tunnel,
             CatalogEntry::Function(_) => EntryType::Function,
             CatalogEntry::Credentials(_) => EntryType::Credentials,
+            CatalogEntry::Role(_) => EntryType::Role,
         }
     }
 
@@ -123,6 +125,7 @@ impl CatalogEntry {
             CatalogEntry::Tunnel(tunnel) => &tunnel.meta,
             CatalogEntry::Function(func) => &func.meta,
             CatalogEntry::Credentials(creds) => &creds.meta,
+            CatalogEntry::Role(role) => &role.meta,
         }
     }
 
@@ -136,6 +139,7 @@ impl CatalogEntry {
             CatalogEntry::Tunnel(tunnel) => &mut tunnel.meta,
             CatalogEntry::Function(func) => &mut func.meta,
             CatalogEntry::Credentials(creds) => &mut creds.meta,
+            CatalogEntry::Role(role) => &mut role.meta,
         }
     }
 }
@@ -153,6 +157,7 @@ impl TryFrom<catalog::catalog_entry::Entry> for CatalogEntry {
             catalog::catalog_entry::Entry::Credentials(v) => {
                 CatalogEntry::Credentials(v.try_into()?)
             }
+            catalog::catalog_entry::Entry::Role(v) => CatalogEntry::Role(v.try_into()?),
         })
     }
 }
@@ -175,6 +180,7 @@ impl TryFrom<CatalogEntry> for catalog::CatalogEntry {
             CatalogEntry::Tunnel(v) => catalog::catalog_entry::Entry::Tunnel(v.into()),
             CatalogEntry::Function(v) => catalog::catalog_entry::Entry::Function(v.into()),
             CatalogEntry::Credentials(v) => catalog::catalog_entry::Entry::Credentials(v.into()),
+            CatalogEntry::Role(v) => catalog::catalog_entry::Entry::Role(v.into()),
         };
         Ok(catalog::CatalogEntry { entry: Some(ent) })
     }
@@ -189,6 +195,7 @@ pub enum EntryType {
     Tunnel,
     Function,
     Credentials,
+    Role,
 }
 
 impl EntryType {
@@ -201,6 +208,7 @@ impl EntryType {
             EntryType::Tunnel => "tunnel",
             EntryType::Function => "function",
             EntryType::Credentials => "credentials",
+            EntryType::Role => "role",
         }
     }
 }
@@ -228,6 +236,7 @@ impl TryFrom<catalog::entry_meta::EntryType> for EntryType {
             catalog::entry_meta::EntryType::Tunnel => EntryType::Tunnel,
             catalog::entry_meta::EntryType::Function => EntryType::Function,
             catalog::entry_meta::EntryType::Credentials => EntryType::Credentials,
+            catalog::entry_meta::EntryType::Role => EntryType::Role,
         })
     }
 }
@@ -242,6 +251,7 @@ impl From<EntryType> for catalog::entry_meta::EntryType {
             EntryType::Tunnel => catalog::entry_meta::EntryType::Tunnel,
             EntryType::Function => catalog::entry_meta::EntryType::Function,
             EntryType::Credentials => catalog::entry_meta::EntryType::Credentials,
+            EntryType::Role => catalog::entry_meta::EntryType::Role,
         }
     }
 }
@@ -832,6 +842,185 @@ impl From<CredentialsEntry> for catalog::CredentialsEntry {
     }
 }
 
+#[derive(Debug, Clone, Copy, Arbitrary, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Privilege {
+    Select,
+    Insert,
+    Update,
+    Delete,
+    Usage,
+    Create,
+}
+
+impl Privilege {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Privilege::Select => "SELECT",
+            Privilege::Insert => "INSERT",
+            Privilege::Update => "UPDATE",
+            Privilege::Delete => "DELETE",
+            Privilege::Usage => "USAGE",
+            Privilege::Create => "CREATE",
+        }
+    }
+
+    /// Privileges that can be granted on tables and views.
+    pub const TABLE_PRIVILEGES: &'static [Privilege] = &[
+        Privilege::Select,
+        Privilege::Insert,
+        Privilege::Update,
+        Privilege::Delete,
+    ];
+
+    /// Privileges that can be granted on schemas.
+    pub const SCHEMA_PRIVILEGES: &'static [Privilege] = &[Privilege::Usage, Privilege::Create];
+}
+
+impl FromStr for Privilege {
+    type Err = ProtoConvError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_uppercase();
+        let privilege = catalog::Privilege::from_str_name(&s)
+            .ok_or_else(|| ProtoConvError::ParseError(format!("invalid privilege: {s}")))?;
+        privilege.try_into()
+    }
+}
+
+impl Display for Privilege {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl TryFrom<i32> for Privilege {
+    type Error = ProtoConvError;
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        catalog::Privilege::try_from(value)
+            .map_err(|_| ProtoConvError::UnknownEnumVariant("Privilege", value))
+            .and_then(|p| p.try_into())
+    }
+}
+
+impl TryFrom<catalog::Privilege> for Privilege {
+    type Error = ProtoConvError;
+    fn try_from(value: catalog::Privilege) -> Result<Self, Self::Error> {
+        Ok(match value {
+            catalog::Privilege::Unknown => {
+                return Err(ProtoConvError::ZeroValueEnumVariant("Privilege"))
+            }
+            catalog::Privilege::Select => Privilege::Select,
+            catalog::Privilege::Insert => Privilege::Insert,
+            catalog::Privilege::Update => Privilege::Update,
+            catalog::Privilege::Delete => Privilege::Delete,
+            catalog::Privilege::Usage => Privilege::Usage,
+            catalog::Privilege::Create => Privilege::Create,
+        })
+    }
+}
+
+impl From<Privilege> for catalog::Privilege {
+    fn from(value: Privilege) -> Self {
+        match value {
+            Privilege::Select => catalog::Privilege::Select,
+            Privilege::Insert => catalog::Privilege::Insert,
+            Privilege::Update => catalog::Privilege::Update,
+            Privilege::Delete => catalog::Privilege::Delete,
+            Privilege::Usage => catalog::Privilege::Usage,
+            Privilege::Create => catalog::Privilege::Create,
+        }
+    }
+}
+
+impl From<Privilege> for i32 {
+    fn from(value: Privilege) -> Self {
+        let value: catalog::Privilege = value.into();
+        value as i32
+    }
+}
+
+#[derive(Debug, Clone, Arbitrary, PartialEq, Eq)]
+pub struct RoleGrant {
+    pub object_id: u32,
+    pub privileges: Vec<Privilege>,
+}
+
+impl TryFrom<catalog::RoleGrant> for RoleGrant {
+    type Error = ProtoConvError;
+    fn try_from(value: catalog::RoleGrant) -> Result<Self, Self::Error> {
+        Ok(RoleGrant {
+            object_id: value.object_id,
+            privileges: value
+                .privileges
+                .into_iter()
+                .map(Privilege::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<RoleGrant> for catalog::RoleGrant {
+    fn from(value: RoleGrant) -> Self {
+        catalog::RoleGrant {
+            object_id: value.object_id,
+            privileges: value.privileges.into_iter().map(i32::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Arbitrary, PartialEq, Eq)]
+pub struct RoleEntry {
+    pub meta: EntryMeta,
+    /// Stored password verifier, formatted the same as in postgres'
+    /// `pg_authid`. Empty if the role has no password.
+    pub password_verifier: String,
+    pub login: bool,
+    pub superuser: bool,
+    pub grants: Vec<RoleGrant>,
+}
+
+impl RoleEntry {
+    /// Check if this role was granted a privilege on an object.
+    ///
+    /// Superusers always have every privilege.
+    pub fn has_privilege(&self, object_id: u32, privilege: Privilege) -> bool {
+        self.superuser
+            || self
+                .grants
+                .iter()
+                .any(|grant| grant.object_id == object_id && grant.privileges.contains(&privilege))
+    }
+}
+
+impl TryFrom<catalog::RoleEntry> for RoleEntry {
+    type Error = ProtoConvError;
+    fn try_from(value: catalog::RoleEntry) -> Result<Self, Self::Error> {
+        let meta: EntryMeta = value.meta.required("meta")?;
+        Ok(RoleEntry {
+            meta,
+            password_verifier: value.password_verifier,
+            login: value.login,
+            superuser: value.superuser,
+            grants: value
+                .grants
+                .into_iter()
+                .map(RoleGrant::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<RoleEntry> for catalog::RoleEntry {
+    fn from(value: RoleEntry) -> Self {
+        catalog::RoleEntry {
+            meta: Some(value.meta.into()),
+            password_verifier: value.password_verifier,
+            login: value.login,
+            superuser: value.superuser,
+            grants: value.grants.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -847,6 +1036,15 @@ mod tests {
         }
     }
 
+    proptest! {
+        #[test]
+        fn roundtrip_role_entry(expected in any::<RoleEntry>()) {
+            let p: catalog::RoleEntry = expected.clone().into();
+            let got: RoleEntry = p.try_into().unwrap();
+            assert_eq!(expected, got);
+        }
+    }
+
     proptest! {
         #[test]
         fn roundtrip_entry_meta(expected in any::<EntryMeta>()) {
@@ -93,6 +93,7 @@ pub enum DatabaseOptions {
     Delta(DatabaseOptionsDeltaLake),
     SqlServer(DatabaseOptionsSqlServer),
     Clickhouse(DatabaseOptionsClickhouse),
+    Sqlite(DatabaseOptionsSqlite),
 }
 
 impl DatabaseOptions {
@@ -106,6 +107,7 @@ impl DatabaseOptions {
     pub const DELTA: &'static str = "delta";
     pub const SQL_SERVER: &'static str = "sql_server";
     pub const CLICKHOUSE: &'static str = "clickhouse";
+    pub const SQLITE: &'static str = "sqlite";
 
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -119,6 +121,7 @@ impl DatabaseOptions {
             DatabaseOptions::Delta(_) => Self::DELTA,
             DatabaseOptions::SqlServer(_) => Self::SQL_SERVER,
             DatabaseOptions::Clickhouse(_) => Self::CLICKHOUSE,
+            DatabaseOptions::Sqlite(_) => Self::SQLITE,
         }
     }
 }
@@ -157,6 +160,7 @@ impl TryFrom<options::database_options::Options> for DatabaseOptions {
             options::database_options::Options::Clickhouse(v) => {
                 DatabaseOptions::Clickhouse(v.try_into()?)
             }
+            options::database_options::Options::Sqlite(v) => DatabaseOptions::Sqlite(v.try_into()?),
         })
     }
 }
@@ -187,6 +191,7 @@ impl From<DatabaseOptions> for options::database_options::Options {
             DatabaseOptions::Clickhouse(v) => {
                 options::database_options::Options::Clickhouse(v.into())
             }
+            DatabaseOptions::Sqlite(v) => options::database_options::Options::Sqlite(v.into()),
         }
     }
 }
@@ -366,6 +371,31 @@ impl From<DatabaseOptionsClickhouse> for options::DatabaseOptionsClickhouse {
     }
 }
 
+#[derive(Debug, Clone, Arbitrary, PartialEq, Eq, Hash)]
+pub struct DatabaseOptionsSqlite {
+    pub location: String,
+    pub storage_options: StorageOptions,
+}
+
+impl TryFrom<options::DatabaseOptionsSqlite> for DatabaseOptionsSqlite {
+    type Error = ProtoConvError;
+    fn try_from(value: options::DatabaseOptionsSqlite) -> Result<Self, Self::Error> {
+        Ok(DatabaseOptionsSqlite {
+            location: value.location,
+            storage_options: value.storage_options.required("storage_options")?,
+        })
+    }
+}
+
+impl From<DatabaseOptionsSqlite> for options::DatabaseOptionsSqlite {
+    fn from(value: DatabaseOptionsSqlite) -> Self {
+        options::DatabaseOptionsSqlite {
+            location: value.location,
+            storage_options: Some(value.storage_options.into()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Arbitrary, PartialEq, Eq, Hash)]
 pub struct DatabaseOptionsSnowflake {
     pub account_name: String,
@@ -549,6 +579,7 @@ pub enum TableOptions {
     Lance(TableOptionsObjectStore),
     Bson(TableOptionsObjectStore),
     Clickhouse(TableOptionsClickhouse),
+    Sqlite(TableOptionsSqlite),
 }
 
 impl TableOptions {
@@ -569,9 +600,13 @@ impl TableOptions {
     pub const LANCE: &'static str = "lance";
     pub const BSON: &'static str = "bson";
     pub const CLICKHOUSE: &'static str = "clickhouse";
+    pub const SQLITE: &'static str = "sqlite";
 
     pub const fn new_internal(columns: Vec<InternalColumnDefinition>) -> TableOptions {
-        TableOptions::Internal(TableOptionsInternal { columns })
+        TableOptions::Internal(TableOptionsInternal {
+            columns,
+            partition_by: Vec::new(),
+        })
     }
 
     pub fn as_str(&self) -> &'static str {
@@ -593,6 +628,7 @@ impl TableOptions {
             TableOptions::Lance(_) => Self::LANCE,
             TableOptions::Bson(_) => Self::BSON,
             TableOptions::Clickhouse(_) => Self::CLICKHOUSE,
+            TableOptions::Sqlite(_) => Self::SQLITE,
         }
     }
 }
@@ -626,6 +662,7 @@ impl TryFrom<options::table_options::Options> for TableOptions {
             options::table_options::Options::Clickhouse(v) => {
                 TableOptions::Clickhouse(v.try_into()?)
             }
+            options::table_options::Options::Sqlite(v) => TableOptions::Sqlite(v.try_into()?),
         })
     }
 }
@@ -658,6 +695,7 @@ impl TryFrom<TableOptions> for options::table_options::Options {
             TableOptions::Lance(v) => options::table_options::Options::Lance(v.into()),
             TableOptions::Bson(v) => options::table_options::Options::Bson(v.into()),
             TableOptions::Clickhouse(v) => options::table_options::Options::Clickhouse(v.into()),
+            TableOptions::Sqlite(v) => options::table_options::Options::Sqlite(v.into()),
         })
     }
 }
@@ -674,6 +712,9 @@ impl TryFrom<TableOptions> for options::TableOptions {
 #[derive(Debug, Clone, Arbitrary, PartialEq, Eq, Hash)]
 pub struct TableOptionsInternal {
     pub columns: Vec<InternalColumnDefinition>,
+    /// Columns the table is partitioned by. Empty if the table isn't
+    /// partitioned.
+    pub partition_by: Vec<String>,
 }
 
 impl From<DFSchemaRef> for TableOptionsInternal {
@@ -688,6 +729,7 @@ impl From<DFSchemaRef> for TableOptionsInternal {
                     arrow_type: col.data_type().clone(),
                 })
                 .collect::<Vec<_>>(),
+            partition_by: Vec::new(),
         }
     }
 }
@@ -704,6 +746,7 @@ impl From<SchemaRef> for TableOptionsInternal {
                     arrow_type: col.data_type().clone(),
                 })
                 .collect::<Vec<_>>(),
+            partition_by: Vec::new(),
         }
     }
 }
@@ -717,6 +760,7 @@ impl TryFrom<options::TableOptionsInternal> for TableOptionsInternal {
                 .into_iter()
                 .map(|col| col.try_into())
                 .collect::<Result<_, _>>()?,
+            partition_by: value.partition_by,
         })
     }
 }
@@ -730,6 +774,7 @@ impl TryFrom<TableOptionsInternal> for options::TableOptionsInternal {
                 .into_iter()
                 .map(|col| col.try_into())
                 .collect::<Result<_, _>>()?,
+            partition_by: value.partition_by,
         })
     }
 }
@@ -848,6 +893,7 @@ pub struct TableOptionsLocal {
     pub location: String,
     pub file_type: String,
     pub compression: Option<String>,
+    pub partition_columns: Vec<String>,
 }
 
 impl TryFrom<options::TableOptionsLocal> for TableOptionsLocal {
@@ -857,6 +903,7 @@ impl TryFrom<options::TableOptionsLocal> for TableOptionsLocal {
             location: value.location,
             file_type: value.file_type,
             compression: value.compression,
+            partition_columns: value.partition_columns,
         })
     }
 }
@@ -867,6 +914,7 @@ impl From<TableOptionsLocal> for options::TableOptionsLocal {
             location: value.location,
             file_type: value.file_type,
             compression: value.compression,
+            partition_columns: value.partition_columns,
         }
     }
 }
@@ -878,6 +926,7 @@ pub struct TableOptionsGcs {
     pub location: String,
     pub file_type: String,
     pub compression: Option<String>,
+    pub partition_columns: Vec<String>,
 }
 
 impl TryFrom<options::TableOptionsGcs> for TableOptionsGcs {
@@ -889,6 +938,7 @@ impl TryFrom<options::TableOptionsGcs> for TableOptionsGcs {
             location: value.location,
             file_type: value.file_type,
             compression: value.compression,
+            partition_columns: value.partition_columns,
         })
     }
 }
@@ -901,6 +951,7 @@ impl From<TableOptionsGcs> for options::TableOptionsGcs {
             location: value.location,
             file_type: value.file_type,
             compression: value.compression,
+            partition_columns: value.partition_columns,
         }
     }
 }
@@ -914,6 +965,7 @@ pub struct TableOptionsS3 {
     pub location: String,
     pub file_type: String,
     pub compression: Option<String>,
+    pub partition_columns: Vec<String>,
 }
 
 impl TryFrom<options::TableOptionsS3> for TableOptionsS3 {
@@ -927,6 +979,7 @@ impl TryFrom<options::TableOptionsS3> for TableOptionsS3 {
             location: value.location,
             file_type: value.file_type,
             compression: value.compression,
+            partition_columns: value.partition_columns,
         })
     }
 }
@@ -941,6 +994,7 @@ impl From<TableOptionsS3> for options::TableOptionsS3 {
             location: value.location,
             file_type: value.file_type,
             compression: value.compression,
+            partition_columns: value.partition_columns,
         }
     }
 }
@@ -1025,6 +1079,34 @@ impl From<TableOptionsClickhouse> for options::TableOptionsClickhouse {
     }
 }
 
+#[derive(Debug, Clone, Arbitrary, PartialEq, Eq, Hash)]
+pub struct TableOptionsSqlite {
+    pub location: String,
+    pub storage_options: StorageOptions,
+    pub table: String,
+}
+
+impl TryFrom<options::TableOptionsSqlite> for TableOptionsSqlite {
+    type Error = ProtoConvError;
+    fn try_from(value: options::TableOptionsSqlite) -> Result<Self, Self::Error> {
+        Ok(TableOptionsSqlite {
+            location: value.location,
+            storage_options: value.storage_options.required("storage_options")?,
+            table: value.table,
+        })
+    }
+}
+
+impl From<TableOptionsSqlite> for options::TableOptionsSqlite {
+    fn from(value: TableOptionsSqlite) -> Self {
+        options::TableOptionsSqlite {
+            location: value.location,
+            storage_options: Some(value.storage_options.into()),
+            table: value.table,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Arbitrary, PartialEq, Eq, Hash)]
 pub struct TableOptionsSnowflake {
     pub account_name: String,
@@ -1075,6 +1157,7 @@ pub struct TableOptionsObjectStore {
     pub file_type: Option<String>,
     pub compression: Option<String>,
     pub schema_sample_size: Option<i64>,
+    pub partition_columns: Vec<String>,
 }
 
 impl TryFrom<options::TableOptionsObjectStore> for TableOptionsObjectStore {
@@ -1086,6 +1169,7 @@ impl TryFrom<options::TableOptionsObjectStore> for TableOptionsObjectStore {
             file_type: value.file_type,
             compression: value.compression,
             schema_sample_size: value.schema_sample_size,
+            partition_columns: value.partition_columns,
         })
     }
 }
@@ -1098,6 +1182,7 @@ impl From<TableOptionsObjectStore> for options::TableOptionsObjectStore {
             file_type: value.file_type,
             compression: value.compression,
             schema_sample_size: value.schema_sample_size,
+            partition_columns: value.partition_columns,
         }
     }
 }
@@ -1476,6 +1561,9 @@ pub enum CopyToFormatOptions {
     Parquet(CopyToFormatOptionsParquet),
     Json(CopyToFormatOptionsJson),
     Bson,
+    Iceberg,
+    Arrow,
+    Delta,
 }
 
 impl Default for CopyToFormatOptions {
@@ -1492,6 +1580,9 @@ impl CopyToFormatOptions {
     pub const PARQUET: &'static str = "parquet";
     pub const JSON: &'static str = "json";
     pub const BSON: &'static str = "bson";
+    pub const ICEBERG: &'static str = "iceberg";
+    pub const ARROW: &'static str = "arrow";
+    pub const DELTA: &'static str = "delta";
 
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -1499,6 +1590,9 @@ impl CopyToFormatOptions {
             Self::Parquet(_) => Self::PARQUET,
             Self::Json(_) => Self::JSON,
             Self::Bson => Self::BSON,
+            Self::Iceberg => Self::ICEBERG,
+            Self::Arrow => Self::ARROW,
+            Self::Delta => Self::DELTA,
         }
     }
 }
@@ -1,4 +1,4 @@
-use super::catalog::SourceAccessMode;
+use super::catalog::{Privilege, SourceAccessMode};
 use super::options::{
     CredentialsOptions, DatabaseOptions, TableOptions, TableOptionsInternal, TunnelOptions,
 };
@@ -24,6 +24,10 @@ pub enum Mutation {
     CreateCredentials(CreateCredentials),
     CreateCredential(CreateCredential),
     DropCredentials(DropCredentials),
+    CreateRole(CreateRole),
+    DropRole(DropRole),
+    GrantPrivileges(GrantPrivileges),
+    RevokePrivileges(RevokePrivileges),
     // Deployment metadata updates
     UpdateDeploymentStorage(UpdateDeploymentStorage),
 }
@@ -67,6 +71,14 @@ impl TryFrom<service::mutation::Mutation> for Mutation {
             service::mutation::Mutation::DropCredentials(v) => {
                 Mutation::DropCredentials(v.try_into()?)
             }
+            service::mutation::Mutation::CreateRole(v) => Mutation::CreateRole(v.try_into()?),
+            service::mutation::Mutation::DropRole(v) => Mutation::DropRole(v.try_into()?),
+            service::mutation::Mutation::GrantPrivileges(v) => {
+                Mutation::GrantPrivileges(v.try_into()?)
+            }
+            service::mutation::Mutation::RevokePrivileges(v) => {
+                Mutation::RevokePrivileges(v.try_into()?)
+            }
             service::mutation::Mutation::UpdateDeploymentStorage(v) => {
                 Mutation::UpdateDeploymentStorage(v.try_into()?)
             }
@@ -104,6 +116,12 @@ impl TryFrom<Mutation> for service::mutation::Mutation {
                 service::mutation::Mutation::CreateCredential(v.into())
             }
             Mutation::DropCredentials(v) => service::mutation::Mutation::DropCredentials(v.into()),
+            Mutation::CreateRole(v) => service::mutation::Mutation::CreateRole(v.into()),
+            Mutation::DropRole(v) => service::mutation::Mutation::DropRole(v.into()),
+            Mutation::GrantPrivileges(v) => service::mutation::Mutation::GrantPrivileges(v.into()),
+            Mutation::RevokePrivileges(v) => {
+                service::mutation::Mutation::RevokePrivileges(v.into())
+            }
             Mutation::UpdateDeploymentStorage(v) => {
                 service::mutation::Mutation::UpdateDeploymentStorage(v.into())
             }
@@ -703,6 +721,163 @@ impl From<DropCredentials> for service::DropCredentials {
     }
 }
 
+#[derive(Debug, Clone, Arbitrary, PartialEq, Eq)]
+pub struct CreateRole {
+    pub name: String,
+    pub password_verifier: String,
+    pub login: bool,
+    pub superuser: bool,
+    pub if_not_exists: bool,
+}
+
+impl TryFrom<service::CreateRole> for CreateRole {
+    type Error = ProtoConvError;
+    fn try_from(value: service::CreateRole) -> Result<Self, Self::Error> {
+        Ok(CreateRole {
+            name: value.name,
+            password_verifier: value.password_verifier,
+            login: value.login,
+            superuser: value.superuser,
+            if_not_exists: value.if_not_exists,
+        })
+    }
+}
+
+impl From<CreateRole> for service::CreateRole {
+    fn from(value: CreateRole) -> Self {
+        service::CreateRole {
+            name: value.name,
+            password_verifier: value.password_verifier,
+            login: value.login,
+            superuser: value.superuser,
+            if_not_exists: value.if_not_exists,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Arbitrary, PartialEq, Eq)]
+pub struct DropRole {
+    pub name: String,
+    pub if_exists: bool,
+}
+
+impl TryFrom<service::DropRole> for DropRole {
+    type Error = ProtoConvError;
+    fn try_from(value: service::DropRole) -> Result<Self, Self::Error> {
+        Ok(DropRole {
+            name: value.name,
+            if_exists: value.if_exists,
+        })
+    }
+}
+
+impl From<DropRole> for service::DropRole {
+    fn from(value: DropRole) -> Self {
+        service::DropRole {
+            name: value.name,
+            if_exists: value.if_exists,
+        }
+    }
+}
+
+/// A schema, or a table or view in a schema.
+#[derive(Debug, Clone, Arbitrary, PartialEq, Eq, Hash)]
+pub struct PrivilegeObject {
+    pub schema: String,
+    pub name: Option<String>,
+}
+
+impl TryFrom<service::PrivilegeObject> for PrivilegeObject {
+    type Error = ProtoConvError;
+    fn try_from(value: service::PrivilegeObject) -> Result<Self, Self::Error> {
+        Ok(PrivilegeObject {
+            schema: value.schema,
+            name: value.name,
+        })
+    }
+}
+
+impl From<PrivilegeObject> for service::PrivilegeObject {
+    fn from(value: PrivilegeObject) -> Self {
+        service::PrivilegeObject {
+            schema: value.schema,
+            name: value.name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Arbitrary, PartialEq, Eq)]
+pub struct GrantPrivileges {
+    pub roles: Vec<String>,
+    pub objects: Vec<PrivilegeObject>,
+    pub privileges: Vec<Privilege>,
+}
+
+impl TryFrom<service::GrantPrivileges> for GrantPrivileges {
+    type Error = ProtoConvError;
+    fn try_from(value: service::GrantPrivileges) -> Result<Self, Self::Error> {
+        Ok(GrantPrivileges {
+            roles: value.roles,
+            objects: value
+                .objects
+                .into_iter()
+                .map(PrivilegeObject::try_from)
+                .collect::<Result<_, _>>()?,
+            privileges: value
+                .privileges
+                .into_iter()
+                .map(Privilege::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<GrantPrivileges> for service::GrantPrivileges {
+    fn from(value: GrantPrivileges) -> Self {
+        service::GrantPrivileges {
+            roles: value.roles,
+            objects: value.objects.into_iter().map(Into::into).collect(),
+            privileges: value.privileges.into_iter().map(i32::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Arbitrary, PartialEq, Eq)]
+pub struct RevokePrivileges {
+    pub roles: Vec<String>,
+    pub objects: Vec<PrivilegeObject>,
+    pub privileges: Vec<Privilege>,
+}
+
+impl TryFrom<service::RevokePrivileges> for RevokePrivileges {
+    type Error = ProtoConvError;
+    fn try_from(value: service::RevokePrivileges) -> Result<Self, Self::Error> {
+        Ok(RevokePrivileges {
+            roles: value.roles,
+            objects: value
+                .objects
+                .into_iter()
+                .map(PrivilegeObject::try_from)
+                .collect::<Result<_, _>>()?,
+            privileges: value
+                .privileges
+                .into_iter()
+                .map(Privilege::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<RevokePrivileges> for service::RevokePrivileges {
+    fn from(value: RevokePrivileges) -> Self {
+        service::RevokePrivileges {
+            roles: value.roles,
+            objects: value.objects.into_iter().map(Into::into).collect(),
+            privileges: value.privileges.into_iter().map(i32::from).collect(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Arbitrary, PartialEq, Eq)]
 pub struct UpdateDeploymentStorage {
     pub new_storage_size: u64,
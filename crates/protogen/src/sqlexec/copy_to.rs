@@ -62,7 +62,7 @@ pub struct CopyToDestinationOptionsAzure {
 
 #[derive(Clone, PartialEq, Message)]
 pub struct CopyToFormatOptions {
-    #[prost(oneof = "CopyToFormatOptionsEnum", tags = "1, 2, 3")]
+    #[prost(oneof = "CopyToFormatOptionsEnum", tags = "1, 2, 3, 4, 5, 6")]
     pub copy_to_format_options_enum: Option<CopyToFormatOptionsEnum>,
 }
 
@@ -74,6 +74,12 @@ pub enum CopyToFormatOptionsEnum {
     Json(CopyToFormatOptionsJson),
     #[prost(message, tag = "3")]
     Parquet(CopyToFormatOptionsParquet),
+    #[prost(message, tag = "4")]
+    Iceberg(CopyToFormatOptionsIceberg),
+    #[prost(message, tag = "5")]
+    Arrow(CopyToFormatOptionsArrow),
+    #[prost(message, tag = "6")]
+    Delta(CopyToFormatOptionsDelta),
 }
 
 #[derive(Clone, PartialEq, Message)]
@@ -96,6 +102,15 @@ pub struct CopyToFormatOptionsParquet {
     pub row_group_size: u64,
 }
 
+#[derive(Clone, PartialEq, Message)]
+pub struct CopyToFormatOptionsIceberg {}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CopyToFormatOptionsArrow {}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CopyToFormatOptionsDelta {}
+
 impl TryFrom<crate::metastore::types::options::CopyToFormatOptions> for CopyToFormatOptions {
     type Error = crate::errors::ProtoConvError;
     fn try_from(
@@ -131,6 +146,27 @@ impl TryFrom<crate::metastore::types::options::CopyToFormatOptions> for CopyToFo
                     )),
                 })
             }
+            crate::metastore::types::options::CopyToFormatOptions::Iceberg => {
+                Ok(CopyToFormatOptions {
+                    copy_to_format_options_enum: Some(CopyToFormatOptionsEnum::Iceberg(
+                        CopyToFormatOptionsIceberg {},
+                    )),
+                })
+            }
+            crate::metastore::types::options::CopyToFormatOptions::Arrow => {
+                Ok(CopyToFormatOptions {
+                    copy_to_format_options_enum: Some(CopyToFormatOptionsEnum::Arrow(
+                        CopyToFormatOptionsArrow {},
+                    )),
+                })
+            }
+            crate::metastore::types::options::CopyToFormatOptions::Delta => {
+                Ok(CopyToFormatOptions {
+                    copy_to_format_options_enum: Some(CopyToFormatOptionsEnum::Delta(
+                        CopyToFormatOptionsDelta {},
+                    )),
+                })
+            }
         }
     }
 }
@@ -167,6 +203,15 @@ impl TryFrom<CopyToFormatOptions> for crate::metastore::types::options::CopyToFo
                     },
                 ),
             ),
+            CopyToFormatOptionsEnum::Iceberg(_) => {
+                Ok(crate::metastore::types::options::CopyToFormatOptions::Iceberg)
+            }
+            CopyToFormatOptionsEnum::Arrow(_) => {
+                Ok(crate::metastore::types::options::CopyToFormatOptions::Arrow)
+            }
+            CopyToFormatOptionsEnum::Delta(_) => {
+                Ok(crate::metastore::types::options::CopyToFormatOptions::Delta)
+            }
         }
     }
 }
@@ -2,6 +2,7 @@ mod postgres;
 pub use postgres::*;
 
 use crate::gen::metastore::catalog::TableEntry;
+use crate::gen::metastore::service::PrivilegeObject;
 use datafusion_proto::protobuf::{LogicalExprNode, Schema};
 use prost::{Message, Oneof};
 
@@ -44,6 +45,8 @@ pub struct CreateTableExec {
     pub or_replace: bool,
     #[prost(message, tag = "5")]
     pub arrow_schema: Option<Schema>,
+    #[prost(string, repeated, tag = "6")]
+    pub partition_by: Vec<String>,
 }
 
 #[derive(Clone, PartialEq, Message)]
@@ -294,10 +297,90 @@ pub struct DeleteExec {
     pub where_expr: Option<LogicalExprNode>,
 }
 
+#[derive(Clone, PartialEq, Message)]
+pub struct OptimizeTableExec {
+    #[prost(message, tag = "1")]
+    pub table: Option<TableEntry>,
+    #[prost(string, repeated, tag = "2")]
+    pub zorder_by: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct VacuumTableExec {
+    #[prost(message, tag = "1")]
+    pub table: Option<TableEntry>,
+    #[prost(uint64, optional, tag = "2")]
+    pub retain_hours: Option<u64>,
+    #[prost(bool, tag = "3")]
+    pub dry_run: bool,
+    #[prost(bool, tag = "4")]
+    pub skip_retention_check: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CreateRoleExec {
+    #[prost(uint64, tag = "1")]
+    pub catalog_version: u64,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(string, tag = "3")]
+    pub password_verifier: String,
+    #[prost(bool, tag = "4")]
+    pub login: bool,
+    #[prost(bool, tag = "5")]
+    pub superuser: bool,
+    #[prost(bool, tag = "6")]
+    pub if_not_exists: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct DropRolesExec {
+    #[prost(uint64, tag = "1")]
+    pub catalog_version: u64,
+    #[prost(string, repeated, tag = "2")]
+    pub names: Vec<String>,
+    #[prost(bool, tag = "3")]
+    pub if_exists: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct GrantPrivilegesExec {
+    #[prost(uint64, tag = "1")]
+    pub catalog_version: u64,
+    #[prost(string, repeated, tag = "2")]
+    pub roles: Vec<String>,
+    #[prost(message, repeated, tag = "3")]
+    pub objects: Vec<PrivilegeObject>,
+    #[prost(
+        enumeration = "crate::gen::metastore::catalog::Privilege",
+        repeated,
+        tag = "4"
+    )]
+    pub privileges: Vec<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct RevokePrivilegesExec {
+    #[prost(uint64, tag = "1")]
+    pub catalog_version: u64,
+    #[prost(string, repeated, tag = "2")]
+    pub roles: Vec<String>,
+    #[prost(message, repeated, tag = "3")]
+    pub objects: Vec<PrivilegeObject>,
+    #[prost(
+        enumeration = "crate::gen::metastore::catalog::Privilege",
+        repeated,
+        tag = "4"
+    )]
+    pub privileges: Vec<i32>,
+}
+
 #[derive(Clone, PartialEq, Message)]
 pub struct InsertExec {
     #[prost(bytes, tag = "1")]
     pub provider_id: Vec<u8>, // UUID
+    #[prost(bool, tag = "2")]
+    pub overwrite: bool,
 }
 
 #[derive(Clone, PartialEq, Message)]
@@ -342,7 +425,7 @@ pub struct AnalyzeExec {
 pub struct ExecutionPlanExtension {
     #[prost(
         oneof = "ExecutionPlanExtensionType",
-        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31"
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34"
     )]
     pub inner: Option<ExecutionPlanExtensionType>,
 }
@@ -418,4 +501,17 @@ pub enum ExecutionPlanExtensionType {
     DescribeTable(DescribeTableExec),
     #[prost(message, tag = "32")]
     CreateCredentialExec(CreateCredentialExec),
+    // Maintenance
+    #[prost(message, tag = "33")]
+    OptimizeTableExec(OptimizeTableExec),
+    #[prost(message, tag = "34")]
+    VacuumTableExec(VacuumTableExec),
+    #[prost(message, tag = "35")]
+    CreateRoleExec(CreateRoleExec),
+    #[prost(message, tag = "36")]
+    DropRolesExec(DropRolesExec),
+    #[prost(message, tag = "37")]
+    GrantPrivilegesExec(GrantPrivilegesExec),
+    #[prost(message, tag = "38")]
+    RevokePrivilegesExec(RevokePrivilegesExec),
 }
@@ -44,6 +44,9 @@ pub enum RpcsrvError {
     #[error(transparent)]
     Arrow(#[from] datafusion::arrow::error::ArrowError),
 
+    #[error(transparent)]
+    Flight(#[from] arrow_flight::error::FlightError),
+
     #[error("{0:?}")]
     TonicTransport(#[from] tonic::transport::Error),
 
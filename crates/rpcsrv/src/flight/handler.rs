@@ -1,15 +1,29 @@
 use crate::{
     errors::{Result, RpcsrvError},
+    flight::{
+        metadata,
+        put::{self, PutStream},
+        session::{
+            CancelFlightInfoResult, CancelStatus, CloseSessionResult, CloseSessionStatus,
+            FlightSession, FlightSessionConfig, CANCEL_FLIGHT_INFO, CLOSE_SESSION,
+        },
+    },
     util::ConnKey,
 };
 
 use dashmap::DashMap;
-use datafusion::{arrow::ipc::writer::IpcWriteOptions, logical_expr::LogicalPlan};
+use datafusion::{
+    arrow::datatypes::{Field, Schema},
+    arrow::ipc::writer::IpcWriteOptions,
+    arrow::record_batch::RecordBatch,
+    logical_expr::LogicalPlan,
+};
 use datafusion_ext::vars::SessionVars;
 use once_cell::sync::Lazy;
 use sqlexec::{
     engine::{Engine, SessionStorageConfig},
-    session::Session,
+    session::{ExecutionResult, Session, TransactionStatus},
+    session_registry::SessionRegistry,
     OperationInfo,
 };
 use std::{pin::Pin, sync::Arc};
@@ -19,8 +33,9 @@ use uuid::Uuid;
 pub use arrow_flight::flight_service_server::FlightServiceServer;
 use arrow_flight::{
     encode::FlightDataEncoderBuilder, error::FlightError::ExternalError,
-    flight_service_server::FlightService, sql::*, Action, FlightDescriptor, FlightEndpoint,
-    FlightInfo, IpcMessage, SchemaAsIpc, Ticket,
+    flight_service_server::FlightService, sql::*, Action, ActionType, Criteria, Empty, FlightData,
+    FlightDescriptor, FlightEndpoint, FlightInfo, IpcMessage, PutResult, SchemaAsIpc, SchemaResult,
+    Ticket,
 };
 use arrow_flight::{
     sql::{
@@ -30,9 +45,10 @@ use arrow_flight::{
     HandshakeRequest, HandshakeResponse,
 };
 use futures::Stream;
-use futures::TryStreamExt;
+use futures::{StreamExt, TryStreamExt};
 use prost::Message;
 use tonic::{Request, Response, Status, Streaming};
+use tracing::debug;
 
 static INSTANCE_SQL_DATA: Lazy<SqlInfoData> = Lazy::new(|| {
     let mut builder = SqlInfoDataBuilder::new();
@@ -40,6 +56,43 @@ static INSTANCE_SQL_DATA: Lazy<SqlInfoData> = Lazy::new(|| {
     builder.append(SqlInfo::FlightSqlServerName, "GlareDB Flight Server");
     builder.append(SqlInfo::FlightSqlServerVersion, env!("CARGO_PKG_VERSION"));
     builder.append(SqlInfo::FlightSqlServerArrowVersion, "1.3");
+    builder.append(SqlInfo::FlightSqlServerReadOnly, false);
+    builder.append(SqlInfo::FlightSqlServerSql, true);
+    builder.append(SqlInfo::FlightSqlServerSubstrait, false);
+    builder.append(
+        SqlInfo::FlightSqlServerTransaction,
+        SqlSupportedTransaction::Transaction as i32,
+    );
+    builder.append(SqlInfo::FlightSqlServerCancel, true);
+    // SQL syntax information
+    builder.append(SqlInfo::SqlDdlCatalog, true);
+    builder.append(SqlInfo::SqlDdlSchema, true);
+    builder.append(SqlInfo::SqlDdlTable, true);
+    builder.append(
+        SqlInfo::SqlIdentifierCase,
+        SqlSupportedCaseSensitivity::SqlCaseSensitivityLowercase as i32,
+    );
+    builder.append(SqlInfo::SqlIdentifierQuoteChar, "\"");
+    builder.append(
+        SqlInfo::SqlQuotedIdentifierCase,
+        SqlSupportedCaseSensitivity::SqlCaseSensitivityCaseSensitive as i32,
+    );
+    builder.append(SqlInfo::SqlAllTablesAreSelectable, true);
+    builder.append(
+        SqlInfo::SqlNullOrdering,
+        SqlNullOrdering::SqlNullsSortedHigh as i32,
+    );
+    builder.append(SqlInfo::SqlSearchStringEscape, "\\");
+    builder.append(SqlInfo::SqlSupportsColumnAliasing, true);
+    builder.append(SqlInfo::SqlNullPlusNullIsNull, true);
+    builder.append(SqlInfo::SqlSupportsTableCorrelationNames, true);
+    builder.append(SqlInfo::SqlSupportsExpressionsInOrderBy, true);
+    builder.append(SqlInfo::SqlSupportsOrderByUnrelated, true);
+    builder.append(SqlInfo::SqlSupportsLikeEscapeClause, true);
+    builder.append(SqlInfo::SqlSchemaTerm, "schema");
+    builder.append(SqlInfo::SqlCatalogTerm, "database");
+    builder.append(SqlInfo::SqlCatalogAtStart, true);
+    builder.append(SqlInfo::SqlTransactionsSupported, true);
     builder.build().unwrap()
 });
 
@@ -49,14 +102,15 @@ pub const FLIGHTSQL_DATABASE_HEADER: &str = "x-glaredb-database";
 pub const FLIGHTSQL_GCS_BUCKET_HEADER: &str = "x-glaredb-gcs-bucket";
 pub struct FlightSessionHandler {
     engine: Arc<Engine>,
-    // since plans can be tied to any session, we can't use a single session to store them.
-    logical_plans: DashMap<String, LogicalPlan>,
-    // TODO: currently, we aren't removing these sessions, so this will grow forever.
-    // there's no close/shutdown hook, so the sessions can at most only be tied to a single transaction, not a connection.
-    // We'll want to implement a time based eviction policy, or a max size.
-    // We use [`Session`] instead of [`TrackedSession`] because tracked sessions need to be
-    // explicitly closed, and we don't have a way to do that yet.
-    sessions: DashMap<ConnKey, Arc<Mutex<Session>>>,
+    conf: FlightSessionConfig,
+    /// Registry of sessions in the engine, used for expiring idle sessions.
+    registry: Arc<SessionRegistry>,
+    // Sessions are removed when closed by the client, or after being idle for
+    // longer than the configured timeout.
+    // We use [`Session`] instead of [`TrackedSession`] because tracked sessions
+    // would prevent the engine from shutting down while clients hold on to
+    // sessions.
+    sessions: DashMap<ConnKey, FlightSession>,
 }
 
 impl FlightSessionHandler {
@@ -65,10 +119,10 @@ impl FlightSessionHandler {
         req: &Request<Ticket>,
         query: ActionExecuteLogicalPlan,
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
-        let ctx = self.get_or_create_ctx(req).await?;
-        let ctx = ctx.lock().await;
+        let sess = self.get_or_create_session(req).await?;
+        let ctx = sess.session.lock().await;
         let ActionExecuteLogicalPlan { handle } = query;
-        let lp = self
+        let lp = sess
             .logical_plans
             .get(&handle)
             .ok_or_else(|| Status::internal(format!("Unable to find logical plan {}", handle)))?
@@ -101,27 +155,183 @@ impl FlightSessionHandler {
         Ok(Response::new(Box::pin(stream)))
     }
 
-    pub fn new(engine: Arc<Engine>) -> Self {
+    /// Build the flight info for a catalog metadata command.
+    ///
+    /// The ticket for the single endpoint is the encoded command.
+    fn metadata_flight_info<C: ProstMessageExt>(
+        command: C,
+        schema: &Schema,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let ticket = Ticket::new(command.as_any().encode_to_vec());
+        let endpoint = FlightEndpoint::new().with_ticket(ticket);
+
+        let flight_info = FlightInfo::new()
+            .try_with_schema(schema)
+            .map_err(RpcsrvError::from)?
+            .with_endpoint(endpoint)
+            .with_descriptor(request.into_inner());
+
+        Ok(Response::new(flight_info))
+    }
+
+    /// Stream a single batch back to the client.
+    fn batch_response(batch: RecordBatch) -> Response<<Self as FlightService>::DoGetStream> {
+        let stream = FlightDataEncoderBuilder::new()
+            .with_schema(batch.schema())
+            .build(futures::stream::once(async { Ok(batch) }))
+            .map_err(Status::from);
+        Response::new(Box::pin(stream))
+    }
+
+    pub fn new(engine: Arc<Engine>, conf: FlightSessionConfig) -> Self {
+        let registry = engine.session_registry();
         Self {
             engine,
-            logical_plans: DashMap::new(),
+            conf,
+            registry,
             sessions: DashMap::new(),
         }
     }
 
+    /// Periodically close sessions that have been idle for longer than the
+    /// configured timeout.
+    pub fn start_session_expiry(self: &Arc<Self>) {
+        let handler = Arc::downgrade(self);
+        let interval = self.conf.expiry_interval();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // First tick completes immediately.
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match handler.upgrade() {
+                    Some(handler) => handler.expire_idle_sessions(),
+                    None => return,
+                }
+            }
+        });
+    }
+
+    /// Close all sessions that have been idle for longer than the configured
+    /// timeout.
+    fn expire_idle_sessions(&self) {
+        let idle = self.registry.idle_sessions(self.conf.idle_timeout);
+        if idle.is_empty() {
+            return;
+        }
+        self.sessions.retain(|_, sess| !idle.contains(&sess.id));
+        for id in &idle {
+            self.registry.remove(id);
+        }
+        debug!(expired = idle.len(), "expired idle flight sessions");
+    }
+
+    /// Close the session for the client making the request.
+    ///
+    /// Closing a session that doesn't exist (e.g. because it already expired)
+    /// isn't an error.
+    fn do_action_close_session(&self, request: &Request<Action>) -> CloseSessionResult {
+        if let Some((_, sess)) = self.sessions.remove(&conn_key(request)) {
+            self.registry.remove(&sess.id);
+        }
+        CloseSessionResult {
+            status: CloseSessionStatus::Closed as i32,
+        }
+    }
+
+    /// Cancel all statements executing in the client's session.
+    ///
+    /// Queries aren't tracked by flight info, so this cancels everything
+    /// running in the session.
+    fn do_action_cancel_flight_info(&self, request: &Request<Action>) -> CancelFlightInfoResult {
+        let status = match self.sessions.get(&conn_key(request)) {
+            Some(sess) => {
+                sess.cancel.cancel();
+                CancelStatus::Cancelled
+            }
+            None => CancelStatus::NotCancellable,
+        };
+        CancelFlightInfoResult {
+            status: status as i32,
+        }
+    }
+
+    /// Execute an update statement, returning the number of rows affected.
+    async fn put_statement_update(
+        &self,
+        request: &Request<()>,
+        cmd: CommandStatementUpdate,
+        stream: PutStream,
+    ) -> Result<i64> {
+        let ctx = self.get_or_create_ctx(request).await?;
+        let mut ctx = ctx.lock().await;
+
+        let handle = Uuid::new_v4().to_string();
+        ctx.prepare_portal(&handle, &cmd.query).await?;
+        let result = put::execute_update(&mut ctx, &handle, stream).await;
+        ctx.remove_portal(&handle);
+        ctx.remove_prepared_statement(&handle);
+
+        result
+    }
+
+    /// Execute a prepared update statement, returning the number of rows
+    /// affected.
+    ///
+    /// Statements with parameters are executed once for every row sent by
+    /// the client.
+    async fn put_prepared_statement_update(
+        &self,
+        request: &Request<()>,
+        cmd: CommandPreparedStatementUpdate,
+        stream: PutStream,
+    ) -> Result<i64> {
+        let handle = std::str::from_utf8(&cmd.prepared_statement_handle)
+            .map_err(|e| RpcsrvError::ParseError(e.to_string()))?;
+
+        let ctx = self.get_or_create_ctx(request).await?;
+        let mut ctx = ctx.lock().await;
+
+        let has_params = !ctx
+            .get_prepared_statement(handle)?
+            .ordered_input_parameters()
+            .is_empty();
+        if has_params {
+            put::execute_update_with_params(&mut ctx, handle, stream).await
+        } else {
+            put::execute_update(&mut ctx, handle, stream).await
+        }
+    }
+
     async fn get_or_create_ctx<T>(
         &self,
         request: &Request<T>,
     ) -> Result<Arc<Mutex<Session>>, Status> {
-        let remote = request.remote_addr().unwrap();
+        Ok(self.get_or_create_session(request).await?.session)
+    }
 
-        let ip = remote.ip().to_string();
-        let port = remote.port().to_string();
-        let conn_key = ConnKey { ip, port };
+    /// Get the session for the client making the request, creating a new one
+    /// if needed.
+    async fn get_or_create_session<T>(
+        &self,
+        request: &Request<T>,
+    ) -> Result<FlightSession, Status> {
+        let conn_key = conn_key(request);
+
+        if let Some(sess) = self.sessions.get(&conn_key) {
+            self.registry.touch(&sess.id);
+            return Ok(sess.clone());
+        }
 
-        if self.sessions.contains_key(&conn_key) {
-            let sess = self.sessions.get(&conn_key).unwrap().clone();
-            return Ok(sess);
+        if self.sessions.len() >= self.conf.max_sessions {
+            self.expire_idle_sessions();
+            if self.sessions.len() >= self.conf.max_sessions {
+                return Err(Status::resource_exhausted(format!(
+                    "Too many open sessions (max {})",
+                    self.conf.max_sessions
+                )));
+            }
         }
 
         let db_id = request
@@ -139,26 +349,84 @@ impl FlightSessionHandler {
                 "database id must be specified when using a gcs bucket".to_string(),
             ));
         }
+        // Flight SQL clients aren't authenticated as a specific user, so
+        // sessions run as the server's bootstrap user.
         let session_vars = SessionVars::default()
+            .with_user_name(
+                self.engine.bootstrap_user(),
+                datafusion::variable::VarType::System,
+            )
             .with_database_id(
                 db_id.unwrap_or_else(Uuid::nil),
                 datafusion::variable::VarType::System,
             )
             .with_force_catalog_refresh(true, datafusion::variable::VarType::System);
 
+        let database_id = session_vars.database_id();
         let sess = self
             .engine
             .new_untracked_session(session_vars, SessionStorageConfig::new(bucket_path))
             .await
             .map_err(RpcsrvError::from)?;
 
-        let sess = Arc::new(Mutex::new(sess));
-        self.sessions.insert(conn_key.clone(), sess.clone());
+        let sess = FlightSession {
+            id: self
+                .registry
+                .register(database_id, format!("{}:{}", conn_key.ip, conn_key.port)),
+            cancel: sess.cancel_handle(),
+            session: Arc::new(Mutex::new(sess)),
+            logical_plans: Arc::new(DashMap::new()),
+        };
+        if let Some(prev) = self.sessions.insert(conn_key, sess.clone()) {
+            // Concurrent requests from the same client both created a session.
+            self.registry.remove(&prev.id);
+        }
 
         Ok(sess)
     }
 }
 
+/// Get the key for the client connection a request was made on.
+fn conn_key<T>(request: &Request<T>) -> ConnKey {
+    let remote = request.remote_addr().unwrap();
+    ConnKey {
+        ip: remote.ip().to_string(),
+        port: remote.port().to_string(),
+    }
+}
+
+/// Get the id of the transaction for the client connection a request was made
+/// on.
+///
+/// Sessions are tied to the client's connection and have at most one
+/// transaction, so the id is derived from the connection.
+fn transaction_id<T>(request: &Request<T>) -> Vec<u8> {
+    request.remote_addr().unwrap().to_string().into_bytes()
+}
+
+/// Execute a transaction control statement (e.g. `BEGIN`) in the session.
+pub(crate) async fn execute_transaction_statement(sess: &mut Session, sql: &str) -> Result<()> {
+    let handle = Uuid::new_v4().to_string();
+    sess.prepare_portal(&handle, sql).await?;
+    let result = sess.execute_portal(&handle, 0).await;
+    sess.remove_portal(&handle);
+    sess.remove_prepared_statement(&handle);
+    match result? {
+        ExecutionResult::Error(e) => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Stream a single action result back to the client.
+fn action_response<M: Message>(
+    message: M,
+) -> Response<<FlightSessionHandler as FlightService>::DoActionStream> {
+    let result = arrow_flight::Result {
+        body: message.encode_to_vec().into(),
+    };
+    Response::new(Box::pin(futures::stream::once(async { Ok(result) })))
+}
+
 #[tonic::async_trait]
 impl FlightSqlService for FlightSessionHandler {
     type FlightService = Self;
@@ -238,8 +506,8 @@ impl FlightSqlService for FlightSessionHandler {
         let handle = String::from_utf8(cmd.prepared_statement_handle.to_vec()).ok();
         let handle = handle.unwrap_or_else(|| Uuid::new_v4().to_string());
 
-        let ctx = self.get_or_create_ctx(&req).await?;
-        let ctx = ctx.lock().await;
+        let sess = self.get_or_create_session(&req).await?;
+        let ctx = sess.session.lock().await;
         let portal = ctx.get_portal(&handle).map_err(RpcsrvError::from)?;
 
         let plan = portal.logical_plan().unwrap();
@@ -249,7 +517,7 @@ impl FlightSqlService for FlightSessionHandler {
             .try_into_datafusion_plan()
             .map_err(RpcsrvError::from)?;
 
-        self.logical_plans.insert(handle.clone(), plan);
+        sess.logical_plans.insert(handle.clone(), plan);
 
         let action = ActionExecuteLogicalPlan {
             handle: handle.to_string(),
@@ -273,14 +541,129 @@ impl FlightSqlService for FlightSessionHandler {
         query: CommandGetSqlInfo,
         request: Request<FlightDescriptor>,
     ) -> Result<Response<FlightInfo>, Status> {
-        let flight_descriptor = request.into_inner();
+        let schema = query.clone().into_builder(&INSTANCE_SQL_DATA).schema();
+        Self::metadata_flight_info(query, &schema, request)
+    }
 
-        let flight_info = FlightInfo::new()
-            .try_with_schema(query.into_builder(&INSTANCE_SQL_DATA).schema().as_ref())
-            .map_err(RpcsrvError::from)?
-            .with_descriptor(flight_descriptor);
+    async fn do_get_sql_info(
+        &self,
+        query: CommandGetSqlInfo,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let batch = query
+            .into_builder(&INSTANCE_SQL_DATA)
+            .build()
+            .map_err(RpcsrvError::from)?;
+        Ok(Self::batch_response(batch))
+    }
 
-        Ok(tonic::Response::new(flight_info))
+    async fn get_flight_info_catalogs(
+        &self,
+        query: CommandGetCatalogs,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.clone().into_builder().schema();
+        Self::metadata_flight_info(query, &schema, request)
+    }
+
+    async fn do_get_catalogs(
+        &self,
+        query: CommandGetCatalogs,
+        request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let ctx = self.get_or_create_ctx(&request).await?;
+        let mut ctx = ctx.lock().await;
+        ctx.maybe_refresh_catalog()
+            .await
+            .map_err(RpcsrvError::from)?;
+
+        let user = ctx.get_session_vars().user_name();
+        let batch = metadata::get_catalogs(ctx.get_session_catalog(), &user, query)?;
+        Ok(Self::batch_response(batch))
+    }
+
+    async fn get_flight_info_schemas(
+        &self,
+        query: CommandGetDbSchemas,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.clone().into_builder().schema();
+        Self::metadata_flight_info(query, &schema, request)
+    }
+
+    async fn do_get_schemas(
+        &self,
+        query: CommandGetDbSchemas,
+        request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let ctx = self.get_or_create_ctx(&request).await?;
+        let mut ctx = ctx.lock().await;
+        ctx.maybe_refresh_catalog()
+            .await
+            .map_err(RpcsrvError::from)?;
+
+        let user = ctx.get_session_vars().user_name();
+        let batch = metadata::get_db_schemas(ctx.get_session_catalog(), &user, query).await?;
+        Ok(Self::batch_response(batch))
+    }
+
+    async fn get_flight_info_tables(
+        &self,
+        query: CommandGetTables,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.clone().into_builder().schema();
+        Self::metadata_flight_info(query, &schema, request)
+    }
+
+    async fn do_get_tables(
+        &self,
+        query: CommandGetTables,
+        request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let ctx = self.get_or_create_ctx(&request).await?;
+        let mut ctx = ctx.lock().await;
+        ctx.maybe_refresh_catalog()
+            .await
+            .map_err(RpcsrvError::from)?;
+
+        let user = ctx.get_session_vars().user_name();
+        let batch = metadata::get_tables(ctx.get_session_catalog(), &user, query).await?;
+        Ok(Self::batch_response(batch))
+    }
+
+    async fn get_flight_info_table_types(
+        &self,
+        query: CommandGetTableTypes,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Self::metadata_flight_info(query, &metadata::TABLE_TYPES_SCHEMA, request)
+    }
+
+    async fn do_get_table_types(
+        &self,
+        _query: CommandGetTableTypes,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let batch = metadata::get_table_types()?;
+        Ok(Self::batch_response(batch))
+    }
+
+    async fn get_flight_info_primary_keys(
+        &self,
+        query: CommandGetPrimaryKeys,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Self::metadata_flight_info(query, &metadata::PRIMARY_KEYS_SCHEMA, request)
+    }
+
+    async fn do_get_primary_keys(
+        &self,
+        query: CommandGetPrimaryKeys,
+        _request: Request<Ticket>,
+    ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
+        let batch = metadata::get_primary_keys(query)?;
+        Ok(Self::batch_response(batch))
     }
 
     async fn do_action_create_prepared_statement(
@@ -288,33 +671,59 @@ impl FlightSqlService for FlightSessionHandler {
         query: ActionCreatePreparedStatementRequest,
         req: Request<Action>,
     ) -> Result<ActionCreatePreparedStatementResult, Status> {
-        let handle = query
-            .transaction_id
-            .map(|id| String::from_utf8(id.to_vec()).unwrap())
-            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        // Statements always run in the client's session, so they're part of
+        // the session's transaction if there is one.
+        let handle = Uuid::new_v4().to_string();
 
         let ctx = self.get_or_create_ctx(&req).await?;
         let mut ctx = ctx.lock().await;
 
-        ctx.prepare_portal(&handle, &query.query)
+        ctx.prepare_statement(handle.clone(), query.query.as_str(), Vec::new())
             .await
             .map_err(RpcsrvError::from)?;
 
-        let portal = ctx.get_portal(&handle).map_err(RpcsrvError::from)?;
+        // Statements with parameters are bound once the client sends the
+        // parameter values.
+        let has_params = !ctx
+            .get_prepared_statement(&handle)
+            .map_err(RpcsrvError::from)?
+            .ordered_input_parameters()
+            .is_empty();
+        if !has_params {
+            ctx.bind_portal(&handle, Vec::new())
+                .map_err(RpcsrvError::from)?;
+        }
 
-        let output_schema = portal.output_schema().ok_or_else(|| {
+        let stmt = ctx
+            .get_prepared_statement(&handle)
+            .map_err(RpcsrvError::from)?;
+
+        let output_schema = stmt.output_schema().ok_or_else(|| {
             Status::internal("Expected a valid output schema, instead received: None".to_string())
         })?;
 
         let message = SchemaAsIpc::new(output_schema, &IpcWriteOptions::default())
             .try_into()
             .map_err(RpcsrvError::from)?;
-
         let IpcMessage(schema_bytes) = message;
+
+        // Parameters are named after their placeholders, e.g. "$1".
+        let parameter_schema = Schema::new(
+            stmt.ordered_input_parameters()
+                .into_iter()
+                .enumerate()
+                .map(|(idx, (_, typ))| Field::new(format!("${}", idx + 1), typ, true))
+                .collect::<Vec<_>>(),
+        );
+        let message = SchemaAsIpc::new(&parameter_schema, &IpcWriteOptions::default())
+            .try_into()
+            .map_err(RpcsrvError::from)?;
+        let IpcMessage(parameter_schema_bytes) = message;
+
         let res = ActionCreatePreparedStatementResult {
             prepared_statement_handle: handle.into(),
             dataset_schema: schema_bytes,
-            parameter_schema: Default::default(), // TODO: parameters
+            parameter_schema: parameter_schema_bytes,
         };
 
         Ok(res)
@@ -328,16 +737,216 @@ impl FlightSqlService for FlightSessionHandler {
         let handle = std::str::from_utf8(&query.prepared_statement_handle)
             .map_err(|e| RpcsrvError::ParseError(e.to_string()))?;
 
+        let sess = self.get_or_create_session(&req).await?;
+        sess.session.lock().await.remove_portal(handle);
+        sess.logical_plans.remove(handle);
+
+        Ok(())
+    }
+
+    async fn do_action_begin_transaction(
+        &self,
+        _query: ActionBeginTransactionRequest,
+        req: Request<Action>,
+    ) -> Result<ActionBeginTransactionResult, Status> {
         let ctx = self.get_or_create_ctx(&req).await?;
-        ctx.lock().await.remove_portal(handle);
-        self.logical_plans.remove(handle);
+        let mut ctx = ctx.lock().await;
+        if ctx.transaction_status() != TransactionStatus::Idle {
+            return Err(Status::failed_precondition(
+                "There is already a transaction in progress",
+            ));
+        }
+        execute_transaction_statement(&mut ctx, "BEGIN").await?;
+
+        Ok(ActionBeginTransactionResult {
+            transaction_id: transaction_id(&req).into(),
+        })
+    }
+
+    async fn do_action_end_transaction(
+        &self,
+        query: ActionEndTransactionRequest,
+        req: Request<Action>,
+    ) -> Result<(), Status> {
+        let ctx = self.get_or_create_ctx(&req).await?;
+        let mut ctx = ctx.lock().await;
+        if query.transaction_id != transaction_id(&req)
+            || ctx.transaction_status() == TransactionStatus::Idle
+        {
+            return Err(Status::not_found("Transaction not found"));
+        }
+
+        // The enum for the action isn't exported, match on its name instead.
+        let sql = match query.action().as_str_name() {
+            "END_TRANSACTION_COMMIT" => "COMMIT",
+            "END_TRANSACTION_ROLLBACK" => "ROLLBACK",
+            other => {
+                return Err(Status::invalid_argument(format!(
+                    "Unsupported end transaction action: {other}"
+                )))
+            }
+        };
+        execute_transaction_statement(&mut ctx, sql).await?;
 
         Ok(())
     }
 
+    async fn do_action_fallback(
+        &self,
+        request: Request<Action>,
+    ) -> Result<Response<<Self as FlightService>::DoActionStream>, Status> {
+        match request.get_ref().r#type.as_str() {
+            CLOSE_SESSION => Ok(action_response(self.do_action_close_session(&request))),
+            CANCEL_FLIGHT_INFO => Ok(action_response(self.do_action_cancel_flight_info(&request))),
+            other => Err(Status::invalid_argument(format!(
+                "Unsupported action: {other}"
+            ))),
+        }
+    }
+
+    async fn list_custom_actions(&self) -> Option<Vec<Result<ActionType, Status>>> {
+        Some(vec![
+            Ok(ActionType {
+                r#type: CLOSE_SESSION.to_string(),
+                description: "Close the current session.".to_string(),
+            }),
+            Ok(ActionType {
+                r#type: CANCEL_FLIGHT_INFO.to_string(),
+                description: "Cancel queries running in the current session.".to_string(),
+            }),
+        ])
+    }
+
     async fn register_sql_info(&self, _id: i32, _result: &SqlInfo) {}
 }
 
+/// Flight service for a [`FlightSessionHandler`].
+///
+/// Requests are dispatched through [`FlightSqlService`], except for DoPut.
+/// The DoPut dispatch in `arrow_flight` consumes the message holding the
+/// command before handing the stream over, dropping the schema the client
+/// sent along with it.
+pub struct FlightSqlServer {
+    handler: Arc<FlightSessionHandler>,
+}
+
+impl FlightSqlServer {
+    pub fn new(handler: Arc<FlightSessionHandler>) -> Self {
+        FlightSqlServer { handler }
+    }
+}
+
+#[tonic::async_trait]
+impl FlightService for FlightSqlServer {
+    type HandshakeStream = <FlightSessionHandler as FlightService>::HandshakeStream;
+    type ListFlightsStream = <FlightSessionHandler as FlightService>::ListFlightsStream;
+    type DoGetStream = <FlightSessionHandler as FlightService>::DoGetStream;
+    type DoPutStream = <FlightSessionHandler as FlightService>::DoPutStream;
+    type DoActionStream = <FlightSessionHandler as FlightService>::DoActionStream;
+    type ListActionsStream = <FlightSessionHandler as FlightService>::ListActionsStream;
+    type DoExchangeStream = <FlightSessionHandler as FlightService>::DoExchangeStream;
+
+    async fn handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        FlightService::handshake(self.handler.as_ref(), request).await
+    }
+
+    async fn list_flights(
+        &self,
+        request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        FlightService::list_flights(self.handler.as_ref(), request).await
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        FlightService::get_flight_info(self.handler.as_ref(), request).await
+    }
+
+    async fn get_schema(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        FlightService::get_schema(self.handler.as_ref(), request).await
+    }
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        FlightService::do_get(self.handler.as_ref(), request).await
+    }
+
+    async fn do_put(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        let (metadata, extensions, mut stream) = request.into_parts();
+        let first = stream
+            .message()
+            .await?
+            .ok_or_else(|| Status::invalid_argument("Missing flight descriptor"))?;
+        let cmd = match &first.flight_descriptor {
+            Some(descriptor) => {
+                Any::decode(&*descriptor.cmd).map_err(|e| RpcsrvError::ParseError(e.to_string()))?
+            }
+            None => return Err(Status::invalid_argument("Missing flight descriptor")),
+        };
+
+        // Keep the first message in the stream, it may hold the schema.
+        let data = futures::stream::once(async { Ok(first) })
+            .chain(stream)
+            .boxed();
+        let request = Request::from_parts(metadata, extensions, ());
+
+        let record_count = if let Some(cmd) = cmd.unpack().map_err(RpcsrvError::from)? {
+            self.handler
+                .put_statement_update(&request, cmd, data)
+                .await?
+        } else if let Some(cmd) = cmd.unpack().map_err(RpcsrvError::from)? {
+            self.handler
+                .put_prepared_statement_update(&request, cmd, data)
+                .await?
+        } else {
+            return Err(Status::unimplemented(format!(
+                "DoPut not implemented for {}",
+                cmd.type_url
+            )));
+        };
+
+        let result = DoPutUpdateResult { record_count };
+        let output = futures::stream::iter(vec![Ok(PutResult {
+            app_metadata: result.as_any().encode_to_vec().into(),
+        })]);
+        Ok(Response::new(Box::pin(output)))
+    }
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        FlightService::do_action(self.handler.as_ref(), request).await
+    }
+
+    async fn list_actions(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        FlightService::list_actions(self.handler.as_ref(), request).await
+    }
+
+    async fn do_exchange(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        FlightService::do_exchange(self.handler.as_ref(), request).await
+    }
+}
+
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ActionExecuteLogicalPlan {
     #[prost(string, tag = "2")]
@@ -361,3 +970,250 @@ impl ProstMessageExt for ActionExecuteLogicalPlan {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use arrow_flight::flight_service_client::FlightServiceClient;
+    use tokio::net::TcpListener;
+    use tonic::transport::server::TcpIncoming;
+    use tonic::transport::{Channel, Endpoint, Server};
+    use tonic::Code;
+
+    use super::*;
+
+    /// Start a flight server backed by an in-memory engine, returning the
+    /// handler and the address to connect to.
+    async fn start_server(conf: FlightSessionConfig) -> (Arc<FlightSessionHandler>, String) {
+        let engine = Arc::new(Engine::from_data_dir(None).await.unwrap());
+        let handler = Arc::new(FlightSessionHandler::new(engine, conf));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+        let service = FlightServiceServer::new(FlightSqlServer::new(handler.clone()));
+        tokio::spawn(
+            Server::builder()
+                .add_service(service)
+                .serve_with_incoming(incoming),
+        );
+
+        (handler, addr)
+    }
+
+    /// Connect a new client. Every client gets its own connection, and so its
+    /// own session.
+    async fn connect(addr: &str) -> FlightServiceClient<Channel> {
+        let channel = Endpoint::new(addr.to_string())
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        FlightServiceClient::new(channel)
+    }
+
+    /// Run a query, creating a session for the client if needed.
+    async fn query(client: &mut FlightServiceClient<Channel>, sql: &str) -> Result<(), Status> {
+        let cmd = CommandStatementQuery {
+            query: sql.to_string(),
+            transaction_id: None,
+        };
+        let info = client
+            .get_flight_info(FlightDescriptor::new_cmd(cmd.as_any().encode_to_vec()))
+            .await?
+            .into_inner();
+        let ticket = info.endpoint[0].ticket.clone().unwrap();
+        let mut stream = client.do_get(ticket).await?.into_inner();
+        while stream.message().await?.is_some() {}
+        Ok(())
+    }
+
+    /// Run an action that returns a single result.
+    async fn action<M: Message + Default>(
+        client: &mut FlightServiceClient<Channel>,
+        r#type: &str,
+        body: Vec<u8>,
+    ) -> M {
+        let action = Action {
+            r#type: r#type.to_string(),
+            body: body.into(),
+        };
+        let mut stream = client.do_action(action).await.unwrap().into_inner();
+        let result = stream.message().await.unwrap().unwrap();
+        M::decode(result.body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn idle_sessions_expire() {
+        let conf = FlightSessionConfig {
+            idle_timeout: Duration::from_millis(10),
+            max_sessions: 8,
+        };
+        let (handler, addr) = start_server(conf).await;
+
+        let mut client = connect(&addr).await;
+        query(&mut client, "select 1").await.unwrap();
+        assert_eq!(1, handler.sessions.len());
+        assert_eq!(1, handler.registry.len());
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        handler.expire_idle_sessions();
+        assert!(handler.sessions.is_empty());
+        assert!(handler.registry.is_empty());
+
+        // Expired clients get a new session on their next request.
+        query(&mut client, "select 1").await.unwrap();
+        assert_eq!(1, handler.sessions.len());
+    }
+
+    #[tokio::test]
+    async fn max_sessions_rejected() {
+        let conf = FlightSessionConfig {
+            idle_timeout: Duration::from_secs(60),
+            max_sessions: 1,
+        };
+        let (handler, addr) = start_server(conf).await;
+
+        let mut first = connect(&addr).await;
+        query(&mut first, "select 1").await.unwrap();
+
+        let mut second = connect(&addr).await;
+        let err = query(&mut second, "select 1").await.unwrap_err();
+        assert_eq!(Code::ResourceExhausted, err.code());
+        assert_eq!(1, handler.sessions.len());
+
+        // Closing the first session makes room for the second.
+        let _: CloseSessionResult = action(&mut first, CLOSE_SESSION, Vec::new()).await;
+        query(&mut second, "select 1").await.unwrap();
+        assert_eq!(1, handler.sessions.len());
+    }
+
+    #[tokio::test]
+    async fn close_session_drops_plans() {
+        let (handler, addr) = start_server(FlightSessionConfig::default()).await;
+        let mut client = connect(&addr).await;
+
+        let request = ActionCreatePreparedStatementRequest {
+            query: "select 1".to_string(),
+            transaction_id: None,
+        };
+        let result: Any = action(
+            &mut client,
+            "CreatePreparedStatement",
+            request.as_any().encode_to_vec(),
+        )
+        .await;
+        let result: ActionCreatePreparedStatementResult = result.unpack().unwrap().unwrap();
+
+        let cmd = CommandPreparedStatementQuery {
+            prepared_statement_handle: result.prepared_statement_handle,
+        };
+        let info = client
+            .get_flight_info(FlightDescriptor::new_cmd(cmd.as_any().encode_to_vec()))
+            .await
+            .unwrap()
+            .into_inner();
+        let ticket = info.endpoint[0].ticket.clone().unwrap();
+        assert_eq!(
+            1,
+            handler.sessions.iter().next().unwrap().logical_plans.len()
+        );
+
+        let result: CloseSessionResult = action(&mut client, CLOSE_SESSION, Vec::new()).await;
+        assert_eq!(CloseSessionStatus::Closed, result.status());
+        assert!(handler.sessions.is_empty());
+        assert!(handler.registry.is_empty());
+
+        // The plan was dropped along with the session.
+        let err = client.do_get(ticket).await.unwrap_err();
+        assert!(
+            err.message().contains("Unable to find logical plan"),
+            "unexpected error: {err}"
+        );
+
+        // Closing a session that doesn't exist isn't an error.
+        let result: CloseSessionResult = action(&mut client, CLOSE_SESSION, Vec::new()).await;
+        assert_eq!(CloseSessionStatus::Closed, result.status());
+    }
+
+    #[tokio::test]
+    async fn begin_end_transaction() {
+        let (handler, addr) = start_server(FlightSessionConfig::default()).await;
+        let mut client = connect(&addr).await;
+
+        let begin = || Action {
+            r#type: "BeginTransaction".to_string(),
+            body: ActionBeginTransactionRequest {}
+                .as_any()
+                .encode_to_vec()
+                .into(),
+        };
+        let end = |transaction_id, action| Action {
+            r#type: "EndTransaction".to_string(),
+            body: ActionEndTransactionRequest {
+                transaction_id,
+                action,
+            }
+            .as_any()
+            .encode_to_vec()
+            .into(),
+        };
+        // Value of `END_TRANSACTION_COMMIT`.
+        const COMMIT: i32 = 1;
+
+        let result = client
+            .do_action(begin())
+            .await
+            .unwrap()
+            .into_inner()
+            .message()
+            .await
+            .unwrap()
+            .unwrap();
+        let result: ActionBeginTransactionResult =
+            Any::decode(result.body).unwrap().unpack().unwrap().unwrap();
+        let sess = handler.sessions.iter().next().unwrap().value().clone();
+        assert_eq!(
+            TransactionStatus::InBlock,
+            sess.session.lock().await.transaction_status()
+        );
+
+        // Sessions only have a single transaction.
+        let err = client.do_action(begin()).await.unwrap_err();
+        assert_eq!(Code::FailedPrecondition, err.code());
+
+        client
+            .do_action(end(result.transaction_id.clone(), COMMIT))
+            .await
+            .unwrap();
+        assert_eq!(
+            TransactionStatus::Idle,
+            sess.session.lock().await.transaction_status()
+        );
+
+        let err = client
+            .do_action(end(result.transaction_id, COMMIT))
+            .await
+            .unwrap_err();
+        assert_eq!(Code::NotFound, err.code());
+    }
+
+    #[tokio::test]
+    async fn cancel_flight_info() {
+        let (_handler, addr) = start_server(FlightSessionConfig::default()).await;
+        let mut client = connect(&addr).await;
+
+        let result: CancelFlightInfoResult =
+            action(&mut client, CANCEL_FLIGHT_INFO, Vec::new()).await;
+        assert_eq!(CancelStatus::NotCancellable, result.status());
+
+        query(&mut client, "select 1").await.unwrap();
+        let result: CancelFlightInfoResult =
+            action(&mut client, CANCEL_FLIGHT_INFO, Vec::new()).await;
+        assert_eq!(CancelStatus::Cancelled, result.status());
+
+        // The session is still usable after canceling.
+        query(&mut client, "select 1").await.unwrap();
+    }
+}
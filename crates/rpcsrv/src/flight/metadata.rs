@@ -0,0 +1,439 @@
+//! Catalog metadata for Flight SQL clients.
+//!
+//! Flight SQL catalogs map to databases, and db schemas map to the schemas
+//! within a database. The internal database is listed using the session
+//! catalog, external databases are listed using a virtual lister for the
+//! database.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use arrow_flight::sql::{
+    CommandGetCatalogs, CommandGetDbSchemas, CommandGetPrimaryKeys, CommandGetTables,
+};
+use catalog::session_catalog::SessionCatalog;
+use datafusion::arrow::array::{new_empty_array, StringArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use once_cell::sync::Lazy;
+use protogen::metastore::types::catalog::{CatalogEntry, DatabaseEntry, Privilege};
+use protogen::metastore::types::options::DatabaseOptions;
+use sqlbuiltins::functions::get_virtual_lister_for_external_db;
+
+use tracing::warn;
+
+use crate::errors::{Result, RpcsrvError};
+
+/// Table types reported to clients.
+pub const TABLE_TYPES: &[&str] = &[TABLE_TYPE_TABLE, TABLE_TYPE_VIEW];
+
+const TABLE_TYPE_TABLE: &str = "TABLE";
+const TABLE_TYPE_VIEW: &str = "VIEW";
+
+/// How long to wait on a single external database when listing its schemas or
+/// tables. Databases that take longer are skipped.
+const EXTERNAL_LISTING_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub static TABLE_TYPES_SCHEMA: Lazy<SchemaRef> = Lazy::new(|| {
+    Arc::new(Schema::new(vec![Field::new(
+        "table_type",
+        DataType::Utf8,
+        false,
+    )]))
+});
+
+pub static PRIMARY_KEYS_SCHEMA: Lazy<SchemaRef> = Lazy::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("catalog_name", DataType::Utf8, true),
+        Field::new("db_schema_name", DataType::Utf8, true),
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("column_name", DataType::Utf8, false),
+        Field::new("key_name", DataType::Utf8, true),
+        Field::new("key_sequence", DataType::Int32, false),
+    ]))
+});
+
+/// Build the batch for `CommandGetCatalogs`.
+pub fn get_catalogs(
+    catalog: &SessionCatalog,
+    user: &str,
+    query: CommandGetCatalogs,
+) -> Result<RecordBatch> {
+    let mut builder = query.into_builder();
+    for db in visible_databases(catalog, user, None) {
+        builder.append(&db.meta.name);
+    }
+    Ok(builder.build()?)
+}
+
+/// Build the batch for `CommandGetDbSchemas`.
+///
+/// Databases that fail to list their schemas are skipped.
+pub async fn get_db_schemas(
+    catalog: &SessionCatalog,
+    user: &str,
+    query: CommandGetDbSchemas,
+) -> Result<RecordBatch> {
+    let filter = query.catalog.clone();
+    let schema_pattern = query.db_schema_filter_pattern.clone();
+    let mut builder = query.into_builder();
+    for db in visible_databases(catalog, user, filter.as_deref()) {
+        let schemas = match &db.options {
+            DatabaseOptions::Internal(_) => internal_schemas(catalog, user)
+                .map(|schema| schema.to_string())
+                .collect(),
+            opts => {
+                match with_listing_timeout(list_external_schemas(opts, schema_pattern.as_deref()))
+                    .await
+                {
+                    Ok(schemas) => schemas,
+                    Err(e) => {
+                        warn!(%e, database = %db.meta.name, "failed to list schemas for database");
+                        continue;
+                    }
+                }
+            }
+        };
+        for schema in schemas {
+            if matches_pattern(schema_pattern.as_deref(), &schema) {
+                builder.append(&db.meta.name, schema);
+            }
+        }
+    }
+    Ok(builder.build()?)
+}
+
+/// Build the batch for `CommandGetTables`.
+///
+/// Table schemas are only included for tables with known columns, other
+/// tables (e.g. views) get an empty schema. Databases that fail to list their
+/// tables are skipped.
+pub async fn get_tables(
+    catalog: &SessionCatalog,
+    user: &str,
+    query: CommandGetTables,
+) -> Result<RecordBatch> {
+    let filter = query.catalog.clone();
+    let tables_filter = TablesFilter {
+        schema_pattern: query.db_schema_filter_pattern.clone(),
+        table_pattern: query.table_name_filter_pattern.clone(),
+        table_types: query.table_types.clone(),
+        include_schema: query.include_schema,
+    };
+    let mut builder = query.into_builder();
+    for db in visible_databases(catalog, user, filter.as_deref()) {
+        let tables = match &db.options {
+            DatabaseOptions::Internal(_) => internal_tables(catalog, user, &tables_filter),
+            opts => match with_listing_timeout(list_external_tables(opts, &tables_filter)).await {
+                Ok(tables) => tables,
+                Err(e) => {
+                    warn!(%e, database = %db.meta.name, "failed to list tables for database");
+                    continue;
+                }
+            },
+        };
+        for table in tables {
+            builder.append(
+                &db.meta.name,
+                &table.schema,
+                &table.name,
+                table.table_type,
+                &table.table_schema,
+            )?;
+        }
+    }
+    Ok(builder.build()?)
+}
+
+/// Build the batch for `CommandGetTableTypes`.
+pub fn get_table_types() -> Result<RecordBatch> {
+    Ok(RecordBatch::try_new(
+        TABLE_TYPES_SCHEMA.clone(),
+        vec![Arc::new(StringArray::from(TABLE_TYPES.to_vec()))],
+    )?)
+}
+
+/// Build the batch for `CommandGetPrimaryKeys`.
+///
+/// Primary keys aren't supported, so this is always empty.
+pub fn get_primary_keys(_query: CommandGetPrimaryKeys) -> Result<RecordBatch> {
+    let columns = PRIMARY_KEYS_SCHEMA
+        .fields()
+        .iter()
+        .map(|f| new_empty_array(f.data_type()))
+        .collect();
+    Ok(RecordBatch::try_new(PRIMARY_KEYS_SCHEMA.clone(), columns)?)
+}
+
+/// Get the databases visible to the user, optionally only returning the
+/// database with the given name.
+///
+/// External databases are only visible to superusers.
+fn visible_databases<'a>(
+    catalog: &'a SessionCatalog,
+    user: &'a str,
+    name: Option<&'a str>,
+) -> impl Iterator<Item = &'a DatabaseEntry> + 'a {
+    catalog
+        .iter_entries()
+        .filter_map(move |ent| match ent.entry {
+            CatalogEntry::Database(db) if name.is_some_and(|name| name != db.meta.name) => None,
+            CatalogEntry::Database(db) => match db.options {
+                DatabaseOptions::Internal(_) => Some(db),
+                _ if catalog.is_superuser(user) => Some(db),
+                _ => None,
+            },
+            _ => None,
+        })
+}
+
+/// Get the names of schemas in the internal database the user has `USAGE` on.
+fn internal_schemas<'a>(
+    catalog: &'a SessionCatalog,
+    user: &'a str,
+) -> impl Iterator<Item = &'a str> + 'a {
+    catalog
+        .iter_entries()
+        .filter_map(move |ent| match ent.entry {
+            CatalogEntry::Schema(schema)
+                if catalog.has_privilege(user, schema.meta.id, Privilege::Usage) =>
+            {
+                Some(schema.meta.name.as_str())
+            }
+            _ => None,
+        })
+}
+
+/// Filters from `CommandGetTables`.
+///
+/// These are applied while listing so that external databases aren't asked
+/// for tables and columns that won't be returned.
+struct TablesFilter {
+    schema_pattern: Option<String>,
+    table_pattern: Option<String>,
+    table_types: Vec<String>,
+    include_schema: bool,
+}
+
+impl TablesFilter {
+    fn matches_schema(&self, schema: &str) -> bool {
+        matches_pattern(self.schema_pattern.as_deref(), schema)
+    }
+
+    fn matches_table(&self, table: &str, table_type: &str) -> bool {
+        matches_pattern(self.table_pattern.as_deref(), table)
+            && (self.table_types.is_empty() || self.table_types.iter().any(|t| t == table_type))
+    }
+}
+
+/// A table to append to the `CommandGetTables` batch.
+struct TableInfo {
+    schema: String,
+    name: String,
+    table_type: &'static str,
+    table_schema: Schema,
+}
+
+/// List tables and views in the internal database the user can select from.
+fn internal_tables(catalog: &SessionCatalog, user: &str, filter: &TablesFilter) -> Vec<TableInfo> {
+    let mut tables = Vec::new();
+    for ent in catalog.iter_entries() {
+        let meta = ent.entry.get_meta();
+        let schema = match ent.parent_entry {
+            Some(parent) if !meta.is_temp => &parent.get_meta().name,
+            _ => continue,
+        };
+        let table_type = match ent.entry {
+            CatalogEntry::Table(_) => TABLE_TYPE_TABLE,
+            CatalogEntry::View(_) => TABLE_TYPE_VIEW,
+            _ => continue,
+        };
+        if !filter.matches_schema(schema)
+            || !filter.matches_table(&meta.name, table_type)
+            || !catalog.can_access(user, meta, Privilege::Select)
+        {
+            continue;
+        }
+        let table_schema = match ent.entry {
+            CatalogEntry::Table(table) if filter.include_schema => {
+                let fields = table
+                    .get_internal_columns()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|col| Field::new(&col.name, col.arrow_type.clone(), col.nullable))
+                    .collect::<Vec<_>>();
+                Schema::new(fields)
+            }
+            _ => Schema::empty(),
+        };
+        tables.push(TableInfo {
+            schema: schema.clone(),
+            name: meta.name.clone(),
+            table_type,
+            table_schema,
+        });
+    }
+    tables
+}
+
+/// Run a listing against an external database, erroring if it doesn't
+/// complete within `EXTERNAL_LISTING_TIMEOUT`.
+async fn with_listing_timeout<T>(fut: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+    tokio::time::timeout(EXTERNAL_LISTING_TIMEOUT, fut)
+        .await
+        .map_err(|_| {
+            RpcsrvError::Internal(format!(
+                "listing timed out after {}s",
+                EXTERNAL_LISTING_TIMEOUT.as_secs()
+            ))
+        })?
+}
+
+/// List schemas in an external database matching the pattern.
+async fn list_external_schemas(
+    opts: &DatabaseOptions,
+    pattern: Option<&str>,
+) -> Result<Vec<String>> {
+    let lister = get_virtual_lister_for_external_db(opts).await?;
+    let schemas = lister.list_schemas().await?;
+    Ok(schemas
+        .into_iter()
+        .filter(|schema| matches_pattern(pattern, schema))
+        .collect())
+}
+
+/// List tables in an external database matching the filter.
+///
+/// Tables in external databases are always reported as `TABLE`.
+async fn list_external_tables(
+    opts: &DatabaseOptions,
+    filter: &TablesFilter,
+) -> Result<Vec<TableInfo>> {
+    let mut tables = Vec::new();
+    if !filter.table_types.is_empty() && !filter.table_types.iter().any(|t| t == TABLE_TYPE_TABLE) {
+        return Ok(tables);
+    }
+    let lister = get_virtual_lister_for_external_db(opts).await?;
+    for schema in lister.list_schemas().await? {
+        if !filter.matches_schema(&schema) {
+            continue;
+        }
+        for table in lister.list_tables(&schema).await? {
+            if !filter.matches_table(&table, TABLE_TYPE_TABLE) {
+                continue;
+            }
+            let table_schema = if filter.include_schema {
+                Schema::new(lister.list_columns(&schema, &table).await?)
+            } else {
+                Schema::empty()
+            };
+            tables.push(TableInfo {
+                schema: schema.clone(),
+                name: table,
+                table_type: TABLE_TYPE_TABLE,
+                table_schema,
+            });
+        }
+    }
+    Ok(tables)
+}
+
+/// Check if a value matches a Flight SQL filter pattern.
+///
+/// Patterns follow `LIKE` semantics: `%` matches any sequence of characters,
+/// `_` matches a single character, and `\` escapes the next character. A
+/// missing pattern matches everything.
+fn matches_pattern(pattern: Option<&str>, value: &str) -> bool {
+    let pattern = match pattern {
+        Some(pattern) => pattern,
+        None => return true,
+    };
+    let value: Vec<_> = value.chars().collect();
+
+    // matched[i] is true if the pattern consumed so far matches the first `i`
+    // characters of the value. Each pattern character is processed once, so
+    // this runs in O(pattern * value) time regardless of the number of `%`.
+    let mut matched = vec![false; value.len() + 1];
+    matched[0] = true;
+
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        let mut next = vec![false; value.len() + 1];
+        match c {
+            '%' => {
+                let mut any = false;
+                for (i, m) in matched.iter().enumerate() {
+                    any |= *m;
+                    next[i] = any;
+                }
+            }
+            '_' => next[1..].copy_from_slice(&matched[..value.len()]),
+            c => {
+                // A trailing backslash matches itself.
+                let c = if c == '\\' {
+                    chars.next().unwrap_or(c)
+                } else {
+                    c
+                };
+                for (i, v) in value.iter().enumerate() {
+                    next[i + 1] = matched[i] && *v == c;
+                }
+            }
+        }
+        matched = next;
+    }
+
+    matched[value.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_types() {
+        let batch = get_table_types().unwrap();
+        assert_eq!(TABLE_TYPES.len(), batch.num_rows());
+        assert_eq!(TABLE_TYPES_SCHEMA.clone(), batch.schema());
+    }
+
+    #[test]
+    fn filter_patterns() {
+        // Would take exponential time with a backtracking matcher.
+        let many_wildcards = "%a".repeat(30);
+        let many_a = "a".repeat(29);
+
+        let cases = [
+            (None, "anything", true),
+            (Some("%"), "", true),
+            (Some("public"), "public", true),
+            (Some("public"), "public2", false),
+            (Some("pub%"), "public", true),
+            (Some("%lic"), "public", true),
+            (Some("p_blic"), "public", true),
+            (Some("p_blic"), "pblic", false),
+            (Some("%a%b%"), "xaxbx", true),
+            (Some("%a%b%"), "xbxax", false),
+            (Some("my\\_table"), "my_table", true),
+            (Some("my\\_table"), "myxtable", false),
+            (Some("my\\%"), "my%", true),
+            (Some("my\\%"), "mytable", false),
+            (Some("trailing\\"), "trailing\\", true),
+            (Some(many_wildcards.as_str()), many_a.as_str(), false),
+        ];
+        for (pattern, value, expected) in cases {
+            assert_eq!(
+                expected,
+                matches_pattern(pattern, value),
+                "pattern: {pattern:?}, value: {value}"
+            );
+        }
+    }
+
+    #[test]
+    fn primary_keys_empty() {
+        let batch = get_primary_keys(CommandGetPrimaryKeys::default()).unwrap();
+        assert_eq!(0, batch.num_rows());
+        assert_eq!(PRIMARY_KEYS_SCHEMA.clone(), batch.schema());
+    }
+}
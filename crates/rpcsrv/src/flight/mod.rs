@@ -1,2 +1,5 @@
 pub mod handler;
+pub mod metadata;
 pub mod proxy;
+pub mod put;
+pub mod session;
@@ -177,8 +177,11 @@ impl FlightService for CloudFlightProxyHandler {
     ) -> Result<Response<Self::DoPutStream>, Status> {
         let meta = request.metadata_mut();
         let mut client = self.connect(meta).await?;
-        let req = request.into_inner();
-        let req = ProxiedRequestStream::new(req);
+        // Forward the metadata so that the upstream node knows which database
+        // the data is for.
+        let meta = request.metadata().clone();
+        let mut req = Request::new(ProxiedRequestStream::new(request.into_inner()));
+        *req.metadata_mut() = meta;
         let res = client.do_put(req).await?;
         let res = res.into_inner();
         Ok(Response::new(res.boxed()))
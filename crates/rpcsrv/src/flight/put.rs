@@ -0,0 +1,311 @@
+//! Handling of Arrow data sent by clients with DoPut.
+//!
+//! Updates are executed through portals in the session. Data sent alongside a
+//! `COPY <table> FROM STDIN` statement is inserted directly into the table,
+//! which is how clients bulk ingest batches. For other prepared statements,
+//! each row of the incoming batches holds the values for the statement's
+//! parameters.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow_flight::utils::flight_data_to_arrow_batch;
+use arrow_flight::FlightData;
+use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::buffer::Buffer;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{Field, Schema, SchemaRef};
+use datafusion::arrow::ipc::reader::read_dictionary;
+use datafusion::arrow::ipc::{root_as_message, MessageHeader};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::scalar::ScalarValue;
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
+use sqlexec::copy_in::CopyInSink;
+use sqlexec::session::{ExecutionResult, Session, TransactionStatus};
+use tonic::Status;
+
+use crate::errors::{Result, RpcsrvError};
+use crate::flight::handler::execute_transaction_statement;
+
+/// Messages sent by the client with DoPut, starting with the message holding
+/// the flight descriptor.
+pub type PutStream = BoxStream<'static, Result<FlightData, Status>>;
+
+/// Decodes record batches from a DoPut stream.
+///
+/// Batches are decoded using the schema sent by the client, which is
+/// usually part of the message holding the flight descriptor. Dictionaries
+/// sent by the client are kept for decoding the batches that reference them.
+pub struct PutBatchStream {
+    inner: PutStream,
+    schema: Option<SchemaRef>,
+    dictionaries_by_id: HashMap<i64, ArrayRef>,
+}
+
+impl PutBatchStream {
+    pub fn new(inner: PutStream) -> Self {
+        PutBatchStream {
+            inner,
+            schema: None,
+            dictionaries_by_id: HashMap::new(),
+        }
+    }
+
+    /// Get the next batch from the client, cast to the expected schema.
+    ///
+    /// Returns `None` once the client has finished sending data.
+    pub async fn next_batch(&mut self, expected: &SchemaRef) -> Result<Option<RecordBatch>> {
+        while let Some(data) = self.inner.try_next().await? {
+            // Messages may only contain the descriptor or app metadata.
+            if data.data_header.is_empty() {
+                continue;
+            }
+            let message = root_as_message(&data.data_header)
+                .map_err(|e| RpcsrvError::ParseError(format!("invalid IPC message: {e}")))?;
+            match message.header_type() {
+                MessageHeader::Schema => self.schema = Some(Arc::new(Schema::try_from(&data)?)),
+                MessageHeader::DictionaryBatch => {
+                    let schema = self.schema.as_ref().ok_or_else(|| {
+                        RpcsrvError::ParseError(
+                            "Received dictionary batch before schema".to_string(),
+                        )
+                    })?;
+                    let batch = message.header_as_dictionary_batch().ok_or_else(|| {
+                        RpcsrvError::ParseError("invalid dictionary batch message".to_string())
+                    })?;
+                    read_dictionary(
+                        &Buffer::from(&data.data_body),
+                        batch,
+                        schema,
+                        &mut self.dictionaries_by_id,
+                        &message.version(),
+                    )?;
+                }
+                MessageHeader::RecordBatch => {
+                    let schema = self.schema.clone().ok_or_else(|| {
+                        RpcsrvError::ParseError("Received record batch before schema".to_string())
+                    })?;
+                    let batch =
+                        flight_data_to_arrow_batch(&data, schema, &self.dictionaries_by_id)?;
+                    return Ok(Some(cast_batch(&batch, expected)?));
+                }
+                other => {
+                    return Err(RpcsrvError::ParseError(format!(
+                        "unsupported IPC message: {other:?}"
+                    )))
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Execute a bound portal, returning the number of rows affected.
+///
+/// Data from the client is only read for `COPY ... FROM STDIN`.
+pub async fn execute_update(sess: &mut Session, portal: &str, stream: PutStream) -> Result<i64> {
+    match sess.execute_portal(portal, 0).await? {
+        ExecutionResult::CopyIn { sink } => copy_in(sink, stream).await,
+        result => affected_rows(result),
+    }
+}
+
+/// Execute a prepared statement once for every row of parameters sent by
+/// the client, returning the total number of rows affected.
+///
+/// All rows are executed in a single transaction, so nothing is applied if
+/// one of them fails. If the session is already in a transaction block, the
+/// updates are part of that transaction instead.
+pub async fn execute_update_with_params(
+    sess: &mut Session,
+    portal: &str,
+    stream: PutStream,
+) -> Result<i64> {
+    if sess.transaction_status() != TransactionStatus::Idle {
+        return execute_each_row(sess, portal, stream).await;
+    }
+
+    execute_transaction_statement(sess, "BEGIN").await?;
+    let result = execute_each_row(sess, portal, stream).await;
+    let end = if result.is_ok() { "COMMIT" } else { "ROLLBACK" };
+    execute_transaction_statement(sess, end).await?;
+    result
+}
+
+/// Execute a prepared statement for every row of parameters.
+async fn execute_each_row(sess: &mut Session, portal: &str, stream: PutStream) -> Result<i64> {
+    let params = sess
+        .get_prepared_statement(portal)?
+        .ordered_input_parameters();
+    let schema: SchemaRef = Arc::new(Schema::new(
+        params
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (_, typ))| Field::new(format!("${}", idx + 1), typ, true))
+            .collect::<Vec<_>>(),
+    ));
+
+    let mut batches = PutBatchStream::new(stream);
+    let mut count = 0;
+    while let Some(batch) = batches.next_batch(&schema).await? {
+        for row in 0..batch.num_rows() {
+            let values = batch
+                .columns()
+                .iter()
+                .map(|col| ScalarValue::try_from_array(col, row))
+                .collect::<Result<Vec<_>, _>>()?;
+            sess.bind_portal(portal, values)?;
+            count += affected_rows(sess.execute_portal(portal, 0).await?)?;
+        }
+    }
+    Ok(count)
+}
+
+/// Insert all batches sent by the client using the sink.
+async fn copy_in(mut sink: CopyInSink, stream: PutStream) -> Result<i64> {
+    let schema = sink.schema();
+    let mut batches = PutBatchStream::new(stream);
+    while let Some(batch) = batches.next_batch(&schema).await? {
+        sink.send(batch).await?;
+    }
+    Ok(sink.finish().await? as i64)
+}
+
+/// Get the number of rows affected by a statement.
+///
+/// Statements that don't modify rows (e.g. DDL) affect zero rows.
+fn affected_rows(result: ExecutionResult) -> Result<i64> {
+    Ok(match result {
+        ExecutionResult::InsertSuccess { rows_inserted } => rows_inserted as i64,
+        ExecutionResult::DeleteSuccess { deleted_rows } => deleted_rows as i64,
+        ExecutionResult::UpdateSuccess { updated_rows } => updated_rows as i64,
+        ExecutionResult::Error(e) => return Err(e.into()),
+        ExecutionResult::Query { .. }
+        | ExecutionResult::Suspended { .. }
+        | ExecutionResult::Fetch { .. } => {
+            return Err(RpcsrvError::Internal(
+                "Statement returns rows, execute it as a query instead".to_string(),
+            ))
+        }
+        ExecutionResult::CopyIn { .. } => {
+            return Err(RpcsrvError::Internal(
+                "COPY FROM STDIN can't be used with parameters".to_string(),
+            ))
+        }
+        _ => 0,
+    })
+}
+
+/// Cast the columns of a batch to the expected schema.
+fn cast_batch(batch: &RecordBatch, expected: &SchemaRef) -> Result<RecordBatch> {
+    if batch.num_columns() != expected.fields().len() {
+        return Err(RpcsrvError::Internal(format!(
+            "Expected {} columns, received {}",
+            expected.fields().len(),
+            batch.num_columns()
+        )));
+    }
+    let columns = batch
+        .columns()
+        .iter()
+        .zip(expected.fields())
+        .map(|(col, field)| cast(col, field.data_type()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(RecordBatch::try_new(expected.clone(), columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_flight::encode::FlightDataEncoderBuilder;
+    use arrow_flight::utils::batches_to_flight_data;
+    use arrow_flight::FlightDescriptor;
+    use datafusion::arrow::array::{DictionaryArray, Int32Array, Int64Array, StringArray};
+    use datafusion::arrow::datatypes::DataType;
+    use futures::{stream, StreamExt};
+
+    use super::*;
+
+    /// Encode a batch the way clients send it, with the schema in the message
+    /// holding the descriptor.
+    fn put_stream(batch: RecordBatch) -> PutStream {
+        FlightDataEncoderBuilder::new()
+            .with_flight_descriptor(Some(FlightDescriptor::new_cmd(b"cmd".to_vec())))
+            .build(stream::iter([Ok(batch)]))
+            .map_err(Status::from)
+            .boxed()
+    }
+
+    fn int32_batch() -> RecordBatch {
+        RecordBatch::try_from_iter([("a", Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef)])
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn decode_with_client_schema() {
+        let expected = Arc::new(Schema::new(vec![Field::new("$1", DataType::Int64, true)]));
+        let mut batches = PutBatchStream::new(put_stream(int32_batch()));
+
+        let out = batches.next_batch(&expected).await.unwrap().unwrap();
+        assert_eq!(expected, out.schema());
+        assert_eq!(
+            &Int64Array::from(vec![1, 2]),
+            out.column(0).as_any().downcast_ref::<Int64Array>().unwrap()
+        );
+        assert!(batches.next_batch(&expected).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn decode_dictionary_batches() {
+        let keys = Int32Array::from(vec![0, 1, 0]);
+        let values = Arc::new(StringArray::from(vec!["a", "b"]));
+        let array = DictionaryArray::try_new(keys, values).unwrap();
+        let batch = RecordBatch::try_from_iter([("a", Arc::new(array) as ArrayRef)]).unwrap();
+
+        // Sent as the schema, then the dictionary, then the batch.
+        let data = batches_to_flight_data(&batch.schema(), vec![batch]).unwrap();
+        assert_eq!(3, data.len());
+        let stream = stream::iter(data.into_iter().map(Ok)).boxed();
+
+        let expected = Arc::new(Schema::new(vec![Field::new("$1", DataType::Utf8, true)]));
+        let mut batches = PutBatchStream::new(stream);
+
+        let out = batches.next_batch(&expected).await.unwrap().unwrap();
+        assert_eq!(
+            &StringArray::from(vec!["a", "b", "a"]),
+            out.column(0)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap()
+        );
+        assert!(batches.next_batch(&expected).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn batch_before_schema() {
+        let expected = Arc::new(Schema::new(vec![Field::new("$1", DataType::Int64, true)]));
+        let stream = put_stream(int32_batch()).skip(1).boxed();
+        let mut batches = PutBatchStream::new(stream);
+
+        batches.next_batch(&expected).await.unwrap_err();
+    }
+
+    #[test]
+    fn cast_batch_to_expected() {
+        let batch = int32_batch();
+        let expected = Arc::new(Schema::new(vec![Field::new("$1", DataType::Int64, true)]));
+
+        let out = cast_batch(&batch, &expected).unwrap();
+        assert_eq!(expected, out.schema());
+        assert_eq!(
+            &Int64Array::from(vec![1, 2]),
+            out.column(0).as_any().downcast_ref::<Int64Array>().unwrap()
+        );
+
+        let wrong = Arc::new(Schema::new(vec![
+            Field::new("$1", DataType::Int64, true),
+            Field::new("$2", DataType::Int64, true),
+        ]));
+        cast_batch(&batch, &wrong).unwrap_err();
+    }
+}
@@ -0,0 +1,122 @@
+//! Lifecycle of Flight SQL sessions.
+//!
+//! Flight SQL doesn't have a connection that's held open for the duration of
+//! a session, so sessions are kept around between requests. Sessions are
+//! closed when the client sends a `CloseSession` action, or once they've been
+//! idle for longer than the configured timeout.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use datafusion::logical_expr::LogicalPlan;
+use sqlexec::cancel::CancelHandle;
+use sqlexec::session::Session;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Action type for closing the caller's session.
+pub const CLOSE_SESSION: &str = "CloseSession";
+
+/// Action type for canceling a query that was started from a flight info.
+pub const CANCEL_FLIGHT_INFO: &str = "CancelFlightInfo";
+
+/// Configuration for Flight SQL sessions.
+#[derive(Debug, Clone, Copy)]
+pub struct FlightSessionConfig {
+    /// Sessions that haven't been used for this long are closed.
+    pub idle_timeout: Duration,
+    /// Maximum number of open sessions. New sessions are rejected once this
+    /// is reached.
+    pub max_sessions: usize,
+}
+
+impl FlightSessionConfig {
+    /// How often to check for idle sessions.
+    pub fn expiry_interval(&self) -> Duration {
+        self.idle_timeout
+            .clamp(Duration::from_secs(1), Duration::from_secs(60))
+    }
+}
+
+impl Default for FlightSessionConfig {
+    fn default() -> Self {
+        FlightSessionConfig {
+            idle_timeout: Duration::from_secs(15 * 60),
+            max_sessions: 1024,
+        }
+    }
+}
+
+/// A session opened by a Flight SQL client.
+#[derive(Clone)]
+pub struct FlightSession {
+    /// Id of the session in the engine's session registry.
+    pub id: Uuid,
+    pub session: Arc<Mutex<Session>>,
+    /// Handle for canceling statements without needing to wait on the
+    /// session lock.
+    pub cancel: CancelHandle,
+    /// Plans for prepared statements, keyed by statement handle. These are
+    /// dropped along with the session.
+    pub logical_plans: Arc<DashMap<String, LogicalPlan>>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CloseSessionResult {
+    #[prost(enumeration = "CloseSessionStatus", tag = "1")]
+    pub status: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CloseSessionStatus {
+    Unspecified = 0,
+    Closed = 1,
+    Closing = 2,
+    NotClosable = 3,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelFlightInfoResult {
+    #[prost(enumeration = "CancelStatus", tag = "1")]
+    pub status: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CancelStatus {
+    Unspecified = 0,
+    Cancelled = 1,
+    Cancelling = 2,
+    NotCancellable = 3,
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+
+    use super::*;
+
+    #[test]
+    fn expiry_interval_bounds() {
+        let conf = FlightSessionConfig {
+            idle_timeout: Duration::from_millis(10),
+            max_sessions: 1,
+        };
+        assert_eq!(Duration::from_secs(1), conf.expiry_interval());
+        assert_eq!(
+            Duration::from_secs(60),
+            FlightSessionConfig::default().expiry_interval()
+        );
+    }
+
+    #[test]
+    fn close_session_result_roundtrip() {
+        let res = CloseSessionResult {
+            status: CloseSessionStatus::Closed as i32,
+        };
+        let decoded = CloseSessionResult::decode(res.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(CloseSessionStatus::Closed, decoded.status());
+    }
+}
@@ -9,6 +9,9 @@ mod util;
 
 pub mod export {
     pub use arrow_flight;
+    pub use datafusion::arrow::array::{ArrayRef, Int64Array, StringArray};
     pub use datafusion::arrow::datatypes::Schema;
+    pub use datafusion::arrow::record_batch::RecordBatch;
+    pub use prost;
     pub use tonic;
 }
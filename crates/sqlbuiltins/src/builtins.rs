@@ -13,7 +13,9 @@
 //! database node will be able to see it, but will not be able to execute
 //! appropriately. We can revisit this if this isn't acceptable long-term.
 
-use datafusion::arrow::datatypes::{DataType, Field as ArrowField, Schema as ArrowSchema};
+use datafusion::arrow::datatypes::{
+    DataType, Field as ArrowField, Schema as ArrowSchema, TimeUnit,
+};
 use once_cell::sync::Lazy;
 use pgrepr::oid::FIRST_GLAREDB_BUILTIN_ID;
 use protogen::metastore::types::options::InternalColumnDefinition;
@@ -229,6 +231,42 @@ pub static GLARE_DEPLOYMENT_METADATA: Lazy<BuiltinTable> = Lazy::new(|| BuiltinT
     oid: 16410,
 });
 
+pub static GLARE_ROLES: Lazy<BuiltinTable> = Lazy::new(|| BuiltinTable {
+    schema: INTERNAL_SCHEMA,
+    name: "roles",
+    columns: InternalColumnDefinition::from_tuples([
+        ("oid", DataType::UInt32, false),
+        ("role_name", DataType::Utf8, false),
+        ("login", DataType::Boolean, false),
+        ("superuser", DataType::Boolean, false),
+        ("has_password", DataType::Boolean, false),
+    ]),
+    oid: 16412,
+});
+
+/// Active Flight SQL sessions for the current database.
+///
+/// Sessions are only listed for superusers since they expose client addresses.
+pub static GLARE_FLIGHT_SESSIONS: Lazy<BuiltinTable> = Lazy::new(|| BuiltinTable {
+    schema: INTERNAL_SCHEMA,
+    name: "flight_sessions",
+    columns: InternalColumnDefinition::from_tuples([
+        ("session_id", DataType::Utf8, false),
+        ("client_addr", DataType::Utf8, false),
+        (
+            "created_at",
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            false,
+        ),
+        (
+            "last_active_at",
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            false,
+        ),
+    ]),
+    oid: 16413,
+});
+
 /// Cached table metadata for external databases.
 ///
 /// This stores information for all tables, and all columns for each table.
@@ -283,6 +321,8 @@ impl BuiltinTable {
             &GLARE_SSH_KEYS,
             &GLARE_DEPLOYMENT_METADATA,
             &GLARE_CACHED_EXTERNAL_DATABASE_TABLES,
+            &GLARE_ROLES,
+            &GLARE_FLIGHT_SESSIONS,
         ]
     }
 }
@@ -6,7 +6,9 @@ mod table;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use catalog::session_catalog::SessionCatalog;
 use datafusion::logical_expr::{AggregateFunction, BuiltinScalarFunction, Expr, Signature};
+use datafusion_ext::vars::SessionVars;
 use once_cell::sync::Lazy;
 
 use protogen::metastore::types::catalog::FunctionType;
@@ -17,6 +19,8 @@ use scalars::postgres::*;
 use scalars::{ConnectionId, Version};
 use table::{BuiltinTableFuncs, TableFunc};
 
+pub use table::virtual_listing::get_virtual_lister_for_external_db;
+
 /// All builtin functions available for all sessions.
 pub static FUNCTION_REGISTRY: Lazy<FunctionRegistry> = Lazy::new(FunctionRegistry::new);
 
@@ -100,6 +104,20 @@ pub trait BuiltinScalarUDF: BuiltinFunction {
     /// Builds an expression for the function using the provided arguments.
     fn as_expr(&self, args: Vec<Expr>) -> Expr;
 
+    /// Builds an expression for the function with access to the session's
+    /// catalog and variables.
+    ///
+    /// Defaults to [`BuiltinScalarUDF::as_expr`]. Functions that depend on
+    /// catalog state should override this.
+    fn as_expr_with_session(
+        &self,
+        args: Vec<Expr>,
+        _catalog: &SessionCatalog,
+        _vars: &SessionVars,
+    ) -> Expr {
+        self.as_expr(args)
+    }
+
     /// The namespace of the function.
     /// Defaults to global (None)
     fn namespace(&self) -> FunctionNamespace {
@@ -1,5 +1,9 @@
+use catalog::session_catalog::SessionCatalog;
 use datafusion::logical_expr::expr::ScalarFunction;
+use datafusion_ext::vars::SessionVars;
+use protogen::metastore::types::catalog::{CatalogEntry, Privilege};
 
+use crate::builtins::DEFAULT_CATALOG;
 use crate::functions::FunctionNamespace;
 
 use super::{df_scalars::array_to_string, *};
@@ -160,18 +164,28 @@ impl ConstBuiltinFunction for HasSchemaPrivilege {
 
 impl BuiltinScalarUDF for HasSchemaPrivilege {
     fn as_expr(&self, args: Vec<Expr>) -> Expr {
-        let udf = ScalarUDF {
-            name: Self::NAME.to_string(),
-            signature: ConstBuiltinFunction::signature(self).unwrap(),
-            return_type: Arc::new(|_| Ok(Arc::new(DataType::Boolean))),
-            fun: Arc::new(move |_input| {
-                Ok(ColumnarValue::Scalar(ScalarValue::Boolean(Some(true))))
-            }),
-        };
-        Expr::ScalarUDF(datafusion::logical_expr::expr::ScalarUDF::new(
-            Arc::new(udf),
+        privilege_udf(
+            Self::NAME,
+            ConstBuiltinFunction::signature(self).unwrap(),
             args,
-        ))
+            None,
+            check_schema_privilege,
+        )
+    }
+
+    fn as_expr_with_session(
+        &self,
+        args: Vec<Expr>,
+        catalog: &SessionCatalog,
+        vars: &SessionVars,
+    ) -> Expr {
+        privilege_udf(
+            Self::NAME,
+            ConstBuiltinFunction::signature(self).unwrap(),
+            args,
+            Some(PrivilegeContext::new(catalog, vars)),
+            check_schema_privilege,
+        )
     }
 
     fn namespace(&self) -> FunctionNamespace {
@@ -200,18 +214,28 @@ impl ConstBuiltinFunction for HasDatabasePrivilege {
 
 impl BuiltinScalarUDF for HasDatabasePrivilege {
     fn as_expr(&self, args: Vec<Expr>) -> Expr {
-        let udf = ScalarUDF {
-            name: Self::NAME.to_string(),
-            signature: ConstBuiltinFunction::signature(self).unwrap(),
-            return_type: Arc::new(|_| Ok(Arc::new(DataType::Boolean))),
-            fun: Arc::new(move |_input| {
-                Ok(ColumnarValue::Scalar(ScalarValue::Boolean(Some(true))))
-            }),
-        };
-        Expr::ScalarUDF(datafusion::logical_expr::expr::ScalarUDF::new(
-            Arc::new(udf),
+        privilege_udf(
+            Self::NAME,
+            ConstBuiltinFunction::signature(self).unwrap(),
             args,
-        ))
+            None,
+            check_database_privilege,
+        )
+    }
+
+    fn as_expr_with_session(
+        &self,
+        args: Vec<Expr>,
+        catalog: &SessionCatalog,
+        vars: &SessionVars,
+    ) -> Expr {
+        privilege_udf(
+            Self::NAME,
+            ConstBuiltinFunction::signature(self).unwrap(),
+            args,
+            Some(PrivilegeContext::new(catalog, vars)),
+            check_database_privilege,
+        )
     }
 
     fn namespace(&self) -> FunctionNamespace {
@@ -239,18 +263,28 @@ impl ConstBuiltinFunction for HasTablePrivilege {
 
 impl BuiltinScalarUDF for HasTablePrivilege {
     fn as_expr(&self, args: Vec<Expr>) -> Expr {
-        let udf = ScalarUDF {
-            name: Self::NAME.to_string(),
-            signature: ConstBuiltinFunction::signature(self).unwrap(),
-            return_type: Arc::new(|_| Ok(Arc::new(DataType::Boolean))),
-            fun: Arc::new(move |_input| {
-                Ok(ColumnarValue::Scalar(ScalarValue::Boolean(Some(true))))
-            }),
-        };
-        Expr::ScalarUDF(datafusion::logical_expr::expr::ScalarUDF::new(
-            Arc::new(udf),
+        privilege_udf(
+            Self::NAME,
+            ConstBuiltinFunction::signature(self).unwrap(),
             args,
-        ))
+            None,
+            check_table_privilege,
+        )
+    }
+
+    fn as_expr_with_session(
+        &self,
+        args: Vec<Expr>,
+        catalog: &SessionCatalog,
+        vars: &SessionVars,
+    ) -> Expr {
+        privilege_udf(
+            Self::NAME,
+            ConstBuiltinFunction::signature(self).unwrap(),
+            args,
+            Some(PrivilegeContext::new(catalog, vars)),
+            check_table_privilege,
+        )
     }
 
     fn namespace(&self) -> FunctionNamespace {
@@ -258,6 +292,219 @@ impl BuiltinScalarUDF for HasTablePrivilege {
     }
 }
 
+/// Session state needed to answer privilege checks.
+#[derive(Clone)]
+struct PrivilegeContext {
+    catalog: Arc<SessionCatalog>,
+    user: String,
+    database: String,
+    search_path: Vec<String>,
+}
+
+impl PrivilegeContext {
+    fn new(catalog: &SessionCatalog, vars: &SessionVars) -> Self {
+        PrivilegeContext {
+            catalog: Arc::new(catalog.clone()),
+            user: vars.user_name(),
+            database: vars.database_name(),
+            search_path: vars.implicit_search_path(),
+        }
+    }
+
+    /// Error if the user isn't the current user, and doesn't have a role.
+    fn check_user_exists(&self, user: &str) -> Result<(), BuiltinError> {
+        if user != self.user && self.catalog.resolve_role(user).is_none() {
+            return Err(BuiltinError::InvalidValue(format!(
+                "role \"{user}\" does not exist"
+            )));
+        }
+        Ok(())
+    }
+}
+
+type PrivilegeCheck = fn(&PrivilegeContext, &str, &str, &str) -> Result<bool, BuiltinError>;
+
+/// Build an expression for one of the `has_*_privilege` functions.
+///
+/// Without a session context, all privileges are reported as held.
+fn privilege_udf(
+    name: &str,
+    signature: Signature,
+    args: Vec<Expr>,
+    ctx: Option<PrivilegeContext>,
+    check: PrivilegeCheck,
+) -> Expr {
+    let udf = ScalarUDF {
+        name: name.to_string(),
+        signature,
+        return_type: Arc::new(|_| Ok(Arc::new(DataType::Boolean))),
+        fun: Arc::new(move |input| match &ctx {
+            Some(ctx) => Ok(eval_privilege_check(input, ctx, check)?),
+            None => Ok(ColumnarValue::Scalar(ScalarValue::Boolean(Some(true)))),
+        }),
+    };
+    Expr::ScalarUDF(datafusion::logical_expr::expr::ScalarUDF::new(
+        Arc::new(udf),
+        args,
+    ))
+}
+
+/// Run a privilege check for every row of the input.
+///
+/// Arguments are either `(user, object, privileges)` or `(object,
+/// privileges)`, in which case the current user is checked. Null arguments
+/// produce a null result.
+fn eval_privilege_check(
+    input: &[ColumnarValue],
+    ctx: &PrivilegeContext,
+    check: PrivilegeCheck,
+) -> Result<ColumnarValue, BuiltinError> {
+    let check_row = |idx: usize| -> Result<ScalarValue, BuiltinError> {
+        let mut args = Vec::with_capacity(input.len());
+        for value in input {
+            let scalar = match value {
+                ColumnarValue::Scalar(scalar) => scalar.clone(),
+                ColumnarValue::Array(arr) => ScalarValue::try_from_array(arr, idx)?,
+            };
+            match scalar {
+                ScalarValue::Utf8(Some(v)) | ScalarValue::LargeUtf8(Some(v)) => args.push(v),
+                ScalarValue::Utf8(None) | ScalarValue::LargeUtf8(None) | ScalarValue::Null => {
+                    return Ok(ScalarValue::Boolean(None))
+                }
+                other => {
+                    return Err(BuiltinError::IncorrectType(
+                        other.data_type(),
+                        DataType::Utf8,
+                    ))
+                }
+            }
+        }
+        let has_privilege = match args.as_slice() {
+            [user, object, privileges] => check(ctx, user, object, privileges)?,
+            [object, privileges] => check(ctx, &ctx.user, object, privileges)?,
+            _ => return Err(BuiltinError::MissingValueAtIndex(args.len())),
+        };
+        Ok(ScalarValue::Boolean(Some(has_privilege)))
+    };
+
+    let num_rows = input.iter().find_map(|value| match value {
+        ColumnarValue::Array(arr) => Some(arr.len()),
+        ColumnarValue::Scalar(_) => None,
+    });
+    match num_rows {
+        Some(num_rows) => Ok(ColumnarValue::Array(scalar_iter_to_array(
+            (0..num_rows).map(|idx| -> Result<ScalarValue, ExtensionError> { Ok(check_row(idx)?) }),
+        )?)),
+        None => Ok(ColumnarValue::Scalar(check_row(0)?)),
+    }
+}
+
+/// Parse a comma separated list of privileges, e.g. 'SELECT, INSERT'.
+fn parse_privileges(
+    privileges: &str,
+    allowed: &[Privilege],
+) -> Result<Vec<Privilege>, BuiltinError> {
+    privileges
+        .split(',')
+        .map(|privilege| match privilege.trim().parse::<Privilege>() {
+            Ok(parsed) if allowed.contains(&parsed) => Ok(parsed),
+            _ => Err(BuiltinError::InvalidValue(format!(
+                "unrecognized privilege type: \"{}\"",
+                privilege.trim()
+            ))),
+        })
+        .collect()
+}
+
+fn check_table_privilege(
+    ctx: &PrivilegeContext,
+    user: &str,
+    table: &str,
+    privileges: &str,
+) -> Result<bool, BuiltinError> {
+    ctx.check_user_exists(user)?;
+    let privileges = parse_privileges(privileges, Privilege::TABLE_PRIVILEGES)?;
+
+    let ent = match table.split_once('.') {
+        Some((schema, name)) => ctx.catalog.resolve_entry(DEFAULT_CATALOG, schema, name),
+        None => ctx
+            .search_path
+            .iter()
+            .find_map(|schema| ctx.catalog.resolve_entry(DEFAULT_CATALOG, schema, table)),
+    };
+    let oid = match ent {
+        Some(ent @ (CatalogEntry::Table(_) | CatalogEntry::View(_))) => ent.get_meta().id,
+        _ => {
+            return Err(BuiltinError::InvalidValue(format!(
+                "relation \"{table}\" does not exist"
+            )))
+        }
+    };
+
+    Ok(privileges
+        .into_iter()
+        .any(|privilege| ctx.catalog.has_privilege(user, oid, privilege)))
+}
+
+fn check_schema_privilege(
+    ctx: &PrivilegeContext,
+    user: &str,
+    schema: &str,
+    privileges: &str,
+) -> Result<bool, BuiltinError> {
+    ctx.check_user_exists(user)?;
+    let privileges = parse_privileges(privileges, Privilege::SCHEMA_PRIVILEGES)?;
+
+    let oid = match ctx.catalog.resolve_schema(schema) {
+        Some(ent) => ent.meta.id,
+        None => {
+            return Err(BuiltinError::InvalidValue(format!(
+                "schema \"{schema}\" does not exist"
+            )))
+        }
+    };
+
+    Ok(privileges
+        .into_iter()
+        .any(|privilege| ctx.catalog.has_privilege(user, oid, privilege)))
+}
+
+/// Database privileges aren't granted, and are instead derived from the role.
+/// Roles with `LOGIN` can connect, and only superusers can create schemas.
+/// Temporary tables can always be created.
+fn check_database_privilege(
+    ctx: &PrivilegeContext,
+    user: &str,
+    database: &str,
+    privileges: &str,
+) -> Result<bool, BuiltinError> {
+    ctx.check_user_exists(user)?;
+    if database != ctx.database && ctx.catalog.resolve_database(database).is_none() {
+        return Err(BuiltinError::InvalidValue(format!(
+            "database \"{database}\" does not exist"
+        )));
+    }
+
+    let mut has_privilege = false;
+    for privilege in privileges.split(',') {
+        let privilege = privilege.trim();
+        has_privilege |= match privilege.to_uppercase().as_str() {
+            "CONNECT" => match ctx.catalog.resolve_role(user) {
+                Some(role) => role.login || role.superuser,
+                None => true,
+            },
+            "CREATE" => ctx.catalog.is_superuser(user),
+            "TEMP" | "TEMPORARY" => true,
+            _ => {
+                return Err(BuiltinError::InvalidValue(format!(
+                    "unrecognized privilege type: \"{privilege}\""
+                )))
+            }
+        };
+    }
+    Ok(has_privilege)
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct CurrentSchemas;
 
@@ -1,12 +1,17 @@
 use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use datafusion::datasource::TableProvider;
+use datafusion::scalar::ScalarValue;
 use datafusion_ext::{
     errors::{ExtensionError, Result},
     functions::{FuncParamValue, TableFuncContextProvider},
 };
-use datasources::lake::{iceberg::table::IcebergTable, storage_options_into_object_store};
+use datasources::lake::{
+    iceberg::table::{IcebergTable, SnapshotSelector},
+    storage_options_into_object_store,
+};
 use protogen::metastore::types::catalog::{FunctionType, RuntimePreference};
 
 use crate::functions::{
@@ -21,7 +26,8 @@ pub struct IcebergScan;
 impl ConstBuiltinFunction for IcebergScan {
     const NAME: &'static str = "iceberg_scan";
     const DESCRIPTION: &'static str = "Scans an iceberg table";
-    const EXAMPLE: &'static str = "SELECT * FROM iceberg_scan('file:///path/to/table')";
+    const EXAMPLE: &'static str =
+        "SELECT * FROM iceberg_scan('file:///path/to/table', as_of => '2023-11-01 12:00:00')";
     const FUNCTION_TYPE: FunctionType = FunctionType::TableReturning;
 }
 
@@ -42,6 +48,17 @@ impl TableFunc for IcebergScan {
         args: Vec<FuncParamValue>,
         mut opts: HashMap<String, FuncParamValue>,
     ) -> Result<Arc<dyn TableProvider>> {
+        let snapshot = match (opts.remove("snapshot_id"), opts.remove("as_of")) {
+            (None, None) => SnapshotSelector::Current,
+            (Some(id), None) => SnapshotSelector::Id(id.try_into()?),
+            (None, Some(as_of)) => SnapshotSelector::AsOf(param_to_timestamp(as_of)?),
+            (Some(_), Some(_)) => {
+                return Err(ExtensionError::String(
+                    "Only one of 'snapshot_id' or 'as_of' may be provided".to_string(),
+                ))
+            }
+        };
+
         // TODO: Reduce duplication
         let (loc, opts) = table_location_and_opts(ctx, args, &mut opts)?;
 
@@ -50,8 +67,90 @@ impl TableFunc for IcebergScan {
         let table = IcebergTable::open(loc.clone(), store)
             .await
             .map_err(ExtensionError::access)?;
-        let reader = table.table_reader().await.map_err(ExtensionError::access)?;
+        let reader = table
+            .table_reader_at(snapshot)
+            .await
+            .map_err(ExtensionError::access)?;
 
         Ok(reader)
     }
 }
+
+/// Convert a parameter to a timestamp for time travel.
+///
+/// Accepts timestamp strings (assumed to be UTC if no offset is provided),
+/// timestamp values, or integers as milliseconds since the epoch.
+fn param_to_timestamp(value: FuncParamValue) -> Result<DateTime<Utc>> {
+    let invalid = |value: &FuncParamValue| ExtensionError::InvalidParamValue {
+        param: value.to_string(),
+        expected: "timestamp",
+    };
+
+    let ts = match &value {
+        FuncParamValue::Scalar(ScalarValue::Utf8(Some(s))) => {
+            let s = s.trim();
+            if let Ok(ts) = DateTime::parse_from_rfc3339(s) {
+                Some(ts.with_timezone(&Utc))
+            } else if let Ok(ts) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f") {
+                Some(Utc.from_utc_datetime(&ts))
+            } else if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+                date.and_hms_opt(0, 0, 0)
+                    .map(|ts| Utc.from_utc_datetime(&ts))
+            } else {
+                None
+            }
+        }
+        FuncParamValue::Scalar(ScalarValue::TimestampSecond(Some(v), _)) => {
+            Utc.timestamp_opt(*v, 0).single()
+        }
+        FuncParamValue::Scalar(ScalarValue::TimestampMillisecond(Some(v), _)) => {
+            Utc.timestamp_millis_opt(*v).single()
+        }
+        FuncParamValue::Scalar(ScalarValue::TimestampMicrosecond(Some(v), _)) => Utc
+            .timestamp_opt(
+                v.div_euclid(1_000_000),
+                (v.rem_euclid(1_000_000) * 1000) as u32,
+            )
+            .single(),
+        FuncParamValue::Scalar(ScalarValue::TimestampNanosecond(Some(v), _)) => {
+            Some(Utc.timestamp_nanos(*v))
+        }
+        _ => {
+            let ms: i64 = value.clone().try_into().map_err(|_| invalid(&value))?;
+            Utc.timestamp_millis_opt(ms).single()
+        }
+    };
+
+    ts.ok_or_else(|| invalid(&value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_param_to_timestamp() {
+        let expected = Utc.with_ymd_and_hms(2023, 11, 1, 12, 30, 0).unwrap();
+
+        let test_cases = vec![
+            FuncParamValue::Scalar(ScalarValue::Utf8(Some("2023-11-01 12:30:00".to_string()))),
+            FuncParamValue::Scalar(ScalarValue::Utf8(Some(
+                "2023-11-01T14:30:00+02:00".to_string(),
+            ))),
+            FuncParamValue::Scalar(ScalarValue::Int64(Some(expected.timestamp_millis()))),
+            FuncParamValue::Scalar(ScalarValue::TimestampMicrosecond(
+                Some(expected.timestamp_micros()),
+                None,
+            )),
+        ];
+
+        for tc in test_cases {
+            assert_eq!(expected, param_to_timestamp(tc.clone()).unwrap(), "{tc}");
+        }
+
+        param_to_timestamp(FuncParamValue::Scalar(ScalarValue::Utf8(Some(
+            "yesterday".to_string(),
+        ))))
+        .unwrap_err();
+    }
+}
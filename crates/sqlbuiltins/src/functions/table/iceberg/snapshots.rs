@@ -63,7 +63,7 @@ impl TableFunc for IcebergSnapshots {
             Field::new("snapshot_id", DataType::Int64, false),
             Field::new("timestamp_ms", DataType::Int64, false),
             Field::new("manifest_list", DataType::Utf8, false),
-            Field::new("schema_id", DataType::Int32, false),
+            Field::new("schema_id", DataType::Int32, true),
         ]));
 
         let mut snapshot_id = Int64Builder::new();
@@ -75,7 +75,7 @@ impl TableFunc for IcebergSnapshots {
             snapshot_id.append_value(snapshot.snapshot_id);
             timestamp_ms.append_value(snapshot.timestamp_ms);
             manifest_list.append_value(&snapshot.manifest_list);
-            schema_id.append_value(snapshot.schema_id);
+            schema_id.append_option(snapshot.schema_id);
         }
 
         let batch = RecordBatch::try_new(
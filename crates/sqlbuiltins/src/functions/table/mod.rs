@@ -13,9 +13,10 @@ mod mysql;
 mod object_store;
 mod postgres;
 mod snowflake;
+mod sqlite;
 mod sqlserver;
 mod system;
-mod virtual_listing;
+pub(crate) mod virtual_listing;
 
 use ::object_store::aws::AmazonS3ConfigKey;
 use ::object_store::azure::AzureConfigKey;
@@ -41,9 +42,10 @@ use self::iceberg::{data_files::IcebergDataFiles, scan::IcebergScan, snapshots::
 use self::lance::LanceScan;
 use self::mongodb::ReadMongoDb;
 use self::mysql::ReadMysql;
-use self::object_store::{READ_CSV, READ_JSON, READ_PARQUET};
+use self::object_store::{READ_ARROW, READ_AVRO, READ_CSV, READ_JSON, READ_ORC, READ_PARQUET};
 use self::postgres::ReadPostgres;
 use self::snowflake::ReadSnowflake;
+use self::sqlite::ReadSqlite;
 use self::sqlserver::ReadSqlServer;
 use self::system::cache_external_tables::CacheExternalDatabaseTables;
 use self::virtual_listing::{ListColumns, ListSchemas, ListTables};
@@ -63,6 +65,15 @@ pub trait TableFunc: BuiltinFunction {
         _parent: RuntimePreference,
     ) -> Result<RuntimePreference>;
 
+    /// Whether the function reads data outside of the catalog, e.g. from an
+    /// external database or an object store.
+    ///
+    /// Such functions can only be called by superusers, since there's no
+    /// catalog object to grant privileges on.
+    fn reads_external_data(&self) -> bool {
+        true
+    }
+
     /// Return a table provider using the provided args.
     async fn create_provider(
         &self,
@@ -89,10 +100,14 @@ impl BuiltinTableFuncs {
             Arc::new(ReadClickhouse),
             Arc::new(ReadSqlServer),
             Arc::new(ReadCassandra),
+            Arc::new(ReadSqlite),
             // Object store
             Arc::new(READ_PARQUET),
             Arc::new(READ_CSV),
             Arc::new(READ_JSON),
+            Arc::new(READ_ARROW),
+            Arc::new(READ_AVRO),
+            Arc::new(READ_ORC),
             Arc::new(BsonScan),
             // Data lakes
             Arc::new(DeltaScan),
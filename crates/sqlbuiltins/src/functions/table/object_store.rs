@@ -4,6 +4,8 @@ use std::{sync::Arc, vec};
 
 use async_trait::async_trait;
 use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::datasource::file_format::arrow::ArrowFormat;
+use datafusion::datasource::file_format::avro::AvroFormat;
 use datafusion::datasource::file_format::csv::CsvFormat;
 use datafusion::datasource::file_format::file_compression_type::FileCompressionType;
 use datafusion::datasource::file_format::json::JsonFormat;
@@ -20,6 +22,8 @@ use datasources::object_store::gcs::GcsStoreAccess;
 use datasources::object_store::generic::GenericStoreAccess;
 use datasources::object_store::http::HttpStoreAccess;
 use datasources::object_store::local::LocalStoreAccess;
+use datasources::object_store::orc::OrcFormat;
+use datasources::object_store::partition::PartitionColumns;
 use datasources::object_store::s3::S3StoreAccess;
 use datasources::object_store::{MultiSourceTableProvider, ObjStoreAccess};
 
@@ -102,6 +106,63 @@ pub const READ_JSON: ObjScanTableFunc<JsonOptionsReader> = ObjScanTableFunc {
     phantom: PhantomData,
 };
 
+#[derive(Debug, Clone, Copy)]
+pub struct ArrowOptionsReader;
+
+impl OptionReader for ArrowOptionsReader {
+    type Format = ArrowFormat;
+
+    fn read_options(_opts: &HashMap<String, FuncParamValue>) -> Result<Self::Format> {
+        Ok(ArrowFormat)
+    }
+}
+
+pub const READ_ARROW: ObjScanTableFunc<ArrowOptionsReader> = ObjScanTableFunc {
+    name: "read_ipc",
+    aliases: &["read_arrow", "ipc_scan"],
+    description: "Returns a table by scanning the given Arrow IPC file(s).",
+    example: "SELECT * FROM read_ipc('./my_data.arrow')",
+    phantom: PhantomData,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct AvroOptionsReader;
+
+impl OptionReader for AvroOptionsReader {
+    type Format = AvroFormat;
+
+    fn read_options(_opts: &HashMap<String, FuncParamValue>) -> Result<Self::Format> {
+        Ok(AvroFormat)
+    }
+}
+
+pub const READ_AVRO: ObjScanTableFunc<AvroOptionsReader> = ObjScanTableFunc {
+    name: "read_avro",
+    aliases: &["avro_scan"],
+    description: "Returns a table by scanning the given Avro file(s).",
+    example: "SELECT * FROM read_avro('./my_data.avro')",
+    phantom: PhantomData,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct OrcOptionsReader;
+
+impl OptionReader for OrcOptionsReader {
+    type Format = OrcFormat;
+
+    fn read_options(_opts: &HashMap<String, FuncParamValue>) -> Result<Self::Format> {
+        Ok(OrcFormat)
+    }
+}
+
+pub const READ_ORC: ObjScanTableFunc<OrcOptionsReader> = ObjScanTableFunc {
+    name: "read_orc",
+    aliases: &["orc_scan"],
+    description: "Returns a table by scanning the given ORC file(s).",
+    example: "SELECT * FROM read_orc('./my_data.orc')",
+    phantom: PhantomData,
+};
+
 pub trait OptionReader: Sync + Send + Sized {
     type Format: FileFormat + WithCompression + 'static;
 
@@ -126,6 +187,34 @@ impl WithCompression for JsonFormat {
     }
 }
 
+impl WithCompression for ArrowFormat {
+    fn with_compression(self, _compression: FileCompressionType) -> Result<Self> {
+        // Arrow IPC files handle compression internally on a per buffer
+        // basis.
+        Err(ExtensionError::String(
+            "compression not supported for arrow".to_string(),
+        ))
+    }
+}
+
+impl WithCompression for AvroFormat {
+    fn with_compression(self, _compression: FileCompressionType) -> Result<Self> {
+        // Avro files handle compression internally on a per block basis.
+        Err(ExtensionError::String(
+            "compression not supported for avro".to_string(),
+        ))
+    }
+}
+
+impl WithCompression for OrcFormat {
+    fn with_compression(self, _compression: FileCompressionType) -> Result<Self> {
+        // ORC files handle compression internally on a per stream basis.
+        Err(ExtensionError::String(
+            "compression not supported for orc".to_string(),
+        ))
+    }
+}
+
 impl WithCompression for ParquetFormat {
     fn with_compression(self, _compression: FileCompressionType) -> Result<Self> {
         // TODO: Snappy is a common compression algo to use parquet. If we want
@@ -269,6 +358,21 @@ impl<Opts: OptionReader> TableFunc for ObjScanTableFunc<Opts> {
             format = format.with_compression(compression)?;
         }
 
+        // Hive style partition columns, either a comma separated string or a
+        // list of column names. Inferred from the paths if not provided.
+        let partition_columns = match opts.remove("partition_columns") {
+            Some(cols) if cols.is_valid::<String>() => {
+                let cols: String = cols.try_into()?;
+                cols.split(',')
+                    .map(|col| col.trim().to_string())
+                    .filter(|col| !col.is_empty())
+                    .collect()
+            }
+            Some(cols) => cols.try_into()?,
+            None => Vec::new(),
+        };
+        let partition_columns = PartitionColumns::from(partition_columns);
+
         // Optimize creating a table provider for objects by clubbing the same
         // store together.
         let mut fn_registry: HashMap<
@@ -295,9 +399,13 @@ impl<Opts: OptionReader> TableFunc for ObjScanTableFunc<Opts> {
         let table = fn_registry
             .into_values()
             .map(|(access, locations)| {
-                let provider =
-                    get_table_provider(ctx, format.clone(), access, locations.into_iter());
-                provider
+                get_table_provider(
+                    ctx,
+                    format.clone(),
+                    access,
+                    locations.into_iter(),
+                    &partition_columns,
+                )
             })
             .collect::<futures::stream::FuturesUnordered<_>>()
             .try_collect::<Vec<_>>()
@@ -317,10 +425,11 @@ async fn get_table_provider(
     ft: Arc<dyn FileFormat>,
     access: Arc<dyn ObjStoreAccess>,
     locations: impl Iterator<Item = DatasourceUrl>,
+    partition_columns: &PartitionColumns,
 ) -> Result<Arc<dyn TableProvider>> {
     let state = ctx.get_session_state();
     let prov = access
-        .create_table_provider(&state, ft, locations.collect())
+        .create_table_provider(&state, ft, locations.collect(), partition_columns)
         .await
         .map_err(|e| ExtensionError::Access(Box::new(e)))?;
 
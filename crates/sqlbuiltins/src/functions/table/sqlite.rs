@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::datasource::TableProvider;
+use datafusion_ext::errors::{ExtensionError, Result};
+use datafusion_ext::functions::{FuncParamValue, TableFuncContextProvider};
+use datasources::common::url::{DatasourceUrl, DatasourceUrlType};
+use datasources::sqlite::{SqliteAccess, SqliteTableProvider};
+use protogen::metastore::types::catalog::{FunctionType, RuntimePreference};
+use protogen::metastore::types::options::StorageOptions;
+
+use super::{table_location_and_opts, TableFunc};
+use crate::functions::ConstBuiltinFunction;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ReadSqlite;
+
+impl ConstBuiltinFunction for ReadSqlite {
+    const NAME: &'static str = "read_sqlite";
+    const DESCRIPTION: &'static str = "Read a table from a SQLite database file";
+    const EXAMPLE: &'static str = "SELECT * FROM read_sqlite('./path/to/db.sqlite', 'table')";
+    const FUNCTION_TYPE: FunctionType = FunctionType::TableReturning;
+}
+
+#[async_trait]
+impl TableFunc for ReadSqlite {
+    fn detect_runtime(
+        &self,
+        args: &[FuncParamValue],
+        _parent: RuntimePreference,
+    ) -> Result<RuntimePreference> {
+        let url: String = args
+            .first()
+            .ok_or_else(|| ExtensionError::ExpectedIndexedArgument {
+                index: 0,
+                what: "location of the database".to_string(),
+            })?
+            .clone()
+            .try_into()?;
+        let source_url =
+            DatasourceUrl::try_new(url).map_err(|e| ExtensionError::Access(Box::new(e)))?;
+
+        Ok(match source_url.datasource_url_type() {
+            DatasourceUrlType::File => RuntimePreference::Local,
+            _ => RuntimePreference::Remote,
+        })
+    }
+
+    async fn create_provider(
+        &self,
+        ctx: &dyn TableFuncContextProvider,
+        mut args: Vec<FuncParamValue>,
+        mut opts: HashMap<String, FuncParamValue>,
+    ) -> Result<Arc<dyn TableProvider>> {
+        if !(2..=3).contains(&args.len()) {
+            return Err(ExtensionError::InvalidNumArgs);
+        }
+
+        // Pull out the table name, leaving the location and optional
+        // credentials.
+        let table: String = args.remove(1).try_into()?;
+
+        let location: String = args[0].clone().try_into()?;
+        let source_url =
+            DatasourceUrl::try_new(&location).map_err(|e| ExtensionError::Access(Box::new(e)))?;
+
+        // Publicly accessible databases can be read over http without
+        // credentials.
+        let storage_options =
+            if source_url.datasource_url_type() == DatasourceUrlType::Http && args.len() == 1 {
+                StorageOptions::default()
+            } else {
+                let (_, storage_options) = table_location_and_opts(ctx, args, &mut opts)?;
+                storage_options
+            };
+
+        let access = SqliteAccess {
+            location,
+            storage_options,
+        };
+        let prov = SqliteTableProvider::try_new(access, table)
+            .await
+            .map_err(|e| ExtensionError::Access(Box::new(e)))?;
+
+        Ok(Arc::new(prov))
+    }
+}
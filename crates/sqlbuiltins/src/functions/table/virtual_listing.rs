@@ -17,11 +17,13 @@ use datasources::mongodb::MongoDbAccessor;
 use datasources::mysql::MysqlAccessor;
 use datasources::postgres::PostgresAccess;
 use datasources::snowflake::{SnowflakeAccessor, SnowflakeDbConnection};
+use datasources::sqlite::SqliteAccess;
 use datasources::sqlserver::SqlServerAccess;
 use protogen::metastore::types::catalog::{FunctionType, RuntimePreference};
 use protogen::metastore::types::options::{
     DatabaseOptions, DatabaseOptionsBigQuery, DatabaseOptionsMongoDb, DatabaseOptionsMysql,
     DatabaseOptionsPostgres, DatabaseOptionsSnowflake, DatabaseOptionsSqlServer,
+    DatabaseOptionsSqlite,
 };
 
 use super::TableFunc;
@@ -272,7 +274,7 @@ pub(crate) async fn get_virtual_lister_for_db<'a, 'b: 'a, 'c: 'b>(
 /// Gets a lister for an external database using the provided options.
 ///
 /// Will panic if attempting to get a lister for an internal database.
-pub(crate) async fn get_virtual_lister_for_external_db(
+pub async fn get_virtual_lister_for_external_db(
     opts: &DatabaseOptions,
 ) -> Result<Box<dyn VirtualLister>> {
     let lister: Box<dyn VirtualLister> = match opts {
@@ -341,6 +343,17 @@ pub(crate) async fn get_virtual_lister_for_external_db(
             let state = access.connect().await.map_err(ExtensionError::access)?;
             Box::new(state)
         }
+        DatabaseOptions::Sqlite(DatabaseOptionsSqlite {
+            location,
+            storage_options,
+        }) => {
+            let access = SqliteAccess {
+                location: location.clone(),
+                storage_options: storage_options.clone(),
+            };
+            let state = access.connect().await.map_err(ExtensionError::access)?;
+            Box::new(state)
+        }
         DatabaseOptions::Clickhouse(_) => {
             return Err(ExtensionError::Unimplemented(
                 "Clickhouse information listing",
@@ -82,8 +82,8 @@ pub fn validate_database_creds_support(database: &str, creds: &str) -> Result<()
         (database, creds),
         // Google cloud
         (DatabaseOptions::BIGQUERY, CredentialsOptions::GCP) |
-        // Delta
-        (DatabaseOptions::DELTA, CredentialsOptions::GCP | CredentialsOptions::AWS | CredentialsOptions::AZURE)
+        // Delta & Sqlite
+        (DatabaseOptions::DELTA | DatabaseOptions::SQLITE, CredentialsOptions::GCP | CredentialsOptions::AWS | CredentialsOptions::AZURE)
     ) {
         Ok(())
     } else {
@@ -107,8 +107,8 @@ pub fn validate_table_creds_support(table: &str, creds: &str) -> Result<()> {
         (TableOptions::S3_STORAGE, CredentialsOptions::AWS) |
         // Azure
         (TableOptions::AZURE, CredentialsOptions::AZURE) |
-        // Delta & Iceberg & Lance
-        (TableOptions::DELTA | TableOptions::ICEBERG | TableOptions::LANCE, CredentialsOptions::GCP | CredentialsOptions::AWS | CredentialsOptions::AZURE )
+        // Delta & Iceberg & Lance & Sqlite
+        (TableOptions::DELTA | TableOptions::ICEBERG | TableOptions::LANCE | TableOptions::SQLITE, CredentialsOptions::GCP | CredentialsOptions::AWS | CredentialsOptions::AZURE )
     ) {
         Ok(())
     } else {
@@ -0,0 +1,149 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::physical_plan::{RecordBatchStream, SendableRecordBatchStream};
+use futures::future::BoxFuture;
+use futures::{Future, FutureExt, Stream, StreamExt};
+use tokio::sync::watch;
+
+use crate::errors::ExecError;
+
+/// Handle for canceling statements executing in a session.
+///
+/// Canceling only affects statements that are executing at the time of the
+/// cancel. Statements started afterwards run as normal.
+#[derive(Debug, Clone)]
+pub struct CancelHandle {
+    tx: Arc<watch::Sender<()>>,
+}
+
+impl CancelHandle {
+    pub fn new() -> Self {
+        let (tx, _) = watch::channel(());
+        CancelHandle { tx: Arc::new(tx) }
+    }
+
+    /// Cancel all statements currently executing.
+    pub fn cancel(&self) {
+        self.tx.send_replace(());
+    }
+
+    /// Returns a future that completes on the next call to `cancel`.
+    pub(crate) fn canceled(&self) -> BoxFuture<'static, ()> {
+        let mut rx = self.tx.subscribe();
+        async move {
+            if rx.changed().await.is_err() {
+                // Handle dropped, nothing can cancel us anymore.
+                futures::future::pending::<()>().await;
+            }
+        }
+        .boxed()
+    }
+}
+
+impl Default for CancelHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Wrap a stream so that it's stopped on cancel.
+pub fn with_cancel(
+    stream: SendableRecordBatchStream,
+    handle: &CancelHandle,
+) -> SendableRecordBatchStream {
+    Box::pin(CancelableStream {
+        schema: stream.schema(),
+        stream: Some(stream),
+        canceled: handle.canceled(),
+    })
+}
+
+/// Stream adapter that errors once the statement is canceled.
+///
+/// Similar to the statement timeout, the inner stream is dropped on cancel.
+struct CancelableStream {
+    schema: SchemaRef,
+    /// The stream being wrapped. Set to `None` after being canceled.
+    stream: Option<SendableRecordBatchStream>,
+    canceled: BoxFuture<'static, ()>,
+}
+
+impl RecordBatchStream for CancelableStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Stream for CancelableStream {
+    type Item = DataFusionResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.stream.is_none() {
+            return Poll::Ready(None);
+        }
+
+        if self.canceled.as_mut().poll(cx).is_ready() {
+            self.stream = None;
+            return Poll::Ready(Some(Err(DataFusionError::External(Box::new(
+                ExecError::QueryCanceled,
+            )))));
+        }
+
+        match self.stream.as_mut() {
+            Some(stream) => stream.poll_next_unpin(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::datatypes::Schema;
+    use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+
+    fn pending_stream() -> SendableRecordBatchStream {
+        Box::pin(RecordBatchStreamAdapter::new(
+            Arc::new(Schema::empty()),
+            futures::stream::pending::<DataFusionResult<RecordBatch>>(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn cancel_running() {
+        let handle = CancelHandle::new();
+        let mut stream = with_cancel(pending_stream(), &handle);
+
+        handle.cancel();
+
+        let err = ExecError::from(stream.next().await.unwrap().unwrap_err());
+        assert!(matches!(
+            err.as_query_canceled(),
+            Some(ExecError::QueryCanceled)
+        ));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn cancel_before_start() {
+        let handle = CancelHandle::new();
+        handle.cancel();
+
+        // Streams created after the cancel shouldn't be affected.
+        let schema = Arc::new(Schema::empty());
+        let batch = RecordBatch::new_empty(schema.clone());
+        let stream = Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::iter(vec![DataFusionResult::Ok(batch)]),
+        ));
+        let mut stream = with_cancel(stream, &handle);
+
+        assert!(stream.next().await.unwrap().is_ok());
+        assert!(stream.next().await.is_none());
+    }
+}
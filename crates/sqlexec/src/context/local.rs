@@ -1,3 +1,4 @@
+use crate::cursor::Cursor;
 use crate::distexec::scheduler::Scheduler;
 use crate::environment::EnvironmentReader;
 use crate::errors::{internal, ExecError, Result};
@@ -5,9 +6,12 @@ use crate::parser::StatementWithExtensions;
 use crate::planner::logical_plan::*;
 use crate::planner::session_planner::SessionPlanner;
 use crate::remote::client::{RemoteClient, RemoteSessionClient};
+use crate::session_registry::SessionRegistry;
 use catalog::mutator::CatalogMutator;
 use catalog::session_catalog::SessionCatalog;
-use datafusion::arrow::datatypes::{DataType, Field as ArrowField, Schema as ArrowSchema};
+use datafusion::arrow::datatypes::{
+    DataType, Field as ArrowField, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef,
+};
 use datafusion::common::SchemaReference;
 use datafusion::execution::context::{
     SessionConfig, SessionContext as DfSessionContext, SessionState, TaskContext,
@@ -17,8 +21,10 @@ use datafusion::sql::TableReference;
 use datafusion_ext::session_metrics::SessionMetricsHandler;
 use datafusion_ext::vars::SessionVars;
 use datasources::native::access::NativeTableStorage;
+use datasources::native::errors::NativeError;
 use pgrepr::format::Format;
-use pgrepr::types::arrow_to_pg_type;
+use pgrepr::types::{arrow_to_pg_type, pg_to_arrow_type};
+use protogen::metastore::strategy::ResolveErrorStrategy;
 
 use datafusion::variable::VarType;
 use protogen::rpcsrv::types::service::{
@@ -55,6 +61,11 @@ pub struct LocalSessionContext {
     prepared: HashMap<String, PreparedStatement>,
     /// Bound portals.
     portals: HashMap<String, Portal>,
+    /// Results of portals that were suspended during execution, keyed by
+    /// portal name.
+    suspended_portals: HashMap<String, Cursor>,
+    /// Cursors created with `DECLARE`.
+    cursors: HashMap<String, DeclaredCursor>,
     /// Handler to push metrics into tracker.
     metrics_handler: SessionMetricsHandler,
     /// Datafusion session context used for planning and execution.
@@ -76,6 +87,7 @@ impl LocalSessionContext {
         metrics_handler: SessionMetricsHandler,
         spill_path: Option<PathBuf>,
         task_scheduler: Scheduler,
+        session_registry: Arc<SessionRegistry>,
     ) -> Result<LocalSessionContext> {
         let database_id = vars.database_id();
         let runtime = new_datafusion_runtime_env(&vars, &catalog, spill_path)?;
@@ -87,7 +99,8 @@ impl LocalSessionContext {
         conf = conf
             .with_extension(Arc::new(catalog_mutator))
             .with_extension(Arc::new(native_tables.clone()))
-            .with_extension(Arc::new(catalog.get_temp_catalog().clone()));
+            .with_extension(Arc::new(catalog.get_temp_catalog().clone()))
+            .with_extension(session_registry);
 
         let state = SessionState::new_with_config_rt(conf, Arc::new(runtime))
             .add_physical_optimizer_rule(Arc::new(RuntimeGroupPullUp {}));
@@ -102,6 +115,8 @@ impl LocalSessionContext {
             tables: native_tables,
             prepared: HashMap::new(),
             portals: HashMap::new(),
+            suspended_portals: HashMap::new(),
+            cursors: HashMap::new(),
             metrics_handler,
             df_ctx,
             env_reader: None,
@@ -148,7 +163,7 @@ impl LocalSessionContext {
 
         self.exec_client = Some(client.clone());
         self.df_ctx = df_ctx;
-        self.catalog = catalog;
+        self.catalog = catalog.with_bootstrap_user(self.catalog.bootstrap_user());
 
         Ok(())
     }
@@ -214,8 +229,78 @@ impl LocalSessionContext {
         &mut self.catalog
     }
 
+    /// Start buffering catalog changes and native table writes.
+    pub(crate) fn begin_transaction(&self) {
+        self.catalog_mutator().begin_transaction();
+        self.tables.begin_transaction();
+    }
+
+    /// Commit buffered native table writes and catalog changes.
+    ///
+    /// Native table writes are checked for conflicts first. Catalog changes
+    /// are then applied all at once, and the native table writes are only
+    /// written out if that succeeds. If anything conflicts before that point,
+    /// the whole transaction is rolled back.
+    pub(crate) async fn commit_transaction(&mut self) -> Result<()> {
+        if let Err(e) = self.tables.check_transaction_conflicts().await {
+            self.rollback_transaction().await?;
+            return Err(native_commit_error(e));
+        }
+
+        let mutator = self.catalog_mutator();
+        match mutator.commit_transaction().await {
+            Ok(Some(state)) => self.catalog.swap_state(state),
+            Ok(None) => (),
+            Err(e) => {
+                self.tables.rollback_transaction().await;
+                self.reset_catalog_state().await?;
+                return Err(match e.strategy {
+                    Some(ResolveErrorStrategy::FetchCatalogAndRetry) => {
+                        ExecError::TransactionConflict(e.msg)
+                    }
+                    _ => e.into(),
+                });
+            }
+        }
+
+        self.tables
+            .commit_transaction()
+            .await
+            .map_err(native_commit_error)
+    }
+
+    /// Discard buffered native table writes and catalog changes made during
+    /// the transaction.
+    pub(crate) async fn rollback_transaction(&mut self) -> Result<()> {
+        self.tables.rollback_transaction().await;
+        if let Some(state) = self.catalog_mutator().rollback_transaction().await? {
+            self.catalog.swap_state(state);
+        }
+        Ok(())
+    }
+
+    /// Swap out the session's catalog for the latest cached catalog,
+    /// discarding anything only this session could see.
+    async fn reset_catalog_state(&mut self) -> Result<()> {
+        if let Some(client) = self.catalog_mutator().get_metastore_client() {
+            let state = client.get_cached_state().await?;
+            self.catalog.swap_state(state);
+        }
+        Ok(())
+    }
+
     pub async fn maybe_refresh_state(&mut self) -> Result<()> {
         let mutator = self.catalog_mutator();
+
+        // Once an open transaction has changed the catalog, the session sees
+        // the transaction's view of the catalog until the transaction ends.
+        if let Some(state) = mutator.transaction_state() {
+            if !Arc::ptr_eq(self.catalog.get_state(), &state) {
+                self.catalog.swap_state(state);
+            }
+            return Ok(());
+        }
+
         let client = mutator.get_metastore_client();
         self.catalog
             .maybe_refresh_state(client, self.get_session_vars().force_catalog_refresh())
@@ -228,7 +313,7 @@ impl LocalSessionContext {
         &mut self,
         name: String,
         stmt: Option<StatementWithExtensions>,
-        _params: Vec<i32>, // TODO: We can use these for providing types for parameters.
+        params: Vec<i32>,
     ) -> Result<()> {
         // Refresh the cached catalog state if necessary
         self.maybe_refresh_state().await?;
@@ -243,7 +328,7 @@ impl LocalSessionContext {
             ));
         }
 
-        let stmt = PreparedStatement::build(stmt, self).await?;
+        let stmt = PreparedStatement::build(stmt, &params, self).await?;
         self.prepared.insert(name, stmt);
 
         Ok(())
@@ -289,6 +374,7 @@ impl LocalSessionContext {
             stmt,
             result_formats,
         };
+        self.suspended_portals.remove(&portal_name);
         self.portals.insert(portal_name, portal);
 
         Ok(())
@@ -318,6 +404,77 @@ impl LocalSessionContext {
     /// Remove a portal.
     pub fn remove_portal(&mut self, name: &str) {
         self.portals.remove(name);
+        self.suspended_portals.remove(name);
+    }
+
+    /// Remove all portals, including suspended ones.
+    pub fn close_all_portals(&mut self) {
+        self.portals.clear();
+        self.suspended_portals.clear();
+    }
+
+    /// Keep the remaining results of a portal so that executing it again
+    /// continues where it left off.
+    pub fn suspend_portal(&mut self, name: &str, cursor: Cursor) -> Result<()> {
+        if !self.portals.contains_key(name) {
+            return Err(ExecError::UnknownPortal(name.to_string()));
+        }
+        self.suspended_portals.insert(name.to_string(), cursor);
+        Ok(())
+    }
+
+    /// Take the remaining results of a suspended portal, if the portal was
+    /// suspended.
+    pub fn take_suspended_portal(&mut self, name: &str) -> Option<Cursor> {
+        self.suspended_portals.remove(name)
+    }
+
+    /// Add a cursor with the given name.
+    pub fn declare_cursor(&mut self, name: String, cursor: Cursor, hold: bool) -> Result<()> {
+        if self.cursors.contains_key(&name) {
+            return Err(ExecError::DuplicateCursor(name));
+        }
+        self.cursors.insert(name, DeclaredCursor { cursor, hold });
+        Ok(())
+    }
+
+    /// Get a cursor by name.
+    pub fn get_cursor_mut(&mut self, name: &str) -> Result<&mut Cursor> {
+        self.cursors
+            .get_mut(name)
+            .map(|declared| &mut declared.cursor)
+            .ok_or_else(|| ExecError::UnknownCursor(name.to_string()))
+    }
+
+    /// Get the schema of the rows returned by a cursor, if the cursor exists.
+    pub fn get_cursor_schema(&self, name: &str) -> Option<ArrowSchemaRef> {
+        self.cursors
+            .get(name)
+            .map(|declared| declared.cursor.schema())
+    }
+
+    /// Close a cursor.
+    pub fn close_cursor(&mut self, name: &str) -> Result<()> {
+        match self.cursors.remove(name) {
+            Some(_) => Ok(()),
+            None => Err(ExecError::UnknownCursor(name.to_string())),
+        }
+    }
+
+    /// Close all cursors.
+    pub fn close_all_cursors(&mut self) {
+        self.cursors.clear();
+    }
+
+    /// Close everything that only lives for the duration of a transaction.
+    ///
+    /// Cursors not declared `WITH HOLD` are closed, and suspended portals are
+    /// removed since their results can't be continued.
+    pub(crate) fn close_transaction_cursors(&mut self) {
+        self.cursors.retain(|_, declared| declared.hold);
+        for (name, _) in self.suspended_portals.drain() {
+            self.portals.remove(&name);
+        }
     }
 
     /// Get a datafusion task context to use for physical plan execution.
@@ -401,13 +558,30 @@ pub struct PreparedStatement {
 impl PreparedStatement {
     /// Create and plan a new prepared statement.
     // TODO: Not sure if we want to delay the planning portion.
+    ///
+    /// `params` are the parameter type oids provided by the client. An oid of
+    /// 0 leaves the type of that parameter unspecified.
     async fn build(
         mut stmt: Option<StatementWithExtensions>,
+        params: &[i32],
         ctx: &LocalSessionContext,
     ) -> Result<Self> {
         if let Some(inner) = stmt.take() {
+            let client_types: Vec<Option<PgType>> = params
+                .iter()
+                .map(|oid| PgType::from_oid(*oid as u32))
+                .collect();
+
+            // Datafusion only accepts a list of types for parameters, so only
+            // the leading types that we know how to map are provided to the
+            // planner. Everything else is inferred.
+            let planner_types: Vec<DataType> = client_types
+                .iter()
+                .map_while(|typ| typ.as_ref().and_then(pg_to_arrow_type))
+                .collect();
+
             // Go ahead and plan using the session context.
-            let planner = SessionPlanner::new(ctx);
+            let planner = SessionPlanner::new(ctx).with_param_types(planner_types);
             let plan = planner.plan_ast(inner.clone()).await?;
             let schema = plan.output_schema();
             let pg_types = match &schema {
@@ -421,16 +595,7 @@ impl PreparedStatement {
                 None => Vec::new(),
             };
 
-            // Convert inferred arrow types for parameters into their associated
-            // pg type.
-            let parameter_types: HashMap<_, _> = plan
-                .get_parameter_types()?
-                .into_iter()
-                .map(|(id, arrow_type)| {
-                    let typ = arrow_type.map(|typ| (arrow_to_pg_type(&typ, None), typ));
-                    (id, typ)
-                })
-                .collect();
+            let parameter_types = merge_parameter_types(plan.get_parameter_types()?, client_types);
 
             Ok(PreparedStatement {
                 stmt: Some(inner),
@@ -461,11 +626,78 @@ impl PreparedStatement {
         })
     }
 
+    pub fn output_schema(&self) -> Option<&ArrowSchema> {
+        self.output_schema.as_ref()
+    }
+
     /// Returns the type of the input parameters. Input paramets are keyed as
     /// "$n" starting at "$1".
     pub fn input_paramaters(&self) -> Option<&HashMap<String, Option<(PgType, DataType)>>> {
         self.parameter_types.as_ref()
     }
+
+    /// Returns the types of the input parameters ordered by parameter index.
+    ///
+    /// Parameters without a known type are omitted.
+    pub fn ordered_input_parameters(&self) -> Vec<(PgType, DataType)> {
+        let params = match &self.parameter_types {
+            Some(params) => params,
+            None => return Vec::new(),
+        };
+        (1..=params.len())
+            .filter_map(|idx| params.get(&format!("${idx}")).cloned().flatten())
+            .collect()
+    }
+}
+
+/// Convert an error from committing native table writes.
+fn native_commit_error(e: NativeError) -> ExecError {
+    match e {
+        NativeError::TransactionConflict(msg) => ExecError::TransactionConflict(msg),
+        other => other.into(),
+    }
+}
+
+/// Merge the parameter types inferred during planning with the types provided
+/// by the client.
+///
+/// Parameters are numbered up to the highest of the placeholders found in the
+/// plan and the number of types sent by the client. The inferred arrow type
+/// takes precedence, falling back to the client's type, then to text. The
+/// client's pg type is always reported back as-is so that it can decode the
+/// values it sends.
+fn merge_parameter_types(
+    inferred: HashMap<String, Option<DataType>>,
+    client_types: Vec<Option<PgType>>,
+) -> HashMap<String, Option<(PgType, DataType)>> {
+    let max_inferred = inferred
+        .keys()
+        .filter_map(|id| id.strip_prefix('$')?.parse::<usize>().ok())
+        .max()
+        .unwrap_or(0);
+    let count = max_inferred.max(client_types.len());
+
+    (1..=count)
+        .map(|idx| {
+            let id = format!("${idx}");
+            let client_type = client_types.get(idx - 1).cloned().flatten();
+            let arrow_type = inferred
+                .get(&id)
+                .cloned()
+                .flatten()
+                .or_else(|| client_type.as_ref().and_then(pg_to_arrow_type))
+                .unwrap_or(DataType::Utf8);
+            let pg_type = client_type.unwrap_or_else(|| arrow_to_pg_type(&arrow_type, None));
+            (id, Some((pg_type, arrow_type)))
+        })
+        .collect()
+}
+
+/// A cursor created with `DECLARE`.
+struct DeclaredCursor {
+    cursor: Cursor,
+    /// Keep the cursor open after the transaction ends.
+    hold: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -491,6 +723,9 @@ impl Portal {
     pub fn input_paramaters(&self) -> Option<&HashMap<String, Option<(PgType, DataType)>>> {
         self.stmt.input_paramaters()
     }
+    pub fn ordered_input_parameters(&self) -> Vec<(PgType, DataType)> {
+        self.stmt.ordered_input_parameters()
+    }
     pub fn output_schema(&self) -> Option<&ArrowSchema> {
         self.stmt.output_schema.as_ref()
     }
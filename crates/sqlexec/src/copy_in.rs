@@ -0,0 +1,184 @@
+//! Support for `COPY <table> FROM STDIN`.
+//!
+//! The client sends the data over the connection, which gets decoded into
+//! batches by the protocol layer and sent through a `CopyInSink`. A background
+//! task inserts the batches into the target table as they arrive.
+use std::sync::Arc;
+
+use datafusion::arrow::array::new_null_array;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::TableProvider;
+use datafusion::execution::TaskContext;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::errors::{ExecError, Result};
+use crate::planner::physical_plan::copy_from::CopyFromStdinExec;
+use crate::planner::physical_plan::get_count_from_batch;
+use crate::planner::physical_plan::insert::InsertExec;
+
+/// Number of decoded batches that may be buffered before the client is made to
+/// wait on the insert.
+const COPY_IN_BUFFER: usize = 4;
+
+/// Format of the data sent by the client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CopyInFormat {
+    /// Postgres' text format. Rows are separated by newlines, and values are
+    /// escaped with backslashes.
+    Text { delimiter: u8, null: String },
+    /// Comma separated values.
+    Csv {
+        delimiter: u8,
+        quote: u8,
+        header: bool,
+        null: String,
+    },
+    /// Postgres' binary format.
+    Binary,
+}
+
+impl CopyInFormat {
+    pub const TEXT: &'static str = "text";
+    pub const CSV: &'static str = "csv";
+    pub const BINARY: &'static str = "binary";
+
+    pub fn is_binary(&self) -> bool {
+        matches!(self, CopyInFormat::Binary)
+    }
+}
+
+/// Destination for batches decoded from the client.
+///
+/// Dropping the sink without calling `finish` aborts the copy, and nothing is
+/// inserted.
+pub struct CopyInSink {
+    /// Schema of the data sent by the client.
+    schema: SchemaRef,
+    /// Schema of the table being copied into.
+    table_schema: SchemaRef,
+    /// Table columns the client sends data for, if not all of them.
+    columns: Option<Vec<usize>>,
+    format: CopyInFormat,
+    /// Sender for batches to insert. Dropped to signal the end of the data.
+    batches: Option<mpsc::Sender<RecordBatch>>,
+    /// Background insert, returning the number of rows inserted.
+    insert: Option<JoinHandle<Result<u64>>>,
+}
+
+impl CopyInSink {
+    /// Start inserting into the table, returning the sink that feeds it.
+    ///
+    /// If `columns` is provided, the client only sends data for those table
+    /// columns and the rest are filled with nulls.
+    pub(crate) fn start(
+        table: Arc<dyn TableProvider>,
+        columns: Option<Vec<usize>>,
+        format: CopyInFormat,
+        context: Arc<TaskContext>,
+    ) -> Result<CopyInSink> {
+        let table_schema = table.schema();
+        let schema = match &columns {
+            Some(columns) => Arc::new(table_schema.project(columns)?),
+            None => table_schema.clone(),
+        };
+        let (tx, rx) = mpsc::channel(COPY_IN_BUFFER);
+        let source = Arc::new(CopyFromStdinExec::new(table_schema.clone(), rx));
+
+        let insert = tokio::spawn(async move {
+            let batch = InsertExec::do_insert(table, source, false, context).await?;
+            Ok(get_count_from_batch(&batch).unwrap_or_default())
+        });
+
+        Ok(CopyInSink {
+            schema,
+            table_schema,
+            columns,
+            format,
+            batches: Some(tx),
+            insert: Some(insert),
+        })
+    }
+
+    /// Schema of the data the client sends.
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    pub fn format(&self) -> &CopyInFormat {
+        &self.format
+    }
+
+    /// Send a batch to be inserted.
+    ///
+    /// Errors if the insert has already failed.
+    pub async fn send(&mut self, batch: RecordBatch) -> Result<()> {
+        let batch = self.fill_missing_columns(batch)?;
+        let batches = self
+            .batches
+            .as_ref()
+            .ok_or_else(|| ExecError::String("copy already finished".to_string()))?;
+
+        if batches.send(batch).await.is_err() {
+            // The receiver is only dropped once the insert stops, get the
+            // actual error from the task.
+            self.batches = None;
+            return Err(self.wait().await.err().unwrap_or_else(|| {
+                ExecError::String("copy insert stopped before receiving all data".to_string())
+            }));
+        }
+        Ok(())
+    }
+
+    /// Expand a batch of client data to the table's schema, filling columns
+    /// the client didn't send data for with nulls.
+    fn fill_missing_columns(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        let columns = match &self.columns {
+            Some(columns) => columns,
+            None => return Ok(batch),
+        };
+
+        let arrays = self
+            .table_schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let sent = columns.iter().position(|col| *col == idx);
+                match sent {
+                    Some(pos) => batch.column(pos).clone(),
+                    None => new_null_array(field.data_type(), batch.num_rows()),
+                }
+            })
+            .collect();
+
+        Ok(RecordBatch::try_new(self.table_schema.clone(), arrays)?)
+    }
+
+    /// Signal that all data has been sent, returning the number of rows
+    /// inserted once the insert completes.
+    pub async fn finish(mut self) -> Result<u64> {
+        self.batches = None;
+        self.wait().await
+    }
+
+    async fn wait(&mut self) -> Result<u64> {
+        match self.insert.take() {
+            Some(insert) => insert
+                .await
+                .map_err(|e| ExecError::String(format!("copy insert task failed: {e}")))?,
+            None => Err(ExecError::String("copy already finished".to_string())),
+        }
+    }
+}
+
+impl Drop for CopyInSink {
+    fn drop(&mut self) {
+        // Closing the channel would otherwise look like the end of the data,
+        // inserting whatever was sent so far.
+        if let Some(insert) = self.insert.take() {
+            insert.abort();
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Incremental reads of query results.
+//!
+//! Used for portals that were suspended after returning the max number of
+//! rows requested by an execute, and for cursors created with `DECLARE`.
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::SendableRecordBatchStream;
+use futures::{stream, StreamExt};
+
+use crate::errors::Result;
+
+/// A query result that's read a number of rows at a time.
+///
+/// The underlying stream is kept open between reads. Dropping the cursor
+/// drops the stream, stopping the query.
+pub struct Cursor {
+    stream: SendableRecordBatchStream,
+    /// Rows read from the stream that haven't been returned yet.
+    pending: Option<RecordBatch>,
+}
+
+impl Cursor {
+    pub fn new(stream: SendableRecordBatchStream) -> Cursor {
+        Cursor {
+            stream,
+            pending: None,
+        }
+    }
+
+    /// Schema of the rows returned from the cursor.
+    pub fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+
+    /// Read up to `limit` rows from the cursor, or all remaining rows if no
+    /// limit is provided.
+    ///
+    /// Fewer rows than the limit are only returned once there's nothing left
+    /// to read.
+    pub async fn fetch(&mut self, limit: Option<usize>) -> Result<Vec<RecordBatch>> {
+        let mut remaining = limit.unwrap_or(usize::MAX);
+        let mut batches = Vec::new();
+
+        while remaining > 0 {
+            let batch = match self.pending.take() {
+                Some(batch) => batch,
+                None => match self.stream.next().await {
+                    Some(batch) => batch?,
+                    None => break,
+                },
+            };
+
+            if batch.num_rows() > remaining {
+                self.pending = Some(batch.slice(remaining, batch.num_rows() - remaining));
+                batches.push(batch.slice(0, remaining));
+                break;
+            }
+
+            remaining -= batch.num_rows();
+            batches.push(batch);
+        }
+
+        Ok(batches)
+    }
+
+    /// Convert the cursor into a stream of the rows that haven't been read
+    /// yet.
+    pub fn into_stream(self) -> SendableRecordBatchStream {
+        let schema = self.stream.schema();
+        let pending = stream::iter(self.pending.map(Ok));
+        Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            pending.chain(self.stream),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::array::{Array, Int32Array};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::error::Result as DataFusionResult;
+    use std::sync::Arc;
+
+    fn cursor(batch_sizes: &[i32]) -> Cursor {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let mut start = 0;
+        let batches: Vec<DataFusionResult<RecordBatch>> = batch_sizes
+            .iter()
+            .map(|size| {
+                let values = Int32Array::from_iter_values(start..start + size);
+                start += size;
+                Ok(RecordBatch::try_new(schema.clone(), vec![Arc::new(values)]).unwrap())
+            })
+            .collect();
+        Cursor::new(Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            stream::iter(batches),
+        )))
+    }
+
+    fn values(batches: &[RecordBatch]) -> Vec<i32> {
+        batches
+            .iter()
+            .flat_map(|batch| {
+                let col = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap();
+                (0..col.len()).map(|idx| col.value(idx)).collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn fetch_across_batches() {
+        let mut cursor = cursor(&[3, 3]);
+
+        assert_eq!(vec![0, 1], values(&cursor.fetch(Some(2)).await.unwrap()));
+        assert_eq!(vec![2, 3, 4], values(&cursor.fetch(Some(3)).await.unwrap()));
+        assert_eq!(vec![5], values(&cursor.fetch(Some(3)).await.unwrap()));
+        assert!(cursor.fetch(Some(3)).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fetch_all() {
+        let mut cursor = cursor(&[2, 2]);
+
+        assert_eq!(vec![0], values(&cursor.fetch(Some(1)).await.unwrap()));
+        assert_eq!(vec![1, 2, 3], values(&cursor.fetch(None).await.unwrap()));
+    }
+
+    #[tokio::test]
+    async fn into_stream_includes_pending() {
+        let mut cursor = cursor(&[4]);
+        cursor.fetch(Some(1)).await.unwrap();
+
+        let batches: Vec<_> = cursor
+            .into_stream()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<DataFusionResult<_>>()
+            .unwrap();
+        assert_eq!(vec![1, 2, 3], values(&batches));
+    }
+}
@@ -2,7 +2,8 @@ use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use datafusion::common::FileType;
+use datafusion::datasource::file_format::arrow::ArrowFormat;
+use datafusion::datasource::file_format::avro::AvroFormat;
 use datafusion::datasource::file_format::csv::CsvFormat;
 use datafusion::datasource::file_format::file_compression_type::FileCompressionType;
 use datafusion::datasource::file_format::json::JsonFormat;
@@ -21,13 +22,17 @@ use datasources::lake::iceberg::table::IcebergTable;
 use datasources::lance::scan_lance_table;
 use datasources::mongodb::{MongoDbAccessor, MongoDbTableAccessInfo};
 use datasources::mysql::{MysqlAccessor, MysqlTableAccess};
+use datasources::native::access::NativeTable;
 use datasources::object_store::gcs::GcsStoreAccess;
 use datasources::object_store::generic::GenericStoreAccess;
 use datasources::object_store::local::LocalStoreAccess;
+use datasources::object_store::orc::OrcFormat;
+use datasources::object_store::partition::PartitionColumns;
 use datasources::object_store::s3::S3StoreAccess;
-use datasources::object_store::{ObjStoreAccess, ObjStoreAccessor};
+use datasources::object_store::{FileType, ObjStoreAccess, ObjStoreAccessor};
 use datasources::postgres::{PostgresAccess, PostgresTableProvider, PostgresTableProviderConfig};
 use datasources::snowflake::{SnowflakeAccessor, SnowflakeDbConnection, SnowflakeTableAccess};
+use datasources::sqlite::{SqliteAccess, SqliteTableProvider, SQLITE_SCHEMA};
 use datasources::sqlserver::{
     SqlServerAccess, SqlServerTableProvider, SqlServerTableProviderConfig,
 };
@@ -35,11 +40,12 @@ use protogen::metastore::types::catalog::{CatalogEntry, DatabaseEntry, FunctionE
 use protogen::metastore::types::options::{
     DatabaseOptions, DatabaseOptionsBigQuery, DatabaseOptionsClickhouse, DatabaseOptionsDebug,
     DatabaseOptionsDeltaLake, DatabaseOptionsMongoDb, DatabaseOptionsMysql,
-    DatabaseOptionsPostgres, DatabaseOptionsSnowflake, DatabaseOptionsSqlServer, TableOptions,
-    TableOptionsBigQuery, TableOptionsClickhouse, TableOptionsDebug, TableOptionsGcs,
-    TableOptionsInternal, TableOptionsLocal, TableOptionsMongoDb, TableOptionsMysql,
-    TableOptionsObjectStore, TableOptionsPostgres, TableOptionsS3, TableOptionsSnowflake,
-    TableOptionsSqlServer, TunnelOptions,
+    DatabaseOptionsPostgres, DatabaseOptionsSnowflake, DatabaseOptionsSqlServer,
+    DatabaseOptionsSqlite, StorageOptions, TableOptions, TableOptionsBigQuery,
+    TableOptionsClickhouse, TableOptionsDebug, TableOptionsGcs, TableOptionsInternal,
+    TableOptionsLocal, TableOptionsMongoDb, TableOptionsMysql, TableOptionsObjectStore,
+    TableOptionsPostgres, TableOptionsS3, TableOptionsSnowflake, TableOptionsSqlServer,
+    TableOptionsSqlite, TunnelOptions,
 };
 use sqlbuiltins::builtins::DEFAULT_CATALOG;
 use sqlbuiltins::functions::FUNCTION_REGISTRY;
@@ -226,6 +232,20 @@ impl<'a> ExternalDispatcher<'a> {
                 let table = ClickhouseTableProvider::try_new(access, name).await?;
                 Ok(Arc::new(table))
             }
+            DatabaseOptions::Sqlite(DatabaseOptionsSqlite {
+                location,
+                storage_options,
+            }) => {
+                if schema != SQLITE_SCHEMA {
+                    return Err(DispatchError::MissingEntry {
+                        schema: schema.to_string(),
+                        name: name.to_string(),
+                    });
+                }
+                let access = self.sqlite_access(location, storage_options)?;
+                let table = SqliteTableProvider::try_new(access, name).await?;
+                Ok(Arc::new(table))
+            }
         }
     }
 
@@ -338,6 +358,7 @@ impl<'a> ExternalDispatcher<'a> {
                 location,
                 file_type,
                 compression,
+                partition_columns,
             }) => {
                 if self.disable_local_fs_access {
                     return Err(DispatchError::InvalidDispatch(
@@ -350,6 +371,7 @@ impl<'a> ExternalDispatcher<'a> {
                     location,
                     file_type,
                     compression.as_ref(),
+                    partition_columns,
                 )
                 .await
             }
@@ -359,6 +381,7 @@ impl<'a> ExternalDispatcher<'a> {
                 location,
                 file_type,
                 compression,
+                partition_columns,
             }) => {
                 let access = Arc::new(GcsStoreAccess {
                     service_account_key: service_account_key.clone(),
@@ -369,6 +392,7 @@ impl<'a> ExternalDispatcher<'a> {
                     location,
                     file_type,
                     compression.as_ref(),
+                    partition_columns,
                 )
                 .await
             }
@@ -380,6 +404,7 @@ impl<'a> ExternalDispatcher<'a> {
                 location,
                 file_type,
                 compression,
+                partition_columns,
             }) => {
                 let access = Arc::new(S3StoreAccess {
                     region: region.clone(),
@@ -392,6 +417,7 @@ impl<'a> ExternalDispatcher<'a> {
                     location,
                     file_type,
                     compression.as_ref(),
+                    partition_columns,
                 )
                 .await
             }
@@ -400,6 +426,7 @@ impl<'a> ExternalDispatcher<'a> {
                 storage_options,
                 file_type,
                 compression,
+                partition_columns,
                 ..
             }) => {
                 // File type should be known at this point since creating the
@@ -423,6 +450,7 @@ impl<'a> ExternalDispatcher<'a> {
                     DatasourceUrl::try_new(location)?.path(), // TODO: Workaround again
                     file_type,
                     compression.as_ref(),
+                    partition_columns,
                 )
                 .await
             }
@@ -431,9 +459,10 @@ impl<'a> ExternalDispatcher<'a> {
                 storage_options,
                 ..
             }) => {
-                let provider =
-                    Arc::new(load_table_direct(location, storage_options.clone()).await?);
-                Ok(provider)
+                // Wrapped as a native table so that external delta tables can
+                // be inserted into.
+                let table = load_table_direct(location, storage_options.clone()).await?;
+                Ok(NativeTable::new(table).into_table_provider())
             }
             TableOptions::Iceberg(TableOptionsObjectStore {
                 location,
@@ -473,6 +502,15 @@ impl<'a> ExternalDispatcher<'a> {
                 let table = ClickhouseTableProvider::try_new(access, table).await?;
                 Ok(Arc::new(table))
             }
+            TableOptions::Sqlite(TableOptionsSqlite {
+                location,
+                storage_options,
+                table,
+            }) => {
+                let access = self.sqlite_access(location, storage_options)?;
+                let table = SqliteTableProvider::try_new(access, table).await?;
+                Ok(Arc::new(table))
+            }
             TableOptions::Lance(TableOptionsObjectStore {
                 location,
                 storage_options,
@@ -500,12 +538,31 @@ impl<'a> ExternalDispatcher<'a> {
         }
     }
 
+    fn sqlite_access(
+        &self,
+        location: &str,
+        storage_options: &StorageOptions,
+    ) -> Result<SqliteAccess> {
+        if self.disable_local_fs_access
+            && matches!(DatasourceUrl::try_new(location)?, DatasourceUrl::File(_))
+        {
+            return Err(DispatchError::InvalidDispatch(
+                "Local file access is not supported in cloud mode",
+            ));
+        }
+        Ok(SqliteAccess {
+            location: location.to_string(),
+            storage_options: storage_options.clone(),
+        })
+    }
+
     async fn create_obj_store_table_provider(
         &self,
         access: Arc<dyn ObjStoreAccess>,
         path: impl AsRef<str>,
         file_type: &str,
         compression: Option<&String>,
+        partition_columns: &[String],
     ) -> Result<Arc<dyn TableProvider>> {
         let path = path.as_ref();
         let compression = compression
@@ -515,23 +572,29 @@ impl<'a> ExternalDispatcher<'a> {
 
         let ft: FileType = file_type.parse()?;
         let ft: Arc<dyn FileFormat> = match ft {
-            FileType::CSV => Arc::new(
+            FileType::Csv => Arc::new(
                 CsvFormat::default()
                     .with_file_compression_type(compression)
                     .with_schema_infer_max_rec(Some(20480)),
             ),
-            FileType::PARQUET => Arc::new(ParquetFormat::default()),
-            FileType::JSON => {
+            FileType::Parquet => Arc::new(ParquetFormat::default()),
+            FileType::Json => {
                 Arc::new(JsonFormat::default().with_file_compression_type(compression))
             }
-            _ => return Err(DispatchError::InvalidDispatch("Unsupported file type")),
+            FileType::Arrow => Arc::new(ArrowFormat),
+            FileType::Avro => Arc::new(AvroFormat),
+            FileType::Orc => Arc::new(OrcFormat),
         };
 
         let accessor = ObjStoreAccessor::new(access)?;
         let objects = accessor.list_globbed(path).await?;
+        let base = accessor.glob_base(path)?;
 
         let state = self.df_ctx.state();
-        let provider = accessor.into_table_provider(&state, ft, objects).await?;
+        let partition_columns = PartitionColumns::from(partition_columns.to_vec());
+        let provider = accessor
+            .into_table_provider(&state, ft, objects, &[base], &partition_columns)
+            .await?;
 
         Ok(provider)
     }
@@ -13,7 +13,7 @@ use datafusion::prelude::{Column, Expr};
 use datafusion_ext::functions::{DefaultTableContextProvider, FuncParamValue};
 use datasources::native::access::NativeTableStorage;
 use protogen::metastore::types::catalog::{
-    CatalogEntry, DatabaseEntry, EntryMeta, EntryType, FunctionEntry, ViewEntry,
+    CatalogEntry, DatabaseEntry, EntryMeta, EntryType, FunctionEntry, Privilege, ViewEntry,
 };
 use sqlbuiltins::functions::FUNCTION_REGISTRY;
 
@@ -60,6 +60,13 @@ pub enum DispatchError {
     #[error("Invalid dispatch: {0}")]
     InvalidDispatch(&'static str),
 
+    #[error("Permission denied for user '{user}': missing {privilege} privilege on '{object}'")]
+    PermissionDenied {
+        user: String,
+        privilege: Privilege,
+        object: String,
+    },
+
     #[error(transparent)]
     RemoteDispatch(Box<dyn std::error::Error + Send + Sync>),
 
@@ -90,6 +97,8 @@ pub enum DispatchError {
     #[error(transparent)]
     ClickhouseDatasource(#[from] datasources::clickhouse::errors::ClickhouseError),
     #[error(transparent)]
+    SqliteDatasource(#[from] datasources::sqlite::errors::SqliteError),
+    #[error(transparent)]
     NativeDatasource(#[from] datasources::native::errors::NativeError),
     #[error(transparent)]
     CommonDatasource(#[from] datasources::common::errors::DatasourceCommonError),
@@ -173,6 +182,10 @@ pub struct Dispatcher<'a> {
     df_ctx: &'a DfSessionContext,
     /// Whether or not local file system access should be disabled.
     disable_local_fs_access: bool,
+    /// User the session is running as.
+    user: String,
+    /// Privilege the user needs on tables and views being dispatched to.
+    privilege: Privilege,
 }
 
 impl<'a> Dispatcher<'a> {
@@ -182,6 +195,8 @@ impl<'a> Dispatcher<'a> {
         view_planner: &'a dyn ViewPlanner,
         df_ctx: &'a DfSessionContext,
         disable_local_fs_access: bool,
+        user: String,
+        privilege: Privilege,
     ) -> Self {
         Dispatcher {
             catalog,
@@ -189,7 +204,36 @@ impl<'a> Dispatcher<'a> {
             view_planner,
             df_ctx,
             disable_local_fs_access,
+            user,
+            privilege,
+        }
+    }
+
+    /// Check that the user has the required privilege on a table or view.
+    pub fn check_access(&self, meta: &EntryMeta) -> Result<()> {
+        if !self.catalog.can_access(&self.user, meta, self.privilege) {
+            return Err(DispatchError::PermissionDenied {
+                user: self.user.clone(),
+                privilege: self.privilege,
+                object: meta.name.clone(),
+            });
         }
+        Ok(())
+    }
+
+    /// Check that the user can access objects in an external database.
+    ///
+    /// Objects in external databases don't have catalog entries, so
+    /// privileges can't be granted on them. Only superusers can access them.
+    pub fn check_external_access(&self, db_ent: &DatabaseEntry) -> Result<()> {
+        if !self.catalog.is_superuser(&self.user) {
+            return Err(DispatchError::PermissionDenied {
+                user: self.user.clone(),
+                privilege: self.privilege,
+                object: db_ent.meta.name.clone(),
+            });
+        }
+        Ok(())
     }
 
     /// Dispatch to a table provider.
@@ -199,6 +243,7 @@ impl<'a> Dispatcher<'a> {
         if !matches!(ent.entry_type(), EntryType::View | EntryType::Table) {
             return Err(DispatchError::InvalidEntryTypeForDispatch(ent.entry_type()));
         }
+        self.check_access(ent.get_meta())?;
 
         match ent {
             CatalogEntry::View(view) => self.dispatch_view(&view).await,
@@ -215,7 +260,7 @@ impl<'a> Dispatcher<'a> {
             }
             // Dispatch to builtin tables.
             CatalogEntry::Table(tbl) if tbl.meta.builtin => {
-                SystemTableDispatcher::new(self.catalog, self.tables)
+                SystemTableDispatcher::new(self.catalog, self.tables, self.df_ctx)
                     .dispatch(&tbl)
                     .await
             }
@@ -241,6 +286,7 @@ impl<'a> Dispatcher<'a> {
         schema: &str,
         name: &str,
     ) -> Result<Arc<dyn TableProvider>> {
+        self.check_external_access(db_ent)?;
         ExternalDispatcher::new(self.catalog, self.df_ctx, self.disable_local_fs_access)
             .dispatch_external(&db_ent.meta.name, schema, name)
             .await
@@ -257,20 +303,28 @@ impl<'a> Dispatcher<'a> {
 
     pub async fn dispatch_table_function(
         &self,
-        func: &FunctionEntry,
+        ent: &FunctionEntry,
         args: Vec<FuncParamValue>,
         opts: HashMap<String, FuncParamValue>,
     ) -> Result<Arc<dyn TableProvider>> {
-        let func = match FUNCTION_REGISTRY.get_table_func(&func.meta.name) {
+        let func = match FUNCTION_REGISTRY.get_table_func(&ent.meta.name) {
             Some(func) => func,
             None => {
                 return Err(DispatchError::String(format!(
                     "'{}' cannot be used in the FROM clause of a query.",
-                    func.meta.name
+                    ent.meta.name
                 )))
             }
         };
 
+        if func.reads_external_data() && !self.catalog.is_superuser(&self.user) {
+            return Err(DispatchError::PermissionDenied {
+                user: self.user.clone(),
+                privilege: self.privilege,
+                object: ent.meta.name.clone(),
+            });
+        }
+
         let prov = func
             .create_provider(
                 &DefaultTableContextProvider::new(self.catalog, self.df_ctx),
@@ -1,10 +1,15 @@
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use catalog::session_catalog::SessionCatalog;
-use datafusion::arrow::array::{BooleanBuilder, ListBuilder, StringBuilder, UInt32Builder};
+use datafusion::arrow::array::{
+    BooleanBuilder, ListBuilder, StringBuilder, TimestampMicrosecondBuilder, UInt32Builder,
+};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::datasource::{MemTable, TableProvider};
+use datafusion::execution::context::SessionContext as DfSessionContext;
 use datafusion::logical_expr::TypeSignature;
+use datafusion_ext::vars::SessionVars;
 use datasources::common::ssh::key::SshKey;
 use datasources::common::ssh::SshConnectionParameters;
 use datasources::native::access::NativeTableStorage;
@@ -12,22 +17,33 @@ use protogen::metastore::types::catalog::{CatalogEntry, EntryType, SourceAccessM
 use protogen::metastore::types::options::TunnelOptions;
 use sqlbuiltins::builtins::{
     BuiltinTable, DATABASE_DEFAULT, GLARE_CACHED_EXTERNAL_DATABASE_TABLES, GLARE_COLUMNS,
-    GLARE_CREDENTIALS, GLARE_DATABASES, GLARE_DEPLOYMENT_METADATA, GLARE_FUNCTIONS, GLARE_SCHEMAS,
-    GLARE_SSH_KEYS, GLARE_TABLES, GLARE_TUNNELS, GLARE_VIEWS, SCHEMA_CURRENT_SESSION,
+    GLARE_CREDENTIALS, GLARE_DATABASES, GLARE_DEPLOYMENT_METADATA, GLARE_FLIGHT_SESSIONS,
+    GLARE_FUNCTIONS, GLARE_ROLES, GLARE_SCHEMAS, GLARE_SSH_KEYS, GLARE_TABLES, GLARE_TUNNELS,
+    GLARE_VIEWS, SCHEMA_CURRENT_SESSION,
 };
 use sqlbuiltins::functions::FUNCTION_REGISTRY;
 
 use super::{DispatchError, Result};
+use crate::session_registry::SessionRegistry;
 
 /// Dispatch to builtin system tables.
 pub struct SystemTableDispatcher<'a> {
     catalog: &'a SessionCatalog,
     tables: &'a NativeTableStorage,
+    df_ctx: &'a DfSessionContext,
 }
 
 impl<'a> SystemTableDispatcher<'a> {
-    pub fn new(catalog: &'a SessionCatalog, tables: &'a NativeTableStorage) -> Self {
-        SystemTableDispatcher { catalog, tables }
+    pub fn new(
+        catalog: &'a SessionCatalog,
+        tables: &'a NativeTableStorage,
+        df_ctx: &'a DfSessionContext,
+    ) -> Self {
+        SystemTableDispatcher {
+            catalog,
+            tables,
+            df_ctx,
+        }
     }
 
     pub async fn dispatch(&self, ent: &TableEntry) -> Result<Arc<dyn TableProvider>> {
@@ -43,6 +59,10 @@ impl<'a> SystemTableDispatcher<'a> {
             Arc::new(self.build_glare_tunnels())
         } else if GLARE_CREDENTIALS.matches(schema, name) {
             Arc::new(self.build_glare_credentials())
+        } else if GLARE_ROLES.matches(schema, name) {
+            Arc::new(self.build_glare_roles())
+        } else if GLARE_FLIGHT_SESSIONS.matches(schema, name) {
+            Arc::new(self.build_glare_flight_sessions())
         } else if GLARE_TABLES.matches(schema, name) {
             Arc::new(self.build_glare_tables())
         } else if GLARE_COLUMNS.matches(schema, name) {
@@ -219,6 +239,90 @@ impl<'a> SystemTableDispatcher<'a> {
         MemTable::try_new(arrow_schema, vec![vec![batch]]).unwrap()
     }
 
+    fn build_glare_roles(&self) -> MemTable {
+        let arrow_schema = Arc::new(GLARE_ROLES.arrow_schema());
+
+        let mut oid = UInt32Builder::new();
+        let mut role_name = StringBuilder::new();
+        let mut login = BooleanBuilder::new();
+        let mut superuser = BooleanBuilder::new();
+        let mut has_password = BooleanBuilder::new();
+
+        for role in self
+            .catalog
+            .iter_entries()
+            .filter(|ent| ent.entry_type() == EntryType::Role)
+        {
+            oid.append_value(role.oid);
+            role_name.append_value(&role.entry.get_meta().name);
+
+            let role = match role.entry {
+                CatalogEntry::Role(role) => role,
+                other => unreachable!("unexpected entry type: {other:?}"),
+            };
+
+            login.append_value(role.login);
+            superuser.append_value(role.superuser);
+            has_password.append_value(!role.password_verifier.is_empty());
+        }
+
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![
+                Arc::new(oid.finish()),
+                Arc::new(role_name.finish()),
+                Arc::new(login.finish()),
+                Arc::new(superuser.finish()),
+                Arc::new(has_password.finish()),
+            ],
+        )
+        .unwrap();
+        MemTable::try_new(arrow_schema, vec![vec![batch]]).unwrap()
+    }
+
+    fn build_glare_flight_sessions(&self) -> MemTable {
+        let arrow_schema = Arc::new(GLARE_FLIGHT_SESSIONS.arrow_schema());
+
+        let mut session_id = StringBuilder::new();
+        let mut client_addr = StringBuilder::new();
+        let mut created_at = TimestampMicrosecondBuilder::new().with_timezone("UTC");
+        let mut last_active_at = TimestampMicrosecondBuilder::new().with_timezone("UTC");
+
+        // Sessions not created through the engine (e.g. remote sessions) won't
+        // have a registry, and there's nothing to list. Sessions expose the
+        // addresses of other clients, so only superusers can see them.
+        let conf = self.df_ctx.copied_config();
+        let database_id = conf
+            .options()
+            .extensions
+            .get::<SessionVars>()
+            .filter(|vars| self.catalog.is_superuser(&vars.user_name()))
+            .map(|vars| vars.database_id());
+        let sessions = match (conf.get_extension::<SessionRegistry>(), database_id) {
+            (Some(registry), Some(database_id)) => registry.database_sessions(database_id),
+            _ => Vec::new(),
+        };
+
+        for sess in sessions {
+            session_id.append_value(sess.session_id.to_string());
+            client_addr.append_value(&sess.client_addr);
+            created_at.append_value(unix_micros(sess.created_at));
+            last_active_at.append_value(unix_micros(sess.last_active_at));
+        }
+
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![
+                Arc::new(session_id.finish()),
+                Arc::new(client_addr.finish()),
+                Arc::new(created_at.finish()),
+                Arc::new(last_active_at.finish()),
+            ],
+        )
+        .unwrap();
+        MemTable::try_new(arrow_schema, vec![vec![batch]]).unwrap()
+    }
+
     fn build_glare_schemas(&self) -> MemTable {
         let arrow_schema = Arc::new(GLARE_SCHEMAS.arrow_schema());
 
@@ -613,3 +717,10 @@ pub(crate) fn join_types<T: Iterator<Item = U>, U: std::fmt::Display>(
         .collect::<Vec<String>>()
         .join(delimiter)
 }
+
+/// Get microseconds since the unix epoch for a timestamp.
+fn unix_micros(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as i64)
+        .unwrap_or_default()
+}
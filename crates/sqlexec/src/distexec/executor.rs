@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::task::Poll;
 use std::{
@@ -35,6 +36,9 @@ pub struct Task {
 
     /// This index of this node in relation to the parent query node.
     pub child: usize,
+
+    /// Set when the output of the query is no longer needed.
+    pub canceled: Arc<AtomicBool>,
 }
 
 impl Task {
@@ -125,7 +129,12 @@ impl TaskExecutor {
     }
 
     fn execute_inner(task: Task) {
-        // TODO: Check canceled.
+        if task.canceled.load(Ordering::Relaxed) {
+            // No rescheduling. Dropping the task drops the source for this
+            // partition.
+            debug!(partition = %task.partition, child = %task.child, "skipping canceled task");
+            return;
+        }
 
         let partition = task.partition;
         let child = task.child;
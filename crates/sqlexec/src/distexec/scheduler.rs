@@ -1,6 +1,7 @@
 use datafusion::execution::TaskContext;
 use datafusion::physical_plan::ExecutionPlan;
 
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use super::executor::{Task, TaskExecutor};
@@ -11,6 +12,9 @@ use super::Result;
 pub struct OutputSink {
     pub batches: Arc<dyn Sink>,
     pub errors: Arc<dyn ErrorSink>,
+    /// Flag indicating that the output is no longer needed. Tasks for the
+    /// plan stop executing once this is set.
+    pub canceled: Arc<AtomicBool>,
 }
 
 /// A scheduler for scheduling execution plans.
@@ -73,6 +77,7 @@ impl Scheduler {
                     errors: output.errors.clone(),
                     child: stage.output.map(|o| o.child).unwrap_or(0),
                     partition,
+                    canceled: output.canceled.clone(),
                 };
 
                 self.schedule_task(task);
@@ -5,6 +5,7 @@ use futures::channel::mpsc;
 use futures::{ready, Stream, StreamExt};
 use parking_lot::Mutex;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
@@ -20,9 +21,18 @@ pub fn create_coalescing_adapter(
 ) -> (CoalescingAdapterSink, AdapterStream) {
     let (tx, rx) = mpsc::unbounded();
     let closed = Mutex::new(vec![false; partition.partition_count()]);
-
-    let sink = CoalescingAdapterSink { tx, closed };
-    let stream = AdapterStream { rx, schema };
+    let canceled = Arc::new(AtomicBool::new(false));
+
+    let sink = CoalescingAdapterSink {
+        tx,
+        closed,
+        canceled: canceled.clone(),
+    };
+    let stream = AdapterStream {
+        rx,
+        schema,
+        canceled,
+    };
 
     (sink, stream)
 }
@@ -34,6 +44,16 @@ pub struct CoalescingAdapterSink {
     tx: mpsc::UnboundedSender<Option<Result<RecordBatch>>>,
     /// Tracks closed input partitions.
     closed: Mutex<Vec<bool>>,
+    /// Set once the adapter stream is dropped.
+    canceled: Arc<AtomicBool>,
+}
+
+impl CoalescingAdapterSink {
+    /// Get the flag indicating that the output of this sink is no longer
+    /// being read.
+    pub fn canceled(&self) -> Arc<AtomicBool> {
+        self.canceled.clone()
+    }
 }
 
 impl Sink for CoalescingAdapterSink {
@@ -89,6 +109,15 @@ impl ErrorSink for CoalescingAdapterSink {
 pub struct AdapterStream {
     rx: mpsc::UnboundedReceiver<Option<Result<RecordBatch>>>,
     schema: Arc<Schema>,
+    canceled: Arc<AtomicBool>,
+}
+
+impl Drop for AdapterStream {
+    fn drop(&mut self) {
+        // Nothing will read the output anymore, stop executing tasks for the
+        // pipeline.
+        self.canceled.store(true, Ordering::Relaxed);
+    }
 }
 
 impl Stream for AdapterStream {
@@ -3,6 +3,7 @@ use crate::distexec::executor::TaskExecutor;
 use crate::distexec::scheduler::Scheduler;
 use crate::errors::{ExecError, Result};
 use crate::session::Session;
+use crate::session_registry::SessionRegistry;
 use catalog::client::{MetastoreClientSupervisor, DEFAULT_METASTORE_CLIENT_CONFIG};
 use object_store::azure::AzureConfigKey;
 use sqlbuiltins::builtins::{SCHEMA_CURRENT_SESSION, SCHEMA_DEFAULT};
@@ -17,9 +18,11 @@ use std::ops::{Deref, DerefMut};
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
 
-use catalog::session_catalog::{ResolveConfig, SessionCatalog};
+use catalog::session_catalog::{ResolveConfig, SessionCatalog, DEFAULT_BOOTSTRAP_USER};
+use datafusion::variable::VarType;
 use datafusion_ext::vars::SessionVars;
 use datasources::common::errors::DatasourceCommonError;
 use datasources::common::url::{DatasourceUrl, DatasourceUrlType};
@@ -29,10 +32,12 @@ use metastore::util::MetastoreClientMode;
 use object_store_util::conf::StorageConfig;
 use object_store_util::shared::SharedObjectStore;
 use protogen::gen::metastore::service::metastore_service_client::MetastoreServiceClient;
+use protogen::metastore::types::catalog::{CatalogEntry, RoleEntry};
+use protogen::metastore::types::options::TableOptions;
 use protogen::rpcsrv::types::common;
 use telemetry::Tracker;
 use tonic::transport::Channel;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use url::Url;
 use uuid::Uuid;
 
@@ -317,6 +322,16 @@ pub struct Engine {
     spill_path: Option<PathBuf>,
     /// Number of active sessions.
     session_counter: Arc<AtomicU64>,
+    /// Databases that sessions have been opened for. Used for background
+    /// maintenance of native tables.
+    databases: Mutex<HashMap<Uuid, OpenDatabase>>,
+    /// Sessions that outlive a single request (e.g. Flight SQL sessions).
+    session_registry: Arc<SessionRegistry>,
+    /// User configured when starting the server.
+    ///
+    /// Sessions created without a user name (Flight SQL, RPC and local
+    /// sessions) run as this user.
+    bootstrap_user: String,
     /// Scheduler for running tasks (physical plan).
     task_scheduler: Scheduler,
     /// Task executors.
@@ -349,6 +364,9 @@ impl Engine {
             storage,
             spill_path,
             session_counter: Arc::new(AtomicU64::new(0)),
+            databases: Mutex::new(HashMap::new()),
+            session_registry: Arc::new(SessionRegistry::new()),
+            bootstrap_user: DEFAULT_BOOTSTRAP_USER.to_string(),
             task_scheduler,
             _task_executors: task_executors,
         })
@@ -405,11 +423,52 @@ impl Engine {
         self
     }
 
+    /// Set the user that's allowed to do anything without having a role in
+    /// the catalog.
+    pub fn with_bootstrap_user(mut self, user: impl Into<String>) -> Engine {
+        self.bootstrap_user = user.into();
+        self
+    }
+
+    /// Get the user that's allowed to do anything without having a role in
+    /// the catalog.
+    pub fn bootstrap_user(&self) -> &str {
+        &self.bootstrap_user
+    }
+
     /// Get the current number of sessions.
     pub fn session_count(&self) -> u64 {
         self.session_counter.load(Ordering::Relaxed)
     }
 
+    /// Get the registry for sessions that outlive a single request.
+    pub fn session_registry(&self) -> Arc<SessionRegistry> {
+        self.session_registry.clone()
+    }
+
+    /// Look up a role by name in a database's catalog.
+    ///
+    /// Used for authenticating connections before a session is created.
+    pub async fn resolve_role(&self, database_id: Uuid, name: &str) -> Result<Option<RoleEntry>> {
+        let metastore = self.supervisor.init_client(database_id).await?;
+        let state = metastore.get_cached_state().await?;
+        let role = state.entries.values().find_map(|ent| match ent {
+            CatalogEntry::Role(role) if role.meta.name == name => Some(role.clone()),
+            _ => None,
+        });
+        Ok(role)
+    }
+
+    /// Check if any roles exist in a database's catalog.
+    pub async fn has_roles(&self, database_id: Uuid) -> Result<bool> {
+        let metastore = self.supervisor.init_client(database_id).await?;
+        let state = metastore.get_cached_state().await?;
+        Ok(state
+            .entries
+            .values()
+            .any(|ent| matches!(ent, CatalogEntry::Role(_))))
+    }
+
     /// Create a new local session, initializing it with the provided session
     /// variables.
     // TODO: This is _very_ easy to mess up with the vars since we implement
@@ -440,11 +499,19 @@ impl Engine {
         vars: SessionVars,
         storage: SessionStorageConfig,
     ) -> Result<Session> {
+        // Sessions that weren't authenticated as a specific user run as the
+        // bootstrap user.
+        let vars = if vars.user_name().is_empty() {
+            vars.with_user_name(&self.bootstrap_user, VarType::System)
+        } else {
+            vars
+        };
         let database_id = vars.database_id();
         let metastore = self.supervisor.init_client(database_id).await?;
         let native = self
             .storage
             .new_native_tables_storage(database_id, &storage)?;
+        let database_ref = self.open_database(database_id, storage.clone());
         let state = metastore.get_cached_state().await?;
         let catalog = SessionCatalog::new(
             state,
@@ -452,9 +519,10 @@ impl Engine {
                 default_schema_oid: SCHEMA_DEFAULT.oid,
                 session_schema_oid: SCHEMA_CURRENT_SESSION.oid,
             },
-        );
+        )
+        .with_bootstrap_user(&self.bootstrap_user);
 
-        Session::new(
+        let mut session = Session::new(
             vars,
             catalog,
             metastore.into(),
@@ -462,7 +530,31 @@ impl Engine {
             self.tracker.clone(),
             self.spill_path.clone(),
             self.task_scheduler.clone(),
-        )
+            self.session_registry.clone(),
+        )?;
+        session.database_ref = Some(database_ref);
+
+        Ok(session)
+    }
+
+    /// Record that a session is being opened for a database.
+    ///
+    /// The returned reference should be held by the session. The database
+    /// stays registered for maintenance as long as references to it exist.
+    fn open_database(&self, database_id: Uuid, storage: SessionStorageConfig) -> Arc<()> {
+        let mut databases = self.databases.lock().unwrap();
+        let existing = databases
+            .get(&database_id)
+            .and_then(|db| db.sessions.upgrade());
+        let database_ref = existing.unwrap_or_default();
+        databases.insert(
+            database_id,
+            OpenDatabase {
+                storage,
+                sessions: Arc::downgrade(&database_ref),
+            },
+        );
+        database_ref
     }
 
     /// Create a new remote session for plan execution.
@@ -486,13 +578,115 @@ impl Engine {
                 default_schema_oid: SCHEMA_DEFAULT.oid,
                 session_schema_oid: SCHEMA_CURRENT_SESSION.oid,
             },
-        );
+        )
+        .with_bootstrap_user(&self.bootstrap_user);
 
         let context =
             RemoteSessionContext::new(catalog, metastore.into(), native, self.spill_path.clone())?;
 
         Ok(context)
     }
+
+    /// Start a background task that periodically compacts and vacuums native
+    /// tables for every database this engine has open sessions for.
+    ///
+    /// Vacuum uses the default retention period for tables.
+    pub fn start_native_table_maintenance(self: &Arc<Self>, interval: Duration) {
+        let engine = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // First tick completes immediately.
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let engine = match engine.upgrade() {
+                    Some(engine) => engine,
+                    None => return,
+                };
+
+                let databases: Vec<_> = engine
+                    .databases
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|(id, db)| (*id, db.storage.clone()))
+                    .collect();
+                for (db_id, storage) in databases {
+                    if let Err(e) = engine.maintain_native_tables(db_id, &storage).await {
+                        warn!(%e, %db_id, "failed to run native table maintenance");
+                    }
+                }
+
+                // Databases without open sessions are dropped after one last
+                // pass so that writes from closed sessions still get
+                // compacted.
+                engine
+                    .databases
+                    .lock()
+                    .unwrap()
+                    .retain(|_, db| db.sessions.strong_count() > 0);
+            }
+        });
+    }
+
+    /// Optimize and vacuum all native tables in a database.
+    ///
+    /// Failing to maintain a table is logged, and doesn't stop maintenance of
+    /// the remaining tables.
+    async fn maintain_native_tables(
+        &self,
+        db_id: Uuid,
+        storage: &SessionStorageConfig,
+    ) -> Result<()> {
+        let metastore = self.supervisor.init_client(db_id).await?;
+        let native = self.storage.new_native_tables_storage(db_id, storage)?;
+        let state = metastore.get_cached_state().await?;
+
+        for ent in state.entries.values() {
+            let table = match ent {
+                CatalogEntry::Table(table)
+                    if !table.meta.builtin
+                        && !table.meta.external
+                        && !table.meta.is_temp
+                        && matches!(table.options, TableOptions::Internal(_)) =>
+                {
+                    table
+                }
+                _ => continue,
+            };
+
+            let files_removed = match native.optimize_table(table, Vec::new()).await {
+                Ok(n) => n,
+                Err(e) => {
+                    warn!(%e, %db_id, table = %table.meta.name, "failed to optimize native table");
+                    continue;
+                }
+            };
+            let files_deleted = match native.vacuum_table(table, None, false, false).await {
+                Ok(n) => n,
+                Err(e) => {
+                    warn!(%e, %db_id, table = %table.meta.name, "failed to vacuum native table");
+                    continue;
+                }
+            };
+            debug!(
+                table = %table.meta.name,
+                %files_removed,
+                %files_deleted,
+                "maintained native table"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A database that sessions have been opened for.
+struct OpenDatabase {
+    /// Storage config of the most recently opened session.
+    storage: SessionStorageConfig,
+    /// Reference held by every open session for this database.
+    sessions: Weak<()>,
 }
 
 /// A thin wrapper around a session.
@@ -1,3 +1,5 @@
+use datafusion::common::DataFusionError;
+
 #[derive(Debug, thiserror::Error)]
 pub enum ExecError {
     #[error("SQL statement currently unsupported: {0}")]
@@ -27,6 +29,18 @@ pub enum ExecError {
     #[error("Unknown portal with name: {0}")]
     UnknownPortal(String),
 
+    #[error("Statement requires {0} parameters, but none were provided")]
+    MissingParameters(usize),
+
+    #[error("cursor \"{0}\" does not exist")]
+    UnknownCursor(String),
+
+    #[error("cursor \"{0}\" already exists")]
+    DuplicateCursor(String),
+
+    #[error("DECLARE CURSOR can only be used in transaction blocks")]
+    CursorOutsideTransaction,
+
     #[error("Empty search path, unable to resolve schema")]
     EmptySearchPath,
 
@@ -61,7 +75,7 @@ pub enum ExecError {
     MissingObject { typ: &'static str, name: String },
 
     #[error(transparent)]
-    DataFusion(#[from] datafusion::common::DataFusionError),
+    DataFusion(#[from] DataFusionError),
 
     #[error(transparent)]
     ParseError(#[from] datafusion::sql::sqlparser::parser::ParserError),
@@ -115,6 +129,18 @@ pub enum ExecError {
     #[error("Invalid temporary table: {reason}")]
     InvalidTempTable { reason: String },
 
+    #[error("current transaction is aborted, commands ignored until end of transaction block")]
+    InFailedTransaction,
+
+    #[error("could not commit transaction: {0}")]
+    TransactionConflict(String),
+
+    #[error("canceling statement due to statement timeout")]
+    StatementTimeout,
+
+    #[error("canceling statement due to user request")]
+    QueryCanceled,
+
     #[error("internal error: {0}")]
     Internal(String),
 
@@ -164,6 +190,30 @@ pub enum ExecError {
     ReqwestError(#[from] reqwest::Error),
 }
 
+impl ExecError {
+    /// Get the underlying error if this error was caused by canceling the
+    /// statement, either by the user or by the statement timeout.
+    ///
+    /// This includes cancels that happened while streaming results.
+    pub fn as_query_canceled(&self) -> Option<&ExecError> {
+        match self {
+            ExecError::QueryCanceled | ExecError::StatementTimeout => Some(self),
+            ExecError::DataFusion(e) => query_canceled_from_datafusion(e),
+            _ => None,
+        }
+    }
+}
+
+fn query_canceled_from_datafusion(e: &DataFusionError) -> Option<&ExecError> {
+    match e {
+        DataFusionError::External(e) => e
+            .downcast_ref::<ExecError>()
+            .and_then(|e| e.as_query_canceled()),
+        DataFusionError::Context(_, e) => query_canceled_from_datafusion(e),
+        _ => None,
+    }
+}
+
 pub type Result<T, E = ExecError> = std::result::Result<T, E>;
 
 #[allow(unused_macros)]
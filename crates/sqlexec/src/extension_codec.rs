@@ -20,7 +20,7 @@ use datafusion_ext::runtime::runtime_group::RuntimeGroupExec;
 use datafusion_proto::logical_plan::from_proto::parse_expr;
 use datafusion_proto::physical_plan::PhysicalExtensionCodec;
 use protogen::export::prost::Message;
-use protogen::metastore::types::catalog::RuntimePreference;
+use protogen::metastore::types::catalog::{Privilege, RuntimePreference};
 use uuid::Uuid;
 
 use crate::planner::physical_plan::alter_database::AlterDatabaseExec;
@@ -31,6 +31,7 @@ use crate::planner::physical_plan::create_credential::CreateCredentialExec;
 use crate::planner::physical_plan::create_credentials::CreateCredentialsExec;
 use crate::planner::physical_plan::create_external_database::CreateExternalDatabaseExec;
 use crate::planner::physical_plan::create_external_table::CreateExternalTableExec;
+use crate::planner::physical_plan::create_role::CreateRoleExec;
 use crate::planner::physical_plan::create_schema::CreateSchemaExec;
 use crate::planner::physical_plan::create_table::CreateTableExec;
 use crate::planner::physical_plan::create_temp_table::CreateTempTableExec;
@@ -40,15 +41,20 @@ use crate::planner::physical_plan::delete::DeleteExec;
 use crate::planner::physical_plan::describe_table::DescribeTableExec;
 use crate::planner::physical_plan::drop_credentials::DropCredentialsExec;
 use crate::planner::physical_plan::drop_database::DropDatabaseExec;
+use crate::planner::physical_plan::drop_roles::DropRolesExec;
 use crate::planner::physical_plan::drop_schemas::DropSchemasExec;
 use crate::planner::physical_plan::drop_tables::DropTablesExec;
 use crate::planner::physical_plan::drop_tunnel::DropTunnelExec;
 use crate::planner::physical_plan::drop_views::DropViewsExec;
+use crate::planner::physical_plan::grant_privileges::GrantPrivilegesExec;
 use crate::planner::physical_plan::insert::InsertExec;
+use crate::planner::physical_plan::optimize_table::OptimizeTableExec;
 use crate::planner::physical_plan::remote_scan::ProviderReference;
+use crate::planner::physical_plan::revoke_privileges::RevokePrivilegesExec;
 use crate::planner::physical_plan::set_var::SetVarExec;
 use crate::planner::physical_plan::show_var::ShowVarExec;
 use crate::planner::physical_plan::update::UpdateExec;
+use crate::planner::physical_plan::vacuum_table::VacuumTableExec;
 use crate::planner::physical_plan::values::ExtValuesExec;
 use crate::planner::physical_plan::{
     client_recv::ClientExchangeRecvExec, remote_scan::RemoteScanExec,
@@ -250,6 +256,7 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                     or_replace: ext.or_replace,
                     arrow_schema: Arc::new(schema),
                     source: inputs.first().cloned(),
+                    partition_by: ext.partition_by,
                 })
             }
             proto::ExecutionPlanExtensionType::CreateTempTableExec(ext) => {
@@ -410,6 +417,7 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                             })?
                             .clone(),
                     )),
+                    overwrite: ext.overwrite,
                 })
             }
             proto::ExecutionPlanExtensionType::DeleteExec(ext) => {
@@ -425,6 +433,69 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                     where_expr,
                 })
             }
+            proto::ExecutionPlanExtensionType::OptimizeTableExec(ext) => {
+                Arc::new(OptimizeTableExec {
+                    table: ext
+                        .table
+                        .ok_or_else(|| DataFusionError::Internal("missing table".to_string()))?
+                        .try_into()?,
+                    zorder_by: ext.zorder_by,
+                })
+            }
+            proto::ExecutionPlanExtensionType::VacuumTableExec(ext) => Arc::new(VacuumTableExec {
+                table: ext
+                    .table
+                    .ok_or_else(|| DataFusionError::Internal("missing table".to_string()))?
+                    .try_into()?,
+                retain_hours: ext.retain_hours,
+                skip_retention_check: ext.skip_retention_check,
+                dry_run: ext.dry_run,
+            }),
+            proto::ExecutionPlanExtensionType::CreateRoleExec(ext) => Arc::new(CreateRoleExec {
+                catalog_version: ext.catalog_version,
+                name: ext.name,
+                password_verifier: ext.password_verifier,
+                login: ext.login,
+                superuser: ext.superuser,
+                if_not_exists: ext.if_not_exists,
+            }),
+            proto::ExecutionPlanExtensionType::DropRolesExec(ext) => Arc::new(DropRolesExec {
+                catalog_version: ext.catalog_version,
+                names: ext.names,
+                if_exists: ext.if_exists,
+            }),
+            proto::ExecutionPlanExtensionType::GrantPrivilegesExec(ext) => {
+                Arc::new(GrantPrivilegesExec {
+                    catalog_version: ext.catalog_version,
+                    roles: ext.roles,
+                    objects: ext
+                        .objects
+                        .into_iter()
+                        .map(|o| o.try_into())
+                        .collect::<Result<_, _>>()?,
+                    privileges: ext
+                        .privileges
+                        .into_iter()
+                        .map(Privilege::try_from)
+                        .collect::<Result<_, _>>()?,
+                })
+            }
+            proto::ExecutionPlanExtensionType::RevokePrivilegesExec(ext) => {
+                Arc::new(RevokePrivilegesExec {
+                    catalog_version: ext.catalog_version,
+                    roles: ext.roles,
+                    objects: ext
+                        .objects
+                        .into_iter()
+                        .map(|o| o.try_into())
+                        .collect::<Result<_, _>>()?,
+                    privileges: ext
+                        .privileges
+                        .into_iter()
+                        .map(Privilege::try_from)
+                        .collect::<Result<_, _>>()?,
+                })
+            }
             proto::ExecutionPlanExtensionType::CopyToExec(ext) => Arc::new(CopyToExec {
                 format: ext
                     .format
@@ -557,6 +628,7 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                 if_not_exists: exec.if_not_exists,
                 or_replace: exec.or_replace,
                 arrow_schema: Some(exec.arrow_schema.clone().try_into()?),
+                partition_by: exec.partition_by.clone(),
             })
         } else if let Some(exec) = node.as_any().downcast_ref::<CreateTempTableExec>() {
             proto::ExecutionPlanExtensionType::CreateTempTableExec(proto::CreateTempTableExec {
@@ -718,6 +790,7 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
 
             proto::ExecutionPlanExtensionType::InsertExec(proto::InsertExec {
                 provider_id: id.into_bytes().to_vec(),
+                overwrite: exec.overwrite,
             })
         } else if let Some(exec) = node.as_any().downcast_ref::<DeleteExec>() {
             proto::ExecutionPlanExtensionType::DeleteExec(proto::DeleteExec {
@@ -728,6 +801,47 @@ impl<'a> PhysicalExtensionCodec for GlareDBExtensionCodec<'a> {
                     .map(|expr| expr.try_into())
                     .transpose()?,
             })
+        } else if let Some(exec) = node.as_any().downcast_ref::<OptimizeTableExec>() {
+            proto::ExecutionPlanExtensionType::OptimizeTableExec(proto::OptimizeTableExec {
+                table: Some(exec.table.clone().try_into()?),
+                zorder_by: exec.zorder_by.clone(),
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<VacuumTableExec>() {
+            proto::ExecutionPlanExtensionType::VacuumTableExec(proto::VacuumTableExec {
+                table: Some(exec.table.clone().try_into()?),
+                retain_hours: exec.retain_hours,
+                dry_run: exec.dry_run,
+                skip_retention_check: exec.skip_retention_check,
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<CreateRoleExec>() {
+            proto::ExecutionPlanExtensionType::CreateRoleExec(proto::CreateRoleExec {
+                catalog_version: exec.catalog_version,
+                name: exec.name.clone(),
+                password_verifier: exec.password_verifier.clone(),
+                login: exec.login,
+                superuser: exec.superuser,
+                if_not_exists: exec.if_not_exists,
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<DropRolesExec>() {
+            proto::ExecutionPlanExtensionType::DropRolesExec(proto::DropRolesExec {
+                catalog_version: exec.catalog_version,
+                names: exec.names.clone(),
+                if_exists: exec.if_exists,
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<GrantPrivilegesExec>() {
+            proto::ExecutionPlanExtensionType::GrantPrivilegesExec(proto::GrantPrivilegesExec {
+                catalog_version: exec.catalog_version,
+                roles: exec.roles.clone(),
+                objects: exec.objects.iter().cloned().map(|o| o.into()).collect(),
+                privileges: exec.privileges.iter().map(|p| (*p).into()).collect(),
+            })
+        } else if let Some(exec) = node.as_any().downcast_ref::<RevokePrivilegesExec>() {
+            proto::ExecutionPlanExtensionType::RevokePrivilegesExec(proto::RevokePrivilegesExec {
+                catalog_version: exec.catalog_version,
+                roles: exec.roles.clone(),
+                objects: exec.objects.iter().cloned().map(|o| o.into()).collect(),
+                privileges: exec.privileges.iter().map(|p| (*p).into()).collect(),
+            })
         } else if let Some(exec) = node.as_any().downcast_ref::<CopyToExec>() {
             proto::ExecutionPlanExtensionType::CopyToExec(proto::CopyToExec {
                 format: Some(exec.format.clone().try_into()?),
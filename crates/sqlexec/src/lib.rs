@@ -1,5 +1,8 @@
 //! SQL execution.
+pub mod cancel;
 pub mod context;
+pub mod copy_in;
+pub mod cursor;
 pub mod distexec;
 pub mod engine;
 pub mod environment;
@@ -8,10 +11,12 @@ pub mod extension_codec;
 pub mod parser;
 pub mod remote;
 pub mod session;
+pub mod session_registry;
 
 mod dispatch;
 mod planner;
 mod resolve;
+mod timeout;
 
 pub use planner::logical_plan::{LogicalPlan, OperationInfo};
 
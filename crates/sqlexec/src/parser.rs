@@ -395,6 +395,159 @@ impl fmt::Display for CopyToStmt {
     }
 }
 
+/// A source for a COPY FROM statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CopyFromSource {
+    /// Data is sent by the client over the connection.
+    Stdin,
+    /// Data is read from a file.
+    Location(Ident),
+}
+
+impl fmt::Display for CopyFromSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CopyFromSource::Stdin => write!(f, "STDIN"),
+            CopyFromSource::Location(location) => write!(f, "{location}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyFromStmt {
+    /// Table to copy the data into.
+    pub table: ObjectName,
+    /// Columns the data is for. Empty if the data is for all columns.
+    pub columns: Vec<Ident>,
+    /// Source to copy the data from.
+    pub source: CopyFromSource,
+    /// Optional format of the data.
+    pub format: Option<Ident>,
+    /// Optional credentials (for cloud storage).
+    pub credentials: Option<Ident>,
+    /// COPY FROM specific options.
+    pub options: StmtOptions,
+}
+
+impl fmt::Display for CopyFromStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "COPY {}", self.table)?;
+        if !self.columns.is_empty() {
+            write!(f, " (")?;
+            let mut sep = "";
+            for col in self.columns.iter() {
+                write!(f, "{sep}{col}")?;
+                sep = ", ";
+            }
+            write!(f, ")")?;
+        }
+        write!(f, " FROM {}", self.source)?;
+        if let Some(format) = self.format.as_ref() {
+            write!(f, " FORMAT {format}")?;
+        }
+        if let Some(creds) = self.credentials.as_ref() {
+            write!(f, " CREDENTIALS {creds}")?;
+        }
+        if !self.options.is_empty() {
+            write!(f, " {}", self.options)?;
+        }
+        Ok(())
+    }
+}
+
+/// CREATE TABLE ... PARTITION BY (col, ..) [AS query]
+///
+/// A native table create statement with partitioning. Statements without a
+/// `PARTITION BY` clause are left to `sqlparser`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatePartitionedTableStmt {
+    /// The underlying `CREATE TABLE` statement.
+    pub create: ast::Statement,
+    /// Columns to partition the table by.
+    pub partition_by: Vec<Ident>,
+}
+
+impl fmt::Display for CreatePartitionedTableStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The source query (if any) needs to come after the partition clause.
+        let mut create = self.create.clone();
+        let query = match &mut create {
+            ast::Statement::CreateTable { query, .. } => query.take(),
+            _ => None,
+        };
+
+        write!(f, "{create} PARTITION BY (")?;
+        let mut sep = "";
+        for col in self.partition_by.iter() {
+            write!(f, "{sep}{col}")?;
+            sep = ", ";
+        }
+        write!(f, ")")?;
+
+        if let Some(query) = query {
+            write!(f, " AS {query}")?;
+        }
+        Ok(())
+    }
+}
+
+/// OPTIMIZE table [ZORDER BY (col, ..)]
+///
+/// Compacts the files backing a native table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimizeTableStmt {
+    pub name: ObjectName,
+    /// Columns to z-order the data by. If empty, files are only compacted.
+    pub zorder_by: Vec<Ident>,
+}
+
+impl fmt::Display for OptimizeTableStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "OPTIMIZE {}", self.name)?;
+        if !self.zorder_by.is_empty() {
+            write!(f, " ZORDER BY (")?;
+            let mut sep = "";
+            for col in self.zorder_by.iter() {
+                write!(f, "{sep}{col}")?;
+                sep = ", ";
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+/// VACUUM table [RETAIN n HOURS [UNSAFE]] [DRY RUN]
+///
+/// Removes files no longer referenced by a native table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VacuumTableStmt {
+    pub name: ObjectName,
+    /// Only remove files that were removed from the table longer than this
+    /// many hours ago.
+    pub retain_hours: Option<u64>,
+    /// Allow a retention period shorter than the table's configured minimum.
+    pub skip_retention_check: bool,
+    /// Only list the files that would be removed.
+    pub dry_run: bool,
+}
+
+impl fmt::Display for VacuumTableStmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "VACUUM {}", self.name)?;
+        if let Some(hours) = self.retain_hours {
+            write!(f, " RETAIN {hours} HOURS")?;
+        }
+        if self.skip_retention_check {
+            write!(f, " UNSAFE")?;
+        }
+        if self.dry_run {
+            write!(f, " DRY RUN")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StatementWithExtensions {
     /// Statement parsed by `sqlparser`.
@@ -423,6 +576,14 @@ pub enum StatementWithExtensions {
     DropCredentials(DropCredentialsStmt),
     /// Copy To extension.
     CopyTo(CopyToStmt),
+    /// Copy From extension.
+    CopyFrom(CopyFromStmt),
+    /// Create table with partitioning extension.
+    CreatePartitionedTable(CreatePartitionedTableStmt),
+    /// Optimize table extension.
+    OptimizeTable(OptimizeTableStmt),
+    /// Vacuum table extension.
+    VacuumTable(VacuumTableStmt),
 }
 
 impl fmt::Display for StatementWithExtensions {
@@ -441,6 +602,10 @@ impl fmt::Display for StatementWithExtensions {
             StatementWithExtensions::CreateCredentials(stmt) => write!(f, "{}", stmt),
             StatementWithExtensions::DropCredentials(stmt) => write!(f, "{}", stmt),
             StatementWithExtensions::CopyTo(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::CopyFrom(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::CreatePartitionedTable(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::OptimizeTable(stmt) => write!(f, "{}", stmt),
+            StatementWithExtensions::VacuumTable(stmt) => write!(f, "{}", stmt),
         }
     }
 }
@@ -541,6 +706,14 @@ impl<'a> CustomParser<'a> {
                     self.parser.next_token();
                     self.parse_copy()
                 }
+                _ if w.value.eq_ignore_ascii_case("OPTIMIZE") => {
+                    self.parser.next_token();
+                    self.parse_optimize()
+                }
+                _ if w.value.eq_ignore_ascii_case("VACUUM") => {
+                    self.parser.next_token();
+                    self.parse_vacuum()
+                }
                 _ => Ok(StatementWithExtensions::Statement(
                     self.parser.parse_statement()?,
                 )),
@@ -586,12 +759,53 @@ impl<'a> CustomParser<'a> {
                 self.parser.prev_token();
             }
 
-            Ok(StatementWithExtensions::Statement(
-                self.parser.parse_create()?,
-            ))
+            let create = self.parser.parse_create()?;
+            self.parse_partition_by(create)
         }
     }
 
+    /// Parse an optional `PARTITION BY (col, ..) [AS query]` clause following
+    /// a `CREATE TABLE` statement parsed by `sqlparser`.
+    fn parse_partition_by(
+        &mut self,
+        mut create: ast::Statement,
+    ) -> Result<StatementWithExtensions, ParserError> {
+        let query = match &mut create {
+            ast::Statement::CreateTable { query, .. } => query,
+            _ => return Ok(StatementWithExtensions::Statement(create)),
+        };
+
+        if !self
+            .parser
+            .parse_keywords(&[Keyword::PARTITION, Keyword::BY])
+        {
+            return Ok(StatementWithExtensions::Statement(create));
+        }
+
+        if query.is_some() {
+            return Err(ParserError::ParserError(
+                "PARTITION BY must come before AS in CREATE TABLE".to_string(),
+            ));
+        }
+
+        self.parser.expect_token(&Token::LParen)?;
+        let partition_by = self
+            .parser
+            .parse_comma_separated(Parser::parse_identifier)?;
+        self.parser.expect_token(&Token::RParen)?;
+
+        if self.parser.parse_keyword(Keyword::AS) {
+            *query = Some(Box::new(self.parser.parse_query()?));
+        }
+
+        Ok(StatementWithExtensions::CreatePartitionedTable(
+            CreatePartitionedTableStmt {
+                create,
+                partition_by,
+            },
+        ))
+    }
+
     /// Parse a SQL ALTER statement
     fn parse_alter(&mut self) -> Result<StatementWithExtensions, ParserError> {
         if self.parser.parse_keyword(Keyword::DATABASE) {
@@ -622,6 +836,18 @@ impl<'a> CustomParser<'a> {
             CopyToSource::Query(query)
         } else {
             let table_name = self.parser.parse_object_name()?;
+            if self.parser.consume_token(&Token::LParen) {
+                // Column lists are only supported for COPY FROM.
+                let columns = self
+                    .parser
+                    .parse_comma_separated(Parser::parse_identifier)?;
+                self.parser.expect_token(&Token::RParen)?;
+                self.parser.expect_keyword(Keyword::FROM)?;
+                return self.parse_copy_from(table_name, columns);
+            }
+            if self.parser.parse_keyword(Keyword::FROM) {
+                return self.parse_copy_from(table_name, Vec::new());
+            }
             CopyToSource::Table(table_name)
         };
 
@@ -647,6 +873,140 @@ impl<'a> CustomParser<'a> {
         }))
     }
 
+    /// Parse the rest of a COPY FROM statement:
+    ///
+    /// COPY table [(col, ..)] FROM STDIN [WITH] (..)
+    /// or
+    /// COPY table [(col, ..)] FROM 'location' [FORMAT ..] [CREDENTIALS ..] [OPTIONS] (..)
+    fn parse_copy_from(
+        &mut self,
+        table: ObjectName,
+        columns: Vec<Ident>,
+    ) -> Result<StatementWithExtensions, ParserError> {
+        let source = if self.parser.parse_keyword(Keyword::STDIN) {
+            CopyFromSource::Stdin
+        } else {
+            CopyFromSource::Location(self.parser.parse_identifier()?)
+        };
+
+        // [FORMAT ..]
+        let format = self.parse_data_format()?;
+
+        // [CREDENTIALS ..]
+        let credentials = self.parse_connection_credentials()?;
+
+        // Postgres uses `WITH (..)` for options.
+        let _ = self.parser.parse_keyword(Keyword::WITH);
+
+        // OPTIONS (..), or the older unparenthesized options.
+        let options = match self.parser.peek_token().token {
+            Token::LParen => self.parse_options()?,
+            Token::Word(w) if w.value.eq_ignore_ascii_case("OPTIONS") => self.parse_options()?,
+            _ => self.parse_legacy_copy_options()?,
+        };
+
+        Ok(StatementWithExtensions::CopyFrom(CopyFromStmt {
+            table,
+            columns,
+            source,
+            format,
+            credentials,
+            options,
+        }))
+    }
+
+    /// Parse the unparenthesized COPY options Postgres accepted before 9.0,
+    /// e.g. `CSV HEADER` or `DELIMITER AS '|'`.
+    ///
+    /// Options are converted to their parenthesized equivalents.
+    fn parse_legacy_copy_options(&mut self) -> Result<StmtOptions, ParserError> {
+        let mut options = BTreeMap::new();
+        loop {
+            let (key, value) = if self.parser.parse_keyword(Keyword::BINARY) {
+                ("format", OptionValue::UnquotedLiteral("binary".to_string()))
+            } else if self.parser.parse_keyword(Keyword::CSV) {
+                ("format", OptionValue::UnquotedLiteral("csv".to_string()))
+            } else if self.parser.parse_keyword(Keyword::HEADER) {
+                ("header", OptionValue::Boolean(true))
+            } else if self.parser.parse_keyword(Keyword::DELIMITER) {
+                let _ = self.parser.parse_keyword(Keyword::AS);
+                let delimiter = self.parser.parse_literal_string()?;
+                ("delimiter", OptionValue::QuotedLiteral(delimiter))
+            } else if self.parser.parse_keyword(Keyword::NULL) {
+                let _ = self.parser.parse_keyword(Keyword::AS);
+                let null = self.parser.parse_literal_string()?;
+                ("null", OptionValue::QuotedLiteral(null))
+            } else if self.parser.parse_keyword(Keyword::QUOTE) {
+                let _ = self.parser.parse_keyword(Keyword::AS);
+                let quote = self.parser.parse_literal_string()?;
+                ("quote", OptionValue::QuotedLiteral(quote))
+            } else {
+                break;
+            };
+            options.insert(key.to_string(), value);
+        }
+        Ok(StmtOptions::new(options))
+    }
+
+    /// Parse the rest of an OPTIMIZE statement:
+    ///
+    /// OPTIMIZE [TABLE] table [ZORDER BY (col, ..)]
+    fn parse_optimize(&mut self) -> Result<StatementWithExtensions, ParserError> {
+        let _ = self.parser.parse_keyword(Keyword::TABLE);
+        let name = self.parser.parse_object_name()?;
+        validate_object_name(&name)?;
+
+        let zorder_by = if self.consume_token(&Token::make_keyword("ZORDER")) {
+            self.parser.expect_keyword(Keyword::BY)?;
+            self.parser.expect_token(&Token::LParen)?;
+            let cols = self
+                .parser
+                .parse_comma_separated(Parser::parse_identifier)?;
+            self.parser.expect_token(&Token::RParen)?;
+            cols
+        } else {
+            Vec::new()
+        };
+
+        Ok(StatementWithExtensions::OptimizeTable(OptimizeTableStmt {
+            name,
+            zorder_by,
+        }))
+    }
+
+    /// Parse the rest of a VACUUM statement:
+    ///
+    /// VACUUM [TABLE] table [RETAIN n HOURS [UNSAFE]] [DRY RUN]
+    fn parse_vacuum(&mut self) -> Result<StatementWithExtensions, ParserError> {
+        let _ = self.parser.parse_keyword(Keyword::TABLE);
+        let name = self.parser.parse_object_name()?;
+        validate_object_name(&name)?;
+
+        let (retain_hours, skip_retention_check) =
+            if self.consume_token(&Token::make_keyword("RETAIN")) {
+                let hours = self.parser.parse_literal_uint()?;
+                self.expect_token(&Token::make_keyword("HOURS"))?;
+                let skip_check = self.consume_token(&Token::make_keyword("UNSAFE"));
+                (Some(hours), skip_check)
+            } else {
+                (None, false)
+            };
+
+        let dry_run = if self.consume_token(&Token::make_keyword("DRY")) {
+            self.expect_token(&Token::make_keyword("RUN"))?;
+            true
+        } else {
+            false
+        };
+
+        Ok(StatementWithExtensions::VacuumTable(VacuumTableStmt {
+            name,
+            retain_hours,
+            skip_retention_check,
+            dry_run,
+        }))
+    }
+
     /// Report unexpected token.
     fn expected<T>(&self, expected: &str, found: Token) -> Result<T, ParserError> {
         Err(ParserError::ParserError(format!(
@@ -850,7 +1210,12 @@ impl<'a> CustomParser<'a> {
             // Optional `=`
             let _ = self.parser.consume_token(&Token::Eq);
 
-            let value = self.parse_options_value()?;
+            // A key without a value is a boolean option being enabled, e.g.
+            // `(FORMAT csv, HEADER)`.
+            let value = match self.parser.peek_token().token {
+                Token::Comma | Token::RParen => OptionValue::Boolean(true),
+                _ => self.parse_options_value()?,
+            };
 
             options.insert(key.to_lowercase(), value);
             let comma = self.parser.consume_token(&Token::Comma);
@@ -1356,6 +1721,152 @@ mod tests {
         }
     }
 
+    #[test]
+    fn copy_from_roundtrips() {
+        let test_cases = [
+            "COPY table FROM STDIN",
+            "COPY table FROM STDIN OPTIONS (format = csv)",
+            "COPY table (a, b) FROM STDIN",
+            "COPY table FROM 's3://bucket/file.csv'",
+            "COPY table FROM 's3://bucket/file.csv' FORMAT csv CREDENTIALS aws_creds",
+            "COPY table FROM 'file.csv' OPTIONS (delimiter = '|', header = TRUE)",
+        ];
+
+        for test_case in test_cases {
+            let stmt = CustomParser::parse_sql(test_case)
+                .unwrap()
+                .pop_front()
+                .unwrap();
+            assert_eq!(test_case, stmt.to_string().as_str());
+        }
+    }
+
+    #[test]
+    fn optimize_vacuum_roundtrips() {
+        let test_cases = [
+            "OPTIMIZE my_table",
+            "OPTIMIZE my_schema.my_table ZORDER BY (a, b)",
+            "VACUUM my_table",
+            "VACUUM my_table RETAIN 24 HOURS",
+            "VACUUM my_table RETAIN 0 HOURS DRY RUN",
+            "VACUUM my_table RETAIN 0 HOURS UNSAFE DRY RUN",
+        ];
+
+        for test_case in test_cases {
+            let stmt = CustomParser::parse_sql(test_case)
+                .unwrap()
+                .pop_front()
+                .unwrap();
+            assert_eq!(test_case, stmt.to_string().as_str());
+        }
+    }
+
+    #[test]
+    fn create_partitioned_table_roundtrips() {
+        let test_cases = [
+            "CREATE TABLE t1 (a INT, b TEXT) PARTITION BY (b)",
+            "CREATE TABLE s1.t1 (a INT, b TEXT, c DATE) PARTITION BY (b, c)",
+            "CREATE TABLE t1 PARTITION BY (b) AS SELECT 1 AS a, 'x' AS b",
+        ];
+
+        for test_case in test_cases {
+            let stmt = CustomParser::parse_sql(test_case)
+                .unwrap()
+                .pop_front()
+                .unwrap();
+            assert!(matches!(
+                stmt,
+                StatementWithExtensions::CreatePartitionedTable(_)
+            ));
+            assert_eq!(test_case, stmt.to_string().as_str());
+        }
+
+        // Plain create table statements are left as-is.
+        let stmt = CustomParser::parse_sql("CREATE TABLE t1 (a INT)")
+            .unwrap()
+            .pop_front()
+            .unwrap();
+        assert!(matches!(stmt, StatementWithExtensions::Statement(_)));
+    }
+
+    #[test]
+    fn copy_from_stdin_with_options() {
+        let stmt = CustomParser::parse_sql("COPY t FROM STDIN WITH (format binary)")
+            .unwrap()
+            .pop_front()
+            .unwrap();
+
+        let mut options = BTreeMap::new();
+        options.insert(
+            "format".to_string(),
+            OptionValue::UnquotedLiteral("binary".to_string()),
+        );
+        let expected = StatementWithExtensions::CopyFrom(CopyFromStmt {
+            table: ObjectName(vec![Ident::new("t")]),
+            columns: Vec::new(),
+            source: CopyFromSource::Stdin,
+            format: None,
+            credentials: None,
+            options: StmtOptions::new(options),
+        });
+        assert_eq!(expected, stmt);
+    }
+
+    #[test]
+    fn copy_from_stdin_legacy_options() {
+        let test_cases = [
+            "COPY t FROM STDIN WITH CSV HEADER",
+            "COPY t FROM STDIN CSV HEADER",
+            "COPY t FROM STDIN WITH (FORMAT csv, HEADER)",
+        ];
+
+        let mut options = BTreeMap::new();
+        options.insert(
+            "format".to_string(),
+            OptionValue::UnquotedLiteral("csv".to_string()),
+        );
+        options.insert("header".to_string(), OptionValue::Boolean(true));
+        let expected = StatementWithExtensions::CopyFrom(CopyFromStmt {
+            table: ObjectName(vec![Ident::new("t")]),
+            columns: Vec::new(),
+            source: CopyFromSource::Stdin,
+            format: None,
+            credentials: None,
+            options: StmtOptions::new(options),
+        });
+
+        for test_case in test_cases {
+            let stmt = CustomParser::parse_sql(test_case)
+                .unwrap()
+                .pop_front()
+                .unwrap();
+            assert_eq!(expected, stmt, "{test_case}");
+        }
+
+        let stmt = CustomParser::parse_sql("COPY t (a, b) FROM STDIN DELIMITER AS '|' NULL 'x'")
+            .unwrap()
+            .pop_front()
+            .unwrap();
+        let mut options = BTreeMap::new();
+        options.insert(
+            "delimiter".to_string(),
+            OptionValue::QuotedLiteral("|".to_string()),
+        );
+        options.insert(
+            "null".to_string(),
+            OptionValue::QuotedLiteral("x".to_string()),
+        );
+        let expected = StatementWithExtensions::CopyFrom(CopyFromStmt {
+            table: ObjectName(vec![Ident::new("t")]),
+            columns: vec![Ident::new("a"), Ident::new("b")],
+            source: CopyFromSource::Stdin,
+            format: None,
+            credentials: None,
+            options: StmtOptions::new(options),
+        });
+        assert_eq!(expected, stmt);
+    }
+
     #[test]
     fn options_parse() {
         let mut options = BTreeMap::new();
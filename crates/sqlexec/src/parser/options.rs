@@ -1,8 +1,8 @@
 use std::{collections::BTreeMap, fmt};
 
 use datafusion::common::parsers::CompressionTypeVariant;
-use datafusion::common::FileType;
 use datafusion::sql::sqlparser::parser::ParserError;
+use datasources::object_store::FileType;
 use datasources::{debug::DebugTableType, mongodb::MongoDbProtocol};
 use protogen::metastore::types::options::StorageOptions;
 
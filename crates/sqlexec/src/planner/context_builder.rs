@@ -22,7 +22,7 @@ use datafusion_ext::planner::AsyncContextProvider;
 
 use datafusion_ext::runtime::table_provider::RuntimeAwareTableProvider;
 use protogen::metastore::types::catalog::{
-    CatalogEntry, DatabaseEntry, FunctionEntry, RuntimePreference, TableEntry,
+    CatalogEntry, DatabaseEntry, FunctionEntry, Privilege, RuntimePreference, TableEntry,
 };
 use protogen::metastore::types::options::TableOptions;
 use protogen::rpcsrv::types::service::ResolvedTableReference;
@@ -48,6 +48,8 @@ pub struct PartialContextProvider<'a> {
     /// Entry resolver to use to resolve tables and other objects.
     resolver: EntryResolver<'a>,
     runtime_preference: RuntimePreference,
+    /// Privilege required on the tables and views being resolved.
+    privilege: Privilege,
 }
 
 impl<'a> PartialContextProvider<'a> {
@@ -59,9 +61,18 @@ impl<'a> PartialContextProvider<'a> {
             ctx,
             resolver,
             runtime_preference: RuntimePreference::Unspecified,
+            privilege: Privilege::Select,
         })
     }
 
+    /// Set the privilege the session user needs on resolved tables and views.
+    ///
+    /// Defaults to `SELECT`.
+    pub fn with_privilege(mut self, privilege: Privilege) -> Self {
+        self.privilege = privilege;
+        self
+    }
+
     fn new_dispatcher(&self) -> Dispatcher {
         Dispatcher::new(
             self.ctx.get_session_catalog(),
@@ -69,6 +80,8 @@ impl<'a> PartialContextProvider<'a> {
             self.ctx,
             self.ctx.df_ctx(),
             self.ctx.get_session_vars().is_cloud_instance(),
+            self.ctx.get_session_vars().user_name(),
+            self.privilege,
         )
     }
 
@@ -184,6 +197,18 @@ impl<'a> PartialContextProvider<'a> {
 
         use ResolvedEntry::*;
 
+        // Check privileges before dispatching since remote dispatches don't go
+        // through the local dispatcher.
+        match &ent {
+            Entry(ent @ (CatalogEntry::Table(_) | CatalogEntry::View(_))) => {
+                self.new_dispatcher().check_access(ent.get_meta())?
+            }
+            NeedsExternalResolution { db_ent, .. } => {
+                self.new_dispatcher().check_external_access(db_ent)?
+            }
+            _ => (),
+        }
+
         let provider = match (ent, self.ctx.exec_client()) {
             // (view, _)
             // Rely on further planning to determine how to handle views.
@@ -372,9 +397,13 @@ impl<'a> AsyncContextProvider for PartialContextProvider<'a> {
     }
 
     fn get_scalar_udf(&mut self, name: &str, args: Vec<Expr>) -> Option<Expr> {
-        FUNCTION_REGISTRY
-            .get_scalar_udf(name)
-            .map(|f| f.as_expr(args))
+        FUNCTION_REGISTRY.get_scalar_udf(name).map(|f| {
+            f.as_expr_with_session(
+                args,
+                self.ctx.get_session_catalog(),
+                &self.ctx.get_session_vars(),
+            )
+        })
     }
 
     async fn get_variable_type(&mut self, _variable_names: &[String]) -> Option<DataType> {
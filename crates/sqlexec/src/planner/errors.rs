@@ -66,6 +66,9 @@ pub enum PlanError {
     #[error("Expected exactly on SQL statement, got: {0:?}")]
     ExpectedExactlyOneStatement(Vec<crate::parser::StatementWithExtensions>),
 
+    #[error("Permission denied for user '{user}': {msg}")]
+    PermissionDenied { user: String, msg: String },
+
     #[error("Not allowed to write into the object: {0}")]
     ObjectNotAllowedToWriteInto(OwnedTableReference),
 
@@ -129,6 +132,7 @@ impl_from_dispatch_variant!(datasources::lake::iceberg::errors::IcebergError);
 impl_from_dispatch_variant!(datasources::object_store::errors::ObjectStoreSourceError);
 impl_from_dispatch_variant!(datasources::sqlserver::errors::SqlServerError);
 impl_from_dispatch_variant!(datasources::clickhouse::errors::ClickhouseError);
+impl_from_dispatch_variant!(datasources::sqlite::errors::SqliteError);
 
 #[allow(unused_macros)]
 macro_rules! internal {
@@ -8,9 +8,10 @@ use datafusion::logical_expr::{Extension as LogicalPlanExtension, UserDefinedLog
 
 use super::logical_plan::{
     AlterDatabase, AlterTable, AlterTunnelRotateKeys, CopyTo, CreateCredential, CreateCredentials,
-    CreateExternalDatabase, CreateExternalTable, CreateSchema, CreateTable, CreateTempTable,
-    CreateTunnel, CreateView, Delete, DescribeTable, DropCredentials, DropDatabase, DropSchemas,
-    DropTables, DropTunnel, DropViews, Insert, SetVariable, ShowVariable, Update,
+    CreateExternalDatabase, CreateExternalTable, CreateRole, CreateSchema, CreateTable,
+    CreateTempTable, CreateTunnel, CreateView, Delete, DescribeTable, DropCredentials,
+    DropDatabase, DropRoles, DropSchemas, DropTables, DropTunnel, DropViews, GrantPrivileges,
+    Insert, OptimizeTable, RevokePrivileges, SetVariable, ShowVariable, Update, VacuumTable,
 };
 
 /// This tracks all of our extensions so that we can ensure an exhaustive match on anywhere that uses the extension
@@ -25,6 +26,7 @@ pub enum ExtensionType {
     CreateCredentials,
     CreateExternalDatabase,
     CreateExternalTable,
+    CreateRole,
     CreateSchema,
     CreateTable,
     CreateTempTable,
@@ -34,15 +36,20 @@ pub enum ExtensionType {
     DropTables,
     DropCredentials,
     DropDatabase,
+    DropRoles,
     DropSchemas,
     DropTunnel,
     DropViews,
+    GrantPrivileges,
+    RevokePrivileges,
     SetVariable,
     ShowVariable,
     CopyTo,
     Update,
     Insert,
     Delete,
+    OptimizeTable,
+    VacuumTable,
 }
 
 impl FromStr for ExtensionType {
@@ -56,6 +63,7 @@ impl FromStr for ExtensionType {
             CreateCredentials::EXTENSION_NAME => Self::CreateCredentials,
             CreateExternalDatabase::EXTENSION_NAME => Self::CreateExternalDatabase,
             CreateExternalTable::EXTENSION_NAME => Self::CreateExternalTable,
+            CreateRole::EXTENSION_NAME => Self::CreateRole,
             CreateSchema::EXTENSION_NAME => Self::CreateSchema,
             CreateTable::EXTENSION_NAME => Self::CreateTable,
             CreateTempTable::EXTENSION_NAME => Self::CreateTempTable,
@@ -65,15 +73,20 @@ impl FromStr for ExtensionType {
             DropTables::EXTENSION_NAME => Self::DropTables,
             DropCredentials::EXTENSION_NAME => Self::DropCredentials,
             DropDatabase::EXTENSION_NAME => Self::DropDatabase,
+            DropRoles::EXTENSION_NAME => Self::DropRoles,
             DropSchemas::EXTENSION_NAME => Self::DropSchemas,
             DropTunnel::EXTENSION_NAME => Self::DropTunnel,
             DropViews::EXTENSION_NAME => Self::DropViews,
+            GrantPrivileges::EXTENSION_NAME => Self::GrantPrivileges,
+            RevokePrivileges::EXTENSION_NAME => Self::RevokePrivileges,
             SetVariable::EXTENSION_NAME => Self::SetVariable,
             ShowVariable::EXTENSION_NAME => Self::ShowVariable,
             CopyTo::EXTENSION_NAME => Self::CopyTo,
             Update::EXTENSION_NAME => Self::Update,
             Insert::EXTENSION_NAME => Self::Insert,
             Delete::EXTENSION_NAME => Self::Delete,
+            OptimizeTable::EXTENSION_NAME => Self::OptimizeTable,
+            VacuumTable::EXTENSION_NAME => Self::VacuumTable,
             _ => return Err(internal!("unknown extension type: {}", s)),
         })
     }
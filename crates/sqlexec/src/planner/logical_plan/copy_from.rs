@@ -0,0 +1,34 @@
+use datafusion::datasource::TableProvider;
+
+use crate::copy_in::CopyInFormat;
+
+use super::*;
+
+/// Copy data sent by the client into a table.
+///
+/// Unlike most other plans, this isn't executed through datafusion since the
+/// data can only be read once the client is told to start sending.
+#[derive(Clone)]
+pub struct CopyFromStdin {
+    pub table: Arc<dyn TableProvider>,
+    /// Indices of the table columns the client sends data for, in the order
+    /// the data is sent. `None` if data is sent for all columns.
+    pub columns: Option<Vec<usize>>,
+    pub format: CopyInFormat,
+}
+
+impl std::fmt::Debug for CopyFromStdin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CopyFromStdin")
+            .field("table", &self.table.schema())
+            .field("columns", &self.columns)
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+impl From<CopyFromStdin> for LogicalPlan {
+    fn from(plan: CopyFromStdin) -> Self {
+        LogicalPlan::CopyFromStdin(plan)
+    }
+}
@@ -7,6 +7,8 @@ pub struct CreateTable {
     pub or_replace: bool,
     pub schema: DFSchemaRef,
     pub source: Option<DfLogicalPlan>,
+    /// Columns to partition the table by.
+    pub partition_by: Vec<String>,
 }
 
 impl UserDefinedLogicalNodeCore for CreateTable {
@@ -12,6 +12,8 @@ pub struct Insert {
     pub source: DfLogicalPlan,
     pub provider: ProviderReference,
     pub runtime_preference: RuntimePreference,
+    /// Replace the existing rows in the table (`INSERT OVERWRITE`).
+    pub overwrite: bool,
 }
 
 impl UserDefinedLogicalNodeCore for Insert {
@@ -1,11 +1,13 @@
 mod alter_database;
 mod alter_table;
 mod alter_tunnel_rotate_keys;
+mod copy_from;
 mod copy_to;
 mod create_credential;
 mod create_credentials;
 mod create_external_database;
 mod create_external_table;
+mod create_role;
 mod create_schema;
 mod create_table;
 mod create_temp_table;
@@ -15,30 +17,39 @@ mod delete;
 mod describe_table;
 mod drop_credentials;
 mod drop_database;
+mod drop_roles;
 mod drop_schemas;
 mod drop_tables;
 mod drop_tunnel;
 mod drop_views;
+mod grant_privileges;
 mod insert;
+mod optimize_table;
+mod revoke_privileges;
 mod set_variable;
 mod show_variable;
 mod update;
+mod vacuum_table;
 
 use crate::errors::{internal, Result};
 use crate::planner::extension::ExtensionNode;
 
 use datafusion::arrow::datatypes::{DataType, Schema as ArrowSchema};
-use datafusion::common::{DFField, DFSchema, DFSchemaRef};
+use datafusion::common::tree_node::{Transformed, TreeNode, VisitRecursion};
+use datafusion::common::{DFField, DFSchema, DFSchemaRef, DataFusionError};
+use datafusion::logical_expr::expr::Placeholder;
 use datafusion::logical_expr::UserDefinedLogicalNodeCore;
-use datafusion::logical_expr::{Explain, Expr, LogicalPlan as DfLogicalPlan};
+use datafusion::logical_expr::{Explain, Expr, Extension, LogicalPlan as DfLogicalPlan};
 use datafusion::scalar::ScalarValue;
 use datafusion::sql::sqlparser::ast;
 use datafusion::sql::TableReference;
 use once_cell::sync::Lazy;
+use protogen::metastore::types::catalog::Privilege;
 use protogen::metastore::types::options::{CopyToDestinationOptions, CopyToFormatOptions};
 use protogen::metastore::types::options::{
     CredentialsOptions, DatabaseOptions, TableOptions, TunnelOptions,
 };
+use protogen::metastore::types::service::PrivilegeObject;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
@@ -47,11 +58,13 @@ use std::sync::Arc;
 pub use alter_database::*;
 pub use alter_table::*;
 pub use alter_tunnel_rotate_keys::*;
+pub use copy_from::*;
 pub use copy_to::*;
 pub use create_credential::*;
 pub use create_credentials::*;
 pub use create_external_database::*;
 pub use create_external_table::*;
+pub use create_role::*;
 pub use create_schema::*;
 pub use create_table::*;
 pub use create_temp_table::*;
@@ -61,14 +74,19 @@ pub use delete::*;
 pub use describe_table::*;
 pub use drop_credentials::*;
 pub use drop_database::*;
+pub use drop_roles::*;
 pub use drop_schemas::*;
 pub use drop_tables::*;
 pub use drop_tunnel::*;
 pub use drop_views::*;
+pub use grant_privileges::*;
 pub use insert::*;
+pub use optimize_table::*;
+pub use revoke_privileges::*;
 pub use set_variable::*;
 pub use show_variable::*;
 pub use update::*;
+pub use vacuum_table::*;
 
 use super::physical_plan::{
     GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA, GENERIC_OPERATION_PHYSICAL_SCHEMA,
@@ -121,6 +139,10 @@ pub enum LogicalPlan {
     Datafusion(DfLogicalPlan),
     /// Plans related to transaction management.
     Transaction(TransactionPlan),
+    /// Copy data sent by the client into a table.
+    CopyFromStdin(CopyFromStdin),
+    /// Plans for declaring, fetching from, and closing cursors.
+    Cursor(CursorPlan),
     Noop,
 }
 
@@ -141,25 +163,63 @@ impl LogicalPlan {
                 let schema: ArrowSchema = plan.schema().as_ref().into();
                 Some(schema)
             }
+            LogicalPlan::Cursor(CursorPlan::Fetch { schema, .. }) => schema.clone(),
             _ => None,
         }
     }
 
     /// Get parameter types for the logical plan.
     ///
+    /// Parameters that a type couldn't be inferred for are included with a
+    /// type of `None`.
+    ///
     /// Note this will only try to get the parameters if the plan is a
     /// datafusion logical plan. Possible support for other plans may come
     /// later.
     pub fn get_parameter_types(&self) -> Result<HashMap<String, Option<DataType>>> {
         Ok(match self {
-            LogicalPlan::Datafusion(plan) => plan.get_parameter_types()?,
+            LogicalPlan::Datafusion(plan) => {
+                let mut types = plan.get_parameter_types()?;
+
+                // Datafusion leaves out placeholders without a type (e.g.
+                // `$1::text`). We still want to count them as parameters.
+                plan.apply(&mut |plan| {
+                    plan.inspect_expressions(|expr| {
+                        expr.apply(&mut |expr| {
+                            if let Expr::Placeholder(Placeholder { id, .. }) = expr {
+                                types.entry(id.clone()).or_insert(None);
+                            }
+                            Ok(VisitRecursion::Continue)
+                        })?;
+                        Ok::<_, DataFusionError>(())
+                    })?;
+                    Ok(VisitRecursion::Continue)
+                })?;
+
+                if let DfLogicalPlan::Extension(ext) = plan {
+                    for expr in dml_expressions(ext) {
+                        expr.apply(&mut |expr| {
+                            if let Expr::Placeholder(Placeholder { id, data_type }) = expr {
+                                let typ = types.entry(id.clone()).or_insert(None);
+                                if typ.is_none() {
+                                    *typ = data_type.clone();
+                                }
+                            }
+                            Ok(VisitRecursion::Continue)
+                        })?;
+                    }
+                }
+
+                types
+            }
             _ => HashMap::new(),
         })
     }
 
     /// Replace placeholders in this plan with the provided scalars.
     ///
-    /// Note this currently only replaces placeholders for datafusion plans.
+    /// Note this currently only replaces placeholders for datafusion plans,
+    /// and UPDATE and DELETE plans.
     pub fn replace_placeholders(&mut self, scalars: Vec<ScalarValue>) -> Result<()> {
         if let LogicalPlan::Datafusion(plan) = self {
             // Replace placeholders in the inner plan if the wrapped in an
@@ -182,12 +242,89 @@ impl LogicalPlan {
             }
 
             *plan = plan.replace_params_with_values(&scalars)?;
+
+            if let DfLogicalPlan::Extension(ext) = plan {
+                if let Some(replaced) = replace_dml_placeholders(ext, &scalars)? {
+                    *plan = DfLogicalPlan::Extension(replaced);
+                }
+            }
         }
 
         Ok(())
     }
 }
 
+/// Get the expressions of UPDATE and DELETE plans.
+///
+/// These aren't exposed to datafusion through `expressions` since they
+/// reference columns of the table being modified instead of an input.
+fn dml_expressions(ext: &Extension) -> Vec<&Expr> {
+    let node = ext.node.as_any();
+    if let Some(update) = node.downcast_ref::<Update>() {
+        update
+            .updates
+            .iter()
+            .map(|(_, expr)| expr)
+            .chain(update.where_expr.as_ref())
+            .collect()
+    } else if let Some(delete) = node.downcast_ref::<Delete>() {
+        delete.where_expr.iter().collect()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Replace placeholders in UPDATE and DELETE plans.
+///
+/// Returns `None` if the extension isn't one of those plans.
+fn replace_dml_placeholders(ext: &Extension, scalars: &[ScalarValue]) -> Result<Option<Extension>> {
+    let node = ext.node.as_any();
+    let replace = |expr: &Expr| replace_placeholder_values(expr.clone(), scalars);
+
+    if let Some(update) = node.downcast_ref::<Update>() {
+        let updates = update
+            .updates
+            .iter()
+            .map(|(column, expr)| Ok((column.clone(), replace(expr)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let update = Update {
+            table: update.table.clone(),
+            updates,
+            where_expr: update.where_expr.as_ref().map(replace).transpose()?,
+        };
+        return Ok(Some(update.into_extension()));
+    }
+
+    if let Some(delete) = node.downcast_ref::<Delete>() {
+        let delete = Delete {
+            table: delete.table.clone(),
+            where_expr: delete.where_expr.as_ref().map(replace).transpose()?,
+        };
+        return Ok(Some(delete.into_extension()));
+    }
+
+    Ok(None)
+}
+
+/// Replace placeholders (`$1`, `$2`, ...) in an expression with literals.
+fn replace_placeholder_values(expr: Expr, scalars: &[ScalarValue]) -> Result<Expr> {
+    let expr = expr.transform(&|expr| {
+        if let Expr::Placeholder(Placeholder { id, .. }) = &expr {
+            let value = id
+                .strip_prefix('$')
+                .and_then(|idx| idx.parse::<usize>().ok())
+                .and_then(|idx| idx.checked_sub(1))
+                .and_then(|idx| scalars.get(idx))
+                .ok_or_else(|| {
+                    DataFusionError::Plan(format!("No value found for placeholder with id {id}"))
+                })?;
+            return Ok(Transformed::Yes(Expr::Literal(value.clone())));
+        }
+        Ok(Transformed::No(expr))
+    })?;
+    Ok(expr)
+}
+
 impl From<DfLogicalPlan> for LogicalPlan {
     fn from(plan: DfLogicalPlan) -> Self {
         LogicalPlan::Datafusion(plan)
@@ -284,3 +421,31 @@ impl From<TransactionPlan> for LogicalPlan {
         LogicalPlan::Transaction(plan)
     }
 }
+
+#[derive(Clone, Debug)]
+pub enum CursorPlan {
+    /// Create a cursor for a query.
+    Declare {
+        name: String,
+        /// Keep the cursor open after the transaction ends.
+        hold: bool,
+        plan: DfLogicalPlan,
+    },
+    /// Fetch rows from a cursor.
+    Fetch {
+        name: String,
+        /// Number of rows to fetch. Fetches all remaining rows if `None`.
+        count: Option<usize>,
+        /// Schema of the cursor at the time of planning. `None` if the cursor
+        /// doesn't exist, in which case executing the plan errors.
+        schema: Option<ArrowSchema>,
+    },
+    /// Close a cursor, or all cursors if no name is provided.
+    Close { name: Option<String> },
+}
+
+impl From<CursorPlan> for LogicalPlan {
+    fn from(plan: CursorPlan) -> Self {
+        LogicalPlan::Cursor(plan)
+    }
+}
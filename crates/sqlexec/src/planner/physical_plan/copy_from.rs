@@ -0,0 +1,104 @@
+use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::{
+    stream::RecordBatchStreamAdapter, DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning,
+    SendableRecordBatchStream, Statistics,
+};
+use futures::stream;
+use parking_lot::Mutex;
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Source for `COPY ... FROM STDIN`, producing the batches decoded from the
+/// client as they're received.
+///
+/// Can only be executed once.
+#[derive(Debug)]
+pub struct CopyFromStdinExec {
+    schema: Arc<Schema>,
+    batches: Mutex<Option<mpsc::Receiver<RecordBatch>>>,
+}
+
+impl CopyFromStdinExec {
+    pub fn new(schema: Arc<Schema>, batches: mpsc::Receiver<RecordBatch>) -> Self {
+        CopyFromStdinExec {
+            schema,
+            batches: Mutex::new(Some(batches)),
+        }
+    }
+}
+
+impl ExecutionPlan for CopyFromStdinExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        Vec::new()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        if children.is_empty() {
+            Ok(self)
+        } else {
+            Err(DataFusionError::Plan(
+                "Cannot change children for CopyFromStdinExec".to_string(),
+            ))
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> DataFusionResult<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Execution(
+                "CopyFromStdinExec only supports 1 partition".to_string(),
+            ));
+        }
+
+        let batches = self.batches.lock().take().ok_or_else(|| {
+            DataFusionError::Execution("CopyFromStdinExec already executed".to_string())
+        })?;
+
+        let stream = stream::unfold(batches, |mut batches| async move {
+            batches.recv().await.map(|batch| (Ok(batch), batches))
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            stream,
+        )))
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+impl DisplayAs for CopyFromStdinExec {
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CopyFromStdinExec")
+    }
+}
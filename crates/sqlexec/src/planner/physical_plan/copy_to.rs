@@ -10,11 +10,14 @@ use datafusion::physical_plan::{
     SendableRecordBatchStream, Statistics,
 };
 use datafusion_ext::metrics::WriteOnlyDataSourceMetricsExecAdapter;
+use datasources::common::sink::arrow::ArrowSink;
 use datasources::common::sink::bson::BsonSink;
 use datasources::common::sink::csv::{CsvSink, CsvSinkOpts};
 use datasources::common::sink::json::{JsonSink, JsonSinkOpts};
 use datasources::common::sink::parquet::{ParquetSink, ParquetSinkOpts};
 use datasources::common::url::DatasourceUrl;
+use datasources::lake::delta::sink::DeltaSink;
+use datasources::lake::iceberg::sink::IcebergSink;
 use datasources::object_store::gcs::GcsStoreAccess;
 use datasources::object_store::generic::GenericStoreAccess;
 use datasources::object_store::local::LocalStoreAccess;
@@ -22,11 +25,13 @@ use datasources::object_store::s3::S3StoreAccess;
 use datasources::object_store::ObjStoreAccess;
 use futures::stream;
 use object_store::azure::AzureConfigKey;
+use object_store::ObjectStore;
 use protogen::metastore::types::options::{
     CopyToDestinationOptions, CopyToFormatOptions, StorageOptions,
 };
 use std::any::Any;
 use std::fmt;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use super::{new_operation_with_count_batch, GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA};
@@ -105,59 +110,19 @@ impl DisplayAs for CopyToExec {
 
 impl CopyToExec {
     async fn copy_to(self, context: Arc<TaskContext>) -> DataFusionResult<RecordBatch> {
-        let sink = match (self.dest, self.format) {
-            (CopyToDestinationOptions::Local(local_options), format) => {
-                {
+        let sink = match self.format {
+            // Iceberg and delta tables are directories, and the table handles
+            // creating everything it needs.
+            CopyToFormatOptions::Iceberg => get_iceberg_sink(&self.dest)?,
+            CopyToFormatOptions::Delta => get_delta_sink(&self.dest)?,
+            format => {
+                if let CopyToDestinationOptions::Local(local_options) = &self.dest {
                     // Create the path if it doesn't exist (for local).
                     let _ = tokio::fs::File::create(&local_options.location).await?;
                 }
-                let access = LocalStoreAccess;
-                get_sink_for_obj(format, &access, &local_options.location)?
-            }
-            (CopyToDestinationOptions::Gcs(gcs_options), format) => {
-                let access = GcsStoreAccess {
-                    bucket: gcs_options.bucket,
-                    service_account_key: gcs_options.service_account_key,
-                };
-                get_sink_for_obj(format, &access, &gcs_options.location)?
-            }
-            (CopyToDestinationOptions::S3(s3_options), format) => {
-                let access = S3StoreAccess {
-                    region: s3_options.region,
-                    bucket: s3_options.bucket,
-                    access_key_id: s3_options.access_key_id,
-                    secret_access_key: s3_options.secret_access_key,
-                };
-                get_sink_for_obj(format, &access, &s3_options.location)?
-            }
-            (CopyToDestinationOptions::Azure(azure_options), format) => {
-                // Create storage options using well-known key names.
-                let opts = StorageOptions::new_from_iter([
-                    (AzureConfigKey::AccountName.as_ref(), azure_options.account),
-                    (AzureConfigKey::AccessKey.as_ref(), azure_options.access_key),
-                ]);
-                let access =
-                    GenericStoreAccess::new_from_location_and_opts(&azure_options.location, opts)
-                        .map_err(|e| DataFusionError::External(Box::new(e)))?;
-
-                // TODO: It's weird we need to do this here, but
-                // `get_sink_for_obj` is expected a path relative to the root of
-                // the store. The location we have here is the full url
-                // (azure://...) and so will actually cause object store to
-                // error.
-                //
-                // By converting to a data source url, we can get the path we
-                // need.
-                //
-                // @vaibhav I'd like for us to look into switchin all object
-                // store "locations" to use the full url (with scheme) so that
-                // we can be consistent with this. It would also help with user
-                // experience since they wouldn't need to know which part of the
-                // location is the "bucket" and which is the "location" (path).
-                let source_url = DatasourceUrl::try_new(&azure_options.location)
-                    .map_err(|e| DataFusionError::External(Box::new(e)))?;
 
-                get_sink_for_obj(format, &access, &source_url.path())?
+                let (access, path) = get_access_and_path(&self.dest)?;
+                get_sink_for_obj(format, access.as_ref(), &path)?
             }
         };
 
@@ -168,6 +133,66 @@ impl CopyToExec {
     }
 }
 
+/// Get the object store access for a copy location, along with the path to
+/// the object relative to the root of the store.
+pub fn get_access_and_path(
+    dest: &CopyToDestinationOptions,
+) -> DataFusionResult<(Arc<dyn ObjStoreAccess>, String)> {
+    Ok(match dest {
+        CopyToDestinationOptions::Local(local_options) => {
+            (Arc::new(LocalStoreAccess), local_options.location.clone())
+        }
+        CopyToDestinationOptions::Gcs(gcs_options) => {
+            let access = GcsStoreAccess {
+                bucket: gcs_options.bucket.clone(),
+                service_account_key: gcs_options.service_account_key.clone(),
+            };
+            (Arc::new(access), gcs_options.location.clone())
+        }
+        CopyToDestinationOptions::S3(s3_options) => {
+            let access = S3StoreAccess {
+                region: s3_options.region.clone(),
+                bucket: s3_options.bucket.clone(),
+                access_key_id: s3_options.access_key_id.clone(),
+                secret_access_key: s3_options.secret_access_key.clone(),
+            };
+            (Arc::new(access), s3_options.location.clone())
+        }
+        CopyToDestinationOptions::Azure(azure_options) => {
+            // Create storage options using well-known key names.
+            let opts = StorageOptions::new_from_iter([
+                (AzureConfigKey::AccountName.as_ref(), &azure_options.account),
+                (
+                    AzureConfigKey::AccessKey.as_ref(),
+                    &azure_options.access_key,
+                ),
+            ]);
+            let access =
+                GenericStoreAccess::new_from_location_and_opts(&azure_options.location, opts)
+                    .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+            // TODO: It's weird we need to do this here, but
+            // `get_sink_for_obj` is expected a path relative to the root of
+            // the store. The location we have here is the full url
+            // (azure://...) and so will actually cause object store to
+            // error.
+            //
+            // By converting to a data source url, we can get the path we
+            // need.
+            //
+            // @vaibhav I'd like for us to look into switchin all object
+            // store "locations" to use the full url (with scheme) so that
+            // we can be consistent with this. It would also help with user
+            // experience since they wouldn't need to know which part of the
+            // location is the "bucket" and which is the "location" (path).
+            let source_url = DatasourceUrl::try_new(&azure_options.location)
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+            (Arc::new(access), source_url.path().into_owned())
+        }
+    })
+}
+
 /// Get a sink for writing a file to.
 fn get_sink_for_obj(
     format: CopyToFormatOptions,
@@ -205,6 +230,60 @@ fn get_sink_for_obj(
             },
         )),
         CopyToFormatOptions::Bson => Box::new(BsonSink::from_obj_store(store, path)),
+        CopyToFormatOptions::Arrow => Box::new(ArrowSink::from_obj_store(store, path)),
+        CopyToFormatOptions::Iceberg | CopyToFormatOptions::Delta => {
+            return Err(DataFusionError::Internal(format!(
+                "{} sinks cannot be created for a single object",
+                format.as_str()
+            )))
+        }
     };
     Ok(sink)
 }
+
+/// Get a sink for appending to an iceberg table at the copy destination.
+fn get_iceberg_sink(dest: &CopyToDestinationOptions) -> DataFusionResult<Box<dyn DataSink>> {
+    let (url, store) = get_table_url_and_store(dest)?;
+    Ok(Box::new(IcebergSink::new(url, store)))
+}
+
+/// Get a sink for appending to a delta table at the copy destination.
+fn get_delta_sink(dest: &CopyToDestinationOptions) -> DataFusionResult<Box<dyn DataSink>> {
+    let (url, store) = get_table_url_and_store(dest)?;
+    Ok(Box::new(DeltaSink::new(url, store)))
+}
+
+/// Get the url of a table (a directory) at the copy destination, along with
+/// the store the table lives in.
+fn get_table_url_and_store(
+    dest: &CopyToDestinationOptions,
+) -> DataFusionResult<(DatasourceUrl, Arc<dyn ObjectStore>)> {
+    let url = match dest {
+        CopyToDestinationOptions::Local(local_options) => {
+            DatasourceUrl::File(PathBuf::from(&local_options.location))
+        }
+        CopyToDestinationOptions::Gcs(gcs_options) => DatasourceUrl::try_new(format!(
+            "gs://{}/{}",
+            gcs_options.bucket,
+            gcs_options.location.trim_start_matches('/')
+        ))
+        .map_err(|e| DataFusionError::External(Box::new(e)))?,
+        CopyToDestinationOptions::S3(s3_options) => DatasourceUrl::try_new(format!(
+            "s3://{}/{}",
+            s3_options.bucket,
+            s3_options.location.trim_start_matches('/')
+        ))
+        .map_err(|e| DataFusionError::External(Box::new(e)))?,
+        CopyToDestinationOptions::Azure(azure_options) => {
+            DatasourceUrl::try_new(&azure_options.location)
+                .map_err(|e| DataFusionError::External(Box::new(e)))?
+        }
+    };
+
+    let (access, _) = get_access_and_path(dest)?;
+    let store = access
+        .create_store()
+        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+    Ok((url, store))
+}
@@ -18,6 +18,7 @@ use datafusion::{
 };
 use datasources::native::access::{NativeTable, NativeTableStorage, SaveMode};
 use futures::stream;
+use protogen::metastore::types::options::TableOptionsInternal;
 use protogen::metastore::types::{service, service::Mutation};
 use sqlbuiltins::builtins::DEFAULT_CATALOG;
 use tracing::debug;
@@ -37,6 +38,7 @@ pub struct CreateTableExec {
     pub or_replace: bool,
     pub arrow_schema: SchemaRef,
     pub source: Option<Arc<dyn ExecutionPlan>>,
+    pub partition_by: Vec<String>,
 }
 
 impl ExecutionPlan for CreateTableExec {
@@ -74,6 +76,7 @@ impl ExecutionPlan for CreateTableExec {
             or_replace: self.or_replace,
             arrow_schema: self.arrow_schema.clone(),
             source: children.first().cloned(),
+            partition_by: self.partition_by.clone(),
         }))
     }
 
@@ -133,7 +136,10 @@ impl CreateTableExec {
                 [Mutation::CreateTable(service::CreateTable {
                     schema: self.tbl_reference.schema.clone().into_owned(),
                     name: self.tbl_reference.name.clone().into_owned(),
-                    options: self.arrow_schema.into(),
+                    options: TableOptionsInternal {
+                        partition_by: self.partition_by,
+                        ..self.arrow_schema.into()
+                    },
                     if_not_exists,
                     or_replace,
                 })],
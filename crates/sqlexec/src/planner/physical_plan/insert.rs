@@ -26,6 +26,7 @@ use super::{new_operation_with_count_batch, GENERIC_OPERATION_AND_COUNT_PHYSICAL
 pub struct InsertExec {
     pub provider: ProviderReference,
     pub source: Arc<WriteOnlyDataSourceMetricsExecAdapter>,
+    pub overwrite: bool,
 }
 
 impl ExecutionPlan for InsertExec {
@@ -58,6 +59,7 @@ impl ExecutionPlan for InsertExec {
             source: Arc::new(WriteOnlyDataSourceMetricsExecAdapter::new(
                 children.first().unwrap().clone(),
             )),
+            overwrite: self.overwrite,
         }))
     }
 
@@ -80,7 +82,7 @@ impl ExecutionPlan for InsertExec {
                 )),
                 ProviderReference::Provider(provider) => {
                     // TODO: Add background job to track storage for native tables.
-                    Self::do_insert(provider, this.source, context).await
+                    Self::do_insert(provider, this.source, this.overwrite, context).await
                 }
             }
         });
@@ -106,6 +108,7 @@ impl InsertExec {
     pub async fn do_insert(
         table: Arc<dyn TableProvider>,
         source: Arc<dyn ExecutionPlan>,
+        overwrite: bool,
         context: Arc<TaskContext>,
     ) -> DataFusionResult<RecordBatch> {
         let state = SessionState::new_with_config_rt(
@@ -119,7 +122,7 @@ impl InsertExec {
             source
         };
 
-        let exec = table.insert_into(&state, source, false).await?;
+        let exec = table.insert_into(&state, source, overwrite).await?;
 
         let mut stream = exec.execute(0, context)?;
 
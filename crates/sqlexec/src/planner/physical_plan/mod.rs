@@ -3,11 +3,13 @@ pub mod alter_table;
 pub mod alter_tunnel_rotate_keys;
 pub mod client_recv;
 pub mod client_send;
+pub mod copy_from;
 pub mod copy_to;
 pub mod create_credential;
 pub mod create_credentials;
 pub mod create_external_database;
 pub mod create_external_table;
+pub mod create_role;
 pub mod create_schema;
 pub mod create_table;
 pub mod create_temp_table;
@@ -17,18 +19,23 @@ pub mod delete;
 pub mod describe_table;
 pub mod drop_credentials;
 pub mod drop_database;
+pub mod drop_roles;
 pub mod drop_schemas;
 pub mod drop_tables;
 pub mod drop_temp_tables;
 pub mod drop_tunnel;
 pub mod drop_views;
+pub mod grant_privileges;
 pub mod insert;
+pub mod optimize_table;
 pub mod remote_exec;
 pub mod remote_scan;
+pub mod revoke_privileges;
 pub mod send_recv;
 pub mod set_var;
 pub mod show_var;
 pub mod update;
+pub mod vacuum_table;
 pub mod values;
 
 use datafusion::arrow::array::{StringArray, UInt64Array};
@@ -9,6 +9,7 @@ use datafusion::physical_plan::{
     DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
     Statistics,
 };
+use datafusion_ext::vars::SessionVars;
 use futures::{stream, Stream, StreamExt, TryStreamExt};
 use protogen::gen::rpcsrv::service::RecordBatchResponse;
 use std::any::Any;
@@ -21,6 +22,7 @@ use std::task::{Context, Poll};
 use tonic::Streaming;
 
 use crate::remote::client::RemoteSessionClient;
+use crate::timeout::with_statement_timeout;
 
 /// Execute a physical plan on a remote service.
 #[derive(Debug, Clone)]
@@ -81,7 +83,7 @@ impl ExecutionPlan for RemoteExecutionExec {
     fn execute(
         &self,
         partition: usize,
-        _context: Arc<TaskContext>,
+        context: Arc<TaskContext>,
     ) -> DataFusionResult<SendableRecordBatchStream> {
         // TODO: Behavior is unknown when executing with more than one
         // partition.
@@ -95,10 +97,19 @@ impl ExecutionPlan for RemoteExecutionExec {
             self.query_text.clone(),
         ))
         .try_flatten();
-        Ok(Box::pin(RecordBatchStreamAdapter::new(
-            self.schema(),
-            stream,
-        )))
+        let stream = Box::pin(RecordBatchStreamAdapter::new(self.schema(), stream));
+
+        // Enforce the statement timeout on the remote stream directly so that
+        // the request to the remote node is dropped once the deadline passes.
+        match context
+            .session_config()
+            .options()
+            .extensions
+            .get::<SessionVars>()
+        {
+            Some(vars) => Ok(with_statement_timeout(stream, vars)),
+            None => Ok(stream),
+        }
     }
 
     fn statistics(&self) -> Statistics {
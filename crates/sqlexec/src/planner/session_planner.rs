@@ -3,11 +3,17 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use datafusion::arrow::datatypes::{
-    DataType, Field, Schema, TimeUnit, DECIMAL128_MAX_PRECISION, DECIMAL_DEFAULT_SCALE,
+    DataType, Field, Schema, SchemaRef, TimeUnit, DECIMAL128_MAX_PRECISION, DECIMAL_DEFAULT_SCALE,
 };
 use datafusion::common::parsers::CompressionTypeVariant;
-use datafusion::common::{FileType, OwnedSchemaReference, OwnedTableReference, ToDFSchema};
-use datafusion::logical_expr::{cast, col, LogicalPlanBuilder};
+use datafusion::common::{Column, OwnedSchemaReference, OwnedTableReference, ToDFSchema};
+use datafusion::datasource::file_format::arrow::ArrowFormat;
+use datafusion::datasource::file_format::csv::CsvFormat;
+use datafusion::datasource::file_format::json::JsonFormat;
+use datafusion::datasource::file_format::parquet::ParquetFormat;
+use datafusion::datasource::file_format::FileFormat;
+use datafusion::datasource::provider_as_source;
+use datafusion::logical_expr::{cast, col, Expr, LogicalPlanBuilder};
 use datafusion::sql::planner::{object_name_to_table_reference, IdentNormalizer, PlannerContext};
 use datafusion::sql::sqlparser::ast::{self, Ident, ObjectName, ObjectType};
 use datafusion::sql::TableReference;
@@ -26,16 +32,18 @@ use datasources::mysql::{MysqlAccessor, MysqlDbConnection, MysqlTableAccess};
 use datasources::object_store::gcs::GcsStoreAccess;
 use datasources::object_store::generic::GenericStoreAccess;
 use datasources::object_store::local::LocalStoreAccess;
+use datasources::object_store::partition::PartitionColumns;
 use datasources::object_store::s3::S3StoreAccess;
-use datasources::object_store::{file_type_from_path, ObjStoreAccess, ObjStoreAccessor};
+use datasources::object_store::{file_type_from_path, FileType, ObjStoreAccess, ObjStoreAccessor};
 use datasources::postgres::{PostgresAccess, PostgresDbConnection};
 use datasources::snowflake::{SnowflakeAccessor, SnowflakeDbConnection, SnowflakeTableAccess};
+use datasources::sqlite::SqliteAccess;
 use datasources::sqlserver::SqlServerAccess;
 use object_store::aws::AmazonS3ConfigKey;
 use object_store::azure::AzureConfigKey;
 use object_store::gcp::GoogleConfigKey;
 use protogen::metastore::types::catalog::{
-    CatalogEntry, DatabaseEntry, RuntimePreference, SourceAccessMode, TableEntry,
+    CatalogEntry, DatabaseEntry, Privilege, RuntimePreference, SourceAccessMode, TableEntry,
 };
 use protogen::metastore::types::options::{
     CopyToDestinationOptions, CopyToDestinationOptionsAzure, CopyToDestinationOptionsGcs,
@@ -44,14 +52,16 @@ use protogen::metastore::types::options::{
     CredentialsOptions, CredentialsOptionsAws, CredentialsOptionsAzure, CredentialsOptionsDebug,
     CredentialsOptionsGcp, DatabaseOptions, DatabaseOptionsBigQuery, DatabaseOptionsClickhouse,
     DatabaseOptionsDebug, DatabaseOptionsDeltaLake, DatabaseOptionsMongoDb, DatabaseOptionsMysql,
-    DatabaseOptionsPostgres, DatabaseOptionsSnowflake, DatabaseOptionsSqlServer, DeltaLakeCatalog,
-    DeltaLakeUnityCatalog, StorageOptions, TableOptions, TableOptionsBigQuery,
-    TableOptionsClickhouse, TableOptionsDebug, TableOptionsGcs, TableOptionsLocal,
-    TableOptionsMongoDb, TableOptionsMysql, TableOptionsObjectStore, TableOptionsPostgres,
-    TableOptionsS3, TableOptionsSnowflake, TableOptionsSqlServer, TunnelOptions,
-    TunnelOptionsDebug, TunnelOptionsInternal, TunnelOptionsSsh,
+    DatabaseOptionsPostgres, DatabaseOptionsSnowflake, DatabaseOptionsSqlServer,
+    DatabaseOptionsSqlite, DeltaLakeCatalog, DeltaLakeUnityCatalog, StorageOptions, TableOptions,
+    TableOptionsBigQuery, TableOptionsClickhouse, TableOptionsDebug, TableOptionsGcs,
+    TableOptionsLocal, TableOptionsMongoDb, TableOptionsMysql, TableOptionsObjectStore,
+    TableOptionsPostgres, TableOptionsS3, TableOptionsSnowflake, TableOptionsSqlServer,
+    TableOptionsSqlite, TunnelOptions, TunnelOptionsDebug, TunnelOptionsInternal, TunnelOptionsSsh,
+};
+use protogen::metastore::types::service::{
+    AlterDatabaseOperation, AlterTableOperation, PrivilegeObject,
 };
-use protogen::metastore::types::service::{AlterDatabaseOperation, AlterTableOperation};
 use sqlbuiltins::builtins::{CURRENT_SESSION_SCHEMA, DEFAULT_CATALOG};
 use sqlbuiltins::validation::{
     validate_copyto_dest_creds_support, validate_copyto_dest_format_support,
@@ -61,12 +71,14 @@ use sqlbuiltins::validation::{
 use tracing::debug;
 
 use crate::context::local::LocalSessionContext;
+use crate::copy_in::CopyInFormat;
 use crate::parser::options::StmtOptions;
 use crate::parser::{
     self, validate_ident, validate_object_name, AlterDatabaseStmt, AlterTableStmtExtension,
-    AlterTunnelAction, AlterTunnelStmt, CopyToSource, CopyToStmt, CreateCredentialStmt,
-    CreateCredentialsStmt, CreateExternalDatabaseStmt, CreateExternalTableStmt, CreateTunnelStmt,
-    DropCredentialsStmt, DropDatabaseStmt, DropTunnelStmt, StatementWithExtensions,
+    AlterTunnelAction, AlterTunnelStmt, CopyFromSource, CopyFromStmt, CopyToSource, CopyToStmt,
+    CreateCredentialStmt, CreateCredentialsStmt, CreateExternalDatabaseStmt,
+    CreateExternalTableStmt, CreatePartitionedTableStmt, CreateTunnelStmt, DropCredentialsStmt,
+    DropDatabaseStmt, DropTunnelStmt, OptimizeTableStmt, StatementWithExtensions, VacuumTableStmt,
 };
 use crate::planner::errors::{internal, PlanError, Result};
 use crate::planner::logical_plan::*;
@@ -76,11 +88,15 @@ use crate::resolve::{EntryResolver, ResolvedEntry};
 
 use super::context_builder::PartialContextProvider;
 use super::extension::ExtensionNode;
+use super::physical_plan::copy_to::get_access_and_path;
 use super::physical_plan::remote_scan::ProviderReference;
 
 /// Plan SQL statements for a session.
 pub struct SessionPlanner<'a> {
     ctx: &'a LocalSessionContext,
+    /// Types of the parameters provided by the client, ordered by parameter
+    /// index.
+    param_types: Vec<DataType>,
 }
 
 struct PlanCredentialArgs {
@@ -121,14 +137,34 @@ impl From<CreateCredentialStmt> for PlanCredentialArgs {
 
 impl<'a> SessionPlanner<'a> {
     pub fn new(ctx: &'a LocalSessionContext) -> Self {
-        SessionPlanner { ctx }
+        SessionPlanner {
+            ctx,
+            param_types: Vec::new(),
+        }
+    }
+
+    /// Use the provided types for parameters when planning statements instead
+    /// of inferring them.
+    pub fn with_param_types(mut self, param_types: Vec<DataType>) -> Self {
+        self.param_types = param_types;
+        self
+    }
+
+    /// Create a planner context with the provided parameter types.
+    fn planner_context(&self) -> PlannerContext {
+        PlannerContext::new().with_prepare_param_data_types(self.param_types.clone())
     }
 
     pub async fn plan_ast(&self, mut statement: StatementWithExtensions) -> Result<LogicalPlan> {
         debug!(%statement, "planning sql statement");
 
         // Run replacers as needed.
-        if let StatementWithExtensions::Statement(inner) = &mut statement {
+        if let StatementWithExtensions::Statement(inner)
+        | StatementWithExtensions::CreatePartitionedTable(CreatePartitionedTableStmt {
+            create: inner,
+            ..
+        }) = &mut statement
+        {
             preprocess(inner, &mut CastRegclassReplacer { ctx: self.ctx })?;
             preprocess(inner, &mut EscapedStringToDoubleQuoted)?;
         }
@@ -157,6 +193,12 @@ impl<'a> SessionPlanner<'a> {
             }
             StatementWithExtensions::DropCredentials(stmt) => self.plan_drop_credentials(stmt),
             StatementWithExtensions::CopyTo(stmt) => self.plan_copy_to(stmt).await,
+            StatementWithExtensions::CopyFrom(stmt) => self.plan_copy_from(stmt).await,
+            StatementWithExtensions::CreatePartitionedTable(stmt) => {
+                self.plan_create_table(stmt.create, stmt.partition_by).await
+            }
+            StatementWithExtensions::OptimizeTable(stmt) => self.plan_optimize_table(stmt),
+            StatementWithExtensions::VacuumTable(stmt) => self.plan_vacuum_table(stmt),
         }
     }
 
@@ -164,6 +206,8 @@ impl<'a> SessionPlanner<'a> {
         &self,
         mut stmt: CreateExternalDatabaseStmt,
     ) -> Result<LogicalPlan> {
+        self.require_superuser("create external databases")?;
+
         let datasource = normalize_ident(stmt.datasource);
 
         let tunnel = stmt.tunnel.map(normalize_ident);
@@ -317,6 +361,25 @@ impl<'a> SessionPlanner<'a> {
 
                 DatabaseOptions::Clickhouse(DatabaseOptionsClickhouse { connection_string })
             }
+            DatabaseOptions::SQLITE => {
+                let location: String = m.remove_required("location")?;
+                let mut storage_options = StorageOptions::try_from(m)?;
+                if let Some(creds) = creds_options {
+                    storage_options_with_credentials(&mut storage_options, creds);
+                }
+
+                // Validate
+                let access = SqliteAccess {
+                    location: location.clone(),
+                    storage_options: storage_options.clone(),
+                };
+                access.validate_access().await?;
+
+                DatabaseOptions::Sqlite(DatabaseOptionsSqlite {
+                    location,
+                    storage_options,
+                })
+            }
             DatabaseOptions::DEBUG => {
                 datasources::debug::validate_tunnel_connections(tunnel_options.as_ref())?;
                 DatabaseOptions::Debug(DatabaseOptionsDebug {})
@@ -524,17 +587,41 @@ impl<'a> SessionPlanner<'a> {
                     table: table_name,
                 })
             }
+            TableOptions::SQLITE => {
+                let location: String = m.remove_required("location")?;
+                let table_name: String = m.remove_required("table")?;
+                let mut storage_options = StorageOptions::try_from(m)?;
+                if let Some(creds) = creds_options {
+                    storage_options_with_credentials(&mut storage_options, creds);
+                }
+
+                // Validate
+                let access = SqliteAccess {
+                    location: location.clone(),
+                    storage_options: storage_options.clone(),
+                };
+                access.validate_table_access(&table_name).await?;
+
+                TableOptions::Sqlite(TableOptionsSqlite {
+                    location,
+                    storage_options,
+                    table: table_name,
+                })
+            }
             TableOptions::LOCAL => {
                 let location: String = m.remove_required("location")?;
 
+                let partition_columns = remove_partition_columns(m)?;
+
                 let access = Arc::new(LocalStoreAccess);
                 let (file_type, compression) =
                     validate_and_get_file_type_and_compression(access, &location, m).await?;
 
                 TableOptions::Local(TableOptionsLocal {
                     location,
-                    file_type: format!("{file_type:?}").to_lowercase(),
+                    file_type: file_type.to_string(),
                     compression: compression.map(|c| c.to_string()),
+                    partition_columns,
                 })
             }
             TableOptions::GCS => {
@@ -548,6 +635,7 @@ impl<'a> SessionPlanner<'a> {
 
                 let bucket: String = m.remove_required("bucket")?;
                 let location: String = m.remove_required("location")?;
+                let partition_columns = remove_partition_columns(m)?;
 
                 let access = Arc::new(GcsStoreAccess {
                     bucket: bucket.clone(),
@@ -562,6 +650,7 @@ impl<'a> SessionPlanner<'a> {
                     location,
                     file_type: file_type.to_string(),
                     compression: compression.map(|c| c.to_string()),
+                    partition_columns,
                 })
             }
             TableOptions::S3_STORAGE => {
@@ -585,6 +674,7 @@ impl<'a> SessionPlanner<'a> {
                 let region: String = m.remove_required("region")?;
                 let bucket: String = m.remove_required("bucket")?;
                 let location: String = m.remove_required("location")?;
+                let partition_columns = remove_partition_columns(m)?;
 
                 let access = Arc::new(S3StoreAccess {
                     region: region.clone(),
@@ -603,6 +693,7 @@ impl<'a> SessionPlanner<'a> {
                     location,
                     file_type: file_type.to_string(),
                     compression: compression.map(|c| c.to_string()),
+                    partition_columns,
                 })
             }
             TableOptions::AZURE => {
@@ -622,6 +713,7 @@ impl<'a> SessionPlanner<'a> {
                 let access_key = m.remove_required_or("access_key", access_key)?;
 
                 let location: String = m.remove_required("location")?;
+                let partition_columns = remove_partition_columns(m)?;
 
                 let mut opts = StorageOptions::default();
                 opts.inner
@@ -652,6 +744,7 @@ impl<'a> SessionPlanner<'a> {
                     file_type: Some(file_type.to_string()),
                     compression: compression.map(|c| c.to_string()),
                     schema_sample_size: None,
+                    partition_columns,
                 })
             }
             TableOptions::DELTA | TableOptions::ICEBERG => {
@@ -671,6 +764,7 @@ impl<'a> SessionPlanner<'a> {
                         file_type: None,
                         compression: None,
                         schema_sample_size: None,
+                        partition_columns: Vec::new(),
                     })
                 } else {
                     let url = DatasourceUrl::try_new(&location)?;
@@ -687,6 +781,7 @@ impl<'a> SessionPlanner<'a> {
                         file_type: None,
                         compression: None,
                         schema_sample_size: None,
+                        partition_columns: Vec::new(),
                     })
                 }
             }
@@ -718,6 +813,7 @@ impl<'a> SessionPlanner<'a> {
                     file_type: None,
                     compression: None,
                     schema_sample_size: None,
+                    partition_columns: Vec::new(),
                 })
             }
             TableOptions::BSON => {
@@ -739,15 +835,18 @@ impl<'a> SessionPlanner<'a> {
                     file_type: None,
                     compression: None,
                     schema_sample_size,
+                    partition_columns: Vec::new(),
                 })
             }
             other => return Err(internal!("unsupported datasource: {}", other)),
         };
 
         let table_name = object_name_to_table_ref(stmt.name)?;
+        let tbl_reference = self.ctx.resolve_table_ref(table_name)?;
+        self.require_schema_privilege(&tbl_reference.schema, Privilege::Create)?;
 
         let plan = CreateExternalTable {
-            tbl_reference: self.ctx.resolve_table_ref(table_name)?,
+            tbl_reference,
             or_replace: stmt.or_replace,
             if_not_exists: stmt.if_not_exists,
             table_options: external_table_options,
@@ -758,6 +857,8 @@ impl<'a> SessionPlanner<'a> {
     }
 
     fn plan_create_tunnel(&self, mut stmt: CreateTunnelStmt) -> Result<LogicalPlan> {
+        self.require_superuser("create tunnels")?;
+
         let m = &mut stmt.options;
 
         let tunnel_type = normalize_ident(stmt.tunnel);
@@ -793,6 +894,8 @@ impl<'a> SessionPlanner<'a> {
         mut stmt: PlanCredentialArgs,
         deprecated: bool,
     ) -> Result<LogicalPlan> {
+        self.require_superuser("create credentials")?;
+
         let m = &mut stmt.options;
 
         let provider = normalize_ident(stmt.provider);
@@ -860,9 +963,64 @@ impl<'a> SessionPlanner<'a> {
             ast::Statement::Commit { .. } => Ok(TransactionPlan::Commit.into()),
             ast::Statement::Rollback { .. } => Ok(TransactionPlan::Abort.into()),
 
+            // DECLARE <name> CURSOR FOR <query>
+            ast::Statement::Declare {
+                name, hold, query, ..
+            } => {
+                let mut planner = SqlQueryPlanner::new(&mut context_provider);
+                let plan = planner
+                    .query_to_plan_with_context(*query, &mut self.planner_context())
+                    .await?;
+                Ok(CursorPlan::Declare {
+                    name: normalize_ident(name),
+                    hold: hold.unwrap_or(false),
+                    plan,
+                }
+                .into())
+            }
+            // FETCH [<direction>] FROM <name>
+            ast::Statement::Fetch {
+                name,
+                direction,
+                into: None,
+            } => {
+                let count = match direction {
+                    ast::FetchDirection::Next | ast::FetchDirection::Forward { limit: None } => {
+                        Some(1)
+                    }
+                    ast::FetchDirection::Count { limit }
+                    | ast::FetchDirection::Forward { limit: Some(limit) } => {
+                        Some(fetch_count(limit)?)
+                    }
+                    ast::FetchDirection::All | ast::FetchDirection::ForwardAll => None,
+                    _ => return Err(PlanError::UnsupportedFeature("FETCH direction")),
+                };
+                let name = normalize_ident(name);
+                let schema = self
+                    .ctx
+                    .get_cursor_schema(&name)
+                    .map(|schema| schema.as_ref().clone());
+                Ok(CursorPlan::Fetch {
+                    name,
+                    count,
+                    schema,
+                }
+                .into())
+            }
+            // CLOSE { <name> | ALL }
+            ast::Statement::Close { cursor } => {
+                let name = match cursor {
+                    ast::CloseCursor::All => None,
+                    ast::CloseCursor::Specific { name } => Some(normalize_ident(name)),
+                };
+                Ok(CursorPlan::Close { name }.into())
+            }
+
             ast::Statement::Query(q) => {
                 let mut planner = SqlQueryPlanner::new(&mut context_provider);
-                let plan = planner.query_to_plan(*q).await?;
+                let plan = planner
+                    .query_to_plan_with_context(*q, &mut self.planner_context())
+                    .await?;
                 Ok(LogicalPlan::Datafusion(plan))
             }
 
@@ -900,6 +1058,8 @@ impl<'a> SessionPlanner<'a> {
                 schema_name,
                 if_not_exists,
             } => {
+                self.require_superuser("create schemas")?;
+
                 // TODO: Schema Authorization
                 let schema_name = match schema_name {
                     ast::SchemaName::Simple(name) => {
@@ -929,108 +1089,11 @@ impl<'a> SessionPlanner<'a> {
             // Normal tables OR Tables generated from a source query.
             // CREATE TABLE
             // CREATE TABLE table2 AS (SELECT * FROM table1);
-            ast::Statement::CreateTable {
+            stmt @ ast::Statement::CreateTable {
                 external: false,
-                if_not_exists,
-                or_replace,
                 engine: None,
-                name,
-                columns,
-                query,
-                temporary,
                 ..
-            } => {
-                validate_object_name(&name)?;
-                let table_name = object_name_to_table_ref(name)?;
-
-                let (source, arrow_cols) = if let Some(q) = query {
-                    let mut ctx = context_provider;
-
-                    let mut planner = SqlQueryPlanner::new(&mut ctx);
-
-                    let source = planner.query_to_plan(*q).await?;
-                    let df_fields = source.schema().fields();
-
-                    let mut columns = columns.into_iter();
-                    let mut fields = Vec::with_capacity(df_fields.len());
-                    for df_field in df_fields {
-                        let field = df_field.field().as_ref().clone();
-                        let field = if let Some(column) = columns.next() {
-                            // If we have a cast for the column, we can update the schema.
-                            validate_ident(&column.name)?;
-                            let name = normalize_ident(column.name);
-                            let data_type = convert_data_type(&column.data_type)?;
-                            field.with_name(name).with_data_type(data_type)
-                        } else {
-                            field
-                        };
-                        fields.push(field);
-                    }
-
-                    // Update the source plan with the new schema casts and alias.
-                    let project_exprs: Vec<_> = fields
-                        .iter()
-                        .zip(df_fields.iter())
-                        .map(|(field, df_field)| {
-                            cast(
-                                col(df_field.unqualified_column()),
-                                field.data_type().clone(),
-                            )
-                            .alias(field.name())
-                        })
-                        .collect();
-
-                    let source = LogicalPlanBuilder::from(source)
-                        .project(project_exprs)?
-                        .build()?;
-
-                    (Some(source), fields)
-                } else {
-                    let mut arrow_cols = Vec::with_capacity(columns.len());
-                    for column in columns.into_iter() {
-                        validate_ident(&column.name)?;
-                        let name = normalize_ident(column.name);
-                        let data_type = convert_data_type(&column.data_type)?;
-                        let field = Field::new(name, data_type, /* nullable = */ true);
-                        arrow_cols.push(field);
-                    }
-                    (None, arrow_cols)
-                };
-
-                if temporary {
-                    let table_name = match table_name {
-                        TableReference::Bare { table } => table.into_owned(),
-                        _ => return Err(internal!("cannot specify schema with temporary tables")),
-                    };
-                    let df_schema = Schema::new(arrow_cols.clone());
-                    let df_schema = df_schema.to_dfschema_ref()?;
-
-                    let plan = CreateTempTable {
-                        tbl_reference: FullObjectReference {
-                            database: DEFAULT_CATALOG.into(),
-                            schema: CURRENT_SESSION_SCHEMA.into(),
-                            name: table_name.into(),
-                        },
-                        schema: df_schema,
-                        if_not_exists,
-                        or_replace,
-                        source,
-                    };
-
-                    Ok(plan.into_logical_plan())
-                } else {
-                    let df_schema = Schema::new(arrow_cols.clone());
-                    let df_schema = df_schema.to_dfschema_ref()?;
-                    let create_table = CreateTable {
-                        tbl_reference: self.ctx.resolve_table_ref(table_name)?,
-                        schema: df_schema,
-                        if_not_exists,
-                        or_replace,
-                        source,
-                    };
-                    Ok(create_table.into_logical_plan())
-                }
-            }
+            } => self.plan_create_table(stmt, Vec::new()).await,
 
             // Views
             ast::Statement::CreateView {
@@ -1044,6 +1107,8 @@ impl<'a> SessionPlanner<'a> {
             } => {
                 validate_object_name(&name)?;
                 let name = object_name_to_table_ref(name)?;
+                let view_reference = self.ctx.resolve_table_ref(name)?;
+                self.require_schema_privilege(&view_reference.schema, Privilege::Create)?;
 
                 if !with_options.is_empty() {
                     return Err(PlanError::UnsupportedFeature("view options"));
@@ -1078,7 +1143,7 @@ impl<'a> SessionPlanner<'a> {
                     })
                 } else {
                     Ok(CreateView {
-                        view_reference: self.ctx.resolve_table_ref(name)?,
+                        view_reference,
                         sql: query_string,
                         columns,
                         or_replace,
@@ -1092,7 +1157,7 @@ impl<'a> SessionPlanner<'a> {
                 into: _,
                 table_name,
                 columns,
-                overwrite: false,
+                overwrite,
                 source,
                 partitioned: None,
                 after_columns,
@@ -1113,45 +1178,17 @@ impl<'a> SessionPlanner<'a> {
 
                 let mut planner = SqlQueryPlanner::new(&mut context_provider);
                 let source = planner
-                    .insert_to_source_plan(&table_name, &columns, source)
+                    .insert_to_source_plan(&table_name, &columns, source, &self.param_types)
                     .await?;
 
-                let access_mode = self
-                    .get_access_mode(table_name.clone())?
-                    .unwrap_or(SourceAccessMode::ReadOnly);
-
-                if !access_mode.has_write_access() {
-                    return Err(PlanError::ObjectNotAllowedToWriteInto(
-                        table_name.to_owned_reference(),
-                    ));
-                }
-
-                let state = self.ctx.df_ctx().state();
-                let mut ctx_provider = PartialContextProvider::new(self.ctx, &state)?;
-
-                let provider = ctx_provider.table_provider(table_name).await?;
-
-                let (runtime_preference, provider) = match (
-                    provider.preference,
-                    provider
-                        .provider
-                        .as_any()
-                        .downcast_ref::<StubRemoteTableProvider>(),
-                ) {
-                    (RuntimePreference::Remote, Some(stub)) => (
-                        RuntimePreference::Remote,
-                        ProviderReference::RemoteReference(stub.id()),
-                    ),
-                    _ => (
-                        RuntimePreference::Local,
-                        ProviderReference::Provider(provider.provider),
-                    ),
-                };
+                let (runtime_preference, provider, _) =
+                    self.plan_insert_target(table_name, overwrite).await?;
 
                 Ok(Insert {
                     source,
                     provider,
                     runtime_preference,
+                    overwrite,
                 }
                 .into_logical_plan())
             }
@@ -1173,6 +1210,7 @@ impl<'a> SessionPlanner<'a> {
                         validate_object_name(&name)?;
                         let name = object_name_to_table_ref(name)?;
                         let name = self.ctx.resolve_table_ref(name)?;
+                        self.require_schema_privilege(&name.schema, Privilege::Create)?;
 
                         let schema = name.schema.into_owned();
                         let name = name.name.into_owned();
@@ -1210,7 +1248,9 @@ impl<'a> SessionPlanner<'a> {
                 for name in names.into_iter() {
                     validate_object_name(&name)?;
                     let r = object_name_to_table_ref(name)?;
-                    refs.push(self.ctx.resolve_table_ref(r)?);
+                    let r = self.ctx.resolve_table_ref(r)?;
+                    self.require_schema_privilege(&r.schema, Privilege::Create)?;
+                    refs.push(r);
                 }
 
                 let plan = DropTables {
@@ -1231,7 +1271,9 @@ impl<'a> SessionPlanner<'a> {
                 for name in names.into_iter() {
                     validate_object_name(&name)?;
                     let r = object_name_to_table_ref(name)?;
-                    refs.push(self.ctx.resolve_table_ref(r)?);
+                    let r = self.ctx.resolve_table_ref(r)?;
+                    self.require_schema_privilege(&r.schema, Privilege::Create)?;
+                    refs.push(r);
                 }
                 Ok(DropViews {
                     if_exists,
@@ -1248,6 +1290,8 @@ impl<'a> SessionPlanner<'a> {
                 names,
                 ..
             } => {
+                self.require_superuser("drop schemas")?;
+
                 let mut refs = Vec::with_capacity(names.len());
                 for name in names.into_iter() {
                     validate_object_name(&name)?;
@@ -1262,6 +1306,105 @@ impl<'a> SessionPlanner<'a> {
                 .into_logical_plan())
             }
 
+            // Drop roles
+            ast::Statement::Drop {
+                object_type: ObjectType::Role,
+                if_exists,
+                names,
+                ..
+            } => {
+                self.require_superuser("drop roles")?;
+
+                let names = names
+                    .into_iter()
+                    .map(object_name_to_role_name)
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(DropRoles { names, if_exists }.into_logical_plan())
+            }
+
+            // CREATE ROLE <name> [WITH] [LOGIN] [SUPERUSER] [PASSWORD '<password>']
+            ast::Statement::CreateRole {
+                names,
+                if_not_exists,
+                login,
+                password,
+                superuser,
+                ..
+            } => {
+                self.require_superuser("create roles")?;
+
+                let name = match <[ObjectName; 1]>::try_from(names) {
+                    Ok([name]) => object_name_to_role_name(name)?,
+                    Err(_) => {
+                        return Err(PlanError::UnsupportedFeature(
+                            "CREATE ROLE with multiple roles",
+                        ))
+                    }
+                };
+                let password_verifier = match password {
+                    Some(ast::Password::Password(ast::Expr::Value(
+                        ast::Value::SingleQuotedString(password),
+                    ))) => password_verifier(&password),
+                    Some(ast::Password::Password(other)) => {
+                        return Err(PlanError::String(format!(
+                            "Password must be a string literal, got: {other}"
+                        )))
+                    }
+                    Some(ast::Password::NullPassword) | None => String::new(),
+                };
+
+                Ok(CreateRole {
+                    name,
+                    password_verifier,
+                    login: login.unwrap_or(false),
+                    superuser: superuser.unwrap_or(false),
+                    if_not_exists,
+                }
+                .into_logical_plan())
+            }
+
+            // GRANT <privileges> ON <objects> TO <roles>
+            ast::Statement::Grant {
+                privileges,
+                objects,
+                grantees,
+                with_grant_option,
+                ..
+            } => {
+                self.require_superuser("grant privileges")?;
+
+                if with_grant_option {
+                    return Err(PlanError::UnsupportedFeature("GRANT ... WITH GRANT OPTION"));
+                }
+                let (objects, privileges) = self.plan_privilege_objects(privileges, objects)?;
+
+                Ok(GrantPrivileges {
+                    roles: plan_grantees(grantees)?,
+                    objects,
+                    privileges,
+                }
+                .into_logical_plan())
+            }
+
+            // REVOKE <privileges> ON <objects> FROM <roles>
+            ast::Statement::Revoke {
+                privileges,
+                objects,
+                grantees,
+                ..
+            } => {
+                self.require_superuser("revoke privileges")?;
+
+                let (objects, privileges) = self.plan_privilege_objects(privileges, objects)?;
+
+                Ok(RevokePrivileges {
+                    roles: plan_grantees(grantees)?,
+                    objects,
+                    privileges,
+                }
+                .into_logical_plan())
+            }
+
             // "SET ...".
             //
             // NOTE: Only session local variables are supported. Transaction
@@ -1341,21 +1484,18 @@ impl<'a> SessionPlanner<'a> {
                     let mut planner = SqlQueryPlanner::new(&mut context_provider);
                     Some(
                         planner
-                            .sql_to_expr(where_expr, &schema, &mut PlannerContext::new())
+                            .sql_to_expr(where_expr, &schema, &mut self.planner_context())
                             .await?,
                     )
                 } else {
                     None
                 };
 
-                let resolver = EntryResolver::from_context(self.ctx);
-                let ent = resolver
-                    .resolve_entry_from_reference(table_name)?
-                    .try_into_table_entry()?;
-                // External deletes not supported yet.
-                if ent.meta.external {
-                    return Err(PlanError::UnsupportedFeature("DELETE with external tables"));
-                }
+                let ent = self.resolve_modify_target(
+                    table_name,
+                    Privilege::Delete,
+                    "DELETE with external tables",
+                )?;
 
                 Ok(Delete {
                     table: ent,
@@ -1389,13 +1529,14 @@ impl<'a> SessionPlanner<'a> {
                 let schema = table_source.schema().to_dfschema()?;
 
                 let mut planner = SqlQueryPlanner::new(&mut context_provider);
+                let mut planner_context = self.planner_context();
                 let mut updates = Vec::new();
 
                 for assignment in assignments {
                     if assignment.id.len() == 1 {
                         let column = assignment.id.last().unwrap().value.clone();
                         let update_value = planner
-                            .sql_to_expr(assignment.value, &schema, &mut PlannerContext::new())
+                            .sql_to_expr(assignment.value, &schema, &mut planner_context)
                             .await?;
                         updates.push((column, update_value));
                     } else {
@@ -1408,21 +1549,18 @@ impl<'a> SessionPlanner<'a> {
                 let where_expr = if let Some(where_expr) = selection {
                     Some(
                         planner
-                            .sql_to_expr(where_expr, &schema, &mut PlannerContext::new())
+                            .sql_to_expr(where_expr, &schema, &mut planner_context)
                             .await?,
                     )
                 } else {
                     None
                 };
 
-                let resolver = EntryResolver::from_context(self.ctx);
-                let ent = resolver
-                    .resolve_entry_from_reference(table_name)?
-                    .try_into_table_entry()?;
-                // External updates not supported yet.
-                if ent.meta.external {
-                    return Err(PlanError::UnsupportedFeature("UPDATE with external tables"));
-                }
+                let ent = self.resolve_modify_target(
+                    table_name,
+                    Privilege::Update,
+                    "UPDATE with external tables",
+                )?;
 
                 Ok(Update {
                     table: ent,
@@ -1437,6 +1575,8 @@ impl<'a> SessionPlanner<'a> {
     }
 
     fn plan_drop_database(&self, stmt: DropDatabaseStmt) -> Result<LogicalPlan> {
+        self.require_superuser("drop databases")?;
+
         let mut names = Vec::with_capacity(stmt.names.len());
         for name in stmt.names.into_iter() {
             validate_ident(&name)?;
@@ -1452,6 +1592,8 @@ impl<'a> SessionPlanner<'a> {
     }
 
     fn plan_drop_tunnel(&self, stmt: DropTunnelStmt) -> Result<LogicalPlan> {
+        self.require_superuser("drop tunnels")?;
+
         let mut names = Vec::with_capacity(stmt.names.len());
         for name in stmt.names.into_iter() {
             validate_ident(&name)?;
@@ -1467,6 +1609,8 @@ impl<'a> SessionPlanner<'a> {
     }
 
     fn plan_drop_credentials(&self, stmt: DropCredentialsStmt) -> Result<LogicalPlan> {
+        self.require_superuser("drop credentials")?;
+
         let mut names = Vec::with_capacity(stmt.names.len());
         for name in stmt.names.into_iter() {
             validate_ident(&name)?;
@@ -1482,6 +1626,8 @@ impl<'a> SessionPlanner<'a> {
     }
 
     fn plan_alter_tunnel(&self, stmt: AlterTunnelStmt) -> Result<LogicalPlan> {
+        self.require_superuser("alter tunnels")?;
+
         validate_ident(&stmt.name)?;
         let name = normalize_ident(stmt.name);
 
@@ -1501,6 +1647,8 @@ impl<'a> SessionPlanner<'a> {
     }
 
     fn plan_alter_database(&self, stmt: AlterDatabaseStmt) -> Result<LogicalPlan> {
+        self.require_superuser("alter databases")?;
+
         validate_ident(&stmt.name)?;
         let name = normalize_ident(stmt.name);
 
@@ -1524,6 +1672,7 @@ impl<'a> SessionPlanner<'a> {
         validate_object_name(&stmt.name)?;
         let name = object_name_to_table_ref(stmt.name)?;
         let name = self.ctx.resolve_table_ref(name)?;
+        self.require_schema_privilege(&name.schema, Privilege::Create)?;
         let schema = name.schema.into_owned();
         let name = name.name.into_owned();
 
@@ -1543,7 +1692,199 @@ impl<'a> SessionPlanner<'a> {
         .into_logical_plan())
     }
 
+    /// Plan a `CREATE TABLE` statement, optionally partitioning the table by
+    /// the provided columns.
+    async fn plan_create_table(
+        &self,
+        statement: ast::Statement,
+        partition_by: Vec<Ident>,
+    ) -> Result<LogicalPlan> {
+        let (if_not_exists, or_replace, name, columns, query, temporary) = match statement {
+            ast::Statement::CreateTable {
+                external: false,
+                if_not_exists,
+                or_replace,
+                engine: None,
+                name,
+                columns,
+                query,
+                temporary,
+                ..
+            } => (if_not_exists, or_replace, name, columns, query, temporary),
+            other => return Err(PlanError::UnsupportedSQLStatement(other.to_string())),
+        };
+
+        validate_object_name(&name)?;
+        let table_name = object_name_to_table_ref(name)?;
+
+        let (source, arrow_cols) = if let Some(q) = query {
+            let state = self.ctx.df_ctx().state();
+            let mut ctx = PartialContextProvider::new(self.ctx, &state)?;
+
+            let mut planner = SqlQueryPlanner::new(&mut ctx);
+
+            let source = planner.query_to_plan(*q).await?;
+            let df_fields = source.schema().fields();
+
+            let mut columns = columns.into_iter();
+            let mut fields = Vec::with_capacity(df_fields.len());
+            for df_field in df_fields {
+                let field = df_field.field().as_ref().clone();
+                let field = if let Some(column) = columns.next() {
+                    // If we have a cast for the column, we can update the schema.
+                    validate_ident(&column.name)?;
+                    let name = normalize_ident(column.name);
+                    let data_type = convert_data_type(&column.data_type)?;
+                    field.with_name(name).with_data_type(data_type)
+                } else {
+                    field
+                };
+                fields.push(field);
+            }
+
+            // Update the source plan with the new schema casts and alias.
+            let project_exprs: Vec<_> = fields
+                .iter()
+                .zip(df_fields.iter())
+                .map(|(field, df_field)| {
+                    cast(
+                        col(df_field.unqualified_column()),
+                        field.data_type().clone(),
+                    )
+                    .alias(field.name())
+                })
+                .collect();
+
+            let source = LogicalPlanBuilder::from(source)
+                .project(project_exprs)?
+                .build()?;
+
+            (Some(source), fields)
+        } else {
+            let mut arrow_cols = Vec::with_capacity(columns.len());
+            for column in columns.into_iter() {
+                validate_ident(&column.name)?;
+                let name = normalize_ident(column.name);
+                let data_type = convert_data_type(&column.data_type)?;
+                let field = Field::new(name, data_type, /* nullable = */ true);
+                arrow_cols.push(field);
+            }
+            (None, arrow_cols)
+        };
+
+        let partition_by = partition_by
+            .into_iter()
+            .map(|ident| {
+                validate_ident(&ident)?;
+                Ok(normalize_ident(ident))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        validate_partition_columns(&arrow_cols, &partition_by)?;
+
+        if temporary {
+            if !partition_by.is_empty() {
+                return Err(PlanError::UnsupportedFeature(
+                    "PARTITION BY with temporary tables",
+                ));
+            }
+            let table_name = match table_name {
+                TableReference::Bare { table } => table.into_owned(),
+                _ => return Err(internal!("cannot specify schema with temporary tables")),
+            };
+            let df_schema = Schema::new(arrow_cols.clone());
+            let df_schema = df_schema.to_dfschema_ref()?;
+
+            let plan = CreateTempTable {
+                tbl_reference: FullObjectReference {
+                    database: DEFAULT_CATALOG.into(),
+                    schema: CURRENT_SESSION_SCHEMA.into(),
+                    name: table_name.into(),
+                },
+                schema: df_schema,
+                if_not_exists,
+                or_replace,
+                source,
+            };
+
+            Ok(plan.into_logical_plan())
+        } else {
+            let tbl_reference = self.ctx.resolve_table_ref(table_name)?;
+            self.require_schema_privilege(&tbl_reference.schema, Privilege::Create)?;
+
+            let df_schema = Schema::new(arrow_cols.clone());
+            let df_schema = df_schema.to_dfschema_ref()?;
+            let create_table = CreateTable {
+                tbl_reference,
+                schema: df_schema,
+                if_not_exists,
+                or_replace,
+                source,
+                partition_by,
+            };
+            Ok(create_table.into_logical_plan())
+        }
+    }
+
+    fn plan_optimize_table(&self, stmt: OptimizeTableStmt) -> Result<LogicalPlan> {
+        self.require_superuser("optimize tables")?;
+
+        let table = self.resolve_native_table(stmt.name, "OPTIMIZE")?;
+
+        let schema = table.get_internal_columns().unwrap_or_default();
+        let mut zorder_by = Vec::with_capacity(stmt.zorder_by.len());
+        for col in stmt.zorder_by {
+            let col = normalize_ident(col);
+            if !schema.iter().any(|c| c.name == col) {
+                return Err(PlanError::String(format!(
+                    "Column '{col}' does not exist in table '{}'",
+                    table.meta.name
+                )));
+            }
+            zorder_by.push(col);
+        }
+
+        Ok(OptimizeTable { table, zorder_by }.into_logical_plan())
+    }
+
+    fn plan_vacuum_table(&self, stmt: VacuumTableStmt) -> Result<LogicalPlan> {
+        self.require_superuser("vacuum tables")?;
+
+        let table = self.resolve_native_table(stmt.name, "VACUUM")?;
+
+        Ok(VacuumTable {
+            table,
+            retain_hours: stmt.retain_hours,
+            skip_retention_check: stmt.skip_retention_check,
+            dry_run: stmt.dry_run,
+        }
+        .into_logical_plan())
+    }
+
+    /// Resolve a table for a maintenance operation, erroring if the table
+    /// isn't backed by native storage.
+    fn resolve_native_table(&self, name: ObjectName, operation: &str) -> Result<TableEntry> {
+        validate_object_name(&name)?;
+        let name = object_name_to_table_ref(name)?;
+
+        let resolver = EntryResolver::from_context(self.ctx);
+        let ent = resolver
+            .resolve_entry_from_reference(name)?
+            .try_into_table_entry()?;
+        if ent.meta.external || ent.meta.is_temp || ent.meta.builtin {
+            return Err(PlanError::String(format!(
+                "{operation} is only supported for native tables, '{}' is not a native table",
+                ent.meta.name
+            )));
+        }
+
+        Ok(ent)
+    }
+
     async fn plan_copy_to(&self, stmt: CopyToStmt) -> Result<LogicalPlan> {
+        // Destinations are files and object stores outside of the catalog, so
+        // there's nothing to grant privileges on.
+        self.require_superuser("copy to external destinations")?;
+
         let query = match stmt.source {
             CopyToSource::Table(table) => {
                 validate_object_name(&table)?;
@@ -1565,8 +1906,384 @@ impl<'a> SessionPlanner<'a> {
 
         let mut m = stmt.options;
 
-        let dest = normalize_ident(stmt.dest);
+        let dest = self.plan_copy_location(normalize_ident(stmt.dest), stmt.credentials, &mut m)?;
+        let format = plan_copy_format(stmt.format.as_ref(), &dest, &mut m)?;
+
+        validate_copyto_dest_format_support(dest.as_str(), format.as_str()).map_err(|e| {
+            PlanError::InvalidExternalTable {
+                source: Box::new(e),
+            }
+        })?;
+
+        Ok(CopyTo {
+            format,
+            dest,
+            source,
+        }
+        .into_logical_plan())
+    }
+
+    async fn plan_copy_from(&self, stmt: CopyFromStmt) -> Result<LogicalPlan> {
+        validate_object_name(&stmt.table)?;
+        let table_name = object_name_to_table_ref(stmt.table)?;
+        let (runtime_preference, provider, table_schema) =
+            self.plan_insert_target(table_name, false).await?;
+
+        let mut m = stmt.options;
+
+        let location = match stmt.source {
+            CopyFromSource::Stdin => {
+                let table = match provider {
+                    ProviderReference::Provider(table) => table,
+                    ProviderReference::RemoteReference(_) => {
+                        return Err(PlanError::UnsupportedFeature(
+                            "COPY FROM STDIN into remote tables",
+                        ))
+                    }
+                };
+                let columns = if stmt.columns.is_empty() {
+                    None
+                } else {
+                    Some(copy_in_column_indices(&table_schema, stmt.columns)?)
+                };
+                let format = plan_copy_in_format(stmt.format.as_ref(), &mut m)?;
+                return Ok(CopyFromStdin {
+                    table,
+                    columns,
+                    format,
+                }
+                .into());
+            }
+            CopyFromSource::Location(_) if !stmt.columns.is_empty() => {
+                return Err(PlanError::UnsupportedFeature(
+                    "COPY FROM a location with a column list",
+                ))
+            }
+            CopyFromSource::Location(location) => location,
+        };
+
+        self.require_superuser("copy from external sources")?;
 
+        let location =
+            self.plan_copy_location(normalize_ident(location), stmt.credentials, &mut m)?;
+        let format = plan_copy_format(stmt.format.as_ref(), &location, &mut m)?;
+
+        let file_format: Arc<dyn FileFormat> = match format {
+            CopyToFormatOptions::Csv(CopyToFormatOptionsCsv { delim, header }) => Arc::new(
+                CsvFormat::default()
+                    .with_delimiter(delim)
+                    .with_has_header(header)
+                    .with_schema_infer_max_rec(Some(20480)),
+            ),
+            CopyToFormatOptions::Parquet(_) => Arc::new(ParquetFormat::default()),
+            CopyToFormatOptions::Json(_) => Arc::new(JsonFormat::default()),
+            CopyToFormatOptions::Arrow => Arc::new(ArrowFormat),
+            CopyToFormatOptions::Bson {} => {
+                return Err(PlanError::UnsupportedFeature("COPY FROM for bson"))
+            }
+            CopyToFormatOptions::Iceberg => {
+                return Err(PlanError::UnsupportedFeature("COPY FROM for iceberg"))
+            }
+            CopyToFormatOptions::Delta => {
+                return Err(PlanError::UnsupportedFeature("COPY FROM for delta"))
+            }
+        };
+
+        let (access, path) = get_access_and_path(&location)?;
+        let accessor = ObjStoreAccessor::new(access)?;
+        let objects = accessor.list_globbed(path).await?;
+        if objects.is_empty() {
+            return Err(PlanError::String(format!(
+                "no files found at location: {}",
+                location.location()
+            )));
+        }
+
+        let state = self.ctx.df_ctx().state();
+        let source = accessor
+            .into_table_provider(
+                &state,
+                file_format,
+                objects,
+                &[],
+                &PartitionColumns::Disabled,
+            )
+            .await?;
+
+        // Columns are matched up by position, casting to the types of the
+        // table being copied into.
+        let source_schema = source.schema();
+        if source_schema.fields().len() != table_schema.fields().len() {
+            return Err(PlanError::String(format!(
+                "COPY FROM source has {} columns, but table has {} columns",
+                source_schema.fields().len(),
+                table_schema.fields().len()
+            )));
+        }
+        let exprs = source_schema
+            .fields()
+            .iter()
+            .zip(table_schema.fields())
+            .map(|(source, target)| {
+                let source = Expr::Column(Column::new_unqualified(source.name()));
+                cast(source, target.data_type().clone()).alias(target.name())
+            });
+
+        let source = LogicalPlanBuilder::scan("copy_source", provider_as_source(source), None)?
+            .project(exprs)?
+            .build()?;
+
+        Ok(Insert {
+            source,
+            provider,
+            runtime_preference,
+            overwrite: false,
+        }
+        .into_logical_plan())
+    }
+
+    /// Resolve the objects and privileges for a GRANT or REVOKE.
+    ///
+    /// `ALL` expands to every privilege applicable to the type of object.
+    fn plan_privilege_objects(
+        &self,
+        privileges: ast::Privileges,
+        objects: ast::GrantObjects,
+    ) -> Result<(Vec<PrivilegeObject>, Vec<Privilege>)> {
+        let (objects, all_privileges) = match objects {
+            ast::GrantObjects::Tables(names) => {
+                let mut objects = Vec::with_capacity(names.len());
+                for name in names {
+                    validate_object_name(&name)?;
+                    let r = object_name_to_table_ref(name)?;
+                    let r = self.ctx.resolve_table_ref(r)?;
+                    objects.push(PrivilegeObject {
+                        schema: r.schema.into_owned(),
+                        name: Some(r.name.into_owned()),
+                    });
+                }
+                (objects, Privilege::TABLE_PRIVILEGES)
+            }
+            ast::GrantObjects::AllTablesInSchema { schemas } => {
+                let catalog = self.ctx.get_session_catalog();
+                let mut objects = Vec::new();
+                for name in schemas {
+                    validate_object_name(&name)?;
+                    let r = object_name_to_schema_ref(name)?;
+                    let schema = self.ctx.resolve_schema_ref(r).schema.into_owned();
+                    let schema_id = catalog
+                        .resolve_schema(&schema)
+                        .ok_or_else(|| PlanError::String(format!("Missing schema: {schema}")))?
+                        .meta
+                        .id;
+
+                    let tables = catalog.iter_entries().filter_map(|ent| match ent.entry {
+                        CatalogEntry::Table(_) | CatalogEntry::View(_)
+                            if !ent.builtin && ent.entry.get_meta().parent == schema_id =>
+                        {
+                            Some(PrivilegeObject {
+                                schema: schema.clone(),
+                                name: Some(ent.entry.get_meta().name.clone()),
+                            })
+                        }
+                        _ => None,
+                    });
+                    objects.extend(tables);
+                }
+                (objects, Privilege::TABLE_PRIVILEGES)
+            }
+            ast::GrantObjects::Schemas(names) => {
+                let mut objects = Vec::with_capacity(names.len());
+                for name in names {
+                    validate_object_name(&name)?;
+                    let r = object_name_to_schema_ref(name)?;
+                    objects.push(PrivilegeObject {
+                        schema: self.ctx.resolve_schema_ref(r).schema.into_owned(),
+                        name: None,
+                    });
+                }
+                (objects, Privilege::SCHEMA_PRIVILEGES)
+            }
+            ast::GrantObjects::Sequences(_) | ast::GrantObjects::AllSequencesInSchema { .. } => {
+                return Err(PlanError::UnsupportedFeature("privileges on sequences"))
+            }
+        };
+
+        let privileges = match privileges {
+            ast::Privileges::All { .. } => all_privileges.to_vec(),
+            ast::Privileges::Actions(actions) => actions
+                .into_iter()
+                .map(|action| match action {
+                    ast::Action::Select { columns: None } => Ok(Privilege::Select),
+                    ast::Action::Insert { columns: None } => Ok(Privilege::Insert),
+                    ast::Action::Update { columns: None } => Ok(Privilege::Update),
+                    ast::Action::Delete => Ok(Privilege::Delete),
+                    ast::Action::Usage => Ok(Privilege::Usage),
+                    ast::Action::Create => Ok(Privilege::Create),
+                    other => Err(PlanError::String(format!("Unsupported privilege: {other}"))),
+                })
+                .collect::<Result<Vec<_>>>()?,
+        };
+
+        Ok((objects, privileges))
+    }
+
+    /// Error if the session user isn't a superuser.
+    fn require_superuser(&self, operation: &str) -> Result<()> {
+        let user = self.ctx.get_session_vars().user_name();
+        if !self.ctx.get_session_catalog().is_superuser(&user) {
+            return Err(PlanError::PermissionDenied {
+                user,
+                msg: format!("only superusers can {operation}"),
+            });
+        }
+        Ok(())
+    }
+
+    /// Error if the session user doesn't have a privilege on a schema.
+    ///
+    /// Missing schemas are left for the catalog to error on.
+    fn require_schema_privilege(&self, schema: &str, privilege: Privilege) -> Result<()> {
+        let user = self.ctx.get_session_vars().user_name();
+        let catalog = self.ctx.get_session_catalog();
+        if let Some(ent) = catalog.resolve_schema(schema) {
+            if !catalog.has_privilege(&user, ent.meta.id, privilege) {
+                return Err(PlanError::PermissionDenied {
+                    user,
+                    msg: format!("missing {privilege} privilege on schema '{schema}'"),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve the table entry targeted by an UPDATE or DELETE.
+    ///
+    /// Native tables can always be modified. External tables can only be
+    /// modified if they're delta tables, and have write access. The session
+    /// user must have `privilege` on the table.
+    fn resolve_modify_target(
+        &self,
+        table_name: OwnedTableReference,
+        privilege: Privilege,
+        unsupported: &'static str,
+    ) -> Result<TableEntry> {
+        let resolver = EntryResolver::from_context(self.ctx);
+        let ent = resolver
+            .resolve_entry_from_reference(table_name.clone())?
+            .try_into_table_entry()?;
+
+        let user = self.ctx.get_session_vars().user_name();
+        if !self
+            .ctx
+            .get_session_catalog()
+            .can_access(&user, &ent.meta, privilege)
+        {
+            return Err(PlanError::PermissionDenied {
+                user,
+                msg: format!("missing {privilege} privilege on '{}'", ent.meta.name),
+            });
+        }
+
+        if ent.meta.external {
+            if !matches!(ent.options, TableOptions::Delta(_)) {
+                return Err(PlanError::UnsupportedFeature(unsupported));
+            }
+            if !ent.access_mode.has_write_access() {
+                return Err(PlanError::ObjectNotAllowedToWriteInto(table_name));
+            }
+            self.check_external_delta_write(&ent)?;
+        }
+
+        Ok(ent)
+    }
+
+    /// Check that an external delta table can be written to.
+    ///
+    /// Writes to external delta tables are committed as soon as they're
+    /// executed and can't be rolled back, so they're not allowed inside a
+    /// transaction block.
+    fn check_external_delta_write(&self, ent: &TableEntry) -> Result<()> {
+        if ent.meta.external
+            && matches!(ent.options, TableOptions::Delta(_))
+            && self.ctx.get_native_tables().in_transaction()
+        {
+            return Err(PlanError::String(format!(
+                "Cannot write to external delta table '{}' inside a transaction block",
+                ent.meta.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Resolve the table being inserted into, checking that it's writable.
+    ///
+    /// Overwriting a table removes its existing rows, so also requires the
+    /// `DELETE` privilege.
+    ///
+    /// Returns the provider to insert into along with the table's schema.
+    async fn plan_insert_target(
+        &self,
+        table_name: OwnedTableReference,
+        overwrite: bool,
+    ) -> Result<(RuntimePreference, ProviderReference, SchemaRef)> {
+        let access_mode = self
+            .get_access_mode(table_name.clone())?
+            .unwrap_or(SourceAccessMode::ReadOnly);
+
+        if !access_mode.has_write_access() {
+            return Err(PlanError::ObjectNotAllowedToWriteInto(
+                table_name.to_owned_reference(),
+            ));
+        }
+
+        let resolver = EntryResolver::from_context(self.ctx);
+        if let ResolvedEntry::Entry(CatalogEntry::Table(ent)) =
+            resolver.resolve_entry_from_reference(table_name.clone())?
+        {
+            self.check_external_delta_write(&ent)?;
+        }
+
+        let state = self.ctx.df_ctx().state();
+        if overwrite {
+            PartialContextProvider::new(self.ctx, &state)?
+                .with_privilege(Privilege::Delete)
+                .table_provider(table_name.clone())
+                .await?;
+        }
+        let mut ctx_provider =
+            PartialContextProvider::new(self.ctx, &state)?.with_privilege(Privilege::Insert);
+
+        let provider = ctx_provider.table_provider(table_name).await?;
+        let schema = provider.provider.schema();
+
+        let (runtime_preference, provider) = match (
+            provider.preference,
+            provider
+                .provider
+                .as_any()
+                .downcast_ref::<StubRemoteTableProvider>(),
+        ) {
+            (RuntimePreference::Remote, Some(stub)) => (
+                RuntimePreference::Remote,
+                ProviderReference::RemoteReference(stub.id()),
+            ),
+            _ => (
+                RuntimePreference::Local,
+                ProviderReference::Provider(provider.provider),
+            ),
+        };
+
+        Ok((runtime_preference, provider, schema))
+    }
+
+    /// Resolve the object store location for COPY TO and COPY FROM.
+    fn plan_copy_location(
+        &self,
+        location: String,
+        credentials: Option<Ident>,
+        m: &mut StmtOptions,
+    ) -> Result<CopyToDestinationOptions> {
         // We currently support two versions of COPY TO:
         //
         // 1: COPY <source> TO <s3|gcs|azure> OPTIONS (...)
@@ -1577,16 +2294,18 @@ impl<'a> SessionPlanner<'a> {
         // is what lets us differentiate between those, and if `url` is `None`,
         // we'll resolve the actual object destination from the OPTIONS down
         // below.
+        //
+        // COPY FROM supports the same forms for the location being read from.
         let (dest, uri) = if matches!(
-            dest.as_str(),
+            location.as_str(),
             CopyToDestinationOptions::LOCAL
                 | CopyToDestinationOptions::GCS
                 | CopyToDestinationOptions::S3_STORAGE
                 | CopyToDestinationOptions::AZURE
         ) {
-            (dest.as_str(), None)
+            (location.as_str(), None)
         } else {
-            let u = DatasourceUrl::try_new(&dest)?;
+            let u = DatasourceUrl::try_new(&location)?;
             let d = match u.datasource_url_type() {
                 DatasourceUrlType::File => CopyToDestinationOptions::LOCAL,
                 DatasourceUrlType::Gcs => CopyToDestinationOptions::GCS,
@@ -1597,7 +2316,7 @@ impl<'a> SessionPlanner<'a> {
             (d, Some(u))
         };
 
-        let creds = stmt.credentials.map(normalize_ident);
+        let creds = credentials.map(normalize_ident);
         let creds_options = self.get_credentials_opts(&creds)?;
         if let Some(creds_options) = &creds_options {
             validate_copyto_dest_creds_support(dest, creds_options.as_str()).map_err(|e| {
@@ -1628,7 +2347,7 @@ impl<'a> SessionPlanner<'a> {
 
         let dest = match dest {
             CopyToDestinationOptions::LOCAL => {
-                let location = get_location(&mut m, &uri)?;
+                let location = get_location(m, &uri)?;
                 CopyToDestinationOptions::Local(CopyToDestinationOptionsLocal { location })
             }
             CopyToDestinationOptions::GCS => {
@@ -1640,8 +2359,8 @@ impl<'a> SessionPlanner<'a> {
                 let service_account_key =
                     m.remove_optional_or("service_account_key", service_account_key)?;
 
-                let bucket = get_bucket(&mut m, &uri)?;
-                let location = get_location(&mut m, &uri)?;
+                let bucket = get_bucket(m, &uri)?;
+                let location = get_location(m, &uri)?;
 
                 CopyToDestinationOptions::Gcs(CopyToDestinationOptionsGcs {
                     service_account_key,
@@ -1668,8 +2387,8 @@ impl<'a> SessionPlanner<'a> {
                     m.remove_optional_or("secret_access_key", secret_access_key)?;
 
                 let region = m.remove_required("region")?;
-                let bucket = get_bucket(&mut m, &uri)?;
-                let location = get_location(&mut m, &uri)?;
+                let bucket = get_bucket(m, &uri)?;
+                let location = get_location(m, &uri)?;
 
                 CopyToDestinationOptions::S3(CopyToDestinationOptionsS3 {
                     access_key_id,
@@ -1721,58 +2440,7 @@ impl<'a> SessionPlanner<'a> {
             }
         };
 
-        let loc = dest.location();
-        let loc = Path::new(loc);
-        let ext = loc
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| ext.to_lowercase());
-
-        let format = match stmt
-            .format
-            .as_ref()
-            .map(|f| f.value.as_str())
-            // Choose from specified format "OR" from location.
-            .or(ext.as_deref())
-        {
-            None => {
-                // TODO: Choose the default based on destination.
-                CopyToFormatOptions::default()
-            }
-            Some(CopyToFormatOptions::CSV) => {
-                let delim = m.remove_optional::<char>("delimeter")?.unwrap_or(',');
-                let header = m.remove_optional::<bool>("header")?.unwrap_or(true);
-                CopyToFormatOptions::Csv(CopyToFormatOptionsCsv {
-                    delim: delim as u8,
-                    header,
-                })
-            }
-            Some(CopyToFormatOptions::PARQUET) => {
-                let row_group_size = m
-                    .remove_optional::<usize>("row_group_size")?
-                    .unwrap_or(122880);
-                CopyToFormatOptions::Parquet(CopyToFormatOptionsParquet { row_group_size })
-            }
-            Some(CopyToFormatOptions::JSON) => {
-                let array = m.remove_optional::<bool>("array")?.unwrap_or(false);
-                CopyToFormatOptions::Json(CopyToFormatOptionsJson { array })
-            }
-            Some(CopyToFormatOptions::BSON) => CopyToFormatOptions::Bson {},
-            Some(other) => return Err(internal!("unsupported output format: {other}")),
-        };
-
-        validate_copyto_dest_format_support(dest.as_str(), format.as_str()).map_err(|e| {
-            PlanError::InvalidExternalTable {
-                source: Box::new(e),
-            }
-        })?;
-
-        Ok(CopyTo {
-            format,
-            dest,
-            source,
-        }
-        .into_logical_plan())
+        Ok(dest)
     }
 
     fn get_tunnel_opts(&self, tunnel: &Option<String>) -> Result<Option<TunnelOptions>> {
@@ -1834,6 +2502,20 @@ impl<'a> SessionPlanner<'a> {
     }
 }
 
+/// Removes the comma separated list of declared hive partition columns.
+fn remove_partition_columns(m: &mut StmtOptions) -> Result<Vec<String>> {
+    let cols = m
+        .remove_optional::<String>("partition_columns")?
+        .map(|cols| {
+            cols.split(',')
+                .map(|col| col.trim().to_string())
+                .filter(|col| !col.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(cols)
+}
+
 /// Creates an accessor from object store external table and validates if the
 /// location returns any objects. If objects are returned, tries to get the file
 /// type and compression of the object.
@@ -1895,17 +2577,201 @@ async fn validate_and_get_file_type_and_compression(
     Ok((file_type, compression))
 }
 
+/// Resolve the format for COPY TO and COPY FROM, falling back to the file
+/// extension of the location if no format was provided.
+fn plan_copy_format(
+    format: Option<&Ident>,
+    dest: &CopyToDestinationOptions,
+    m: &mut StmtOptions,
+) -> Result<CopyToFormatOptions> {
+    let loc = dest.location();
+    let loc = Path::new(loc);
+    let ext = loc
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+
+    let format = match format
+        .map(|f| f.value.as_str())
+        // Choose from specified format "OR" from location.
+        .or(ext.as_deref())
+    {
+        None => {
+            // TODO: Choose the default based on destination.
+            CopyToFormatOptions::default()
+        }
+        Some(CopyToFormatOptions::CSV) => {
+            // The misspelled "delimeter" is still accepted for compatibility.
+            let delim = match m.remove_optional::<char>("delimiter")? {
+                Some(delim) => delim,
+                None => m.remove_optional::<char>("delimeter")?.unwrap_or(','),
+            };
+            let header = m.remove_optional::<bool>("header")?.unwrap_or(true);
+            CopyToFormatOptions::Csv(CopyToFormatOptionsCsv {
+                delim: delim as u8,
+                header,
+            })
+        }
+        Some(CopyToFormatOptions::PARQUET) => {
+            let row_group_size = m
+                .remove_optional::<usize>("row_group_size")?
+                .unwrap_or(122880);
+            CopyToFormatOptions::Parquet(CopyToFormatOptionsParquet { row_group_size })
+        }
+        Some(CopyToFormatOptions::JSON) => {
+            let array = m.remove_optional::<bool>("array")?.unwrap_or(false);
+            CopyToFormatOptions::Json(CopyToFormatOptionsJson { array })
+        }
+        Some(CopyToFormatOptions::BSON) => CopyToFormatOptions::Bson {},
+        Some(CopyToFormatOptions::ICEBERG) => CopyToFormatOptions::Iceberg,
+        Some(CopyToFormatOptions::ARROW) => CopyToFormatOptions::Arrow,
+        Some(CopyToFormatOptions::DELTA) => CopyToFormatOptions::Delta,
+        Some(other) => return Err(internal!("unsupported output format: {other}")),
+    };
+
+    Ok(format)
+}
+
+/// Resolve the format of data sent by the client for COPY FROM STDIN.
+///
+/// Defaults match Postgres.
+fn plan_copy_in_format(format: Option<&Ident>, m: &mut StmtOptions) -> Result<CopyInFormat> {
+    let format = match format {
+        Some(format) => Some(format.value.to_lowercase()),
+        None => m
+            .remove_optional::<String>("format")?
+            .map(|f| f.to_lowercase()),
+    };
+
+    // Options are parsed as a single char, which still may be more than one
+    // byte. Postgres requires a single one-byte character.
+    fn single_byte(m: &mut StmtOptions, key: &str, default: u8) -> Result<u8> {
+        match m.remove_optional::<char>(key)? {
+            Some(c) if c.is_ascii() => Ok(c as u8),
+            Some(c) => Err(PlanError::String(format!(
+                "COPY {key} must be a single one-byte character, got '{c}'"
+            ))),
+            None => Ok(default),
+        }
+    }
+
+    Ok(match format.as_deref().unwrap_or(CopyInFormat::TEXT) {
+        CopyInFormat::TEXT => CopyInFormat::Text {
+            delimiter: single_byte(m, "delimiter", b'\t')?,
+            null: m
+                .remove_optional::<String>("null")?
+                .unwrap_or_else(|| "\\N".to_string()),
+        },
+        CopyInFormat::CSV => CopyInFormat::Csv {
+            delimiter: single_byte(m, "delimiter", b',')?,
+            quote: single_byte(m, "quote", b'"')?,
+            header: m.remove_optional::<bool>("header")?.unwrap_or(false),
+            null: m.remove_optional::<String>("null")?.unwrap_or_default(),
+        },
+        CopyInFormat::BINARY => CopyInFormat::Binary,
+        other => return Err(internal!("unsupported COPY format: {other}")),
+    })
+}
+
+/// Resolve the columns listed in a COPY FROM STDIN to their indices in the
+/// table's schema, in the order they were listed.
+fn copy_in_column_indices(schema: &SchemaRef, columns: Vec<Ident>) -> Result<Vec<usize>> {
+    let mut indices = Vec::with_capacity(columns.len());
+    for col in columns {
+        let col = normalize_ident(col);
+        let idx = schema.index_of(&col).map_err(|_| {
+            PlanError::String(format!("column \"{col}\" does not exist in the table"))
+        })?;
+        if indices.contains(&idx) {
+            return Err(PlanError::String(format!(
+                "column \"{col}\" specified more than once"
+            )));
+        }
+        indices.push(idx);
+    }
+    Ok(indices)
+}
+
+/// Get the number of rows to fetch from a `FETCH` count.
+fn fetch_count(limit: ast::Value) -> Result<usize> {
+    match limit {
+        ast::Value::Number(n, _) => Ok(n.parse()?),
+        other => Err(PlanError::String(format!(
+            "invalid count for FETCH: {other}"
+        ))),
+    }
+}
+
 /// Resolves an ident (unquoted -> lowercase else case sensitive).
 fn normalize_ident(ident: Ident) -> String {
     let normalizer = IdentNormalizer::new(/* normalize = */ true);
     normalizer.normalize(ident)
 }
 
+/// Check that partition columns exist in the table and that at least one
+/// column is left unpartitioned.
+fn validate_partition_columns(fields: &[Field], partition_by: &[String]) -> Result<()> {
+    for (idx, col) in partition_by.iter().enumerate() {
+        if !fields.iter().any(|f| f.name() == col) {
+            return Err(PlanError::String(format!(
+                "Partition column '{col}' does not exist in table"
+            )));
+        }
+        if partition_by[..idx].contains(col) {
+            return Err(PlanError::String(format!(
+                "Partition column '{col}' specified more than once"
+            )));
+        }
+    }
+    if !partition_by.is_empty() && partition_by.len() == fields.len() {
+        return Err(PlanError::String(
+            "Cannot partition a table by all of its columns".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 fn object_name_to_table_ref(name: ObjectName) -> Result<OwnedTableReference> {
     let r = object_name_to_table_reference(name, /* enable_normalization = */ true)?;
     Ok(r)
 }
 
+/// Convert an object name to a role name. Role names can't be qualified.
+fn object_name_to_role_name(name: ObjectName) -> Result<String> {
+    validate_object_name(&name)?;
+    match name {
+        ObjectName(mut idents) if idents.len() == 1 => Ok(normalize_ident(idents.pop().unwrap())),
+        other => Err(PlanError::String(format!(
+            "Role name cannot be qualified: {other}"
+        ))),
+    }
+}
+
+fn plan_grantees(grantees: Vec<Ident>) -> Result<Vec<String>> {
+    grantees
+        .into_iter()
+        .map(|ident| {
+            validate_ident(&ident)?;
+            Ok(normalize_ident(ident))
+        })
+        .collect()
+}
+
+/// Get the verifier to store for a role's password.
+///
+/// Passwords that are already MD5 or SCRAM-SHA-256 verifiers are stored as is,
+/// same as postgres. Everything else is hashed using SCRAM-SHA-256.
+fn password_verifier(password: &str) -> String {
+    let is_md5 = password
+        .strip_prefix("md5")
+        .is_some_and(|hash| hash.len() == 32 && hash.chars().all(|c| c.is_ascii_hexdigit()));
+    if is_md5 || password.starts_with("SCRAM-SHA-256$") {
+        password.to_string()
+    } else {
+        postgres_protocol::password::scram_sha_256(password.as_bytes())
+    }
+}
+
 fn quoted_table_ref(table_ref: TableReference<'_>) -> String {
     match table_ref {
         TableReference::Bare { table } => format!("{table:?}"),
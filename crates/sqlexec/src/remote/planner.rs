@@ -23,9 +23,10 @@ use std::sync::Arc;
 use crate::planner::extension::ExtensionType;
 use crate::planner::logical_plan::{
     AlterDatabase, AlterTable, AlterTunnelRotateKeys, CopyTo, CreateCredential, CreateCredentials,
-    CreateExternalDatabase, CreateExternalTable, CreateSchema, CreateTable, CreateTempTable,
-    CreateTunnel, CreateView, Delete, DescribeTable, DropCredentials, DropDatabase, DropSchemas,
-    DropTables, DropTunnel, DropViews, Insert, SetVariable, ShowVariable, Update,
+    CreateExternalDatabase, CreateExternalTable, CreateRole, CreateSchema, CreateTable,
+    CreateTempTable, CreateTunnel, CreateView, Delete, DescribeTable, DropCredentials,
+    DropDatabase, DropRoles, DropSchemas, DropTables, DropTunnel, DropViews, GrantPrivileges,
+    Insert, OptimizeTable, RevokePrivileges, SetVariable, ShowVariable, Update, VacuumTable,
 };
 use crate::planner::physical_plan::alter_database::AlterDatabaseExec;
 use crate::planner::physical_plan::alter_table::AlterTableExec;
@@ -37,6 +38,7 @@ use crate::planner::physical_plan::create_credential::CreateCredentialExec;
 use crate::planner::physical_plan::create_credentials::CreateCredentialsExec;
 use crate::planner::physical_plan::create_external_database::CreateExternalDatabaseExec;
 use crate::planner::physical_plan::create_external_table::CreateExternalTableExec;
+use crate::planner::physical_plan::create_role::CreateRoleExec;
 use crate::planner::physical_plan::create_schema::CreateSchemaExec;
 use crate::planner::physical_plan::create_table::CreateTableExec;
 use crate::planner::physical_plan::create_temp_table::CreateTempTableExec;
@@ -46,18 +48,23 @@ use crate::planner::physical_plan::delete::DeleteExec;
 use crate::planner::physical_plan::describe_table::DescribeTableExec;
 use crate::planner::physical_plan::drop_credentials::DropCredentialsExec;
 use crate::planner::physical_plan::drop_database::DropDatabaseExec;
+use crate::planner::physical_plan::drop_roles::DropRolesExec;
 use crate::planner::physical_plan::drop_schemas::DropSchemasExec;
 use crate::planner::physical_plan::drop_tables::DropTablesExec;
 use crate::planner::physical_plan::drop_temp_tables::DropTempTablesExec;
 use crate::planner::physical_plan::drop_tunnel::DropTunnelExec;
 use crate::planner::physical_plan::drop_views::DropViewsExec;
+use crate::planner::physical_plan::grant_privileges::GrantPrivilegesExec;
 use crate::planner::physical_plan::insert::InsertExec;
+use crate::planner::physical_plan::optimize_table::OptimizeTableExec;
 use crate::planner::physical_plan::remote_exec::RemoteExecutionExec;
 use crate::planner::physical_plan::remote_scan::ProviderReference;
+use crate::planner::physical_plan::revoke_privileges::RevokePrivilegesExec;
 use crate::planner::physical_plan::send_recv::SendRecvJoinExec;
 use crate::planner::physical_plan::set_var::SetVarExec;
 use crate::planner::physical_plan::show_var::ShowVarExec;
 use crate::planner::physical_plan::update::UpdateExec;
+use crate::planner::physical_plan::vacuum_table::VacuumTableExec;
 
 use super::client::RemoteSessionClient;
 
@@ -175,6 +182,7 @@ impl ExtensionPlanner for DDLExtensionPlanner {
                     or_replace: lp.or_replace,
                     arrow_schema: Arc::new(lp.schema.as_ref().into()),
                     source: physical_inputs.first().cloned(),
+                    partition_by: lp.partition_by.clone(),
                 };
                 RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
             }
@@ -374,6 +382,7 @@ impl ExtensionPlanner for DDLExtensionPlanner {
                     source: Arc::new(WriteOnlyDataSourceMetricsExecAdapter::new(
                         physical_inputs.first().unwrap().clone(),
                     )),
+                    overwrite: lp.overwrite,
                 });
                 RuntimeGroupExec::new(lp.runtime_preference, exec)
             }
@@ -385,6 +394,65 @@ impl ExtensionPlanner for DDLExtensionPlanner {
                 };
                 RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
             }
+            ExtensionType::OptimizeTable => {
+                let lp = require_downcast_lp::<OptimizeTable>(node);
+                let exec = OptimizeTableExec {
+                    table: lp.table.clone(),
+                    zorder_by: lp.zorder_by.clone(),
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::VacuumTable => {
+                let lp = require_downcast_lp::<VacuumTable>(node);
+                let exec = VacuumTableExec {
+                    table: lp.table.clone(),
+                    retain_hours: lp.retain_hours,
+                    skip_retention_check: lp.skip_retention_check,
+                    dry_run: lp.dry_run,
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::CreateRole => {
+                let lp = require_downcast_lp::<CreateRole>(node);
+                let exec = CreateRoleExec {
+                    catalog_version: self.catalog.version(),
+                    name: lp.name.clone(),
+                    password_verifier: lp.password_verifier.clone(),
+                    login: lp.login,
+                    superuser: lp.superuser,
+                    if_not_exists: lp.if_not_exists,
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::DropRoles => {
+                let lp = require_downcast_lp::<DropRoles>(node);
+                let exec = DropRolesExec {
+                    catalog_version: self.catalog.version(),
+                    names: lp.names.clone(),
+                    if_exists: lp.if_exists,
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::GrantPrivileges => {
+                let lp = require_downcast_lp::<GrantPrivileges>(node);
+                let exec = GrantPrivilegesExec {
+                    catalog_version: self.catalog.version(),
+                    roles: lp.roles.clone(),
+                    objects: lp.objects.clone(),
+                    privileges: lp.privileges.clone(),
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
+            ExtensionType::RevokePrivileges => {
+                let lp = require_downcast_lp::<RevokePrivileges>(node);
+                let exec = RevokePrivilegesExec {
+                    catalog_version: self.catalog.version(),
+                    roles: lp.roles.clone(),
+                    objects: lp.objects.clone(),
+                    privileges: lp.privileges.clone(),
+                };
+                RuntimeGroupExec::new(RuntimePreference::Remote, Arc::new(exec))
+            }
         };
 
         Ok(Some(Arc::new(runtime_group_exec)))
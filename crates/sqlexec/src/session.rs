@@ -5,7 +5,10 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use crate::cancel::{with_cancel, CancelHandle};
 use crate::context::local::{LocalSessionContext, Portal, PreparedStatement};
+use crate::copy_in::CopyInSink;
+use crate::cursor::Cursor;
 use crate::distexec::scheduler::{OutputSink, Scheduler};
 use crate::distexec::stream::create_coalescing_adapter;
 use crate::environment::EnvironmentReader;
@@ -19,25 +22,29 @@ use crate::planner::physical_plan::{
 use crate::planner::session_planner::SessionPlanner;
 use crate::remote::client::RemoteClient;
 use crate::remote::planner::{DDLExtensionPlanner, RemotePhysicalPlanner};
+use crate::session_registry::SessionRegistry;
+use crate::timeout::{statement_timeout, with_statement_timeout};
 use catalog::mutator::CatalogMutator;
 use catalog::session_catalog::SessionCatalog;
-use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::error::{DataFusionError, Result as DataFusionResult};
 use datafusion::logical_expr::LogicalPlan as DfLogicalPlan;
 use datafusion::physical_plan::empty::EmptyExec;
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::{
     execute_stream, ExecutionPlan, RecordBatchStream, SendableRecordBatchStream,
 };
 use datafusion::physical_planner::{DefaultPhysicalPlanner, PhysicalPlanner};
 use datafusion::scalar::ScalarValue;
+use datafusion::sql::sqlparser::ast;
 use datafusion_ext::metrics::AggregatedMetrics;
 use datafusion_ext::session_metrics::{
     BatchStreamWithMetricSender, ExecutionStatus, QueryMetrics, SessionMetricsHandler,
 };
 use datafusion_ext::vars::SessionVars;
 use datasources::native::access::NativeTableStorage;
-use futures::{Stream, StreamExt};
+use futures::{Future, FutureExt, Stream, StreamExt};
 use once_cell::sync::Lazy;
 use pgrepr::format::Format;
 use telemetry::Tracker;
@@ -57,6 +64,12 @@ pub enum ExecutionResult {
         /// Inner results stream from execution.
         stream: SendableRecordBatchStream,
     },
+    /// Rows returned from a portal before it was suspended after reaching the
+    /// max number of rows for the execute. Executing the portal again returns
+    /// the rest of the rows.
+    Suspended { stream: SendableRecordBatchStream },
+    /// Rows fetched from a cursor.
+    Fetch { stream: SendableRecordBatchStream },
     /// Execution errored.
     Error(DataFusionError),
     /// No batches returned.
@@ -75,6 +88,16 @@ pub enum ExecutionResult {
     UpdateSuccess { updated_rows: usize },
     /// Data successfully copied.
     CopySuccess,
+    /// Table files compacted.
+    OptimizeSuccess { files_removed: usize },
+    /// Unreferenced table files removed.
+    VacuumSuccess { files_deleted: usize },
+    /// Ready to receive data from the client for `COPY ... FROM STDIN`.
+    CopyIn { sink: CopyInSink },
+    /// Cursor declared.
+    DeclareCursor,
+    /// Cursor closed.
+    CloseCursor,
     /// Table created.
     CreateTable,
     /// Database created.
@@ -109,6 +132,14 @@ pub enum ExecutionResult {
     DropTunnel,
     /// Credentials are dropped.
     DropCredentials,
+    /// Role created.
+    CreateRole,
+    /// Roles dropped.
+    DropRoles,
+    /// Privileges granted.
+    Grant,
+    /// Privileges revoked.
+    Revoke,
 }
 // this just makes the `prepare_statement` method a bit more ergonomic.
 pub struct PrepareStatementArg {
@@ -158,12 +189,9 @@ impl ExecutionResult {
     /// This will look at the first batch in the stream to determine which
     /// result it is.
     pub async fn from_stream(mut stream: SendableRecordBatchStream) -> ExecutionResult {
-        let schema = stream.schema();
-        // If we don't match either of these schemas, just assume these results
-        // are from a normal SELECT query.
-        if !(schema.eq(&GENERIC_OPERATION_PHYSICAL_SCHEMA)
-            || schema.eq(&GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA))
-        {
+        // If we don't match either of the operation schemas, just assume these
+        // results are from a normal SELECT query.
+        if !is_operation_schema(&stream.schema()) {
             return ExecutionResult::Query { stream };
         }
 
@@ -204,6 +232,8 @@ impl ExecutionResult {
         match self {
             ExecutionResult::Error(_) => "error",
             ExecutionResult::Query { .. } => "query",
+            ExecutionResult::Suspended { .. } => "suspended",
+            ExecutionResult::Fetch { .. } => "fetch",
             ExecutionResult::EmptyQuery => "empty_query",
             ExecutionResult::Begin => "begin",
             ExecutionResult::Commit => "commit",
@@ -212,6 +242,11 @@ impl ExecutionResult {
             ExecutionResult::DeleteSuccess { .. } => "delete",
             ExecutionResult::UpdateSuccess { .. } => "update",
             ExecutionResult::CopySuccess => "copy",
+            ExecutionResult::OptimizeSuccess { .. } => "optimize",
+            ExecutionResult::VacuumSuccess { .. } => "vacuum",
+            ExecutionResult::CopyIn { .. } => "copy_in",
+            ExecutionResult::DeclareCursor => "declare_cursor",
+            ExecutionResult::CloseCursor => "close_cursor",
             ExecutionResult::CreateTable => "create_table",
             ExecutionResult::CreateDatabase => "create_database",
             ExecutionResult::CreateTunnel => "create_tunnel",
@@ -229,6 +264,10 @@ impl ExecutionResult {
             ExecutionResult::DropDatabase => "drop_database",
             ExecutionResult::DropTunnel => "drop_tunnel",
             ExecutionResult::DropCredentials => "drop_credentials",
+            ExecutionResult::CreateRole => "create_role",
+            ExecutionResult::DropRoles => "drop_roles",
+            ExecutionResult::Grant => "grant",
+            ExecutionResult::Revoke => "revoke",
         }
     }
 
@@ -251,6 +290,10 @@ impl ExecutionResult {
                 | ExecutionResult::DropDatabase
                 | ExecutionResult::DropTunnel
                 | ExecutionResult::DropCredentials
+                | ExecutionResult::CreateRole
+                | ExecutionResult::DropRoles
+                | ExecutionResult::Grant
+                | ExecutionResult::Revoke
         )
     }
 
@@ -273,6 +316,12 @@ impl ExecutionResult {
                 updated_rows: count.unwrap_or_default() as usize,
             },
             "copy" => ExecutionResult::CopySuccess,
+            "optimize" => ExecutionResult::OptimizeSuccess {
+                files_removed: count.unwrap_or_default() as usize,
+            },
+            "vacuum" => ExecutionResult::VacuumSuccess {
+                files_deleted: count.unwrap_or_default() as usize,
+            },
             "create_table" => ExecutionResult::CreateTable,
             "create_database" => ExecutionResult::CreateDatabase,
             "create_tunnel" => ExecutionResult::CreateTunnel,
@@ -290,6 +339,10 @@ impl ExecutionResult {
             "drop_database" => ExecutionResult::DropDatabase,
             "drop_tunnel" => ExecutionResult::DropTunnel,
             "drop_credentials" => ExecutionResult::DropCredentials,
+            "create_role" => ExecutionResult::CreateRole,
+            "drop_roles" => ExecutionResult::DropRoles,
+            "grant" => ExecutionResult::Grant,
+            "revoke" => ExecutionResult::Revoke,
             _ => return None,
         })
     }
@@ -304,6 +357,8 @@ impl fmt::Display for ExecutionResult {
             ExecutionResult::Query { .. } => {
                 write!(f, "Query")
             }
+            ExecutionResult::Suspended { .. } => write!(f, "Suspended"),
+            ExecutionResult::Fetch { .. } => write!(f, "Fetch"),
             ExecutionResult::EmptyQuery => write!(f, "No results"),
             ExecutionResult::Begin => write!(f, "Begin"),
             ExecutionResult::Commit => write!(f, "Commit"),
@@ -330,6 +385,15 @@ impl fmt::Display for ExecutionResult {
                 }
             }
             ExecutionResult::CopySuccess => write!(f, "Copy success"),
+            ExecutionResult::OptimizeSuccess { files_removed } => {
+                write!(f, "Optimized table, compacted {files_removed} files")
+            }
+            ExecutionResult::VacuumSuccess { files_deleted } => {
+                write!(f, "Vacuumed table, removed {files_deleted} files")
+            }
+            ExecutionResult::CopyIn { .. } => write!(f, "Copy in"),
+            ExecutionResult::DeclareCursor => write!(f, "Cursor declared"),
+            ExecutionResult::CloseCursor => write!(f, "Cursor closed"),
             ExecutionResult::CreateTable => write!(f, "Table created"),
             ExecutionResult::CreateDatabase => write!(f, "Database created"),
             ExecutionResult::CreateTunnel => write!(f, "Tunnel created"),
@@ -347,10 +411,62 @@ impl fmt::Display for ExecutionResult {
             ExecutionResult::DropDatabase => write!(f, "Database(s) dropped"),
             ExecutionResult::DropTunnel => write!(f, "Tunnel(s) dropped"),
             ExecutionResult::DropCredentials => write!(f, "Credentials dropped"),
+            ExecutionResult::CreateRole => write!(f, "Role created"),
+            ExecutionResult::DropRoles => write!(f, "Role(s) dropped"),
+            ExecutionResult::Grant => write!(f, "Privileges granted"),
+            ExecutionResult::Revoke => write!(f, "Privileges revoked"),
         }
     }
 }
 
+/// Check if a statement ends a transaction block.
+fn is_transaction_end(stmt: &StatementWithExtensions) -> bool {
+    matches!(
+        stmt,
+        StatementWithExtensions::Statement(
+            ast::Statement::Commit { .. } | ast::Statement::Rollback { .. }
+        )
+    )
+}
+
+/// Check if a schema is one of the schemas used for reporting the result of
+/// an operation (e.g. an insert) instead of query results.
+fn is_operation_schema(schema: &SchemaRef) -> bool {
+    schema.eq(&GENERIC_OPERATION_PHYSICAL_SCHEMA)
+        || schema.eq(&GENERIC_OPERATION_AND_COUNT_PHYSICAL_SCHEMA)
+}
+
+/// Run a future to completion, stopping it early on cancel or once the
+/// statement timeout is hit.
+///
+/// This is for work that isn't driven through a stream returned from
+/// `execute_physical_plan`, like fetching from a cursor.
+async fn run_with_cancel<T>(
+    cancel: &CancelHandle,
+    vars: &SessionVars,
+    fut: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let canceled = cancel.canceled();
+    let deadline = match statement_timeout(vars) {
+        Some(timeout) => tokio::time::sleep(timeout).boxed(),
+        None => futures::future::pending::<()>().boxed(),
+    };
+
+    tokio::select! {
+        result = fut => result,
+        _ = canceled => Err(ExecError::QueryCanceled),
+        _ = deadline => Err(ExecError::StatementTimeout),
+    }
+}
+
+/// Create a stream over batches that have already been read.
+fn batches_stream(schema: SchemaRef, batches: Vec<RecordBatch>) -> SendableRecordBatchStream {
+    Box::pin(RecordBatchStreamAdapter::new(
+        schema,
+        futures::stream::iter(batches.into_iter().map(Ok)),
+    ))
+}
+
 /// Simple stream adapter to use after we've inspected the first batch in a
 /// stream.
 struct StreamAndFirstResult {
@@ -375,6 +491,19 @@ impl RecordBatchStream for StreamAndFirstResult {
     }
 }
 
+/// Transaction state of a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransactionStatus {
+    /// Not in a transaction block. Every statement is committed on its own.
+    #[default]
+    Idle,
+    /// In a transaction block.
+    InBlock,
+    /// In a transaction block where a statement failed. Everything but ending
+    /// the block is rejected.
+    Failed,
+}
+
 /// A per-client user session.
 ///
 /// This is a thin wrapper around a session context. Having a layer between
@@ -382,6 +511,13 @@ impl RecordBatchStream for StreamAndFirstResult {
 /// in the future (e.g. consensus).
 pub struct Session {
     pub(crate) ctx: LocalSessionContext,
+    /// State of the current transaction block.
+    txn_status: TransactionStatus,
+    /// Handle for canceling executing statements.
+    cancel: CancelHandle,
+    /// Keeps the engine from forgetting about this session's database while
+    /// the session is open.
+    pub(crate) database_ref: Option<Arc<()>>,
 }
 
 impl Session {
@@ -398,6 +534,7 @@ impl Session {
         tracker: Arc<Tracker>,
         spill_path: Option<PathBuf>,
         task_scheduler: Scheduler,
+        session_registry: Arc<SessionRegistry>,
     ) -> Result<Session> {
         let metrics_handler = SessionMetricsHandler::new(
             vars.user_id(),
@@ -414,9 +551,15 @@ impl Session {
             metrics_handler,
             spill_path,
             task_scheduler,
+            session_registry,
         )?;
 
-        Ok(Session { ctx })
+        Ok(Session {
+            ctx,
+            txn_status: TransactionStatus::Idle,
+            cancel: CancelHandle::new(),
+            database_ref: None,
+        })
     }
 
     pub async fn attach_remote_session(
@@ -431,6 +574,11 @@ impl Session {
         self.ctx.get_session_catalog()
     }
 
+    /// Refresh the session catalog if a newer catalog version is available.
+    pub async fn maybe_refresh_catalog(&mut self) -> Result<()> {
+        self.ctx.maybe_refresh_state().await
+    }
+
     pub fn register_env_reader(&mut self, env_reader: Box<dyn EnvironmentReader>) {
         self.ctx.register_env_reader(env_reader);
     }
@@ -472,6 +620,22 @@ impl Session {
     pub async fn execute_physical_plan(
         &self,
         plan: Arc<dyn ExecutionPlan>,
+    ) -> Result<SendableRecordBatchStream> {
+        let stream = self.start_physical_plan(plan)?;
+        Ok(self.guard_stream(stream))
+    }
+
+    /// Apply the statement timeout and cancel to a stream.
+    fn guard_stream(&self, stream: SendableRecordBatchStream) -> SendableRecordBatchStream {
+        let stream = with_statement_timeout(stream, &self.ctx.get_session_vars());
+        with_cancel(stream, &self.cancel)
+    }
+
+    /// Start executing a physical plan without the statement timeout or
+    /// cancel applied to the output stream.
+    fn start_physical_plan(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
     ) -> Result<SendableRecordBatchStream> {
         let context = self.ctx.task_context();
         let stream = if self.ctx.get_session_vars().enable_experimental_scheduler() {
@@ -482,7 +646,8 @@ impl Session {
 
             let output = OutputSink {
                 batches: sink.clone(),
-                errors: sink,
+                errors: sink.clone(),
+                canceled: sink.canceled(),
             };
 
             scheduler.schedule(plan, context, output)?;
@@ -498,6 +663,29 @@ impl Session {
         self.ctx.get_session_vars().clone()
     }
 
+    /// Get a handle for canceling statements executing in this session.
+    ///
+    /// The handle may be used from outside of the session (e.g. in response
+    /// to a Postgres cancel request on a different connection).
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Get the state of the current transaction block.
+    pub fn transaction_status(&self) -> TransactionStatus {
+        self.txn_status
+    }
+
+    /// Mark the current transaction block as failed, if there is one.
+    ///
+    /// Should be called whenever an error is returned to the client while in
+    /// a transaction block.
+    pub fn mark_transaction_failed(&mut self) {
+        if self.txn_status == TransactionStatus::InBlock {
+            self.txn_status = TransactionStatus::Failed;
+        }
+    }
+
     /// Prepare a parsed statement for future execution.
     pub async fn prepare_statement<T: TryInto<PrepareStatementArg, Error = ExecError>>(
         &mut self,
@@ -507,14 +695,28 @@ impl Session {
     ) -> Result<()> {
         let stmt: PrepareStatementArg = stmt.try_into()?;
 
+        if self.txn_status == TransactionStatus::Failed
+            && !stmt.stmt.as_ref().map(is_transaction_end).unwrap_or(false)
+        {
+            return Err(ExecError::InFailedTransaction);
+        }
+
         self.ctx.prepare_statement(name, stmt.stmt, params).await
     }
 
     /// Like 'prepare_statement', but for a portal.
+    ///
+    /// Errors if the statement takes parameters since there's nothing to bind
+    /// them to. Use `prepare_statement` and `bind_portal` for those.
     pub async fn prepare_portal(&mut self, portal_id: &str, query: &str) -> Result<()> {
         self.prepare_statement(portal_id.to_string(), query, Vec::new())
             .await?;
         let prepared = self.get_prepared_statement(portal_id)?;
+        let num_params = prepared.ordered_input_parameters().len();
+        if num_params > 0 {
+            self.remove_prepared_statement(portal_id);
+            return Err(ExecError::MissingParameters(num_params));
+        }
 
         let num_fields = prepared.output_fields().map(|f| f.len()).unwrap_or(0);
         self.bind_statement(
@@ -526,6 +728,20 @@ impl Session {
         Ok(())
     }
 
+    /// Bind parameters to a prepared statement, creating a portal with the
+    /// same name. Replaces the portal if it was already bound.
+    pub fn bind_portal(&mut self, portal_id: &str, params: Vec<ScalarValue>) -> Result<()> {
+        let prepared = self.get_prepared_statement(portal_id)?;
+        let num_fields = prepared.output_fields().map(|f| f.len()).unwrap_or(0);
+        self.remove_portal(portal_id);
+        self.bind_statement(
+            portal_id.to_string(),
+            portal_id,
+            params,
+            vec![Format::Text; num_fields],
+        )
+    }
+
     pub fn get_prepared_statement(&self, name: &str) -> Result<&PreparedStatement> {
         self.ctx.get_prepared_statement(name)
     }
@@ -557,25 +773,156 @@ impl Session {
             .bind_statement(portal_name, stmt_name, params, result_formats)
     }
 
+    /// Handle the end of a batch of extended protocol messages.
+    ///
+    /// Outside of a transaction block, the implicit transaction ends here and
+    /// all portals are closed, same as Postgres. Portals live until the end
+    /// of the block otherwise.
+    pub fn sync(&mut self) {
+        if self.txn_status == TransactionStatus::Idle {
+            self.ctx.close_all_portals();
+        }
+    }
+
+    /// Execute a transaction control statement.
+    ///
+    /// Inside of a transaction block, native table writes and catalog changes
+    /// are buffered by the session until commit. Statements in the block see
+    /// the buffered changes.
+    ///
+    /// Note that writes are only buffered for tables managed by this node.
+    /// Remote sessions only track the transaction status.
+    async fn execute_transaction_plan(&mut self, plan: TransactionPlan) -> Result<ExecutionResult> {
+        match plan {
+            TransactionPlan::Begin => {
+                // Postgres just warns if there's already a transaction in
+                // progress.
+                if self.txn_status == TransactionStatus::Idle {
+                    self.ctx.begin_transaction();
+                    self.txn_status = TransactionStatus::InBlock;
+                }
+                Ok(ExecutionResult::Begin)
+            }
+            TransactionPlan::Commit => match self.txn_status {
+                TransactionStatus::Idle => Ok(ExecutionResult::Commit),
+                TransactionStatus::InBlock => {
+                    self.txn_status = TransactionStatus::Idle;
+                    self.ctx.close_transaction_cursors();
+                    self.ctx.commit_transaction().await?;
+                    Ok(ExecutionResult::Commit)
+                }
+                TransactionStatus::Failed => {
+                    // Committing a failed transaction rolls it back.
+                    self.txn_status = TransactionStatus::Idle;
+                    self.ctx.close_transaction_cursors();
+                    self.ctx.rollback_transaction().await?;
+                    Ok(ExecutionResult::Rollback)
+                }
+            },
+            TransactionPlan::Abort => {
+                if self.txn_status != TransactionStatus::Idle {
+                    self.txn_status = TransactionStatus::Idle;
+                    self.ctx.close_transaction_cursors();
+                    self.ctx.rollback_transaction().await?;
+                }
+                Ok(ExecutionResult::Rollback)
+            }
+        }
+    }
+
+    /// Execute a cursor statement.
+    ///
+    /// Cursors not declared `WITH HOLD` must be declared in a transaction
+    /// block, and are closed once the transaction ends.
+    async fn execute_cursor_plan(
+        &mut self,
+        plan: CursorPlan,
+        op: &OperationInfo,
+    ) -> Result<ExecutionResult> {
+        match plan {
+            CursorPlan::Declare { name, hold, plan } => {
+                if !hold && self.txn_status == TransactionStatus::Idle {
+                    return Err(ExecError::CursorOutsideTransaction);
+                }
+                let physical = self.create_physical_plan(plan, op).await?;
+                // Cancel and timeout are applied per fetch instead of over
+                // the life of the cursor.
+                let stream = self.start_physical_plan(physical)?;
+                self.ctx.declare_cursor(name, Cursor::new(stream), hold)?;
+                Ok(ExecutionResult::DeclareCursor)
+            }
+            CursorPlan::Fetch { name, count, .. } => {
+                let cancel = self.cancel.clone();
+                let vars = self.ctx.get_session_vars();
+                let cursor = self.ctx.get_cursor_mut(&name)?;
+                let schema = cursor.schema();
+                let batches = run_with_cancel(&cancel, &vars, cursor.fetch(count)).await?;
+                Ok(ExecutionResult::Fetch {
+                    stream: batches_stream(schema, batches),
+                })
+            }
+            CursorPlan::Close { name } => {
+                match name {
+                    Some(name) => self.ctx.close_cursor(&name)?,
+                    None => self.ctx.close_all_cursors(),
+                }
+                Ok(ExecutionResult::CloseCursor)
+            }
+        }
+    }
+
     /// Execute a logical plan.
     pub async fn execute_logical_plan(
         &mut self,
         plan: LogicalPlan,
         op: &OperationInfo,
     ) -> Result<(Arc<dyn ExecutionPlan>, ExecutionResult)> {
-        // Note that transaction support is fake, in that we don't currently do
-        // anything and do not provide any transactional semantics.
-        //
-        // We stub out transaction commands since many tools (even BI ones) will
-        // try to open a transaction for some queries.
+        self.execute_logical_plan_inner(plan, op, false).await
+    }
+
+    /// Execute a logical plan.
+    ///
+    /// If `unguarded_query` is true, the statement timeout and cancel aren't
+    /// applied to the stream of query results. This is for portals that may
+    /// be suspended, where they're applied per Execute instead.
+    async fn execute_logical_plan_inner(
+        &mut self,
+        plan: LogicalPlan,
+        op: &OperationInfo,
+        unguarded_query: bool,
+    ) -> Result<(Arc<dyn ExecutionPlan>, ExecutionResult)> {
         match plan {
             LogicalPlan::Noop => Ok((EMPTY_EXEC_PLAN.clone(), ExecutionResult::EmptyQuery)),
-            LogicalPlan::Transaction(_plan) => {
-                Ok((EMPTY_EXEC_PLAN.clone(), ExecutionResult::EmptyQuery))
+            LogicalPlan::Transaction(plan) => {
+                let result = self.execute_transaction_plan(plan).await?;
+                Ok((EMPTY_EXEC_PLAN.clone(), result))
+            }
+            LogicalPlan::Datafusion(_) | LogicalPlan::CopyFromStdin(_) | LogicalPlan::Cursor(_)
+                if self.txn_status == TransactionStatus::Failed =>
+            {
+                Err(ExecError::InFailedTransaction)
+            }
+            LogicalPlan::Cursor(plan) => {
+                let result = self.execute_cursor_plan(plan, op).await?;
+                Ok((EMPTY_EXEC_PLAN.clone(), result))
+            }
+            LogicalPlan::CopyFromStdin(plan) => {
+                let sink = CopyInSink::start(
+                    plan.table,
+                    plan.columns,
+                    plan.format,
+                    self.ctx.task_context(),
+                )?;
+                Ok((EMPTY_EXEC_PLAN.clone(), ExecutionResult::CopyIn { sink }))
             }
             LogicalPlan::Datafusion(plan) => {
                 let physical = self.create_physical_plan(plan, op).await?;
-                let stream = self.execute_physical_plan(physical.clone()).await?;
+                let stream = self.start_physical_plan(physical.clone())?;
+                let stream = if unguarded_query && !is_operation_schema(&stream.schema()) {
+                    stream
+                } else {
+                    self.guard_stream(stream)
+                };
 
                 let stream = ExecutionResult::from_stream(stream).await;
 
@@ -617,13 +964,23 @@ impl Session {
 
     /// Execute a portal.
     ///
+    /// If `max_rows` is greater than zero, at most that many rows are
+    /// returned from queries. The portal is suspended if the limit is
+    /// reached, and executing it again continues from where it left off.
+    ///
     /// This will handle metrics tracking for query executions.
-    // TODO: Handle max rows.
     pub async fn execute_portal(
         &mut self,
         portal_name: &str,
-        _max_rows: i32,
+        max_rows: i32,
     ) -> Result<ExecutionResult> {
+        if let Some(cursor) = self.ctx.take_suspended_portal(portal_name) {
+            if self.txn_status == TransactionStatus::Failed {
+                return Err(ExecError::InFailedTransaction);
+            }
+            return self.fetch_portal_rows(portal_name, cursor, max_rows).await;
+        }
+
         let portal = self.ctx.get_portal(portal_name)?;
 
         let plan = match &portal.stmt.plan {
@@ -642,9 +999,15 @@ impl Session {
             ..Default::default()
         };
 
-        let stream = match self.execute_logical_plan(plan, &op).await {
+        // Queries that may be suspended get the statement timeout and cancel
+        // applied per Execute, like fetching from a cursor.
+        let stream = match self
+            .execute_logical_plan_inner(plan, &op, max_rows > 0)
+            .await
+        {
             Ok((plan, result)) => match result {
                 ExecutionResult::Error(e) => {
+                    self.mark_transaction_failed();
                     metrics.execution_status = ExecutionStatus::Fail;
                     metrics.error_message = Some(e.to_string());
                     self.ctx.get_metrics_handler().push_metric(metrics);
@@ -683,6 +1046,7 @@ impl Session {
                 }
             },
             Err(e) => {
+                self.mark_transaction_failed();
                 metrics.execution_status = ExecutionStatus::Fail;
                 metrics.error_message = Some(e.to_string());
 
@@ -694,7 +1058,46 @@ impl Session {
             }
         };
 
-        Ok(stream)
+        match stream {
+            ExecutionResult::Query { stream } if max_rows > 0 => {
+                self.fetch_portal_rows(portal_name, Cursor::new(stream), max_rows)
+                    .await
+            }
+            other => Ok(other),
+        }
+    }
+
+    /// Get up to `max_rows` rows from the results of a portal, suspending the
+    /// portal if the limit is reached. All remaining rows are returned if
+    /// `max_rows` isn't greater than zero.
+    async fn fetch_portal_rows(
+        &mut self,
+        portal_name: &str,
+        mut cursor: Cursor,
+        max_rows: i32,
+    ) -> Result<ExecutionResult> {
+        if max_rows <= 0 {
+            return Ok(ExecutionResult::Query {
+                stream: self.guard_stream(cursor.into_stream()),
+            });
+        }
+
+        let max_rows = max_rows as usize;
+        let schema = cursor.schema();
+        let vars = self.ctx.get_session_vars();
+        let batches = run_with_cancel(&self.cancel, &vars, cursor.fetch(Some(max_rows))).await?;
+        let num_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+        let stream = batches_stream(schema, batches);
+
+        // Like Postgres, we don't look ahead to see if there's any more rows
+        // after hitting the limit. The next execute will just return zero
+        // rows.
+        if num_rows < max_rows {
+            Ok(ExecutionResult::Query { stream })
+        } else {
+            self.ctx.suspend_portal(portal_name, cursor)?;
+            Ok(ExecutionResult::Suspended { stream })
+        }
     }
 
     /// Helper for converting SQL statement to a logical plan.
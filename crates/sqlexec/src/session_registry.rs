@@ -0,0 +1,133 @@
+//! Registry for sessions that aren't tied to a single connection.
+//!
+//! Flight SQL clients don't hold a connection open for the lifetime of a
+//! session, so these sessions are kept around between requests until they're
+//! explicitly closed or have been idle for too long. The registry keeps track
+//! of them so that they can be expired and listed in a system table.
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+use parking_lot::Mutex;
+use uuid::Uuid;
+
+/// Information about a registered session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionInfo {
+    /// Unique id for the session.
+    pub session_id: Uuid,
+    /// Database the session is for.
+    pub database_id: Uuid,
+    /// Address of the client that created the session.
+    pub client_addr: String,
+    /// When the session was created.
+    pub created_at: SystemTime,
+    /// When the session was last used.
+    pub last_active_at: SystemTime,
+}
+
+/// Tracks sessions that outlive a single request.
+#[derive(Debug, Default)]
+pub struct SessionRegistry {
+    sessions: Mutex<HashMap<Uuid, SessionInfo>>,
+}
+
+impl SessionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new session, returning the id for the session.
+    pub fn register(&self, database_id: Uuid, client_addr: impl Into<String>) -> Uuid {
+        let now = SystemTime::now();
+        let info = SessionInfo {
+            session_id: Uuid::new_v4(),
+            database_id,
+            client_addr: client_addr.into(),
+            created_at: now,
+            last_active_at: now,
+        };
+        let session_id = info.session_id;
+        self.sessions.lock().insert(session_id, info);
+        session_id
+    }
+
+    /// Mark a session as being used.
+    pub fn touch(&self, session_id: &Uuid) {
+        if let Some(info) = self.sessions.lock().get_mut(session_id) {
+            info.last_active_at = SystemTime::now();
+        }
+    }
+
+    /// Remove a session from the registry.
+    pub fn remove(&self, session_id: &Uuid) -> Option<SessionInfo> {
+        self.sessions.lock().remove(session_id)
+    }
+
+    /// Get the number of registered sessions.
+    pub fn len(&self) -> usize {
+        self.sessions.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.lock().is_empty()
+    }
+
+    /// Get all registered sessions for a database.
+    pub fn database_sessions(&self, database_id: Uuid) -> Vec<SessionInfo> {
+        self.sessions
+            .lock()
+            .values()
+            .filter(|info| info.database_id == database_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Get the ids of sessions that haven't been used for at least `timeout`.
+    pub fn idle_sessions(&self, timeout: Duration) -> Vec<Uuid> {
+        self.sessions
+            .lock()
+            .values()
+            .filter(|info| {
+                // Clocks going backwards means the session was recently used.
+                info.last_active_at
+                    .elapsed()
+                    .is_ok_and(|idle| idle >= timeout)
+            })
+            .map(|info| info.session_id)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_and_expire() {
+        let registry = SessionRegistry::new();
+        let db1 = Uuid::new_v4();
+        let db2 = Uuid::new_v4();
+
+        let sess1 = registry.register(db1, "127.0.0.1:1234");
+        let sess2 = registry.register(db2, "127.0.0.1:1235");
+        assert_eq!(2, registry.len());
+
+        let sessions = registry.database_sessions(db1);
+        assert_eq!(1, sessions.len());
+        assert_eq!(sess1, sessions[0].session_id);
+        assert_eq!("127.0.0.1:1234", sessions[0].client_addr);
+
+        assert!(registry.idle_sessions(Duration::from_secs(60)).is_empty());
+        let mut idle = registry.idle_sessions(Duration::ZERO);
+        idle.sort();
+        let mut expected = vec![sess1, sess2];
+        expected.sort();
+        assert_eq!(expected, idle);
+
+        assert!(registry.remove(&sess1).is_some());
+        assert!(registry.remove(&sess1).is_none());
+        assert!(registry.database_sessions(db1).is_empty());
+        assert_eq!(1, registry.len());
+    }
+}
@@ -0,0 +1,127 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::physical_plan::{RecordBatchStream, SendableRecordBatchStream};
+use datafusion_ext::vars::SessionVars;
+use futures::{Future, Stream, StreamExt};
+use tokio::time::Sleep;
+
+use crate::errors::ExecError;
+
+/// Get the statement timeout configured for the session.
+///
+/// Returns `None` if the timeout is disabled (zero or negative).
+pub fn statement_timeout(vars: &SessionVars) -> Option<Duration> {
+    let ms = vars.statement_timeout();
+    if ms > 0 {
+        Some(Duration::from_millis(ms as u64))
+    } else {
+        None
+    }
+}
+
+/// Wrap a stream with the statement timeout from the session vars, if one is
+/// set.
+pub fn with_statement_timeout(
+    stream: SendableRecordBatchStream,
+    vars: &SessionVars,
+) -> SendableRecordBatchStream {
+    match statement_timeout(vars) {
+        Some(timeout) => Box::pin(StatementTimeoutStream::new(stream, timeout)),
+        None => stream,
+    }
+}
+
+/// Stream adapter that errors once the statement deadline passes.
+///
+/// The inner stream is dropped when the deadline is hit, cancelling any
+/// in-flight work (including requests to external sources and remote nodes).
+pub struct StatementTimeoutStream {
+    schema: SchemaRef,
+    /// The stream being wrapped. Set to `None` after timing out.
+    stream: Option<SendableRecordBatchStream>,
+    deadline: Pin<Box<Sleep>>,
+}
+
+impl StatementTimeoutStream {
+    pub fn new(stream: SendableRecordBatchStream, timeout: Duration) -> Self {
+        StatementTimeoutStream {
+            schema: stream.schema(),
+            stream: Some(stream),
+            deadline: Box::pin(tokio::time::sleep(timeout)),
+        }
+    }
+}
+
+impl RecordBatchStream for StatementTimeoutStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Stream for StatementTimeoutStream {
+    type Item = DataFusionResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.stream.is_none() {
+            return Poll::Ready(None);
+        }
+
+        // Check the deadline first so that streams that are always ready
+        // still get cut off.
+        if self.deadline.as_mut().poll(cx).is_ready() {
+            self.stream = None;
+            return Poll::Ready(Some(Err(DataFusionError::External(Box::new(
+                ExecError::StatementTimeout,
+            )))));
+        }
+
+        match self.stream.as_mut() {
+            Some(stream) => stream.poll_next_unpin(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::datatypes::Schema;
+    use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn times_out_pending_stream() {
+        let schema = Arc::new(Schema::empty());
+        let inner = Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::pending::<DataFusionResult<RecordBatch>>(),
+        ));
+        let mut stream = StatementTimeoutStream::new(inner, Duration::from_millis(10));
+
+        let err = ExecError::from(stream.next().await.unwrap().unwrap_err());
+        assert!(matches!(
+            err.as_query_canceled(),
+            Some(ExecError::StatementTimeout)
+        ));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn completes_before_deadline() {
+        let schema = Arc::new(Schema::empty());
+        let batch = RecordBatch::new_empty(schema.clone());
+        let inner = Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::iter(vec![DataFusionResult::Ok(batch)]),
+        ));
+        let mut stream = StatementTimeoutStream::new(inner, Duration::from_secs(60));
+
+        assert!(stream.next().await.unwrap().is_ok());
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -1,5 +1,5 @@
 use logutil::{LoggingMode, Verbosity};
-use pgsrv::auth::SingleUserAuthenticator;
+use pgsrv::auth::{PasswordMethod, SingleUserAuthenticator};
 use std::{
     collections::{BTreeMap, HashMap},
     path::{Path, PathBuf},
@@ -205,10 +205,11 @@ impl Cli {
                 };
 
                 let mut builder = ComputeServer::builder()
-                    .with_authenticator(SingleUserAuthenticator {
-                        user: "glaredb".to_string(),
-                        password: "glaredb".to_string(),
-                    })
+                    .with_authenticator(SingleUserAuthenticator::new(
+                        "glaredb",
+                        "glaredb",
+                        PasswordMethod::default(),
+                    ))
                     .with_pg_listener_opt(pg_listener)
                     .with_rpc_listener_opt(rpc_listener)
                     .with_metastore_addr_opt(self.metastore_addr.clone())
@@ -20,7 +20,8 @@ use sqlexec::errors::ExecError;
 use sqlexec::remote::client::RemoteClient;
 use sqlexec::session::ExecutionResult;
 use sqllogictest::{
-    parse_with_name, AsyncDB, ColumnType, DBOutput, DefaultColumnType, Injected, Record, Runner,
+    default_validator, parse_with_name, AsyncDB, ColumnType, DBOutput, DefaultColumnType, Injected,
+    Record, Runner,
 };
 use std::ops::Deref;
 use std::sync::Arc;
@@ -77,6 +78,53 @@ pub trait FnTest: Send + Sync {
 
 const ENV_REGEX: &str = r"\$\{\s*(\w+)\s*\}";
 
+/// Marker in expected query results that matches any text.
+const RESULTS_WILDCARD: &str = "...";
+
+/// Validate query results, allowing [`RESULTS_WILDCARD`] in the expected
+/// results.
+///
+/// Results without a wildcard are validated the same way as the default
+/// validator. Otherwise the rows are compared as a single whitespace
+/// normalized string, which allows asserting on output that is only partially
+/// deterministic (e.g. `EXPLAIN` output containing file names).
+fn validate_results(actual: &[Vec<String>], expected: &[String]) -> bool {
+    if !expected.iter().any(|line| line.contains(RESULTS_WILDCARD)) {
+        return default_validator(actual, expected);
+    }
+
+    let normalize = |s: &str| s.split_ascii_whitespace().collect::<Vec<_>>().join(" ");
+    let actual = normalize(
+        &actual
+            .iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    let expected = normalize(&expected.join(" "));
+
+    let segments: Vec<_> = expected.split(RESULTS_WILDCARD).map(str::trim).collect();
+    let (first, rest) = segments
+        .split_first()
+        .expect("split returns at least one item");
+    let (last, middle) = rest
+        .split_last()
+        .expect("expected results contain a wildcard");
+
+    let mut remaining = match actual.strip_prefix(first) {
+        Some(remaining) => remaining,
+        None => return false,
+    };
+    for segment in middle {
+        match remaining.find(segment) {
+            Some(idx) => remaining = &remaining[idx + segment.len()..],
+            None => return false,
+        }
+    }
+    remaining.ends_with(last)
+}
+
 pub enum Test {
     File(PathBuf),
     FnTest(Box<dyn FnTest>),
@@ -107,6 +155,7 @@ impl Test {
                     let client = client.clone();
                     async { Ok(client) }
                 });
+                runner.with_validator(validate_results);
 
                 runner
                     .run_multi_async(records)
@@ -270,6 +319,14 @@ impl RpcTestClient {
 pub struct FlightSqlTestClient {
     client: FlightSqlServiceClient<Channel>,
 }
+
+impl Deref for FlightSqlTestClient {
+    type Target = FlightSqlServiceClient<Channel>;
+    fn deref(&self) -> &Self::Target {
+        &self.client
+    }
+}
+
 impl FlightSqlTestClient {
     pub async fn new(config: &Config) -> Result<Self> {
         let port = config.get_ports().first().unwrap();
@@ -332,6 +389,7 @@ impl AsyncDB for PgTestClient {
     async fn run(&mut self, sql: &str) -> Result<DBOutput<Self::ColumnType>, Self::Error> {
         let mut output = Vec::new();
         let mut num_columns = 0;
+        let mut affected = 0;
 
         let rows = self
             .simple_query(sql)
@@ -356,12 +414,12 @@ impl AsyncDB for PgTestClient {
                     }
                     output.push(row_output);
                 }
-                SimpleQueryMessage::CommandComplete(_) => {}
+                SimpleQueryMessage::CommandComplete(n) => affected = n,
                 _ => unreachable!(),
             }
         }
         if output.is_empty() && num_columns == 0 {
-            Ok(DBOutput::StatementComplete(0))
+            Ok(DBOutput::StatementComplete(affected))
         } else {
             Ok(DBOutput::Rows {
                 types: vec![DefaultColumnType::Text; num_columns],
@@ -378,6 +436,7 @@ impl AsyncDB for RpcTestClient {
     async fn run(&mut self, sql: &str) -> Result<DBOutput<Self::ColumnType>, Self::Error> {
         let mut output = Vec::new();
         let mut num_columns = 0;
+        let mut affected = 0;
         let RpcTestClient { session, .. } = self;
 
         let mut session = session.lock().await;
@@ -397,7 +456,7 @@ impl AsyncDB for RpcTestClient {
             let stream = session.execute_portal(&UNNAMED, 0).await?;
 
             match stream {
-                ExecutionResult::Query { stream, .. } => {
+                ExecutionResult::Query { stream, .. } | ExecutionResult::Fetch { stream } => {
                     let batches = stream
                         .collect::<Vec<_>>()
                         .await
@@ -439,13 +498,16 @@ impl AsyncDB for RpcTestClient {
                         }
                     }
                 }
+                ExecutionResult::InsertSuccess { rows_inserted } => affected = rows_inserted,
+                ExecutionResult::DeleteSuccess { deleted_rows } => affected = deleted_rows,
+                ExecutionResult::UpdateSuccess { updated_rows } => affected = updated_rows,
                 ExecutionResult::Error(e) => return Err(e.into()),
                 _ => (),
             }
         }
 
         if output.is_empty() && num_columns == 0 {
-            Ok(DBOutput::StatementComplete(0))
+            Ok(DBOutput::StatementComplete(affected as u64))
         } else {
             Ok(DBOutput::Rows {
                 types: vec![DefaultColumnType::Text; num_columns],
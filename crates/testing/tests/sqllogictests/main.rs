@@ -5,7 +5,10 @@ use anyhow::Result;
 use hooks::{AllTestsHook, SshTunnelHook};
 use std::sync::Arc;
 use testing::slt::runner::SltRunner;
-use tests::{PgBinaryEncoding, SshKeysTest};
+use tests::{
+    FlightSessionsTest, FlightSqlMetadataTest, FlightSqlPutTest, PgBinaryEncoding,
+    RolePrivilegesTest, SshKeysTest,
+};
 
 fn main() -> Result<()> {
     SltRunner::new()
@@ -13,6 +16,19 @@ fn main() -> Result<()> {
         // Rust tests
         .test("sqllogictests/ssh_keys", Box::new(SshKeysTest))?
         .test("pgproto/binary_encoding", Box::new(PgBinaryEncoding))?
+        .test(
+            "sqllogictests/role_privileges",
+            Box::new(RolePrivilegesTest),
+        )?
+        .test(
+            "sqllogictests/flight_metadata",
+            Box::new(FlightSqlMetadataTest),
+        )?
+        .test("sqllogictests/flight_put", Box::new(FlightSqlPutTest))?
+        .test(
+            "sqllogictests/flight_sessions",
+            Box::new(FlightSessionsTest),
+        )?
         // Add hooks
         .hook("*", Arc::new(AllTestsHook))?
         // SSH Tunnels hook
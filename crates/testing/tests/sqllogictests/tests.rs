@@ -1,9 +1,25 @@
 use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use futures::{stream, TryStreamExt};
+use rpcsrv::export::arrow_flight::encode::FlightDataEncoderBuilder;
+use rpcsrv::export::arrow_flight::sql::client::FlightSqlServiceClient;
+use rpcsrv::export::arrow_flight::sql::{
+    ActionCreatePreparedStatementRequest, ActionCreatePreparedStatementResult, Any,
+    CommandGetDbSchemas, CommandGetTables, CommandPreparedStatementUpdate, CommandStatementUpdate,
+    DoPutUpdateResult, ProstMessageExt,
+};
+use rpcsrv::export::arrow_flight::utils::flight_data_to_arrow_batch;
+use rpcsrv::export::arrow_flight::{Action, FlightDescriptor, FlightInfo};
+use rpcsrv::export::prost::Message;
+use rpcsrv::export::tonic::transport::Channel;
+use rpcsrv::export::{ArrayRef, Int64Array, RecordBatch, Schema, StringArray};
+use sqllogictest::{AsyncDB, DBOutput};
 use testing::slt::runner::{FnTest, TestClient};
-use tokio_postgres::Config;
+use tokio_postgres::{Config, NoTls};
 use tracing::warn;
 
 macro_rules! test_assert {
@@ -149,3 +165,434 @@ impl FnTest for PgBinaryEncoding {
         Ok(())
     }
 }
+
+/// Checks that privileges are enforced for a role that logs in with
+/// restricted privileges.
+pub struct RolePrivilegesTest;
+
+impl RolePrivilegesTest {
+    const ROLE: &'static str = "privileges_reader";
+    const PASSWORD: &'static str = "privileges_secret";
+
+    async fn assert_denied(client: &tokio_postgres::Client, query: &str) -> Result<()> {
+        match client.batch_execute(query).await {
+            Ok(_) => Err(anyhow!("query should be denied: {query}")),
+            Err(e) => {
+                let msg = e
+                    .as_db_error()
+                    .map(|e| e.message().to_string())
+                    .unwrap_or_else(|| e.to_string());
+                test_assert!(
+                    msg.contains("Permission denied"),
+                    anyhow!("unexpected error for query '{query}': {msg}")
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl FnTest for RolePrivilegesTest {
+    async fn run(
+        &self,
+        config: &Config,
+        client: TestClient,
+        vars: &mut HashMap<String, String>,
+    ) -> Result<()> {
+        let client = match client {
+            TestClient::Pg(client) => client,
+            TestClient::Rpc(_) | TestClient::FlightSql(_) => {
+                warn!("role privileges test requires a pg connection. Skipping...");
+                return Ok(());
+            }
+        };
+
+        client
+            .batch_execute(&format!(
+                "
+CREATE ROLE {role} WITH LOGIN PASSWORD '{password}';
+CREATE SCHEMA privileges_schema;
+CREATE TABLE privileges_schema.readable (a INT);
+CREATE TABLE privileges_schema.hidden (a INT);
+INSERT INTO privileges_schema.readable VALUES (1), (2);
+GRANT USAGE ON SCHEMA privileges_schema TO {role};
+GRANT SELECT ON privileges_schema.readable TO {role};
+                ",
+                role = Self::ROLE,
+                password = Self::PASSWORD,
+            ))
+            .await?;
+
+        let mut role_config = config.clone();
+        role_config.user(Self::ROLE).password(Self::PASSWORD);
+        let (role_client, conn) = role_config.connect(NoTls).await?;
+        let conn = tokio::spawn(conn);
+
+        let rows = role_client
+            .query("SELECT a FROM privileges_schema.readable", &[])
+            .await?;
+        test_assert!(
+            rows.len() == 2,
+            anyhow!("granted SELECT should return 2 rows, got {}", rows.len())
+        );
+
+        let tmp = vars
+            .get("TMP")
+            .cloned()
+            .unwrap_or_else(|| std::env::temp_dir().to_string_lossy().into_owned());
+        let denied = [
+            "SELECT a FROM privileges_schema.hidden".to_string(),
+            "INSERT INTO privileges_schema.readable VALUES (3)".to_string(),
+            "DELETE FROM privileges_schema.readable WHERE a = 1".to_string(),
+            format!("COPY privileges_schema.readable TO '{tmp}/privileges.csv'"),
+            format!("SELECT * FROM read_csv('{tmp}/privileges.csv')"),
+        ];
+        for query in &denied {
+            Self::assert_denied(&role_client, query).await?;
+        }
+
+        // Table functions that don't read external data are allowed.
+        let rows = role_client
+            .query("SELECT * FROM generate_series(1, 3)", &[])
+            .await?;
+        test_assert!(
+            rows.len() == 3,
+            anyhow!("generate_series should return 3 rows, got {}", rows.len())
+        );
+
+        // Denied statements must not have modified the table.
+        let row = client
+            .query_one("SELECT count(*) FROM privileges_schema.readable", &[])
+            .await?;
+        let count: i64 = row.get(0);
+        test_assert!(count == 2, anyhow!("table should have 2 rows, got {count}"));
+
+        // Dropping the role takes away everything from a connected session.
+        client
+            .batch_execute(&format!("DROP ROLE {role};", role = Self::ROLE))
+            .await?;
+        Self::assert_denied(&role_client, "SELECT a FROM privileges_schema.readable").await?;
+
+        drop(role_client);
+        conn.await??;
+
+        // And the role can no longer log in.
+        test_assert!(
+            role_config.connect(NoTls).await.is_err(),
+            anyhow!("dropped role should not be able to connect")
+        );
+
+        client
+            .batch_execute("DROP SCHEMA privileges_schema CASCADE;")
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Checks catalog, schema and table listing over Flight SQL.
+pub struct FlightSqlMetadataTest;
+
+impl FlightSqlMetadataTest {
+    /// Fetch the results for a metadata command, returning the string values
+    /// of the given columns for each row.
+    async fn fetch_rows(
+        client: &mut FlightSqlServiceClient<Channel>,
+        info: FlightInfo,
+        columns: &[usize],
+    ) -> Result<Vec<Vec<String>>> {
+        let ticket = info
+            .endpoint
+            .first()
+            .and_then(|endpoint| endpoint.ticket.clone())
+            .ok_or_else(|| anyhow!("flight info missing ticket"))?;
+        let mut stream = client.do_get(ticket).await?;
+        let flight_data = stream
+            .message()
+            .await?
+            .ok_or_else(|| anyhow!("missing schema message"))?;
+        let schema = Arc::new(Schema::try_from(&flight_data)?);
+
+        let mut rows = Vec::new();
+        while let Some(flight_data) = stream.message().await? {
+            let batch = flight_data_to_arrow_batch(&flight_data, schema.clone(), &HashMap::new())?;
+            for row_idx in 0..batch.num_rows() {
+                let mut row = Vec::with_capacity(columns.len());
+                for &col_idx in columns {
+                    let col = batch
+                        .column(col_idx)
+                        .as_any()
+                        .downcast_ref::<StringArray>()
+                        .ok_or_else(|| anyhow!("column {col_idx} is not a string column"))?;
+                    row.push(col.value(row_idx).to_string());
+                }
+                rows.push(row);
+            }
+        }
+        rows.sort();
+        Ok(rows)
+    }
+}
+
+#[async_trait]
+impl FnTest for FlightSqlMetadataTest {
+    async fn run(
+        &self,
+        _config: &Config,
+        client: TestClient,
+        _vars: &mut HashMap<String, String>,
+    ) -> Result<()> {
+        let mut flight = match client {
+            TestClient::FlightSql(client) => client,
+            TestClient::Pg(_) | TestClient::Rpc(_) => {
+                warn!("flight sql metadata test requires a flight sql connection. Skipping...");
+                return Ok(());
+            }
+        };
+
+        for query in [
+            "CREATE SCHEMA flight_metadata",
+            "CREATE TABLE flight_metadata.events (id INT, kind TEXT)",
+            "CREATE TABLE flight_metadata.users (id INT)",
+            "CREATE VIEW flight_metadata.events_view AS SELECT id FROM flight_metadata.events",
+        ] {
+            flight.run(query).await?;
+        }
+
+        let mut client = flight.deref().clone();
+
+        let info = client.get_catalogs().await?;
+        let catalogs = Self::fetch_rows(&mut client, info, &[0]).await?;
+        test_assert!(
+            catalogs.contains(&vec!["default".to_string()]),
+            anyhow!("catalogs should include the default database: {catalogs:?}")
+        );
+
+        let info = client
+            .get_db_schemas(CommandGetDbSchemas {
+                catalog: Some("default".to_string()),
+                db_schema_filter_pattern: Some("flight\\_meta%".to_string()),
+            })
+            .await?;
+        let schemas = Self::fetch_rows(&mut client, info, &[0, 1]).await?;
+        test_assert!(
+            schemas == vec![vec!["default".to_string(), "flight_metadata".to_string()]],
+            anyhow!("unexpected schemas: {schemas:?}")
+        );
+
+        let tables_query = |table_pattern: &str, table_types: &[&str]| CommandGetTables {
+            catalog: Some("default".to_string()),
+            db_schema_filter_pattern: Some("flight_metadata".to_string()),
+            table_name_filter_pattern: Some(table_pattern.to_string()),
+            table_types: table_types.iter().map(|t| t.to_string()).collect(),
+            include_schema: false,
+        };
+
+        let info = client.get_tables(tables_query("%", &[])).await?;
+        let tables = Self::fetch_rows(&mut client, info, &[2, 3]).await?;
+        let expected = [
+            ["events", "TABLE"],
+            ["events_view", "VIEW"],
+            ["users", "TABLE"],
+        ]
+        .map(|row| row.map(str::to_string).to_vec())
+        .to_vec();
+        test_assert!(tables == expected, anyhow!("unexpected tables: {tables:?}"));
+
+        let info = client
+            .get_tables(tables_query("events%", &["VIEW"]))
+            .await?;
+        let tables = Self::fetch_rows(&mut client, info, &[2, 3]).await?;
+        test_assert!(
+            tables == vec![vec!["events_view".to_string(), "VIEW".to_string()]],
+            anyhow!("unexpected filtered tables: {tables:?}")
+        );
+
+        let info = client.get_tables(tables_query("missing%", &[])).await?;
+        let tables = Self::fetch_rows(&mut client, info, &[2]).await?;
+        test_assert!(
+            tables.is_empty(),
+            anyhow!("no tables should match: {tables:?}")
+        );
+
+        flight.run("DROP SCHEMA flight_metadata CASCADE").await?;
+
+        Ok(())
+    }
+}
+
+/// Checks DoPut over Flight SQL, both for bulk ingest with `COPY ... FROM
+/// STDIN` and for prepared updates with parameters.
+pub struct FlightSqlPutTest;
+
+impl FlightSqlPutTest {
+    /// Send a batch with DoPut for a command, returning the number of rows
+    /// affected.
+    async fn put(
+        client: &mut FlightSqlServiceClient<Channel>,
+        cmd: Any,
+        batch: RecordBatch,
+    ) -> Result<i64> {
+        let descriptor = FlightDescriptor::new_cmd(cmd.encode_to_vec());
+        let data: Vec<_> = FlightDataEncoderBuilder::new()
+            .with_flight_descriptor(Some(descriptor))
+            .build(stream::iter([Ok(batch)]))
+            .try_collect()
+            .await?;
+
+        let mut results = client.do_put(stream::iter(data)).await?;
+        let result = results
+            .message()
+            .await?
+            .ok_or_else(|| anyhow!("missing put result"))?;
+        let result: DoPutUpdateResult = Any::decode(&*result.app_metadata)?
+            .unpack()?
+            .ok_or_else(|| anyhow!("put result is not an update result"))?;
+        Ok(result.record_count)
+    }
+
+    /// Create a prepared statement, returning its handle.
+    async fn prepare(client: &mut FlightSqlServiceClient<Channel>, query: &str) -> Result<Vec<u8>> {
+        let request = ActionCreatePreparedStatementRequest {
+            query: query.to_string(),
+            transaction_id: None,
+        };
+        let action = Action {
+            r#type: "CreatePreparedStatement".to_string(),
+            body: request.as_any().encode_to_vec().into(),
+        };
+        let mut results = client.do_action(action).await?;
+        let result = results
+            .message()
+            .await?
+            .ok_or_else(|| anyhow!("missing prepared statement result"))?;
+        let result: ActionCreatePreparedStatementResult = Any::decode(&*result.body)?
+            .unpack()?
+            .ok_or_else(|| anyhow!("result is not a prepared statement"))?;
+        Ok(result.prepared_statement_handle.to_vec())
+    }
+}
+
+#[async_trait]
+impl FnTest for FlightSqlPutTest {
+    async fn run(
+        &self,
+        _config: &Config,
+        client: TestClient,
+        _vars: &mut HashMap<String, String>,
+    ) -> Result<()> {
+        let mut flight = match client {
+            TestClient::FlightSql(client) => client,
+            TestClient::Pg(_) | TestClient::Rpc(_) => {
+                warn!("flight sql put test requires a flight sql connection. Skipping...");
+                return Ok(());
+            }
+        };
+        let mut client = flight.deref().clone();
+
+        flight
+            .run("CREATE TABLE flight_put (id INT, name TEXT)")
+            .await?;
+
+        // Ids are sent as 64 bit ints, and need to be cast to the table's
+        // column type after decoding.
+        let batch = RecordBatch::try_from_iter([
+            ("id", Arc::new(Int64Array::from(vec![1, 2, 3])) as ArrayRef),
+            (
+                "name",
+                Arc::new(StringArray::from(vec!["a", "b", "c"])) as ArrayRef,
+            ),
+        ])?;
+        let cmd = CommandStatementUpdate {
+            query: "COPY flight_put FROM STDIN".to_string(),
+            transaction_id: None,
+        };
+        let count = Self::put(&mut client, cmd.as_any(), batch).await?;
+        test_assert!(
+            count == 3,
+            anyhow!("copy should insert 3 rows, got {count}")
+        );
+
+        let handle =
+            Self::prepare(&mut client, "UPDATE flight_put SET name = $1 WHERE id = $2").await?;
+        let params = RecordBatch::try_from_iter([
+            (
+                "$1",
+                Arc::new(StringArray::from(vec!["x", "y"])) as ArrayRef,
+            ),
+            ("$2", Arc::new(Int64Array::from(vec![1, 2])) as ArrayRef),
+        ])?;
+        let cmd = CommandPreparedStatementUpdate {
+            prepared_statement_handle: handle.into(),
+        };
+        let count = Self::put(&mut client, cmd.as_any(), params).await?;
+        test_assert!(
+            count == 2,
+            anyhow!("update should affect 2 rows, got {count}")
+        );
+
+        let rows = match flight
+            .run("SELECT id, name FROM flight_put ORDER BY id")
+            .await?
+        {
+            DBOutput::Rows { rows, .. } => rows,
+            other => return Err(anyhow!("unexpected output: {other:?}")),
+        };
+        let expected = [["1", "x"], ["2", "y"], ["3", "c"]]
+            .map(|row| row.map(str::to_string).to_vec())
+            .to_vec();
+        test_assert!(rows == expected, anyhow!("unexpected rows: {rows:?}"));
+
+        flight.run("DROP TABLE flight_put").await?;
+
+        Ok(())
+    }
+}
+
+/// Checks listing Flight SQL sessions.
+///
+/// Only Flight SQL clients get registered sessions, so the expected number of
+/// sessions depends on the protocol.
+pub struct FlightSessionsTest;
+
+#[async_trait]
+impl FnTest for FlightSessionsTest {
+    async fn run(
+        &self,
+        _config: &Config,
+        client: TestClient,
+        _vars: &mut HashMap<String, String>,
+    ) -> Result<()> {
+        const QUERY: &str = "
+SELECT count(*)
+    FROM glare_catalog.flight_sessions
+    WHERE last_active_at >= created_at AND client_addr <> ''
+        ";
+
+        // Postgres connections don't have flight sessions, while the flight
+        // client's own session is the only one for the database.
+        let (count, expected) = match client {
+            TestClient::Pg(client) => {
+                let row = client.query_one(QUERY, &[]).await?;
+                (row.get::<_, i64>(0).to_string(), "0")
+            }
+            TestClient::FlightSql(mut client) => match client.run(QUERY).await? {
+                DBOutput::Rows { rows, .. } if rows.len() == 1 && rows[0].len() == 1 => {
+                    (rows[0][0].clone(), "1")
+                }
+                other => return Err(anyhow!("unexpected output: {other:?}")),
+            },
+            TestClient::Rpc(_) => {
+                warn!("flight sessions test requires a pg or flight sql connection. Skipping...");
+                return Ok(());
+            }
+        };
+        test_assert!(
+            count == expected,
+            anyhow!("expected {expected} flight sessions, got {count}")
+        );
+
+        Ok(())
+    }
+}
@@ -31,3 +31,11 @@ CREATE OR REPLACE TABLE bikeshare_trips (
 ) ENGINE MergeTree
   ORDER BY trip_id;
 
+
+-- Table for testing inserts.
+CREATE OR REPLACE TABLE insert_test (
+    a Int64,
+    b Nullable(String),
+    c Nullable(Float64)
+) ENGINE MergeTree
+  ORDER BY a;
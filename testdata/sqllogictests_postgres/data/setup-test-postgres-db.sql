@@ -85,3 +85,11 @@ CREATE TABLE IF NOT EXISTS bikeshare_trips (
 );
 
 \copy bikeshare_trips FROM './testdata/sqllogictests_datasources_common/data/gcs-artifacts/bikeshare_trips.csv' CSV HEADER;
+
+-- Table for testing inserts into postgres.
+DROP TABLE IF EXISTS insert_test;
+CREATE TABLE insert_test (
+    a INT8,
+    b TEXT,
+    c NUMERIC(10, 2)
+);
@@ -132,3 +132,14 @@ FROM '/repo/testdata/sqllogictests_datasources_common/data/gcs-artifacts/bikesha
   WITH (FORMAT = 'CSV',
         FIRSTROW = 2)
 
+
+IF OBJECT_ID('dbo.insert_test', 'u') IS NOT NULL
+   DROP TABLE insert_test;
+GO
+
+-- Table for testing inserts.
+CREATE TABLE insert_test (
+    a INT,
+    b VARCHAR(255),
+    c REAL
+);